@@ -114,6 +114,37 @@ pub struct RunCmd {
 	/// commonly `127.0.0.1:6831`.
 	#[structopt(long)]
 	pub jaeger_agent: Option<std::net::SocketAddr>,
+
+	/// Path to a file of collators to prefer when validating a para.
+	///
+	/// Each line holds a para id and the SS58 encoded key of a collator of that para,
+	/// separated by whitespace.
+	#[structopt(long, parse(from_os_str))]
+	pub collator_allowlist: Option<std::path::PathBuf>,
+
+	/// How long, in seconds, collators which kept providing bad collations are refused.
+	///
+	/// Defaults to one hour.
+	#[structopt(long)]
+	pub collator_block_cooldown: Option<u64>,
+
+	/// How many blocks finality has to lag behind before all approval messages for the oldest
+	/// unfinalized blocks are resent to all connected validators.
+	///
+	/// Defaults to 16.
+	#[structopt(long)]
+	pub approval_aggression_threshold: Option<u32>,
+
+	/// How many new blocks to wait for between consecutive resends of approval messages while
+	/// finality keeps lagging behind.
+	///
+	/// Defaults to 8.
+	#[structopt(long)]
+	pub approval_aggression_period: Option<u32>,
+
+	/// Never resend approval messages when finality lags behind.
+	#[structopt(long, conflicts_with = "approval-aggression-threshold")]
+	pub no_approval_aggression: bool,
 }
 
 #[allow(missing_docs)]
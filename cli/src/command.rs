@@ -208,6 +208,20 @@ pub fn run() -> Result<()> {
 			}
 
 			let jaeger_agent = cli.run.jaeger_agent;
+			let collator_allowlist = cli.run.collator_allowlist.clone();
+			let collator_block_cooldown = cli.run.collator_block_cooldown
+				.map(std::time::Duration::from_secs);
+			let approval_aggression_config = {
+				let default = service::ApprovalAggressionConfig::default();
+				service::ApprovalAggressionConfig {
+					resend_threshold: if cli.run.no_approval_aggression {
+						None
+					} else {
+						cli.run.approval_aggression_threshold.or(default.resend_threshold)
+					},
+					resend_period: cli.run.approval_aggression_period.unwrap_or(default.resend_period),
+				}
+			};
 
 			runner.run_node_until_exit(move |config| async move {
 				let role = config.role.clone();
@@ -224,6 +238,9 @@ pub fn run() -> Result<()> {
 						cli.run.no_beefy,
 						jaeger_agent,
 						None,
+						collator_allowlist,
+						collator_block_cooldown,
+						approval_aggression_config,
 					).map(|full| full.task_manager).map_err(Into::into)
 				}
 			})
@@ -23,6 +23,11 @@
 //! Each of n validators stores their piece of data. We assume n=3f+k, 0 < k ≤ 3.
 //! f is the maximum number of faulty validators in the system.
 //! The data is coded so any f+1 chunks can be used to reconstruct the full data.
+//!
+//! The code is systematic: the first chunks, as many as [`systematic_recovery_threshold`]
+//! gives, hold the original data bytes. When all of them are present, the data can be rebuilt by
+//! simply interleaving them with [`reconstruct_from_systematic_v1`], which is much cheaper than
+//! the full decoding.
 
 use parity_scale_codec::{Encode, Decode};
 use polkadot_primitives::v0::{self, Hash as H256, BlakeTwo256, HashT};
@@ -90,6 +95,22 @@ pub const fn recovery_threshold(n_validators: usize) -> Result<usize, Error> {
 	Ok(needed + 1)
 }
 
+/// Obtain the number of systematic chunks, i.e. the chunks holding the original data bytes.
+///
+/// These are the chunks with the indices `0..systematic_recovery_threshold(n_validators)`. This is
+/// the largest power of 2 not greater than [`recovery_threshold`], as the code operates on the
+/// power of 2 number of data chunks.
+pub fn systematic_recovery_threshold(n_validators: usize) -> Result<usize, Error> {
+	let threshold = recovery_threshold(n_validators)?;
+
+	let mut n_systematic = 1;
+	while n_systematic * 2 <= threshold {
+		n_systematic *= 2;
+	}
+
+	Ok(n_systematic)
+}
+
 fn code_params(n_validators: usize) -> Result<CodeParams, Error> {
 	// we need to be able to reconstruct from 1/3 - eps
 
@@ -225,6 +246,52 @@ fn reconstruct<'a, I: 'a, T: Decode>(n_validators: usize, chunks: I) -> Result<T
 	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
 }
 
+/// Reconstruct the v1 available data from the systematic chunks.
+///
+/// Provide the data of the chunks with the indices `0..systematic_recovery_threshold(n_validators)`,
+/// in order. Any chunks past those are ignored. If too few chunks are provided, recovery is not
+/// possible.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic_v1(n_validators: usize, chunks: Vec<Vec<u8>>)
+	-> Result<AvailableData, Error>
+{
+	reconstruct_from_systematic(n_validators, chunks)
+}
+
+/// Reconstruct decodable data from the systematic chunks.
+///
+/// Every chunk holds one 2-byte symbol of each consecutive run of the data, so the data is
+/// obtained by taking the symbols of all the chunks in turn.
+fn reconstruct_from_systematic<T: Decode>(n_validators: usize, chunks: Vec<Vec<u8>>)
+	-> Result<T, Error>
+{
+	let n_systematic = systematic_recovery_threshold(n_validators)?;
+	if chunks.len() < n_systematic {
+		return Err(Error::NotEnoughChunks);
+	}
+
+	let chunks = &chunks[..n_systematic];
+	let shard_len = chunks[0].len();
+
+	if shard_len % 2 != 0 {
+		return Err(Error::UnevenLength);
+	}
+
+	if shard_len == 0 || chunks.iter().any(|chunk| chunk.len() != shard_len) {
+		return Err(Error::NonUniformChunks);
+	}
+
+	let mut payload_bytes = Vec::with_capacity(shard_len * n_systematic);
+	for symbol in (0..shard_len).step_by(2) {
+		for chunk in chunks {
+			payload_bytes.extend_from_slice(&chunk[symbol..symbol + 2]);
+		}
+	}
+
+	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
+}
+
 /// An iterator that yields merkle branches and chunk data for all chunks to
 /// be sent to other validators.
 pub struct Branches<'a, I> {
@@ -406,6 +473,51 @@ mod tests {
 		assert_eq!(reconstructed, available_data);
 	}
 
+	#[test]
+	fn systematic_recovery_threshold_is_power_of_2() {
+		assert_eq!(systematic_recovery_threshold(2), Ok(1));
+		assert_eq!(systematic_recovery_threshold(10), Ok(4));
+		assert_eq!(systematic_recovery_threshold(13), Ok(4));
+		assert_eq!(systematic_recovery_threshold(1000), Ok(256));
+		assert_eq!(systematic_recovery_threshold(1), Err(Error::NotEnoughValidators));
+	}
+
+	#[test]
+	fn systematic_round_trip_works() {
+		let available_data = AvailableData {
+			pov_block: PoVBlock {
+				block_data: BlockData((0..255).collect()),
+			},
+			omitted_validation: Default::default(),
+		};
+
+		for n_validators in [2, 3, 10, 13, 100, 1000].iter().cloned() {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let reconstructed: AvailableData = reconstruct_from_systematic(
+				n_validators,
+				chunks,
+			).unwrap();
+
+			assert_eq!(reconstructed, available_data);
+		}
+	}
+
+	#[test]
+	fn systematic_reconstruction_needs_all_systematic_chunks() {
+		let available_data = AvailableData {
+			pov_block: PoVBlock {
+				block_data: BlockData((0..255).collect()),
+			},
+			omitted_validation: Default::default(),
+		};
+
+		let mut chunks = obtain_chunks(10, &available_data).unwrap();
+		chunks.truncate(3);
+
+		let reconstructed: Result<AvailableData, _> = reconstruct_from_systematic(10, chunks);
+		assert_eq!(reconstructed, Err(Error::NotEnoughChunks));
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(
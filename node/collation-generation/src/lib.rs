@@ -300,6 +300,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 			let metrics = metrics.clone();
 			ctx.spawn("collation generation collation builder", Box::pin(async move {
 				let persisted_validation_data_hash = validation_data.hash();
+				let parent_head_data_hash = validation_data.parent_head.hash();
 
 				let (collation, result_sender) = match (task_config.collator)(relay_parent, &validation_data).await {
 					Some(collation) => collation.into_inner(),
@@ -400,7 +401,12 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				metrics.on_collation_generated();
 
 				if let Err(err) = task_sender.send(AllMessages::CollatorProtocol(
-					CollatorProtocolMessage::DistributeCollation(ccr, pov, result_sender)
+					CollatorProtocolMessage::DistributeCollation(
+						ccr,
+						parent_head_data_hash,
+						pov,
+						result_sender,
+					)
 				)).await {
 					tracing::warn!(
 						target: LOG_TARGET,
@@ -834,6 +840,7 @@ mod tests {
 			match &sent_messages[0] {
 				AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
 					CandidateReceipt { descriptor, .. },
+					_parent_head_data_hash,
 					_pov,
 					..
 				)) => {
@@ -31,3 +31,4 @@
 //! time being we share the same DB with the rest of Substrate.
 
 pub mod v1;
+pub mod v2;
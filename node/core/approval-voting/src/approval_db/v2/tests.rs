@@ -534,7 +534,8 @@ fn force_approve_works() {
 		).unwrap();
 	}
 
-	force_approve(&store, TEST_CONFIG,  block_hash_d, 2).unwrap();
+	let approved_hashes = force_approve(&store, TEST_CONFIG,  block_hash_d, 2).unwrap();
+	assert_eq!(approved_hashes, vec![block_hash_b, block_hash_a]);
 
 	assert!(load_block_entry(
 		&store,
@@ -625,3 +626,80 @@ fn load_all_blocks_works() {
 		vec![block_hash_a, block_hash_b, block_hash_c],
 	)
 }
+
+#[test]
+fn migrate_from_v1_works() {
+	let store = kvdb_memorydb::create(NUM_COLUMNS);
+
+	let hash_a = Hash::repeat_byte(1);
+	let candidate_hash = CandidateHash(Hash::repeat_byte(3));
+
+	let block_entry = make_block_entry(
+		hash_a,
+		Default::default(),
+		1,
+		vec![(CoreIndex(0), candidate_hash)],
+	);
+
+	let v1_candidate_entry = v1::CandidateEntry {
+		candidate: make_candidate(1.into(), hash_a),
+		session: 5,
+		block_assignments: vec![
+			(hash_a, v1::ApprovalEntry {
+				tranches: vec![v1::TrancheEntry {
+					tranche: 1,
+					assignments: vec![
+						(ValidatorIndex(2), v1::Tick(6_500_000_000)),
+						(ValidatorIndex(300), v1::Tick(6_500_000_001)),
+					],
+				}],
+				backing_group: GroupIndex(1),
+				our_assignment: None,
+				our_approval_sig: None,
+				assignments: make_bitvec(400),
+				approved: false,
+			})
+		].into_iter().collect(),
+		approvals: make_bitvec(400),
+	};
+
+	let mut tx = DBTransaction::new();
+	write_block_entry(&mut tx, &hash_a, &block_entry);
+	tx.put_vec(DATA_COL, &candidate_entry_key(&candidate_hash)[..], v1_candidate_entry.encode());
+	store.write(tx).unwrap();
+
+	let v1_len = store.get(DATA_COL, &candidate_entry_key(&candidate_hash)[..]).unwrap().unwrap().len();
+
+	migrate_from_v1(&store, &TEST_CONFIG).unwrap();
+
+	let expected = CandidateEntry {
+		candidate: make_candidate(1.into(), hash_a),
+		session: 5,
+		block_assignments: vec![
+			(hash_a, ApprovalEntry {
+				tranches: vec![TrancheEntry {
+					tranche: 1,
+					assignments: vec![
+						(ValidatorIndex(2).into(), Tick(6_500_000_000)),
+						(ValidatorIndex(300).into(), Tick(6_500_000_001)),
+					],
+				}],
+				backing_group: GroupIndex(1),
+				our_assignment: None,
+				our_approval_sig: None,
+				assignments: make_bitvec(400),
+				approved: false,
+			})
+		].into_iter().collect(),
+		approvals: make_bitvec(400),
+	};
+
+	let v2_len = store.get(DATA_COL, &candidate_entry_key(&candidate_hash)[..]).unwrap().unwrap().len();
+	assert!(v2_len < v1_len);
+
+	assert_eq!(
+		load_candidate_entry(&store, &TEST_CONFIG, &candidate_hash).unwrap(),
+		Some(expected),
+	);
+	assert_eq!(load_block_entry(&store, &TEST_CONFIG, &hash_a).unwrap(), Some(block_entry));
+}
@@ -31,6 +31,7 @@
 use polkadot_node_subsystem::{
 	messages::{
 		RuntimeApiMessage, RuntimeApiRequest, ChainApiMessage, ApprovalDistributionMessage,
+		ChainSelectionMessage,
 	},
 	SubsystemContext, SubsystemError, SubsystemResult,
 };
@@ -730,7 +731,11 @@ pub(crate) async fn handle_new_head(
 			}
 		};
 
-		let block_entry = approval_db::v1::BlockEntry {
+		// Blocks with no candidates, or where all candidates were insta-approved,
+		// are approved from the moment of import.
+		let approved_at_import = approved_bitfield.all();
+
+		let block_entry = approval_db::v2::BlockEntry {
 			block_hash,
 			parent_hash: block_header.parent_hash,
 			block_number: block_header.number,
@@ -751,8 +756,13 @@ pub(crate) async fn handle_new_head(
 				"Enacting force-approve",
 			);
 
-			approval_db::v1::force_approve(db_writer, db_config, block_hash, up_to)
+			let approved_hashes = approval_db::v2::force_approve(db_writer, db_config, block_hash, up_to)
 				.map_err(|e| SubsystemError::with_origin("approval-voting", e))?;
+
+			// Notify chain-selection of all approved hashes.
+			for hash in approved_hashes {
+				ctx.send_message(ChainSelectionMessage::Approved(hash).into()).await;
+			}
 		}
 
 		tracing::trace!(
@@ -762,20 +772,25 @@ pub(crate) async fn handle_new_head(
 			"Writing BlockEntry",
 		);
 
-		let candidate_entries = approval_db::v1::add_block_entry(
+		let candidate_entries = approval_db::v2::add_block_entry(
 			db_writer,
 			&db_config,
 			block_entry,
 			n_validators,
 			|candidate_hash| {
 				included_candidates.iter().find(|(hash, _, _, _)| candidate_hash == hash)
-					.map(|(_, receipt, core, backing_group)| approval_db::v1::NewCandidateInfo {
+					.map(|(_, receipt, core, backing_group)| approval_db::v2::NewCandidateInfo {
 						candidate: receipt.clone(),
 						backing_group: *backing_group,
 						our_assignment: assignments.get(core).map(|a| a.clone().into()),
 					})
 			}
 		).map_err(|e| SubsystemError::with_origin("approval-voting", e))?;
+
+		if approved_at_import {
+			ctx.send_message(ChainSelectionMessage::Approved(block_hash).into()).await;
+		}
+
 		approval_meta.push(BlockApprovalMeta {
 			hash: block_hash,
 			number: block_header.number,
@@ -1122,7 +1137,7 @@ mod tests {
 
 		db.block_entries.insert(
 			known_hash,
-			crate::approval_db::v1::BlockEntry {
+			crate::approval_db::v2::BlockEntry {
 				block_hash: known_hash,
 				parent_hash: Default::default(),
 				block_number: known_number,
@@ -1199,7 +1214,7 @@ mod tests {
 
 		db.block_entries.insert(
 			head_hash,
-			crate::approval_db::v1::BlockEntry {
+			crate::approval_db::v2::BlockEntry {
 				block_hash: head_hash,
 				parent_hash: Default::default(),
 				block_number: 18,
@@ -1249,7 +1264,7 @@ mod tests {
 
 		db.block_entries.insert(
 			parent_hash,
-			crate::approval_db::v1::BlockEntry {
+			crate::approval_db::v2::BlockEntry {
 				block_hash: parent_hash,
 				parent_hash: Default::default(),
 				block_number: 18,
@@ -1297,7 +1312,7 @@ mod tests {
 
 		db.block_entries.insert(
 			parent_hash,
-			crate::approval_db::v1::BlockEntry {
+			crate::approval_db::v2::BlockEntry {
 				block_hash: parent_hash,
 				parent_hash: Default::default(),
 				block_number: 18,
@@ -1893,7 +1908,7 @@ mod tests {
 		let mut state = single_session_state(session, session_info);
 		state.db.block_entries.insert(
 			parent_hash.clone(),
-			crate::approval_db::v1::BlockEntry {
+			crate::approval_db::v2::BlockEntry {
 				block_hash: parent_hash.clone(),
 				parent_hash: Default::default(),
 				block_number: 4,
@@ -1926,7 +1941,7 @@ mod tests {
 				assert_eq!(candidates[1].1.approvals().len(), 6);
 				// the first candidate should be insta-approved
 				// the second should not
-				let entry: BlockEntry = crate::approval_db::v1::load_block_entry(
+				let entry: BlockEntry = crate::approval_db::v2::load_block_entry(
 					&db_writer,
 					&TEST_CONFIG,
 					&hash,
@@ -26,10 +26,11 @@ use polkadot_node_subsystem::{
 		AssignmentCheckResult, ApprovalCheckResult, ApprovalVotingMessage,
 		RuntimeApiMessage, RuntimeApiRequest, ChainApiMessage, ApprovalDistributionMessage,
 		ValidationFailed, CandidateValidationMessage, AvailabilityRecoveryMessage,
+		DisputeCoordinatorMessage, ChainSelectionMessage,
 	},
 	errors::RecoveryError,
 	Subsystem, SubsystemContext, SubsystemError, SubsystemResult, SpawnedSubsystem,
-	FromOverseer, OverseerSignal,
+	SubsystemSender, FromOverseer, OverseerSignal,
 };
 use polkadot_node_subsystem_util::{
 	metrics::{self, prometheus},
@@ -38,15 +39,14 @@ use polkadot_primitives::v1::{
 	ValidatorIndex, Hash, SessionIndex, SessionInfo, CandidateHash,
 	CandidateReceipt, BlockNumber, PersistedValidationData,
 	ValidationCode, CandidateDescriptor, ValidatorPair, ValidatorSignature, ValidatorId,
-	CandidateIndex, GroupIndex,
+	CandidateIndex, GroupIndex, DisputeStatement, ValidDisputeStatementKind,
 };
-use polkadot_node_primitives::{ValidationResult, PoV};
+use polkadot_node_primitives::{ValidationResult, PoV, InvalidCandidate, SignedDisputeStatement};
 use polkadot_node_primitives::approval::{
 	IndirectAssignmentCert, IndirectSignedApprovalVote, ApprovalVote, DelayTranche,
 	BlockApprovalMeta,
 };
 use polkadot_node_jaeger as jaeger;
-use parity_scale_codec::Encode;
 use sc_keystore::LocalKeystore;
 use sp_consensus::SyncOracle;
 use sp_consensus_slots::Slot;
@@ -74,7 +74,7 @@ mod import;
 mod time;
 mod persisted_entries;
 
-use crate::approval_db::v1::Config as DatabaseConfig;
+use crate::approval_db::v2::Config as DatabaseConfig;
 
 #[cfg(test)]
 mod tests;
@@ -278,6 +278,15 @@ impl ApprovalVotingSubsystem {
 	}
 }
 
+/// Migrate the approval-voting data stored in the given column of the DB from version 1 of the
+/// on-disk schema to version 2.
+///
+/// This is meant to be run once, at startup, before the subsystem is started.
+pub fn migrate_db_v1_to_v2(db: &dyn KeyValueDB, col_data: u32) -> std::io::Result<()> {
+	approval_db::v2::migrate_from_v1(db, &DatabaseConfig { col_data })
+		.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+}
+
 impl<C> Subsystem<C> for ApprovalVotingSubsystem
 	where C: SubsystemContext<Message = ApprovalVotingMessage>
 {
@@ -440,35 +449,35 @@ trait DBReader {
 }
 
 // This is a submodule to enforce opacity of the inner DB type.
-mod approval_db_v1_reader {
+mod approval_db_v2_reader {
 	use super::{
 		DBReader, KeyValueDB, Hash, CandidateHash, BlockEntry, CandidateEntry,
 		SubsystemResult, SubsystemError, DatabaseConfig, approval_db,
 	};
 
-	/// A DB reader that uses the approval-db V1 under the hood.
-	pub(super) struct ApprovalDBV1Reader<T> {
+	/// A DB reader that uses the approval-db V2 under the hood.
+	pub(super) struct ApprovalDBV2Reader<T> {
 		inner: T,
 		config: DatabaseConfig,
 	}
 
-	impl<T> ApprovalDBV1Reader<T> {
+	impl<T> ApprovalDBV2Reader<T> {
 		pub(super) fn new(inner: T, config: DatabaseConfig) -> Self {
-			ApprovalDBV1Reader {
+			ApprovalDBV2Reader {
 				inner,
 				config,
 			}
 		}
 	}
 
-	impl<'a, T: 'a> DBReader for ApprovalDBV1Reader<T>
+	impl<'a, T: 'a> DBReader for ApprovalDBV2Reader<T>
 		where T: std::ops::Deref<Target=(dyn KeyValueDB + 'a)>
 	{
 		fn load_block_entry(
 			&self,
 			block_hash: &Hash,
 		) -> SubsystemResult<Option<BlockEntry>> {
-			approval_db::v1::load_block_entry(&*self.inner, &self.config, block_hash)
+			approval_db::v2::load_block_entry(&*self.inner, &self.config, block_hash)
 				.map(|e| e.map(Into::into))
 				.map_err(|e| SubsystemError::with_origin("approval-voting", e))
 		}
@@ -477,18 +486,18 @@ mod approval_db_v1_reader {
 			&self,
 			candidate_hash: &CandidateHash,
 		) -> SubsystemResult<Option<CandidateEntry>> {
-			approval_db::v1::load_candidate_entry(&*self.inner, &self.config, candidate_hash)
+			approval_db::v2::load_candidate_entry(&*self.inner, &self.config, candidate_hash)
 				.map(|e| e.map(Into::into))
 				.map_err(|e| SubsystemError::with_origin("approval-voting", e))
 		}
 
 		fn load_all_blocks(&self) -> SubsystemResult<Vec<Hash>> {
-			approval_db::v1::load_all_blocks(&*self.inner, &self.config)
+			approval_db::v2::load_all_blocks(&*self.inner, &self.config)
 				.map_err(|e| SubsystemError::with_origin("approval-voting", e))
 		}
 	}
 }
-use approval_db_v1_reader::ApprovalDBV1Reader;
+use approval_db_v2_reader::ApprovalDBV2Reader;
 
 struct ApprovalStatus {
 	required_tranches: RequiredTranches,
@@ -575,6 +584,14 @@ enum Action {
 		candidate: CandidateReceipt,
 		backing_group: GroupIndex,
 	},
+	ImportDisputeStatement {
+		candidate_hash: CandidateHash,
+		candidate: CandidateReceipt,
+		session: SessionIndex,
+		statement: SignedDisputeStatement,
+		validator_index: ValidatorIndex,
+	},
+	NoteApprovedInChainSelection(Hash),
 	BecomeActive,
 	Conclude,
 }
@@ -594,7 +611,7 @@ async fn run<C>(
 		session_window: Default::default(),
 		keystore: subsystem.keystore,
 		slot_duration_millis: subsystem.slot_duration_millis,
-		db: ApprovalDBV1Reader::new(subsystem.db.clone(), subsystem.db_config.clone()),
+		db: ApprovalDBV2Reader::new(subsystem.db.clone(), subsystem.db_config.clone()),
 		clock,
 		assignment_criteria,
 	};
@@ -689,7 +706,7 @@ async fn handle_actions(
 	mode: &mut Mode,
 	actions: impl IntoIterator<Item = Action>,
 ) -> SubsystemResult<bool> {
-	let mut transaction = approval_db::v1::Transaction::new(db_config);
+	let mut transaction = approval_db::v2::Transaction::new(db_config);
 	let mut conclude = false;
 
 	for action in actions {
@@ -743,11 +760,29 @@ async fn handle_actions(
 					background_tasks.entry(relay_block_number).or_default().push(handle);
 				}
 			}
+			Action::ImportDisputeStatement {
+				candidate_hash,
+				candidate,
+				session,
+				statement,
+				validator_index,
+			} => {
+				ctx.send_message(DisputeCoordinatorMessage::ImportStatements {
+					candidate_hash,
+					candidate_receipt: candidate,
+					session,
+					statements: vec![(statement, validator_index)],
+					pending_confirmation: None,
+				}.into()).await;
+			}
+			Action::NoteApprovedInChainSelection(block_hash) => {
+				ctx.send_message(ChainSelectionMessage::Approved(block_hash).into()).await;
+			}
 			Action::BecomeActive => {
 				*mode = Mode::Active;
 
 				let messages = distribution_messages_for_activation(
-					ApprovalDBV1Reader::new(db, db_config)
+					ApprovalDBV2Reader::new(db, db_config)
 				)?;
 
 				ctx.send_messages(messages.into_iter().map(Into::into)).await;
@@ -952,7 +987,7 @@ async fn handle_from_overseer(
 		FromOverseer::Signal(OverseerSignal::BlockFinalized(block_hash, block_number)) => {
 			*last_finalized_height = Some(block_number);
 
-			approval_db::v1::canonicalize(db_writer, &db_config, block_number, block_hash)
+			approval_db::v2::canonicalize(db_writer, &db_config, block_number, block_hash)
 				.map_err(|e| SubsystemError::with_origin("db", e))?;
 
 			wakeups.prune_finalized_wakeups(block_number);
@@ -1230,9 +1265,7 @@ fn approval_signing_payload(
 	approval_vote: ApprovalVote,
 	session_index: SessionIndex,
 ) -> Vec<u8> {
-	const MAGIC: [u8; 4] = *b"APPR";
-
-	(MAGIC, approval_vote, session_index).encode()
+	approval_vote.signing_payload(session_index)
 }
 
 // `Option::cmp` treats `None` as less than `Some`.
@@ -1514,7 +1547,24 @@ fn check_and_import_approval<T>(
 		"Importing approval vote",
 	);
 
-	let actions = import_checked_approval(
+	// Only votes which are new to us need to be imported into the dispute coordinator.
+	let dispute_statement = if candidate_entry.approvals()
+		.get(approval.validator.0 as usize)
+		.map_or(false, |b| *b)
+	{
+		None
+	} else {
+		Some(approval_dispute_statement(
+			&block_entry,
+			&candidate_entry,
+			approved_candidate_hash,
+			pubkey.clone(),
+			approval.validator,
+			approval.signature,
+		))
+	};
+
+	let mut actions = import_checked_approval(
 		state,
 		&metrics,
 		block_entry,
@@ -1523,6 +1573,8 @@ fn check_and_import_approval<T>(
 		ApprovalSource::Remote(approval.validator),
 	);
 
+	actions.extend(dispute_statement);
+
 	Ok((actions, t))
 }
 
@@ -1585,6 +1637,7 @@ fn import_checked_approval(
 	}
 
 	let mut actions = Vec::new();
+	let mut newly_approved_block = false;
 	let block_hash = block_entry.block_hash();
 	let block_number = block_entry.block_number();
 
@@ -1621,6 +1674,7 @@ fn import_checked_approval(
 
 			if is_block_approved && !was_block_approved {
 				metrics.on_block_approved(status.tranche_now as _);
+				newly_approved_block = true;
 			}
 
 			actions.push(Action::WriteBlockEntry(block_entry));
@@ -1676,6 +1730,10 @@ fn import_checked_approval(
 
 	}
 
+	if newly_approved_block {
+		actions.push(Action::NoteApprovedInChainSelection(block_hash));
+	}
+
 	actions
 }
 
@@ -1927,6 +1985,7 @@ async fn launch_approval(
 	).await;
 
 	let candidate = candidate.clone();
+	let mut sender = ctx.sender().clone();
 	let background = async move {
 		let _span = jaeger::Span::from_encodable((block_hash, candidate_hash), "launch-approval")
 			.with_relay_parent(block_hash)
@@ -1952,8 +2011,14 @@ async fn launch_approval(
 					(candidate_hash, candidate.descriptor.para_id),
 				);
 
-				// TODO: dispute. Either the merkle trie is bad or the erasure root is.
-				// https://github.com/paritytech/polkadot/issues/2176
+				// Either the merkle trie is bad or the erasure root is.
+				sender.send_message(DisputeCoordinatorMessage::IssueLocalStatement(
+					session_index,
+					candidate_hash,
+					candidate.clone(),
+					false,
+				).into()).await;
+
 				return;
 			}
 		};
@@ -1982,7 +2047,7 @@ async fn launch_approval(
 		let _ = background_tx.send(BackgroundRequest::CandidateValidation(
 			available_data.validation_data,
 			validation_code,
-			candidate.descriptor,
+			candidate.descriptor.clone(),
 			available_data.pov,
 			val_tx,
 		)).await;
@@ -2015,8 +2080,15 @@ async fn launch_approval(
 					"Detected invalid candidate as an approval checker.",
 				);
 
-				// TODO: issue dispute, but not for timeouts.
-				// https://github.com/paritytech/polkadot/issues/2176
+				// Timeouts may be caused by local conditions, so don't dispute over them.
+				if let InvalidCandidate::Timeout = reason { return }
+
+				sender.send_message(DisputeCoordinatorMessage::IssueLocalStatement(
+					session_index,
+					candidate_hash,
+					candidate,
+					false,
+				).into()).await;
 			}
 			Ok(Err(e)) => {
 				tracing::error!(
@@ -2134,7 +2206,16 @@ fn issue_approval(
 		"Issuing approval vote",
 	);
 
-	let actions = import_checked_approval(
+	let dispute_statement = approval_dispute_statement(
+		&block_entry,
+		&candidate_entry,
+		candidate_hash,
+		validator_pubkey.clone(),
+		validator_index,
+		sig.clone(),
+	);
+
+	let mut actions = import_checked_approval(
 		state,
 		metrics,
 		block_entry,
@@ -2143,6 +2224,8 @@ fn issue_approval(
 		ApprovalSource::Local(validator_index as _, sig.clone()),
 	);
 
+	actions.push(dispute_statement);
+
 	metrics.on_approval_produced();
 
 	// dispatch to approval distribution.
@@ -2158,6 +2241,33 @@ fn issue_approval(
 	Ok(actions)
 }
 
+// Create an action importing an approval vote, which has already been checked, into the
+// dispute coordinator.
+fn approval_dispute_statement(
+	block_entry: &BlockEntry,
+	candidate_entry: &CandidateEntry,
+	candidate_hash: CandidateHash,
+	validator_public: ValidatorId,
+	validator_index: ValidatorIndex,
+	signature: ValidatorSignature,
+) -> Action {
+	let statement = SignedDisputeStatement::new_unchecked_from_trusted_source(
+		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking),
+		candidate_hash,
+		block_entry.session(),
+		validator_public,
+		signature,
+	);
+
+	Action::ImportDisputeStatement {
+		candidate_hash,
+		candidate: candidate_entry.candidate_receipt().clone(),
+		session: block_entry.session(),
+		statement,
+		validator_index,
+	}
+}
+
 // Sign an approval vote. Fails if the key isn't present in the store.
 fn sign_approval(
 	keystore: &LocalKeystore,
@@ -321,7 +321,7 @@ fn add_block(
 ) {
 	db.block_entries.insert(
 		block_hash,
-		approval_db::v1::BlockEntry {
+		approval_db::v2::BlockEntry {
 			block_hash,
 			parent_hash: Default::default(),
 			block_number: 0,
@@ -347,7 +347,7 @@ fn add_candidate_to_block(
 
 	let candidate_entry = db.candidate_entries
 		.entry(candidate_hash)
-		.or_insert_with(|| approval_db::v1::CandidateEntry {
+		.or_insert_with(|| approval_db::v2::CandidateEntry {
 			session: block_entry.session(),
 			block_assignments: Default::default(),
 			candidate: CandidateReceipt::default(),
@@ -358,7 +358,7 @@ fn add_candidate_to_block(
 
 	candidate_entry.add_approval_entry(
 		block_hash,
-		approval_db::v1::ApprovalEntry {
+		approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group,
 			our_assignment: None,
@@ -692,7 +692,7 @@ fn accepts_and_imports_approval_after_assignment() {
 
 	assert_eq!(res, ApprovalCheckResult::Accepted);
 
-	assert_eq!(actions.len(), 1);
+	assert_eq!(actions.len(), 2);
 	assert_matches!(
 		actions.get(0).unwrap(),
 		Action::WriteCandidateEntry(c_hash, c_entry) => {
@@ -701,6 +701,14 @@ fn accepts_and_imports_approval_after_assignment() {
 			assert!(!c_entry.approval_entry(&block_hash).unwrap().is_approved());
 		}
 	);
+	assert_matches!(
+		actions.get(1).unwrap(),
+		Action::ImportDisputeStatement { candidate_hash: c_hash, session, validator_index: v, .. } => {
+			assert_eq!(c_hash, &candidate_hash);
+			assert_eq!(*session, 1);
+			assert_eq!(v, &validator_index);
+		}
+	);
 }
 
 #[test]
@@ -862,6 +870,12 @@ fn import_checked_approval_updates_entries_and_schedules() {
 				assert!(c_entry.mark_approval(validator_index_b));
 			}
 		);
+		assert_matches!(
+			actions.get(2).unwrap(),
+			Action::NoteApprovedInChainSelection(b_hash) => {
+				assert_eq!(b_hash, &block_hash);
+			}
+		);
 	}
 }
 
@@ -870,10 +884,10 @@ fn assignment_triggered_by_all_with_less_than_threshold() {
 	let block_hash = Hash::repeat_byte(0x01);
 
 	let mut candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -886,7 +900,7 @@ fn assignment_triggered_by_all_with_less_than_threshold() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -916,10 +930,10 @@ fn assignment_not_triggered_by_all_with_threshold() {
 	let block_hash = Hash::repeat_byte(0x01);
 
 	let mut candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -932,7 +946,7 @@ fn assignment_not_triggered_by_all_with_threshold() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -968,10 +982,10 @@ fn assignment_not_triggered_if_already_triggered() {
 	let block_hash = Hash::repeat_byte(0x01);
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -984,7 +998,7 @@ fn assignment_not_triggered_if_already_triggered() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1006,10 +1020,10 @@ fn assignment_not_triggered_by_exact() {
 	let block_hash = Hash::repeat_byte(0x01);
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -1022,7 +1036,7 @@ fn assignment_not_triggered_by_exact() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1045,10 +1059,10 @@ fn assignment_not_triggered_more_than_maximum() {
 	let maximum_broadcast = 10;
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -1061,7 +1075,7 @@ fn assignment_not_triggered_more_than_maximum() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1089,10 +1103,10 @@ fn assignment_triggered_if_at_maximum() {
 	let maximum_broadcast = 10;
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -1105,7 +1119,7 @@ fn assignment_triggered_if_at_maximum() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1133,10 +1147,10 @@ fn assignment_not_triggered_if_at_maximum_but_clock_is_before() {
 	let maximum_broadcast = 10;
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -1149,7 +1163,7 @@ fn assignment_not_triggered_if_at_maximum_but_clock_is_before() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1177,10 +1191,10 @@ fn assignment_not_triggered_if_at_maximum_but_clock_is_before_with_drift() {
 	let maximum_broadcast = 10;
 
 	let candidate_entry: CandidateEntry = {
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			backing_group: GroupIndex(0),
-			our_assignment: Some(approval_db::v1::OurAssignment {
+			our_assignment: Some(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
@@ -1193,7 +1207,7 @@ fn assignment_not_triggered_if_at_maximum_but_clock_is_before_with_drift() {
 			approved: false,
 		};
 
-		approval_db::v1::CandidateEntry {
+		approval_db::v2::CandidateEntry {
 			candidate: Default::default(),
 			session: 1,
 			block_assignments: vec![(block_hash, approval_entry)].into_iter().collect(),
@@ -1389,7 +1403,7 @@ fn import_checked_approval_sets_one_block_bit_at_a_time() {
 		ApprovalSource::Remote(validator_index_b),
 	);
 
-	assert_eq!(actions.len(), 2);
+	assert_eq!(actions.len(), 3);
 	assert_matches!(
 		actions.get(0).unwrap(),
 		Action::WriteBlockEntry(b_entry) => {
@@ -1407,6 +1421,13 @@ fn import_checked_approval_sets_one_block_bit_at_a_time() {
 			assert!(c_entry.approval_entry(&block_hash).unwrap().is_approved());
 		}
 	);
+
+	assert_matches!(
+		actions.get(2).unwrap(),
+		Action::NoteApprovedInChainSelection(b_hash) => {
+			assert_eq!(b_hash, &block_hash);
+		}
+	);
 }
 
 #[test]
@@ -1610,7 +1631,7 @@ fn process_wakeup_trigger_assignment_launch_approval() {
 		.unwrap()
 		.approval_entry_mut(&block_hash)
 		.unwrap()
-		.set_our_assignment(approval_db::v1::OurAssignment {
+		.set_our_assignment(approval_db::v2::OurAssignment {
 			cert: garbage_assignment_cert(
 				AssignmentCertKind::RelayVRFModulo { sample: 0 }
 			),
@@ -1688,7 +1709,7 @@ fn process_wakeup_schedules_wakeup() {
 		.unwrap()
 		.approval_entry_mut(&block_hash)
 		.unwrap()
-		.set_our_assignment(approval_db::v1::OurAssignment {
+		.set_our_assignment(approval_db::v2::OurAssignment {
 			cert: garbage_assignment_cert(
 				AssignmentCertKind::RelayVRFModulo { sample: 0 }
 			),
@@ -1771,7 +1792,7 @@ fn local_approval_import_always_updates_approval_entry() {
 				.approval_entry_mut(&block_hash)
 				.unwrap();
 
-			approval_entry.set_our_assignment(approval_db::v1::OurAssignment {
+			approval_entry.set_our_assignment(approval_db::v2::OurAssignment {
 				cert: garbage_assignment_cert(
 					AssignmentCertKind::RelayVRFModulo { sample: 0 }
 				),
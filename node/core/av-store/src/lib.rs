@@ -1014,6 +1014,22 @@ fn process_message(
 				}
 			}
 		}
+		AvailabilityStoreMessage::QueryChunkSize(candidate, tx) => {
+			let meta = load_meta(&subsystem.db, &subsystem.config, &candidate)?;
+			let validator_index = meta.and_then(|meta| meta.chunks_stored.first_one());
+
+			let chunk_size = match validator_index {
+				Some(index) => load_chunk(
+					&subsystem.db,
+					&subsystem.config,
+					&candidate,
+					ValidatorIndex(index as _),
+				)?.map(|chunk| chunk.chunk.len()),
+				None => None,
+			};
+
+			let _ = tx.send(chunk_size);
+		}
 		AvailabilityStoreMessage::QueryChunkAvailability(candidate, validator_index, tx) => {
 			let a = load_meta(&subsystem.db, &subsystem.config, &candidate)?
 				.map_or(false, |m|
@@ -609,6 +609,22 @@ fn query_all_chunks_works() {
 			virtual_overseer.send(FromOverseer::Communication { msg }).await;
 			assert_eq!(rx.await.unwrap().len(), 0);
 		}
+
+		{
+			let (tx, rx) = oneshot::channel();
+
+			let msg = AvailabilityStoreMessage::QueryChunkSize(candidate_hash_2, tx);
+			virtual_overseer.send(FromOverseer::Communication { msg }).await;
+			assert_eq!(rx.await.unwrap(), Some(3));
+		}
+
+		{
+			let (tx, rx) = oneshot::channel();
+
+			let msg = AvailabilityStoreMessage::QueryChunkSize(candidate_hash_3, tx);
+			virtual_overseer.send(FromOverseer::Communication { msg }).await;
+			assert_eq!(rx.await.unwrap(), None);
+		}
 		virtual_overseer
 	});
 }
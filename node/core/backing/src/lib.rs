@@ -30,9 +30,10 @@ use polkadot_primitives::v1::{
 	BackedCandidate, CandidateCommitments, CandidateDescriptor, CandidateHash,
 	CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreIndex, CoreState, Hash, Id as ParaId,
 	SigningContext, ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation,
+	SessionIndex, DisputeStatement, ValidDisputeStatementKind,
 };
 use polkadot_node_primitives::{
-	Statement, SignedFullStatement, ValidationResult, PoV, AvailableData,
+	Statement, SignedFullStatement, ValidationResult, PoV, AvailableData, SignedDisputeStatement,
 };
 use polkadot_subsystem::{
 	PerLeafSpan, Stage, SubsystemSender,
@@ -40,7 +41,7 @@ use polkadot_subsystem::{
 	messages::{
 		AllMessages, AvailabilityDistributionMessage, AvailabilityStoreMessage,
 		CandidateBackingMessage, CandidateValidationMessage, CollatorProtocolMessage,
-		ProvisionableData, ProvisionerMessage, RuntimeApiRequest,
+		DisputeCoordinatorMessage, ProvisionableData, ProvisionerMessage, RuntimeApiRequest,
 		StatementDistributionMessage, ValidationFailed
 	}
 };
@@ -147,6 +148,8 @@ impl ValidatedCandidateCommand {
 pub struct CandidateBackingJob {
 	/// The hash of the relay parent on top of which this job is doing it's work.
 	parent: Hash,
+	/// The session index of the child of the relay parent.
+	session_index: SessionIndex,
 	/// The `ParaId` assigned to this validator
 	assignment: Option<ParaId>,
 	/// The collator required to author the candidate, if any.
@@ -614,9 +617,19 @@ impl CandidateBackingJob {
 				self.fallbacks.remove(&candidate_hash);
 				// sanity check.
 				if !self.issued_statements.contains(&candidate_hash) {
-					if res.is_ok() {
-						let statement = Statement::Valid(candidate_hash);
-						self.sign_import_and_distribute_statement(sender, statement, &root_span).await?;
+					match res {
+						Ok(_) => {
+							let statement = Statement::Valid(candidate_hash);
+							self.sign_import_and_distribute_statement(sender, statement, &root_span).await?;
+						}
+						Err(candidate) => {
+							sender.send_message(DisputeCoordinatorMessage::IssueLocalStatement(
+								self.session_index,
+								candidate_hash,
+								candidate,
+								false,
+							).into()).await;
+						}
 					}
 					self.issued_statements.insert(candidate_hash);
 				}
@@ -775,6 +788,10 @@ impl CandidateBackingJob {
 
 		let summary = self.table.import_statement(&self.table_context, stmt);
 
+		if summary.is_some() {
+			self.dispatch_new_statement_to_dispute_coordinator(sender, statement).await;
+		}
+
 		let unbacked_span = if let Some(attested) = summary.as_ref()
 			.and_then(|s| self.table.attested_candidate(&s.candidate, &self.table_context))
 		{
@@ -821,6 +838,53 @@ impl CandidateBackingJob {
 		Ok(summary)
 	}
 
+	/// Import a statement which has been newly accepted into the statement table
+	/// into the dispute coordinator as a backing vote.
+	async fn dispatch_new_statement_to_dispute_coordinator(
+		&self,
+		sender: &mut JobSender<impl SubsystemSender>,
+		statement: &SignedFullStatement,
+	) {
+		let candidate_hash = statement.payload().candidate_hash();
+		let validator_index = statement.validator_index();
+
+		let candidate_receipt = match self.table.get_candidate(&candidate_hash) {
+			Some(c) => c.to_plain(),
+			None => return,
+		};
+
+		let validator_public = match self.table_context.validators.get(validator_index.0 as usize) {
+			Some(v) => v.clone(),
+			None => return,
+		};
+
+		let dispute_statement = match statement.payload() {
+			Statement::Seconded(_) => DisputeStatement::Valid(
+				ValidDisputeStatementKind::BackingSeconded(self.parent),
+			),
+			Statement::Valid(_) => DisputeStatement::Valid(
+				ValidDisputeStatementKind::BackingValid(self.parent),
+			),
+		};
+
+		// Signatures of statements in the table have already been checked.
+		let statement = SignedDisputeStatement::new_unchecked_from_trusted_source(
+			dispute_statement,
+			candidate_hash,
+			self.session_index,
+			validator_public,
+			statement.signature().clone(),
+		);
+
+		sender.send_message(DisputeCoordinatorMessage::ImportStatements {
+			candidate_hash,
+			candidate_receipt,
+			session: self.session_index,
+			statements: vec![(statement, validator_index)],
+			pending_confirmation: None,
+		}.into()).await;
+	}
+
 	async fn process_msg(
 		&mut self,
 		root_span: &jaeger::Span,
@@ -1196,6 +1260,7 @@ impl util::JobTrait for CandidateBackingJob {
 			let (background_tx, background_rx) = mpsc::channel(16);
 			let job = CandidateBackingJob {
 				parent,
+				session_index,
 				assignment,
 				required_collator,
 				issued_statements: HashSet::new(),
@@ -1467,6 +1532,26 @@ mod tests {
 		}, subsystem));
 	}
 
+	async fn assert_import_into_dispute_coordinator(
+		virtual_overseer: &mut VirtualOverseer,
+		expected_candidate_hash: CandidateHash,
+		expected_validator_index: ValidatorIndex,
+	) {
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				statements,
+				..
+			}) => {
+				assert_eq!(candidate_hash, expected_candidate_hash);
+				assert_eq!(statements.len(), 1);
+				assert_eq!(statements[0].1, expected_validator_index);
+				assert_matches!(statements[0].0.statement(), DisputeStatement::Valid(_));
+			}
+		);
+	}
+
 	fn make_erasure_root(test: &TestState, pov: PoV) -> Hash {
 		let available_data = AvailableData {
 			validation_data: test.validation_data.clone(),
@@ -1624,6 +1709,12 @@ mod tests {
 				}
 			);
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate.hash(),
+				ValidatorIndex(0),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::StatementDistribution(
@@ -1705,6 +1796,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(2),
+			).await;
+
 			// Sending a `Statement::Seconded` for our assignment will start
 			// validation process. The first thing requested is the PoV.
 			assert_matches!(
@@ -1753,6 +1850,12 @@ mod tests {
 				}
 			);
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(0),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::StatementDistribution(
@@ -1769,6 +1872,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(5),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::Provisioner(
@@ -1853,8 +1962,15 @@ mod tests {
 			).await.ok().flatten().expect("should be signed");
 
 			let statement = CandidateBackingMessage::Statement(test_state.relay_parent, signed_a.clone());
+
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(2),
+			).await;
+
 			// Sending a `Statement::Seconded` for our assignment will start
 			// validation process. The first thing requested is PoV from the
 			// `PoVDistribution`.
@@ -1895,6 +2011,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(5),
+			).await;
+
 			let statement = CandidateBackingMessage::Statement(
 				test_state.relay_parent,
 				signed_c.clone(),
@@ -1902,6 +2024,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(3),
+			).await;
+
 			// Candidate gets backed entirely by other votes.
 			assert_matches!(
 				virtual_overseer.recv().await,
@@ -2000,6 +2128,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication { msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(2),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::AvailabilityDistribution(
@@ -2044,6 +2178,12 @@ mod tests {
 					}
 			);
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(0),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::StatementDistribution(
@@ -2204,6 +2344,12 @@ mod tests {
 				}
 			);
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_b.hash(),
+				ValidatorIndex(0),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::StatementDistribution(
@@ -2266,6 +2412,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate.hash(),
+				ValidatorIndex(2),
+			).await;
+
 			// Subsystem requests PoV and requests validation.
 			assert_matches!(
 				virtual_overseer.recv().await,
@@ -2295,6 +2447,17 @@ mod tests {
 				}
 			);
 
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::DisputeCoordinator(
+					DisputeCoordinatorMessage::IssueLocalStatement(session, candidate_hash, _, valid)
+				) => {
+					assert_eq!(session, test_state.signing_context.session_index);
+					assert_eq!(candidate_hash, candidate.hash());
+					assert!(!valid);
+				}
+			);
+
 			// Ask subsystem to `Second` a candidate that already has a statement issued about.
 			// This should emit no actions from subsystem.
 			let second = CandidateBackingMessage::Second(
@@ -2388,6 +2551,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate.hash(),
+				ValidatorIndex(2),
+			).await;
+
 			// Subsystem requests PoV and requests validation.
 			assert_matches!(
 				virtual_overseer.recv().await,
@@ -2676,6 +2845,12 @@ mod tests {
 			);
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate.hash(),
+				ValidatorIndex(2),
+			).await;
+
 			// Subsystem requests PoV and requests validation.
 			// We cancel - should mean retry on next backing statement.
 			assert_matches!(
@@ -2704,8 +2879,13 @@ mod tests {
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
 			// Not deterministic which message comes first:
-			for _ in 0u32..2 {
+			for _ in 0u32..4 {
 				match virtual_overseer.recv().await {
+					AllMessages::DisputeCoordinator(
+						DisputeCoordinatorMessage::ImportStatements { candidate_hash, .. }
+					) => {
+						assert_eq!(candidate_hash, candidate.hash());
+					}
 					AllMessages::Provisioner(
 						ProvisionerMessage::ProvisionableData(
 							_,
@@ -2834,6 +3014,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(0),
+			).await;
+
 			let statement = CandidateBackingMessage::Statement(
 				test_state.relay_parent,
 				signed_b.clone(),
@@ -2841,6 +3027,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(5),
+			).await;
+
 			let statement = CandidateBackingMessage::Statement(
 				test_state.relay_parent,
 				signed_c.clone(),
@@ -2848,6 +3040,12 @@ mod tests {
 
 			virtual_overseer.send(FromOverseer::Communication{ msg: statement }).await;
 
+			assert_import_into_dispute_coordinator(
+				&mut virtual_overseer,
+				candidate_a_hash,
+				ValidatorIndex(2),
+			).await;
+
 			assert_matches!(
 				virtual_overseer.recv().await,
 				AllMessages::Provisioner(
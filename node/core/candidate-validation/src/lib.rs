@@ -28,7 +28,7 @@ use polkadot_subsystem::{
 	FromOverseer, OverseerSignal,
 	messages::{
 		AllMessages, CandidateValidationMessage, RuntimeApiMessage,
-		ValidationFailed, RuntimeApiRequest,
+		ValidationFailed, RuntimeApiRequest, PreCheckOutcome,
 	},
 };
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
@@ -41,7 +41,9 @@ use polkadot_primitives::v1::{
 	OccupiedCoreAssumption, Hash, CandidateCommitments,
 };
 use polkadot_parachain::primitives::{ValidationParams, ValidationResult as WasmValidationResult};
-use polkadot_node_core_pvf::{Pvf, ValidationHost, ValidationError, InvalidCandidate as WasmInvalidCandidate};
+use polkadot_node_core_pvf::{
+	Pvf, ValidationHost, ValidationError, PrecheckError, InvalidCandidate as WasmInvalidCandidate,
+};
 
 use parity_scale_codec::Encode;
 
@@ -165,6 +167,20 @@ async fn run(
 						Err(e) => return Err(e),
 					}
 				}
+				CandidateValidationMessage::PreCheck(
+					relay_parent,
+					validation_code_hash,
+					response_sender,
+				) => {
+					let precheck_result = precheck_pvf(
+						&mut ctx,
+						&mut validation_host,
+						relay_parent,
+						validation_code_hash,
+					).await?;
+
+					let _ = response_sender.send(precheck_result);
+				}
 			}
 		}
 	}
@@ -332,6 +348,69 @@ async fn spawn_validate_from_chain_state(
 	validation_result
 }
 
+async fn precheck_pvf(
+	ctx: &mut impl SubsystemContext<Message = CandidateValidationMessage>,
+	mut validation_backend: impl ValidationBackend,
+	relay_parent: Hash,
+	validation_code_hash: Hash,
+) -> SubsystemResult<PreCheckOutcome> {
+	let (tx, rx) = oneshot::channel();
+	let validation_code = match runtime_api_request(
+		ctx,
+		relay_parent,
+		RuntimeApiRequest::ValidationCodeByHash(validation_code_hash, tx),
+		rx,
+	).await? {
+		Ok(Some(code)) => code,
+		Ok(None) | Err(_) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?validation_code_hash,
+				"Pre-check: validation code is not available",
+			);
+			return Ok(PreCheckOutcome::Failed);
+		}
+	};
+
+	let raw_validation_code = match sp_maybe_compressed_blob::decompress(
+		&validation_code.0,
+		VALIDATION_CODE_BOMB_LIMIT,
+	) {
+		Ok(code) => code,
+		Err(e) => {
+			tracing::debug!(target: LOG_TARGET, err=?e, "Pre-check: invalid validation code");
+
+			// The code is going to fail to decompress at every attempt of validation as well.
+			return Ok(PreCheckOutcome::Invalid);
+		}
+	};
+
+	let outcome = match validation_backend.precheck_pvf(raw_validation_code.to_vec()).await {
+		Ok(()) => PreCheckOutcome::Valid,
+		Err(PrecheckError::Invalid(e)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?validation_code_hash,
+				err = %e,
+				"Pre-check: the PVF failed to prepare",
+			);
+			PreCheckOutcome::Invalid
+		}
+		Err(PrecheckError::InternalError(e)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?validation_code_hash,
+				err = %e,
+				"Pre-check: failed to conclude",
+			);
+			PreCheckOutcome::Failed
+		}
+	};
+
+	Ok(outcome)
+}
+
 async fn validate_candidate_exhaustive(
 	mut validation_backend: impl ValidationBackend,
 	persisted_validation_data: PersistedValidationData,
@@ -436,6 +515,8 @@ trait ValidationBackend {
 		raw_validation_code: Vec<u8>,
 		params: ValidationParams
 	) -> Result<WasmValidationResult, ValidationError>;
+
+	async fn precheck_pvf(&mut self, raw_validation_code: Vec<u8>) -> Result<(), PrecheckError>;
 }
 
 #[async_trait]
@@ -461,6 +542,21 @@ impl ValidationBackend for &'_ mut ValidationHost {
 
 		validation_result
 	}
+
+	async fn precheck_pvf(&mut self, raw_validation_code: Vec<u8>) -> Result<(), PrecheckError> {
+		let (tx, rx) = oneshot::channel();
+		// Call the inherent method explicitly, it has the same name as this one.
+		if let Err(err) = ValidationHost::precheck_pvf(
+			&mut **self,
+			Pvf::from_code(raw_validation_code),
+			tx,
+		).await {
+			return Err(PrecheckError::InternalError(format!("cannot send pvf to the validation host: {:?}", err)));
+		}
+
+		rx.await
+			.map_err(|_| PrecheckError::InternalError("pre-check was cancelled".into()))?
+	}
 }
 
 /// Does basic checks of a candidate. Provide the encoded PoV-block. Returns `Ok` if basic checks
@@ -888,12 +984,21 @@ mod tests {
 
 	struct MockValidatorBackend {
 		result: Result<WasmValidationResult, ValidationError>,
+		precheck_result: Result<(), PrecheckError>,
 	}
 
 	impl MockValidatorBackend {
 		fn with_hardcoded_result(result: Result<WasmValidationResult, ValidationError>) -> Self {
 			Self {
 				result,
+				precheck_result: Ok(()),
+			}
+		}
+
+		fn with_hardcoded_precheck_result(precheck_result: Result<(), PrecheckError>) -> Self {
+			Self {
+				result: Err(ValidationError::InternalError("not used in pre-checking".into())),
+				precheck_result,
 			}
 		}
 	}
@@ -907,6 +1012,10 @@ mod tests {
 		) -> Result<WasmValidationResult, ValidationError> {
 			self.result.clone()
 		}
+
+		async fn precheck_pvf(&mut self, _raw_validation_code: Vec<u8>) -> Result<(), PrecheckError> {
+			self.precheck_result.clone()
+		}
 	}
 
 	#[test]
@@ -1203,4 +1312,83 @@ mod tests {
 			Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
 		);
 	}
+
+	fn run_precheck(
+		backend: MockValidatorBackend,
+		validation_code: Option<ValidationCode>,
+	) -> PreCheckOutcome {
+		let relay_parent = [2; 32].into();
+		let validation_code_hash = Hash::repeat_byte(3);
+
+		let pool = TaskExecutor::new();
+		let (mut ctx, mut ctx_handle) = test_helpers::make_subsystem_context(pool.clone());
+
+		let (check_fut, check_result) = precheck_pvf(
+			&mut ctx,
+			backend,
+			relay_parent,
+			validation_code_hash,
+		).remote_handle();
+
+		let test_fut = async move {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					rp,
+					RuntimeApiRequest::ValidationCodeByHash(hash, tx),
+				)) => {
+					assert_eq!(rp, relay_parent);
+					assert_eq!(hash, validation_code_hash);
+
+					let _ = tx.send(Ok(validation_code));
+				}
+			);
+
+			check_result.await.unwrap()
+		};
+
+		executor::block_on(future::join(test_fut, check_fut)).0
+	}
+
+	#[test]
+	fn precheck_works() {
+		assert_eq!(
+			run_precheck(
+				MockValidatorBackend::with_hardcoded_precheck_result(Ok(())),
+				Some(ValidationCode(vec![3; 16])),
+			),
+			PreCheckOutcome::Valid,
+		);
+	}
+
+	#[test]
+	fn precheck_invalid_pvf_is_invalid() {
+		assert_eq!(
+			run_precheck(
+				MockValidatorBackend::with_hardcoded_precheck_result(
+					Err(PrecheckError::Invalid("compilation failed".into())),
+				),
+				Some(ValidationCode(vec![3; 16])),
+			),
+			PreCheckOutcome::Invalid,
+		);
+	}
+
+	#[test]
+	fn precheck_fails_without_conclusion() {
+		assert_eq!(
+			run_precheck(
+				MockValidatorBackend::with_hardcoded_precheck_result(
+					Err(PrecheckError::InternalError("didn't make it".into())),
+				),
+				Some(ValidationCode(vec![3; 16])),
+			),
+			PreCheckOutcome::Failed,
+		);
+
+		assert_eq!(
+			run_precheck(MockValidatorBackend::with_hardcoded_precheck_result(Ok(())), None),
+			PreCheckOutcome::Failed,
+		);
+	}
 }
@@ -0,0 +1,623 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the subsystem.
+//!
+//! These primarily revolve around having a backend which is shared between
+//! both the test code and the tested subsystem, and which also gives the
+//! test code the ability to wait for write operations to occur.
+
+use super::*;
+use std::collections::{HashMap, BTreeMap};
+
+use futures::channel::oneshot;
+use futures::future::{self, BoxFuture};
+use parking_lot::Mutex;
+use sp_core::testing::TaskExecutor;
+use assert_matches::assert_matches;
+
+use polkadot_primitives::v1::{BlakeTwo256, HashT, ConsensusLog};
+use polkadot_node_subsystem::{
+	jaeger, ActiveLeavesUpdate, ActivatedLeaf, LeafStatus,
+	messages::AllMessages,
+};
+use polkadot_node_subsystem_test_helpers as test_helpers;
+
+type VirtualOverseer = test_helpers::TestSubsystemContextHandle<ChainSelectionMessage>;
+
+#[derive(Default)]
+struct TestBackendInner {
+	leaves: LeafEntrySet,
+	block_entries: HashMap<Hash, BlockEntry>,
+	blocks_by_number: BTreeMap<BlockNumber, Vec<Hash>>,
+	stagnant_at: BTreeMap<Timestamp, Vec<Hash>>,
+}
+
+#[derive(Clone, Default)]
+struct TestBackend {
+	inner: Arc<Mutex<TestBackendInner>>,
+}
+
+impl TestBackend {
+	fn leaves(&self) -> Vec<Hash> {
+		self.inner.lock().leaves.clone().into_hashes_descending().collect()
+	}
+
+	fn block_entry(&self, hash: &Hash) -> Option<BlockEntry> {
+		self.inner.lock().block_entries.get(hash).cloned()
+	}
+
+	fn has_blocks_at(&self, number: BlockNumber) -> bool {
+		self.inner.lock().blocks_by_number.contains_key(&number)
+	}
+}
+
+impl Backend for TestBackend {
+	fn load_block_entry(&self, hash: &Hash) -> Result<Option<BlockEntry>, Error> {
+		Ok(self.inner.lock().block_entries.get(hash).map(|e| e.clone()))
+	}
+	fn load_leaves(&self) -> Result<LeafEntrySet, Error> {
+		Ok(self.inner.lock().leaves.clone())
+	}
+	fn load_stagnant_at(&self, timestamp: Timestamp) -> Result<Vec<Hash>, Error> {
+		Ok(self.inner.lock().stagnant_at.get(&timestamp).map_or(Vec::new(), |s| s.clone()))
+	}
+	fn load_stagnant_at_up_to(&self, up_to: Timestamp)
+		-> Result<Vec<(Timestamp, Vec<Hash>)>, Error>
+	{
+		Ok(self.inner.lock().stagnant_at.range(..=up_to).map(|(t, v)| (*t, v.clone())).collect())
+	}
+	fn load_first_block_number(&self) -> Result<Option<BlockNumber>, Error> {
+		Ok(self.inner.lock().blocks_by_number.range(..).map(|(k, _)| *k).next())
+	}
+	fn load_blocks_by_number(&self, number: BlockNumber) -> Result<Vec<Hash>, Error> {
+		Ok(self.inner.lock().blocks_by_number.get(&number).map_or(Vec::new(), |v| v.clone()))
+	}
+
+	fn write<I>(&mut self, ops: I) -> Result<(), Error>
+		where I: IntoIterator<Item = BackendWriteOp>
+	{
+		let mut inner = self.inner.lock();
+
+		for op in ops {
+			match op {
+				BackendWriteOp::WriteBlockEntry(entry) => {
+					inner.block_entries.insert(entry.block_hash, entry);
+				}
+				BackendWriteOp::WriteBlocksByNumber(number, hashes) => {
+					if hashes.is_empty() {
+						inner.blocks_by_number.remove(&number);
+					} else {
+						inner.blocks_by_number.insert(number, hashes);
+					}
+				}
+				BackendWriteOp::WriteViableLeaves(leaves) => {
+					inner.leaves = leaves;
+				}
+				BackendWriteOp::WriteStagnantAt(time, hashes) => {
+					inner.stagnant_at.insert(time, hashes);
+				}
+				BackendWriteOp::DeleteBlocksByNumber(number) => {
+					inner.blocks_by_number.remove(&number);
+				}
+				BackendWriteOp::DeleteBlockEntry(hash) => {
+					inner.block_entries.remove(&hash);
+				}
+				BackendWriteOp::DeleteStagnantAt(time) => {
+					inner.stagnant_at.remove(&time);
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+struct TestClock;
+
+impl Clock for TestClock {
+	fn timestamp_now(&self) -> Timestamp {
+		0
+	}
+}
+
+// The chain data served to the subsystem over the `ChainApi`.
+#[derive(Default)]
+struct TestChain {
+	headers: HashMap<Hash, Header>,
+	weights: HashMap<Hash, BlockWeight>,
+	finalized: (BlockNumber, Hash),
+}
+
+impl TestChain {
+	fn new(finalized_number: BlockNumber, finalized_hash: Hash) -> Self {
+		TestChain {
+			finalized: (finalized_number, finalized_hash),
+			..Default::default()
+		}
+	}
+
+	// Add a child of the given parent with the given weight, returning its hash.
+	//
+	// The salt is used to distinguish between siblings.
+	fn add_child(
+		&mut self,
+		parent_hash: Hash,
+		parent_number: BlockNumber,
+		salt: u8,
+		weight: BlockWeight,
+		reversions: &[BlockNumber],
+	) -> Hash {
+		let mut header = Header {
+			parent_hash,
+			number: parent_number + 1,
+			state_root: BlakeTwo256::hash_of(&salt),
+			extrinsics_root: Hash::zero(),
+			digest: Default::default(),
+		};
+
+		for r in reversions {
+			header.digest.push(ConsensusLog::Revert(*r).into());
+		}
+
+		let hash = header.hash();
+		self.headers.insert(hash, header);
+		self.weights.insert(hash, weight);
+
+		hash
+	}
+
+	// Add a chain of `len` blocks on top of the given parent, each one with
+	// a weight of one more than its parent. Returns the hashes in ascending order.
+	fn add_chain(
+		&mut self,
+		parent_hash: Hash,
+		parent_number: BlockNumber,
+		parent_weight: BlockWeight,
+		len: BlockNumber,
+		salt: u8,
+	) -> Vec<Hash> {
+		let mut hashes = Vec::new();
+		let mut parent_hash = parent_hash;
+		for i in 0..len {
+			let hash = self.add_child(
+				parent_hash,
+				parent_number + i,
+				salt,
+				parent_weight + i + 1,
+				&[],
+			);
+			hashes.push(hash);
+			parent_hash = hash;
+		}
+
+		hashes
+	}
+
+	fn answer(&self, msg: AllMessages) {
+		match msg {
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx)) => {
+				let _ = tx.send(Ok(self.finalized.0));
+			}
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockHash(number, tx)) => {
+				assert_eq!(number, self.finalized.0);
+				let _ = tx.send(Ok(Some(self.finalized.1)));
+			}
+			AllMessages::ChainApi(ChainApiMessage::BlockHeader(hash, tx)) => {
+				let _ = tx.send(Ok(self.headers.get(&hash).cloned()));
+			}
+			AllMessages::ChainApi(ChainApiMessage::BlockWeight(hash, tx)) => {
+				let _ = tx.send(Ok(self.weights.get(&hash).cloned()));
+			}
+			msg => panic!("Unexpected message {:?}", msg),
+		}
+	}
+}
+
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	test: impl FnOnce(TestBackend, VirtualOverseer) -> T
+) {
+	let pool = TaskExecutor::new();
+	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool);
+
+	let backend = TestBackend::default();
+	let subsystem = crate::run(
+		context,
+		backend.clone(),
+		StagnantCheckInterval::never(),
+		Box::new(TestClock),
+	);
+
+	let test_fut = test(backend, virtual_overseer);
+	let test_and_conclude = async move {
+		let mut virtual_overseer = test_fut.await;
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem, test_and_conclude));
+}
+
+// Answer chain API requests until the response to a previously sent request arrives.
+async fn answer_requests_until<T>(
+	virtual_overseer: &mut VirtualOverseer,
+	chain: &TestChain,
+	rx: oneshot::Receiver<T>,
+) -> T {
+	let mut rx = rx.fuse();
+	loop {
+		futures::select! {
+			res = rx => return res.unwrap(),
+			msg = virtual_overseer.recv().fuse() => chain.answer(msg),
+		}
+	}
+}
+
+async fn query_leaves(
+	virtual_overseer: &mut VirtualOverseer,
+	chain: &TestChain,
+) -> Vec<Hash> {
+	let (tx, rx) = oneshot::channel();
+	virtual_overseer.send(FromOverseer::Communication {
+		msg: ChainSelectionMessage::Leaves(tx),
+	}).await;
+
+	answer_requests_until(virtual_overseer, chain, rx).await
+}
+
+async fn query_best_leaf_containing(
+	virtual_overseer: &mut VirtualOverseer,
+	chain: &TestChain,
+	required: Hash,
+) -> Option<Hash> {
+	let (tx, rx) = oneshot::channel();
+	virtual_overseer.send(FromOverseer::Communication {
+		msg: ChainSelectionMessage::BestLeafContaining(required, tx),
+	}).await;
+
+	answer_requests_until(virtual_overseer, chain, rx).await
+}
+
+// Activate the given head and return the leaves after the import has concluded.
+async fn import_head(
+	virtual_overseer: &mut VirtualOverseer,
+	chain: &TestChain,
+	head: Hash,
+) -> Vec<Hash> {
+	let number = chain.headers.get(&head).unwrap().number;
+	virtual_overseer.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+		ActiveLeavesUpdate::start_work(ActivatedLeaf {
+			hash: head,
+			number,
+			status: LeafStatus::Fresh,
+			span: Arc::new(jaeger::Span::Disabled),
+		})
+	))).await;
+
+	// The leaves request is only handled after the import has concluded.
+	query_leaves(virtual_overseer, chain).await
+}
+
+async fn finalize_block(
+	virtual_overseer: &mut VirtualOverseer,
+	chain: &mut TestChain,
+	hash: Hash,
+) {
+	let number = chain.headers.get(&hash).unwrap().number;
+	chain.finalized = (number, hash);
+
+	virtual_overseer.send(FromOverseer::Signal(
+		OverseerSignal::BlockFinalized(hash, number),
+	)).await;
+}
+
+async fn approve_block(
+	virtual_overseer: &mut VirtualOverseer,
+	hash: Hash,
+) {
+	virtual_overseer.send(FromOverseer::Communication {
+		msg: ChainSelectionMessage::Approved(hash),
+	}).await;
+}
+
+#[test]
+fn no_leaves_returns_finalized_block() {
+	test_harness(|_backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let chain = TestChain::new(0, finalized_hash);
+
+		assert_eq!(query_leaves(&mut virtual_overseer, &chain).await, vec![finalized_hash]);
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn import_chain_with_ancestors_unknown() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+		let blocks = chain.add_chain(finalized_hash, 0, 0, 5, 1);
+
+		// Importing only the head also imports all ancestors back to the finalized block.
+		let leaves = import_head(&mut virtual_overseer, &chain, *blocks.last().unwrap()).await;
+		assert_eq!(leaves, vec![*blocks.last().unwrap()]);
+
+		for (i, hash) in blocks.iter().enumerate() {
+			let entry = backend.block_entry(hash).unwrap();
+			assert_eq!(entry.block_number, i as BlockNumber + 1);
+			assert_eq!(entry.weight, i as BlockWeight + 1);
+			assert!(entry.viability.is_viable());
+		}
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn leaves_ordered_by_weight_then_number() {
+	test_harness(|_backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+
+		// A: 3 blocks with weights 1..3.
+		// B: 2 blocks with weights 10..11.
+		// C: 4 blocks with weights 1..4, on top of the first block of A.
+		let a = chain.add_chain(finalized_hash, 0, 0, 3, 1);
+		let b = chain.add_chain(finalized_hash, 0, 9, 2, 2);
+		let c = chain.add_chain(a[0], 1, 1, 3, 3);
+
+		import_head(&mut virtual_overseer, &chain, *a.last().unwrap()).await;
+		import_head(&mut virtual_overseer, &chain, *b.last().unwrap()).await;
+		let leaves = import_head(&mut virtual_overseer, &chain, *c.last().unwrap()).await;
+
+		assert_eq!(leaves, vec![*b.last().unwrap(), *c.last().unwrap(), *a.last().unwrap()]);
+
+		assert_eq!(
+			query_best_leaf_containing(&mut virtual_overseer, &chain, a[0]).await,
+			Some(*c.last().unwrap()),
+		);
+		assert_eq!(
+			query_best_leaf_containing(&mut virtual_overseer, &chain, a[1]).await,
+			Some(*a.last().unwrap()),
+		);
+		assert_eq!(
+			query_best_leaf_containing(&mut virtual_overseer, &chain, finalized_hash).await,
+			Some(*b.last().unwrap()),
+		);
+		assert_eq!(
+			query_best_leaf_containing(&mut virtual_overseer, &chain, Hash::repeat_byte(0xff)).await,
+			None,
+		);
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn reversion_digest_makes_ancestor_and_descendants_unviable() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+
+		// A: 4 blocks, heaviest.
+		// B: 2 blocks, lighter.
+		let a = chain.add_chain(finalized_hash, 0, 10, 4, 1);
+		let b = chain.add_chain(finalized_hash, 0, 0, 2, 2);
+
+		import_head(&mut virtual_overseer, &chain, *a.last().unwrap()).await;
+		let leaves = import_head(&mut virtual_overseer, &chain, *b.last().unwrap()).await;
+		assert_eq!(leaves, vec![*a.last().unwrap(), *b.last().unwrap()]);
+
+		// A5 reverts A2.
+		let a5 = chain.add_child(*a.last().unwrap(), 4, 1, 15, &[2]);
+		let leaves = import_head(&mut virtual_overseer, &chain, a5).await;
+
+		// A1 is now the only viable block in chain A.
+		assert_eq!(leaves, vec![a[0], *b.last().unwrap()]);
+
+		assert!(backend.block_entry(&a[0]).unwrap().viability.is_viable());
+		assert!(backend.block_entry(&a[1]).unwrap().viability.explicitly_reverted);
+		for hash in a[2..].iter().chain(Some(&a5)) {
+			assert_eq!(
+				backend.block_entry(hash).unwrap().viability.earliest_unviable_ancestor,
+				Some(a[1]),
+			);
+		}
+
+		// Blocks built on top of reverted blocks are never viable.
+		let a6 = chain.add_child(a5, 5, 1, 16, &[]);
+		let leaves = import_head(&mut virtual_overseer, &chain, a6).await;
+		assert_eq!(leaves, vec![a[0], *b.last().unwrap()]);
+		assert_eq!(
+			backend.block_entry(&a6).unwrap().viability.earliest_unviable_ancestor,
+			Some(a[1]),
+		);
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn reversion_of_finalized_block_is_ignored() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(5, finalized_hash);
+
+		let a1 = chain.add_child(finalized_hash, 5, 1, 1, &[]);
+		let a2 = chain.add_child(a1, 6, 1, 2, &[5]);
+
+		let leaves = import_head(&mut virtual_overseer, &chain, a2).await;
+		assert_eq!(leaves, vec![a2]);
+		assert!(backend.block_entry(&a2).unwrap().viability.is_viable());
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn finalization_prunes_dead_forks() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+
+		// A: 4 blocks.
+		// B: 3 blocks forking off A1.
+		// C: 2 blocks.
+		let a = chain.add_chain(finalized_hash, 0, 0, 4, 1);
+		let b = chain.add_chain(a[0], 1, 1, 3, 2);
+		let c = chain.add_chain(finalized_hash, 0, 0, 2, 3);
+
+		import_head(&mut virtual_overseer, &chain, *a.last().unwrap()).await;
+		import_head(&mut virtual_overseer, &chain, *b.last().unwrap()).await;
+		import_head(&mut virtual_overseer, &chain, *c.last().unwrap()).await;
+
+		finalize_block(&mut virtual_overseer, &mut chain, a[1]).await;
+
+		let leaves = query_leaves(&mut virtual_overseer, &chain).await;
+		assert_eq!(leaves, vec![*a.last().unwrap()]);
+
+		assert!(!backend.has_blocks_at(1));
+		assert!(!backend.has_blocks_at(2));
+		for hash in a[..2].iter().chain(&b).chain(&c) {
+			assert!(backend.block_entry(hash).is_none());
+		}
+		for hash in &a[2..] {
+			assert!(backend.block_entry(hash).is_some());
+		}
+
+		// Finalizing the leaf leaves no unfinalized blocks.
+		finalize_block(&mut virtual_overseer, &mut chain, *a.last().unwrap()).await;
+		let leaves = query_leaves(&mut virtual_overseer, &chain).await;
+		assert_eq!(leaves, vec![*a.last().unwrap()]);
+		assert!(backend.leaves().is_empty());
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn finalizing_reverted_block_makes_descendants_viable() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+
+		let a1 = chain.add_child(finalized_hash, 0, 1, 1, &[]);
+		let a2 = chain.add_child(a1, 1, 1, 2, &[]);
+		let a3 = chain.add_child(a2, 2, 1, 3, &[1]);
+
+		let leaves = import_head(&mut virtual_overseer, &chain, a3).await;
+		assert_eq!(leaves, vec![finalized_hash]);
+
+		// Finality overrides reversions.
+		finalize_block(&mut virtual_overseer, &mut chain, a1).await;
+		let leaves = query_leaves(&mut virtual_overseer, &chain).await;
+		assert_eq!(leaves, vec![a3]);
+		assert!(backend.block_entry(&a3).unwrap().viability.is_viable());
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn approval_message_marks_block_approved() {
+	test_harness(|backend, mut virtual_overseer| async move {
+		let finalized_hash = Hash::repeat_byte(0);
+		let mut chain = TestChain::new(0, finalized_hash);
+		let a = chain.add_chain(finalized_hash, 0, 0, 2, 1);
+
+		import_head(&mut virtual_overseer, &chain, a[1]).await;
+		assert_matches!(backend.block_entry(&a[0]).unwrap().viability.approval, Approval::Unapproved);
+
+		approve_block(&mut virtual_overseer, a[0]).await;
+
+		// Messages are handled in order, so the approval is applied once leaves are returned.
+		let leaves = query_leaves(&mut virtual_overseer, &chain).await;
+		assert_eq!(leaves, vec![a[1]]);
+		assert_matches!(backend.block_entry(&a[0]).unwrap().viability.approval, Approval::Approved);
+		assert_matches!(backend.block_entry(&a[1]).unwrap().viability.approval, Approval::Unapproved);
+
+		virtual_overseer
+	})
+}
+
+// Stagnation is tested directly against the tree, as the subsystem only
+// checks for stagnant blocks on a timer.
+#[test]
+fn stagnant_blocks_unviable_until_approved() {
+	let mut backend = TestBackend::default();
+
+	let finalized_hash = Hash::repeat_byte(0);
+	let a1 = Hash::repeat_byte(1);
+	let a2 = Hash::repeat_byte(2);
+	let b1 = Hash::repeat_byte(3);
+
+	let ops = {
+		let mut overlay = OverlayedBackend::new(&backend);
+		crate::tree::import_block(&mut overlay, a1, 1, finalized_hash, Vec::new(), 5, 10).unwrap();
+		crate::tree::import_block(&mut overlay, a2, 2, a1, Vec::new(), 6, 20).unwrap();
+		crate::tree::import_block(&mut overlay, b1, 1, finalized_hash, Vec::new(), 1, 20).unwrap();
+		overlay.into_write_ops()
+	};
+	backend.write(ops).unwrap();
+	assert_eq!(backend.leaves(), vec![a2, b1]);
+
+	// A1 is approved in time, A2 is not.
+	let ops = {
+		let mut overlay = OverlayedBackend::new(&backend);
+		crate::tree::approve_block(&mut overlay, a1).unwrap();
+		overlay.into_write_ops()
+	};
+	backend.write(ops).unwrap();
+
+	let ops = crate::tree::detect_stagnant(&backend, 15).unwrap().into_write_ops();
+	backend.write(ops).unwrap();
+	assert_eq!(backend.leaves(), vec![a2, b1]);
+
+	let ops = crate::tree::detect_stagnant(&backend, 20).unwrap().into_write_ops();
+	backend.write(ops).unwrap();
+
+	assert_matches!(backend.block_entry(&a2).unwrap().viability.approval, Approval::Stagnant);
+	assert_matches!(backend.block_entry(&b1).unwrap().viability.approval, Approval::Stagnant);
+	assert_eq!(backend.leaves(), vec![a1]);
+	assert!(backend.inner.lock().stagnant_at.is_empty());
+
+	// A late approval makes the block viable again.
+	let ops = {
+		let mut overlay = OverlayedBackend::new(&backend);
+		crate::tree::approve_block(&mut overlay, a2).unwrap();
+		overlay.into_write_ops()
+	};
+	backend.write(ops).unwrap();
+	assert_eq!(backend.leaves(), vec![a2]);
+}
+
+#[test]
+fn extract_reversion_logs_ignores_future_and_sorts() {
+	let mut header = Header {
+		parent_hash: Hash::zero(),
+		number: 10,
+		state_root: Hash::zero(),
+		extrinsics_root: Hash::zero(),
+		digest: Default::default(),
+	};
+
+	header.digest.push(ConsensusLog::Revert(8).into());
+	header.digest.push(ConsensusLog::Revert(10).into());
+	header.digest.push(ConsensusLog::Revert(3).into());
+	header.digest.push(ConsensusLog::ForceApprove(4).into());
+
+	assert_eq!(extract_reversion_logs(&header), vec![3, 8]);
+}
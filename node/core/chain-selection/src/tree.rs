@@ -0,0 +1,499 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implements the tree-view over the data backend which we use to determine
+//! viable leaves.
+//!
+//! The metadata is structured as a tree, with the root implicitly being the
+//! finalized block, which is not stored as part of the tree.
+//!
+//! Each direct descendant of the finalized block acts as its own sub-tree,
+//! and as the finalized block advances, orphaned sub-trees are entirely pruned.
+
+use polkadot_primitives::v1::{BlockNumber, Hash};
+use polkadot_node_primitives::BlockWeight;
+
+use super::{
+	LOG_TARGET,
+	Approval, BlockEntry, Error, LeafEntry, LeafEntrySet, ViabilityCriteria, Timestamp,
+};
+use crate::backend::{Backend, OverlayedBackend};
+
+// A viability update to be applied to a block.
+struct ViabilityUpdate(Option<Hash>);
+
+impl ViabilityUpdate {
+	// Apply the viability update to a single block, yielding the updated
+	// block entry along with a vector of children and the updates to apply
+	// to them.
+	fn apply(self, mut entry: BlockEntry) -> (
+		BlockEntry,
+		Vec<(Hash, ViabilityUpdate)>
+	) {
+		// 1. When an ancestor has changed from unviable to viable,
+		// we erase the `earliest_unviable_ancestor` of all descendants
+		// until encountering an explicitly unviable descendant D.
+		//
+		// We then update the `earliest_unviable_ancestor` for all
+		// descendants of D to be equal to D.
+		//
+		// 2. When an ancestor A has changed from viable to unviable,
+		// we update the `earliest_unviable_ancestor` for all blocks
+		// to A.
+		//
+		// The following algorithm covers both cases.
+		//
+		// Furthermore, if there has been any change in viability,
+		// it is necessary to visit every single descendant of the root
+		// block.
+		let maybe_earliest_unviable = self.0;
+		let next_earliest_unviable = {
+			if maybe_earliest_unviable.is_none() && !entry.viability.is_explicitly_viable() {
+				Some(entry.block_hash)
+			} else {
+				maybe_earliest_unviable
+			}
+		};
+		entry.viability.earliest_unviable_ancestor = maybe_earliest_unviable;
+
+		let recurse = entry.children.iter()
+			.cloned()
+			.map(move |c| (c, ViabilityUpdate(next_earliest_unviable)))
+			.collect();
+
+		(entry, recurse)
+	}
+}
+
+// Propagate viability update to descendants of the given block. This writes
+// the `base` entry as well as all descendants. If the parent of the block
+// entry is not viable, this will not affect any descendants.
+//
+// The set of viable leaves is recomputed for every block visited, as well
+// as for the parent of the base block.
+fn propagate_viability_update(
+	backend: &mut OverlayedBackend<impl Backend>,
+	base: BlockEntry,
+) -> Result<(), Error> {
+	enum BlockEntryRef {
+		Explicit(BlockEntry),
+		Hash(Hash),
+	}
+
+	if !base.viability.is_parent_viable() {
+		// If the parent of the block is still unviable,
+		// then the `earliest_viable_ancestor` will not change
+		// regardless of the change in the block here.
+		//
+		// Furthermore, in such cases, the set of viable leaves
+		// does not change at all.
+		backend.write_block_entry(base);
+		return Ok(())
+	}
+
+	let base_parent = base.parent_hash;
+	let mut touched = vec![base_parent];
+
+	let mut tree_frontier = vec![(BlockEntryRef::Explicit(base), ViabilityUpdate(None))];
+	while let Some((entry_ref, update)) = tree_frontier.pop() {
+		let entry = match entry_ref {
+			BlockEntryRef::Explicit(entry) => entry,
+			BlockEntryRef::Hash(hash) => match backend.load_block_entry(&hash)? {
+				None => {
+					tracing::warn!(
+						target: LOG_TARGET,
+						block_hash = ?hash,
+						"Missing expected block entry"
+					);
+
+					continue;
+				}
+				Some(entry) => entry,
+			}
+		};
+
+		let (new_entry, children) = update.apply(entry);
+
+		touched.push(new_entry.block_hash);
+		backend.write_block_entry(new_entry);
+
+		tree_frontier.extend(
+			children.into_iter().map(|(h, update)| (BlockEntryRef::Hash(h), update))
+		);
+	}
+
+	// Now that all viability information in the sub-tree is up-to-date,
+	// recompute the leaf status of every visited block.
+	let mut viable_leaves = backend.load_leaves()?;
+	for hash in touched {
+		update_leaf_status(backend, &mut viable_leaves, hash)?;
+	}
+	backend.write_leaves(viable_leaves);
+
+	Ok(())
+}
+
+// A block is a viable leaf if it is viable itself and none of its children are.
+//
+// Blocks which are not in the tree, such as the finalized block, are ignored.
+fn update_leaf_status(
+	backend: &OverlayedBackend<impl Backend>,
+	viable_leaves: &mut LeafEntrySet,
+	hash: Hash,
+) -> Result<(), Error> {
+	let entry = match backend.load_block_entry(&hash)? {
+		None => return Ok(()),
+		Some(entry) => entry,
+	};
+
+	let mut is_leaf = entry.viability.is_viable();
+	if is_leaf {
+		for child in &entry.children {
+			if backend.load_block_entry(child)?.map_or(false, |c| c.viability.is_viable()) {
+				is_leaf = false;
+				break
+			}
+		}
+	}
+
+	if is_leaf {
+		viable_leaves.insert(entry.leaf_entry());
+	} else {
+		viable_leaves.remove(&hash);
+	}
+
+	Ok(())
+}
+
+/// Imports a new block and applies any reversions to ancestors.
+pub(crate) fn import_block(
+	backend: &mut OverlayedBackend<impl Backend>,
+	block_hash: Hash,
+	block_number: BlockNumber,
+	parent_hash: Hash,
+	reversion_logs: Vec<BlockNumber>,
+	weight: BlockWeight,
+	stagnant_at: Timestamp,
+) -> Result<(), Error> {
+	add_block(backend, block_hash, block_number, parent_hash, weight, stagnant_at)?;
+	apply_reversions(
+		backend,
+		block_hash,
+		block_number,
+		reversion_logs,
+	)?;
+
+	Ok(())
+}
+
+// Load the entry of the ancestor of the given block with the given number.
+//
+// Returns `None` if the ancestor is finalized or otherwise unknown.
+fn load_ancestor(
+	backend: &OverlayedBackend<impl Backend>,
+	block_hash: Hash,
+	ancestor_number: BlockNumber,
+) -> Result<Option<BlockEntry>, Error> {
+	let mut current = backend.load_block_entry(&block_hash)?;
+	while let Some(entry) = current {
+		if entry.block_number == ancestor_number {
+			return Ok(Some(entry))
+		}
+
+		if entry.block_number < ancestor_number {
+			break
+		}
+
+		current = backend.load_block_entry(&entry.parent_hash)?;
+	}
+
+	Ok(None)
+}
+
+// Add a new block to the tree, which is assumed to be unreverted and unapproved,
+// but not stagnant. It inherits viability from its parent, if any.
+//
+// This updates the parent entry, if any, and updates the viable leaves set accordingly.
+// This also schedules a stagnation-check update and adds the block to the blocks-by-number
+// mapping.
+fn add_block(
+	backend: &mut OverlayedBackend<impl Backend>,
+	block_hash: Hash,
+	block_number: BlockNumber,
+	parent_hash: Hash,
+	weight: BlockWeight,
+	stagnant_at: Timestamp,
+) -> Result<(), Error> {
+	let mut leaves = backend.load_leaves()?;
+	let parent_entry = backend.load_block_entry(&parent_hash)?;
+
+	let inherited_viability = parent_entry.as_ref()
+		.and_then(|parent| parent.non_viable_ancestor_for_child());
+
+	// 1. Add the block to the DB assuming it's not reverted.
+	backend.write_block_entry(
+		BlockEntry {
+			block_hash,
+			block_number,
+			parent_hash,
+			children: Vec::new(),
+			viability: ViabilityCriteria {
+				earliest_unviable_ancestor: inherited_viability,
+				explicitly_reverted: false,
+				approval: Approval::Unapproved,
+			},
+			weight,
+		}
+	);
+
+	// 2. Update leaves if inherited viability is fine.
+	if inherited_viability.is_none() {
+		leaves.remove(&parent_hash);
+		leaves.insert(LeafEntry { block_hash, block_number, weight });
+		backend.write_leaves(leaves);
+	}
+
+	// 3. Add to parent's descendants.
+	if let Some(mut parent_entry) = parent_entry {
+		parent_entry.children.push(block_hash);
+		backend.write_block_entry(parent_entry);
+	}
+
+	// 4. Add to blocks-by-number.
+	let mut blocks_by_number = backend.load_blocks_by_number(block_number)?;
+	blocks_by_number.push(block_hash);
+	backend.write_blocks_by_number(block_number, blocks_by_number);
+
+	// 5. Add stagnation timeout.
+	let mut stagnant_at_list = backend.load_stagnant_at(stagnant_at)?;
+	stagnant_at_list.push(block_hash);
+	backend.write_stagnant_at(stagnant_at, stagnant_at_list);
+
+	Ok(())
+}
+
+// Assuming that a block is already imported, accepts the number of the block
+// as well as a list of reversions triggered by the block in ascending order.
+fn apply_reversions(
+	backend: &mut OverlayedBackend<impl Backend>,
+	block_hash: Hash,
+	block_number: BlockNumber,
+	reversions: Vec<BlockNumber>,
+) -> Result<(), Error> {
+	// Note: since revert numbers are in ascending order, the expensive propagation
+	// of unviability is only heavy on the first log.
+	for revert_number in reversions {
+		let mut ancestor_entry = match load_ancestor(&*backend, block_hash, revert_number)? {
+			None => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?block_hash,
+					block_number,
+					revert_target = revert_number,
+					"Block has signaled that a finalized ancestor be reverted. Finalized blocks cannot be reverted.",
+				);
+
+				continue
+			}
+			Some(ancestor_entry) => {
+				tracing::info!(
+					target: LOG_TARGET,
+					?block_hash,
+					block_number,
+					revert_target = revert_number,
+					revert_hash = ?ancestor_entry.block_hash,
+					"A block has signaled that its ancestor be reverted due to a bad parachain block.",
+				);
+
+				ancestor_entry
+			}
+		};
+
+		ancestor_entry.viability.explicitly_reverted = true;
+		propagate_viability_update(backend, ancestor_entry)?;
+	}
+
+	Ok(())
+}
+
+/// Finalize a block with the given number and hash.
+///
+/// This will prune all sub-trees not descending from the given block,
+/// all block entries at or before the given height,
+/// and will update the viability of all sub-trees descending from the given
+/// block if the finalized block was not viable.
+///
+/// This is assumed to start with a fresh backend, and will produce
+/// an overlay over the backend with all the changes applied.
+pub(super) fn finalize_block<'a, B: Backend + 'a>(
+	backend: &'a B,
+	finalized_hash: Hash,
+	finalized_number: BlockNumber,
+) -> Result<OverlayedBackend<'a, B>, Error> {
+	let earliest_stored_number = match backend.load_first_block_number()? {
+		None => {
+			// This implies that there are no unfinalized blocks and hence nothing
+			// to update.
+			return Ok(OverlayedBackend::new(backend));
+		}
+		Some(e) => e,
+	};
+
+	let mut backend = OverlayedBackend::new(backend);
+	let mut viable_leaves = backend.load_leaves()?;
+
+	// Walk all numbers up to the finalized number and remove those entries.
+	for number in earliest_stored_number..finalized_number {
+		let blocks_at = backend.load_blocks_by_number(number)?;
+		backend.delete_blocks_by_number(number);
+
+		for block in blocks_at {
+			viable_leaves.remove(&block);
+			backend.delete_block_entry(&block);
+		}
+	}
+
+	// Remove all blocks at the finalized height, with the exception of the finalized block,
+	// and their descendants, recursively.
+	{
+		let blocks_at_finalized_height = backend.load_blocks_by_number(finalized_number)?;
+		backend.delete_blocks_by_number(finalized_number);
+
+		let mut frontier: Vec<_> = blocks_at_finalized_height
+			.into_iter()
+			.filter(|h| h != &finalized_hash)
+			.map(|h| (h, finalized_number))
+			.collect();
+
+		while let Some((dead_hash, dead_number)) = frontier.pop() {
+			let entry = backend.load_block_entry(&dead_hash)?;
+			backend.delete_block_entry(&dead_hash);
+			viable_leaves.remove(&dead_hash);
+
+			// This does a few extra `clone`s but is unlikely to be
+			// a bottleneck. Code complexity is very low as a result.
+			let mut blocks_at_height = backend.load_blocks_by_number(dead_number)?;
+			blocks_at_height.retain(|h| h != &dead_hash);
+			backend.write_blocks_by_number(dead_number, blocks_at_height);
+
+			// Add all children to the frontier.
+			let next_height = dead_number + 1;
+			frontier.extend(
+				entry.into_iter().flat_map(|e| e.children).map(|h| (h, next_height))
+			);
+		}
+	}
+
+	// Visit and remove the finalized block, fetching its children.
+	let children_of_finalized: Vec<_> = {
+		let finalized_entry = backend.load_block_entry(&finalized_hash)?;
+		backend.delete_block_entry(&finalized_hash);
+		viable_leaves.remove(&finalized_hash);
+
+		finalized_entry.into_iter().flat_map(|e| e.children).collect()
+	};
+
+	backend.write_leaves(viable_leaves);
+
+	// Update the viability of each child.
+	for child in children_of_finalized {
+		if let Some(mut child) = backend.load_block_entry(&child)? {
+			// Finalized blocks are always viable.
+			child.viability.earliest_unviable_ancestor = None;
+
+			propagate_viability_update(&mut backend, child)?;
+		} else {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?finalized_hash,
+				finalized_number,
+				child_hash = ?child,
+				"Missing child of finalized block",
+			);
+
+			// No need to do anything, but this is an inconsistent state.
+		}
+	}
+
+	Ok(backend)
+}
+
+/// Mark a block as approved and update the viability of itself and its
+/// descendants accordingly.
+pub(super) fn approve_block(
+	backend: &mut OverlayedBackend<impl Backend>,
+	approved_hash: Hash,
+) -> Result<(), Error> {
+	if let Some(mut entry) = backend.load_block_entry(&approved_hash)? {
+		let was_viable = entry.viability.is_viable();
+		entry.viability.approval = Approval::Approved;
+		let is_viable = entry.viability.is_viable();
+
+		// Approval can change the viability in only one direction.
+		// If the viability has changed, then we propagate that to children
+		// and recalculate the viable leaf set.
+		if !was_viable && is_viable {
+			propagate_viability_update(backend, entry)?;
+		} else {
+			backend.write_block_entry(entry);
+		}
+	} else {
+		tracing::debug!(
+			target: LOG_TARGET,
+			block_hash = ?approved_hash,
+			"Missing entry for freshly-approved block. Ignoring"
+		);
+	}
+
+	Ok(())
+}
+
+/// Check whether any blocks up to the given timestamp are stagnant and update
+/// accordingly.
+///
+/// This accepts a fresh backend and returns an overlay on top of it representing
+/// all changes made.
+pub(super) fn detect_stagnant<'a, B: 'a + Backend>(
+	backend: &'a B,
+	up_to: Timestamp,
+) -> Result<OverlayedBackend<'a, B>, Error> {
+	let stagnant_up_to = backend.load_stagnant_at_up_to(up_to)?;
+	let mut backend = OverlayedBackend::new(backend);
+
+	// As this is in ascending order, only the earliest stagnant
+	// blocks will involve heavy viability propagations.
+	for (timestamp, maybe_stagnant) in stagnant_up_to {
+		backend.delete_stagnant_at(timestamp);
+
+		for block_hash in maybe_stagnant {
+			if let Some(mut entry) = backend.load_block_entry(&block_hash)? {
+				let was_viable = entry.viability.is_viable();
+				if let Approval::Unapproved = entry.viability.approval {
+					entry.viability.approval = Approval::Stagnant;
+				}
+				let is_viable = entry.viability.is_viable();
+
+				if was_viable && !is_viable {
+					propagate_viability_update(&mut backend, entry)?;
+				} else {
+					backend.write_block_entry(entry);
+				}
+			}
+		}
+	}
+
+	Ok(backend)
+}
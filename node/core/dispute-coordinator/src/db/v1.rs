@@ -0,0 +1,363 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! V1 database for the dispute coordinator.
+
+use polkadot_primitives::v1::{
+	CandidateReceipt, ValidDisputeStatementKind, InvalidDisputeStatementKind, ValidatorIndex,
+	ValidatorSignature, SessionIndex, CandidateHash,
+};
+use polkadot_node_primitives::{DisputeStatus, DISPUTE_WINDOW};
+
+use kvdb::{DBTransaction, KeyValueDB};
+use parity_scale_codec::{Encode, Decode};
+
+
+const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
+const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
+const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
+
+fn candidate_votes_key(session: SessionIndex, candidate_hash: &CandidateHash) -> [u8; 15 + 4 + 32] {
+	let mut buf = [0u8; 15 + 4 + 32];
+	buf[..4].copy_from_slice(&session.to_be_bytes());
+	buf[4..4 + 15].copy_from_slice(CANDIDATE_VOTES_SUBKEY);
+	candidate_hash.using_encoded(|s| buf[(4 + 15)..].copy_from_slice(s));
+
+	buf
+}
+
+// The prefix of all DB keys for candidate votes within a given session.
+fn candidate_votes_session_prefix(session: SessionIndex) -> [u8; 4 + 15] {
+	let mut buf = [0; 4 + 15];
+	buf[..4].copy_from_slice(&session.to_be_bytes());
+	buf[4..].copy_from_slice(CANDIDATE_VOTES_SUBKEY);
+	buf
+}
+
+fn decode_candidate_votes_key(key: &[u8]) -> Option<(SessionIndex, CandidateHash)> {
+	if key.len() != 15 + 4 + 32 {
+		return None;
+	}
+
+	let mut session_buf = [0; 4];
+	session_buf.copy_from_slice(&key[..4]);
+	let session = SessionIndex::from_be_bytes(session_buf);
+
+	if &key[4..4 + 15] != CANDIDATE_VOTES_SUBKEY {
+		return None;
+	}
+
+	CandidateHash::decode(&mut &key[(4 + 15)..]).ok().map(|hash| (session, hash))
+}
+
+/// Column configuration information for the DB.
+#[derive(Debug, Clone)]
+pub struct ColumnConfiguration {
+	/// The column in the key-value DB where data is stored.
+	pub col_data: u32,
+}
+
+/// Tracked votes on candidates, for the purposes of dispute resolution.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CandidateVotes {
+	/// The receipt of the candidate itself.
+	pub candidate_receipt: CandidateReceipt,
+	/// Votes of validity, sorted by validator index.
+	pub valid: Vec<(ValidDisputeStatementKind, ValidatorIndex, ValidatorSignature)>,
+	/// Votes of invalidity, sorted by validator index.
+	pub invalid: Vec<(InvalidDisputeStatementKind, ValidatorIndex, ValidatorSignature)>,
+}
+
+impl From<CandidateVotes> for polkadot_node_primitives::CandidateVotes {
+	fn from(db_votes: CandidateVotes) -> polkadot_node_primitives::CandidateVotes {
+		polkadot_node_primitives::CandidateVotes {
+			candidate_receipt: db_votes.candidate_receipt,
+			valid: db_votes.valid,
+			invalid: db_votes.invalid,
+		}
+	}
+}
+
+impl From<polkadot_node_primitives::CandidateVotes> for CandidateVotes {
+	fn from(primitive_votes: polkadot_node_primitives::CandidateVotes) -> CandidateVotes {
+		CandidateVotes {
+			candidate_receipt: primitive_votes.candidate_receipt,
+			valid: primitive_votes.valid,
+			invalid: primitive_votes.invalid,
+		}
+	}
+}
+
+/// The mapping for recent disputes; any which have not yet been pruned for being ancient.
+pub type RecentDisputes = std::collections::BTreeMap<(SessionIndex, CandidateHash), DisputeStatus>;
+
+/// Errors while accessing things from the DB.
+#[derive(Debug, derive_more::From, derive_more::Display)]
+pub enum Error {
+	Io(std::io::Error),
+	InvalidDecoding(parity_scale_codec::Error),
+}
+
+impl std::error::Error for Error {}
+
+/// Result alias for DB errors.
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn load_decode<D: Decode>(db: &dyn KeyValueDB, col_data: u32, key: &[u8])
+	-> Result<Option<D>>
+{
+	match db.get(col_data, key)? {
+		None => Ok(None),
+		Some(raw) => D::decode(&mut &raw[..])
+			.map(Some)
+			.map_err(Into::into),
+	}
+}
+
+/// Load the candidate votes for the specific session-candidate pair, if any.
+pub(crate) fn load_candidate_votes(
+	db: &dyn KeyValueDB,
+	config: &ColumnConfiguration,
+	session: SessionIndex,
+	candidate_hash: &CandidateHash,
+) -> Result<Option<CandidateVotes>> {
+	load_decode(db, config.col_data, &candidate_votes_key(session, candidate_hash))
+}
+
+/// Load the earliest session, if any.
+pub(crate) fn load_earliest_session(
+	db: &dyn KeyValueDB,
+	config: &ColumnConfiguration,
+) -> Result<Option<SessionIndex>> {
+	load_decode(db, config.col_data, EARLIEST_SESSION_KEY)
+}
+
+/// Load the recent disputes, if any.
+pub(crate) fn load_recent_disputes(
+	db: &dyn KeyValueDB,
+	config: &ColumnConfiguration,
+) -> Result<Option<RecentDisputes>> {
+	load_decode(db, config.col_data, RECENT_DISPUTES_KEY)
+}
+
+/// An atomic transaction to be committed to the underlying DB.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Transaction {
+	earliest_session: Option<SessionIndex>,
+	recent_disputes: Option<RecentDisputes>,
+	write_candidate_votes: Vec<(SessionIndex, CandidateHash, CandidateVotes)>,
+	delete_candidate_votes: Vec<(SessionIndex, CandidateHash)>,
+}
+
+impl Transaction {
+	/// Prepare a write to the 'earliest session' field of the DB.
+	///
+	/// Later calls to this function will override earlier ones.
+	pub(crate) fn put_earliest_session(&mut self, session: SessionIndex) {
+		self.earliest_session = Some(session);
+	}
+
+	/// Prepare a write to the recent disputes stored in the DB.
+	///
+	/// Later calls to this function will override earlier ones.
+	pub(crate) fn put_recent_disputes(&mut self, recent_disputes: RecentDisputes) {
+		self.recent_disputes = Some(recent_disputes);
+	}
+
+	/// Prepare a write of the candidate votes under the indicated candidate.
+	///
+	/// Later calls to this function for the same candidate will override earlier ones.
+	/// Any calls to this function will be overridden by deletions of the same candidate.
+	pub(crate) fn put_candidate_votes(
+		&mut self,
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		votes: CandidateVotes,
+	) {
+		self.write_candidate_votes.push((session, candidate_hash, votes))
+	}
+
+	/// Prepare a deletion of the candidate votes under the indicated candidate.
+	///
+	/// Any calls to this function will override writes to the same candidate.
+	pub(crate) fn delete_candidate_votes(
+		&mut self,
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+	) {
+		self.delete_candidate_votes.push((session, candidate_hash))
+	}
+
+	/// Write the transaction atomically to the DB.
+	pub(crate) fn write(self, db: &dyn KeyValueDB, config: &ColumnConfiguration) -> Result<()> {
+		let mut tx = DBTransaction::new();
+
+		if let Some(s) = self.earliest_session {
+			tx.put_vec(config.col_data, EARLIEST_SESSION_KEY, s.encode());
+		}
+
+		if let Some(a) = self.recent_disputes {
+			tx.put_vec(config.col_data, RECENT_DISPUTES_KEY, a.encode());
+		}
+
+		for (session, candidate_hash, votes) in self.write_candidate_votes {
+			tx.put_vec(config.col_data, &candidate_votes_key(session, &candidate_hash), votes.encode());
+		}
+
+		for (session, candidate_hash) in self.delete_candidate_votes {
+			tx.delete(config.col_data, &candidate_votes_key(session, &candidate_hash));
+		}
+
+		db.write(tx).map_err(Into::into)
+	}
+}
+
+/// Maybe prune data in the DB based on the provided session index.
+///
+/// This is intended to be called on every block, and as such will be used to populate the DB on
+/// first launch. If the on-disk data does not need to be pruned, only a single storage read
+/// will be performed.
+///
+/// If one or more ancient sessions are pruned, all metadata on candidates within the ancient
+/// session will be deleted.
+pub(crate) fn note_current_session(
+	store: &dyn KeyValueDB,
+	config: &ColumnConfiguration,
+	current_session: SessionIndex,
+) -> Result<()> {
+	let new_earliest = current_session.saturating_sub(DISPUTE_WINDOW);
+	let mut tx = Transaction::default();
+
+	match load_earliest_session(store, config)? {
+		None => {
+			// First launch - write new-earliest.
+			tx.put_earliest_session(new_earliest);
+		}
+		Some(prev_earliest) if new_earliest > prev_earliest => {
+			// Prune all data in the outdated sessions.
+			tx.put_earliest_session(new_earliest);
+
+			// Clear recent disputes metadata.
+			{
+				let mut recent_disputes = load_recent_disputes(store, config)?.unwrap_or_default();
+
+				let lower_bound = (
+					new_earliest,
+					CandidateHash(Default::default()),
+				);
+
+				let prev_len = recent_disputes.len();
+				recent_disputes = recent_disputes.split_off(&lower_bound);
+
+				if recent_disputes.len() != prev_len {
+					tx.put_recent_disputes(recent_disputes);
+				}
+			}
+
+			// Clear all candidate data with session less than the new earliest kept.
+			for session in prev_earliest..new_earliest {
+				let prefix = candidate_votes_session_prefix(session);
+
+				store.iter_with_prefix(config.col_data, &prefix[..])
+					.filter_map(|(k, _)| decode_candidate_votes_key(&k[..]))
+					.for_each(|(session, candidate_hash)| {
+						tx.delete_candidate_votes(session, candidate_hash);
+					});
+			}
+		}
+		Some(_) => {
+			// nothing to do.
+		}
+	};
+
+	tx.write(store, config)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn make_db() -> (std::sync::Arc<dyn KeyValueDB>, ColumnConfiguration) {
+		(std::sync::Arc::new(kvdb_memorydb::create(1)), ColumnConfiguration { col_data: 0 })
+	}
+
+	fn dummy_votes() -> CandidateVotes {
+		CandidateVotes {
+			candidate_receipt: Default::default(),
+			valid: Vec::new(),
+			invalid: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn candidate_votes_key_roundtrip() {
+		let hash = CandidateHash(polkadot_primitives::v1::Hash::repeat_byte(1));
+		let key = candidate_votes_key(5, &hash);
+
+		assert!(key.starts_with(&candidate_votes_session_prefix(5)));
+		assert_eq!(decode_candidate_votes_key(&key[..]), Some((5, hash)));
+	}
+
+	#[test]
+	fn note_current_session_prunes_old() {
+		let (store, config) = make_db();
+
+		let hash_a = CandidateHash(polkadot_primitives::v1::Hash::repeat_byte(1));
+		let hash_b = CandidateHash(polkadot_primitives::v1::Hash::repeat_byte(2));
+
+		let prev_earliest_session = 0;
+		let new_earliest_session = 5;
+		let current_session = 5 + DISPUTE_WINDOW;
+
+		let very_old = 3;
+		let slightly_old = 4;
+		let very_recent = current_session - 1;
+
+		let mut tx = Transaction::default();
+		tx.put_earliest_session(prev_earliest_session);
+		tx.put_recent_disputes(vec![
+			((very_old, hash_a), DisputeStatus::Active),
+			((slightly_old, hash_a), DisputeStatus::Active),
+			((new_earliest_session, hash_a), DisputeStatus::Active),
+			((very_recent, hash_a), DisputeStatus::Active),
+		].into_iter().collect());
+		tx.put_candidate_votes(very_old, hash_a, dummy_votes());
+		tx.put_candidate_votes(slightly_old, hash_b, dummy_votes());
+		tx.put_candidate_votes(new_earliest_session, hash_a, dummy_votes());
+		tx.put_candidate_votes(very_recent, hash_b, dummy_votes());
+		tx.write(&*store, &config).unwrap();
+
+		note_current_session(&*store, &config, current_session).unwrap();
+
+		assert_eq!(
+			load_earliest_session(&*store, &config).unwrap(),
+			Some(new_earliest_session),
+		);
+
+		assert_eq!(
+			load_recent_disputes(&*store, &config).unwrap().unwrap(),
+			vec![
+				((new_earliest_session, hash_a), DisputeStatus::Active),
+				((very_recent, hash_a), DisputeStatus::Active),
+			].into_iter().collect(),
+		);
+
+		assert!(load_candidate_votes(&*store, &config, very_old, &hash_a).unwrap().is_none());
+		assert!(load_candidate_votes(&*store, &config, slightly_old, &hash_b).unwrap().is_none());
+		assert!(load_candidate_votes(&*store, &config, new_earliest_session, &hash_a).unwrap().is_some());
+		assert!(load_candidate_votes(&*store, &config, very_recent, &hash_b).unwrap().is_some());
+	}
+}
@@ -0,0 +1,787 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implements the dispute coordinator subsystem.
+//!
+//! This is the central subsystem of the node-side components which participate in disputes.
+//! This subsystem wraps a database which tracks all statements observed by all validators over
+//! some window of sessions. Votes older than this session window are pruned.
+//!
+//! This subsystem will be the point which produce dispute votes, either positive or negative,
+//! based on locally-observed validation results as well as a sink for votes received by other
+//! subsystems. When importing a dispute vote from another node, this will trigger participation
+//! in the dispute: recovering and validating the candidate and casting a local vote.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use polkadot_node_primitives::{
+	CandidateVotes, DisputeMessage, DisputeStatus, SignedDisputeStatement, Timestamp,
+	DISPUTE_WINDOW,
+};
+use polkadot_node_subsystem::{
+	messages::{
+		DisputeCoordinatorMessage, DisputeDistributionMessage, ImportStatementsResult, RuntimeApiMessage,
+		RuntimeApiRequest,
+	},
+	Subsystem, SubsystemContext, SubsystemError, SpawnedSubsystem, FromOverseer, OverseerSignal,
+	ActiveLeavesUpdate, errors::{ChainApiError, RuntimeApiError},
+};
+use polkadot_primitives::v1::{
+	SessionIndex, CandidateHash, Hash, CandidateReceipt, DisputeStatement, ValidatorIndex,
+	ValidatorId, ValidatorPair, ValidatorSignature, SessionInfo, BlockNumber,
+	ValidDisputeStatementKind, InvalidDisputeStatementKind,
+};
+
+use futures::prelude::*;
+use futures::channel::oneshot;
+use kvdb::KeyValueDB;
+use parity_scale_codec::Error as CodecError;
+use sc_keystore::LocalKeystore;
+use sp_keystore::SyncCryptoStorePtr;
+
+mod db;
+mod participation;
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "parachain::dispute-coordinator";
+
+struct State {
+	keystore: Arc<LocalKeystore>,
+	highest_session: Option<SessionIndex>,
+	// Session information for all sessions within the dispute window.
+	session_info: HashMap<SessionIndex, SessionInfo>,
+}
+
+/// Configuration for the dispute coordinator subsystem.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+	/// The data column in the store to use for dispute data.
+	pub col_data: u32,
+}
+
+impl Config {
+	fn column_config(&self) -> db::v1::ColumnConfiguration {
+		db::v1::ColumnConfiguration { col_data: self.col_data }
+	}
+}
+
+/// An implementation of the dispute coordinator subsystem.
+pub struct DisputeCoordinatorSubsystem {
+	config: Config,
+	store: Arc<dyn KeyValueDB>,
+	keystore: Arc<LocalKeystore>,
+}
+
+impl DisputeCoordinatorSubsystem {
+	/// Create a new instance of the subsystem.
+	pub fn new(
+		store: Arc<dyn KeyValueDB>,
+		config: Config,
+		keystore: Arc<LocalKeystore>,
+	) -> Self {
+		DisputeCoordinatorSubsystem { store, config, keystore }
+	}
+}
+
+impl<Context> Subsystem<Context> for DisputeCoordinatorSubsystem
+	where Context: SubsystemContext<Message = DisputeCoordinatorMessage>
+{
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = run(self, ctx)
+			.map(|_| Ok(()))
+			.boxed();
+
+		SpawnedSubsystem {
+			name: "dispute-coordinator-subsystem",
+			future,
+		}
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+	#[error(transparent)]
+	RuntimeApi(#[from] RuntimeApiError),
+
+	#[error(transparent)]
+	ChainApi(#[from] ChainApiError),
+
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+
+	#[error(transparent)]
+	Oneshot(#[from] oneshot::Canceled),
+
+	#[error(transparent)]
+	Subsystem(#[from] SubsystemError),
+
+	#[error(transparent)]
+	Codec(#[from] CodecError),
+}
+
+impl From<db::v1::Error> for Error {
+	fn from(err: db::v1::Error) -> Self {
+		match err {
+			db::v1::Error::Io(io) => Self::Io(io),
+			db::v1::Error::InvalidDecoding(e) => Self::Codec(e),
+		}
+	}
+}
+
+impl Error {
+	fn trace(&self) {
+		match self {
+			// don't spam the log with spurious errors
+			Self::RuntimeApi(_) |
+			Self::Oneshot(_) => tracing::debug!(target: LOG_TARGET, err = ?self),
+			// it's worth reporting otherwise
+			_ => tracing::warn!(target: LOG_TARGET, err = ?self),
+		}
+	}
+}
+
+async fn run<Context>(subsystem: DisputeCoordinatorSubsystem, mut ctx: Context)
+	where Context: SubsystemContext<Message = DisputeCoordinatorMessage>
+{
+	let mut state = State {
+		keystore: subsystem.keystore.clone(),
+		highest_session: None,
+		session_info: HashMap::new(),
+	};
+
+	loop {
+		match run_iteration(&mut ctx, &subsystem, &mut state).await {
+			Err(e) => {
+				e.trace();
+
+				if let Error::Subsystem(SubsystemError::Context(_)) = e {
+					break;
+				}
+			}
+			Ok(()) => {
+				tracing::info!(target: LOG_TARGET, "received `Conclude` signal, exiting");
+				break;
+			}
+		}
+	}
+}
+
+// Run the subsystem until an error is encountered or a `conclude` signal is received.
+// Most errors are non-fatal and should lead to another call to this function.
+//
+// A return value of `Ok` indicates that an exit should be made, while non-fatal errors
+// lead to another call to this function.
+async fn run_iteration<Context>(
+	ctx: &mut Context,
+	subsystem: &DisputeCoordinatorSubsystem,
+	state: &mut State,
+) -> Result<(), Error>
+	where Context: SubsystemContext<Message = DisputeCoordinatorMessage>
+{
+	let DisputeCoordinatorSubsystem { ref store, ref config, .. } = *subsystem;
+	let store = &**store;
+
+	loop {
+		match ctx.recv().await? {
+			FromOverseer::Signal(OverseerSignal::Conclude) => {
+				return Ok(())
+			}
+			FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+				handle_new_activations(
+					ctx,
+					store,
+					state,
+					config,
+					update,
+				).await?
+			}
+			FromOverseer::Signal(OverseerSignal::BlockFinalized(_, _)) => {},
+			FromOverseer::Communication { msg } => {
+				handle_incoming(
+					ctx,
+					store,
+					state,
+					config,
+					msg,
+				).await?
+			}
+		}
+	}
+}
+
+async fn handle_new_activations(
+	ctx: &mut impl SubsystemContext,
+	store: &dyn KeyValueDB,
+	state: &mut State,
+	config: &Config,
+	update: ActiveLeavesUpdate,
+) -> Result<(), Error> {
+	for new_leaf in update.activated {
+		let (tx, rx) = oneshot::channel();
+		ctx.send_message(RuntimeApiMessage::Request(
+			new_leaf.hash,
+			RuntimeApiRequest::SessionIndexForChild(tx),
+		).into()).await;
+
+		let session = rx.await??;
+
+		if state.highest_session.map_or(true, |s| session > s) {
+			db::v1::note_current_session(store, &config.column_config(), session)?;
+			state.highest_session = Some(session);
+
+			let earliest = session.saturating_sub(DISPUTE_WINDOW);
+			state.session_info.retain(|s, _| *s >= earliest);
+
+			for s in earliest..=session {
+				if state.session_info.contains_key(&s) {
+					continue;
+				}
+
+				let (tx, rx) = oneshot::channel();
+				ctx.send_message(RuntimeApiMessage::Request(
+					new_leaf.hash,
+					RuntimeApiRequest::SessionInfo(s, tx),
+				).into()).await;
+
+				match rx.await?? {
+					Some(info) => {
+						state.session_info.insert(s, info);
+					}
+					None => {
+						tracing::debug!(
+							target: LOG_TARGET,
+							session = s,
+							"No session info available for session within the dispute window",
+						);
+					}
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+async fn handle_incoming(
+	ctx: &mut impl SubsystemContext,
+	store: &dyn KeyValueDB,
+	state: &mut State,
+	config: &Config,
+	message: DisputeCoordinatorMessage,
+) -> Result<(), Error> {
+	match message {
+		DisputeCoordinatorMessage::ImportStatements {
+			candidate_hash,
+			candidate_receipt,
+			session,
+			statements,
+			pending_confirmation,
+		} => {
+			let result = handle_import_statements(
+				ctx,
+				store,
+				state,
+				config,
+				candidate_hash,
+				candidate_receipt,
+				session,
+				statements,
+			).await?;
+
+			if let Some(pending_confirmation) = pending_confirmation {
+				let _ = pending_confirmation.send(result);
+			}
+		}
+		DisputeCoordinatorMessage::ActiveDisputes(rx) => {
+			let recent_disputes = db::v1::load_recent_disputes(store, &config.column_config())?
+				.unwrap_or_default();
+
+			let _ = rx.send(recent_disputes.into_iter()
+				.filter(|(_, status)| status.is_active())
+				.map(|((session, hash), _)| (session, hash))
+				.collect()
+			);
+		}
+		DisputeCoordinatorMessage::RecentDisputes(rx) => {
+			let recent_disputes = db::v1::load_recent_disputes(store, &config.column_config())?
+				.unwrap_or_default();
+
+			let _ = rx.send(recent_disputes.into_iter()
+				.map(|((session, hash), status)| (session, hash, status))
+				.collect()
+			);
+		}
+		DisputeCoordinatorMessage::QueryCandidateVotes(session, candidate_hash, rx) => {
+			let candidate_votes = db::v1::load_candidate_votes(
+				store,
+				&config.column_config(),
+				session,
+				&candidate_hash,
+			)?;
+
+			let _ = rx.send(candidate_votes.map(Into::into));
+		}
+		DisputeCoordinatorMessage::IssueLocalStatement(
+			session,
+			candidate_hash,
+			candidate_receipt,
+			valid,
+		) => {
+			issue_local_statement(
+				ctx,
+				state,
+				store,
+				config,
+				candidate_hash,
+				candidate_receipt,
+				session,
+				valid,
+			).await?;
+		}
+		DisputeCoordinatorMessage::DetermineUndisputedChain {
+			base_number,
+			block_descriptions,
+			tx,
+		} => {
+			let undisputed_chain = determine_undisputed_chain(
+				store,
+				config,
+				base_number,
+				block_descriptions,
+			)?;
+
+			let _ = tx.send(undisputed_chain);
+		}
+	}
+
+	Ok(())
+}
+
+fn insert_into_statement_vec<T>(
+	vec: &mut Vec<(T, ValidatorIndex, ValidatorSignature)>,
+	tag: T,
+	val_index: ValidatorIndex,
+	val_signature: ValidatorSignature,
+) {
+	let pos = match vec.binary_search_by_key(&val_index, |x| x.1) {
+		Ok(_) => return, // no duplicates needed.
+		Err(p) => p,
+	};
+
+	vec.insert(pos, (tag, val_index, val_signature));
+}
+
+// The number of votes required to conclude a dispute in either direction.
+fn supermajority_threshold(n_validators: usize) -> usize {
+	n_validators - n_validators.saturating_sub(1) / 3
+}
+
+// Whether the statement is cast specifically for a dispute, as opposed to being
+// a backing or approval vote.
+fn is_explicit_dispute_statement(statement: &DisputeStatement) -> bool {
+	match statement {
+		DisputeStatement::Valid(ValidDisputeStatementKind::Explicit) |
+		DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit)
+			=> true,
+		_ => false,
+	}
+}
+
+async fn handle_import_statements(
+	ctx: &mut impl SubsystemContext,
+	store: &dyn KeyValueDB,
+	state: &mut State,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	candidate_receipt: CandidateReceipt,
+	session: SessionIndex,
+	statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
+) -> Result<ImportStatementsResult, Error> {
+	if state.highest_session.map_or(true, |s| session < s.saturating_sub(DISPUTE_WINDOW)) {
+		return Ok(ImportStatementsResult::InvalidImport);
+	}
+
+	let validators = match state.session_info.get(&session) {
+		None => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				session,
+				"Missing info for session which has an active dispute",
+			);
+
+			return Ok(ImportStatementsResult::InvalidImport)
+		}
+		Some(info) => info.validators.clone(),
+	};
+
+	let n_validators = validators.len();
+	let supermajority_threshold = supermajority_threshold(n_validators);
+
+	let mut votes = db::v1::load_candidate_votes(
+		store,
+		&config.column_config(),
+		session,
+		&candidate_hash,
+	)?
+		.map(CandidateVotes::from)
+		.unwrap_or_else(|| CandidateVotes {
+			candidate_receipt: candidate_receipt.clone(),
+			valid: Vec::new(),
+			invalid: Vec::new(),
+		});
+
+	// Sets of statements cast only for disputes are not accepted for unknown candidates,
+	// as a DoS prevention mechanism.
+	if votes.valid.is_empty() && votes.invalid.is_empty()
+		&& statements.iter().all(|(s, _)| is_explicit_dispute_statement(s.statement()))
+	{
+		return Ok(ImportStatementsResult::InvalidImport);
+	}
+
+	let was_undisputed = votes.valid.is_empty() || votes.invalid.is_empty();
+
+	// Update candidate votes.
+	for (statement, val_index) in statements {
+		if validators.get(val_index.0 as usize)
+			.map_or(true, |v| v != statement.validator_public())
+		{
+			tracing::debug!(
+				target: LOG_TARGET,
+				?val_index,
+				session,
+				claimed_key = ?statement.validator_public(),
+				"Validator index doesn't match claimed key",
+			);
+
+			continue
+		}
+
+		if statement.candidate_hash() != &candidate_hash || statement.session_index() != session {
+			continue
+		}
+
+		match statement.statement().clone() {
+			DisputeStatement::Valid(valid_kind) => {
+				insert_into_statement_vec(
+					&mut votes.valid,
+					valid_kind,
+					val_index,
+					statement.validator_signature().clone(),
+				);
+			}
+			DisputeStatement::Invalid(invalid_kind) => {
+				insert_into_statement_vec(
+					&mut votes.invalid,
+					invalid_kind,
+					val_index,
+					statement.validator_signature().clone(),
+				);
+			}
+		}
+	}
+
+	// Check if newly disputed.
+	let is_disputed = !votes.valid.is_empty() && !votes.invalid.is_empty();
+	let freshly_disputed = is_disputed && was_undisputed;
+	let concluded_valid = votes.valid.len() >= supermajority_threshold;
+	let concluded_invalid = votes.invalid.len() >= supermajority_threshold;
+
+	let mut tx = db::v1::Transaction::default();
+
+	if is_disputed {
+		let mut recent_disputes = db::v1::load_recent_disputes(store, &config.column_config())?
+			.unwrap_or_default();
+
+		let prev_status = recent_disputes.get(&(session, candidate_hash)).copied();
+
+		let status = {
+			let mut status = prev_status.unwrap_or_else(DisputeStatus::active);
+			let now = current_timestamp();
+
+			if concluded_valid {
+				status = status.concluded_for(now);
+			}
+
+			if concluded_invalid {
+				status = status.concluded_against(now);
+			}
+
+			status
+		};
+
+		if prev_status != Some(status) {
+			recent_disputes.insert((session, candidate_hash), status);
+			tx.put_recent_disputes(recent_disputes);
+		}
+	}
+
+	let voted_indices = votes.voted_indices();
+	tx.put_candidate_votes(session, candidate_hash, votes.into());
+	tx.write(store, &config.column_config())?;
+
+	// Participate in the freshly raised dispute, unless we already have a vote on it.
+	if freshly_disputed {
+		let has_own_vote = voted_indices.iter()
+			.filter_map(|i| validators.get(i.0 as usize))
+			.any(|v| has_key(&state.keystore, v));
+
+		let is_validator = validators.iter().any(|v| has_key(&state.keystore, v));
+
+		if is_validator && !has_own_vote {
+			let sender = ctx.sender().clone();
+			ctx.spawn(
+				"dispute-participation",
+				participation::participate(
+					sender,
+					session,
+					candidate_hash,
+					candidate_receipt,
+				).boxed(),
+			).await?;
+		}
+	}
+
+	Ok(ImportStatementsResult::ValidImport)
+}
+
+fn has_key(keystore: &LocalKeystore, validator: &ValidatorId) -> bool {
+	keystore.key_pair::<ValidatorPair>(validator).ok().flatten().is_some()
+}
+
+async fn issue_local_statement(
+	ctx: &mut impl SubsystemContext,
+	state: &mut State,
+	store: &dyn KeyValueDB,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	candidate_receipt: CandidateReceipt,
+	session: SessionIndex,
+	valid: bool,
+) -> Result<(), Error> {
+	// Load session info.
+	let validators = match state.session_info.get(&session) {
+		None => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				session,
+				"Missing info for session which has an active dispute",
+			);
+
+			return Ok(())
+		}
+		Some(info) => info.validators.clone(),
+	};
+
+	let votes = db::v1::load_candidate_votes(
+		store,
+		&config.column_config(),
+		session,
+		&candidate_hash,
+	)?
+		.map(CandidateVotes::from)
+		.unwrap_or_else(|| CandidateVotes {
+			candidate_receipt: candidate_receipt.clone(),
+			valid: Vec::new(),
+			invalid: Vec::new(),
+		});
+
+	// Sign a statement for each validator index we control which has
+	// not already voted. This should generally be maximum 1 statement.
+	let voted_indices = votes.voted_indices();
+	let keystore = state.keystore.clone() as SyncCryptoStorePtr;
+	let mut statements = Vec::new();
+
+	for (index, validator) in validators.iter().enumerate() {
+		let index = ValidatorIndex(index as _);
+		if voted_indices.contains(&index) {
+			continue
+		}
+
+		if !has_key(&state.keystore, validator) {
+			continue
+		}
+
+		let res = SignedDisputeStatement::sign_explicit(
+			&keystore,
+			valid,
+			candidate_hash,
+			session,
+			validator.clone(),
+		).await;
+
+		match res {
+			Ok(Some(signed_dispute_statement)) => {
+				statements.push((signed_dispute_statement, index));
+			}
+			Ok(None) => {}
+			Err(e) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					err = ?e,
+					"Encountered keystore error while signing dispute statement",
+				);
+			}
+		}
+	}
+
+	// Do import
+	if !statements.is_empty() {
+		handle_import_statements(
+			ctx,
+			store,
+			state,
+			config,
+			candidate_hash,
+			candidate_receipt.clone(),
+			session,
+			statements.clone(),
+		).await?;
+
+		// If the candidate is disputed, send out our votes to the other validators.
+		let votes = db::v1::load_candidate_votes(
+			store,
+			&config.column_config(),
+			session,
+			&candidate_hash,
+		)?.map(CandidateVotes::from);
+
+		if let (Some(votes), Some(info)) = (votes, state.session_info.get(&session)) {
+			for message in make_dispute_messages(info, session, &votes, statements) {
+				ctx.send_message(DisputeDistributionMessage::SendDispute(message).into()).await;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Pair each of our own statements with a vote of the opposing side, so they can be sent out
+/// as `DisputeMessage`s.
+///
+/// Returns an empty vector if the candidate is not disputed.
+fn make_dispute_messages(
+	session_info: &SessionInfo,
+	session: SessionIndex,
+	votes: &CandidateVotes,
+	statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
+) -> Vec<DisputeMessage> {
+	let candidate_hash = votes.candidate_receipt.hash();
+
+	// Votes in the store have been checked on import.
+	let trusted_statement = |statement, index: ValidatorIndex, signature: &ValidatorSignature| {
+		session_info.validators.get(index.0 as usize).map(|public| (
+			SignedDisputeStatement::new_unchecked_from_trusted_source(
+				statement,
+				candidate_hash,
+				session,
+				public.clone(),
+				signature.clone(),
+			),
+			index,
+		))
+	};
+
+	let valid_vote = votes.valid.first().and_then(|(kind, index, signature)|
+		trusted_statement(DisputeStatement::Valid(kind.clone()), *index, signature)
+	);
+	let invalid_vote = votes.invalid.first().and_then(|(kind, index, signature)|
+		trusted_statement(DisputeStatement::Invalid(kind.clone()), *index, signature)
+	);
+
+	let (valid_vote, invalid_vote) = match (valid_vote, invalid_vote) {
+		(Some(valid), Some(invalid)) => (valid, invalid),
+		_ => return Vec::new(),
+	};
+
+	statements.into_iter().filter_map(|(statement, index)| {
+		let ((valid, valid_index), (invalid, invalid_index)) = match statement.statement() {
+			DisputeStatement::Valid(_) => ((statement, index), invalid_vote.clone()),
+			DisputeStatement::Invalid(_) => (valid_vote.clone(), (statement, index)),
+		};
+
+		match DisputeMessage::from_signed_statements(
+			valid,
+			valid_index,
+			invalid,
+			invalid_index,
+			votes.candidate_receipt.clone(),
+			session_info,
+		) {
+			Ok(message) => Some(message),
+			Err(err) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?err,
+					?candidate_hash,
+					"Could not construct dispute message from own statement",
+				);
+				None
+			}
+		}
+	}).collect()
+}
+
+fn determine_undisputed_chain(
+	store: &dyn KeyValueDB,
+	config: &Config,
+	base_number: BlockNumber,
+	block_descriptions: Vec<(Hash, SessionIndex, Vec<CandidateHash>)>,
+) -> Result<Option<(BlockNumber, Hash)>, Error> {
+	let last = block_descriptions.last()
+		.map(|e| (base_number + block_descriptions.len() as BlockNumber, e.0));
+
+	// Fast path for no disputes.
+	let recent_disputes = match db::v1::load_recent_disputes(store, &config.column_config())? {
+		None => return Ok(last),
+		Some(a) if a.is_empty() => return Ok(last),
+		Some(a) => a,
+	};
+
+	// Only disputes which have concluded in favor of the candidate are no obstacle.
+	let is_possibly_invalid = |session, candidate_hash| {
+		recent_disputes.get(&(session, candidate_hash)).map_or(false, |status| match status {
+			DisputeStatus::ConcludedFor(_) => false,
+			DisputeStatus::Active | DisputeStatus::ConcludedAgainst(_) => true,
+		})
+	};
+
+	for (i, (_, session, candidates)) in block_descriptions.iter().enumerate() {
+		if candidates.iter().any(|c| is_possibly_invalid(*session, *c)) {
+			if i == 0 {
+				return Ok(None);
+			} else {
+				return Ok(Some((
+					base_number + i as BlockNumber,
+					block_descriptions[i - 1].0,
+				)));
+			}
+		}
+	}
+
+	Ok(last)
+}
+
+fn current_timestamp() -> Timestamp {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
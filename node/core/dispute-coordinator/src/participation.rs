@@ -0,0 +1,184 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Participation in disputes: recovering and validating a disputed candidate and
+//! casting a local vote on the outcome.
+
+use futures::channel::oneshot;
+
+use polkadot_node_primitives::ValidationResult;
+use polkadot_node_subsystem::{
+	messages::{
+		AvailabilityRecoveryMessage, ChainApiMessage, CandidateValidationMessage,
+		DisputeCoordinatorMessage, RuntimeApiMessage, RuntimeApiRequest,
+	},
+	errors::RecoveryError,
+	SubsystemSender,
+};
+use polkadot_primitives::v1::{CandidateHash, CandidateReceipt, SessionIndex};
+
+use crate::LOG_TARGET;
+
+/// Recover the available data of the disputed candidate, validate it and issue a local
+/// dispute statement reflecting the outcome.
+///
+/// This is intended to run in the background. No statement is issued if the outcome
+/// can't be determined, e.g. because the data is unavailable or the validation code is
+/// unknown.
+pub(crate) async fn participate(
+	mut sender: impl SubsystemSender,
+	session: SessionIndex,
+	candidate_hash: CandidateHash,
+	candidate_receipt: CandidateReceipt,
+) {
+	let (recover_tx, recover_rx) = oneshot::channel();
+	sender.send_message(AvailabilityRecoveryMessage::RecoverAvailableData(
+		candidate_receipt.clone(),
+		session,
+		None,
+		recover_tx,
+	).into()).await;
+
+	let available_data = match recover_rx.await {
+		Err(_) => return,
+		Ok(Ok(data)) => data,
+		Ok(Err(RecoveryError::Invalid)) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				"Data recovery invalid for disputed candidate",
+			);
+
+			// Either the merkle trie or the erasure root is bad, so the candidate is invalid.
+			issue_local_statement(&mut sender, session, candidate_hash, candidate_receipt, false).await;
+			return;
+		}
+		Ok(Err(RecoveryError::Unavailable)) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				"Data unavailable for disputed candidate",
+			);
+
+			return;
+		}
+	};
+
+	let relay_parent = candidate_receipt.descriptor.relay_parent;
+
+	let (number_tx, number_rx) = oneshot::channel();
+	sender.send_message(ChainApiMessage::BlockNumber(relay_parent, number_tx).into()).await;
+
+	let relay_parent_number = match number_rx.await {
+		Ok(Ok(Some(n))) => n,
+		Ok(Ok(None)) | Ok(Err(_)) | Err(_) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?relay_parent,
+				"Number of relay-parent of disputed candidate unknown",
+			);
+
+			return;
+		}
+	};
+
+	let (code_tx, code_rx) = oneshot::channel();
+	sender.send_message(RuntimeApiMessage::Request(
+		relay_parent,
+		RuntimeApiRequest::HistoricalValidationCode(
+			candidate_receipt.descriptor.para_id,
+			relay_parent_number,
+			code_tx,
+		),
+	).into()).await;
+
+	let validation_code = match code_rx.await {
+		Ok(Ok(Some(code))) => code,
+		Ok(Ok(None)) | Ok(Err(_)) | Err(_) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?relay_parent,
+				"Validation code of disputed candidate unavailable",
+			);
+
+			return;
+		}
+	};
+
+	let (validation_tx, validation_rx) = oneshot::channel();
+	sender.send_message(CandidateValidationMessage::ValidateFromExhaustive(
+		available_data.validation_data,
+		validation_code,
+		candidate_receipt.descriptor.clone(),
+		available_data.pov,
+		validation_tx,
+	).into()).await;
+
+	let valid = match validation_rx.await {
+		Err(_) => return,
+		Ok(Err(e)) => {
+			tracing::error!(
+				target: LOG_TARGET,
+				err = ?e,
+				"Failed to validate disputed candidate due to internal error",
+			);
+
+			return;
+		}
+		Ok(Ok(ValidationResult::Valid(commitments, _))) => {
+			if commitments.hash() != candidate_receipt.commitments_hash {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					"Disputed candidate is valid, but its commitments don't match the receipt",
+				);
+
+				false
+			} else {
+				true
+			}
+		}
+		Ok(Ok(ValidationResult::Invalid(reason))) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?reason,
+				"Disputed candidate is invalid",
+			);
+
+			false
+		}
+	};
+
+	issue_local_statement(&mut sender, session, candidate_hash, candidate_receipt, valid).await;
+}
+
+async fn issue_local_statement(
+	sender: &mut impl SubsystemSender,
+	session: SessionIndex,
+	candidate_hash: CandidateHash,
+	candidate_receipt: CandidateReceipt,
+	valid: bool,
+) {
+	sender.send_message(DisputeCoordinatorMessage::IssueLocalStatement(
+		session,
+		candidate_hash,
+		candidate_receipt,
+		valid,
+	).into()).await;
+}
@@ -0,0 +1,677 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use polkadot_primitives::v1::{BlakeTwo256, HashT, ValidatorId, Header};
+use polkadot_node_subsystem::{jaeger, ActivatedLeaf, LeafStatus, messages::{AllMessages, AvailabilityRecoveryMessage}};
+use sp_core::testing::TaskExecutor;
+use parity_scale_codec::Encode;
+use sp_keyring::Sr25519Keyring;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_application_crypto::AppKey;
+use futures::future::{self, BoxFuture};
+use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
+use assert_matches::assert_matches;
+
+// sets up a keystore with the given keyring accounts.
+fn make_keystore(accounts: &[Sr25519Keyring]) -> LocalKeystore {
+	let store = LocalKeystore::in_memory();
+
+	for s in accounts.iter().copied().map(|k| k.to_seed()) {
+		SyncCryptoStore::sr25519_generate_new(
+			&store,
+			ValidatorId::ID,
+			Some(s.as_str()),
+		).unwrap();
+	}
+
+	store
+}
+
+fn session_to_hash(session: SessionIndex, extra: impl Encode) -> Hash {
+	BlakeTwo256::hash_of(&(session, extra))
+}
+
+type VirtualOverseer = TestSubsystemContextHandle<DisputeCoordinatorMessage>;
+
+struct TestState {
+	validators: Vec<Sr25519Keyring>,
+	validator_public: Vec<ValidatorId>,
+	master_keystore: Arc<sc_keystore::LocalKeystore>,
+	subsystem_keystore: Arc<sc_keystore::LocalKeystore>,
+	db: Arc<dyn KeyValueDB>,
+	config: Config,
+}
+
+impl Default for TestState {
+	fn default() -> TestState {
+		let validators = vec![
+			Sr25519Keyring::Alice,
+			Sr25519Keyring::Bob,
+			Sr25519Keyring::Charlie,
+			Sr25519Keyring::Dave,
+			Sr25519Keyring::Eve,
+			Sr25519Keyring::One,
+			Sr25519Keyring::Ferdie,
+		];
+
+		let validator_public = validators.iter()
+			.map(|k| ValidatorId::from(k.public()))
+			.collect();
+
+		let master_keystore = make_keystore(&validators).into();
+		let subsystem_keystore = make_keystore(&[Sr25519Keyring::Alice]).into();
+
+		let db = Arc::new(kvdb_memorydb::create(1));
+		let config = Config {
+			col_data: 0,
+		};
+
+		TestState {
+			validators,
+			validator_public,
+			master_keystore,
+			subsystem_keystore,
+			db,
+			config,
+		}
+	}
+}
+
+impl TestState {
+	async fn activate_leaf_at_session(
+		&self,
+		virtual_overseer: &mut VirtualOverseer,
+		session: SessionIndex,
+		block_number: BlockNumber,
+	) {
+		assert!(block_number > 0);
+
+		let parent_hash = session_to_hash(session, b"parent");
+		let block_header = Header {
+			parent_hash,
+			number: block_number,
+			digest: Default::default(),
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let block_hash = block_header.hash();
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+			ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: block_hash,
+				span: Arc::new(jaeger::Span::Disabled),
+				number: block_number,
+				status: LeafStatus::Fresh,
+			})
+		))).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				h,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				assert_eq!(h, block_hash);
+				let _ = tx.send(Ok(session));
+			}
+		);
+
+		for i in session.saturating_sub(DISPUTE_WINDOW)..=session {
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::SessionInfo(session_index, tx),
+				)) => {
+					assert_eq!(h, block_hash);
+					assert_eq!(session_index, i);
+					let _ = tx.send(Ok(Some(self.session_info())));
+				}
+			);
+		}
+	}
+
+	fn session_info(&self) -> SessionInfo {
+		let discovery_keys = self.validators.iter()
+			.map(|k| <_>::from(k.public()))
+			.collect();
+
+		let assignment_keys = self.validators.iter()
+			.map(|k| <_>::from(k.public()))
+			.collect();
+
+		SessionInfo {
+			validators: self.validator_public.clone(),
+			discovery_keys,
+			assignment_keys,
+			validator_groups: Vec::new(),
+			n_cores: self.validators.len() as _,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 1,
+			n_delay_tranches: 100,
+			no_show_slots: 1,
+			needed_approvals: 10,
+		}
+	}
+
+	async fn issue_statement_with_index(
+		&self,
+		index: usize,
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+		valid: bool,
+	) -> SignedDisputeStatement {
+		let public = self.validator_public[index].clone();
+
+		let keystore = self.master_keystore.clone() as SyncCryptoStorePtr;
+
+		SignedDisputeStatement::sign_explicit(
+			&keystore,
+			valid,
+			candidate_hash,
+			session,
+			public,
+		).await.unwrap().unwrap()
+	}
+
+	fn approval_statement_with_index(
+		&self,
+		index: usize,
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+	) -> SignedDisputeStatement {
+		let statement = DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking);
+		let payload = statement.payload_data(candidate_hash, session);
+		let signature = self.validators[index].sign(&payload[..]);
+
+		SignedDisputeStatement::new_checked(
+			statement,
+			candidate_hash,
+			session,
+			self.validator_public[index].clone(),
+			signature.into(),
+		).unwrap()
+	}
+}
+
+fn test_harness<F>(test: F)
+	where F: FnOnce(TestState, VirtualOverseer) -> BoxFuture<'static, ()>
+{
+	let (ctx, ctx_handle) = make_subsystem_context(TaskExecutor::new());
+
+	let state = TestState::default();
+	let subsystem = DisputeCoordinatorSubsystem::new(
+		state.db.clone(),
+		state.config,
+		state.subsystem_keystore.clone(),
+	);
+
+	let subsystem_task = run(subsystem, ctx);
+	let test_task = test(state, ctx_handle);
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn conflicting_votes_lead_to_dispute_participation() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let valid_vote = test_state.approval_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+		);
+
+		let invalid_vote = test_state.issue_statement_with_index(
+			2,
+			candidate_hash,
+			session,
+			false,
+		).await;
+
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements: vec![
+					(valid_vote, ValidatorIndex(1)),
+					(invalid_vote, ValidatorIndex(2)),
+				],
+				pending_confirmation: None,
+			},
+		}).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::AvailabilityRecovery(AvailabilityRecoveryMessage::RecoverAvailableData(
+				c,
+				s,
+				_,
+				_,
+			)) => {
+				assert_eq!(c, candidate_receipt);
+				assert_eq!(s, session);
+			}
+		);
+
+		{
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+			}).await;
+
+			assert_eq!(rx.await.unwrap(), vec![(session, candidate_hash)]);
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::QueryCandidateVotes(
+					session,
+					candidate_hash,
+					tx,
+				),
+			}).await;
+
+			let votes = rx.await.unwrap().unwrap();
+			assert_eq!(votes.valid.len(), 1);
+			assert_eq!(votes.invalid.len(), 1);
+		}
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
+
+#[test]
+fn local_statement_in_dispute_is_sent_out() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let valid_vote = test_state.approval_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+		);
+
+		let invalid_vote = test_state.issue_statement_with_index(
+			2,
+			candidate_hash,
+			session,
+			false,
+		).await;
+
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements: vec![
+					(valid_vote, ValidatorIndex(1)),
+					(invalid_vote, ValidatorIndex(2)),
+				],
+				pending_confirmation: None,
+			},
+		}).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::AvailabilityRecovery(AvailabilityRecoveryMessage::RecoverAvailableData(..))
+		);
+
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::IssueLocalStatement(
+				session,
+				candidate_hash,
+				candidate_receipt.clone(),
+				true,
+			),
+		}).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::DisputeDistribution(DisputeDistributionMessage::SendDispute(message)) => {
+				assert_eq!(message.candidate_receipt(), &candidate_receipt);
+				assert_eq!(message.session_index(), session);
+				assert_eq!(message.valid_vote().validator_index, ValidatorIndex(0));
+				assert_eq!(message.invalid_vote().validator_index, ValidatorIndex(2));
+			}
+		);
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
+
+#[test]
+fn dispute_statements_for_unknown_candidates_are_discarded() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let valid_vote = test_state.issue_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+			true,
+		).await;
+
+		let invalid_vote = test_state.issue_statement_with_index(
+			2,
+			candidate_hash,
+			session,
+			false,
+		).await;
+
+		let (pending_confirmation, confirmation_rx) = oneshot::channel();
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements: vec![
+					(valid_vote, ValidatorIndex(1)),
+					(invalid_vote, ValidatorIndex(2)),
+				],
+				pending_confirmation: Some(pending_confirmation),
+			},
+		}).await;
+
+		assert_eq!(confirmation_rx.await, Ok(ImportStatementsResult::InvalidImport));
+
+		{
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+			}).await;
+
+			assert!(rx.await.unwrap().is_empty());
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::QueryCandidateVotes(
+					session,
+					candidate_hash,
+					tx,
+				),
+			}).await;
+
+			assert!(rx.await.unwrap().is_none());
+		}
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
+
+#[test]
+fn supermajority_valid_dispute_may_be_finalized() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let supermajority_threshold = supermajority_threshold(test_state.validators.len());
+
+		// Our own vote is issued as part of the test, so participation is not triggered.
+		let valid_vote = test_state.issue_statement_with_index(
+			0,
+			candidate_hash,
+			session,
+			true,
+		).await;
+
+		let invalid_vote = test_state.issue_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+			false,
+		).await;
+
+		let mut statements = vec![
+			(test_state.approval_statement_with_index(2, candidate_hash, session), ValidatorIndex(2)),
+			(valid_vote, ValidatorIndex(0)),
+			(invalid_vote, ValidatorIndex(1)),
+		];
+
+		for i in 3..supermajority_threshold + 1 {
+			let vote = test_state.issue_statement_with_index(
+				i,
+				candidate_hash,
+				session,
+				true,
+			).await;
+
+			statements.push((vote, ValidatorIndex(i as _)));
+		}
+
+		let (pending_confirmation, confirmation_rx) = oneshot::channel();
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements,
+				pending_confirmation: Some(pending_confirmation),
+			},
+		}).await;
+
+		assert_eq!(confirmation_rx.await, Ok(ImportStatementsResult::ValidImport));
+
+		{
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+			}).await;
+
+			assert!(rx.await.unwrap().is_empty());
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::RecentDisputes(tx),
+			}).await;
+
+			assert_matches!(
+				&rx.await.unwrap()[..],
+				[(s, h, DisputeStatus::ConcludedFor(_))] => {
+					assert_eq!(*s, session);
+					assert_eq!(*h, candidate_hash);
+				}
+			);
+
+			let block_hash_a = Hash::repeat_byte(0x0a);
+			let block_hash_b = Hash::repeat_byte(0x0b);
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::DetermineUndisputedChain {
+					base_number: 10,
+					block_descriptions: vec![
+						(block_hash_a, session, vec![]),
+						(block_hash_b, session, vec![candidate_hash]),
+					],
+					tx,
+				},
+			}).await;
+
+			assert_eq!(rx.await.unwrap(), Some((12, block_hash_b)));
+		}
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
+
+#[test]
+fn active_dispute_prevents_finality_of_including_block() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let valid_vote = test_state.issue_statement_with_index(
+			0,
+			candidate_hash,
+			session,
+			true,
+		).await;
+
+		let invalid_vote = test_state.issue_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+			false,
+		).await;
+
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements: vec![
+					(test_state.approval_statement_with_index(2, candidate_hash, session), ValidatorIndex(2)),
+					(valid_vote, ValidatorIndex(0)),
+					(invalid_vote, ValidatorIndex(1)),
+				],
+				pending_confirmation: None,
+			},
+		}).await;
+
+		let block_hash_a = Hash::repeat_byte(0x0a);
+		let block_hash_b = Hash::repeat_byte(0x0b);
+
+		{
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::DetermineUndisputedChain {
+					base_number: 10,
+					block_descriptions: vec![
+						(block_hash_a, session, vec![]),
+						(block_hash_b, session, vec![candidate_hash]),
+					],
+					tx,
+				},
+			}).await;
+
+			assert_eq!(rx.await.unwrap(), Some((11, block_hash_a)));
+
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::DetermineUndisputedChain {
+					base_number: 10,
+					block_descriptions: vec![
+						(block_hash_b, session, vec![candidate_hash]),
+					],
+					tx,
+				},
+			}).await;
+
+			assert_eq!(rx.await.unwrap(), None);
+		}
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
+
+#[test]
+fn ancient_sessions_are_pruned() {
+	test_harness(|test_state, mut virtual_overseer| Box::pin(async move {
+		let session = 1;
+
+		let candidate_receipt = CandidateReceipt::default();
+		let candidate_hash = candidate_receipt.hash();
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session,
+			1,
+		).await;
+
+		let valid_vote = test_state.approval_statement_with_index(
+			1,
+			candidate_hash,
+			session,
+		);
+
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash,
+				candidate_receipt: candidate_receipt.clone(),
+				session,
+				statements: vec![(valid_vote, ValidatorIndex(1))],
+				pending_confirmation: None,
+			},
+		}).await;
+
+		test_state.activate_leaf_at_session(
+			&mut virtual_overseer,
+			session + DISPUTE_WINDOW + 1,
+			2,
+		).await;
+
+		{
+			let (tx, rx) = oneshot::channel();
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: DisputeCoordinatorMessage::QueryCandidateVotes(
+					session,
+					candidate_hash,
+					tx,
+				),
+			}).await;
+
+			assert!(rx.await.unwrap().is_none());
+		}
+
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	}));
+}
@@ -82,6 +82,7 @@ impl ParachainsInherentDataProvider {
 				bitfields: pd.bitfields.into_iter().map(Into::into).collect(),
 				backed_candidates: pd.backed_candidates,
 				disputes: pd.disputes,
+				backing_misbehavior: pd.backing_misbehavior,
 				parent_header,
 			},
 			Err(err) => {
@@ -93,6 +94,7 @@ impl ParachainsInherentDataProvider {
 					bitfields: Vec::new(),
 					backed_candidates: Vec::new(),
 					disputes: Vec::new(),
+					backing_misbehavior: Vec::new(),
 					parent_header,
 				}
 			}
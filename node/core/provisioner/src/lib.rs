@@ -24,22 +24,28 @@ use futures::{
 	channel::{mpsc, oneshot},
 	prelude::*,
 };
+use polkadot_node_primitives::DISPUTE_WINDOW;
 use polkadot_node_subsystem::{
 	errors::{ChainApiError, RuntimeApiError}, PerLeafSpan, SubsystemSender, jaeger,
 	messages::{
-		CandidateBackingMessage, ChainApiMessage, ProvisionableData, ProvisionerInherentData,
-		ProvisionerMessage,
+		CandidateBackingMessage, ChainApiMessage, DisputeCoordinatorMessage, ProvisionableData,
+		ProvisionerInherentData, ProvisionerMessage,
 	},
 };
 use polkadot_node_subsystem_util::{
 	self as util, JobSubsystem, JobSender,
-	request_availability_cores, request_persisted_validation_data, JobTrait, metrics::{self, prometheus},
+	request_availability_cores, request_persisted_validation_data, request_disputes,
+	request_session_index_for_child, JobTrait,
+	metrics::{self, prometheus},
 };
 use polkadot_primitives::v1::{
-	BackedCandidate, BlockNumber, CandidateReceipt, CoreState, Hash, OccupiedCoreAssumption,
-	SignedAvailabilityBitfield, ValidatorIndex,
+	BackedCandidate, BackingMisbehavior, BackingMisbehaviorProof, BlockNumber, CandidateHash,
+	CandidateReceipt, CoreState, DisputeState, DisputeStatement, DisputeStatementSet, Hash,
+	MultiDisputeStatementSet, OccupiedCoreAssumption, SessionIndex, SignedAvailabilityBitfield,
+	ValidatorIndex,
 };
-use std::{pin::Pin, collections::BTreeMap, sync::Arc};
+use statement_table::{generic::ValidityDoubleVote, v1::Misbehavior};
+use std::{pin::Pin, collections::{BTreeMap, HashMap}, sync::Arc};
 use thiserror::Error;
 use futures_timer::Delay;
 
@@ -48,6 +54,12 @@ const PRE_PROPOSE_TIMEOUT: std::time::Duration = core::time::Duration::from_mill
 
 const LOG_TARGET: &str = "parachain::provisioner";
 
+/// The maximum number of dispute votes to include in a single block.
+///
+/// The runtime weighs every dispute statement individually, so this keeps the weight of the
+/// disputes well within the portion of the paras inherent not reserved for backed candidates.
+const MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME: usize = 1_000;
+
 enum InherentAfter {
 	Ready,
 	Wait(Delay),
@@ -86,6 +98,7 @@ pub struct ProvisioningJob {
 	receiver: mpsc::Receiver<ProvisionerMessage>,
 	backed_candidates: Vec<CandidateReceipt>,
 	signed_bitfields: Vec<SignedAvailabilityBitfield>,
+	misbehavior_reports: Vec<(ValidatorIndex, Misbehavior)>,
 	metrics: Metrics,
 	inherent_after: InherentAfter,
 	awaiting_inherent: Vec<oneshot::Sender<ProvisionerInherentData>>
@@ -110,6 +123,15 @@ pub enum Error {
 	#[error("failed to get backed candidates")]
 	CanceledBackedCandidates(#[source] oneshot::Canceled),
 
+	#[error("failed to get recent disputes")]
+	CanceledRecentDisputes(#[source] oneshot::Canceled),
+
+	#[error("failed to get session index")]
+	CanceledSessionIndex(#[source] oneshot::Canceled),
+
+	#[error("failed to get votes of a dispute")]
+	CanceledCandidateVotes(#[source] oneshot::Canceled),
+
 	#[error(transparent)]
 	ChainApi(#[from] ChainApiError),
 
@@ -172,6 +194,7 @@ impl ProvisioningJob {
 			receiver,
 			backed_candidates: Vec::new(),
 			signed_bitfields: Vec::new(),
+			misbehavior_reports: Vec::new(),
 			metrics,
 			inherent_after: InherentAfter::new_from_now(),
 			awaiting_inherent: Vec::new(),
@@ -229,6 +252,7 @@ impl ProvisioningJob {
 			self.relay_parent,
 			&self.signed_bitfields,
 			&self.backed_candidates,
+			&self.misbehavior_reports,
 			return_senders,
 			sender,
 		)
@@ -251,6 +275,9 @@ impl ProvisioningJob {
 					.with_para_id(backed_candidate.descriptor().para_id);
 				self.backed_candidates.push(backed_candidate)
 			}
+			ProvisionableData::MisbehaviorReport(_, validator_index, misbehavior) => {
+				self.misbehavior_reports.push((validator_index, misbehavior))
+			}
 			_ => {}
 		}
 	}
@@ -279,6 +306,7 @@ async fn send_inherent_data(
 	relay_parent: Hash,
 	bitfields: &[SignedAvailabilityBitfield],
 	candidates: &[CandidateReceipt],
+	misbehavior_reports: &[(ValidatorIndex, Misbehavior)],
 	return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
 	from_job: &mut impl SubsystemSender,
 ) -> Result<(), Error> {
@@ -295,10 +323,36 @@ async fn send_inherent_data(
 		from_job,
 	).await?;
 
+	let disputes = match select_disputes(relay_parent, from_job).await {
+		Ok(disputes) => disputes,
+		Err(err) => {
+			tracing::warn!(target: LOG_TARGET, err = ?err, "failed to select disputes");
+			Vec::new()
+		}
+	};
+
+	let backing_misbehavior = if misbehavior_reports.is_empty() {
+		Vec::new()
+	} else {
+		let session = request_session_index_for_child(relay_parent, from_job)
+			.await
+			.await.map_err(|err| Error::CanceledSessionIndex(err))??;
+
+		misbehavior_reports.iter()
+			.filter_map(|(validator_index, misbehavior)| backing_misbehavior_proof(
+				session,
+				relay_parent,
+				*validator_index,
+				misbehavior,
+			))
+			.collect()
+	};
+
 	let inherent_data = ProvisionerInherentData {
 		bitfields,
 		backed_candidates: candidates,
-		disputes: Vec::new(), // until disputes are implemented.
+		disputes,
+		backing_misbehavior,
 	};
 
 	for return_sender in return_senders {
@@ -308,6 +362,52 @@ async fn send_inherent_data(
 	Ok(())
 }
 
+/// Convert a misbehavior report from candidate backing into a proof which can be checked by the
+/// runtime.
+///
+/// Only misbehavior which is provable with the statements' signatures alone is converted, i.e.
+/// seconding two distinct candidates or seconding and then separately vouching for a candidate.
+fn backing_misbehavior_proof(
+	session: SessionIndex,
+	relay_parent: Hash,
+	validator_index: ValidatorIndex,
+	misbehavior: &Misbehavior,
+) -> Option<BackingMisbehaviorProof> {
+	let misbehavior = match *misbehavior {
+		Misbehavior::MultipleCandidates(ref multiple) => {
+			let (ref first, ref first_sig) = multiple.first;
+			let (ref second, ref second_sig) = multiple.second;
+
+			BackingMisbehavior::DoubleSeconded {
+				first: (first.hash(), first_sig.clone()),
+				second: (second.hash(), second_sig.clone()),
+			}
+		}
+		Misbehavior::ValidityDoubleVote(ValidityDoubleVote::IssuedAndValidity(
+			(ref candidate, ref seconded),
+			(ref candidate_hash, ref valid),
+		)) => {
+			if candidate.hash() != *candidate_hash {
+				return None;
+			}
+
+			BackingMisbehavior::SecondedAndValid {
+				candidate_hash: *candidate_hash,
+				seconded: seconded.clone(),
+				valid: valid.clone(),
+			}
+		}
+		Misbehavior::UnauthorizedStatement(_) | Misbehavior::DoubleSign(_) => return None,
+	};
+
+	Some(BackingMisbehaviorProof {
+		session,
+		relay_parent,
+		validator_index,
+		misbehavior,
+	})
+}
+
 /// In general, we want to pick all the bitfields. However, we have the following constraints:
 ///
 /// - not more than one per validator
@@ -468,6 +568,118 @@ async fn select_candidates(
 	Ok(candidates)
 }
 
+/// Select the dispute statement sets to be included in the block.
+///
+/// Disputes the chain does not know about yet take precedence over those it does, and active
+/// disputes over concluded ones. Votes which are already recorded on-chain are never included
+/// again, as the runtime would reject them as duplicates. In total, at most
+/// `MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME` votes are selected.
+async fn select_disputes(
+	relay_parent: Hash,
+	sender: &mut impl SubsystemSender,
+) -> Result<MultiDisputeStatementSet, Error> {
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(DisputeCoordinatorMessage::RecentDisputes(tx).into()).await;
+	let mut recent_disputes = rx.await.map_err(|err| Error::CanceledRecentDisputes(err))?;
+
+	if recent_disputes.is_empty() {
+		return Ok(Vec::new())
+	}
+
+	// The runtime ignores statements from sessions before its dispute period, so don't waste any
+	// space of the inherent on them.
+	let session = request_session_index_for_child(relay_parent, sender)
+		.await
+		.await.map_err(|err| Error::CanceledSessionIndex(err))??;
+	let earliest_session = session.saturating_sub(DISPUTE_WINDOW);
+	recent_disputes.retain(|(session, _, _)| *session >= earliest_session);
+
+	if recent_disputes.is_empty() {
+		return Ok(Vec::new())
+	}
+
+	// Failing to fetch the on-chain state is not fatal: we then just assume the chain knows
+	// about none of the disputes.
+	let onchain: HashMap<(SessionIndex, CandidateHash), DisputeState> =
+		match request_disputes(relay_parent, sender).await.await {
+			Ok(Ok(disputes)) => disputes
+				.into_iter()
+				.map(|(session, candidate_hash, state)| ((session, candidate_hash), state))
+				.collect(),
+			Ok(Err(err)) => {
+				tracing::debug!(target: LOG_TARGET, err = ?err, "failed to fetch on-chain disputes");
+				HashMap::new()
+			}
+			Err(err) => {
+				tracing::debug!(target: LOG_TARGET, err = ?err, "on-chain disputes request canceled");
+				HashMap::new()
+			}
+		};
+
+	recent_disputes.sort_by_key(|(session, candidate_hash, status)| (
+		onchain.contains_key(&(*session, *candidate_hash)),
+		!status.is_active(),
+	));
+
+	let mut remaining_votes = MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME;
+	let mut selected = Vec::new();
+
+	for (session, candidate_hash, _) in recent_disputes {
+		if remaining_votes == 0 {
+			break
+		}
+
+		let onchain_state = onchain.get(&(session, candidate_hash));
+
+		// Late votes on disputes which concluded on-chain are only accepted for a limited
+		// period we don't know about here, so don't risk invalidating the whole inherent.
+		if onchain_state.map_or(false, |state| state.concluded_at.is_some()) {
+			continue
+		}
+
+		let (tx, rx) = oneshot::channel();
+		sender.send_message(
+			DisputeCoordinatorMessage::QueryCandidateVotes(session, candidate_hash, tx).into()
+		).await;
+		let votes = match rx.await.map_err(|err| Error::CanceledCandidateVotes(err))? {
+			Some(votes) => votes,
+			None => continue,
+		};
+
+		let known_onchain = |bits: Option<&BitVec<bitvec::order::Lsb0, u8>>, index: ValidatorIndex| {
+			bits.and_then(|bits| bits.get(index.0 as usize).map(|b| *b)).unwrap_or(false)
+		};
+
+		let valid = votes.valid.into_iter()
+			.filter(|(_, index, _)| !known_onchain(onchain_state.map(|s| &s.validators_for), *index))
+			.map(|(kind, index, signature)| (DisputeStatement::Valid(kind), index, signature));
+		let invalid = votes.invalid.into_iter()
+			.filter(|(_, index, _)| !known_onchain(onchain_state.map(|s| &s.validators_against), *index))
+			.map(|(kind, index, signature)| (DisputeStatement::Invalid(kind), index, signature));
+
+		let statements: Vec<_> = valid.chain(invalid).collect();
+		if statements.is_empty() || statements.len() > remaining_votes {
+			continue
+		}
+
+		remaining_votes -= statements.len();
+		selected.push(DisputeStatementSet {
+			candidate_hash,
+			session,
+			statements,
+		});
+	}
+
+	tracing::debug!(
+		target: LOG_TARGET,
+		"Selected {} dispute statement sets with {} votes",
+		selected.len(),
+		MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME - remaining_votes,
+	);
+
+	Ok(selected)
+}
+
 /// Produces a block number 1 higher than that of the relay parent
 /// in the event of an invalid `relay_parent`, returns `Ok(0)`
 async fn get_block_number_under_construction(
@@ -469,3 +469,265 @@ mod select_candidates {
 		})
 	}
 }
+
+mod select_disputes {
+	use super::super::*;
+	use futures::channel::mpsc;
+	use polkadot_node_primitives::{CandidateVotes, DisputeStatus};
+	use polkadot_node_subsystem::messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest};
+	use polkadot_node_subsystem_test_helpers::TestSubsystemSender;
+	use polkadot_primitives::v1::{
+		InvalidDisputeStatementKind, ValidDisputeStatementKind, ValidatorSignature,
+	};
+
+	const SESSION: SessionIndex = 1;
+
+	fn candidate(n: u8) -> CandidateHash {
+		CandidateHash(Hash::repeat_byte(n))
+	}
+
+	fn votes(valid: impl IntoIterator<Item = u32>, invalid: impl IntoIterator<Item = u32>) -> CandidateVotes {
+		let signature: ValidatorSignature = sp_core::sr25519::Signature([0u8; 64]).into();
+		CandidateVotes {
+			candidate_receipt: Default::default(),
+			valid: valid.into_iter()
+				.map(|i| (ValidDisputeStatementKind::Explicit, ValidatorIndex(i), signature.clone()))
+				.collect(),
+			invalid: invalid.into_iter()
+				.map(|i| (InvalidDisputeStatementKind::Explicit, ValidatorIndex(i), signature.clone()))
+				.collect(),
+		}
+	}
+
+	fn onchain_state(valid: &[usize], invalid: &[usize], concluded: bool) -> DisputeState {
+		let mut validators_for = bitvec::bitvec![bitvec::order::Lsb0, u8; 0; 4];
+		let mut validators_against = validators_for.clone();
+		valid.iter().for_each(|i| validators_for.set(*i, true));
+		invalid.iter().for_each(|i| validators_against.set(*i, true));
+
+		DisputeState {
+			validators_for,
+			validators_against,
+			start: 1,
+			concluded_at: if concluded { Some(2) } else { None },
+		}
+	}
+
+	async fn mock_overseer(
+		mut receiver: mpsc::UnboundedReceiver<AllMessages>,
+		session: SessionIndex,
+		recent: Vec<(SessionIndex, CandidateHash, DisputeStatus)>,
+		onchain: Vec<(SessionIndex, CandidateHash, DisputeState)>,
+		votes: HashMap<CandidateHash, CandidateVotes>,
+	) {
+		while let Some(from_job) = receiver.next().await {
+			match from_job {
+				AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::RecentDisputes(tx)) => {
+					tx.send(recent.clone()).unwrap()
+				}
+				AllMessages::DisputeCoordinator(
+					DisputeCoordinatorMessage::QueryCandidateVotes(_, candidate_hash, tx)
+				) => tx.send(votes.get(&candidate_hash).cloned()).unwrap(),
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx))
+				) => tx.send(Ok(session)).unwrap(),
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(_, RuntimeApiRequest::Disputes(tx))
+				) => tx.send(Ok(onchain.clone())).unwrap(),
+				_ => panic!("Unexpected message: {:?}", from_job),
+			}
+		}
+	}
+
+	fn test_harness<Overseer, Test>(
+		overseer_factory: impl FnOnce(mpsc::UnboundedReceiver<AllMessages>) -> Overseer,
+		test_factory: impl FnOnce(TestSubsystemSender) -> Test,
+	) where
+		Overseer: Future<Output = ()>,
+		Test: Future<Output = ()>,
+	{
+		let (tx, rx) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let overseer = overseer_factory(rx);
+		let test = test_factory(tx);
+
+		futures::pin_mut!(overseer, test);
+
+		let _ = futures::executor::block_on(future::join(overseer, test));
+	}
+
+	#[test]
+	fn prefers_disputes_unknown_onchain() {
+		let recent = vec![
+			(SESSION, candidate(1), DisputeStatus::Active),
+			(SESSION, candidate(2), DisputeStatus::ConcludedFor(0)),
+			(SESSION, candidate(3), DisputeStatus::Active),
+			(SESSION, candidate(4), DisputeStatus::Active),
+		];
+		let onchain = vec![
+			(SESSION, candidate(1), onchain_state(&[0], &[1], false)),
+			(SESSION, candidate(4), onchain_state(&[0], &[1], true)),
+		];
+		let votes = vec![
+			(candidate(1), votes(vec![0, 2], vec![1])),
+			(candidate(2), votes(vec![0], vec![1])),
+			(candidate(3), votes(vec![2], vec![3])),
+			(candidate(4), votes(vec![0, 2], vec![1])),
+		].into_iter().collect();
+
+		test_harness(
+			|r| mock_overseer(r, SESSION, recent, onchain, votes),
+			|mut tx: TestSubsystemSender| async move {
+				let selected = select_disputes(Default::default(), &mut tx).await.unwrap();
+
+				// Unknown and active first, then unknown and concluded, then known. Disputes
+				// which concluded on-chain are left out.
+				let selected_candidates: Vec<_> = selected.iter().map(|s| s.candidate_hash).collect();
+				assert_eq!(selected_candidates, vec![candidate(3), candidate(2), candidate(1)]);
+
+				// Votes already on-chain are not included again:
+				let known = &selected[2];
+				assert_eq!(known.statements.len(), 1);
+				assert_eq!(known.statements[0].1, ValidatorIndex(2));
+				assert!(known.statements[0].0.indicates_validity());
+			},
+		)
+	}
+
+	#[test]
+	fn respects_vote_limit() {
+		let recent = vec![
+			(SESSION, candidate(1), DisputeStatus::Active),
+			(SESSION, candidate(2), DisputeStatus::Active),
+			(SESSION, candidate(3), DisputeStatus::Active),
+		];
+		let max_votes = MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME as u32;
+		let votes = vec![
+			(candidate(1), votes(0..max_votes - 2, vec![max_votes - 2])),
+			(candidate(2), votes(vec![0], vec![1])),
+			(candidate(3), votes(Vec::new(), vec![0])),
+		].into_iter().collect();
+
+		test_harness(
+			|r| mock_overseer(r, SESSION, recent, Vec::new(), votes),
+			|mut tx: TestSubsystemSender| async move {
+				let selected = select_disputes(Default::default(), &mut tx).await.unwrap();
+
+				// The second dispute does not fit anymore, but the third one does:
+				let selected_candidates: Vec<_> = selected.iter().map(|s| s.candidate_hash).collect();
+				assert_eq!(selected_candidates, vec![candidate(1), candidate(3)]);
+				let n_votes: usize = selected.iter().map(|s| s.statements.len()).sum();
+				assert_eq!(n_votes, MAX_DISPUTE_VOTES_FORWARDED_TO_RUNTIME);
+			},
+		)
+	}
+
+	#[test]
+	fn drops_disputes_outside_dispute_window() {
+		let session = SESSION + 1 + DISPUTE_WINDOW;
+		let recent = vec![
+			(SESSION, candidate(1), DisputeStatus::Active),
+			(SESSION + 1, candidate(2), DisputeStatus::Active),
+			(session, candidate(3), DisputeStatus::Active),
+		];
+		let votes = vec![
+			(candidate(1), votes(vec![0], vec![1])),
+			(candidate(2), votes(vec![0], vec![1])),
+			(candidate(3), votes(vec![0], vec![1])),
+		].into_iter().collect();
+
+		test_harness(
+			|r| mock_overseer(r, session, recent, Vec::new(), votes),
+			|mut tx: TestSubsystemSender| async move {
+				let selected = select_disputes(Default::default(), &mut tx).await.unwrap();
+
+				let selected_candidates: Vec<_> = selected.iter().map(|s| s.candidate_hash).collect();
+				assert_eq!(selected_candidates, vec![candidate(2), candidate(3)]);
+			},
+		)
+	}
+}
+
+mod backing_misbehavior_proof {
+	use super::super::*;
+	use polkadot_primitives::v1::{CommittedCandidateReceipt, ValidatorSignature};
+	use statement_table::generic::{MultipleCandidates, SignedStatement, Statement, UnauthorizedStatement};
+
+	const SESSION: SessionIndex = 1;
+
+	fn signature(n: u8) -> ValidatorSignature {
+		sp_core::sr25519::Signature([n; 64]).into()
+	}
+
+	fn receipt(n: u8) -> CommittedCandidateReceipt {
+		let mut receipt = CommittedCandidateReceipt::default();
+		receipt.descriptor.relay_parent = Hash::repeat_byte(n);
+		receipt
+	}
+
+	#[test]
+	fn multiple_candidates_become_double_seconded() {
+		let relay_parent = Hash::repeat_byte(0xAA);
+		let misbehavior = Misbehavior::MultipleCandidates(MultipleCandidates {
+			first: (receipt(1), signature(1)),
+			second: (receipt(2), signature(2)),
+		});
+
+		let proof = backing_misbehavior_proof(SESSION, relay_parent, ValidatorIndex(3), &misbehavior);
+
+		assert_eq!(proof, Some(BackingMisbehaviorProof {
+			session: SESSION,
+			relay_parent,
+			validator_index: ValidatorIndex(3),
+			misbehavior: BackingMisbehavior::DoubleSeconded {
+				first: (receipt(1).hash(), signature(1)),
+				second: (receipt(2).hash(), signature(2)),
+			},
+		}));
+	}
+
+	#[test]
+	fn issued_and_validity_becomes_seconded_and_valid() {
+		let relay_parent = Hash::repeat_byte(0xAA);
+		let misbehavior = Misbehavior::ValidityDoubleVote(ValidityDoubleVote::IssuedAndValidity(
+			(receipt(1), signature(1)),
+			(receipt(1).hash(), signature(2)),
+		));
+
+		let proof = backing_misbehavior_proof(SESSION, relay_parent, ValidatorIndex(3), &misbehavior);
+
+		assert_eq!(proof, Some(BackingMisbehaviorProof {
+			session: SESSION,
+			relay_parent,
+			validator_index: ValidatorIndex(3),
+			misbehavior: BackingMisbehavior::SecondedAndValid {
+				candidate_hash: receipt(1).hash(),
+				seconded: signature(1),
+				valid: signature(2),
+			},
+		}));
+
+		// A validity vote for a different candidate proves nothing.
+		let misbehavior = Misbehavior::ValidityDoubleVote(ValidityDoubleVote::IssuedAndValidity(
+			(receipt(1), signature(1)),
+			(receipt(2).hash(), signature(2)),
+		));
+
+		assert_eq!(backing_misbehavior_proof(SESSION, relay_parent, ValidatorIndex(3), &misbehavior), None);
+	}
+
+	#[test]
+	fn unprovable_misbehavior_is_dropped() {
+		let misbehavior = Misbehavior::UnauthorizedStatement(UnauthorizedStatement {
+			statement: SignedStatement {
+				statement: Statement::Valid(receipt(1).hash()),
+				signature: signature(1),
+				sender: ValidatorIndex(3),
+			},
+		});
+
+		assert_eq!(
+			backing_misbehavior_proof(SESSION, Hash::repeat_byte(0xAA), ValidatorIndex(3), &misbehavior),
+			None,
+		);
+	}
+}
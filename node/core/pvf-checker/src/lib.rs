@@ -0,0 +1,303 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The PVF pre-checker subsystem.
+//!
+//! New validation code, be it for an upgrade or for onboarding of a para, is not enacted by the
+//! runtime until a supermajority of validators have checked that it can be compiled. This
+//! subsystem watches the active leaves for the PVFs awaiting pre-checking, asks the candidate
+//! validation subsystem to prepare them and submits a signed vote with the outcome.
+
+#![deny(unused_crate_dependencies)]
+#![warn(missing_docs)]
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
+
+use polkadot_node_subsystem::{
+	messages::{AllMessages, CandidateValidationMessage, PreCheckOutcome, PvfCheckerMessage},
+	ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, Subsystem,
+	SubsystemContext, SubsystemError, SubsystemResult,
+};
+use polkadot_node_subsystem_util as util;
+use polkadot_primitives::v1::{
+	Hash, PvfCheckStatement, SessionIndex, ValidatorId, ValidatorIndex, ValidatorSignature,
+};
+use sp_application_crypto::AppKey;
+use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "parachain::pvf-checker";
+
+/// The PVF pre-checker subsystem.
+pub struct PvfCheckerSubsystem {
+	keystore: SyncCryptoStorePtr,
+}
+
+impl PvfCheckerSubsystem {
+	/// Create a new instance of the subsystem which signs the votes with the keys from the
+	/// given keystore.
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		PvfCheckerSubsystem { keystore }
+	}
+}
+
+impl<Context> Subsystem<Context> for PvfCheckerSubsystem
+where
+	Context: SubsystemContext<Message = PvfCheckerMessage>,
+{
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = run(ctx, self.keystore)
+			.map_err(|e| SubsystemError::with_origin("pvf-checker", e))
+			.boxed();
+
+		SpawnedSubsystem {
+			name: "pvf-checker-subsystem",
+			future,
+		}
+	}
+}
+
+/// The details of the most recent leaf in which we can vote.
+struct LeafContext {
+	hash: Hash,
+	session_index: SessionIndex,
+	validator_key: ValidatorId,
+	validator_index: ValidatorIndex,
+	/// The code hashes awaiting pre-checking as of this leaf.
+	pending: HashSet<Hash>,
+}
+
+#[derive(Default)]
+struct State {
+	/// The outcomes of the concluded pre-checks: `true` if the PVF was accepted.
+	judgements: HashMap<Hash, bool>,
+	/// The code hashes being pre-checked right now.
+	in_progress: HashSet<Hash>,
+	/// The votes we already submitted. The runtime resets the votes on every session change,
+	/// so the session is a part of the key.
+	voted: HashSet<(SessionIndex, Hash)>,
+	latest_leaf: Option<LeafContext>,
+}
+
+type PrecheckFuture = BoxFuture<'static, (Hash, PreCheckOutcome)>;
+
+async fn run<Context>(mut ctx: Context, keystore: SyncCryptoStorePtr) -> SubsystemResult<()>
+where
+	Context: SubsystemContext<Message = PvfCheckerMessage>,
+{
+	let mut state = State::default();
+	let mut prechecks = FuturesUnordered::<PrecheckFuture>::new();
+
+	loop {
+		futures::select! {
+			msg = ctx.recv().fuse() => match msg? {
+				FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+					handle_active_leaves(&mut ctx, &keystore, &mut state, &mut prechecks, update)
+						.await;
+				}
+				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {}
+				FromOverseer::Signal(OverseerSignal::Conclude) => return Ok(()),
+				FromOverseer::Communication { msg } => match msg {},
+			},
+			(code_hash, outcome) = prechecks.select_next_some() => {
+				handle_precheck_conclusion(&mut ctx, &keystore, &mut state, code_hash, outcome)
+					.await;
+			}
+		}
+	}
+}
+
+async fn handle_active_leaves(
+	ctx: &mut impl SubsystemContext,
+	keystore: &SyncCryptoStorePtr,
+	state: &mut State,
+	prechecks: &mut FuturesUnordered<PrecheckFuture>,
+	update: ActiveLeavesUpdate,
+) {
+	// The PVFs requiring pre-checking and the session are very unlikely to differ between forks,
+	// so it's enough to follow the highest of the activated leaves.
+	let leaf = match update.activated.iter().max_by_key(|leaf| leaf.number) {
+		Some(leaf) => leaf.hash,
+		None => return,
+	};
+
+	let leaf = match leaf_context(ctx, keystore, leaf).await {
+		Some(leaf) => leaf,
+		None => {
+			state.latest_leaf = None;
+			return;
+		}
+	};
+
+	state.judgements.retain(|code_hash, _| leaf.pending.contains(code_hash));
+	state.voted.retain(|(session_index, _)| *session_index == leaf.session_index);
+
+	for &code_hash in &leaf.pending {
+		if let Some(&accept) = state.judgements.get(&code_hash) {
+			// Either a new session has started or the previous submission failed.
+			if state.voted.insert((leaf.session_index, code_hash)) {
+				if !sign_and_submit(ctx, keystore, &leaf, code_hash, accept).await {
+					state.voted.remove(&(leaf.session_index, code_hash));
+				}
+			}
+		} else if state.in_progress.insert(code_hash) {
+			let (tx, rx) = oneshot::channel();
+			ctx.send_message(AllMessages::CandidateValidation(
+				CandidateValidationMessage::PreCheck(leaf.hash, code_hash, tx),
+			)).await;
+
+			prechecks.push(async move {
+				(code_hash, rx.await.unwrap_or(PreCheckOutcome::Failed))
+			}.boxed());
+		}
+	}
+
+	state.latest_leaf = Some(leaf);
+}
+
+async fn handle_precheck_conclusion(
+	ctx: &mut impl SubsystemContext,
+	keystore: &SyncCryptoStorePtr,
+	state: &mut State,
+	code_hash: Hash,
+	outcome: PreCheckOutcome,
+) {
+	state.in_progress.remove(&code_hash);
+
+	let accept = match outcome {
+		PreCheckOutcome::Valid => true,
+		PreCheckOutcome::Invalid => false,
+		PreCheckOutcome::Failed => {
+			// Abstain. The pre-check will be attempted again with the next leaf.
+			tracing::debug!(
+				target: LOG_TARGET,
+				?code_hash,
+				"Pre-check failed to conclude",
+			);
+			return;
+		}
+	};
+
+	state.judgements.insert(code_hash, accept);
+
+	let leaf = match state.latest_leaf {
+		Some(ref leaf) if leaf.pending.contains(&code_hash) => leaf,
+		_ => return,
+	};
+
+	if state.voted.insert((leaf.session_index, code_hash)) {
+		if !sign_and_submit(ctx, keystore, leaf, code_hash, accept).await {
+			state.voted.remove(&(leaf.session_index, code_hash));
+		}
+	}
+}
+
+/// Gathers the information required for voting in the given leaf. Returns `None` if we are not
+/// a validator in it or the runtime API requests failed.
+async fn leaf_context(
+	ctx: &mut impl SubsystemContext,
+	keystore: &SyncCryptoStorePtr,
+	leaf: Hash,
+) -> Option<LeafContext> {
+	let validators = recv_runtime(util::request_validators(leaf, ctx.sender()).await).await?;
+	let (validator_key, validator_index) = util::signing_key_and_index(&validators, keystore).await?;
+
+	let session_index = recv_runtime(
+		util::request_session_index_for_child(leaf, ctx.sender()).await,
+	).await?;
+	let pending = recv_runtime(util::request_pvfs_require_precheck(leaf, ctx.sender()).await).await?;
+
+	Some(LeafContext {
+		hash: leaf,
+		session_index,
+		validator_key,
+		validator_index,
+		pending: pending.into_iter().collect(),
+	})
+}
+
+/// Signs the vote on the given PVF and submits it to the runtime. Returns `true` if the
+/// submission succeeded.
+async fn sign_and_submit(
+	ctx: &mut impl SubsystemContext,
+	keystore: &SyncCryptoStorePtr,
+	leaf: &LeafContext,
+	code_hash: Hash,
+	accept: bool,
+) -> bool {
+	let stmt = PvfCheckStatement {
+		accept,
+		subject: code_hash,
+		session_index: leaf.session_index,
+		validator_index: leaf.validator_index,
+	};
+
+	let signature = CryptoStore::sign_with(
+		&**keystore,
+		ValidatorId::ID,
+		&leaf.validator_key.clone().into(),
+		&stmt.signing_payload(),
+	).await;
+
+	let signature: ValidatorSignature = match signature {
+		Ok(Some(signature)) => match signature.try_into() {
+			Ok(signature) => signature,
+			Err(_) => {
+				tracing::warn!(target: LOG_TARGET, "Keystore produced a malformed signature");
+				return false;
+			}
+		},
+		Ok(None) => {
+			tracing::warn!(target: LOG_TARGET, "The validator key has vanished from the keystore");
+			return false;
+		}
+		Err(e) => {
+			tracing::warn!(target: LOG_TARGET, err = ?e, "Failed to sign a PVF pre-checking vote");
+			return false;
+		}
+	};
+
+	tracing::debug!(
+		target: LOG_TARGET,
+		?code_hash,
+		accept,
+		session_index = leaf.session_index,
+		"Submitting a PVF pre-checking vote",
+	);
+
+	recv_runtime(
+		util::request_submit_pvf_check_statement(leaf.hash, stmt, signature, ctx.sender()).await,
+	).await.is_some()
+}
+
+async fn recv_runtime<T>(receiver: util::RuntimeApiReceiver<T>) -> Option<T> {
+	match receiver.await {
+		Ok(Ok(value)) => Some(value),
+		Ok(Err(e)) => {
+			tracing::debug!(target: LOG_TARGET, err = ?e, "Runtime API request failed");
+			None
+		}
+		Err(_) => {
+			tracing::debug!(target: LOG_TARGET, "Runtime API request was canceled");
+			None
+		}
+	}
+}
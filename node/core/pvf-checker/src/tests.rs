@@ -0,0 +1,272 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use polkadot_node_subsystem::{
+	jaeger, ActivatedLeaf, LeafStatus,
+	messages::{RuntimeApiMessage, RuntimeApiRequest},
+};
+use polkadot_node_subsystem_test_helpers as test_helpers;
+use polkadot_node_subsystem_util::TimeoutExt as _;
+use polkadot_primitives::v1::BlockNumber;
+use sc_keystore::LocalKeystore;
+use sp_core::Pair as _;
+use sp_keyring::Sr25519Keyring;
+use sp_keystore::SyncCryptoStore;
+
+use std::sync::Arc;
+use std::time::Duration;
+use assert_matches::assert_matches;
+use futures::{Future, executor, future};
+
+type VirtualOverseer = test_helpers::TestSubsystemContextHandle<PvfCheckerMessage>;
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	keystore: SyncCryptoStorePtr,
+	test_fn: impl FnOnce(VirtualOverseer) -> T,
+) {
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
+
+	let subsystem = run(context, keystore).map(|x| x.unwrap());
+	let test_fut = test_fn(virtual_overseer);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(async move {
+		let mut overseer = test_fut.await;
+		overseer
+			.send(FromOverseer::Signal(OverseerSignal::Conclude))
+			.timeout(TIMEOUT)
+			.await
+			.expect("Conclude send timeout");
+	}, subsystem));
+}
+
+fn make_alice_keystore() -> SyncCryptoStorePtr {
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	SyncCryptoStore::sr25519_generate_new(
+		&*keystore,
+		ValidatorId::ID,
+		Some(&Sr25519Keyring::Alice.to_seed()),
+	)
+	.expect("Insert key into keystore");
+	keystore
+}
+
+fn validators() -> Vec<ValidatorId> {
+	vec![
+		Sr25519Keyring::Bob.public().into(),
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Charlie.public().into(),
+	]
+}
+
+async fn activate_leaf(overseer: &mut VirtualOverseer, hash: Hash, number: BlockNumber) {
+	let leaf = ActivatedLeaf {
+		hash,
+		number,
+		status: LeafStatus::Fresh,
+		span: Arc::new(jaeger::Span::Disabled),
+	};
+	overseer
+		.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(leaf))))
+		.timeout(TIMEOUT)
+		.await
+		.expect("signal send timeout");
+}
+
+async fn overseer_recv(overseer: &mut VirtualOverseer) -> AllMessages {
+	overseer
+		.recv()
+		.timeout(TIMEOUT)
+		.await
+		.expect("msg recv timeout")
+}
+
+/// Answers the requests made by the subsystem to gather the leaf context.
+async fn answer_leaf_context(
+	overseer: &mut VirtualOverseer,
+	leaf: Hash,
+	session_index: SessionIndex,
+	pending: Vec<Hash>,
+) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::Validators(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			tx.send(Ok(validators())).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::SessionIndexForChild(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			tx.send(Ok(session_index)).unwrap();
+		}
+	);
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::PvfsRequirePrecheck(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			tx.send(Ok(pending)).unwrap();
+		}
+	);
+}
+
+async fn expect_precheck(overseer: &mut VirtualOverseer, code_hash: Hash, outcome: PreCheckOutcome) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::CandidateValidation(CandidateValidationMessage::PreCheck(_, hash, tx)) => {
+			assert_eq!(hash, code_hash);
+			tx.send(outcome).unwrap();
+		}
+	);
+}
+
+async fn expect_vote(
+	overseer: &mut VirtualOverseer,
+	leaf: Hash,
+	session_index: SessionIndex,
+	code_hash: Hash,
+	accept: bool,
+) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::SubmitPvfCheckStatement(stmt, signature, tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			assert_eq!(stmt, PvfCheckStatement {
+				accept,
+				subject: code_hash,
+				session_index,
+				validator_index: ValidatorIndex(1),
+			});
+			assert!(sp_core::sr25519::Pair::verify(
+				&signature.into(),
+				&stmt.signing_payload(),
+				&Sr25519Keyring::Alice.public(),
+			));
+			tx.send(Ok(())).unwrap();
+		}
+	);
+}
+
+async fn expect_nothing(overseer: &mut VirtualOverseer) {
+	assert!(overseer.recv().timeout(TIMEOUT).await.is_none());
+}
+
+#[test]
+fn votes_on_pending_pvfs() {
+	let code_a = Hash::repeat_byte(0xa);
+	let code_b = Hash::repeat_byte(0xb);
+	let leaf = Hash::repeat_byte(1);
+
+	test_harness(make_alice_keystore(), |mut overseer| async move {
+		activate_leaf(&mut overseer, leaf, 1).await;
+		answer_leaf_context(&mut overseer, leaf, 1, vec![code_a]).await;
+		expect_precheck(&mut overseer, code_a, PreCheckOutcome::Valid).await;
+		expect_vote(&mut overseer, leaf, 1, code_a, true).await;
+
+		let leaf = Hash::repeat_byte(2);
+		activate_leaf(&mut overseer, leaf, 2).await;
+		answer_leaf_context(&mut overseer, leaf, 1, vec![code_a, code_b]).await;
+		expect_precheck(&mut overseer, code_b, PreCheckOutcome::Invalid).await;
+		expect_vote(&mut overseer, leaf, 1, code_b, false).await;
+
+		expect_nothing(&mut overseer).await;
+		overseer
+	});
+}
+
+#[test]
+fn votes_again_in_new_session_without_rechecking() {
+	let code = Hash::repeat_byte(0xa);
+
+	test_harness(make_alice_keystore(), |mut overseer| async move {
+		let leaf = Hash::repeat_byte(1);
+		activate_leaf(&mut overseer, leaf, 1).await;
+		answer_leaf_context(&mut overseer, leaf, 1, vec![code]).await;
+		expect_precheck(&mut overseer, code, PreCheckOutcome::Valid).await;
+		expect_vote(&mut overseer, leaf, 1, code, true).await;
+
+		let leaf = Hash::repeat_byte(2);
+		activate_leaf(&mut overseer, leaf, 2).await;
+		answer_leaf_context(&mut overseer, leaf, 2, vec![code]).await;
+		expect_vote(&mut overseer, leaf, 2, code, true).await;
+
+		expect_nothing(&mut overseer).await;
+		overseer
+	});
+}
+
+#[test]
+fn abstains_when_precheck_fails() {
+	let code = Hash::repeat_byte(0xa);
+
+	test_harness(make_alice_keystore(), |mut overseer| async move {
+		let leaf = Hash::repeat_byte(1);
+		activate_leaf(&mut overseer, leaf, 1).await;
+		answer_leaf_context(&mut overseer, leaf, 1, vec![code]).await;
+		expect_precheck(&mut overseer, code, PreCheckOutcome::Failed).await;
+		expect_nothing(&mut overseer).await;
+
+		// The pre-check is retried with the next leaf.
+		let leaf = Hash::repeat_byte(2);
+		activate_leaf(&mut overseer, leaf, 2).await;
+		answer_leaf_context(&mut overseer, leaf, 1, vec![code]).await;
+		expect_precheck(&mut overseer, code, PreCheckOutcome::Valid).await;
+		expect_vote(&mut overseer, leaf, 1, code, true).await;
+
+		overseer
+	});
+}
+
+#[test]
+fn does_nothing_if_not_a_validator() {
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+
+	test_harness(keystore, |mut overseer| async move {
+		let leaf = Hash::repeat_byte(1);
+		activate_leaf(&mut overseer, leaf, 1).await;
+		assert_matches!(
+			overseer_recv(&mut overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Validators(tx),
+			)) => {
+				tx.send(Ok(validators())).unwrap();
+			}
+		);
+
+		expect_nothing(&mut overseer).await;
+		overseer
+	});
+}
@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::LOG_TARGET;
 use always_assert::always;
 use async_std::{
 	path::{Path, PathBuf},
+	prelude::*,
 };
 use polkadot_core_primitives::Hash;
 use std::{
@@ -25,6 +27,40 @@ use std::{
 };
 use parity_scale_codec::{Encode, Decode};
 
+/// The number of bytes read from the beginning of an artifact file when looking for the
+/// [`ArtifactHeader`] on startup. This is enough to fit the header together with the tag of the
+/// [`Artifact`] that follows it.
+const HEADER_PREFIX_LEN: u64 = 1024;
+
+/// The header which every artifact file starts with. It allows to tell on startup whether the
+/// artifact left by the previous run of the node can be reused.
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct ArtifactHeader {
+	/// The version of the node that produced the artifact.
+	node_version: String,
+	/// The fingerprint of the executor configuration the artifact was produced with.
+	config: Vec<u8>,
+	/// The hash of the code the artifact was produced from.
+	code_hash: Hash,
+}
+
+impl ArtifactHeader {
+	/// Returns the header for an artifact of the given code produced by the current executor.
+	pub fn current(code_hash: Hash) -> Self {
+		Self {
+			node_version: crate::executor_intf::NODE_VERSION.to_string(),
+			config: crate::executor_intf::config_fingerprint(),
+			code_hash,
+		}
+	}
+
+	/// Returns `true` if the artifact with this header was produced by the current executor.
+	fn is_current(&self) -> bool {
+		self.node_version == crate::executor_intf::NODE_VERSION &&
+			self.config == crate::executor_intf::config_fingerprint()
+	}
+}
+
 /// A final product of preparation process. Contains either a ready to run compiled artifact or
 /// a description what went wrong.
 #[derive(Encode, Decode)]
@@ -37,17 +73,32 @@ pub enum Artifact {
 	/// or were killed. This state is reported by the validation host (not by the worker).
 	DidntMakeIt,
 	/// The PVF passed all the checks and is ready for execution.
+	#[codec(index = 3)]
 	Compiled { compiled_artifact: Vec<u8> },
 }
 
 impl Artifact {
-	/// Serializes this struct into a byte buffer.
-	pub fn serialize(&self) -> Vec<u8> {
-		self.encode()
+	/// The encoded tag of [`Artifact::Compiled`]. It is used to recognize the compiled artifacts
+	/// on startup without reading them fully.
+	const COMPILED_TAG: u8 = 3;
+
+	/// Serializes this struct into a byte buffer, prefixed with the [`ArtifactHeader`] for the
+	/// given code hash.
+	pub fn serialize(&self, code_hash: Hash) -> Vec<u8> {
+		(ArtifactHeader::current(code_hash), self).encode()
 	}
 
 	/// Deserialize the given byte buffer to an artifact.
+	///
+	/// Fails if the artifact was produced by a different executor.
 	pub fn deserialize(mut bytes: &[u8]) -> Result<Self, String> {
+		let header = ArtifactHeader::decode(&mut bytes).map_err(|e| format!("{:?}", e))?;
+		if !header.is_current() {
+			return Err(format!(
+				"the artifact was produced by the node version {}",
+				header.node_version,
+			));
+		}
 		Artifact::decode(&mut bytes).map_err(|e| format!("{:?}", e))
 	}
 }
@@ -68,7 +119,6 @@ impl ArtifactId {
 	}
 
 	/// Tries to recover the artifact id from the given file name.
-	#[cfg(test)]
 	pub fn from_file_name(file_name: &str) -> Option<Self> {
 		use std::str::FromStr as _;
 
@@ -107,17 +157,23 @@ pub struct Artifacts {
 }
 
 impl Artifacts {
-	/// Initialize a blank cache at the given path. This will clear everything present at the
-	/// given path, to be populated over time.
+	/// Initialize the cache at the given path, reusing the artifacts left there by the previous
+	/// run of the node.
 	///
 	/// The recognized artifacts will be filled in the table and unrecognized will be removed.
+	/// An artifact is recognized if it was produced by the current executor with the current
+	/// configuration and the preparation that produced it didn't time out.
 	pub async fn new(cache_path: &Path) -> Self {
 		// Make sure that the cache path directory and all it's parents are created.
-		// First delete the entire cache. Nodes are long-running so this should populate shortly.
-		let _ = async_std::fs::remove_dir_all(cache_path).await;
 		let _ = async_std::fs::create_dir_all(cache_path).await;
 
-		Self { artifacts: HashMap::new() }
+		let mut artifacts = HashMap::new();
+		let now = SystemTime::now();
+		for artifact_id in scan_for_known_artifacts(cache_path).await {
+			artifacts.insert(artifact_id, ArtifactState::Prepared { last_time_needed: now });
+		}
+
+		Self { artifacts }
 	}
 
 	#[cfg(test)]
@@ -184,10 +240,84 @@ impl Artifacts {
 	}
 }
 
+/// Goes through the files at the given path, removing the ones that can't be reused, and returns
+/// the IDs of the artifacts which can.
+async fn scan_for_known_artifacts(cache_path: &Path) -> Vec<ArtifactId> {
+	let mut entries = match async_std::fs::read_dir(cache_path).await {
+		Ok(entries) => entries,
+		Err(err) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				"failed to read the artifact cache at {}: {:?}",
+				cache_path.display(),
+				err,
+			);
+			return Vec::new();
+		}
+	};
+
+	let mut known = Vec::new();
+	while let Some(entry) = entries.next().await {
+		let path = match entry {
+			Ok(entry) => entry.path(),
+			Err(_) => continue,
+		};
+
+		let artifact_id = path
+			.file_name()
+			.and_then(|file_name| file_name.to_str())
+			.and_then(ArtifactId::from_file_name);
+
+		match artifact_id {
+			Some(artifact_id) if is_reusable(&artifact_id, &path).await => known.push(artifact_id),
+			_ => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					"removing stale entry {} from the artifact cache",
+					path.display(),
+				);
+				if path.is_dir().await {
+					let _ = async_std::fs::remove_dir_all(&path).await;
+				} else {
+					let _ = async_std::fs::remove_file(&path).await;
+				}
+			}
+		}
+	}
+
+	known
+}
+
+/// Checks that the file at the given path is a compiled artifact of the current node. Only the
+/// beginning of the file is read, since the compiled artifacts can be large.
+async fn is_reusable(artifact_id: &ArtifactId, path: &Path) -> bool {
+	let mut prefix = Vec::new();
+	let read = async {
+		let file = async_std::fs::File::open(path).await?;
+		file.take(HEADER_PREFIX_LEN).read_to_end(&mut prefix).await
+	};
+	if read.await.is_err() {
+		return false;
+	}
+
+	let mut bytes = &prefix[..];
+	match ArtifactHeader::decode(&mut bytes) {
+		Ok(header) if header.is_current() && header.code_hash == artifact_id.code_hash => {}
+		_ => return false,
+	}
+
+	// Only the compiled artifacts are worth keeping. The failed or timed out preparation is
+	// retried, since it may have been caused by the environment rather than the code itself.
+	// The compiled artifacts are too large to be decoded from the prefix, so only the tag is
+	// checked.
+	bytes.first() == Some(&Artifact::COMPILED_TAG)
+}
+
 #[cfg(test)]
 mod tests {
 	use async_std::path::Path;
-	use super::{Artifacts, ArtifactId};
+	use super::{Artifact, ArtifactHeader, Artifacts, ArtifactId, ArtifactState};
+	use parity_scale_codec::Encode as _;
 	use sp_core::H256;
 	use std::str::FromStr;
 
@@ -243,4 +373,43 @@ mod tests {
 
 		std::fs::remove_dir_all(fake_cache_path).unwrap();
 	}
+
+	#[test]
+	fn artifacts_reuses_current_artifacts_on_startup() {
+		let cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		std::fs::create_dir_all(&cache_path).unwrap();
+
+		let hash = |byte| H256::repeat_byte(byte);
+		let write = |byte, bytes: Vec<u8>| {
+			let path = ArtifactId::new(hash(byte)).path(Path::new(&cache_path));
+			std::fs::write(path.to_str().unwrap(), bytes).unwrap();
+		};
+
+		let compiled = Artifact::Compiled { compiled_artifact: vec![0; 4096] };
+		write(1, compiled.serialize(hash(1)));
+		write(2, Artifact::PreparationErr("boom".to_string()).serialize(hash(2)));
+		// Produced by another version of the node.
+		let mut outdated = ArtifactHeader::current(hash(3));
+		outdated.node_version.push_str("-outdated");
+		write(3, (outdated, &compiled).encode());
+		// The code hash doesn't match the file name.
+		write(4, compiled.serialize(hash(5)));
+		write(6, Artifact::DidntMakeIt.serialize(hash(6)));
+		std::fs::write(cache_path.join("prepare-artifact-leftover"), b"junk").unwrap();
+
+		let mut artifacts = async_std::task::block_on(Artifacts::new(&cache_path));
+
+		assert!(matches!(
+			artifacts.artifact_state_mut(&ArtifactId::new(hash(1))),
+			Some(ArtifactState::Prepared { .. })
+		));
+		for byte in &[2, 3, 4, 6] {
+			assert!(artifacts.artifact_state_mut(&ArtifactId::new(hash(*byte))).is_none());
+		}
+		assert_eq!(std::fs::read_dir(&cache_path).unwrap().count(), 1);
+
+		std::fs::remove_dir_all(cache_path).unwrap();
+	}
 }
@@ -54,3 +54,13 @@ pub enum InvalidCandidate {
 	/// PVF execution (compilation is not included) took more time than was allotted.
 	HardTimeout,
 }
+
+/// An error raised during pre-checking of a PVF.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrecheckError {
+	/// The PVF failed prevalidation or compilation. The string contains the error message.
+	Invalid(String),
+	/// The pre-check couldn't be concluded for reasons that cannot be attributed to the PVF, e.g.
+	/// the preparation worker didn't make it in time.
+	InternalError(String),
+}
@@ -249,7 +249,10 @@ async fn validate_using_artifact(
 		match unsafe {
 			// SAFETY: this should be safe since the compiled artifact passed here comes from the
 			//         file created by the prepare workers. These files are obtained by calling
-			//         [`executor_intf::prepare`].
+			//         [`executor_intf::prepare`]. The files left on disk by a previous run of
+			//         the node are only reused if their header records the same node version and
+			//         executor configuration, i.e. they were produced by the same `wasmtime`
+			//         with the same settings. Everything else in the cache is removed on startup.
 			crate::executor_intf::execute(compiled_artifact, params, spawner.clone())
 		 } {
 			Err(err) => {
@@ -17,6 +17,7 @@
 //! Interface to the Substrate Executor
 
 use std::any::{TypeId, Any};
+use parity_scale_codec::Encode as _;
 use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{InvokeMethod, WasmModule as _},
@@ -38,6 +39,25 @@ const CONFIG: Config = Config {
 	},
 };
 
+/// The version of the node the artifacts are produced by, including the commit it was built from.
+///
+/// The commit pins the executor together with the `wasmtime` version in use, so the artifacts
+/// produced by any other build of the node are not reused and get recompiled.
+pub const NODE_VERSION: &str = env!("SUBSTRATE_CLI_IMPL_VERSION");
+
+/// Returns a fingerprint of the configuration the artifacts are compiled and executed with.
+///
+/// The artifacts compiled with a different configuration are not reused and get recompiled.
+pub fn config_fingerprint() -> Vec<u8> {
+	(
+		CONFIG.heap_pages,
+		CONFIG.allow_missing_func_imports,
+		CONFIG.semantics.fast_instance_reuse,
+		CONFIG.semantics.stack_depth_metering,
+	)
+		.encode()
+}
+
 /// Runs the prevaldation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
 pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
 	let blob = RuntimeBlob::new(code)?;
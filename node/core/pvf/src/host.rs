@@ -21,8 +21,8 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	Priority, Pvf, ValidationError,
-	artifacts::{Artifacts, ArtifactState, ArtifactId},
+	Priority, Pvf, ValidationError, PrecheckError,
+	artifacts::{Artifact, Artifacts, ArtifactState, ArtifactId},
 	execute, prepare,
 };
 use std::{
@@ -42,6 +42,9 @@ use futures::{
 /// An alias to not spell the type for the oneshot sender for the PVF execution result.
 pub(crate) type ResultSender = oneshot::Sender<Result<ValidationResult, ValidationError>>;
 
+/// An alias to not spell the type for the oneshot sender for the PVF pre-checking result.
+pub(crate) type PrecheckResultSender = oneshot::Sender<Result<(), PrecheckError>>;
+
 /// A handle to the async process serving the validation host requests.
 #[derive(Clone)]
 pub struct ValidationHost {
@@ -86,6 +89,24 @@ impl ValidationHost {
 			.await
 			.map_err(|_| "the inner loop hung up".to_string())
 	}
+
+	/// Prepare the given PVF and report whether it could be prepared successfully. The result will
+	/// be sent to the provided result sender.
+	///
+	/// The preparation is performed with the background priority and its artifact is cached just
+	/// like the one for a heads up, so the subsequent execution of the PVF doesn't have to wait.
+	///
+	/// Returns an error if the request cannot be sent to the validation host, i.e. if it shut down.
+	pub async fn precheck_pvf(
+		&mut self,
+		pvf: Pvf,
+		result_tx: PrecheckResultSender,
+	) -> Result<(), String> {
+		self.to_host_tx
+			.send(ToHost::PrecheckPvf { pvf, result_tx })
+			.await
+			.map_err(|_| "the inner loop hung up".to_string())
+	}
 }
 
 enum ToHost {
@@ -98,6 +119,10 @@ enum ToHost {
 	HeadsUp {
 		active_pvfs: Vec<Pvf>,
 	},
+	PrecheckPvf {
+		pvf: Pvf,
+		result_tx: PrecheckResultSender,
+	},
 }
 
 /// Configuration for the validation host.
@@ -220,20 +245,32 @@ struct PendingExecutionRequest {
 }
 
 /// A mapping from an artifact ID which is in preparation state to the list of pending execution
-/// requests that should be executed once the artifact's prepration is finished.
+/// requests that should be executed once the artifact's prepration is finished, as well as the
+/// list of pre-checking requests that should be answered at that point.
 #[derive(Default)]
-struct AwaitingPrepare(HashMap<ArtifactId, Vec<PendingExecutionRequest>>);
+struct AwaitingPrepare {
+	executions: HashMap<ArtifactId, Vec<PendingExecutionRequest>>,
+	prechecks: HashMap<ArtifactId, Vec<PrecheckResultSender>>,
+}
 
 impl AwaitingPrepare {
 	fn add(&mut self, artifact_id: ArtifactId, params: Vec<u8>, result_tx: ResultSender) {
-		self.0
+		self.executions
 			.entry(artifact_id)
 			.or_default()
 			.push(PendingExecutionRequest { params, result_tx });
 	}
 
+	fn add_precheck(&mut self, artifact_id: ArtifactId, result_tx: PrecheckResultSender) {
+		self.prechecks.entry(artifact_id).or_default().push(result_tx);
+	}
+
 	fn take(&mut self, artifact_id: &ArtifactId) -> Vec<PendingExecutionRequest> {
-		self.0.remove(artifact_id).unwrap_or_default()
+		self.executions.remove(artifact_id).unwrap_or_default()
+	}
+
+	fn take_prechecks(&mut self, artifact_id: &ArtifactId) -> Vec<PrecheckResultSender> {
+		self.prechecks.remove(artifact_id).unwrap_or_default()
 	}
 }
 
@@ -397,6 +434,17 @@ async fn handle_to_host(
 		ToHost::HeadsUp { active_pvfs } => {
 			handle_heads_up(artifacts, prepare_queue, active_pvfs).await?;
 		}
+		ToHost::PrecheckPvf { pvf, result_tx } => {
+			handle_precheck_pvf(
+				cache_path,
+				artifacts,
+				prepare_queue,
+				awaiting_prepare,
+				pvf,
+				result_tx,
+			)
+			.await?;
+		}
 	}
 
 	Ok(())
@@ -496,6 +544,71 @@ async fn handle_heads_up(
 	Ok(())
 }
 
+async fn handle_precheck_pvf(
+	cache_path: &Path,
+	artifacts: &mut Artifacts,
+	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
+	awaiting_prepare: &mut AwaitingPrepare,
+	pvf: Pvf,
+	result_tx: PrecheckResultSender,
+) -> Result<(), Fatal> {
+	let artifact_id = pvf.as_artifact_id();
+
+	if let Some(state) = artifacts.artifact_state_mut(&artifact_id) {
+		match state {
+			ArtifactState::Prepared {
+				ref mut last_time_needed,
+			} => {
+				*last_time_needed = SystemTime::now();
+
+				let outcome = read_precheck_outcome(&artifact_id.path(cache_path)).await;
+				let _ = result_tx.send(outcome);
+			}
+			ArtifactState::Preparing => {
+				// The priority is left as is: pre-checking is not urgent enough to justify
+				// amending it.
+				awaiting_prepare.add_precheck(artifact_id, result_tx);
+			}
+		}
+	} else {
+		artifacts.insert_preparing(artifact_id.clone());
+		send_prepare(
+			prepare_queue,
+			prepare::ToQueue::Enqueue {
+				priority: Priority::Background,
+				pvf,
+			},
+		)
+		.await?;
+
+		awaiting_prepare.add_precheck(artifact_id, result_tx);
+	}
+
+	Ok(())
+}
+
+/// Reads the artifact at the given path and tells whether the preparation that produced it
+/// succeeded.
+async fn read_precheck_outcome(artifact_path: &Path) -> Result<(), PrecheckError> {
+	let bytes = async_std::fs::read(artifact_path)
+		.await
+		.map_err(|e| PrecheckError::InternalError(format!("failed to read the artifact: {}", e)))?;
+
+	match Artifact::deserialize(&bytes) {
+		Ok(Artifact::Compiled { .. }) => Ok(()),
+		Ok(Artifact::PrevalidationErr(msg)) | Ok(Artifact::PreparationErr(msg)) => {
+			Err(PrecheckError::Invalid(msg))
+		}
+		Ok(Artifact::DidntMakeIt) => Err(PrecheckError::InternalError(
+			"the preparation didn't make it in time".to_string(),
+		)),
+		Err(e) => Err(PrecheckError::InternalError(format!(
+			"failed to decode the artifact: {}",
+			e
+		))),
+	}
+}
+
 async fn handle_prepare_done(
 	cache_path: &Path,
 	artifacts: &mut Artifacts,
@@ -548,6 +661,14 @@ async fn handle_prepare_done(
 		.await?;
 	}
 
+	let pending_prechecks = awaiting_prepare.take_prechecks(&artifact_id);
+	if !pending_prechecks.is_empty() {
+		let outcome = read_precheck_outcome(&artifact_path).await;
+		for result_tx in pending_prechecks {
+			let _ = result_tx.send(outcome.clone());
+		}
+	}
+
 	// Now consider the artifact prepared.
 	*state = ArtifactState::Prepared {
 		last_time_needed: SystemTime::now(),
@@ -633,6 +754,10 @@ mod tests {
 		Pvf::from_discriminator(descriminator).as_artifact_id()
 	}
 
+	fn artifact_hash(descriminator: u32) -> polkadot_core_primitives::Hash {
+		Pvf::from_discriminator(descriminator).code_hash
+	}
+
 	fn artifact_path(descriminator: u32) -> PathBuf {
 		artifact_id(descriminator)
 			.path(&PathBuf::from(std::env::temp_dir()))
@@ -1037,4 +1162,56 @@ mod tests {
 
 		test.poll_ensure_to_execute_queue_is_empty().await;
 	}
+
+	#[async_std::test]
+	async fn precheck_pvf_requests() {
+		let mut test = Builder::default().build();
+		let mut host = test.host_handle();
+
+		let (result_tx, result_rx_1) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(3), result_tx).await.unwrap();
+		let (result_tx, result_rx_2) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(3), result_tx).await.unwrap();
+
+		// Both requests are served by a single preparation.
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { priority: Priority::Background, .. }
+		);
+
+		async_std::fs::write(
+			artifact_path(3),
+			Artifact::PreparationErr("boom".to_string()).serialize(artifact_hash(3)),
+		)
+		.await
+		.unwrap();
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue::Prepared(artifact_id(3)))
+			.await
+			.unwrap();
+
+		assert_matches!(
+			run_until(&mut test.run, result_rx_1).await,
+			Ok(Err(PrecheckError::Invalid(msg))) if msg == "boom"
+		);
+		assert_matches!(
+			run_until(&mut test.run, result_rx_2).await,
+			Ok(Err(PrecheckError::Invalid(_)))
+		);
+
+		// The artifact is now prepared, so the outcome is read right away.
+		async_std::fs::write(
+			artifact_path(3),
+			Artifact::Compiled { compiled_artifact: Vec::new() }.serialize(artifact_hash(3)),
+		)
+		.await
+		.unwrap();
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(3), result_tx).await.unwrap();
+		assert_matches!(run_until(&mut test.run, result_rx).await, Ok(Ok(())));
+		test.poll_ensure_to_execute_queue_is_empty().await;
+
+		let _ = async_std::fs::remove_file(artifact_path(3)).await;
+	}
 }
@@ -21,7 +21,7 @@
 //! This crate provides a simple API. You first [`start`] the validation host, which gives you the
 //! [handle][`ValidationHost`] and the future you need to poll.
 //!
-//! Then using the handle the client can send two types of requests:
+//! Then using the handle the client can send three types of requests:
 //!
 //! (a) PVF execution. This accepts the PVF [params][`polkadot_parachain::primitives::ValidationParams`]
 //!     and the PVF [code][`Pvf`], prepares (verifies and compiles) the code, and then executes PVF
@@ -30,6 +30,9 @@
 //! (b) Heads up. This request allows to signal that the given PVF may be needed soon and that it
 //!     should be prepared for execution.
 //!
+//! (c) Pre-check. This request prepares the given PVF and reports whether the preparation was
+//!     successful. It is used to vote on the new validation code before it is enacted.
+//!
 //! The preparation results are cached for some time after they either used or was signalled in heads up.
 //! All requests that depends on preparation of the same PVF are bundled together and will be executed
 //! as soon as the artifact is prepared.
@@ -68,6 +71,11 @@
 //! The artifact is saved on disk and is also tracked by an in memory table. This in memory table
 //! doesn't contain the artifact contents though, only a flag that the given artifact is compiled.
 //!
+//! The artifacts survive the restarts of the node. Each artifact file starts with a header that
+//! records the version of the node, the fingerprint of the executor configuration and the code
+//! hash. On startup, the compiled artifacts with the header matching the current node are put into
+//! the table and the rest, including the failed preparations, are removed.
+//!
 //! The execute workers will be fed by the requests from the execution queue, which is basically a
 //! combination of a path to the compiled artifact and the
 //! [params][`polkadot_parachain::primitives::ValidationParams`].
@@ -88,7 +96,7 @@ mod worker_common;
 #[doc(hidden)]
 pub mod testing;
 
-pub use error::{ValidationError, InvalidCandidate};
+pub use error::{ValidationError, InvalidCandidate, PrecheckError};
 pub use priority::Priority;
 pub use pvf::Pvf;
 
@@ -29,6 +29,7 @@ use async_std::{
 };
 use futures::FutureExt as _;
 use futures_timer::Delay;
+use sp_core::blake2_256;
 use std::{sync::Arc, time::Duration};
 
 const NICENESS_BACKGROUND: i32 = 10;
@@ -89,7 +90,7 @@ pub async fn start_work(
 	}
 
 	with_tmp_file(pid, cache_path, |tmp_file| async move {
-		if let Err(err) = send_request(&mut stream, code, &tmp_file).await {
+		if let Err(err) = send_request(&mut stream, &code, &tmp_file).await {
 			tracing::warn!(
 				target: LOG_TARGET,
 				worker_pid = %pid,
@@ -171,7 +172,7 @@ pub async fn start_work(
 				Outcome::Concluded(IdleWorker { stream, pid })
 			}
 			Selected::IoErr | Selected::Deadline => {
-				let bytes = Artifact::DidntMakeIt.serialize();
+				let bytes = Artifact::DidntMakeIt.serialize(blake2_256(&code).into());
 				// best effort: there is nothing we can do here if the write fails.
 				let _ = async_std::fs::write(&artifact_path, &bytes).await;
 				Outcome::DidntMakeIt
@@ -228,10 +229,10 @@ where
 
 async fn send_request(
 	stream: &mut UnixStream,
-	code: Arc<Vec<u8>>,
+	code: &[u8],
 	tmp_file: &Path,
 ) -> io::Result<()> {
-	framed_send(stream, &*code).await?;
+	framed_send(stream, code).await?;
 	framed_send(stream, path_to_bytes(tmp_file)).await?;
 	Ok(())
 }
@@ -282,7 +283,7 @@ pub fn worker_entrypoint(socket_path: &str) {
 				worker_pid = %std::process::id(),
 				"worker: preparing artifact",
 			);
-			let artifact_bytes = prepare_artifact(&code).serialize();
+			let artifact_bytes = prepare_artifact(&code).serialize(blake2_256(&code).into());
 
 			// Write the serialized artifact into into a temp file.
 			tracing::debug!(
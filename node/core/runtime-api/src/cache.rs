@@ -19,7 +19,7 @@ use polkadot_primitives::v1::{
 	CoreState, GroupRotationInfo, InboundDownwardMessage, InboundHrmpMessage, Hash,
 	PersistedValidationData, Id as ParaId, OccupiedCoreAssumption,
 	SessionIndex, SessionInfo, ValidationCode, ValidatorId, ValidatorIndex,
-	AuthorityDiscoveryId,
+	AuthorityDiscoveryId, CandidateHash, DisputeState,
 };
 use sp_consensus_babe::Epoch;
 use parity_util_mem::{MallocSizeOf, MallocSizeOfExt};
@@ -44,6 +44,9 @@ const SESSION_INFO_CACHE_SIZE: usize = 64 * 1024;
 const DMQ_CONTENTS_CACHE_SIZE: usize = 64 * 1024;
 const INBOUND_HRMP_CHANNELS_CACHE_SIZE: usize = 64 * 1024;
 const CURRENT_BABE_EPOCH_CACHE_SIZE: usize = 64 * 1024;
+const DISPUTES_CACHE_SIZE: usize = 64 * 1024;
+const VALIDATION_CODE_BY_HASH_CACHE_SIZE: usize = 10 * 1024 * 1024;
+const PVFS_REQUIRE_PRECHECK_CACHE_SIZE: usize = 64 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -87,6 +90,9 @@ pub(crate) struct RequestResultCache {
 	dmq_contents: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Vec<InboundDownwardMessage<BlockNumber>>>>,
 	inbound_hrmp_channels_contents: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>>>,
 	current_babe_epoch: MemoryLruCache<Hash, DoesNotAllocate<Epoch>>,
+	disputes: MemoryLruCache<Hash, ResidentSizeOf<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>>,
+	validation_code_by_hash: MemoryLruCache<(Hash, Hash), ResidentSizeOf<Option<ValidationCode>>>,
+	pvfs_require_precheck: MemoryLruCache<Hash, ResidentSizeOf<Vec<Hash>>>,
 }
 
 impl Default for RequestResultCache {
@@ -107,6 +113,9 @@ impl Default for RequestResultCache {
 			dmq_contents: MemoryLruCache::new(DMQ_CONTENTS_CACHE_SIZE),
 			inbound_hrmp_channels_contents: MemoryLruCache::new(INBOUND_HRMP_CHANNELS_CACHE_SIZE),
 			current_babe_epoch: MemoryLruCache::new(CURRENT_BABE_EPOCH_CACHE_SIZE),
+			disputes: MemoryLruCache::new(DISPUTES_CACHE_SIZE),
+			validation_code_by_hash: MemoryLruCache::new(VALIDATION_CODE_BY_HASH_CACHE_SIZE),
+			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_CACHE_SIZE),
 		}
 	}
 }
@@ -231,6 +240,30 @@ impl RequestResultCache {
 	pub(crate) fn cache_current_babe_epoch(&mut self, relay_parent: Hash, epoch: Epoch) {
 		self.current_babe_epoch.insert(relay_parent, DoesNotAllocate(epoch));
 	}
+
+	pub(crate) fn disputes(&mut self, relay_parent: &Hash) -> Option<&Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>> {
+		self.disputes.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_disputes(&mut self, relay_parent: Hash, value: Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>) {
+		self.disputes.insert(relay_parent, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn validation_code_by_hash(&mut self, key: (Hash, Hash)) -> Option<&Option<ValidationCode>> {
+		self.validation_code_by_hash.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_validation_code_by_hash(&mut self, key: (Hash, Hash), value: Option<ValidationCode>) {
+		self.validation_code_by_hash.insert(key, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn pvfs_require_precheck(&mut self, relay_parent: &Hash) -> Option<&Vec<Hash>> {
+		self.pvfs_require_precheck.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_pvfs_require_precheck(&mut self, relay_parent: Hash, value: Vec<Hash>) {
+		self.pvfs_require_precheck.insert(relay_parent, ResidentSizeOf(value));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -249,4 +282,7 @@ pub(crate) enum RequestResult {
 	DmqContents(Hash, ParaId, Vec<InboundDownwardMessage<BlockNumber>>),
 	InboundHrmpChannelsContents(Hash, ParaId, BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>),
 	CurrentBabeEpoch(Hash, Epoch),
+	Disputes(Hash, Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>),
+	ValidationCodeByHash(Hash, Hash, Option<ValidationCode>),
+	PvfsRequirePrecheck(Hash, Vec<Hash>),
 }
@@ -133,6 +133,12 @@ impl<Client> RuntimeApiSubsystem<Client> where
 				self.requests_cache.cache_inbound_hrmp_channel_contents((relay_parent, para_id), contents),
 			CurrentBabeEpoch(relay_parent, epoch) =>
 				self.requests_cache.cache_current_babe_epoch(relay_parent, epoch),
+			Disputes(relay_parent, disputes) =>
+				self.requests_cache.cache_disputes(relay_parent, disputes),
+			ValidationCodeByHash(relay_parent, hash, code) =>
+				self.requests_cache.cache_validation_code_by_hash((relay_parent, hash), code),
+			PvfsRequirePrecheck(relay_parent, hashes) =>
+				self.requests_cache.cache_pvfs_require_precheck(relay_parent, hashes),
 		}
 	}
 
@@ -201,6 +207,15 @@ impl<Client> RuntimeApiSubsystem<Client> where
 			Request::CurrentBabeEpoch(sender) =>
 				query!(current_babe_epoch(), sender)
 					.map(|sender| Request::CurrentBabeEpoch(sender)),
+			Request::Disputes(sender) => query!(disputes(), sender)
+				.map(|sender| Request::Disputes(sender)),
+			Request::ValidationCodeByHash(hash, sender) =>
+				query!(validation_code_by_hash(hash), sender)
+					.map(|sender| Request::ValidationCodeByHash(hash, sender)),
+			Request::PvfsRequirePrecheck(sender) => query!(pvfs_require_precheck(), sender)
+				.map(|sender| Request::PvfsRequirePrecheck(sender)),
+			// Submissions have side effects and thus are never served from the cache.
+			request @ Request::SubmitPvfCheckStatement(..) => Some(request),
 		}
 	}
 
@@ -350,6 +365,20 @@ where
 		Request::DmqContents(id, sender) => query!(DmqContents, dmq_contents(id), sender),
 		Request::InboundHrmpChannelsContents(id, sender) => query!(InboundHrmpChannelsContents, inbound_hrmp_channels_contents(id), sender),
 		Request::CurrentBabeEpoch(sender) => query!(CurrentBabeEpoch, current_epoch(), sender),
+		Request::Disputes(sender) => query!(Disputes, disputes(), sender),
+		Request::ValidationCodeByHash(hash, sender) =>
+			query!(ValidationCodeByHash, validation_code_by_hash(hash), sender),
+		Request::PvfsRequirePrecheck(sender) =>
+			query!(PvfsRequirePrecheck, pvfs_require_precheck(), sender),
+		Request::SubmitPvfCheckStatement(stmt, signature, sender) => {
+			let api = client.runtime_api();
+			let res = api.submit_pvf_check_statement(&BlockId::Hash(relay_parent), stmt, signature)
+				.map_err(|e| RuntimeApiError::from(format!("{:?}", e)));
+			metrics.on_request(res.is_ok());
+			let _ = sender.send(res);
+
+			None
+		}
 	}
 }
 
@@ -420,7 +449,8 @@ mod tests {
 		ValidatorId, ValidatorIndex, GroupRotationInfo, CoreState, PersistedValidationData,
 		Id as ParaId, OccupiedCoreAssumption, SessionIndex, ValidationCode,
 		CommittedCandidateReceipt, CandidateEvent, InboundDownwardMessage,
-		BlockNumber, InboundHrmpMessage, SessionInfo, AuthorityDiscoveryId,
+		BlockNumber, InboundHrmpMessage, SessionInfo, AuthorityDiscoveryId, CandidateHash,
+		DisputeState, PvfCheckStatement, ValidatorSignature,
 	};
 	use polkadot_node_subsystem_test_helpers as test_helpers;
 	use sp_core::testing::TaskExecutor;
@@ -448,6 +478,9 @@ mod tests {
 		dmq_contents: HashMap<ParaId, Vec<InboundDownwardMessage>>,
 		hrmp_channels: HashMap<ParaId, BTreeMap<ParaId, Vec<InboundHrmpMessage>>>,
 		babe_epoch: Option<BabeEpoch>,
+		disputes: Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>,
+		pvfs_require_precheck: Vec<Hash>,
+		submitted_pvf_check_statements: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 	}
 
 	impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -561,6 +594,22 @@ mod tests {
 			) -> Option<ValidationCode> {
 				unreachable!("not used in tests");
 			}
+
+			fn disputes(&self) -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
+				self.disputes.clone()
+			}
+
+			fn pvfs_require_precheck(&self) -> Vec<Hash> {
+				self.pvfs_require_precheck.clone()
+			}
+
+			fn submit_pvf_check_statement(
+				&self,
+				stmt: PvfCheckStatement,
+				signature: ValidatorSignature,
+			) {
+				self.submitted_pvf_check_statements.lock().unwrap().push((stmt, signature));
+			}
 		}
 
 		impl BabeApi<Block> for MockRuntimeApi {
@@ -1227,4 +1276,104 @@ mod tests {
 
 		futures::executor::block_on(future::join(subsystem_task, test_task));
 	}
+
+	#[test]
+	fn requests_disputes() {
+		let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+		let mut runtime_api = MockRuntimeApi::default();
+		runtime_api.disputes = vec![(
+			1,
+			CandidateHash([2; 32].into()),
+			DisputeState {
+				validators_for: Default::default(),
+				validators_against: Default::default(),
+				start: 5,
+				concluded_at: None,
+			},
+		)];
+		let runtime_api = Arc::new(runtime_api);
+		let relay_parent = [1; 32].into();
+		let spawner = sp_core::testing::TaskExecutor::new();
+
+		let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+		let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+		let test_task = async move {
+			let (tx, rx) = oneshot::channel();
+
+			ctx_handle.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::Disputes(tx))
+			}).await;
+
+			assert_eq!(rx.await.unwrap().unwrap(), runtime_api.disputes);
+			ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+		};
+
+		futures::executor::block_on(future::join(subsystem_task, test_task));
+	}
+
+	#[test]
+	fn requests_pvfs_require_precheck() {
+		let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+		let mut runtime_api = MockRuntimeApi::default();
+		runtime_api.pvfs_require_precheck = vec![[1; 32].into(), [2; 32].into()];
+		let runtime_api = Arc::new(runtime_api);
+		let relay_parent = [1; 32].into();
+		let spawner = sp_core::testing::TaskExecutor::new();
+
+		let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+		let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+		let test_task = async move {
+			let (tx, rx) = oneshot::channel();
+
+			ctx_handle.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::PvfsRequirePrecheck(tx))
+			}).await;
+
+			assert_eq!(rx.await.unwrap().unwrap(), runtime_api.pvfs_require_precheck);
+			ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+		};
+
+		futures::executor::block_on(future::join(subsystem_task, test_task));
+	}
+
+	#[test]
+	fn submit_pvf_check_statement_is_not_cached() {
+		let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+		let runtime_api = Arc::new(MockRuntimeApi::default());
+		let relay_parent = [1; 32].into();
+		let spawner = sp_core::testing::TaskExecutor::new();
+
+		let stmt = PvfCheckStatement {
+			accept: true,
+			subject: [1; 32].into(),
+			session_index: 1,
+			validator_index: ValidatorIndex(1),
+		};
+		let signature = ValidatorSignature::from(sp_core::sr25519::Signature([1u8; 64]));
+
+		let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+		let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+		let test_task = async move {
+			for _ in 0..2 {
+				let (tx, rx) = oneshot::channel();
+				ctx_handle.send(FromOverseer::Communication {
+					msg: RuntimeApiMessage::Request(
+						relay_parent,
+						Request::SubmitPvfCheckStatement(stmt.clone(), signature.clone(), tx),
+					),
+				}).await;
+
+				rx.await.unwrap().unwrap();
+			}
+
+			assert_eq!(
+				&*runtime_api.submitted_pvf_check_statements.lock().unwrap(),
+				&[(stmt.clone(), signature.clone()), (stmt, signature)],
+			);
+
+			ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+		};
+
+		futures::executor::block_on(future::join(subsystem_task, test_task));
+	}
 }
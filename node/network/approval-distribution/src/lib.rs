@@ -27,7 +27,7 @@ mod tests;
 use std::collections::{BTreeMap, HashMap, HashSet, hash_map};
 use futures::{channel::oneshot, FutureExt as _};
 use polkadot_primitives::v1::{
-	Hash, BlockNumber, ValidatorIndex, ValidatorSignature, CandidateIndex,
+	AuthorityDiscoveryId, Hash, BlockNumber, ValidatorIndex, ValidatorSignature, CandidateIndex,
 };
 use polkadot_node_primitives::{
 	approval::{AssignmentCert, BlockApprovalMeta, IndirectSignedApprovalVote, IndirectAssignmentCert},
@@ -60,6 +60,31 @@ const BENEFIT_VALID_MESSAGE_FIRST: Rep = Rep::BenefitMinorFirst("Valid message w
 /// The Approval Distribution subsystem.
 pub struct ApprovalDistribution {
 	metrics: Metrics,
+	aggression_config: AggressionConfig,
+}
+
+/// Configuration of the aggression mode, which is engaged when finality lags behind.
+///
+/// While engaged, all assignments and approvals we hold for the oldest unfinalized blocks are
+/// resent to every connected validator, regardless of whether they are known to be aware of the
+/// blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggressionConfig {
+	/// The number of blocks finality has to lag behind our highest known block before
+	/// aggression is engaged. `None` disables aggression entirely.
+	pub resend_threshold: Option<BlockNumber>,
+	/// The number of new blocks to wait for between consecutive resends while finality is still
+	/// lagging.
+	pub resend_period: BlockNumber,
+}
+
+impl Default for AggressionConfig {
+	fn default() -> Self {
+		AggressionConfig {
+			resend_threshold: Some(16),
+			resend_period: 8,
+		}
+	}
 }
 
 /// The [`State`] struct is responsible for tracking the overall state of the subsystem.
@@ -82,6 +107,16 @@ struct State {
 
 	/// Peer view data is partially stored here, and partially inline within the [`BlockEntry`]s
 	peer_views: HashMap<PeerId, View>,
+
+	/// Connected peers which are known to be authorities, as opposed to full nodes.
+	authority_peers: HashMap<PeerId, AuthorityDiscoveryId>,
+
+	/// Configuration of the aggression mode.
+	aggression_config: AggressionConfig,
+
+	/// The number of our highest known block at the time we last resent messages in aggression
+	/// mode. Reset whenever a block is finalized.
+	last_aggression_resend: Option<BlockNumber>,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -189,15 +224,19 @@ impl State {
 		event: NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>,
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, _, authority_id) => {
 				// insert a blank view if none already present
 				tracing::trace!(
 					target: LOG_TARGET,
 					?peer_id,
 					?role,
+					?authority_id,
 					"Peer connected",
 				);
-				self.peer_views.entry(peer_id).or_default();
+				self.peer_views.entry(peer_id.clone()).or_default();
+				if let Some(authority_id) = authority_id {
+					self.authority_peers.insert(peer_id, authority_id);
+				}
 			}
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				tracing::trace!(
@@ -206,6 +245,7 @@ impl State {
 					"Peer disconnected",
 				);
 				self.peer_views.remove(&peer_id);
+				self.authority_peers.remove(&peer_id);
 				self.blocks.iter_mut().for_each(|(_hash, entry)| {
 					entry.known_by.remove(&peer_id);
 				})
@@ -343,6 +383,8 @@ impl State {
 				view_intersection,
 			).await;
 		}
+
+		self.maybe_resend_aggressively(ctx, metrics).await;
 	}
 
 	async fn process_incoming_peer_message(
@@ -500,6 +542,81 @@ impl State {
 			.for_each(|h| {
 				self.blocks.remove(h);
 			});
+
+		// finality advanced, so the oldest unfinalized blocks are different ones now
+		self.last_aggression_resend = None;
+	}
+
+	/// Resend all assignments and approvals for the oldest unfinalized blocks to all connected
+	/// validators if finality lags behind by more than the configured threshold.
+	///
+	/// Full nodes don't take part in approval checking, so they are left out.
+	async fn maybe_resend_aggressively(
+		&mut self,
+		ctx: &mut impl SubsystemContext<Message = ApprovalDistributionMessage>,
+		metrics: &Metrics,
+	) {
+		let resend_threshold = match self.aggression_config.resend_threshold {
+			Some(threshold) => threshold,
+			None => return,
+		};
+
+		let (oldest, newest) = match (
+			self.blocks_by_number.keys().next(),
+			self.blocks_by_number.keys().next_back(),
+		) {
+			(Some(oldest), Some(newest)) => (*oldest, *newest),
+			_ => return,
+		};
+
+		if newest.saturating_sub(oldest) < resend_threshold {
+			return;
+		}
+
+		if let Some(last_resend) = self.last_aggression_resend {
+			if newest < last_resend.saturating_add(self.aggression_config.resend_period) {
+				return;
+			}
+		}
+
+		self.last_aggression_resend = Some(newest);
+
+		let peers = self.peer_views.keys()
+			.filter(|peer_id| self.authority_peers.contains_key(peer_id))
+			.cloned()
+			.collect::<Vec<_>>();
+		let blocks = self.blocks_by_number.get(&oldest).cloned().unwrap_or_default();
+		if peers.is_empty() || blocks.is_empty() {
+			return;
+		}
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			oldest_unfinalized = oldest,
+			highest = newest,
+			num_peers = peers.len(),
+			"Finality is lagging, resending assignments and approvals to all validators",
+		);
+
+		metrics.on_aggression_resend();
+
+		// From now on, all validators are considered to be interested in these blocks, so that
+		// new messages for them are circulated to everyone as well.
+		for block in blocks.iter() {
+			if let Some(entry) = self.blocks.get_mut(block) {
+				for peer_id in peers.iter() {
+					let knowledge = entry.known_by.entry(peer_id.clone()).or_default();
+					knowledge.sent.known_messages.extend(entry.knowledge.known_messages.iter().cloned());
+				}
+			}
+		}
+
+		Self::send_gossip_messages_to_peers(
+			&self.blocks,
+			ctx,
+			peers,
+			blocks,
+		).await;
 	}
 
 	async fn import_and_circulate_assignment(
@@ -998,18 +1115,18 @@ impl State {
 		}
 		// step 6.
 		// send all assignments and approvals for all candidates in those blocks to the peer
-		Self::send_gossip_messages_to_peer(
+		Self::send_gossip_messages_to_peers(
 			entries,
 			ctx,
-			peer_id,
+			vec![peer_id],
 			to_send
 		).await;
 	}
 
-	async fn send_gossip_messages_to_peer(
+	async fn send_gossip_messages_to_peers(
 		entries: &HashMap<Hash, BlockEntry>,
 		ctx: &mut impl SubsystemContext<Message = ApprovalDistributionMessage>,
-		peer_id: PeerId,
+		peers: Vec<PeerId>,
 		blocks: Vec<Hash>,
 	) {
 		let mut assignments = Vec::new();
@@ -1024,9 +1141,9 @@ impl State {
 
 			tracing::trace!(
 				target: LOG_TARGET,
-				"Sending all assignments and approvals in block {} to peer {}",
+				"Sending all assignments and approvals in block {} to peers {:?}",
 				block,
-				peer_id,
+				peers,
 			);
 
 			for (candidate_index, candidate_entry) in entry.candidates.iter().enumerate() {
@@ -1069,12 +1186,12 @@ impl State {
 				target: LOG_TARGET,
 				num = assignments.len(),
 				?num_blocks,
-				?peer_id,
-				"Sending assignments to a peer",
+				?peers,
+				"Sending assignments to peers",
 			);
 
 			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				vec![peer_id.clone()],
+				peers.clone(),
 				protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
 				),
@@ -1086,12 +1203,12 @@ impl State {
 				target: LOG_TARGET,
 				num = approvals.len(),
 				?num_blocks,
-				?peer_id,
-				"Sending approvals to a peer",
+				?peers,
+				"Sending approvals to peers",
 			);
 
 			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				vec![peer_id],
+				peers,
 				protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
 				),
@@ -1122,14 +1239,23 @@ async fn modify_reputation(
 impl ApprovalDistribution {
 	/// Create a new instance of the [`ApprovalDistribution`] subsystem.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { metrics }
+		Self::with_aggression_config(AggressionConfig::default(), metrics)
+	}
+
+	/// Create a new instance of the [`ApprovalDistribution`] subsystem with the given
+	/// configuration of the aggression mode.
+	pub fn with_aggression_config(aggression_config: AggressionConfig, metrics: Metrics) -> Self {
+		Self { metrics, aggression_config }
 	}
 
 	async fn run<Context>(self, ctx: Context)
 	where
 		Context: SubsystemContext<Message = ApprovalDistributionMessage>,
 	{
-		let mut state = State::default();
+		let mut state = State {
+			aggression_config: self.aggression_config.clone(),
+			..Default::default()
+		};
 		self.run_inner(ctx, &mut state).await
 	}
 
@@ -1235,6 +1361,7 @@ struct MetricsInner {
 	assignments_imported_total: prometheus::Counter<prometheus::U64>,
 	approvals_imported_total: prometheus::Counter<prometheus::U64>,
 	unified_with_peer_total: prometheus::Counter<prometheus::U64>,
+	aggression_resends_total: prometheus::Counter<prometheus::U64>,
 
 	time_unify_with_peer: prometheus::Histogram,
 	time_import_pending_now_known: prometheus::Histogram,
@@ -1260,6 +1387,12 @@ impl Metrics {
 		}
 	}
 
+	fn on_aggression_resend(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.aggression_resends_total.inc();
+		}
+	}
+
 	fn time_unify_with_peer(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.time_unify_with_peer.start_timer())
 	}
@@ -1297,6 +1430,13 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			aggression_resends_total: prometheus::register(
+				prometheus::Counter::new(
+					"parachain_approval_distribution_aggression_resends_total",
+					"Number of times all messages for the oldest unfinalized blocks were resent to all peers due to lagging finality.",
+				)?,
+				registry,
+			)?,
 			time_unify_with_peer: prometheus::register(
 				prometheus::Histogram::with_opts(
 					prometheus::HistogramOpts::new(
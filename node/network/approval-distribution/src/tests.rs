@@ -19,7 +19,7 @@ use futures::{future, Future, executor};
 use assert_matches::assert_matches;
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt as _;
-use polkadot_node_network_protocol::{view, ObservedRole};
+use polkadot_node_network_protocol::{view, ObservedRole, peer_set::VALIDATION_PROTOCOL_V2};
 use polkadot_node_primitives::approval::{
 	AssignmentCertKind, RELAY_VRF_MODULO_CONTEXT, VRFOutput, VRFProof,
 };
@@ -113,11 +113,25 @@ async fn setup_peer_with_view(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
 	view: View,
+) {
+	setup_authority_peer_with_view(virtual_overseer, peer_id, None, view).await
+}
+
+async fn setup_authority_peer_with_view(
+	virtual_overseer: &mut VirtualOverseer,
+	peer_id: &PeerId,
+	authority_id: Option<AuthorityDiscoveryId>,
+	view: View,
 ) {
 	overseer_send(
 		virtual_overseer,
 		ApprovalDistributionMessage::NetworkBridgeUpdateV1(
-			NetworkBridgeEvent::PeerConnected(peer_id.clone(), ObservedRole::Full, None)
+			NetworkBridgeEvent::PeerConnected(
+				peer_id.clone(),
+				ObservedRole::Full,
+				VALIDATION_PROTOCOL_V2,
+				authority_id,
+			)
 		)
 	).await;
 	overseer_send(
@@ -1016,3 +1030,126 @@ fn sends_assignments_even_when_state_is_approved() {
 		virtual_overseer
 	});
 }
+
+/// 1. Import an assignment for a block no peer knows about.
+/// 2. Let finality lag behind by more than the aggression threshold.
+/// 3. All messages for the oldest unfinalized block are resent to all validators,
+///    who also receive new messages for that block from then on. Full nodes are left out.
+#[test]
+fn resends_messages_to_all_validators_when_finality_lags() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let authority_a = AuthorityDiscoveryId::from(sp_core::sr25519::Public::from_raw([1; 32]));
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hashes: Vec<_> = (1..=5u8).map(Hash::repeat_byte).collect();
+
+	let state = State {
+		aggression_config: AggressionConfig {
+			resend_threshold: Some(2),
+			resend_period: 2,
+		},
+		..Default::default()
+	};
+
+	let _ = test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_authority_peer_with_view(overseer, &peer_a, Some(authority_a), view![]).await;
+		setup_peer_with_view(overseer, &peer_b, view![]).await;
+
+		let meta = |number: BlockNumber| BlockApprovalMeta {
+			hash: hashes[number as usize - 1],
+			parent_hash: if number == 1 { parent_hash } else { hashes[number as usize - 2] },
+			number,
+			candidates: vec![Default::default(); 1],
+			slot: 1.into(),
+		};
+
+		overseer_send(overseer, ApprovalDistributionMessage::NewBlocks(vec![meta(1)])).await;
+
+		let validator_index = ValidatorIndex(0);
+		let cert = fake_assignment_cert(hashes[0], validator_index);
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), 0u32),
+		).await;
+
+		// finality now lags behind by 2 blocks, which engages aggression
+		overseer_send(overseer, ApprovalDistributionMessage::NewBlocks(vec![meta(2), meta(3)])).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer_a.clone()]);
+				assert_eq!(assignments, vec![(cert.clone(), 0u32)]);
+			}
+		);
+
+		// the resend period has not elapsed yet
+		overseer_send(overseer, ApprovalDistributionMessage::NewBlocks(vec![meta(4)])).await;
+
+		// new messages for the oldest block are now circulated to the peer as well
+		let approval = IndirectSignedApprovalVote {
+			block_hash: hashes[0],
+			candidate_index: 0u32,
+			validator: validator_index,
+			signature: Default::default(),
+		};
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone())).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer_a.clone()]);
+				assert_eq!(approvals, vec![approval.clone()]);
+			}
+		);
+
+		// the resend period has elapsed, so everything is sent again
+		overseer_send(overseer, ApprovalDistributionMessage::NewBlocks(vec![meta(5)])).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer_a.clone()]);
+				assert_eq!(assignments, vec![(cert.clone(), 0u32)]);
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
+				)
+			)) => {
+				assert_eq!(peers, vec![peer_a.clone()]);
+				assert_eq!(approvals, vec![approval]);
+			}
+		);
+
+		assert!(overseer
+			.recv()
+			.timeout(TIMEOUT)
+			.await
+			.is_none(),
+			"no message should be sent",
+		);
+		virtual_overseer
+	});
+}
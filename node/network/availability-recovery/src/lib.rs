@@ -20,6 +20,7 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::pin::Pin;
+use std::time::Duration;
 
 use futures::{channel::oneshot, prelude::*, stream::FuturesUnordered};
 use futures::future::{BoxFuture, RemoteHandle, FutureExt};
@@ -49,8 +50,10 @@ use polkadot_node_network_protocol::{
 		request::RequestError,
 	},
 };
-use polkadot_node_subsystem_util::request_session_info;
-use polkadot_erasure_coding::{branches, branch_hash, recovery_threshold, obtain_chunks_v1};
+use polkadot_node_subsystem_util::{request_session_info, TimeoutExt as _};
+use polkadot_erasure_coding::{
+	branches, branch_hash, recovery_threshold, systematic_recovery_threshold, obtain_chunks_v1,
+};
 mod error;
 
 #[cfg(test)]
@@ -64,9 +67,38 @@ const N_PARALLEL: usize = 50;
 // Size of the LRU cache where we keep recovered data.
 const LRU_SIZE: usize = 16;
 
+// The estimated size of the available data up to which we request it from the backers first,
+// when running with `RecoveryStrategy::BackersFirstIfSizeLower`. The larger data is requested
+// from the validators holding the systematic chunks before falling back to the regular chunks.
+const SMALL_POV_LIMIT: usize = 128 * 1024;
+
+// The time a backer is given to start responding to a full data request.
+const FULL_DATA_BASE_TIMEOUT: Duration = Duration::from_millis(300);
+
+// The lowest bandwidth of a backer, in bytes per second, we are willing to wait for when requesting
+// the full data from it.
+const MIN_BACKER_BANDWIDTH: usize = 10 * 1024 * 1024;
+
 /// The Availability Recovery Subsystem.
 pub struct AvailabilityRecoverySubsystem {
-	fast_path: bool,
+	recovery_strategy: RecoveryStrategy,
+}
+
+/// The way the available data is recovered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RecoveryStrategy {
+	/// Request the full data from the backing group first, if it's known, and fall back to the
+	/// chunks.
+	BackersFirstAlways,
+	/// Always recover the data from the chunks.
+	ChunksAlways,
+	/// Request the full data from the backing group first, if it's known and the estimated size of
+	/// the data is not over the given limit. Fall back to the chunks.
+	///
+	/// Recovering the data from the chunks is expensive for the CPU due to the erasure decoding,
+	/// but it spreads the load over all validators, while the full data is requested from the
+	/// handful of backers.
+	BackersFirstIfSizeLower(usize),
 }
 
 struct RequestFromBackersPhase {
@@ -75,18 +107,33 @@ struct RequestFromBackersPhase {
 	shuffled_backers: Vec<ValidatorIndex>,
 }
 
+type ChunkRequestFuture = BoxFuture<
+	'static,
+	Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>,
+>;
+
+struct RequestSystematicChunksPhase {
+	// the number of the systematic chunks, which are the chunks of the validators
+	// `0..n_systematic`.
+	n_systematic: usize,
+	// the systematic chunks which are yet to be requested, in the order of requesting.
+	to_request: Vec<ValidatorIndex>,
+	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	requesting_chunks: FuturesUnordered<ChunkRequestFuture>,
+}
+
 struct RequestChunksPhase {
 	// a random shuffling of the validators which indicates the order in which we connect to the validators and
 	// request the chunk from them.
 	shuffling: VecDeque<ValidatorIndex>,
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
-	requesting_chunks: FuturesUnordered<BoxFuture<
-		'static,
-		Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
-	>,
+	requesting_chunks: FuturesUnordered<ChunkRequestFuture>,
 }
 
 struct InteractionParams {
+	/// The estimated size of the available data, if known.
+	data_size_estimate: Option<usize>,
+
 	/// Discovery ids of `validators`.
 	validator_authority_keys: Vec<AuthorityDiscoveryId>,
 
@@ -105,6 +152,7 @@ struct InteractionParams {
 
 enum InteractionPhase {
 	RequestFromBackers(RequestFromBackersPhase),
+	RequestSystematicChunks(RequestSystematicChunksPhase),
 	RequestChunks(RequestChunksPhase),
 }
 
@@ -115,8 +163,12 @@ struct Interaction<S> {
 	/// The parameters of the interaction.
 	params: InteractionParams,
 
-	/// The phase of the interaction.
-	phase: InteractionPhase,
+	/// The strategy of the recovery.
+	recovery_strategy: RecoveryStrategy,
+
+	/// The backing group of the candidate, if it is known and the strategy allows to request the
+	/// full data from it.
+	backers: Option<Vec<ValidatorIndex>>,
 }
 
 impl RequestFromBackersPhase {
@@ -155,7 +207,25 @@ impl RequestFromBackersPhase {
 				IfDisconnected::TryConnect,
 			).into()).await;
 
-			match res.await {
+			let response = match params.data_size_estimate {
+				Some(size) => res.timeout(full_data_timeout(size)).await,
+				None => Some(res.await),
+			};
+
+			let response = match response {
+				Some(response) => response,
+				None => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						?validator_index,
+						"Timed out fetching full available data.",
+					);
+					continue;
+				}
+			};
+
+			match response {
 				Ok(req_res::v1::AvailableDataFetchingResponse::AvailableData(data)) => {
 					if reconstructed_data_matches_root(params.validators.len(), &params.erasure_root, &data) {
 						tracing::trace!(
@@ -222,39 +292,7 @@ impl RequestChunksPhase {
 		let max_requests = std::cmp::min(N_PARALLEL, params.threshold);
 		while self.requesting_chunks.len() < max_requests {
 			if let Some(validator_index) = self.shuffling.pop_back() {
-				let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
-				tracing::trace!(
-					target: LOG_TARGET,
-					?validator,
-					?validator_index,
-					candidate_hash = ?params.candidate_hash,
-					"Requesting chunk",
-				);
-
-				// Request data.
-				let raw_request = req_res::v1::ChunkFetchingRequest {
-					candidate_hash: params.candidate_hash,
-					index: validator_index,
-				};
-
-				let (req, res) = OutgoingRequest::new(
-					Recipient::Authority(validator),
-					raw_request.clone(),
-				);
-
-				sender.send_message(NetworkBridgeMessage::SendRequests(
-					vec![Requests::ChunkFetching(req)],
-					IfDisconnected::TryConnect,
-				).into()).await;
-
-				self.requesting_chunks.push(Box::pin(async move {
-					match res.await {
-						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk))
-							=> Ok(Some(chunk.recombine_into_chunk(&raw_request))),
-						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
-						Err(e) => Err((validator_index, e)),
-					}
-				}));
+				self.requesting_chunks.push(request_chunk(params, sender, validator_index).await);
 			} else {
 				break;
 			}
@@ -269,37 +307,8 @@ impl RequestChunksPhase {
 		while let Some(request_result) = self.requesting_chunks.next().await {
 			match request_result {
 				Ok(Some(chunk)) => {
-					// Check merkle proofs of any received chunks.
-
-					let validator_index = chunk.index;
-
-					if let Ok(anticipated_hash) = branch_hash(
-						&params.erasure_root,
-						&chunk.proof,
-						chunk.index.0 as usize,
-					) {
-						let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
-
-						if erasure_chunk_hash != anticipated_hash {
-							tracing::debug!(
-								target: LOG_TARGET,
-								?validator_index,
-								"Merkle proof mismatch",
-							);
-						} else {
-							tracing::trace!(
-								target: LOG_TARGET,
-								?validator_index,
-								"Received valid chunk.",
-							);
-							self.received_chunks.insert(validator_index, chunk);
-						}
-					} else {
-						tracing::debug!(
-							target: LOG_TARGET,
-							?validator_index,
-							"Invalid Merkle proof",
-						);
+					if is_chunk_valid(params, &chunk) {
+						self.received_chunks.insert(chunk.index, chunk);
 					}
 				}
 				Ok(None) => {}
@@ -328,35 +337,16 @@ impl RequestChunksPhase {
 
 	async fn run(
 		&mut self,
+		known_chunks: Vec<ErasureChunk>,
 		params: &InteractionParams,
 		sender: &mut impl SubsystemSender,
 	) -> Result<AvailableData, RecoveryError> {
-		// First query the store for any chunks we've got.
-		{
-			let (tx, rx) = oneshot::channel();
-			sender.send_message(
-				AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx).into()
-			).await;
+		// Don't request the chunks we've got already.
+		let chunk_indices: Vec<_> = known_chunks.iter().map(|c| c.index).collect();
+		self.shuffling.retain(|i| !chunk_indices.contains(i));
 
-			match rx.await {
-				Ok(chunks) => {
-					// This should either be length 1 or 0. If we had the whole data,
-					// we wouldn't have reached this stage.
-					let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
-					self.shuffling.retain(|i| !chunk_indices.contains(i));
-
-					for chunk in chunks {
-						self.received_chunks.insert(chunk.index, chunk);
-					}
-				}
-				Err(oneshot::Canceled) => {
-					tracing::warn!(
-						target: LOG_TARGET,
-						candidate_hash = ?params.candidate_hash,
-						"Failed to reach the availability store"
-					);
-				}
-			}
+		for chunk in known_chunks {
+			self.received_chunks.insert(chunk.index, chunk);
 		}
 
 		loop {
@@ -423,6 +413,201 @@ impl RequestChunksPhase {
 	}
 }
 
+impl RequestSystematicChunksPhase {
+	fn new(n_systematic: usize) -> Self {
+		RequestSystematicChunksPhase {
+			n_systematic,
+			to_request: (0..n_systematic as u32).rev().map(ValidatorIndex).collect(),
+			received_chunks: HashMap::new(),
+			requesting_chunks: FuturesUnordered::new(),
+		}
+	}
+
+	fn has_all_systematic_chunks(&self) -> bool {
+		(0..self.n_systematic as u32).all(|i| self.received_chunks.contains_key(&ValidatorIndex(i)))
+	}
+
+	// Run this phase to completion. If any of the systematic chunks couldn't be fetched, the phase
+	// concludes with `RecoveryError::Unavailable` and the received chunks can be handed over to
+	// the next phase.
+	async fn run(
+		&mut self,
+		known_chunks: Vec<ErasureChunk>,
+		params: &InteractionParams,
+		sender: &mut impl SubsystemSender,
+	) -> Result<AvailableData, RecoveryError> {
+		for chunk in known_chunks {
+			self.to_request.retain(|i| *i != chunk.index);
+			self.received_chunks.insert(chunk.index, chunk);
+		}
+
+		tracing::trace!(
+			target: LOG_TARGET,
+			candidate_hash = ?params.candidate_hash,
+			n_systematic = self.n_systematic,
+			"Requesting systematic chunks",
+		);
+
+		loop {
+			while self.requesting_chunks.len() < N_PARALLEL {
+				match self.to_request.pop() {
+					Some(validator_index) => self.requesting_chunks.push(
+						request_chunk(params, sender, validator_index).await
+					),
+					None => break,
+				}
+			}
+
+			let chunk = match self.requesting_chunks.next().await {
+				Some(Ok(Some(chunk))) if is_chunk_valid(params, &chunk) => Some(chunk),
+				Some(Ok(_)) => None,
+				Some(Err((validator_index, e))) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						err = ?e,
+						?validator_index,
+						"Failure requesting systematic chunk",
+					);
+					None
+				}
+				None => break,
+			};
+
+			match chunk {
+				Some(chunk) => {
+					self.received_chunks.insert(chunk.index, chunk);
+				}
+				None => {
+					// The data can't be rebuilt from the systematic chunks alone. Let the
+					// requests in flight conclude, so that their chunks are not lost.
+					self.to_request.clear();
+				}
+			}
+		}
+
+		if !self.has_all_systematic_chunks() {
+			return Err(RecoveryError::Unavailable);
+		}
+
+		let chunks = (0..self.n_systematic as u32)
+			.filter_map(|i| self.received_chunks.get(&ValidatorIndex(i)))
+			.map(|chunk| chunk.chunk.clone())
+			.collect();
+
+		match polkadot_erasure_coding::reconstruct_from_systematic_v1(params.validators.len(), chunks) {
+			Ok(data) if reconstructed_data_matches_root(
+				params.validators.len(),
+				&params.erasure_root,
+				&data,
+			) => {
+				tracing::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery from systematic chunks complete",
+				);
+
+				Ok(data)
+			}
+			res => {
+				// Leave the final judgement to the regular reconstruction.
+				tracing::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					err = ?res.err(),
+					"Data recovery from systematic chunks failed",
+				);
+
+				Err(RecoveryError::Unavailable)
+			}
+		}
+	}
+}
+
+/// Sends a request for the chunk of the given validator and returns the future of the response.
+async fn request_chunk(
+	params: &InteractionParams,
+	sender: &mut impl SubsystemSender,
+	validator_index: ValidatorIndex,
+) -> ChunkRequestFuture {
+	let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
+	tracing::trace!(
+		target: LOG_TARGET,
+		?validator,
+		?validator_index,
+		candidate_hash = ?params.candidate_hash,
+		"Requesting chunk",
+	);
+
+	// Request data.
+	let raw_request = req_res::v1::ChunkFetchingRequest {
+		candidate_hash: params.candidate_hash,
+		index: validator_index,
+	};
+
+	let (req, res) = OutgoingRequest::new(
+		Recipient::Authority(validator),
+		raw_request.clone(),
+	);
+
+	sender.send_message(NetworkBridgeMessage::SendRequests(
+		vec![Requests::ChunkFetching(req)],
+		IfDisconnected::TryConnect,
+	).into()).await;
+
+	Box::pin(async move {
+		match res.await {
+			Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk))
+				=> Ok(Some(chunk.recombine_into_chunk(&raw_request))),
+			Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
+			Err(e) => Err((validator_index, e)),
+		}
+	})
+}
+
+/// Checks the Merkle proof of the received chunk.
+fn is_chunk_valid(params: &InteractionParams, chunk: &ErasureChunk) -> bool {
+	let validator_index = chunk.index;
+
+	if let Ok(anticipated_hash) = branch_hash(
+		&params.erasure_root,
+		&chunk.proof,
+		chunk.index.0 as usize,
+	) {
+		let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
+
+		if erasure_chunk_hash != anticipated_hash {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?validator_index,
+				"Merkle proof mismatch",
+			);
+			false
+		} else {
+			tracing::trace!(
+				target: LOG_TARGET,
+				?validator_index,
+				"Received valid chunk.",
+			);
+			true
+		}
+	} else {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?validator_index,
+			"Invalid Merkle proof",
+		);
+		false
+	}
+}
+
+/// The time we wait for a backer to respond with the full data of the given estimated size.
+fn full_data_timeout(data_size_estimate: usize) -> Duration {
+	let transfer_millis = data_size_estimate.saturating_mul(1000) / MIN_BACKER_BANDWIDTH;
+	FULL_DATA_BASE_TIMEOUT + Duration::from_millis(transfer_millis as u64)
+}
+
 const fn is_unavailable(
 	received_chunks: usize,
 	requesting_chunks: usize,
@@ -476,26 +661,123 @@ impl<S: SubsystemSender> Interaction<S> {
 			}
 		}
 
-		loop {
+		self.params.data_size_estimate = self.query_data_size_estimate().await;
+
+		let mut phases = VecDeque::with_capacity(2);
+		if let Some(backers) = self.backers.take() {
+			let request_from_backers = match self.recovery_strategy {
+				RecoveryStrategy::BackersFirstIfSizeLower(limit) => {
+					// Prefer the backers if the size is unknown, as most of the data is small.
+					self.params.data_size_estimate.map_or(true, |size| size <= limit)
+				}
+				RecoveryStrategy::BackersFirstAlways => true,
+				RecoveryStrategy::ChunksAlways => false,
+			};
+
+			if request_from_backers {
+				phases.push_back(InteractionPhase::RequestFromBackers(
+					RequestFromBackersPhase::new(backers)
+				));
+			}
+		}
+
+		// Rebuilding the large data from the systematic chunks saves us the costly decoding.
+		let is_large = self.params.data_size_estimate.map_or(false, |size| size > SMALL_POV_LIMIT);
+		if is_large {
+			if let Ok(n_systematic) = systematic_recovery_threshold(self.params.validators.len()) {
+				phases.push_back(InteractionPhase::RequestSystematicChunks(
+					RequestSystematicChunksPhase::new(n_systematic)
+				));
+			}
+		}
+
+		phases.push_back(InteractionPhase::RequestChunks(
+			RequestChunksPhase::new(self.params.validators.len() as _)
+		));
+
+		// The chunks we've got so far, which are handed over from one phase to the next one.
+		let mut known_chunks = None;
+
+		// Each phase runs only if the previous ones couldn't recover the data.
+		while let Some(mut phase) = phases.pop_front() {
 			// These only fail if we cannot reach the underlying subsystem, which case there is nothing
 			// meaningful we can do.
-			match self.phase {
+			let result = match phase {
 				InteractionPhase::RequestFromBackers(ref mut from_backers) => {
-					match from_backers.run(&self.params, &mut self.sender).await {
-						Ok(data) => break Ok(data),
-						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
-						Err(RecoveryError::Unavailable) => {
-							self.phase = InteractionPhase::RequestChunks(
-								RequestChunksPhase::new(self.params.validators.len() as _)
-							)
-						}
-					}
+					from_backers.run(&self.params, &mut self.sender).await
+				}
+				InteractionPhase::RequestSystematicChunks(ref mut systematic) => {
+					let chunks = match known_chunks.take() {
+						Some(chunks) => chunks,
+						None => self.query_local_chunks().await,
+					};
+					systematic.run(chunks, &self.params, &mut self.sender).await
 				}
 				InteractionPhase::RequestChunks(ref mut from_all) => {
-					break from_all.run(&self.params, &mut self.sender).await;
+					let chunks = match known_chunks.take() {
+						Some(chunks) => chunks,
+						None => self.query_local_chunks().await,
+					};
+					from_all.run(chunks, &self.params, &mut self.sender).await
+				}
+			};
+
+			match result {
+				Ok(data) => return Ok(data),
+				Err(RecoveryError::Invalid) => return Err(RecoveryError::Invalid),
+				Err(RecoveryError::Unavailable) => {
+					if let InteractionPhase::RequestSystematicChunks(systematic) = phase {
+						known_chunks = Some(
+							systematic.received_chunks.into_iter().map(|(_, chunk)| chunk).collect()
+						);
+					}
 				}
 			}
 		}
+
+		Err(RecoveryError::Unavailable)
+	}
+
+	/// Queries the store for any chunks we've got.
+	async fn query_local_chunks(&mut self) -> Vec<ErasureChunk> {
+		let (tx, rx) = oneshot::channel();
+		self.sender.send_message(
+			AvailabilityStoreMessage::QueryAllChunks(self.params.candidate_hash, tx).into()
+		).await;
+
+		match rx.await {
+			// This should either be length 1 or 0. If we had the whole data,
+			// we wouldn't have reached this stage.
+			Ok(chunks) => chunks,
+			Err(oneshot::Canceled) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?self.params.candidate_hash,
+					"Failed to reach the availability store"
+				);
+				Vec::new()
+			}
+		}
+	}
+
+	/// Estimates the size of the available data from the size of the chunks we have locally.
+	async fn query_data_size_estimate(&mut self) -> Option<usize> {
+		let (tx, rx) = oneshot::channel();
+		self.sender.send_message(
+			AvailabilityStoreMessage::QueryChunkSize(self.params.candidate_hash, tx).into()
+		).await;
+
+		match rx.await {
+			Ok(chunk_size) => chunk_size.map(|size| size.saturating_mul(self.params.threshold)),
+			Err(oneshot::Canceled) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?self.params.candidate_hash,
+					"Failed to reach the availability store",
+				);
+				None
+			}
+		}
 	}
 }
 
@@ -614,11 +896,13 @@ async fn launch_interaction(
 	session_info: SessionInfo,
 	receipt: CandidateReceipt,
 	backing_group: Option<GroupIndex>,
+	recovery_strategy: RecoveryStrategy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
 
 	let params = InteractionParams {
+		data_size_estimate: None,
 		validator_authority_keys: session_info.discovery_keys.clone(),
 		validators: session_info.validators.clone(),
 		threshold: recovery_threshold(session_info.validators.len())?,
@@ -626,19 +910,16 @@ async fn launch_interaction(
 		erasure_root: receipt.descriptor.erasure_root,
 	};
 
-	let phase = backing_group
+	let backers = backing_group
+		.filter(|_| recovery_strategy != RecoveryStrategy::ChunksAlways)
 		.and_then(|g| session_info.validator_groups.get(g.0 as usize))
-		.map(|group| InteractionPhase::RequestFromBackers(
-			RequestFromBackersPhase::new(group.clone())
-		))
-		.unwrap_or_else(|| InteractionPhase::RequestChunks(
-			RequestChunksPhase::new(params.validators.len() as _)
-		));
+		.cloned();
 
 	let interaction = Interaction {
 		sender: ctx.sender().clone(),
 		params,
-		phase,
+		recovery_strategy,
+		backers,
 	};
 
 	let (remote, remote_handle) = interaction.run().remote_handle();
@@ -667,6 +948,7 @@ async fn handle_recover(
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	backing_group: Option<GroupIndex>,
+	recovery_strategy: RecoveryStrategy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
@@ -706,6 +988,7 @@ async fn handle_recover(
 				session_info,
 				receipt,
 				backing_group,
+				recovery_strategy,
 				response_sender,
 			).await
 		}
@@ -738,12 +1021,18 @@ async fn query_full_data(
 impl AvailabilityRecoverySubsystem {
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which starts with a fast path to request data from backers.
 	pub fn with_fast_path() -> Self {
-		Self { fast_path: true }
+		Self { recovery_strategy: RecoveryStrategy::BackersFirstAlways }
 	}
 
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks
 	pub fn with_chunks_only() -> Self {
-		Self { fast_path: false }
+		Self { recovery_strategy: RecoveryStrategy::ChunksAlways }
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which starts with a fast path to
+	/// request data from backers, unless the data is estimated to be large.
+	pub fn with_fast_path_if_small() -> Self {
+		Self { recovery_strategy: RecoveryStrategy::BackersFirstIfSizeLower(SMALL_POV_LIMIT) }
 	}
 
 	async fn run(
@@ -775,7 +1064,8 @@ impl AvailabilityRecoverySubsystem {
 										&mut ctx,
 										receipt,
 										session_index,
-										maybe_backing_group.filter(|_| self.fast_path),
+										maybe_backing_group,
+										self.recovery_strategy,
 										response_sender,
 									).await {
 										tracing::warn!(
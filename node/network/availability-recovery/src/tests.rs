@@ -42,33 +42,23 @@ type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityReco
 fn test_harness_fast_path<T: Future<Output = VirtualOverseer>>(
 	test: impl FnOnce(VirtualOverseer) -> T,
 ) {
-	let _ = env_logger::builder()
-		.is_test(true)
-		.filter(
-			Some("polkadot_availability_recovery"),
-			log::LevelFilter::Trace,
-		)
-		.try_init();
-
-	let pool = sp_core::testing::TaskExecutor::new();
-
-	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
-
-	let subsystem = AvailabilityRecoverySubsystem::with_fast_path();
-	let subsystem = subsystem.run(context);
-
-	let test_fut = test(virtual_overseer);
+	test_harness(AvailabilityRecoverySubsystem::with_fast_path(), test)
+}
 
-	futures::pin_mut!(test_fut);
-	futures::pin_mut!(subsystem);
+fn test_harness_chunks_only<T: Future<Output = VirtualOverseer>>(
+	test: impl FnOnce(VirtualOverseer) -> T,
+) {
+	test_harness(AvailabilityRecoverySubsystem::with_chunks_only(), test)
+}
 
-	executor::block_on(future::join(async move {
-		let mut overseer = test_fut.await;
-		overseer_signal(&mut overseer, OverseerSignal::Conclude).await;
-	}, subsystem)).1.unwrap();
+fn test_harness_fast_path_if_small<T: Future<Output = VirtualOverseer>>(
+	test: impl FnOnce(VirtualOverseer) -> T,
+) {
+	test_harness(AvailabilityRecoverySubsystem::with_fast_path_if_small(), test)
 }
 
-fn test_harness_chunks_only<T: Future<Output = VirtualOverseer>>(
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	subsystem: AvailabilityRecoverySubsystem,
 	test: impl FnOnce(VirtualOverseer) -> T,
 ) {
 	let _ = env_logger::builder()
@@ -83,7 +73,6 @@ fn test_harness_chunks_only<T: Future<Output = VirtualOverseer>>(
 
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
-	let subsystem = AvailabilityRecoverySubsystem::with_chunks_only();
 	let subsystem = subsystem.run(context);
 
 	let test_fut = test(virtual_overseer);
@@ -180,6 +169,10 @@ impl TestState {
 		recovery_threshold(self.validators.len()).unwrap()
 	}
 
+	fn systematic_threshold(&self) -> usize {
+		systematic_recovery_threshold(self.validators.len()).unwrap()
+	}
+
 	fn impossibility_threshold(&self) -> usize {
 		self.validators.len() - self.threshold() + 1
 	}
@@ -231,6 +224,21 @@ impl TestState {
 		)
 	}
 
+	async fn respond_to_chunk_size_query(
+		&self,
+		virtual_overseer: &mut VirtualOverseer,
+		chunk_size: Option<usize>,
+	) {
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::QueryChunkSize(_, tx)
+			) => {
+				let _ = tx.send(chunk_size);
+			}
+		)
+	}
+
 	async fn respond_to_query_all_request(
 		&self,
 		virtual_overseer: &mut VirtualOverseer,
@@ -474,6 +482,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 		let candidate_hash = test_state.candidate.hash();
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -506,6 +515,7 @@ fn availability_is_recovered_from_chunks_if_no_group_provided() {
 		test_state.test_runtime_api(&mut virtual_overseer).await;
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -556,6 +566,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 		let candidate_hash = test_state.candidate.hash();
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -588,6 +599,7 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 		test_state.test_runtime_api(&mut virtual_overseer).await;
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -645,6 +657,7 @@ fn bad_merkle_path_leads_to_recovery_error() {
 		test_state.chunks[4].chunk = vec![4; 32];
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -701,6 +714,7 @@ fn wrong_chunk_index_leads_to_recovery_error() {
 		test_state.chunks[4] = test_state.chunks[0].clone();
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -767,6 +781,7 @@ fn invalid_erasure_coding_leads_to_invalid_error() {
 		test_state.test_runtime_api(&mut virtual_overseer).await;
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -822,6 +837,7 @@ fn fast_path_backing_group_recovers() {
 		};
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 
 		test_state.test_full_data_requests(
 			candidate_hash,
@@ -876,6 +892,7 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 		};
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 
 		test_state.test_full_data_requests(
 			candidate_hash,
@@ -898,6 +915,240 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 	});
 }
 
+#[test]
+fn small_pov_is_recovered_from_backers_first() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path_if_small(|mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: smallvec![ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}],
+				deactivated: smallvec![],
+			}),
+		).await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			)
+		).await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(
+			&mut virtual_overseer,
+			Some(test_state.chunks[0].chunk.len()),
+		).await;
+
+		test_state.test_full_data_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			|i| if i == 2 { Has::Yes } else { Has::No },
+		).await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn large_pov_is_recovered_from_systematic_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path_if_small(|mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: smallvec![ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}],
+				deactivated: smallvec![],
+			}),
+		).await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			)
+		).await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, Some(SMALL_POV_LIMIT)).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// Only the systematic chunks are requested.
+		test_state.test_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.systematic_threshold(),
+			|i| {
+				assert!(i < test_state.systematic_threshold());
+				Has::Yes
+			},
+		).await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn systematic_recovery_falls_back_to_regular_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path_if_small(|mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: smallvec![ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}],
+				deactivated: smallvec![],
+			}),
+		).await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			)
+		).await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, Some(SMALL_POV_LIMIT)).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// One of the systematic chunks is missing.
+		let who_has = |i| if i == 1 { Has::No } else { Has::Yes };
+
+		test_state.test_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.systematic_threshold(),
+			who_has,
+		).await;
+
+		// The received systematic chunks are reused, so the store isn't queried again.
+		test_state.test_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.threshold(),
+			who_has,
+		).await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn large_pov_is_recovered_from_systematic_chunks_if_chunks_only() {
+	let test_state = TestState::default();
+
+	test_harness_chunks_only(|mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: smallvec![ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}],
+				deactivated: smallvec![],
+			}),
+		).await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			)
+		).await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		// The size is estimated even though the backers are never asked.
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, Some(SMALL_POV_LIMIT)).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// Only the systematic chunks are requested.
+		test_state.test_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.systematic_threshold(),
+			|i| {
+				assert!(i < test_state.systematic_threshold());
+				Has::Yes
+			},
+		).await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn full_data_timeout_grows_with_size() {
+	assert_eq!(full_data_timeout(0), FULL_DATA_BASE_TIMEOUT);
+	assert_eq!(
+		full_data_timeout(MIN_BACKER_BANDWIDTH),
+		FULL_DATA_BASE_TIMEOUT + Duration::from_secs(1),
+	);
+}
+
 #[test]
 fn task_canceled_when_receivers_dropped() {
 	let test_state = TestState::default();
@@ -976,6 +1227,7 @@ fn chunks_retry_until_all_nodes_respond() {
 		let candidate_hash = test_state.candidate.hash();
 
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
 		test_state.test_chunk_requests(
@@ -1070,6 +1322,7 @@ fn does_not_query_local_validator() {
 
 		test_state.test_runtime_api(&mut virtual_overseer).await;
 		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_chunk_size_query(&mut virtual_overseer, None).await;
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |i| i == 0).await;
 
 		let candidate_hash = test_state.candidate.hash();
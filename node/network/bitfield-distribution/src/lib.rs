@@ -513,7 +513,7 @@ where
 	let _timer = metrics.time_handle_network_msg();
 
 	match bridge_message {
-		NetworkBridgeEvent::PeerConnected(peerid, role, _) => {
+		NetworkBridgeEvent::PeerConnected(peerid, role, _, _) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?peerid,
@@ -826,7 +826,7 @@ mod test {
 	use std::sync::Arc;
 	use std::time::Duration;
 	use assert_matches::assert_matches;
-	use polkadot_node_network_protocol::{view, ObservedRole, our_view};
+	use polkadot_node_network_protocol::{view, ObservedRole, our_view, peer_set::VALIDATION_PROTOCOL_V2};
 	use polkadot_subsystem::jaeger;
 
 	macro_rules! launch {
@@ -1321,7 +1321,12 @@ mod test {
 				&mut ctx,
 				&mut state,
 				&Default::default(),
-				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer_b.clone(),
+					ObservedRole::Full,
+					VALIDATION_PROTOCOL_V2,
+					None,
+				),
 			));
 
 			// make peer b interested
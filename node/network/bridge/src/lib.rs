@@ -38,8 +38,8 @@ use polkadot_subsystem::messages::{
 };
 use polkadot_primitives::v1::{Hash, BlockNumber};
 use polkadot_node_network_protocol::{
-	PeerId, peer_set::PeerSet, View, v1 as protocol_v1, OurView, UnifiedReputationChange as Rep,
-	ObservedRole,
+	PeerId, peer_set::{PeerSet, ProtocolVersion, PROTOCOL_V1}, View, v1 as protocol_v1,
+	v2 as protocol_v2, OurView, UnifiedReputationChange as Rep, ObservedRole,
 };
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 
@@ -49,6 +49,7 @@ use polkadot_node_subsystem_util::metrics::{self, prometheus};
 pub use polkadot_node_network_protocol::peer_set::{peer_sets_info, IsAuthority};
 
 use std::collections::{HashMap, hash_map};
+use std::convert::TryFrom;
 use std::iter::ExactSizeIterator;
 use std::sync::Arc;
 
@@ -319,6 +320,8 @@ impl<Net, AD, Context> Subsystem<Context> for NetworkBridge<Net, AD>
 struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
+	/// The version of the peer-set's protocol the peer speaks.
+	version: ProtocolVersion,
 }
 
 #[derive(Debug)]
@@ -467,6 +470,21 @@ where
 							&metrics,
 						).await?
 					}
+					NetworkBridgeMessage::SendValidationMessageV2(peers, msg) => {
+						tracing::trace!(
+							target: LOG_TARGET,
+							action = "SendValidationMessageV2",
+							num_messages = 1,
+						);
+
+						send_validation_message_v2(
+							&mut network_service,
+							&shared,
+							peers,
+							msg,
+							&metrics,
+						).await?
+					}
 					NetworkBridgeMessage::SendValidationMessages(msgs) => {
 						tracing::trace!(
 							target: LOG_TARGET,
@@ -578,17 +596,39 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 				Some(NetworkEvent::Dht(_))
 				| Some(NetworkEvent::SyncConnected { .. })
 				| Some(NetworkEvent::SyncDisconnected { .. }) => {}
-				Some(NetworkEvent::NotificationStreamOpened { remote: peer, protocol, role, .. }) => {
+				Some(NetworkEvent::NotificationStreamOpened {
+					remote: peer,
+					protocol,
+					role,
+					negotiated_fallback,
+				}) => {
 					let role = ObservedRole::from(role);
 					let peer_set = match PeerSet::try_from_protocol_name(&protocol) {
 						None => continue,
 						Some(peer_set) => peer_set,
 					};
 
+					let version = match negotiated_fallback {
+						None => peer_set.get_main_version(),
+						Some(fallback) => match PeerSet::try_get_protocol(&fallback) {
+							Some((fallback_set, version)) if fallback_set == peer_set => version,
+							_ => {
+								tracing::debug!(
+									target: LOG_TARGET,
+									?peer,
+									?fallback,
+									"Unknown fallback protocol negotiated, ignoring peer",
+								);
+								continue
+							}
+						},
+					};
+
 					tracing::debug!(
 						target: LOG_TARGET,
 						action = "PeerConnected",
 						peer_set = ?peer_set,
+						version,
 						peer = ?peer,
 						role = ?role
 					);
@@ -603,7 +643,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						match peer_map.entry(peer.clone()) {
 							hash_map::Entry::Occupied(_) => continue,
 							hash_map::Entry::Vacant(vacant) => {
-								vacant.insert(PeerData { view: View::default() });
+								vacant.insert(PeerData { view: View::default(), version });
 							}
 						}
 
@@ -621,7 +661,12 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						PeerSet::Validation => {
 							dispatch_validation_events_to_all(
 								vec![
-									NetworkBridgeEvent::PeerConnected(peer.clone(), role, maybe_authority),
+									NetworkBridgeEvent::PeerConnected(
+										peer.clone(),
+										role,
+										version,
+										maybe_authority,
+									),
 									NetworkBridgeEvent::PeerViewChange(
 										peer.clone(),
 										View::default(),
@@ -643,7 +688,12 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						PeerSet::Collation => {
 							dispatch_collation_events_to_all(
 								vec![
-									NetworkBridgeEvent::PeerConnected(peer.clone(), role, maybe_authority),
+									NetworkBridgeEvent::PeerConnected(
+										peer.clone(),
+										role,
+										version,
+										maybe_authority,
+									),
 									NetworkBridgeEvent::PeerViewChange(
 										peer.clone(),
 										View::default(),
@@ -706,13 +756,14 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					}
 				}
 				Some(NetworkEvent::NotificationsReceived { remote, messages }) => {
+					let v_version = shared.0.lock().validation_peers.get(&remote).map(|p| p.version);
 					let v_messages: Result<Vec<_>, _> = messages
 						.iter()
 						.filter(|(protocol, _)| {
 							protocol == &PeerSet::Validation.into_protocol_name()
 						})
 						.map(|(_, msg_bytes)| {
-							WireMessage::decode(&mut msg_bytes.as_ref())
+							decode_validation_message(v_version, &mut msg_bytes.as_ref())
 								.map(|m| (m, msg_bytes.len()))
 						})
 						.collect();
@@ -1058,6 +1109,68 @@ async fn send_validation_message<I>(
 	send_message(net, peers, PeerSet::Validation, message, metrics).await
 }
 
+/// Send a message of the v2 validation protocol.
+///
+/// Peers which only speak v1 of the protocol receive the v1 encoding of the message. Messages
+/// which have no v1 equivalent are not sent to them.
+async fn send_validation_message_v2(
+	net: &mut impl Network,
+	shared: &Shared,
+	peers: Vec<PeerId>,
+	message: protocol_v2::ValidationProtocol,
+	metrics: &Metrics,
+) -> SubsystemResult<()> {
+	let (v1_peers, v2_peers): (Vec<_>, Vec<_>) = {
+		let shared = shared.0.lock();
+		peers.into_iter().partition(|peer| {
+			shared.validation_peers.get(peer).map_or(false, |p| p.version == PROTOCOL_V1)
+		})
+	};
+
+	if !v1_peers.is_empty() {
+		match protocol_v1::ValidationProtocol::try_from(message.clone()) {
+			Ok(v1_message) => send_validation_message(
+				net,
+				v1_peers,
+				WireMessage::ProtocolMessage(v1_message),
+				metrics,
+			).await?,
+			Err(_) => tracing::debug!(
+				target: LOG_TARGET,
+				num_peers = v1_peers.len(),
+				"Not sending message without a v1 equivalent to v1 peers",
+			),
+		}
+	}
+
+	if !v2_peers.is_empty() {
+		send_message(
+			net,
+			v2_peers,
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(message),
+			metrics,
+		).await?;
+	}
+
+	Ok(())
+}
+
+/// Decode a message received on the validation peer-set from a peer speaking the given version
+/// of the protocol.
+fn decode_validation_message(
+	version: Option<ProtocolVersion>,
+	bytes: &mut &[u8],
+) -> Result<WireMessage<protocol_v2::ValidationProtocol>, parity_scale_codec::Error> {
+	match version {
+		Some(PROTOCOL_V1) => Ok(match WireMessage::<protocol_v1::ValidationProtocol>::decode(bytes)? {
+			WireMessage::ProtocolMessage(message) => WireMessage::ProtocolMessage(message.into()),
+			WireMessage::ViewUpdate(view) => WireMessage::ViewUpdate(view),
+		}),
+		_ => WireMessage::decode(bytes),
+	}
+}
+
 async fn send_collation_message<I>(
 	net: &mut impl Network,
 	peers: I,
@@ -1073,7 +1186,7 @@ async fn send_collation_message<I>(
 
 
 async fn dispatch_validation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 	ctx: &mut impl SubsystemSender
 ) {
 	dispatch_validation_events_to_all(std::iter::once(event), ctx).await
@@ -1087,7 +1200,7 @@ async fn dispatch_collation_event_to_all(
 }
 
 fn dispatch_validation_event_to_all_unbounded(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 	ctx: &mut impl SubsystemSender
 ) {
 	for msg in AllMessages::dispatch_iter(event) {
@@ -1109,7 +1222,7 @@ async fn dispatch_validation_events_to_all<I>(
 	ctx: &mut impl SubsystemSender
 )
 	where
-		I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::ValidationProtocol>>,
+		I: IntoIterator<Item = NetworkBridgeEvent<protocol_v2::ValidationProtocol>>,
 		I::IntoIter: Send,
 {
 	ctx.send_messages(events.into_iter().flat_map(AllMessages::dispatch_iter)).await
@@ -1168,6 +1281,9 @@ mod tests {
 	use sp_keyring::Sr25519Keyring;
 	use polkadot_primitives::v1::AuthorityDiscoveryId;
 	use polkadot_node_network_protocol::{ObservedRole, request_response::request::Requests};
+	use polkadot_node_network_protocol::peer_set::{
+		ProtocolVersion, COLLATION_PROTOCOL_V2, PROTOCOL_V1, VALIDATION_PROTOCOL_V2,
+	};
 
 	use crate::network::{Network, NetworkAction};
 	use crate::validator_discovery::AuthorityDiscovery;
@@ -1275,6 +1391,23 @@ mod tests {
 			}).await;
 		}
 
+		async fn connect_peer_with_version(
+			&mut self,
+			peer: PeerId,
+			peer_set: PeerSet,
+			version: ProtocolVersion,
+			role: ObservedRole,
+		) {
+			self.send_network_event(NetworkEvent::NotificationStreamOpened {
+				remote: peer,
+				protocol: peer_set.into_protocol_name(),
+				negotiated_fallback: Some(
+					peer_set.get_versioned_protocol_name(version).expect("version is supported; qed"),
+				),
+				role: role.into(),
+			}).await;
+		}
+
 		async fn disconnect_peer(&mut self, peer: PeerId, peer_set: PeerSet) {
 			self.send_network_event(NetworkEvent::NotificationStreamClosed {
 				remote: peer,
@@ -1410,7 +1543,7 @@ mod tests {
 	}
 
 	async fn assert_sends_validation_event_to_all(
-		event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+		event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 		virtual_overseer: &mut TestSubsystemContextHandle<NetworkBridgeMessage>,
 	) {
 		// Ordering must match the enum variant order
@@ -1418,7 +1551,7 @@ mod tests {
 		assert_matches!(
 			virtual_overseer.recv().await,
 			AllMessages::StatementDistribution(
-				StatementDistributionMessage::NetworkBridgeUpdateV1(e)
+				StatementDistributionMessage::NetworkBridgeUpdateV2(e)
 			) if e == event.focus().expect("could not focus message")
 		);
 
@@ -1794,7 +1927,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -1820,6 +1958,159 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn peer_on_fallback_protocol_connects_with_its_version() {
+		test_harness(done_syncing_oracle(), |test_harness| async move {
+			let TestHarness {
+				mut network_handle,
+				mut virtual_overseer,
+			} = test_harness;
+
+			let peer = PeerId::random();
+
+			network_handle.connect_peer_with_version(
+				peer.clone(),
+				PeerSet::Validation,
+				PROTOCOL_V1,
+				ObservedRole::Full,
+			).await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::StatementDistribution(
+					StatementDistributionMessage::StatementFetchingReceiver(_)
+				)
+			);
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					PROTOCOL_V1,
+					None,
+				),
+				&mut virtual_overseer,
+			).await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			).await;
+			virtual_overseer
+		});
+	}
+
+	#[test]
+	fn v2_messages_are_only_sent_to_v1_peers_if_compatible() {
+		test_harness(done_syncing_oracle(), |test_harness| async move {
+			let TestHarness {
+				mut network_handle,
+				mut virtual_overseer,
+			} = test_harness;
+
+			let peer_v1 = PeerId::random();
+			let peer_v2 = PeerId::random();
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::StatementDistribution(
+					StatementDistributionMessage::StatementFetchingReceiver(_)
+				)
+			);
+
+			network_handle.connect_peer_with_version(
+				peer_v1.clone(),
+				PeerSet::Validation,
+				PROTOCOL_V1,
+				ObservedRole::Full,
+			).await;
+			network_handle.connect_peer(
+				peer_v2.clone(),
+				PeerSet::Validation,
+				ObservedRole::Full,
+			).await;
+
+			for (peer, version) in vec![(&peer_v1, PROTOCOL_V1), (&peer_v2, VALIDATION_PROTOCOL_V2)] {
+				assert_sends_validation_event_to_all(
+					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, version, None),
+					&mut virtual_overseer,
+				).await;
+
+				assert_sends_validation_event_to_all(
+					NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+					&mut virtual_overseer,
+				).await;
+			}
+
+			// consume our view updates sent to the peers.
+			{
+				let _view_updates = network_handle.next_network_actions(2).await;
+			}
+
+			// a manifest has no v1 equivalent.
+			let manifest = protocol_v2::ValidationProtocol::StatementDistribution(
+				protocol_v2::StatementDistributionMessage::Manifest(
+					protocol_v2::CandidateManifest {
+						relay_parent: Hash::repeat_byte(1),
+						candidate_hash: Default::default(),
+					}
+				),
+			);
+
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessageV2(
+					vec![peer_v1.clone(), peer_v2.clone()],
+					manifest.clone(),
+				)
+			}).await;
+
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer_v2.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(manifest).encode(),
+				)
+			);
+
+			// messages shared with v1 are sent to both, in the encoding of the peer's version.
+			let approval_distribution_message = protocol_v1::ApprovalDistributionMessage::Approvals(
+				Vec::new()
+			);
+
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessageV2(
+					vec![peer_v1.clone(), peer_v2.clone()],
+					protocol_v2::ValidationProtocol::ApprovalDistribution(
+						approval_distribution_message.clone(),
+					),
+				)
+			}).await;
+
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer_v1.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(protocol_v1::ValidationProtocol::ApprovalDistribution(
+						approval_distribution_message.clone(),
+					)).encode(),
+				)
+			);
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer_v2.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(protocol_v2::ValidationProtocol::ApprovalDistribution(
+						approval_distribution_message,
+					)).encode(),
+				)
+			);
+			virtual_overseer
+		});
+	}
+
 	#[test]
 	fn peer_messages_sent_via_overseer() {
 		test_harness(done_syncing_oracle(), |test_harness| async move {
@@ -1846,7 +2137,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -1918,7 +2214,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -1930,7 +2231,12 @@ mod tests {
 
 			{
 				assert_sends_collation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						COLLATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2003,7 +2309,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_a.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2015,7 +2326,12 @@ mod tests {
 
 			{
 				assert_sends_collation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer_b.clone(),
+						ObservedRole::Full,
+						COLLATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2098,7 +2414,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2110,7 +2431,12 @@ mod tests {
 
 			{
 				assert_sends_collation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						COLLATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2259,7 +2585,12 @@ mod tests {
 			// bridge will inform about all connected peers.
 			{
 				assert_sends_validation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						VALIDATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2271,7 +2602,12 @@ mod tests {
 
 			{
 				assert_sends_collation_event_to_all(
-					NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+					NetworkBridgeEvent::PeerConnected(
+						peer.clone(),
+						ObservedRole::Full,
+						COLLATION_PROTOCOL_V2,
+						None,
+					),
 					&mut virtual_overseer,
 				).await;
 
@@ -2373,6 +2709,10 @@ mod tests {
 				AllMessages::ApprovalVoting(_) => unreachable!("Not interested in network events"),
 				AllMessages::ApprovalDistribution(_) => { cnt += 1; }
 				AllMessages::GossipSupport(_) => unreachable!("Not interested in network events"),
+				AllMessages::DisputeCoordinator(_) => unreachable!("Not interested in network events"),
+				AllMessages::DisputeDistribution(_) => unreachable!("Not interested in network events"),
+				AllMessages::ChainSelection(_) => unreachable!("Not interested in network events"),
+				AllMessages::PvfChecker(_) => unreachable!("Not interested in network events"),
 				// Add variants here as needed, `{ cnt += 1; }` for those that need to be
 				// notified, `unreachable!()` for those that should not.
 			}
@@ -161,6 +161,11 @@ fn multiplex_single(
 			decode_with_peer::<v1::CollationFetchingRequest>(peer, payload)?,
 			pending_response,
 		)),
+		Protocol::CollationFetchingV2 => From::from(IncomingRequest::new(
+			peer,
+			decode_with_peer::<v1::CollationFetchingV2Request>(peer, payload)?,
+			pending_response,
+		)),
 		Protocol::PoVFetching => From::from(IncomingRequest::new(
 			peer,
 			decode_with_peer::<v1::PoVFetchingRequest>(peer, payload)?,
@@ -174,6 +179,11 @@ fn multiplex_single(
 		Protocol::StatementFetching => {
 			panic!("Statement fetching requests are handled directly. qed.");
 		}
+		Protocol::DisputeSending => From::from(IncomingRequest::new(
+			peer,
+			decode_with_peer::<v1::DisputeRequest>(peer, payload)?,
+			pending_response,
+		)),
 	};
 	Ok(r)
 }
@@ -27,10 +27,12 @@ use polkadot_subsystem::{
 	},
 };
 use polkadot_node_network_protocol::{
-	OurView, PeerId, View, peer_set::PeerSet,
+	OurView, PeerId, View,
+	peer_set::{PeerSet, ProtocolVersion, COLLATION_PROTOCOL_V2, PROTOCOL_V1},
 	request_response::{
 		IncomingRequest,
-		v1::{CollationFetchingRequest, CollationFetchingResponse},
+		request::IsRequest,
+		v1::CollationFetchingResponse,
 	},
 	v1 as protocol_v1,
 	UnifiedReputationChange as Rep,
@@ -42,7 +44,7 @@ use polkadot_node_subsystem_util::{
 use polkadot_node_primitives::{SignedFullStatement, Statement, PoV};
 
 use crate::error::{Fatal, NonFatal, log_error};
-use super::{LOG_TARGET,  Result};
+use super::{LOG_TARGET, MAX_CANDIDATES_PER_RELAY_PARENT, Result};
 
 const COST_UNEXPECTED_MESSAGE: Rep = Rep::CostMinor("An unexpected message");
 
@@ -113,29 +115,40 @@ impl metrics::Metrics for Metrics {
 ///
 /// This structure is responsible for keeping track of which validators belong to a certain group for a para. It also
 /// stores a mapping from [`PeerId`] to [`ValidatorId`] as we learn about it over the lifetime of this object. Besides
-/// that it also keeps track to which validators we advertised our collation.
+/// that it also keeps track to which validators we advertised our collations.
 #[derive(Debug)]
 struct ValidatorGroup {
 	/// All [`AuthorityDiscoveryId`]'s that are assigned to us in this group.
 	discovery_ids: HashSet<AuthorityDiscoveryId>,
-	/// All [`ValidatorId`]'s of the current group to that we advertised our collation.
-	advertised_to: HashSet<AuthorityDiscoveryId>,
+	/// All [`ValidatorId`]'s of the current group to that we advertised a collation, per candidate.
+	advertised_to: HashMap<CandidateHash, HashSet<AuthorityDiscoveryId>>,
 }
 
 impl ValidatorGroup {
-	/// Returns `true` if we should advertise our collation to the given peer.
-	fn should_advertise_to(&self, peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>, peer: &PeerId)
-		-> bool {
+	/// Returns `true` if we should advertise the given collation to the given peer.
+	fn should_advertise_to(
+		&self,
+		peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>,
+		peer: &PeerId,
+		candidate_hash: &CandidateHash,
+	) -> bool {
 		match peer_ids.get(peer) {
-			Some(discovery_id) => !self.advertised_to.contains(discovery_id),
+			Some(discovery_id) => !self.advertised_to
+				.get(candidate_hash)
+				.map_or(false, |advertised| advertised.contains(discovery_id)),
 			None => false,
 		}
 	}
 
-	/// Should be called after we advertised our collation to the given `peer` to keep track of it.
-	fn advertised_to_peer(&mut self, peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>, peer: &PeerId) {
+	/// Should be called after we advertised a collation to the given `peer` to keep track of it.
+	fn advertised_to_peer(
+		&mut self,
+		peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>,
+		peer: &PeerId,
+		candidate_hash: CandidateHash,
+	) {
 		if let Some(validator_id) = peer_ids.get(peer) {
-			self.advertised_to.insert(validator_id.clone());
+			self.advertised_to.entry(candidate_hash).or_default().insert(validator_id.clone());
 		}
 	}
 }
@@ -144,7 +157,7 @@ impl From<HashSet<AuthorityDiscoveryId>> for ValidatorGroup {
 	fn from(discovery_ids: HashSet<AuthorityDiscoveryId>) -> Self {
 		Self {
 			discovery_ids,
-			advertised_to: HashSet::new(),
+			advertised_to: HashMap::new(),
 		}
 	}
 }
@@ -178,6 +191,8 @@ impl CollationStatus {
 /// A collation built by the collator.
 struct Collation {
 	receipt: CandidateReceipt,
+	/// Hash of the parent head data the collation builds on.
+	parent_head_data_hash: Hash,
 	pov: PoV,
 	status: CollationStatus,
 }
@@ -205,8 +220,9 @@ struct State {
 
 	/// Possessed collations.
 	///
-	/// We will keep up to one local collation per relay-parent.
-	collations: HashMap<Hash, Collation>,
+	/// We will keep up to [`MAX_CANDIDATES_PER_RELAY_PARENT`] local collations per relay-parent,
+	/// in the order they were distributed.
+	collations: HashMap<Hash, Vec<Collation>>,
 
 	/// The result senders per collation.
 	collation_result_senders: HashMap<CandidateHash, oneshot::Sender<SignedFullStatement>>,
@@ -217,6 +233,9 @@ struct State {
 	/// The mapping from [`PeerId`] to [`ValidatorId`]. This is filled over time as we learn the [`PeerId`]'s by `PeerConnected` events.
 	peer_ids: HashMap<PeerId, AuthorityDiscoveryId>,
 
+	/// The collation protocol version negotiated with each connected peer.
+	peer_versions: HashMap<PeerId, ProtocolVersion>,
+
 	/// Metrics.
 	metrics: Metrics,
 }
@@ -237,6 +256,7 @@ impl State {
 			collation_result_senders: Default::default(),
 			our_validators_groups: Default::default(),
 			peer_ids: Default::default(),
+			peer_versions: Default::default(),
 		}
 	}
 
@@ -254,6 +274,8 @@ impl State {
 ///
 /// Figure out the core our para is assigned to and the relevant validators.
 /// Issue a connection request to these validators.
+/// Further collations on the same relay-parent are kept alongside the first one, up to
+/// [`MAX_CANDIDATES_PER_RELAY_PARENT`], and advertised to the same validators.
 /// If the para is not scheduled or next up on any core, at the relay-parent,
 /// or the relay-parent isn't in the active-leaves set, we ignore the message
 /// as it must be invalid in that case - although this indicates a logic error
@@ -264,10 +286,12 @@ async fn distribute_collation(
 	state: &mut State,
 	id: ParaId,
 	receipt: CandidateReceipt,
+	parent_head_data_hash: Hash,
 	pov: PoV,
 	result_sender: Option<oneshot::Sender<SignedFullStatement>>,
 ) -> Result<()> {
 	let relay_parent = receipt.descriptor.relay_parent;
+	let candidate_hash = receipt.hash();
 
 	// This collation is not in the active-leaves set.
 	if !state.view.contains(&relay_parent) {
@@ -280,71 +304,93 @@ async fn distribute_collation(
 		return Ok(());
 	}
 
-	// We have already seen collation for this relay parent.
-	if state.collations.contains_key(&relay_parent) {
-		return Ok(());
-	}
+	if let Some(collations) = state.collations.get(&relay_parent) {
+		// We have already seen this collation.
+		if collations.iter().any(|c| c.receipt.hash() == candidate_hash) {
+			return Ok(());
+		}
 
-	// Determine which core the para collated-on is assigned to.
-	// If it is not scheduled then ignore the message.
-	let (our_core, num_cores) = match determine_core(ctx, id, relay_parent).await? {
-		Some(core) => core,
-		None => {
-			tracing::warn!(
+		if collations.len() >= MAX_CANDIDATES_PER_RELAY_PARENT {
+			tracing::debug!(
 				target: LOG_TARGET,
-				para_id = %id,
 				?relay_parent,
-				"looks like no core is assigned to {} at {}", id, relay_parent,
+				?candidate_hash,
+				"Already holding the maximum number of collations for this relay parent.",
 			);
 
-			return Ok(())
+			return Ok(());
 		}
-	};
+	}
 
-	// Determine the group on that core and the next group on that core.
-	let (current_validators, next_validators) =
-		determine_our_validators(ctx, runtime, our_core, num_cores, relay_parent,).await?;
+	// Our validators only need to be determined and connected to for the first collation
+	// on a relay parent.
+	if !state.our_validators_groups.contains_key(&relay_parent) {
+		// Determine which core the para collated-on is assigned to.
+		// If it is not scheduled then ignore the message.
+		let (our_core, num_cores) = match determine_core(ctx, id, relay_parent).await? {
+			Some(core) => core,
+			None => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					para_id = %id,
+					?relay_parent,
+					"looks like no core is assigned to {} at {}", id, relay_parent,
+				);
 
-	if current_validators.validators.is_empty() && next_validators.validators.is_empty() {
-		tracing::warn!(
+				return Ok(())
+			}
+		};
+
+		// Determine the group on that core and the next group on that core.
+		let (current_validators, next_validators) =
+			determine_our_validators(ctx, runtime, our_core, num_cores, relay_parent,).await?;
+
+		if current_validators.validators.is_empty() && next_validators.validators.is_empty() {
+			tracing::warn!(
+				target: LOG_TARGET,
+				core = ?our_core,
+				"there are no validators assigned to core",
+			);
+
+			return Ok(());
+		}
+
+		tracing::debug!(
 			target: LOG_TARGET,
+			para_id = %id,
+			relay_parent = %relay_parent,
+			?candidate_hash,
+			pov_hash = ?pov.hash(),
 			core = ?our_core,
-			"there are no validators assigned to core",
+			?current_validators,
+			?next_validators,
+			"Accepted collation, connecting to validators."
 		);
 
-		return Ok(());
-	}
+		let validator_group: HashSet<_> = current_validators.validators.iter().map(Clone::clone).collect();
+
+		// Issue a discovery request for the validators of the current group and the next group:
+		connect_to_validators(
+			ctx,
+			current_validators.validators
+				.into_iter()
+				.chain(next_validators.validators.into_iter())
+				.collect(),
+		).await;
 
-	tracing::debug!(
-		target: LOG_TARGET,
-		para_id = %id,
-		relay_parent = %relay_parent,
-		candidate_hash = ?receipt.hash(),
-		pov_hash = ?pov.hash(),
-		core = ?our_core,
-		?current_validators,
-		?next_validators,
-		"Accepted collation, connecting to validators."
-	);
-
-	let validator_group: HashSet<_> = current_validators.validators.iter().map(Clone::clone).collect();
-
-	// Issue a discovery request for the validators of the current group and the next group:
-	connect_to_validators(
-		ctx,
-		current_validators.validators
-			.into_iter()
-			.chain(next_validators.validators.into_iter())
-			.collect(),
-	).await;
-
-	state.our_validators_groups.insert(relay_parent, validator_group.into());
+		state.our_validators_groups.insert(relay_parent, validator_group.into());
+	}
 
 	if let Some(result_sender) = result_sender {
-		state.collation_result_senders.insert(receipt.hash(), result_sender);
+		state.collation_result_senders.insert(candidate_hash, result_sender);
 	}
 
-	state.collations.insert(relay_parent, Collation { receipt, pov, status: CollationStatus::Created });
+	state.collations.entry(relay_parent).or_default().push(Collation {
+		receipt,
+		parent_head_data_hash,
+		pov,
+		status: CollationStatus::Created,
+	});
 
 	// Make sure already connected peers get collations:
 	for peer_id in state.peers_interested_in_leaf(&relay_parent) {
@@ -458,23 +504,22 @@ async fn connect_to_validators(
 	})).await;
 }
 
-/// Advertise collation to the given `peer`.
+/// Advertise our collations to the given `peer`.
+///
+/// This will only advertise collations if there exist any for the given `relay_parent` and the given `peer` is
+/// set as validator for our para at the given `relay_parent`. Each collation is advertised at most once per validator.
 ///
-/// This will only advertise a collation if there exists one for the given `relay_parent` and the given `peer` is
-/// set as validator for our para at the given `relay_parent`.
+/// Peers on the first version of the collation protocol can only fetch a single collation per relay parent, so
+/// they only get the first one advertised, by relay parent.
 async fn advertise_collation(
 	ctx: &mut impl SubsystemContext,
 	state: &mut State,
 	relay_parent: Hash,
 	peer: PeerId,
 ) {
-	let should_advertise = state.our_validators_groups
-		.get(&relay_parent)
-		.map(|g| g.should_advertise_to(&state.peer_ids, &peer))
-		.unwrap_or(false);
-
-	match (state.collations.get_mut(&relay_parent), should_advertise) {
-		(None, _) => {
+	let collations = match state.collations.get_mut(&relay_parent) {
+		Some(collations) => collations,
+		None => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?relay_parent,
@@ -482,43 +527,63 @@ async fn advertise_collation(
 				"No collation to advertise.",
 			);
 			return
-		},
-		(_, false) => {
+		}
+	};
+
+	let peer_version = state.peer_versions.get(&peer).copied().unwrap_or(PROTOCOL_V1);
+	let advertised_collations = if peer_version < COLLATION_PROTOCOL_V2 { 1 } else { collations.len() };
+
+	for collation in collations.iter_mut().take(advertised_collations) {
+		let candidate_hash = collation.receipt.hash();
+
+		let should_advertise = state.our_validators_groups
+			.get(&relay_parent)
+			.map(|g| g.should_advertise_to(&state.peer_ids, &peer, &candidate_hash))
+			.unwrap_or(false);
+
+		if !should_advertise {
 			tracing::debug!(
 				target: LOG_TARGET,
 				?relay_parent,
+				?candidate_hash,
 				peer_id = %peer,
 				"Not advertising collation as we already advertised it to this validator.",
 			);
-			return
+			continue
 		}
-		(Some(collation), true) => {
-			tracing::debug!(
-				target: LOG_TARGET,
-				?relay_parent,
-				peer_id = %peer,
-				"Advertising collation.",
-			);
-			collation.status.advance_to_advertised()
-		},
-	}
 
-	let wire_message = protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
-		relay_parent,
-	);
+		tracing::debug!(
+			target: LOG_TARGET,
+			?relay_parent,
+			?candidate_hash,
+			peer_id = %peer,
+			"Advertising collation.",
+		);
+		collation.status.advance_to_advertised();
 
-	ctx.send_message(AllMessages::NetworkBridge(
-		NetworkBridgeMessage::SendCollationMessage(
-			vec![peer.clone()],
-			protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
-		)
-	)).await;
+		let wire_message = if peer_version < COLLATION_PROTOCOL_V2 {
+			protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent)
+		} else {
+			protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+				relay_parent,
+				candidate_hash,
+				collation.parent_head_data_hash,
+			)
+		};
 
-	if let Some(validators) = state.our_validators_groups.get_mut(&relay_parent) {
-		validators.advertised_to_peer(&state.peer_ids, &peer);
-	}
+		ctx.send_message(AllMessages::NetworkBridge(
+			NetworkBridgeMessage::SendCollationMessage(
+				vec![peer.clone()],
+				protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+			)
+		)).await;
+
+		if let Some(validators) = state.our_validators_groups.get_mut(&relay_parent) {
+			validators.advertised_to_peer(&state.peer_ids, &peer, candidate_hash);
+		}
 
-	state.metrics.on_advertisment_made();
+		state.metrics.on_advertisment_made();
+	}
 }
 
 /// The main incoming message dispatching switch.
@@ -536,7 +601,7 @@ async fn process_msg(
 		CollateOn(id) => {
 			state.collating_on = Some(id);
 		}
-		DistributeCollation(receipt, pov, result_sender) => {
+		DistributeCollation(receipt, parent_head_data_hash, pov, result_sender) => {
 			let _span1 = state.span_per_relay_parent
 				.get(&receipt.descriptor.relay_parent).map(|s| s.child("distributing-collation"));
 			let _span2 = jaeger::Span::new(&pov, "distributing-collation");
@@ -552,7 +617,16 @@ async fn process_msg(
 					);
 				}
 				Some(id) => {
-					distribute_collation(ctx, runtime, state, id, receipt, pov, result_sender).await?;
+					distribute_collation(
+						ctx,
+						runtime,
+						state,
+						id,
+						receipt,
+						parent_head_data_hash,
+						pov,
+						result_sender,
+					).await?;
 				}
 				None => {
 					tracing::warn!(
@@ -585,40 +659,32 @@ async fn process_msg(
 		},
 		CollationFetchingRequest(incoming) => {
 			let _span = state.span_per_relay_parent.get(&incoming.payload.relay_parent).map(|s| s.child("request-collation"));
-			match state.collating_on {
-				Some(our_para_id) => {
-					if our_para_id == incoming.payload.para_id {
-						let (receipt, pov) = if let Some(collation) = state.collations.get_mut(&incoming.payload.relay_parent) {
-							collation.status.advance_to_requested();
-							(collation.receipt.clone(), collation.pov.clone())
-						} else {
-							tracing::warn!(
-								target: LOG_TARGET,
-								relay_parent = %incoming.payload.relay_parent,
-								"received a `RequestCollation` for a relay parent we don't have collation stored.",
-							);
-
-							return Ok(());
-						};
-
-						let _span = _span.as_ref().map(|s| s.child("sending"));
-						send_collation(state, incoming, receipt, pov).await;
-					} else {
-						tracing::warn!(
-							target: LOG_TARGET,
-							for_para_id = %incoming.payload.para_id,
-							our_para_id = %our_para_id,
-							"received a `CollationFetchingRequest` for unexpected para_id",
-						);
-					}
-				}
-				None => {
-					tracing::warn!(
-						target: LOG_TARGET,
-						for_para_id = %incoming.payload.para_id,
-						"received a `RequestCollation` while not collating on any para",
-					);
-				}
+			// Requests of the first protocol version can't name a candidate, so they are served
+			// the first collation we distributed on the relay parent.
+			let collation = requested_collation(
+				state,
+				incoming.payload.relay_parent,
+				incoming.payload.para_id,
+				None,
+			);
+
+			if let Some((receipt, pov)) = collation {
+				let _span = _span.as_ref().map(|s| s.child("sending"));
+				send_collation(state, incoming, receipt, pov).await;
+			}
+		}
+		CollationFetchingV2Request(incoming) => {
+			let _span = state.span_per_relay_parent.get(&incoming.payload.relay_parent).map(|s| s.child("request-collation"));
+			let collation = requested_collation(
+				state,
+				incoming.payload.relay_parent,
+				incoming.payload.para_id,
+				Some(incoming.payload.candidate_hash),
+			);
+
+			if let Some((receipt, pov)) = collation {
+				let _span = _span.as_ref().map(|s| s.child("sending"));
+				send_collation(state, incoming, receipt, pov).await;
 			}
 		}
 		_ => {},
@@ -627,13 +693,71 @@ async fn process_msg(
 	Ok(())
 }
 
+/// Look up the collation requested by a validator and mark it as requested.
+///
+/// If no `candidate_hash` is given, the first collation on the relay parent is returned.
+fn requested_collation(
+	state: &mut State,
+	relay_parent: Hash,
+	para_id: ParaId,
+	candidate_hash: Option<CandidateHash>,
+) -> Option<(CandidateReceipt, PoV)> {
+	match state.collating_on {
+		Some(our_para_id) if our_para_id == para_id => {},
+		Some(our_para_id) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				for_para_id = %para_id,
+				our_para_id = %our_para_id,
+				"received a `CollationFetchingRequest` for unexpected para_id",
+			);
+
+			return None
+		}
+		None => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				for_para_id = %para_id,
+				"received a `RequestCollation` while not collating on any para",
+			);
+
+			return None
+		}
+	}
+
+	let collation = state.collations.get_mut(&relay_parent).and_then(|collations| match candidate_hash {
+		Some(candidate_hash) => collations.iter_mut().find(|c| c.receipt.hash() == candidate_hash),
+		None => collations.first_mut(),
+	});
+
+	match collation {
+		Some(collation) => {
+			collation.status.advance_to_requested();
+			Some((collation.receipt.clone(), collation.pov.clone()))
+		}
+		None => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				%relay_parent,
+				?candidate_hash,
+				"received a `RequestCollation` for a collation we don't have stored.",
+			);
+
+			None
+		}
+	}
+}
+
 /// Issue a response to a previously requested collation.
-async fn send_collation(
+async fn send_collation<Req>(
 	state: &mut State,
-	request: IncomingRequest<CollationFetchingRequest>,
+	request: IncomingRequest<Req>,
 	receipt: CandidateReceipt,
 	pov: PoV,
-) {
+)
+where
+	Req: IsRequest<Response = CollationFetchingResponse>,
+{
 	if let Err(_) = request.send_response(CollationFetchingResponse::Collation(receipt, pov)) {
 		tracing::warn!(
 			target: LOG_TARGET,
@@ -666,7 +790,7 @@ async fn handle_incoming_peer_message(
 				NetworkBridgeMessage::DisconnectPeer(origin, PeerSet::Collation).into()
 			).await;
 		}
-		AdvertiseCollation(_) => {
+		AdvertiseCollation(_) | AdvertiseCollationV2(_, _, _) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?origin,
@@ -742,15 +866,17 @@ async fn handle_network_msg(
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, observed_role, maybe_authority) => {
+		PeerConnected(peer_id, observed_role, version, maybe_authority) => {
 			// If it is possible that a disconnected validator would attempt a reconnect
 			// it should be handled here.
 			tracing::trace!(
 				target: LOG_TARGET,
 				?peer_id,
 				?observed_role,
+				?version,
 				"Peer connected",
 			);
+			state.peer_versions.insert(peer_id, version);
 			if let Some(authority) = maybe_authority {
 				tracing::trace!(
 					target: LOG_TARGET,
@@ -780,6 +906,7 @@ async fn handle_network_msg(
 			);
 			state.peer_views.remove(&peer_id);
 			state.peer_ids.remove(&peer_id);
+			state.peer_versions.remove(&peer_id);
 		}
 		OurViewChange(view) => {
 			tracing::trace!(
@@ -805,7 +932,7 @@ async fn handle_our_view_change(
 	for removed in state.view.difference(&view) {
 		tracing::debug!(target: LOG_TARGET, relay_parent = ?removed, "Removing relay parent because our view changed.");
 
-		if let Some(collation) = state.collations.remove(removed) {
+		for collation in state.collations.remove(removed).into_iter().flatten() {
 			state.collation_result_senders.remove(&collation.receipt.hash());
 
 			match collation.status {
@@ -883,7 +1010,11 @@ mod tests {
 	use polkadot_node_network_protocol::{
 		our_view,
 		view,
-		request_response::request::IncomingRequest,
+		peer_set::{ProtocolVersion, COLLATION_PROTOCOL_V2, PROTOCOL_V1},
+		request_response::{
+			request::IncomingRequest,
+			v1::{CollationFetchingRequest, CollationFetchingV2Request},
+		},
 	};
 	use polkadot_node_subsystem_util::TimeoutExt;
 	use polkadot_primitives::v1::{AuthorityDiscoveryId, CandidateDescriptor, CollatorPair, GroupRotationInfo, ScheduledCore, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex};
@@ -1173,7 +1304,12 @@ mod tests {
 
 		overseer_send(
 			virtual_overseer,
-			CollatorProtocolMessage::DistributeCollation(candidate.clone(), pov_block.clone(), None),
+			CollatorProtocolMessage::DistributeCollation(
+				candidate.clone(),
+				Hash::repeat_byte(0xAA),
+				pov_block.clone(),
+				None,
+			),
 		).await;
 
 		// obtain the availability cores.
@@ -1248,6 +1384,16 @@ mod tests {
 		virtual_overseer: &mut VirtualOverseer,
 		peer: PeerId,
 		authority_id: Option<AuthorityDiscoveryId>
+	) {
+		connect_peer_with_version(virtual_overseer, peer, authority_id, COLLATION_PROTOCOL_V2).await;
+	}
+
+	/// Connect a peer speaking the given version of the collation protocol
+	async fn connect_peer_with_version(
+		virtual_overseer: &mut VirtualOverseer,
+		peer: PeerId,
+		authority_id: Option<AuthorityDiscoveryId>,
+		version: ProtocolVersion,
 	) {
 		overseer_send(
 			virtual_overseer,
@@ -1255,6 +1401,7 @@ mod tests {
 				NetworkBridgeEvent::PeerConnected(
 					peer.clone(),
 					polkadot_node_network_protocol::ObservedRole::Authority,
+					version,
 					authority_id,
 				),
 			),
@@ -1311,11 +1458,13 @@ mod tests {
 	}
 
 	/// Check that the next received message is a collation advertisement message.
+	///
+	/// Returns the advertised candidate hash.
 	async fn expect_advertise_collation_msg(
 		virtual_overseer: &mut VirtualOverseer,
 		peer: &PeerId,
 		expected_relay_parent: Hash,
-	) {
+	) -> CandidateHash {
 		assert_matches!(
 			overseer_recv(virtual_overseer).await,
 			AllMessages::NetworkBridge(
@@ -1327,14 +1476,17 @@ mod tests {
 				assert_eq!(to[0], *peer);
 				assert_matches!(
 					wire_message,
-					protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
+					protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
 						relay_parent,
+						candidate_hash,
+						_,
 					) => {
 						assert_eq!(relay_parent, expected_relay_parent);
+						candidate_hash
 					}
-				);
+				)
 			}
-		);
+		)
 	}
 
 	/// Send a message that the given peer's view changed.
@@ -1382,7 +1534,12 @@ mod tests {
 
 			// The peer is interested in a leaf that we have a collation for;
 			// advertise it.
-			expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent).await;
+			let candidate_hash = expect_advertise_collation_msg(
+				&mut virtual_overseer,
+				&peer,
+				test_state.relay_parent,
+			).await;
+			assert_eq!(candidate_hash, candidate.hash());
 
 			// Request a collation.
 			let (tx, rx) = oneshot::channel();
@@ -1603,6 +1760,229 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn multiple_collations_per_relay_parent_are_advertised_and_served() {
+		let test_state = TestState::default();
+		let local_peer_id = test_state.local_peer_id.clone();
+		let collator_pair = test_state.collator_pair.clone();
+
+		test_harness(local_peer_id, collator_pair, |test_harness| async move {
+			let mut virtual_overseer = test_harness.virtual_overseer;
+
+			let peer = test_state.current_group_validator_peer_ids()[0].clone();
+			let validator_id = test_state.current_group_validator_authority_ids()[0].clone();
+
+			setup_system(&mut virtual_overseer, &test_state).await;
+
+			connect_peer(&mut virtual_overseer, peer.clone(), Some(validator_id)).await;
+			expect_declare_msg(&mut virtual_overseer, &test_state, &peer).await;
+
+			let DistributeCollation { candidate: first_candidate, pov_block: first_pov } =
+				distribute_collation(&mut virtual_overseer, &test_state, true).await;
+
+			send_peer_view_change(&mut virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+			let advertised = expect_advertise_collation_msg(
+				&mut virtual_overseer,
+				&peer,
+				test_state.relay_parent,
+			).await;
+			assert_eq!(advertised, first_candidate.hash());
+
+			// A competing collation on the same relay parent, building on a different parent head.
+			let second_pov = PoV {
+				block_data: BlockData(vec![1, 2, 3]),
+			};
+			let second_candidate = TestCandidateBuilder {
+				para_id: test_state.para_id,
+				relay_parent: test_state.relay_parent,
+				pov_hash: second_pov.hash(),
+				..Default::default()
+			}.build();
+			let second_parent_head_data_hash = Hash::repeat_byte(0xBB);
+
+			// Validators are already known, so the collation is advertised right away.
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::DistributeCollation(
+					second_candidate.clone(),
+					second_parent_head_data_hash,
+					second_pov.clone(),
+					None,
+				),
+			).await;
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendCollationMessage(
+						to,
+						protocol_v1::CollationProtocol::CollatorProtocol(
+							protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+								relay_parent,
+								candidate_hash,
+								parent_head_data_hash,
+							),
+						),
+					)
+				) => {
+					assert_eq!(to, vec![peer.clone()]);
+					assert_eq!(relay_parent, test_state.relay_parent);
+					assert_eq!(candidate_hash, second_candidate.hash());
+					assert_eq!(parent_head_data_hash, second_parent_head_data_hash);
+				}
+			);
+
+			// The second collation can be requested specifically.
+			let (tx, rx) = oneshot::channel();
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::CollationFetchingV2Request(
+					IncomingRequest::new(
+						peer.clone(),
+						CollationFetchingV2Request {
+							relay_parent: test_state.relay_parent,
+							para_id: test_state.para_id,
+							candidate_hash: second_candidate.hash(),
+						},
+						tx,
+					)
+				)
+			).await;
+
+			assert_matches!(
+				rx.await,
+				Ok(full_response) => {
+					let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
+						= CollationFetchingResponse::decode(
+							&mut full_response.result
+							.expect("We should have a proper answer").as_ref()
+					)
+					.expect("Decoding should work");
+					assert_eq!(receipt, second_candidate);
+					assert_eq!(pov, second_pov);
+				}
+			);
+
+			// Requests which can't name a candidate are served the first collation.
+			let (tx, rx) = oneshot::channel();
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::CollationFetchingRequest(
+					IncomingRequest::new(
+						peer.clone(),
+						CollationFetchingRequest {
+							relay_parent: test_state.relay_parent,
+							para_id: test_state.para_id,
+						},
+						tx,
+					)
+				)
+			).await;
+
+			assert_matches!(
+				rx.await,
+				Ok(full_response) => {
+					let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
+						= CollationFetchingResponse::decode(
+							&mut full_response.result
+							.expect("We should have a proper answer").as_ref()
+					)
+					.expect("Decoding should work");
+					assert_eq!(receipt, first_candidate);
+					assert_eq!(pov, first_pov);
+				}
+			);
+
+			// Unknown candidates are not served.
+			let (tx, rx) = oneshot::channel();
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::CollationFetchingV2Request(
+					IncomingRequest::new(
+						peer,
+						CollationFetchingV2Request {
+							relay_parent: test_state.relay_parent,
+							para_id: test_state.para_id,
+							candidate_hash: CandidateHash(Hash::repeat_byte(0xCC)),
+						},
+						tx,
+					)
+				)
+			).await;
+			assert_matches!(rx.await, Err(_));
+
+			virtual_overseer
+		})
+	}
+
+	#[test]
+	fn v1_peers_are_only_advertised_the_first_collation() {
+		let test_state = TestState::default();
+		let local_peer_id = test_state.local_peer_id.clone();
+		let collator_pair = test_state.collator_pair.clone();
+
+		test_harness(local_peer_id, collator_pair, |test_harness| async move {
+			let mut virtual_overseer = test_harness.virtual_overseer;
+
+			let peer = test_state.current_group_validator_peer_ids()[0].clone();
+			let validator_id = test_state.current_group_validator_authority_ids()[0].clone();
+
+			setup_system(&mut virtual_overseer, &test_state).await;
+
+			connect_peer_with_version(
+				&mut virtual_overseer,
+				peer.clone(),
+				Some(validator_id),
+				PROTOCOL_V1,
+			).await;
+			expect_declare_msg(&mut virtual_overseer, &test_state, &peer).await;
+
+			distribute_collation(&mut virtual_overseer, &test_state, true).await;
+
+			send_peer_view_change(&mut virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+
+			// The first version of the protocol only advertises by relay parent.
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendCollationMessage(
+						to,
+						protocol_v1::CollationProtocol::CollatorProtocol(
+							protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+						),
+					)
+				) => {
+					assert_eq!(to, vec![peer.clone()]);
+					assert_eq!(relay_parent, test_state.relay_parent);
+				}
+			);
+
+			let second_pov = PoV {
+				block_data: BlockData(vec![1, 2, 3]),
+			};
+			let second_candidate = TestCandidateBuilder {
+				para_id: test_state.para_id,
+				relay_parent: test_state.relay_parent,
+				pov_hash: second_pov.hash(),
+				..Default::default()
+			}.build();
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::DistributeCollation(
+					second_candidate,
+					Hash::repeat_byte(0xBB),
+					second_pov,
+					None,
+				),
+			).await;
+
+			// The peer could not fetch a second collation on the relay parent, so it is not advertised.
+			assert!(overseer_recv_with_timeout(&mut virtual_overseer, TIMEOUT).await.is_none());
+			virtual_overseer
+		})
+	}
+
 	#[test]
 	fn collators_reject_declare_messages() {
 		let test_state = TestState::default();
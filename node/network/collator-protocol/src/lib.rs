@@ -20,14 +20,15 @@
 #![deny(missing_docs, unused_crate_dependencies)]
 #![recursion_limit="256"]
 
-use std::time::Duration;
+use std::{collections::{HashMap, HashSet}, sync::Arc, time::Duration};
 
 use futures::{FutureExt, TryFutureExt};
 
+use kvdb::KeyValueDB;
 use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange as Rep};
-use polkadot_primitives::v1::CollatorPair;
+use polkadot_primitives::v1::{CollatorId, CollatorPair, Id as ParaId};
 use polkadot_subsystem::{
 	messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeMessage},
 	SpawnedSubsystem, Subsystem, SubsystemContext, SubsystemError,
@@ -41,6 +42,10 @@ mod validator_side;
 
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
+/// The maximum number of collations a collator keeps and advertises per relay parent. Validators
+/// accept no more advertisements than that per relay parent from a single collator.
+const MAX_CANDIDATES_PER_RELAY_PARENT: usize = 3;
+
 /// A collator eviction policy - how fast to evict collators which are inactive.
 #[derive(Debug, Clone, Copy)]
 pub struct CollatorEvictionPolicy {
@@ -59,6 +64,29 @@ impl Default for CollatorEvictionPolicy {
 	}
 }
 
+/// Configuration of the persistent collator reputation kept by validators.
+#[derive(Debug, Clone)]
+pub struct ReputationConfig {
+	/// The data column in the store to use for collator reputations.
+	pub col_data: u32,
+	/// How long collators which kept providing bad collations are refused.
+	pub cooldown: Duration,
+	/// Collators to prefer per para. Collations of other collators of a para with a non-empty
+	/// allowlist are only fetched if none of the preferred ones provided one in time.
+	pub allowlist: HashMap<ParaId, HashSet<CollatorId>>,
+}
+
+impl ReputationConfig {
+	/// Create a configuration with the default cooldown and no allowlist.
+	pub fn new(col_data: u32) -> Self {
+		ReputationConfig {
+			col_data,
+			cooldown: Duration::from_secs(60 * 60),
+			allowlist: HashMap::new(),
+		}
+	}
+}
+
 /// What side of the collator protocol is being engaged
 pub enum ProtocolSide {
 	/// Validators operate on the relay chain.
@@ -69,6 +97,10 @@ pub enum ProtocolSide {
 		eviction_policy: CollatorEvictionPolicy,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
+		/// The database to persist collator reputations in.
+		db: Arc<dyn KeyValueDB>,
+		/// Configuration of the persistent collator reputation.
+		reputation_config: ReputationConfig,
 	},
 	/// Collators operate on a parachain.
 	Collator(PeerId, CollatorPair, collator_side::Metrics),
@@ -95,11 +127,19 @@ impl CollatorProtocolSubsystem {
 		Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	{
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } => validator_side::run(
+			ProtocolSide::Validator {
+				keystore,
+				eviction_policy,
+				metrics,
+				db,
+				reputation_config,
+			} => validator_side::run(
 				ctx,
 				keystore,
 				eviction_policy,
 				metrics,
+				db,
+				reputation_config,
 			).await,
 			ProtocolSide::Collator(local_peer_id, collator_pair, metrics) => collator_side::run(
 				ctx,
@@ -24,21 +24,24 @@ use futures::{
 };
 use futures_timer::Delay;
 
+use kvdb::KeyValueDB;
 use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::{
 	request_response as req_res, v1 as protocol_v1,
-	peer_set::PeerSet,
+	peer_set::{PeerSet, ProtocolVersion, COLLATION_PROTOCOL_V2, PROTOCOL_V1},
 	request_response::{
 		request::{Recipient, RequestError},
-		v1::{CollationFetchingRequest, CollationFetchingResponse},
+		v1::{CollationFetchingRequest, CollationFetchingResponse, CollationFetchingV2Request},
 		OutgoingRequest, Requests,
 	},
 	OurView, PeerId, UnifiedReputationChange as Rep, View,
 };
 use polkadot_node_primitives::{SignedFullStatement, PoV};
 use polkadot_node_subsystem_util::{TimeoutExt, metrics::{self, prometheus}};
-use polkadot_primitives::v1::{CandidateReceipt, CollatorId, Hash, Id as ParaId};
+use polkadot_primitives::v1::{
+	CandidateHash, CandidateReceipt, CollatorId, Hash, Id as ParaId, OccupiedCoreAssumption,
+};
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
@@ -48,10 +51,18 @@ use polkadot_subsystem::{
 	FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext, SubsystemSender,
 };
 
-use super::{modify_reputation, Result, LOG_TARGET};
+use super::{modify_reputation, ReputationConfig, Result, LOG_TARGET, MAX_CANDIDATES_PER_RELAY_PARENT};
+
+mod reputation;
+use reputation::{now_secs, Reputations};
 
 const COLLATION_FETCH_TIMEOUT: Duration = Duration::from_secs(2);
 
+/// How long to wait before fetching a collation advertised by a collator which is not on the
+/// allowlist of its para or which doesn't build on the known head of the para, giving preferred
+/// collations the chance to be provided first.
+const NON_PREFERRED_FETCH_DELAY: Duration = Duration::from_millis(500);
+
 const COST_UNEXPECTED_MESSAGE: Rep = Rep::CostMinor("An unexpected message");
 /// Message could not be decoded properly.
 const COST_CORRUPTED_MESSAGE: Rep = Rep::CostMinor("Message was corrupt");
@@ -61,7 +72,9 @@ const COST_REQUEST_TIMED_OUT: Rep = Rep::CostMinor("A collation request has time
 const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message signature");
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
+const COST_WRONG_CANDIDATE: Rep = Rep::Malicious("A collator provided a different collation than the requested one");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_BLOCKED_COLLATOR: Rep = Rep::CostMajor("A collator blocked for bad collations connected");
 const BENEFIT_NOTIFY_GOOD: Rep = Rep::BenefitMinor("A collator was noted good by another subsystem");
 
 // How often to check all peers with activity.
@@ -166,8 +179,9 @@ struct PerRequest {
 struct CollatingPeerState {
 	collator_id: CollatorId,
 	para_id: ParaId,
-	// Advertised relay parents.
-	advertisements: HashSet<Hash>,
+	// Advertised candidates per relay parent. `None` stands for an advertisement which doesn't
+	// name a candidate.
+	advertisements: HashMap<Hash, HashSet<Option<CandidateHash>>>,
 	last_active: Instant,
 }
 
@@ -183,18 +197,22 @@ enum AdvertisementError {
 	Duplicate,
 	OutOfOurView,
 	UndeclaredCollator,
+	PeerLimitReached,
 }
 
 struct PeerData {
 	view: View,
 	state: PeerState,
+	/// The negotiated version of the collation protocol.
+	version: ProtocolVersion,
 }
 
 impl PeerData {
-	fn new(view: View) -> Self {
+	fn new(view: View, version: ProtocolVersion) -> Self {
 		PeerData {
 			view,
 			state: PeerState::Connected(Instant::now()),
+			version,
 		}
 	}
 
@@ -212,16 +230,18 @@ impl PeerData {
 	/// Prune old advertisements relative to our view.
 	fn prune_old_advertisements(&mut self, our_view: &View) {
 		if let PeerState::Collating(ref mut peer_state) = self.state {
-			peer_state.advertisements.retain(|a| our_view.contains(a));
+			peer_state.advertisements.retain(|a, _| our_view.contains(a));
 		}
 	}
 
 	/// Note an advertisement by the collator. Returns `true` if the advertisement was imported
-	/// successfully. Fails if the advertisement is duplicate, out of view, or the peer has not
-	/// declared itself a collator.
+	/// successfully. Fails if the advertisement is duplicate, out of view, the peer has already
+	/// advertised too many collations on the relay parent or the peer has not declared itself a
+	/// collator.
 	fn insert_advertisement(
 		&mut self,
 		on_relay_parent: Hash,
+		prospective_candidate: Option<CandidateHash>,
 		our_view: &View,
 	)
 		-> std::result::Result<(CollatorId, ParaId), AdvertisementError>
@@ -230,11 +250,15 @@ impl PeerData {
 			PeerState::Connected(_) => Err(AdvertisementError::UndeclaredCollator),
 			_ if !our_view.contains(&on_relay_parent) => Err(AdvertisementError::OutOfOurView),
 			PeerState::Collating(ref mut state) => {
-				if state.advertisements.insert(on_relay_parent) {
+				let candidates = state.advertisements.entry(on_relay_parent).or_default();
+				if candidates.contains(&prospective_candidate) {
+					Err(AdvertisementError::Duplicate)
+				} else if candidates.len() >= MAX_CANDIDATES_PER_RELAY_PARENT {
+					Err(AdvertisementError::PeerLimitReached)
+				} else {
+					candidates.insert(prospective_candidate);
 					state.last_active = Instant::now();
 					Ok((state.collator_id.clone(), state.para_id.clone()))
-				} else {
-					Err(AdvertisementError::Duplicate)
 				}
 			}
 		}
@@ -256,7 +280,7 @@ impl PeerData {
 		self.state = PeerState::Collating(CollatingPeerState {
 			collator_id,
 			para_id,
-			advertisements: HashSet::new(),
+			advertisements: HashMap::new(),
 			last_active: Instant::now(),
 		});
 	}
@@ -276,10 +300,12 @@ impl PeerData {
 	}
 
 	/// Whether the peer has advertised the given collation.
-	fn has_advertised(&self, relay_parent: &Hash) -> bool {
+	fn has_advertised(&self, relay_parent: &Hash, prospective_candidate: Option<CandidateHash>) -> bool {
 		match self.state {
 			PeerState::Connected(_) => false,
-			PeerState::Collating(ref state) => state.advertisements.contains(relay_parent),
+			PeerState::Collating(ref state) => state.advertisements
+				.get(relay_parent)
+				.map_or(false, |candidates| candidates.contains(&prospective_candidate)),
 		}
 	}
 
@@ -294,7 +320,7 @@ impl PeerData {
 
 impl Default for PeerData {
 	fn default() -> Self {
-		PeerData::new(Default::default())
+		PeerData::new(Default::default(), PROTOCOL_V1)
 	}
 }
 
@@ -445,13 +471,26 @@ struct PendingCollation {
 	relay_parent: Hash,
 	para_id: ParaId,
 	peer_id: PeerId,
+	/// The advertised candidate, if the advertisement named one.
+	prospective_candidate: Option<CandidateHash>,
 	commitments_hash: Option<Hash>,
 }
 
 impl PendingCollation {
-	fn new(relay_parent: Hash, para_id: &ParaId, peer_id: &PeerId) -> Self {
+	fn new(
+		relay_parent: Hash,
+		para_id: &ParaId,
+		peer_id: &PeerId,
+		prospective_candidate: Option<CandidateHash>,
+	) -> Self {
 		let commitments_hash = None;
-		Self { relay_parent, para_id: para_id.clone(), peer_id: peer_id.clone(), commitments_hash }
+		Self {
+			relay_parent,
+			para_id: para_id.clone(),
+			peer_id: peer_id.clone(),
+			prospective_candidate,
+			commitments_hash,
+		}
 	}
 }
 
@@ -463,7 +502,6 @@ type PendingCollationFetch = (
 );
 
 /// All state relevant for the validator side of the protocol lives here.
-#[derive(Default)]
 struct State {
 	/// Our own view.
 	view: OurView,
@@ -492,6 +530,34 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Advertisements of non-preferred collators, to be fetched once their delay has passed.
+	delayed_fetches: FuturesUnordered<BoxFuture<'static, CollationEvent>>,
+
+	/// Persistent reputations of collators.
+	reputations: Reputations,
+
+	/// The hash of the head data of a para at a relay parent, as known to the runtime. `None` if
+	/// it could not be determined.
+	para_heads: HashMap<(Hash, ParaId), Option<Hash>>,
+}
+
+impl State {
+	fn new(metrics: Metrics, reputations: Reputations) -> Self {
+		State {
+			view: Default::default(),
+			active_paras: Default::default(),
+			peer_data: Default::default(),
+			requested_collations: Default::default(),
+			metrics,
+			span_per_relay_parent: Default::default(),
+			collations: Default::default(),
+			pending_candidates: Default::default(),
+			delayed_fetches: Default::default(),
+			reputations,
+			para_heads: Default::default(),
+		}
+	}
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -522,36 +588,65 @@ async fn fetch_collation<Context>(
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>
 {
-	let PendingCollation { relay_parent, para_id, peer_id, .. } = pc;
-	if state.peer_data.get(&peer_id).map_or(false, |d| d.has_advertised(&relay_parent)) {
-		request_collation(ctx, state, relay_parent, para_id, peer_id, tx).await;
+	let PendingCollation { relay_parent, para_id, peer_id, prospective_candidate, .. } = pc;
+	if state.peer_data
+		.get(&peer_id)
+		.map_or(false, |d| d.has_advertised(&relay_parent, prospective_candidate))
+	{
+		request_collation(ctx, state, relay_parent, para_id, prospective_candidate, peer_id, tx).await;
 	}
 }
 
 /// Report a collator for some malicious actions.
+///
+/// This is remembered across restarts and the collator gets disconnected once it is blocked.
 async fn report_collator<Context>(
 	ctx: &mut Context,
-	peer_data: &HashMap<PeerId, PeerData>,
+	state: &State,
 	id: CollatorId,
 )
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>
 {
-	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
-		modify_reputation(ctx, peer_id, COST_REPORT_BAD).await;
+	let blocked = state.reputations.note_bad(&id, now_secs());
+	if let Some(peer_id) = collator_peer_id(&state.peer_data, &id) {
+		modify_reputation(ctx, peer_id.clone(), COST_REPORT_BAD).await;
+		if blocked {
+			disconnect_peer(ctx, peer_id).await;
+		}
+	}
+}
+
+/// A collator provided a collation which doesn't match what it advertised.
+///
+/// This counts against its persistent reputation and disconnects it once it is blocked.
+async fn note_bad_collation(
+	ctx: &mut impl SubsystemContext,
+	reputations: &Reputations,
+	peer_data: &HashMap<PeerId, PeerData>,
+	peer_id: PeerId,
+) {
+	let collator_id = match peer_data.get(&peer_id).and_then(|d| d.collator_id()) {
+		Some(id) => id,
+		None => return,
+	};
+
+	if reputations.note_bad(collator_id, now_secs()) {
+		disconnect_peer(ctx, peer_id).await;
 	}
 }
 
 /// Some other subsystem has reported a collator as a good one, bump reputation.
 async fn note_good_collation<Context>(
 	ctx: &mut Context,
-	peer_data: &HashMap<PeerId, PeerData>,
+	state: &State,
 	id: CollatorId,
 )
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>
 {
-	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
+	state.reputations.note_good(&id, now_secs());
+	if let Some(peer_id) = collator_peer_id(&state.peer_data, &id) {
 		modify_reputation(ctx, peer_id, BENEFIT_NOTIFY_GOOD).await;
 	}
 }
@@ -586,7 +681,9 @@ async fn handle_peer_view_change(
 
 	peer_data.update_view(view);
 	state.requested_collations
-		.retain(|pc, _| pc.peer_id != peer_id || !peer_data.has_advertised(&pc.relay_parent));
+		.retain(|pc, _| {
+			pc.peer_id != peer_id || !peer_data.has_advertised(&pc.relay_parent, pc.prospective_candidate)
+		});
 
 	Ok(())
 }
@@ -597,11 +694,14 @@ async fn handle_peer_view_change(
 ///  - Check if the requested collation is in our view.
 ///  - Update PerRequest records with the `result` field if necessary.
 /// And as such invocations of this function may rely on that.
+///
+/// If the collator advertised a specific candidate, exactly that one is requested.
 async fn request_collation<Context>(
 	ctx: &mut Context,
 	state: &mut State,
 	relay_parent: Hash,
 	para_id: ParaId,
+	prospective_candidate: Option<CandidateHash>,
 	peer_id: PeerId,
 	result: oneshot::Sender<(CandidateReceipt, PoV)>,
 )
@@ -618,7 +718,7 @@ where
 		);
 		return;
 	}
-	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id);
+	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id, prospective_candidate);
 	if state.requested_collations.contains_key(&pending_collation) {
 		tracing::warn!(
 			target: LOG_TARGET,
@@ -630,15 +730,28 @@ where
 		return;
 	}
 
-	let (full_request, response_recv) =
-		OutgoingRequest::new(Recipient::Peer(peer_id), CollationFetchingRequest {
-			relay_parent,
-			para_id,
-		});
-	let requests = Requests::CollationFetching(full_request);
+	let (requests, response_recv) = match prospective_candidate {
+		Some(candidate_hash) => {
+			let (full_request, response_recv) =
+				OutgoingRequest::new(Recipient::Peer(peer_id), CollationFetchingV2Request {
+					relay_parent,
+					para_id,
+					candidate_hash,
+				});
+			(Requests::CollationFetchingV2(full_request), response_recv.boxed())
+		}
+		None => {
+			let (full_request, response_recv) =
+				OutgoingRequest::new(Recipient::Peer(peer_id), CollationFetchingRequest {
+					relay_parent,
+					para_id,
+				});
+			(Requests::CollationFetching(full_request), response_recv.boxed())
+		}
+	};
 
 	let per_request = PerRequest {
-		from_collator: response_recv.boxed().fuse(),
+		from_collator: response_recv.fuse(),
 		to_requester: result,
 		span: state.span_per_relay_parent.get(&relay_parent).map(|s| {
 			s.child("collation-request")
@@ -646,16 +759,14 @@ where
 		}),
 	};
 
-	state.requested_collations.insert(
-		PendingCollation::new(relay_parent, &para_id, &peer_id),
-		per_request
-	);
+	state.requested_collations.insert(pending_collation, per_request);
 
 	tracing::debug!(
 		target: LOG_TARGET,
 		peer_id = %peer_id,
 		%para_id,
 		?relay_parent,
+		?prospective_candidate,
 		"Requesting collation",
 	);
 
@@ -701,6 +812,20 @@ where
 				return
 			}
 
+			if state.reputations.is_blocked(&collator_id, now_secs()) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared as collator, but blocked for bad collations",
+				);
+
+				modify_reputation(ctx, origin.clone(), COST_BLOCKED_COLLATOR).await;
+				disconnect_peer(ctx, origin).await;
+				return
+			}
+
 			if state.active_paras.is_current_or_next(para_id) {
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -725,77 +850,230 @@ where
 			}
 		}
 		AdvertiseCollation(relay_parent) => {
-			let _span = state.span_per_relay_parent.get(&relay_parent).map(|s| s.child("advertise-collation"));
-			if !state.view.contains(&relay_parent) {
+			handle_advertisement(ctx, state, origin, relay_parent, None).await;
+		}
+		AdvertiseCollationV2(relay_parent, candidate_hash, parent_head_data_hash) => {
+			let version = state.peer_data.get(&origin).map_or(PROTOCOL_V1, |d| d.version);
+			if version < COLLATION_PROTOCOL_V2 {
 				tracing::debug!(
 					target: LOG_TARGET,
 					peer_id = ?origin,
 					?relay_parent,
-					"Advertise collation out of view",
+					"Advertisement by candidate hash from a peer on the first protocol version",
 				);
 
 				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-				return;
+				return
 			}
 
-			let peer_data = match state.peer_data.get_mut(&origin) {
-				None => {
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-					return;
-				}
-				Some(p) => p,
-			};
+			handle_advertisement(
+				ctx,
+				state,
+				origin,
+				relay_parent,
+				Some((candidate_hash, parent_head_data_hash)),
+			).await;
+		}
+		CollationSeconded(_, _) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				"Unexpected `CollationSeconded` message, decreasing reputation",
+			);
+		}
+	}
+}
 
-			match peer_data.insert_advertisement(relay_parent, &state.view) {
-				Ok((id, para_id)) => {
-					tracing::debug!(
-						target: LOG_TARGET,
-						peer_id = ?origin,
-						%para_id,
-						?relay_parent,
-						"Received advertise collation",
-					);
-					let (tx, rx) = oneshot::channel::<(
-						CandidateReceipt,
-						PoV,
-					)>();
+/// A collator advertised a collation.
+///
+/// Advertisements of the first protocol version don't carry a `prospective_candidate`, those of
+/// the second one name the candidate hash and the hash of the parent head data it builds on.
+async fn handle_advertisement<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	origin: PeerId,
+	relay_parent: Hash,
+	prospective_candidate: Option<(CandidateHash, Hash)>,
+)
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>
+{
+	let _span = state.span_per_relay_parent.get(&relay_parent).map(|s| s.child("advertise-collation"));
+	let (prospective_candidate, parent_head_data_hash) = match prospective_candidate {
+		Some((candidate_hash, parent_head_data_hash)) => (Some(candidate_hash), Some(parent_head_data_hash)),
+		None => (None, None),
+	};
 
+	if !state.view.contains(&relay_parent) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			?relay_parent,
+			"Advertise collation out of view",
+		);
 
-					let pending_collation = PendingCollation::new(
-						relay_parent,
-						&para_id,
-						&origin,
-					);
-					fetch_collation(ctx, state, pending_collation.clone(), tx).await;
+		modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+		return;
+	}
 
-					let future = async move {
-						((id, pending_collation), rx.timeout(COLLATION_FETCH_TIMEOUT).await)
-					};
-					state.collations.push(Box::pin(future));
-				}
-				Err(e) => {
-					tracing::debug!(
-						target: LOG_TARGET,
-						peer_id = ?origin,
-						?relay_parent,
-						error = ?e,
-						"Invalid advertisement",
-					);
+	let peer_data = match state.peer_data.get_mut(&origin) {
+		None => {
+			modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+			return;
+		}
+		Some(p) => p,
+	};
 
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-				}
+	match peer_data.insert_advertisement(relay_parent, prospective_candidate, &state.view) {
+		Ok((id, para_id)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				%para_id,
+				?relay_parent,
+				?prospective_candidate,
+				?parent_head_data_hash,
+				"Received advertise collation",
+			);
+
+			let pending_collation = PendingCollation::new(
+				relay_parent,
+				&para_id,
+				&origin,
+				prospective_candidate,
+			);
+
+			// Collations building on another head than the one known to the runtime compete with
+			// those building on it, so they are only fetched if no better one shows up in time.
+			let builds_on_known_head = match parent_head_data_hash {
+				Some(parent_head_data_hash) => known_para_head(ctx, state, relay_parent, para_id)
+					.await
+					.map_or(true, |head| head == parent_head_data_hash),
+				None => true,
+			};
+
+			if builds_on_known_head && state.reputations.is_preferred(para_id, &id) {
+				fetch_advertised_collation(ctx, state, id, pending_collation).await;
+			} else {
+				let delayed = Delay::new(NON_PREFERRED_FETCH_DELAY)
+					.map(move |_| (id, pending_collation));
+				state.delayed_fetches.push(Box::pin(delayed));
 			}
 		}
-		CollationSeconded(_, _) => {
-			tracing::warn!(
+		Err(e) => {
+			tracing::debug!(
 				target: LOG_TARGET,
 				peer_id = ?origin,
-				"Unexpected `CollationSeconded` message, decreasing reputation",
+				?relay_parent,
+				error = ?e,
+				"Invalid advertisement",
 			);
+
+			modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 		}
 	}
 }
 
+/// Get the hash of the head data of the para at the given relay parent, querying the runtime if
+/// it isn't known yet.
+async fn known_para_head<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	relay_parent: Hash,
+	para_id: ParaId,
+) -> Option<Hash>
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>
+{
+	if let Some(head) = state.para_heads.get(&(relay_parent, para_id)) {
+		return *head
+	}
+
+	let head = polkadot_node_subsystem_util::request_persisted_validation_data(
+		relay_parent,
+		para_id,
+		OccupiedCoreAssumption::Free,
+		ctx.sender(),
+	)
+		.await
+		.await
+		.ok()
+		.map(|x| x.ok())
+		.flatten()
+		.flatten()
+		.map(|validation_data| validation_data.parent_head.hash());
+
+	if head.is_none() {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?relay_parent,
+			%para_id,
+			"Failed to query the head of the para",
+		);
+	}
+
+	state.para_heads.insert((relay_parent, para_id), head);
+	head
+}
+
+/// Fetch an advertised collation and forward it to candidate backing once received.
+async fn fetch_advertised_collation<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	id: CollatorId,
+	pending_collation: PendingCollation,
+)
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>
+{
+	let (tx, rx) = oneshot::channel::<(
+		CandidateReceipt,
+		PoV,
+	)>();
+
+	fetch_collation(ctx, state, pending_collation.clone(), tx).await;
+
+	let future = async move {
+		((id, pending_collation), rx.timeout(COLLATION_FETCH_TIMEOUT).await)
+	};
+	state.collations.push(Box::pin(future));
+}
+
+/// The delay of an advertisement by a collator not on the allowlist of its para, or of a collation
+/// not building on the known head of the para, has passed.
+///
+/// The collation is only fetched if no collation for the relay parent and para has been
+/// requested or seconded in the meantime.
+async fn handle_delayed_fetch<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	id: CollatorId,
+	pending_collation: PendingCollation,
+)
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>
+{
+	let PendingCollation { relay_parent, para_id, .. } = pending_collation;
+	let already_covered = state.pending_candidates.contains_key(&relay_parent) ||
+		state.requested_collations
+			.keys()
+			.any(|pc| pc.relay_parent == relay_parent && pc.para_id == para_id);
+
+	if already_covered {
+		tracing::debug!(
+			target: LOG_TARGET,
+			peer_id = ?pending_collation.peer_id,
+			%para_id,
+			?relay_parent,
+			collator_id = ?id,
+			"Skipping collation of non-preferred collator",
+		);
+		return
+	}
+
+	fetch_advertised_collation(ctx, state, id, pending_collation).await;
+}
+
 /// A leaf has become inactive so we want to
 ///   - Cancel all ongoing collation requests that are on top of that leaf.
 ///   - Remove all stored collations relevant to that leaf.
@@ -810,6 +1088,8 @@ async fn remove_relay_parent(
 	state.pending_candidates.retain(|k, _| {
 		k != &relay_parent
 	});
+
+	state.para_heads.retain(|(r, _), _| r != &relay_parent);
 	Ok(())
 }
 
@@ -877,8 +1157,8 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, _role, _) => {
-			state.peer_data.entry(peer_id).or_default();
+		PeerConnected(peer_id, _role, version, _) => {
+			state.peer_data.entry(peer_id).or_default().version = version;
 			state.metrics.note_collator_peer_count(state.peer_data.len());
 		},
 		PeerDisconnected(peer_id) => {
@@ -928,7 +1208,7 @@ where
 			);
 		}
 		ReportCollator(id) => {
-			report_collator(ctx, &state.peer_data, id).await;
+			report_collator(ctx, state, id).await;
 		}
 		NetworkBridgeUpdateV1(event) => {
 			if let Err(e) = handle_network_msg(
@@ -944,7 +1224,7 @@ where
 				);
 			}
 		}
-		CollationFetchingRequest(_) => {
+		CollationFetchingRequest(_) | CollationFetchingV2Request(_) => {
 			tracing::warn!(
 				target: LOG_TARGET,
 				"CollationFetchingRequest message is not expected on the validator side of the protocol",
@@ -954,7 +1234,7 @@ where
 			if let Some(collation_event) = state.pending_candidates.remove(&parent) {
 				let (collator_id, pending_collation) = collation_event;
 				let PendingCollation { relay_parent, peer_id, .. } = pending_collation;
-				note_good_collation(ctx, &state.peer_data, collator_id).await;
+				note_good_collation(ctx, state, collator_id).await;
 				notify_collation_seconded(ctx, peer_id, relay_parent, stmt).await;
 			} else {
 				tracing::debug!(
@@ -972,7 +1252,7 @@ where
 				_ => false,
 			} {
 				if let Some((id, _)) = state.pending_candidates.remove(&parent) {
-					report_collator(ctx, &state.peer_data, id).await;
+					report_collator(ctx, state, id).await;
 				}
 			}
 		}
@@ -997,17 +1277,15 @@ pub(crate) async fn run<Context>(
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
 	metrics: Metrics,
+	db: Arc<dyn KeyValueDB>,
+	reputation_config: ReputationConfig,
 ) -> Result<()>
 	where Context: SubsystemContext<Message = CollatorProtocolMessage>
 {
 	use FromOverseer::*;
 	use OverseerSignal::*;
 
-	let mut state = State {
-		metrics,
-
-		..Default::default()
-	};
+	let mut state = State::new(metrics, Reputations::new(db, reputation_config));
 
 	let next_inactivity_stream = futures::stream::unfold(
 		Instant::now() + ACTIVITY_POLL,
@@ -1035,6 +1313,11 @@ pub(crate) async fn run<Context>(
 			}
 			_ = next_inactivity_stream.next() => {
 				disconnect_inactive_peers(&mut ctx, &eviction_policy, &state.peer_data).await;
+				state.reputations.maybe_prune(now_secs());
+			}
+			res = state.delayed_fetches.select_next_some() => {
+				let (id, pending_collation) = res;
+				handle_delayed_fetch(&mut ctx, &mut state, id, pending_collation).await;
 			}
 			res = state.collations.next() => {
 				// If no prior collation for this relay parent has been seconded, then
@@ -1091,7 +1374,13 @@ pub(crate) async fn run<Context>(
 		for (pending_collation, per_req) in state.requested_collations.iter_mut() {
 			// Despite the await, this won't block on the response itself.
 			let finished = poll_collation_response(
-				&mut ctx, &state.metrics, &state.span_per_relay_parent, pending_collation, per_req,
+				&mut ctx,
+				&state.metrics,
+				&state.span_per_relay_parent,
+				&state.reputations,
+				&state.peer_data,
+				pending_collation,
+				per_req,
 			).await;
 			if !finished {
 				retained_requested.insert(pending_collation.clone());
@@ -1128,6 +1417,8 @@ async fn poll_collation_response<Context>(
 	ctx: &mut Context,
 	metrics: &Metrics,
 	spans: &HashMap<Hash, PerLeafSpan>,
+	reputations: &Reputations,
+	peer_data: &HashMap<PeerId, PeerData>,
 	pending_collation: &PendingCollation,
 	per_req: &mut PerRequest
 )
@@ -1208,6 +1499,21 @@ where
 				);
 
 				modify_reputation(ctx, pending_collation.peer_id.clone(), COST_WRONG_PARA).await;
+				note_bad_collation(ctx, reputations, peer_data, pending_collation.peer_id.clone()).await;
+			}
+			Ok(CollationFetchingResponse::Collation(receipt, _))
+				if pending_collation.prospective_candidate.map_or(false, |c| c != receipt.hash()) =>
+			{
+				tracing::debug!(
+					target: LOG_TARGET,
+					expected_candidate_hash = ?pending_collation.prospective_candidate,
+					got_candidate_hash = ?receipt.hash(),
+					peer_id = ?pending_collation.peer_id,
+					"Got wrong candidate for requested collation."
+				);
+
+				modify_reputation(ctx, pending_collation.peer_id.clone(), COST_WRONG_CANDIDATE).await;
+				note_bad_collation(ctx, reputations, peer_data, pending_collation.peer_id.clone()).await;
 			}
 			Ok(CollationFetchingResponse::Collation(receipt, pov)) => {
 				tracing::debug!(
@@ -1259,14 +1565,15 @@ mod tests {
 
 	use polkadot_primitives::v1::{
 		CollatorPair, ValidatorId, ValidatorIndex, CoreState, CandidateDescriptor,
-		GroupRotationInfo, ScheduledCore, OccupiedCore, GroupIndex,
+		GroupRotationInfo, ScheduledCore, OccupiedCore, GroupIndex, HeadData, PersistedValidationData,
 	};
 	use polkadot_node_primitives::BlockData;
 	use polkadot_node_subsystem_util::TimeoutExt;
 	use polkadot_subsystem_testhelpers as test_helpers;
 	use polkadot_subsystem::messages::{RuntimeApiMessage, RuntimeApiRequest};
 	use polkadot_node_network_protocol::{our_view, ObservedRole,
-		request_response::Requests
+		request_response::Requests,
+		peer_set::{ProtocolVersion, COLLATION_PROTOCOL_V2, PROTOCOL_V1},
 	};
 
 	const ACTIVITY_TIMEOUT: Duration = Duration::from_millis(50);
@@ -1360,6 +1667,18 @@ mod tests {
 	}
 
 	fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+		test_harness_with_reputation(
+			Arc::new(kvdb_memorydb::create(1)),
+			ReputationConfig::new(0),
+			test,
+		)
+	}
+
+	fn test_harness_with_reputation<T: Future<Output = VirtualOverseer>>(
+		db: Arc<dyn KeyValueDB>,
+		reputation_config: ReputationConfig,
+		test: impl FnOnce(TestHarness) -> T,
+	) {
 		let _ = env_logger::builder()
 			.is_test(true)
 			.filter(
@@ -1390,6 +1709,8 @@ mod tests {
 				undeclared: DECLARE_TIMEOUT,
 			},
 			Metrics::default(),
+			db,
+			reputation_config,
 		);
 
 		let test_fut = test(TestHarness { virtual_overseer });
@@ -1489,6 +1810,27 @@ mod tests {
 		);
 	}
 
+	async fn respond_to_para_head_query(
+		virtual_overseer: &mut VirtualOverseer,
+		test_state: &TestState,
+		para_head: HeadData,
+	) {
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::PersistedValidationData(para_id, OccupiedCoreAssumption::Free, tx),
+			)) => {
+				assert_eq!(relay_parent, test_state.relay_parent);
+				assert_eq!(para_id, test_state.chain_ids[0]);
+				let _ = tx.send(Ok(Some(PersistedValidationData {
+					parent_head: para_head,
+					..Default::default()
+				})));
+			}
+		);
+	}
+
 	// As we receive a relevant advertisement act on it and issue a collation request.
 	#[test]
 	fn act_on_advertisement() {
@@ -1519,6 +1861,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1598,6 +1941,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1609,6 +1953,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_c,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1679,6 +2024,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1748,6 +2094,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1759,6 +2106,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_c,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -1896,6 +2244,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					)
 				)
@@ -2006,6 +2355,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					)
 				)
@@ -2190,6 +2540,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					)
 				)
@@ -2237,6 +2588,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					)
 				)
@@ -2309,6 +2661,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					)
 				)
@@ -2390,6 +2743,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_b,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -2401,6 +2755,7 @@ mod tests {
 					NetworkBridgeEvent::PeerConnected(
 						peer_c,
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						None,
 					),
 				)
@@ -2539,4 +2894,620 @@ mod tests {
 			virtual_overseer
 		});
 	}
+
+	#[test]
+	fn fetch_advertised_candidates_works() {
+		let test_state = TestState::default();
+
+		test_harness(|test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+				),
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+			let peer_b = PeerId::random();
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(
+						peer_b,
+						ObservedRole::Full,
+						COLLATION_PROTOCOL_V2,
+						None,
+					),
+				)
+			).await;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerMessage(
+						peer_b.clone(),
+						protocol_v1::CollatorProtocolMessage::Declare(
+							test_state.collators[0].public(),
+							test_state.chain_ids[0],
+							test_state.collators[0].sign(&protocol_v1::declare_signature_payload(&peer_b)),
+						)
+					)
+				)
+			).await;
+
+			let mut candidate_a = CandidateReceipt::default();
+			candidate_a.descriptor.para_id = test_state.chain_ids[0];
+			candidate_a.descriptor.relay_parent = test_state.relay_parent;
+			candidate_a.descriptor.pov_hash = Hash::repeat_byte(1);
+
+			let mut candidate_b = candidate_a.clone();
+			candidate_b.descriptor.pov_hash = Hash::repeat_byte(2);
+
+			let para_head = HeadData(vec![1, 2, 3]);
+
+			// The collator advertises two competing collations on the same relay parent.
+			for candidate in &[&candidate_a, &candidate_b, &candidate_a] {
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(
+							peer_b.clone(),
+							protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+								test_state.relay_parent,
+								candidate.hash(),
+								para_head.hash(),
+							)
+						)
+					)
+				).await;
+			}
+
+			respond_to_para_head_query(&mut virtual_overseer, &test_state, para_head).await;
+
+			let mut response_channels = Vec::new();
+			for candidate in &[&candidate_a, &candidate_b] {
+				let response_channel = assert_matches!(
+					overseer_recv(&mut virtual_overseer).await,
+					AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(reqs, IfDisconnected::ImmediateError)
+				) => {
+					let req = reqs.into_iter().next()
+						.expect("There should be exactly one request");
+					match req {
+						Requests::CollationFetchingV2(req) => {
+							let payload = req.payload;
+							assert_eq!(payload.relay_parent, test_state.relay_parent);
+							assert_eq!(payload.para_id, test_state.chain_ids[0]);
+							assert_eq!(payload.candidate_hash, candidate.hash());
+							req.pending_response
+						}
+						_ => panic!("Unexpected request"),
+					}
+				});
+				response_channels.push(response_channel);
+			}
+
+			// The duplicate advertisement is punished.
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(peer, rep),
+				) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, COST_UNEXPECTED_MESSAGE);
+				}
+			);
+
+			let response_channel_b = response_channels.pop().unwrap();
+			let response_channel_a = response_channels.pop().unwrap();
+
+			// Answering with another collation than the requested one is punished as well.
+			response_channel_a.send(Ok(
+				CollationFetchingResponse::Collation(
+					candidate_b.clone(),
+					PoV {
+						block_data: BlockData(vec![]),
+					},
+				).encode()
+			)).expect("Sending response should succeed");
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(peer, rep),
+				) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, COST_WRONG_CANDIDATE);
+				}
+			);
+
+			let pov = PoV {
+				block_data: BlockData(vec![1, 2, 3]),
+			};
+			response_channel_b.send(Ok(
+				CollationFetchingResponse::Collation(
+					candidate_b.clone(),
+					pov.clone(),
+				).encode()
+			)).expect("Sending response should succeed");
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::CandidateBacking(CandidateBackingMessage::Second(relay_parent, candidate_receipt, incoming_pov)
+			) => {
+				assert_eq!(relay_parent, test_state.relay_parent);
+				assert_eq!(candidate_receipt, candidate_b);
+				assert_eq!(incoming_pov, pov);
+			});
+
+			virtual_overseer
+		});
+	}
+
+	// Test that of two competing collations, the one building on the head of the para known to the
+	// runtime is fetched first and the other one is skipped.
+	#[test]
+	fn collations_building_on_known_head_are_preferred() {
+		let test_state = TestState::default();
+
+		test_harness(|test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+				),
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+			let peer_b = PeerId::random();
+			let peer_c = PeerId::random();
+
+			connect_and_declare_with_version(
+				&mut virtual_overseer,
+				peer_b,
+				&test_state.collators[0],
+				test_state.chain_ids[0],
+				COLLATION_PROTOCOL_V2,
+			).await;
+
+			connect_and_declare_with_version(
+				&mut virtual_overseer,
+				peer_c,
+				&test_state.collators[1],
+				test_state.chain_ids[0],
+				COLLATION_PROTOCOL_V2,
+			).await;
+
+			let para_head = HeadData(vec![1, 2, 3]);
+
+			let mut candidate_a = CandidateReceipt::default();
+			candidate_a.descriptor.para_id = test_state.chain_ids[0];
+			candidate_a.descriptor.relay_parent = test_state.relay_parent;
+			candidate_a.descriptor.pov_hash = Hash::repeat_byte(1);
+
+			let mut candidate_b = candidate_a.clone();
+			candidate_b.descriptor.pov_hash = Hash::repeat_byte(2);
+
+			// The first advertisement builds on another head than the known one.
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerMessage(
+						peer_b.clone(),
+						protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+							test_state.relay_parent,
+							candidate_a.hash(),
+							Hash::repeat_byte(0xBB),
+						)
+					)
+				)
+			).await;
+
+			respond_to_para_head_query(&mut virtual_overseer, &test_state, para_head.clone()).await;
+
+			// So it is not fetched right away.
+			assert!(recv_ignoring_disconnects(&mut virtual_overseer, TIMEOUT).await.is_none());
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerMessage(
+						peer_c.clone(),
+						protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+							test_state.relay_parent,
+							candidate_b.hash(),
+							para_head.hash(),
+						)
+					)
+				)
+			).await;
+
+			// The head is known by now, so the competing collation is fetched without querying again.
+			// Keep the request alive, so it doesn't fail.
+			let _request = assert_matches!(
+				recv_ignoring_disconnects(&mut virtual_overseer, TIMEOUT).await,
+				Some(AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendRequests(mut reqs, IfDisconnected::ImmediateError)
+				)) => {
+					match reqs.pop() {
+						Some(Requests::CollationFetchingV2(req)) => {
+							assert_eq!(req.peer, Recipient::Peer(peer_c));
+							assert_eq!(req.payload.relay_parent, test_state.relay_parent);
+							assert_eq!(req.payload.candidate_hash, candidate_b.hash());
+							req
+						}
+						_ => panic!("Unexpected request"),
+					}
+				}
+			);
+
+			// The collation building on the known head is pending, so the other one is skipped.
+			assert!(
+				recv_ignoring_disconnects(&mut virtual_overseer, NON_PREFERRED_FETCH_DELAY * 2)
+					.await
+					.is_none()
+			);
+
+			virtual_overseer
+		});
+	}
+
+	// Receive the next message within `timeout`, skipping disconnects of inactive peers.
+	async fn recv_ignoring_disconnects(
+		overseer: &mut VirtualOverseer,
+		timeout: Duration,
+	) -> Option<AllMessages> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			let now = Instant::now();
+			if now >= deadline {
+				return None
+			}
+
+			match overseer_recv_with_timeout(overseer, deadline - now).await {
+				Some(AllMessages::NetworkBridge(NetworkBridgeMessage::DisconnectPeer(..))) => continue,
+				msg => return msg,
+			}
+		}
+	}
+
+	async fn connect_and_declare(
+		virtual_overseer: &mut VirtualOverseer,
+		peer: PeerId,
+		collator: &CollatorPair,
+		para_id: ParaId,
+	) {
+		connect_and_declare_with_version(virtual_overseer, peer, collator, para_id, PROTOCOL_V1).await;
+	}
+
+	async fn connect_and_declare_with_version(
+		virtual_overseer: &mut VirtualOverseer,
+		peer: PeerId,
+		collator: &CollatorPair,
+		para_id: ParaId,
+		version: ProtocolVersion,
+	) {
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				NetworkBridgeEvent::PeerConnected(
+					peer,
+					ObservedRole::Full,
+					version,
+					None,
+				),
+			)
+		).await;
+
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				NetworkBridgeEvent::PeerMessage(
+					peer.clone(),
+					protocol_v1::CollatorProtocolMessage::Declare(
+						collator.public(),
+						para_id,
+						collator.sign(&protocol_v1::declare_signature_payload(&peer)),
+					),
+				)
+			)
+		).await;
+	}
+
+	// Test that collators reported too often get blocked and stay blocked after a restart.
+	#[test]
+	fn reported_collator_is_blocked_across_restarts() {
+		let test_state = TestState::default();
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+
+		let state = test_state.clone();
+		test_harness_with_reputation(db.clone(), ReputationConfig::new(0), |test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![state.relay_parent])
+				)
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &state).await;
+
+			let peer_b = PeerId::random();
+			connect_and_declare(&mut virtual_overseer, peer_b, &state.collators[0], state.chain_ids[0]).await;
+
+			for _ in 0..3 {
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::ReportCollator(state.collators[0].public()),
+				).await;
+
+				assert_matches!(
+					overseer_recv(&mut virtual_overseer).await,
+					AllMessages::NetworkBridge(
+						NetworkBridgeMessage::ReportPeer(peer, rep),
+					) => {
+						assert_eq!(peer, peer_b);
+						assert_eq!(rep, COST_REPORT_BAD);
+					}
+				);
+			}
+
+			// The third report blocks the collator.
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::DisconnectPeer(peer, PeerSet::Collation),
+				) => {
+					assert_eq!(peer, peer_b);
+				}
+			);
+
+			virtual_overseer
+		});
+
+		test_harness_with_reputation(db, ReputationConfig::new(0), |test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+				)
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+			let peer_b = PeerId::random();
+			connect_and_declare(
+				&mut virtual_overseer,
+				peer_b,
+				&test_state.collators[0],
+				test_state.chain_ids[0],
+			).await;
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(peer, rep),
+				) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, COST_BLOCKED_COLLATOR);
+				}
+			);
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::DisconnectPeer(peer, PeerSet::Collation),
+				) => {
+					assert_eq!(peer, peer_b);
+				}
+			);
+
+			virtual_overseer
+		});
+	}
+
+	// Test that expired cooldowns don't stay in the DB.
+	#[test]
+	fn expired_collator_blocks_are_removed_from_db() {
+		let test_state = TestState::default();
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+		let config = ReputationConfig::new(0);
+		let cooldown = config.cooldown.as_secs();
+		let mut reputations = Reputations::new(db.clone(), config);
+
+		let collator_a = test_state.collators[0].public();
+		let collator_b = test_state.collators[1].public();
+
+		for _ in 0..3 {
+			reputations.note_bad(&collator_a, 0);
+			reputations.note_bad(&collator_b, 0);
+		}
+		assert!(reputations.is_blocked(&collator_a, 0));
+		assert!(reputations.is_blocked(&collator_b, 0));
+		assert_eq!(db.iter(0).count(), 2);
+
+		// Reading an expired block clears it.
+		assert!(!reputations.is_blocked(&collator_a, cooldown));
+		assert_eq!(db.iter(0).count(), 1);
+
+		// Pruning clears all others.
+		reputations.maybe_prune(cooldown - 1);
+		assert_eq!(db.iter(0).count(), 1);
+		reputations.maybe_prune(cooldown - 1 + reputation::PRUNE_INTERVAL_SECS);
+		assert_eq!(db.iter(0).count(), 0);
+	}
+
+	// Advertisements by candidate hash are not part of the first version of the protocol.
+	#[test]
+	fn v2_advertisements_from_v1_peers_are_rejected() {
+		let test_state = TestState::default();
+
+		test_harness(|test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+				),
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+			let peer_b = PeerId::random();
+			connect_and_declare(
+				&mut virtual_overseer,
+				peer_b,
+				&test_state.collators[0],
+				test_state.chain_ids[0],
+			).await;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerMessage(
+						peer_b.clone(),
+						protocol_v1::CollatorProtocolMessage::AdvertiseCollationV2(
+							test_state.relay_parent,
+							CandidateHash(Hash::repeat_byte(1)),
+							Hash::repeat_byte(0xAA),
+						)
+					)
+				)
+			).await;
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(peer, rep),
+				) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, COST_UNEXPECTED_MESSAGE);
+				}
+			);
+
+			virtual_overseer
+		});
+	}
+
+	// Test that advertisements of collators not on the allowlist of their para are only fetched
+	// after a delay and skipped if a preferred collator provided a collation in the meantime.
+	#[test]
+	fn allowlisted_collators_are_preferred() {
+		let test_state = TestState::default();
+
+		let mut reputation_config = ReputationConfig::new(0);
+		reputation_config.allowlist.insert(
+			test_state.chain_ids[0],
+			iter::once(test_state.collators[0].public()).collect(),
+		);
+
+		test_harness_with_reputation(
+			Arc::new(kvdb_memorydb::create(1)),
+			reputation_config,
+			|test_harness| async move {
+				let TestHarness {
+					mut virtual_overseer,
+				} = test_harness;
+
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+					)
+				).await;
+
+				respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+				let peer_b = PeerId::random();
+				let peer_c = PeerId::random();
+
+				connect_and_declare(
+					&mut virtual_overseer,
+					peer_b,
+					&test_state.collators[1],
+					test_state.chain_ids[0],
+				).await;
+
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(
+							peer_b.clone(),
+							protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
+								test_state.relay_parent,
+							)
+						)
+					)
+				).await;
+
+				// Not on the allowlist, so not fetched right away.
+				assert!(recv_ignoring_disconnects(&mut virtual_overseer, TIMEOUT).await.is_none());
+
+				connect_and_declare(
+					&mut virtual_overseer,
+					peer_c,
+					&test_state.collators[0],
+					test_state.chain_ids[0],
+				).await;
+
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(
+							peer_c.clone(),
+							protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
+								test_state.relay_parent,
+							)
+						)
+					)
+				).await;
+
+				// Keep the request alive, so it doesn't fail.
+				let _request = assert_matches!(
+					recv_ignoring_disconnects(&mut virtual_overseer, TIMEOUT).await,
+					Some(AllMessages::NetworkBridge(
+						NetworkBridgeMessage::SendRequests(mut reqs, IfDisconnected::ImmediateError)
+					)) => {
+						match reqs.pop() {
+							Some(Requests::CollationFetching(req)) => {
+								assert_eq!(req.peer, Recipient::Peer(peer_c));
+								assert_eq!(req.payload.relay_parent, test_state.relay_parent);
+								req
+							}
+							_ => panic!("Unexpected request"),
+						}
+					}
+				);
+
+				// The collation of the preferred collator is pending, so the other one is skipped.
+				assert!(
+					recv_ignoring_disconnects(&mut virtual_overseer, NON_PREFERRED_FETCH_DELAY * 2)
+						.await
+						.is_none()
+				);
+
+				virtual_overseer
+			},
+		);
+	}
 }
@@ -0,0 +1,234 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent reputation of collators.
+//!
+//! Network reputation is tied to a `PeerId` and forgotten on restart. Here we keep a score per
+//! `CollatorId` in the parachains DB instead, so collators repeatedly providing bad collations
+//! can be refused for a cooldown period, no matter how often either side restarts.
+//!
+//! The DB schema is
+//!
+//! ```ignore
+//! ("CP_collator_reputation", CollatorId) -> CollatorReputation
+//! ```
+//!
+//! Entries only exist for collators with a non-zero score or an ongoing cooldown. Expired
+//! cooldowns are cleared when read and by a periodic pruning of the whole column.
+
+use std::{sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+
+use kvdb::{DBTransaction, KeyValueDB};
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_primitives::v1::{CollatorId, Id as ParaId};
+
+use crate::{ReputationConfig, LOG_TARGET};
+
+const REPUTATION_PREFIX: &[u8; 22] = b"CP_collator_reputation";
+
+/// Score lost for every bad collation.
+const BAD_COLLATION_PENALTY: i32 = 100;
+/// Score gained for every collation which got seconded.
+const GOOD_COLLATION_BENEFIT: i32 = 10;
+/// Good behaviour doesn't accumulate beyond this.
+const MAX_SCORE: i32 = 1000;
+/// Collators reaching this score are blocked for the configured cooldown.
+const BLOCK_THRESHOLD: i32 = -3 * BAD_COLLATION_PENALTY;
+/// How often, in seconds, expired entries are pruned from the DB.
+pub(super) const PRUNE_INTERVAL_SECS: u64 = 60 * 60;
+
+/// The reputation of a single collator, as stored in the DB.
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+struct CollatorReputation {
+	/// The current score of the collator.
+	score: i32,
+	/// Unix time in seconds until which the collator is refused, if blocked.
+	blocked_until: Option<u64>,
+}
+
+impl CollatorReputation {
+	/// Forget the cooldown if it is over at the given unix time.
+	///
+	/// Returns `true` if there was an expired cooldown.
+	fn clear_expired(&mut self, now: u64) -> bool {
+		match self.blocked_until {
+			Some(until) if until <= now => {
+				self.blocked_until = None;
+				true
+			}
+			_ => false,
+		}
+	}
+}
+
+fn reputation_key(collator_id: &CollatorId) -> Vec<u8> {
+	(REPUTATION_PREFIX, collator_id).encode()
+}
+
+/// The current unix time in seconds.
+pub(super) fn now_secs() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Persistent reputations of all collators together with the operator's preferences.
+pub(super) struct Reputations {
+	db: Arc<dyn KeyValueDB>,
+	config: ReputationConfig,
+	/// Unix time in seconds of the next pruning of the DB.
+	next_prune: u64,
+}
+
+impl Reputations {
+	pub(super) fn new(db: Arc<dyn KeyValueDB>, config: ReputationConfig) -> Self {
+		Reputations { db, config, next_prune: 0 }
+	}
+
+	/// Whether the collator is blocked at the given unix time.
+	pub(super) fn is_blocked(&self, collator_id: &CollatorId, now: u64) -> bool {
+		let mut reputation = self.load(collator_id);
+		if reputation.clear_expired(now) {
+			self.store(collator_id, &reputation);
+		}
+
+		reputation.blocked_until.is_some()
+	}
+
+	/// Note a bad collation of the given collator.
+	///
+	/// Returns `true` if the collator got blocked as a consequence.
+	pub(super) fn note_bad(&self, collator_id: &CollatorId, now: u64) -> bool {
+		let mut reputation = self.load(collator_id);
+		reputation.clear_expired(now);
+		reputation.score = reputation.score.saturating_sub(BAD_COLLATION_PENALTY);
+
+		let blocked = reputation.score <= BLOCK_THRESHOLD;
+		if blocked {
+			tracing::info!(
+				target: LOG_TARGET,
+				?collator_id,
+				cooldown = ?self.config.cooldown,
+				"Blocking collator after repeated bad collations",
+			);
+
+			// Once the cooldown is over, the collator starts over with a clean slate.
+			reputation.score = 0;
+			reputation.blocked_until = Some(now.saturating_add(self.config.cooldown.as_secs()));
+		}
+
+		self.store(collator_id, &reputation);
+		blocked
+	}
+
+	/// Note a good collation of the given collator.
+	pub(super) fn note_good(&self, collator_id: &CollatorId, now: u64) {
+		let mut reputation = self.load(collator_id);
+		reputation.clear_expired(now);
+		reputation.score = reputation.score.saturating_add(GOOD_COLLATION_BENEFIT).min(MAX_SCORE);
+		self.store(collator_id, &reputation);
+	}
+
+	/// Whether the operator restricted the preferred collators of the para.
+	pub(super) fn has_allowlist(&self, para_id: ParaId) -> bool {
+		self.config.allowlist.get(&para_id).map_or(false, |allowed| !allowed.is_empty())
+	}
+
+	/// Whether collations of the collator for the para should be fetched without delay.
+	///
+	/// This is the case for all collators of paras without an allowlist.
+	pub(super) fn is_preferred(&self, para_id: ParaId, collator_id: &CollatorId) -> bool {
+		!self.has_allowlist(para_id) ||
+			self.config.allowlist.get(&para_id).map_or(false, |allowed| allowed.contains(collator_id))
+	}
+
+	/// Remove expired cooldowns from the DB, if the last pruning is long enough ago.
+	///
+	/// Entries left without a score are deleted altogether.
+	pub(super) fn maybe_prune(&mut self, now: u64) {
+		if now < self.next_prune {
+			return
+		}
+		self.next_prune = now.saturating_add(PRUNE_INTERVAL_SECS);
+
+		let mut tx = DBTransaction::new();
+		let mut num_updates = 0;
+		let iter = self.db.iter_with_prefix(self.config.col_data, &REPUTATION_PREFIX[..]);
+		for (key, raw) in iter {
+			let mut reputation = match CollatorReputation::decode(&mut &raw[..]) {
+				Ok(reputation) => reputation,
+				Err(_) => {
+					// Would be treated as a fresh reputation on load anyway.
+					tx.delete(self.config.col_data, &key[..]);
+					num_updates += 1;
+					continue
+				}
+			};
+
+			if !reputation.clear_expired(now) {
+				continue
+			}
+
+			if reputation == CollatorReputation::default() {
+				tx.delete(self.config.col_data, &key[..]);
+			} else {
+				tx.put_vec(self.config.col_data, &key[..], reputation.encode());
+			}
+			num_updates += 1;
+		}
+
+		if num_updates == 0 {
+			return
+		}
+
+		tracing::debug!(target: LOG_TARGET, num_updates, "Pruning collator reputations");
+
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, "Failed to prune collator reputations");
+		}
+	}
+
+	// Reputation is best-effort, so DB errors are only logged and a fresh reputation is assumed.
+	fn load(&self, collator_id: &CollatorId) -> CollatorReputation {
+		let raw = match self.db.get(self.config.col_data, &reputation_key(collator_id)) {
+			Ok(raw) => raw,
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, ?collator_id, "Failed to load collator reputation");
+				return CollatorReputation::default()
+			}
+		};
+
+		raw.and_then(|raw| match CollatorReputation::decode(&mut &raw[..]) {
+			Ok(reputation) => Some(reputation),
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, ?collator_id, "Failed to decode collator reputation");
+				None
+			}
+		}).unwrap_or_default()
+	}
+
+	fn store(&self, collator_id: &CollatorId, reputation: &CollatorReputation) {
+		let mut tx = DBTransaction::new();
+		if reputation == &CollatorReputation::default() {
+			tx.delete(self.config.col_data, &reputation_key(collator_id));
+		} else {
+			tx.put_vec(self.config.col_data, &reputation_key(collator_id), reputation.encode());
+		}
+
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, ?collator_id, "Failed to store collator reputation");
+		}
+	}
+}
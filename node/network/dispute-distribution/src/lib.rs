@@ -0,0 +1,161 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Sending and receiving of `DisputeRequest`s.
+//!
+//! This subsystem essentially consists of two parts:
+//!
+//! - a sender
+//! - and a receiver
+//!
+//! The sender is responsible for getting our vote out to all other validators of the session a
+//! dispute happened in, see [`sender`]. Failed sends are retried on every active leaves update,
+//! until the dispute is no longer active.
+//!
+//! The receiver handles incoming `DisputeRequest`s: it checks the signatures of the contained
+//! votes, imports them into the dispute coordinator and confirms reception to the sender, once
+//! the import succeeded. Each peer may only send a limited number of requests per active leaves
+//! update, see [`receiver`].
+
+use futures::{channel::mpsc, future::Either, FutureExt, StreamExt, TryFutureExt};
+
+use sp_keystore::SyncCryptoStorePtr;
+
+use polkadot_node_subsystem_util::runtime::RuntimeInfo;
+use polkadot_subsystem::{
+	messages::DisputeDistributionMessage, FromOverseer, OverseerSignal, SpawnedSubsystem,
+	Subsystem, SubsystemContext, SubsystemError,
+};
+
+/// Error and [`Result`] type for this subsystem.
+mod error;
+use error::{Fatal, log_error};
+
+/// Sending of our own votes to all other validators of a session.
+mod sender;
+use sender::{DisputeSender, TaskFinish};
+
+/// Handling of incoming dispute requests.
+mod receiver;
+use receiver::DisputesReceiver;
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &'static str = "parachain::dispute-distribution";
+
+/// Capacity of the channel our send tasks use for reporting back to the subsystem.
+const SEND_TASK_CHANNEL_SIZE: usize = 100;
+
+/// The dispute distribution subsystem.
+pub struct DisputeDistributionSubsystem {
+	/// Easy and efficient runtime access for this subsystem.
+	runtime: RuntimeInfo,
+	/// Sender for our dispute requests.
+	disputes_sender: DisputeSender,
+	/// Receiver for messages from our send tasks.
+	sender_rx: mpsc::Receiver<TaskFinish>,
+	/// Handling of incoming dispute requests.
+	disputes_receiver: DisputesReceiver,
+}
+
+impl<Context> Subsystem<Context> for DisputeDistributionSubsystem
+where
+	Context: SubsystemContext<Message = DisputeDistributionMessage> + Sync + Send,
+{
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = self
+			.run(ctx)
+			.map_err(|e| SubsystemError::with_origin("dispute-distribution", e))
+			.boxed();
+
+		SpawnedSubsystem {
+			name: "dispute-distribution-subsystem",
+			future,
+		}
+	}
+}
+
+impl DisputeDistributionSubsystem {
+	/// Create a new instance of the dispute distribution.
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		let runtime = RuntimeInfo::new(Some(keystore));
+		let (tx, sender_rx) = mpsc::channel(SEND_TASK_CHANNEL_SIZE);
+		Self {
+			runtime,
+			disputes_sender: DisputeSender::new(tx),
+			sender_rx,
+			disputes_receiver: DisputesReceiver::new(),
+		}
+	}
+
+	/// Start processing work as passed on from the Overseer.
+	async fn run<Context>(mut self, mut ctx: Context) -> std::result::Result<(), Fatal>
+	where
+		Context: SubsystemContext<Message = DisputeDistributionMessage> + Sync + Send,
+	{
+		loop {
+			let action = {
+				let mut subsystem_next = ctx.recv().fuse();
+				futures::select! {
+					subsystem_msg = subsystem_next => Either::Left(subsystem_msg),
+					from_task = self.sender_rx.next() => Either::Right(from_task),
+				}
+			};
+
+			let message = match action {
+				Either::Left(subsystem_msg) => {
+					subsystem_msg.map_err(|e| Fatal::IncomingMessageChannel(e))?
+				}
+				Either::Right(from_task) => {
+					let from_task = from_task.ok_or(Fatal::SenderExhausted)?;
+					self.disputes_sender.on_task_message(from_task);
+					continue;
+				}
+			};
+
+			match message {
+				FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
+					self.disputes_receiver.update_leaves(&update);
+					log_error(
+						self.disputes_sender.update_leaves(&mut ctx, &mut self.runtime, update).await,
+						"DisputeSender::update_leaves",
+					)?;
+				}
+				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {}
+				FromOverseer::Signal(OverseerSignal::Conclude) => {
+					return Ok(());
+				}
+				FromOverseer::Communication {
+					msg: DisputeDistributionMessage::SendDispute(dispute_msg),
+				} => {
+					log_error(
+						self.disputes_sender.start_sender(&mut ctx, &mut self.runtime, dispute_msg).await,
+						"DisputeSender::start_sender",
+					)?;
+				}
+				FromOverseer::Communication {
+					msg: DisputeDistributionMessage::DisputeSendingRequest(req),
+				} => {
+					log_error(
+						self.disputes_receiver.handle_request(&mut ctx, &mut self.runtime, req).await,
+						"DisputesReceiver::handle_request",
+					)?;
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,174 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Handling of incoming `DisputeRequest`s.
+//!
+//! Votes contained in a request get their signatures checked against the session's validator set
+//! and are then imported into the dispute coordinator. Only once the coordinator reports a
+//! successful import, we confirm reception to the sender - otherwise the sender will retry.
+//!
+//! As anybody can send us requests, we limit the number of requests each peer may send us per
+//! active leaves update. Requests exceeding that limit are rejected and the peer gets punished.
+
+use std::collections::HashMap;
+
+use futures::{channel::oneshot, FutureExt};
+
+use polkadot_node_network_protocol::{
+	PeerId, UnifiedReputationChange as Rep,
+	request_response::{
+		IncomingRequest, request::OutgoingResponse,
+		v1::{DisputeRequest, DisputeResponse},
+	},
+};
+use polkadot_node_subsystem_util::runtime::RuntimeInfo;
+use polkadot_primitives::v1::Hash;
+use polkadot_subsystem::{
+	ActiveLeavesUpdate, SubsystemContext,
+	messages::{AllMessages, DisputeCoordinatorMessage, ImportStatementsResult},
+};
+
+use crate::error::{Fatal, NonFatal, Result};
+use crate::LOG_TARGET;
+
+const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Signatures were invalid.");
+const COST_APPARENT_FLOOD: Rep = Rep::CostMinor("Peer exceeded the rate limit.");
+const COST_INVALID_IMPORT: Rep = Rep::CostMinor("Dispute coordinator refused to import the votes.");
+
+/// How many requests a single peer may send us per active leaves update.
+///
+/// Disputes are rare and every dispute results in a single request per peer (plus retries), so
+/// honest peers should stay way below this limit.
+pub const MAX_REQUESTS_PER_PEER: usize = 10;
+
+/// Receiving side of dispute distribution.
+pub struct DisputesReceiver {
+	/// Most recent active head, used for runtime queries.
+	recent_head: Option<Hash>,
+	/// Number of requests each peer sent us since the last active leaves update.
+	peer_requests: HashMap<PeerId, usize>,
+}
+
+impl DisputesReceiver {
+	/// Create a new `DisputesReceiver`.
+	pub fn new() -> Self {
+		Self {
+			recent_head: None,
+			peer_requests: HashMap::new(),
+		}
+	}
+
+	/// Update the head used for runtime queries and reset rate limits.
+	pub fn update_leaves(&mut self, update: &ActiveLeavesUpdate) {
+		if let Some(leaf) = update.activated.last() {
+			self.recent_head = Some(leaf.hash);
+		} else if self.recent_head.map_or(false, |h| update.deactivated.contains(&h)) {
+			self.recent_head = None;
+		}
+		self.peer_requests.clear();
+	}
+
+	/// Check an incoming request and import its votes into the dispute coordinator.
+	pub async fn handle_request<Context: SubsystemContext>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		req: IncomingRequest<DisputeRequest>,
+	) -> Result<()> {
+		let requests = self.peer_requests.entry(req.peer).or_default();
+		if *requests >= MAX_REQUESTS_PER_PEER {
+			tracing::debug!(
+				target: LOG_TARGET,
+				peer = ?req.peer,
+				"Peer exceeded rate limit for dispute requests, dropping request."
+			);
+			return reject(req, COST_APPARENT_FLOOD)
+		}
+		*requests += 1;
+
+		let head = self.recent_head.ok_or(NonFatal::NoActiveHeads)?;
+		let session_index = req.payload.0.session_index;
+		let info = runtime.get_session_info_by_index(ctx, head, session_index).await?;
+
+		let votes = req.payload.0.clone().try_into_signed_votes(&info.session_info);
+		let (candidate_receipt, valid_vote, invalid_vote) = match votes {
+			Err(()) => {
+				reject(req, COST_INVALID_SIGNATURE)?;
+				return Err(NonFatal::InvalidSignature.into())
+			}
+			Ok(votes) => votes,
+		};
+
+		let (pending_confirmation, confirmation_rx) = oneshot::channel();
+		ctx.send_message(AllMessages::DisputeCoordinator(
+			DisputeCoordinatorMessage::ImportStatements {
+				candidate_hash: candidate_receipt.hash(),
+				candidate_receipt,
+				session: session_index,
+				statements: vec![valid_vote, invalid_vote],
+				pending_confirmation: Some(pending_confirmation),
+			}
+		)).await;
+
+		// Waiting for the import must not block the processing of other requests.
+		ctx.spawn("dispute-import-confirmation", respond_on_import(req, confirmation_rx).boxed())
+			.await
+			.map_err(Fatal::SpawnTask)?;
+
+		Ok(())
+	}
+}
+
+/// Answer the request according to the result of the import.
+async fn respond_on_import(
+	req: IncomingRequest<DisputeRequest>,
+	confirmation_rx: oneshot::Receiver<ImportStatementsResult>,
+) {
+	let peer = req.peer;
+	let result = match confirmation_rx.await {
+		Ok(ImportStatementsResult::ValidImport) =>
+			req.send_response(DisputeResponse::Confirmed).map_err(|_| ()),
+		Ok(ImportStatementsResult::InvalidImport) => reject(req, COST_INVALID_IMPORT).map_err(|_| ()),
+		Err(oneshot::Canceled) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?peer,
+				"Dispute coordinator failed to import votes, not confirming request."
+			);
+			let response = OutgoingResponse {
+				result: Err(()),
+				reputation_changes: Vec::new(),
+				sent_feedback: None,
+			};
+			req.send_outgoing_response(response)
+		}
+	};
+
+	if result.is_err() {
+		tracing::debug!(target: LOG_TARGET, ?peer, "Sending response to dispute request failed.");
+	}
+}
+
+/// Reject a request and punish the peer who sent it.
+fn reject(req: IncomingRequest<DisputeRequest>, rep: Rep) -> Result<()> {
+	let response = OutgoingResponse {
+		result: Err(()),
+		reputation_changes: vec![rep],
+		sent_feedback: None,
+	};
+	req.send_outgoing_response(response)
+		.map_err(|_| NonFatal::SendResponse.into())
+}
@@ -0,0 +1,155 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sending of our own dispute votes.
+//!
+//! For each dispute we take part in, a [`SendTask`] is created, which takes care of getting the
+//! `DisputeRequest` to every validator of the session the dispute happened in. Failed deliveries
+//! are retried on each active leaves update, for as long as the dispute coordinator considers the
+//! dispute active.
+
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+
+use futures::channel::{mpsc, oneshot};
+
+use polkadot_node_network_protocol::request_response::v1::DisputeRequest;
+use polkadot_node_primitives::DisputeMessage;
+use polkadot_node_subsystem_util::runtime::RuntimeInfo;
+use polkadot_primitives::v1::{CandidateHash, Hash, SessionIndex};
+use polkadot_subsystem::{
+	ActiveLeavesUpdate, SubsystemContext,
+	messages::{AllMessages, DisputeCoordinatorMessage},
+};
+
+/// Sending of a single dispute request to all validators of a session.
+mod send_task;
+use send_task::SendTask;
+pub use send_task::{TaskFinish, TaskResult};
+
+use crate::error::{NonFatal, Result};
+use crate::LOG_TARGET;
+
+/// The `DisputeSender` keeps track of all ongoing disputes we need to send our votes for.
+pub struct DisputeSender {
+	/// All heads we currently consider active.
+	active_heads: Vec<Hash>,
+	/// All ongoing dispute sendings this subsystem is aware of.
+	disputes: HashMap<CandidateHash, SendTask>,
+	/// Sender to be cloned for `SendTask`s.
+	tx: mpsc::Sender<TaskFinish>,
+}
+
+impl DisputeSender {
+	/// Create a new `DisputeSender` which can be used to start dispute sendings.
+	pub fn new(tx: mpsc::Sender<TaskFinish>) -> Self {
+		Self {
+			active_heads: Vec::new(),
+			disputes: HashMap::new(),
+			tx,
+		}
+	}
+
+	/// Create a `SendTask` for a particular new dispute.
+	pub async fn start_sender<Context: SubsystemContext>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		msg: DisputeMessage,
+	) -> Result<()> {
+		let req: DisputeRequest = msg.into();
+		let candidate_hash = req.0.candidate_receipt.hash();
+		match self.disputes.entry(candidate_hash) {
+			Entry::Occupied(_) => {
+				tracing::trace!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					"Dispute sending already active."
+				);
+				Ok(())
+			}
+			Entry::Vacant(vacant) => {
+				let send_task = vacant.insert(SendTask::new(self.tx.clone(), req));
+				send_task.refresh_sends(ctx, runtime, &self.active_heads).await
+			}
+		}
+	}
+
+	/// Take care of a change in active leaves.
+	///
+	/// - Drop sendings for disputes which are no longer active.
+	/// - Retry any failed sends.
+	pub async fn update_leaves<Context: SubsystemContext>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		update: ActiveLeavesUpdate,
+	) -> Result<()> {
+		let ActiveLeavesUpdate { activated, deactivated } = update;
+		self.active_heads.retain(|h| !deactivated.contains(h));
+		self.active_heads.extend(activated.into_iter().map(|l| l.hash));
+
+		if self.disputes.is_empty() {
+			return Ok(())
+		}
+
+		let active_disputes: HashSet<CandidateHash> = get_active_disputes(ctx)
+			.await?
+			.into_iter()
+			.map(|(_, candidate_hash)| candidate_hash)
+			.collect();
+
+		// Cleanup obsolete senders:
+		self.disputes.retain(|candidate_hash, _| active_disputes.contains(candidate_hash));
+
+		for send_task in self.disputes.values_mut() {
+			if send_task.has_failed_sends() {
+				send_task.refresh_sends(ctx, runtime, &self.active_heads).await?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Receive message from a sending task.
+	pub fn on_task_message(&mut self, msg: TaskFinish) {
+		let TaskFinish { candidate_hash, receiver, result } = msg;
+
+		let send_task = match self.disputes.get_mut(&candidate_hash) {
+			None => {
+				// Can happen when a dispute ends, with messages still in queue:
+				tracing::trace!(
+					target: LOG_TARGET,
+					?result,
+					"Received `TaskFinish` for non existing dispute."
+				);
+				return
+			}
+			Some(send_task) => send_task,
+		};
+		send_task.on_finished_send(&receiver, result);
+	}
+}
+
+/// Retrieve the currently active disputes from the dispute coordinator.
+async fn get_active_disputes<Context: SubsystemContext>(
+	ctx: &mut Context,
+) -> Result<Vec<(SessionIndex, CandidateHash)>> {
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(AllMessages::DisputeCoordinator(
+		DisputeCoordinatorMessage::ActiveDisputes(tx)
+	)).await;
+	rx.await.map_err(|e| NonFatal::AskActiveDisputesCanceled(e).into())
+}
@@ -0,0 +1,276 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+
+use futures::{Future, FutureExt, SinkExt, channel::mpsc, future::RemoteHandle};
+
+use polkadot_node_network_protocol::request_response::{
+	OutgoingRequest, OutgoingResult, Recipient, Requests,
+	v1::{DisputeRequest, DisputeResponse},
+};
+use polkadot_node_subsystem_util::runtime::RuntimeInfo;
+use polkadot_primitives::v1::{AuthorityDiscoveryId, CandidateHash, Hash};
+use polkadot_subsystem::{
+	SubsystemContext,
+	messages::{AllMessages, IfDisconnected, NetworkBridgeMessage},
+};
+
+use crate::error::{Fatal, NonFatal, Result};
+use crate::LOG_TARGET;
+
+/// Delivery status for a particular dispute.
+///
+/// Keeps track of all the validators that have to be reached for a dispute.
+pub struct SendTask {
+	/// The request we are supposed to get out to all validators of the dispute's session.
+	request: DisputeRequest,
+
+	/// The set of authorities we need to send our message to.
+	deliveries: HashMap<AuthorityDiscoveryId, DeliveryStatus>,
+
+	/// Whether we have any tasks failed since the last refresh.
+	has_failed_sends: bool,
+
+	/// Sender to be cloned for tasks.
+	tx: mpsc::Sender<TaskFinish>,
+}
+
+/// Status of a particular vote/statement delivery to a particular validator.
+enum DeliveryStatus {
+	/// Request is still in flight.
+	Pending(RemoteHandle<()>),
+	/// Succeeded - no need to send request to this peer anymore.
+	Succeeded,
+}
+
+/// A sending task finishes with this result:
+#[derive(Debug)]
+pub struct TaskFinish {
+	/// The candidate this task was running for.
+	pub candidate_hash: CandidateHash,
+	/// The authority the request was sent to.
+	pub receiver: AuthorityDiscoveryId,
+	/// The result of the delivery attempt.
+	pub result: TaskResult,
+}
+
+/// Result of a single delivery attempt.
+#[derive(Debug)]
+pub enum TaskResult {
+	/// Task succeeded in getting the request to its peer.
+	Succeeded,
+	/// Task was not able to get the request out to its peer.
+	///
+	/// It should be retried in that case.
+	Failed,
+}
+
+impl SendTask {
+	/// Create a new `SendTask` for the given request.
+	///
+	/// No requests are sent out, until `refresh_sends` is called.
+	pub fn new(tx: mpsc::Sender<TaskFinish>, request: DisputeRequest) -> Self {
+		Self {
+			request,
+			deliveries: HashMap::new(),
+			// Nothing has been sent yet, so make sure a refresh is not skipped.
+			has_failed_sends: true,
+			tx,
+		}
+	}
+
+	/// Make sure we are sending to all relevant authorities.
+	///
+	/// This function is called right after construction and should also be called on a regular
+	/// basis to ensure we are retrying failed attempts.
+	pub async fn refresh_sends<Context: SubsystemContext>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		active_heads: &[Hash],
+	) -> Result<()> {
+		let new_authorities = self.get_relevant_validators(ctx, runtime, active_heads).await?;
+
+		let add_authorities = new_authorities
+			.iter()
+			.filter(|a| !self.deliveries.contains_key(*a))
+			.cloned()
+			.collect();
+
+		// Get rid of dead/irrelevant tasks/statuses:
+		self.deliveries.retain(|k, _| new_authorities.contains(k));
+
+		// Start any new tasks that are needed:
+		let new_statuses = send_requests(
+			ctx,
+			self.tx.clone(),
+			add_authorities,
+			self.request.clone(),
+		).await?;
+
+		self.deliveries.extend(new_statuses.into_iter());
+		self.has_failed_sends = false;
+		Ok(())
+	}
+
+	/// Whether any sends have failed since the last refresh.
+	pub fn has_failed_sends(&self) -> bool {
+		self.has_failed_sends
+	}
+
+	/// Handle a finished response waiting task.
+	pub fn on_finished_send(&mut self, authority: &AuthorityDiscoveryId, result: TaskResult) {
+		match result {
+			TaskResult::Failed => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					candidate = ?self.request.0.candidate_receipt.hash(),
+					?authority,
+					"Could not get our message out! If this keeps happening, then check chain whether the dispute made it there."
+				);
+				// Remove state, so we know what to try again:
+				self.deliveries.remove(authority);
+				self.has_failed_sends = true;
+			}
+			TaskResult::Succeeded => {
+				let status = match self.deliveries.get_mut(authority) {
+					None => {
+						// Can happen when a sending became irrelevant while the response was
+						// already queued.
+						tracing::debug!(
+							target: LOG_TARGET,
+							candidate = ?self.request.0.candidate_receipt.hash(),
+							?authority,
+							?result,
+							"Received `TaskFinish` for non existing task."
+						);
+						return
+					}
+					Some(status) => status,
+				};
+				// We are done here:
+				*status = DeliveryStatus::Succeeded;
+			}
+		}
+	}
+
+	/// Determine all validators that should receive the given dispute request.
+	///
+	/// This is all parachain validators of the session the candidate occurred, except ourselves.
+	async fn get_relevant_validators<Context: SubsystemContext>(
+		&self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		active_heads: &[Hash],
+	) -> Result<HashSet<AuthorityDiscoveryId>> {
+		let head = *active_heads.first().ok_or(NonFatal::NoActiveHeads)?;
+		let info = runtime.get_session_info_by_index(
+			ctx,
+			head,
+			self.request.0.session_index,
+		).await?;
+		let session_info = &info.session_info;
+		let our_index = info.validator_info.our_index;
+
+		Ok(session_info.discovery_keys
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| Some(*i) != our_index.map(|index| index.0 as usize))
+			.map(|(_, v)| v.clone())
+			.collect()
+		)
+	}
+}
+
+/// Start sending of the given message to all given authorities.
+///
+/// And spawn tasks for handling the response.
+async fn send_requests<Context: SubsystemContext>(
+	ctx: &mut Context,
+	tx: mpsc::Sender<TaskFinish>,
+	receivers: Vec<AuthorityDiscoveryId>,
+	req: DisputeRequest,
+) -> Result<HashMap<AuthorityDiscoveryId, DeliveryStatus>> {
+	let mut statuses = HashMap::with_capacity(receivers.len());
+	let mut reqs = Vec::with_capacity(receivers.len());
+
+	for receiver in receivers {
+		let (outgoing, pending_response) = OutgoingRequest::new(
+			Recipient::Authority(receiver.clone()),
+			req.clone(),
+		);
+
+		reqs.push(Requests::DisputeSending(outgoing));
+
+		let fut = wait_response_task(
+			pending_response,
+			req.0.candidate_receipt.hash(),
+			receiver.clone(),
+			tx.clone(),
+		);
+
+		let (remote, remote_handle) = fut.remote_handle();
+		ctx.spawn("dispute-sender", remote.boxed())
+			.await
+			.map_err(Fatal::SpawnTask)?;
+		statuses.insert(receiver, DeliveryStatus::Pending(remote_handle));
+	}
+
+	if !reqs.is_empty() {
+		let msg = NetworkBridgeMessage::SendRequests(reqs, IfDisconnected::TryConnect);
+		ctx.send_message(AllMessages::NetworkBridge(msg)).await;
+	}
+	Ok(statuses)
+}
+
+/// Future to be spawned in a task for awaiting a response.
+async fn wait_response_task(
+	pending_response: impl Future<Output = OutgoingResult<DisputeResponse>>,
+	candidate_hash: CandidateHash,
+	receiver: AuthorityDiscoveryId,
+	mut tx: mpsc::Sender<TaskFinish>,
+) {
+	let result = pending_response.await;
+	let msg = match result {
+		Err(err) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?receiver,
+				%err,
+				"Error sending dispute statements to node."
+			);
+			TaskFinish { candidate_hash, receiver, result: TaskResult::Failed }
+		}
+		Ok(DisputeResponse::Confirmed) => {
+			tracing::trace!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				?receiver,
+				"Sending dispute message succeeded"
+			);
+			TaskFinish { candidate_hash, receiver, result: TaskResult::Succeeded }
+		}
+	};
+	if let Err(err) = tx.feed(msg).await {
+		tracing::debug!(
+			target: LOG_TARGET,
+			%err,
+			"Failed to notify subsystem about dispute sending result."
+		);
+	}
+}
@@ -0,0 +1,410 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Subsystem unit tests.
+
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use futures::{channel::oneshot, executor, future, Future};
+use parity_scale_codec::{Decode, Encode};
+
+use sc_keystore::LocalKeystore;
+use sp_application_crypto::AppKey;
+use sp_core::testing::TaskExecutor;
+use sp_keyring::Sr25519Keyring;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+
+use polkadot_node_network_protocol::{
+	PeerId,
+	request_response::{
+		IncomingRequest, Requests, network::OutgoingResponse,
+		v1::{DisputeRequest, DisputeResponse},
+	},
+};
+use polkadot_node_primitives::{DisputeMessage, SignedDisputeStatement, UncheckedDisputeMessage};
+use polkadot_primitives::v1::{
+	CandidateHash, CandidateReceipt, DisputeStatement, Hash, InvalidDisputeStatementKind,
+	SessionIndex, SessionInfo, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
+};
+use polkadot_subsystem::{
+	jaeger, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
+	messages::{
+		AllMessages, DisputeCoordinatorMessage, ImportStatementsResult, NetworkBridgeMessage,
+		RuntimeApiMessage, RuntimeApiRequest,
+	},
+};
+use polkadot_subsystem_testhelpers::{make_subsystem_context, TestSubsystemContextHandle};
+
+use super::*;
+use crate::receiver::MAX_REQUESTS_PER_PEER;
+
+type VirtualOverseer = TestSubsystemContextHandle<DisputeDistributionMessage>;
+
+const SESSION: SessionIndex = 1;
+
+struct TestState {
+	validators: Vec<Sr25519Keyring>,
+	validator_public: Vec<ValidatorId>,
+	/// Keystore of the subsystem, containing our (Alice's) key.
+	subsystem_keystore: SyncCryptoStorePtr,
+	candidate_receipt: CandidateReceipt,
+}
+
+impl Default for TestState {
+	fn default() -> TestState {
+		let validators = vec![
+			Sr25519Keyring::Alice,
+			Sr25519Keyring::Bob,
+			Sr25519Keyring::Charlie,
+			Sr25519Keyring::Dave,
+			Sr25519Keyring::Ferdie,
+		];
+
+		let validator_public = validators.iter().map(|k| k.public().into()).collect();
+
+		let store = LocalKeystore::in_memory();
+		SyncCryptoStore::sr25519_generate_new(
+			&store,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Alice.to_seed()),
+		).unwrap();
+
+		let mut candidate_receipt = CandidateReceipt::default();
+		candidate_receipt.descriptor.relay_parent = Hash::repeat_byte(0x42);
+
+		TestState {
+			validators,
+			validator_public,
+			subsystem_keystore: Arc::new(store),
+			candidate_receipt,
+		}
+	}
+}
+
+impl TestState {
+	fn session_info(&self) -> SessionInfo {
+		let discovery_keys = self.validators.iter()
+			.map(|k| <_>::from(k.public()))
+			.collect();
+
+		let assignment_keys = self.validators.iter()
+			.map(|k| <_>::from(k.public()))
+			.collect();
+
+		SessionInfo {
+			validators: self.validator_public.clone(),
+			discovery_keys,
+			assignment_keys,
+			validator_groups: Vec::new(),
+			n_cores: self.validators.len() as _,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 1,
+			n_delay_tranches: 100,
+			no_show_slots: 1,
+			needed_approvals: 10,
+		}
+	}
+
+	fn sign_statement(&self, index: usize, statement: DisputeStatement) -> SignedDisputeStatement {
+		let candidate_hash = self.candidate_receipt.hash();
+		let payload = statement.payload_data(candidate_hash, SESSION);
+		let signature = self.validators[index].sign(&payload[..]);
+
+		SignedDisputeStatement::new_checked(
+			statement,
+			candidate_hash,
+			SESSION,
+			self.validator_public[index].clone(),
+			signature.into(),
+		).unwrap()
+	}
+
+	/// Dispute message with a valid vote of validator `valid` and an invalid vote of `invalid`.
+	fn dispute_message(&self, valid: usize, invalid: usize) -> DisputeMessage {
+		let valid_vote = self.sign_statement(
+			valid,
+			DisputeStatement::Valid(ValidDisputeStatementKind::Explicit),
+		);
+		let invalid_vote = self.sign_statement(
+			invalid,
+			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+		);
+
+		DisputeMessage::from_signed_statements(
+			valid_vote,
+			ValidatorIndex(valid as _),
+			invalid_vote,
+			ValidatorIndex(invalid as _),
+			self.candidate_receipt.clone(),
+			&self.session_info(),
+		).unwrap()
+	}
+}
+
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	keystore: SyncCryptoStorePtr,
+	test: impl FnOnce(VirtualOverseer) -> T,
+) {
+	let pool = TaskExecutor::new();
+	let (ctx, virtual_overseer) = make_subsystem_context(pool);
+
+	let subsystem = DisputeDistributionSubsystem::new(keystore);
+	let subsystem = subsystem.run(ctx);
+
+	let test_fut = async move {
+		let mut virtual_overseer = test(virtual_overseer).await;
+		virtual_overseer.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(test_fut, subsystem)).1.unwrap();
+}
+
+async fn activate_leaf(virtual_overseer: &mut VirtualOverseer, hash: Hash, number: u32) {
+	virtual_overseer.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+		ActiveLeavesUpdate::start_work(ActivatedLeaf {
+			hash,
+			span: Arc::new(jaeger::Span::Disabled),
+			number,
+			status: LeafStatus::Fresh,
+		})
+	))).await;
+}
+
+async fn answer_session_info(virtual_overseer: &mut VirtualOverseer, session_info: SessionInfo) {
+	assert_matches!(
+		virtual_overseer.recv().await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			_,
+			RuntimeApiRequest::SessionInfo(session, tx),
+		)) => {
+			assert_eq!(session, SESSION);
+			tx.send(Ok(Some(session_info))).unwrap();
+		}
+	);
+}
+
+/// Send an incoming `DisputeRequest` to the subsystem and return the receiver of the response.
+async fn send_incoming_request(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	message: DisputeMessage,
+) -> oneshot::Receiver<OutgoingResponse> {
+	let (tx, rx) = oneshot::channel();
+	let req = IncomingRequest::new(peer, DisputeRequest::from(message), tx);
+	virtual_overseer.send(FromOverseer::Communication {
+		msg: DisputeDistributionMessage::DisputeSendingRequest(req),
+	}).await;
+	rx
+}
+
+/// Expect an import of the votes and return the sender for confirming it.
+async fn expect_import(
+	virtual_overseer: &mut VirtualOverseer,
+	candidate_hash: CandidateHash,
+) -> oneshot::Sender<ImportStatementsResult> {
+	assert_matches!(
+		virtual_overseer.recv().await,
+		AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ImportStatements {
+			candidate_hash: c_hash,
+			session,
+			statements,
+			pending_confirmation: Some(pending_confirmation),
+			..
+		}) => {
+			assert_eq!(c_hash, candidate_hash);
+			assert_eq!(session, SESSION);
+			assert_eq!(statements.len(), 2);
+			pending_confirmation
+		}
+	)
+}
+
+#[test]
+fn send_dispute_sends_to_all_other_validators() {
+	let test_state = TestState::default();
+	test_harness(test_state.subsystem_keystore.clone(), |mut virtual_overseer| async move {
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(1), 1).await;
+
+		let message = test_state.dispute_message(1, 2);
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeDistributionMessage::SendDispute(message.clone()),
+		}).await;
+
+		answer_session_info(&mut virtual_overseer, test_state.session_info()).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(reqs, _)) => {
+				// Everybody, but us:
+				assert_eq!(reqs.len(), test_state.validators.len() - 1);
+				for req in reqs {
+					let req = assert_matches!(req, Requests::DisputeSending(req) => req);
+					assert_eq!(req.payload.encode(), DisputeRequest::from(message.clone()).encode());
+					req.pending_response.send(Ok(DisputeResponse::Confirmed.encode())).unwrap();
+				}
+			}
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn incoming_request_gets_imported_and_confirmed() {
+	let test_state = TestState::default();
+	test_harness(test_state.subsystem_keystore.clone(), |mut virtual_overseer| async move {
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(1), 1).await;
+
+		let rx = send_incoming_request(
+			&mut virtual_overseer,
+			PeerId::random(),
+			test_state.dispute_message(1, 2),
+		).await;
+
+		answer_session_info(&mut virtual_overseer, test_state.session_info()).await;
+		expect_import(&mut virtual_overseer, test_state.candidate_receipt.hash()).await
+			.send(ImportStatementsResult::ValidImport)
+			.unwrap();
+
+		let response = rx.await.unwrap();
+		assert!(response.reputation_changes.is_empty());
+		assert_eq!(
+			DisputeResponse::decode(&mut response.result.unwrap().as_slice()).unwrap(),
+			DisputeResponse::Confirmed,
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn incoming_request_is_not_confirmed_without_import() {
+	let test_state = TestState::default();
+	test_harness(test_state.subsystem_keystore.clone(), |mut virtual_overseer| async move {
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(1), 1).await;
+
+		// The coordinator refuses the votes:
+		let rx = send_incoming_request(
+			&mut virtual_overseer,
+			PeerId::random(),
+			test_state.dispute_message(1, 2),
+		).await;
+
+		answer_session_info(&mut virtual_overseer, test_state.session_info()).await;
+		expect_import(&mut virtual_overseer, test_state.candidate_receipt.hash()).await
+			.send(ImportStatementsResult::InvalidImport)
+			.unwrap();
+
+		let response = rx.await.unwrap();
+		assert!(response.result.is_err());
+		assert_eq!(response.reputation_changes.len(), 1);
+
+		// The import fails altogether, e.g. because of a DB error:
+		let rx = send_incoming_request(
+			&mut virtual_overseer,
+			PeerId::random(),
+			test_state.dispute_message(1, 2),
+		).await;
+
+		drop(expect_import(&mut virtual_overseer, test_state.candidate_receipt.hash()).await);
+
+		let response = rx.await.unwrap();
+		assert!(response.result.is_err());
+		assert!(response.reputation_changes.is_empty());
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn incoming_request_with_invalid_signature_gets_punished() {
+	let test_state = TestState::default();
+	test_harness(test_state.subsystem_keystore.clone(), |mut virtual_overseer| async move {
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(1), 1).await;
+
+		// Claim the valid vote came from Dave, although Bob signed it:
+		let mut message: UncheckedDisputeMessage = test_state.dispute_message(1, 2).into();
+		message.valid_vote.validator_index = ValidatorIndex(3);
+
+		let (tx, rx) = oneshot::channel();
+		let req = IncomingRequest::new(PeerId::random(), DisputeRequest(message), tx);
+		virtual_overseer.send(FromOverseer::Communication {
+			msg: DisputeDistributionMessage::DisputeSendingRequest(req),
+		}).await;
+
+		answer_session_info(&mut virtual_overseer, test_state.session_info()).await;
+
+		let response = rx.await.unwrap();
+		assert!(response.result.is_err());
+		assert_eq!(response.reputation_changes.len(), 1);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn flooding_peer_gets_rate_limited() {
+	let test_state = TestState::default();
+	test_harness(test_state.subsystem_keystore.clone(), |mut virtual_overseer| async move {
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(1), 1).await;
+
+		let peer = PeerId::random();
+		let candidate_hash = test_state.candidate_receipt.hash();
+
+		for i in 0..MAX_REQUESTS_PER_PEER {
+			let rx = send_incoming_request(
+				&mut virtual_overseer,
+				peer.clone(),
+				test_state.dispute_message(1, 2),
+			).await;
+			// Session info gets cached after the first request:
+			if i == 0 {
+				answer_session_info(&mut virtual_overseer, test_state.session_info()).await;
+			}
+			expect_import(&mut virtual_overseer, candidate_hash).await
+				.send(ImportStatementsResult::ValidImport)
+				.unwrap();
+			assert!(rx.await.unwrap().result.is_ok());
+		}
+
+		let rx = send_incoming_request(
+			&mut virtual_overseer,
+			peer.clone(),
+			test_state.dispute_message(1, 2),
+		).await;
+		let response = rx.await.unwrap();
+		assert!(response.result.is_err());
+		assert_eq!(response.reputation_changes.len(), 1);
+
+		// Limits get reset on new leaves:
+		activate_leaf(&mut virtual_overseer, Hash::repeat_byte(2), 2).await;
+		let rx = send_incoming_request(
+			&mut virtual_overseer,
+			peer,
+			test_state.dispute_message(1, 2),
+		).await;
+		expect_import(&mut virtual_overseer, candidate_hash).await
+			.send(ImportStatementsResult::ValidImport)
+			.unwrap();
+		assert!(rx.await.unwrap().result.is_ok());
+
+		virtual_overseer
+	});
+}
@@ -407,6 +407,13 @@ pub mod v1 {
 		/// A collation sent to a validator was seconded.
 		#[codec(index = 4)]
 		CollationSeconded(Hash, UncheckedSignedFullStatement),
+		/// Advertise a specific collation to a validator, identified by the relay parent, the
+		/// candidate hash and the hash of the parent head data it builds on. Multiple collations
+		/// may be advertised per relay parent this way, they can be fetched with
+		/// `CollationFetchingV2Request`. Can only be sent once the peer has declared that they
+		/// are a collator with given ID.
+		#[codec(index = 5)]
+		AdvertiseCollationV2(Hash, CandidateHash, Hash),
 	}
 
 	/// All network messages on the validation peer-set.
@@ -447,3 +454,195 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// v2 protocol types.
+///
+/// Only statement distribution has changed since v1, the messages of the other subsystems on the
+/// validation peer-set are those of v1.
+pub mod v2 {
+	use parity_scale_codec::{Encode, Decode};
+	use std::convert::TryFrom;
+
+	use polkadot_primitives::v1::{
+		CandidateHash, CompactStatement, Hash, UncheckedSignedStatement, ValidatorIndex,
+	};
+
+	use polkadot_node_primitives::UncheckedSignedFullStatement;
+
+	pub use super::v1::{
+		ApprovalDistributionMessage, BitfieldDistributionMessage, StatementMetadata,
+	};
+
+	use super::v1;
+
+	/// Network messages used by the statement distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum StatementDistributionMessage {
+		/// A signed full statement under a given relay-parent.
+		#[codec(index = 0)]
+		Statement(Hash, UncheckedSignedFullStatement),
+		/// Seconded statement with large payload (e.g. containing a runtime upgrade).
+		///
+		/// We only gossip the hash in that case, actual payloads can be fetched from sending node
+		/// via req/response.
+		#[codec(index = 1)]
+		LargeStatement(StatementMetadata),
+		/// A statement in its compact form, not carrying the candidate it is about.
+		///
+		/// Only sent after the peer has received a `Manifest` for the candidate or sent us any
+		/// message about it.
+		#[codec(index = 2)]
+		CompactStatement(Hash, UncheckedSignedStatement),
+		/// Announcement of a candidate the sender holds the full receipt of. The receipt can be
+		/// fetched from the sender via req/response.
+		#[codec(index = 3)]
+		Manifest(CandidateManifest),
+	}
+
+	/// Announcement of a candidate under a given relay-parent.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, Hash)]
+	pub struct CandidateManifest {
+		/// Relay parent the candidate is relevant under.
+		pub relay_parent: Hash,
+		/// Hash of the announced candidate.
+		pub candidate_hash: CandidateHash,
+	}
+
+	impl StatementDistributionMessage {
+		/// Get meta data of the given `StatementDistributionMessage`.
+		///
+		/// Returns `None` for messages not containing a statement.
+		pub fn get_metadata(&self) -> Option<StatementMetadata> {
+			match self {
+				Self::Statement(relay_parent, statement) => Some(StatementMetadata {
+					relay_parent: *relay_parent,
+					candidate_hash: statement.unchecked_payload().candidate_hash(),
+					signed_by: statement.unchecked_validator_index(),
+					signature: statement.unchecked_signature().clone(),
+				}),
+				Self::LargeStatement(metadata) => Some(metadata.clone()),
+				Self::CompactStatement(relay_parent, statement) => Some(StatementMetadata {
+					relay_parent: *relay_parent,
+					candidate_hash: *statement.unchecked_payload().candidate_hash(),
+					signed_by: statement.unchecked_validator_index(),
+					signature: statement.unchecked_signature().clone(),
+				}),
+				Self::Manifest(_) => None,
+			}
+		}
+
+		/// Get fingerprint describing the contained statement uniquely.
+		///
+		/// Returns `None` for messages not containing a statement.
+		pub fn get_fingerprint(&self) -> Option<(CompactStatement, ValidatorIndex)> {
+			match self {
+				Self::Statement(_, statement) => Some(
+					(statement.unchecked_payload().to_compact(), statement.unchecked_validator_index())
+				),
+				Self::LargeStatement(meta) =>
+					Some((CompactStatement::Seconded(meta.candidate_hash), meta.signed_by)),
+				Self::CompactStatement(_, statement) => Some(
+					(statement.unchecked_payload().clone(), statement.unchecked_validator_index())
+				),
+				Self::Manifest(_) => None,
+			}
+		}
+
+		/// Get contained relay parent.
+		pub fn get_relay_parent(&self) -> Hash {
+			match self {
+				Self::Statement(r, _) => *r,
+				Self::LargeStatement(meta) => meta.relay_parent,
+				Self::CompactStatement(r, _) => *r,
+				Self::Manifest(manifest) => manifest.relay_parent,
+			}
+		}
+
+		/// Whether or not this message contains a large statement.
+		pub fn is_large_statement(&self) -> bool {
+			if let Self::LargeStatement(_) = self {
+				true
+			} else {
+				false
+			}
+		}
+
+		/// Whether this message contains a `Seconded` statement without the candidate, which
+		/// has to be fetched separately.
+		pub fn requires_candidate_fetch(&self) -> bool {
+			match self {
+				Self::LargeStatement(_) => true,
+				Self::CompactStatement(_, statement) =>
+					matches!(statement.unchecked_payload(), CompactStatement::Seconded(_)),
+				Self::Statement(..) | Self::Manifest(_) => false,
+			}
+		}
+	}
+
+	impl From<v1::StatementDistributionMessage> for StatementDistributionMessage {
+		fn from(message: v1::StatementDistributionMessage) -> Self {
+			match message {
+				v1::StatementDistributionMessage::Statement(relay_parent, statement) =>
+					Self::Statement(relay_parent, statement),
+				v1::StatementDistributionMessage::LargeStatement(metadata) =>
+					Self::LargeStatement(metadata),
+			}
+		}
+	}
+
+	impl TryFrom<StatementDistributionMessage> for v1::StatementDistributionMessage {
+		type Error = crate::WrongVariant;
+
+		fn try_from(message: StatementDistributionMessage) -> Result<Self, Self::Error> {
+			match message {
+				StatementDistributionMessage::Statement(relay_parent, statement) =>
+					Ok(Self::Statement(relay_parent, statement)),
+				StatementDistributionMessage::LargeStatement(metadata) =>
+					Ok(Self::LargeStatement(metadata)),
+				StatementDistributionMessage::CompactStatement(..) |
+				StatementDistributionMessage::Manifest(_) => Err(crate::WrongVariant),
+			}
+		}
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+
+	impl_try_from!(ValidationProtocol, BitfieldDistribution, BitfieldDistributionMessage);
+	impl_try_from!(ValidationProtocol, StatementDistribution, StatementDistributionMessage);
+	impl_try_from!(ValidationProtocol, ApprovalDistribution, ApprovalDistributionMessage);
+
+	impl From<v1::ValidationProtocol> for ValidationProtocol {
+		fn from(message: v1::ValidationProtocol) -> Self {
+			match message {
+				v1::ValidationProtocol::BitfieldDistribution(m) => Self::BitfieldDistribution(m),
+				v1::ValidationProtocol::StatementDistribution(m) => Self::StatementDistribution(m.into()),
+				v1::ValidationProtocol::ApprovalDistribution(m) => Self::ApprovalDistribution(m),
+			}
+		}
+	}
+
+	impl TryFrom<ValidationProtocol> for v1::ValidationProtocol {
+		type Error = crate::WrongVariant;
+
+		fn try_from(message: ValidationProtocol) -> Result<Self, Self::Error> {
+			Ok(match message {
+				ValidationProtocol::BitfieldDistribution(m) => Self::BitfieldDistribution(m),
+				ValidationProtocol::StatementDistribution(m) =>
+					Self::StatementDistribution(TryFrom::try_from(m)?),
+				ValidationProtocol::ApprovalDistribution(m) => Self::ApprovalDistribution(m),
+			})
+		}
+	}
+}
@@ -20,6 +20,19 @@ use sc_network::config::{NonDefaultSetConfig, SetConfig};
 use std::{borrow::Cow, ops::{Index, IndexMut}};
 use strum::{EnumIter, IntoEnumIterator};
 
+/// The version of a notifications protocol, as negotiated with a peer.
+pub type ProtocolVersion = u32;
+
+/// The first version of the validation and collation protocols.
+pub const PROTOCOL_V1: ProtocolVersion = 1;
+
+/// The second version of the validation protocol, using grid routing and compact statements in
+/// statement distribution.
+pub const VALIDATION_PROTOCOL_V2: ProtocolVersion = 2;
+
+/// The second version of the collation protocol, advertising collations by candidate hash.
+pub const COLLATION_PROTOCOL_V2: ProtocolVersion = 2;
+
 /// The peer-sets and thus the protocols which are used for the network.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
 pub enum PeerSet {
@@ -53,7 +66,10 @@ impl PeerSet {
 		match self {
 			PeerSet::Validation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names: (PROTOCOL_V1..VALIDATION_PROTOCOL_V2)
+					.rev()
+					.filter_map(|version| self.get_versioned_protocol_name(version))
+					.collect(),
 				max_notification_size,
 				set_config: sc_network::config::SetConfig {
 					// we allow full nodes to connect to validators for gossip
@@ -68,7 +84,10 @@ impl PeerSet {
 			},
 			PeerSet::Collation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names: (PROTOCOL_V1..COLLATION_PROTOCOL_V2)
+					.rev()
+					.filter_map(|version| self.get_versioned_protocol_name(version))
+					.collect(),
 				max_notification_size,
 				set_config: SetConfig {
 					// Non-authority nodes don't need to accept incoming connections on this peer set:
@@ -85,11 +104,30 @@ impl PeerSet {
 		}
 	}
 
-	/// Get the protocol name associated with each peer set as static str.
+	/// Get the main protocol version of each peer set, i.e. the most recent one we speak.
+	pub const fn get_main_version(self) -> ProtocolVersion {
+		match self {
+			PeerSet::Validation => VALIDATION_PROTOCOL_V2,
+			PeerSet::Collation => COLLATION_PROTOCOL_V2,
+		}
+	}
+
+	/// Get the main protocol name associated with each peer set as static str.
 	pub const fn get_protocol_name_static(self) -> &'static str {
 		match self {
-			PeerSet::Validation => "/polkadot/validation/1",
-			PeerSet::Collation => "/polkadot/collation/1",
+			PeerSet::Validation => "/polkadot/validation/2",
+			PeerSet::Collation => "/polkadot/collation/2",
+		}
+	}
+
+	/// Get the protocol name of the given version of the peer set, if we speak that version.
+	pub fn get_versioned_protocol_name(self, version: ProtocolVersion) -> Option<Cow<'static, str>> {
+		match (self, version) {
+			(PeerSet::Validation, PROTOCOL_V1) => Some("/polkadot/validation/1".into()),
+			(PeerSet::Validation, VALIDATION_PROTOCOL_V2) => Some(self.into_protocol_name()),
+			(PeerSet::Collation, PROTOCOL_V1) => Some("/polkadot/collation/1".into()),
+			(PeerSet::Collation, COLLATION_PROTOCOL_V2) => Some(self.into_protocol_name()),
+			_ => None,
 		}
 	}
 
@@ -106,6 +144,16 @@ impl PeerSet {
 			_ => None,
 		}
 	}
+
+	/// Try parsing any protocol name we speak, including fallbacks, into a peer set and the
+	/// protocol version.
+	pub fn try_get_protocol(name: &Cow<'static, str>) -> Option<(PeerSet, ProtocolVersion)> {
+		PeerSet::iter()
+			.flat_map(|peer_set| (PROTOCOL_V1..=peer_set.get_main_version()).map(move |v| (peer_set, v)))
+			.find(|(peer_set, version)| {
+				peer_set.get_versioned_protocol_name(*version).map_or(false, |n| &n == name)
+			})
+	}
 }
 
 /// A small and nifty collection that allows to store data pertaining to each peer set.
@@ -61,12 +61,16 @@ pub enum Protocol {
 	ChunkFetching,
 	/// Protocol for fetching collations from collators.
 	CollationFetching,
+	/// Protocol for fetching a specific collation from collators, identified by candidate hash.
+	CollationFetchingV2,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
 	PoVFetching,
 	/// Protocol for fetching available data.
 	AvailableDataFetching,
 	/// Fetching of statements that are too large for gossip.
 	StatementFetching,
+	/// Sending of dispute statements with application level confirmations.
+	DisputeSending,
 }
 
 
@@ -102,6 +106,10 @@ const STATEMENTS_TIMEOUT: Duration = Duration::from_secs(1);
 /// to have 3 slow noded connected, to delay transfer for others by `STATEMENTS_TIMEOUT`.
 pub const MAX_PARALLEL_STATEMENT_REQUESTS: u32 = 3;
 
+/// Disputes need to be imported by the receiver before it confirms the request, so we allow for
+/// quite some time. This is fine, as disputes are rare and the sender retries on failure anyway.
+const DISPUTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(12);
+
 impl Protocol {
 	/// Get a configuration for a given Request response protocol.
 	///
@@ -127,7 +135,7 @@ impl Protocol {
 				request_timeout: DEFAULT_REQUEST_TIMEOUT_CONNECTED,
 				inbound_queue: Some(tx),
 			},
-			Protocol::CollationFetching => RequestResponseConfig {
+			Protocol::CollationFetching | Protocol::CollationFetchingV2 => RequestResponseConfig {
 				name: p_name,
 				max_request_size: 1_000,
 				max_response_size: MAX_POV_SIZE as u64 + 1000,
@@ -168,6 +176,16 @@ impl Protocol {
 				request_timeout: Duration::from_secs(1),
 				inbound_queue: Some(tx),
 			},
+			Protocol::DisputeSending => RequestResponseConfig {
+				name: p_name,
+				// A dispute request consists of a candidate receipt and two votes, so this is
+				// plenty.
+				max_request_size: 8_000,
+				// Responses are just confirmations.
+				max_response_size: 100,
+				request_timeout: DISPUTE_REQUEST_TIMEOUT,
+				inbound_queue: Some(tx),
+			},
 		};
 		(rx, cfg)
 	}
@@ -182,7 +200,7 @@ impl Protocol {
 			// as well.
 			Protocol::ChunkFetching => 100,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
-			Protocol::CollationFetching => 10,
+			Protocol::CollationFetching | Protocol::CollationFetchingV2 => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::PoVFetching => 10,
 			// Validators are constantly self-selecting to request available data which may lead
@@ -208,6 +226,10 @@ impl Protocol {
 				);
 				size as usize
 			}
+			// Incoming requests can get bursty, we should also be able to handle them fast on
+			// average, so something in the ballpark of 100 should be fine. Nodes will retry on
+			// failure, so having a good value here is mostly about performance tuning.
+			Protocol::DisputeSending => 100,
 		}
 	}
 
@@ -221,9 +243,11 @@ impl Protocol {
 		match self {
 			Protocol::ChunkFetching => "/polkadot/req_chunk/1",
 			Protocol::CollationFetching => "/polkadot/req_collation/1",
+			Protocol::CollationFetchingV2 => "/polkadot/req_collation/2",
 			Protocol::PoVFetching => "/polkadot/req_pov/1",
 			Protocol::AvailableDataFetching => "/polkadot/req_available_data/1",
 			Protocol::StatementFetching => "/polkadot/req_statement/1",
+			Protocol::DisputeSending => "/polkadot/send_dispute/1",
 		}
 	}
 }
@@ -0,0 +1,207 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Grid topology used for routing statements to peers on the second version of the validation
+//! protocol.
+//!
+//! The validators of a session are placed on a grid of `ceil(sqrt(n))` columns, by validator index.
+//! A validator sends its own statements to all validators sharing its row or its column. Statements
+//! received from a validator in our row are forwarded along our column and vice versa, so every
+//! validator is reached within two hops, while each validator only talks to `O(sqrt(n))` others.
+
+use std::collections::HashMap;
+
+use polkadot_primitives::v1::{AuthorityDiscoveryId, ValidatorIndex};
+
+/// The grid of all validators in a session.
+pub(crate) struct SessionGrid {
+	/// The position of every authority in the grid.
+	authorities: HashMap<AuthorityDiscoveryId, ValidatorIndex>,
+	/// Our neighbors, if we are a validator in the session.
+	our_neighbors: Option<GridNeighbors>,
+}
+
+impl SessionGrid {
+	/// Build the grid from the discovery keys of the session, ordered by validator index.
+	pub(crate) fn new(discovery_keys: &[AuthorityDiscoveryId], our_index: Option<ValidatorIndex>) -> Self {
+		let authorities = discovery_keys.iter()
+			.enumerate()
+			.map(|(i, key)| (key.clone(), ValidatorIndex(i as u32)))
+			.collect();
+
+		SessionGrid {
+			authorities,
+			our_neighbors: our_index.map(|our_index| GridNeighbors::new(our_index, discovery_keys.len())),
+		}
+	}
+
+	/// Whether a statement signed by `originator` should be sent by us to the given peer.
+	///
+	/// Peers which are not validators in the session are never routed to.
+	pub(crate) fn routes_to_peer(
+		&self,
+		originator: ValidatorIndex,
+		peer_authority: Option<&AuthorityDiscoveryId>,
+	) -> bool {
+		let target = peer_authority.and_then(|a| self.authorities.get(a));
+		match (&self.our_neighbors, target) {
+			(Some(neighbors), Some(target)) => neighbors.routes_to(originator, *target),
+			_ => false,
+		}
+	}
+}
+
+/// Our position in the grid.
+#[derive(Debug, Clone)]
+struct GridNeighbors {
+	our_index: ValidatorIndex,
+	n_validators: usize,
+	/// The number of columns of the grid.
+	side: usize,
+}
+
+impl GridNeighbors {
+	fn new(our_index: ValidatorIndex, n_validators: usize) -> Self {
+		let mut side = (n_validators as f64).sqrt() as usize;
+		while side * side < n_validators {
+			side += 1;
+		}
+
+		GridNeighbors { our_index, n_validators, side: side.max(1) }
+	}
+
+	fn row(&self, index: ValidatorIndex) -> usize {
+		index.0 as usize / self.side
+	}
+
+	fn column(&self, index: ValidatorIndex) -> usize {
+		index.0 as usize % self.side
+	}
+
+	fn shares_row(&self, index: ValidatorIndex) -> bool {
+		self.row(index) == self.row(self.our_index)
+	}
+
+	fn shares_column(&self, index: ValidatorIndex) -> bool {
+		self.column(index) == self.column(self.our_index)
+	}
+
+	/// Whether a statement signed by `originator` should be sent by us to `target`.
+	fn routes_to(&self, originator: ValidatorIndex, target: ValidatorIndex) -> bool {
+		if target == self.our_index || target == originator || target.0 as usize >= self.n_validators {
+			return false
+		}
+
+		if originator == self.our_index {
+			self.shares_row(target) || self.shares_column(target)
+		} else if self.shares_row(originator) {
+			self.shares_column(target)
+		} else if self.shares_column(originator) {
+			self.shares_row(target)
+		} else {
+			false
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	fn reached_by(originator: ValidatorIndex, n_validators: usize) -> HashSet<ValidatorIndex> {
+		let all = (0..n_validators as u32).map(ValidatorIndex);
+		let first_hop: Vec<_> = all.clone()
+			.filter(|t| GridNeighbors::new(originator, n_validators).routes_to(originator, *t))
+			.collect();
+
+		let mut reached: HashSet<_> = first_hop.iter().copied().collect();
+		for hop in first_hop {
+			let neighbors = GridNeighbors::new(hop, n_validators);
+			reached.extend(all.clone().filter(|t| neighbors.routes_to(originator, *t)));
+		}
+		reached
+	}
+
+	#[test]
+	fn originator_sends_to_row_and_column() {
+		// 0 1 2
+		// 3 4 5
+		// 6 7 8
+		let neighbors = GridNeighbors::new(ValidatorIndex(4), 9);
+		let targets: HashSet<_> = (0..9).map(ValidatorIndex)
+			.filter(|t| neighbors.routes_to(ValidatorIndex(4), *t))
+			.collect();
+
+		assert_eq!(targets, [1, 3, 5, 7].iter().copied().map(ValidatorIndex).collect());
+	}
+
+	#[test]
+	fn forwards_along_other_dimension() {
+		let neighbors = GridNeighbors::new(ValidatorIndex(4), 9);
+
+		// From our row, forward along our column:
+		assert!(neighbors.routes_to(ValidatorIndex(3), ValidatorIndex(1)));
+		assert!(neighbors.routes_to(ValidatorIndex(3), ValidatorIndex(7)));
+		assert!(!neighbors.routes_to(ValidatorIndex(3), ValidatorIndex(5)));
+
+		// From our column, forward along our row:
+		assert!(neighbors.routes_to(ValidatorIndex(1), ValidatorIndex(3)));
+		assert!(neighbors.routes_to(ValidatorIndex(1), ValidatorIndex(5)));
+		assert!(!neighbors.routes_to(ValidatorIndex(1), ValidatorIndex(7)));
+
+		// Neither - don't forward at all:
+		assert!(!neighbors.routes_to(ValidatorIndex(0), ValidatorIndex(1)));
+		assert!(!neighbors.routes_to(ValidatorIndex(0), ValidatorIndex(3)));
+	}
+
+	#[test]
+	fn all_validators_reached_within_two_hops() {
+		for n_validators in [1, 2, 3, 7, 9, 10, 17, 100, 101].iter().copied() {
+			for originator in (0..n_validators as u32).map(ValidatorIndex) {
+				let mut expected: HashSet<_> = (0..n_validators as u32).map(ValidatorIndex).collect();
+				expected.remove(&originator);
+
+				assert_eq!(
+					reached_by(originator, n_validators),
+					expected,
+					"n_validators: {}, originator: {:?}",
+					n_validators,
+					originator,
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn peers_outside_the_session_are_not_routed_to() {
+		use sp_keyring::Sr25519Keyring;
+
+		let keys: Vec<AuthorityDiscoveryId> = [Sr25519Keyring::Alice, Sr25519Keyring::Bob]
+			.iter()
+			.map(|k| k.public().into())
+			.collect();
+		let stranger: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+
+		let grid = SessionGrid::new(&keys, Some(ValidatorIndex(0)));
+		assert!(grid.routes_to_peer(ValidatorIndex(0), Some(&keys[1])));
+		assert!(!grid.routes_to_peer(ValidatorIndex(0), Some(&stranger)));
+		assert!(!grid.routes_to_peer(ValidatorIndex(0), None));
+
+		let grid = SessionGrid::new(&keys, None);
+		assert!(!grid.routes_to_peer(ValidatorIndex(0), Some(&keys[1])));
+	}
+}
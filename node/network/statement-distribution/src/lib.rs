@@ -45,10 +45,10 @@ use polkadot_primitives::v1::{
 use polkadot_node_network_protocol::{
 	IfDisconnected, PeerId, UnifiedReputationChange as Rep, View,
 	peer_set::{
-		IsAuthority, PeerSet
+		IsAuthority, PeerSet, ProtocolVersion, VALIDATION_PROTOCOL_V2,
 	},
-	v1::{
-		self as protocol_v1, StatementMetadata
+	v2::{
+		self as protocol_v2, StatementMetadata
 	}
 };
 
@@ -59,6 +59,7 @@ use sp_keystore::SyncCryptoStorePtr;
 use util::{Fault, runtime::RuntimeInfo};
 
 use std::collections::{HashMap, HashSet, hash_map::Entry};
+use std::sync::Arc;
 
 mod error;
 pub use error::{Error, NonFatal, Fatal, Result};
@@ -71,6 +72,10 @@ use requester::{RequesterMessage, fetch};
 mod responder;
 use responder::{ResponderMessage, respond};
 
+/// Grid topology for routing statements to peers on the second protocol version.
+mod grid;
+use grid::SessionGrid;
+
 const COST_UNEXPECTED_STATEMENT: Rep = Rep::CostMinor("Unexpected Statement");
 const COST_FETCH_FAIL: Rep = Rep::CostMinor("Requesting `CommittedCandidateReceipt` from peer failed");
 const COST_INVALID_SIGNATURE: Rep = Rep::CostMajor("Invalid Statement Signature");
@@ -197,6 +202,8 @@ struct PeerRelayParentKnowledge {
 	seconded_counts: HashMap<ValidatorIndex, VcPerPeerTracker>,
 	/// How many statements we've received for each candidate that we're aware of.
 	received_message_count: HashMap<CandidateHash, usize>,
+	/// Candidates the peer announced to us via `Manifest`.
+	received_manifests: HashSet<CandidateHash>,
 
 
 	/// How many large statements this peer already sent us.
@@ -342,6 +349,36 @@ impl PeerRelayParentKnowledge {
 		Ok(self.received_candidates.insert(candidate_hash.clone()))
 	}
 
+	/// Note that we are announcing a candidate to the peer via `Manifest`.
+	///
+	/// Returns `true` if the peer was not aware of the candidate before, in which case the
+	/// manifest needs to be sent.
+	fn send_manifest(&mut self, candidate_hash: CandidateHash) -> bool {
+		if self.is_known_candidate(&candidate_hash) {
+			return false
+		}
+		self.sent_candidates.insert(candidate_hash)
+	}
+
+	/// Note a `Manifest` received from the peer.
+	///
+	/// At most `max_manifests` manifests are accepted per relay-parent. This returns `Ok(true)`
+	/// if this is the first time the peer has become aware of the candidate.
+	fn receive_manifest(
+		&mut self,
+		candidate_hash: CandidateHash,
+		max_manifests: usize,
+	) -> std::result::Result<bool, Rep> {
+		if self.received_manifests.contains(&candidate_hash) {
+			return Err(COST_DUPLICATE_STATEMENT);
+		}
+		if self.received_manifests.len() >= max_manifests {
+			return Err(COST_APPARENT_FLOOD);
+		}
+		self.received_manifests.insert(candidate_hash);
+		Ok(self.received_candidates.insert(candidate_hash))
+	}
+
 	/// Note a received large statement metadata.
 	fn receive_large_statement(&mut self) -> std::result::Result<(), Rep> {
 		if self.large_statement_count >= MAX_LARGE_STATEMENTS_PER_SENDER {
@@ -408,9 +445,46 @@ struct PeerData {
 	view_knowledge: HashMap<Hash, PeerRelayParentKnowledge>,
 	// Peer might be an authority.
 	maybe_authority: Option<AuthorityDiscoveryId>,
+	/// The version of the validation protocol the peer speaks.
+	protocol_version: ProtocolVersion,
 }
 
 impl PeerData {
+	/// Whether statements are routed to the peer along the grid, in compact form.
+	fn uses_grid(&self) -> bool {
+		self.protocol_version >= VALIDATION_PROTOCOL_V2
+	}
+
+	/// Check for candidates that the peer is aware of at the given relay-parent.
+	fn is_known_candidate(&self, relay_parent: &Hash, candidate_hash: &CandidateHash) -> bool {
+		self.view_knowledge
+			.get(relay_parent)
+			.map_or(false, |k| k.is_known_candidate(candidate_hash))
+	}
+
+	/// Note that we are announcing a candidate to the peer via `Manifest`.
+	///
+	/// Returns `None` if the relay-parent is not in the peer's view, otherwise whether the peer
+	/// was not aware of the candidate before.
+	fn send_manifest(&mut self, relay_parent: &Hash, candidate_hash: CandidateHash) -> Option<bool> {
+		self.view_knowledge
+			.get_mut(relay_parent)
+			.map(|k| k.send_manifest(candidate_hash))
+	}
+
+	/// Note a `Manifest` received from the peer.
+	fn receive_manifest(
+		&mut self,
+		relay_parent: &Hash,
+		candidate_hash: CandidateHash,
+		max_manifests: usize,
+	) -> std::result::Result<bool, Rep> {
+		self.view_knowledge
+			.get_mut(relay_parent)
+			.ok_or(COST_UNEXPECTED_STATEMENT)?
+			.receive_manifest(candidate_hash, max_manifests)
+	}
+
 	/// Updates our view of the peer's knowledge with this statement's fingerprint based
 	/// on something that we would like to send to the peer.
 	///
@@ -561,7 +635,7 @@ struct FetchingInfo {
 	///
 	/// We use an `IndexMap` here to preserve the ordering of peers sending us messages. This is
 	/// desirable because we reward first sending peers with reputation.
-	available_peers: IndexMap<PeerId, Vec<protocol_v1::StatementDistributionMessage>>,
+	available_peers: IndexMap<PeerId, Vec<protocol_v2::StatementDistributionMessage>>,
 	/// Peers left to try in case the background task needs it.
 	peers_to_try: Vec<PeerId>,
 	/// Sender for sending fresh peers to the fetching task in case of failure.
@@ -625,6 +699,9 @@ struct ActiveHeadData {
 	session_index: sp_staking::SessionIndex,
 	/// How many `Seconded` statements we've seen per validator.
 	seconded_counts: HashMap<ValidatorIndex, usize>,
+	/// The grid of validators of the session, for routing to peers on the second protocol
+	/// version.
+	grid: Arc<SessionGrid>,
 	/// A Jaeger span for this head, so we can attach data to it.
 	span: PerLeafSpan,
 }
@@ -633,6 +710,7 @@ impl ActiveHeadData {
 	fn new(
 		validators: Vec<ValidatorId>,
 		session_index: sp_staking::SessionIndex,
+		grid: Arc<SessionGrid>,
 		span: PerLeafSpan,
 	) -> Self {
 		ActiveHeadData {
@@ -642,6 +720,7 @@ impl ActiveHeadData {
 			validators,
 			session_index,
 			seconded_counts: Default::default(),
+			grid,
 			span,
 		}
 	}
@@ -816,6 +895,27 @@ impl ActiveHeadData {
 		-> impl Iterator<Item = StoredStatement<'_>> + '_ {
 		self.statements().filter(move |s| s.compact().candidate_hash() == &candidate_hash)
 	}
+
+	/// Get the full candidate, if we fetched it, shared it or received it in a `Seconded`
+	/// statement.
+	fn committed_candidate(&self, candidate_hash: &CandidateHash) -> Option<&CommittedCandidateReceipt> {
+		match self.waiting_large_statements.get(candidate_hash) {
+			Some(LargeStatementStatus::FetchedOrShared(committed)) => Some(committed),
+			_ => seconded_candidate(&self.statements, candidate_hash),
+		}
+	}
+}
+
+/// Find the candidate in a stored `Seconded` statement about it, if any.
+fn seconded_candidate<'a>(
+	statements: &'a IndexMap<StoredStatementComparator, SignedFullStatement>,
+	candidate_hash: &CandidateHash,
+) -> Option<&'a CommittedCandidateReceipt> {
+	statements.iter().find_map(|(comparator, statement)| match (&comparator.compact, statement.payload()) {
+		(CompactStatement::Seconded(h), Statement::Seconded(committed)) if h == candidate_hash =>
+			Some(committed),
+		_ => None,
+	})
 }
 
 /// Check a statement signature under this parent hash.
@@ -857,6 +957,8 @@ async fn circulate_statement_and_dependents(
 		.with_candidate(statement.payload().candidate_hash())
 		.with_stage(jaeger::Stage::StatementDistribution);
 
+	let grid = active_head.grid.clone();
+
 	// First circulate the statement directly to all peers needing it.
 	// The borrow of `active_head` needs to encompass only this (Rust) statement.
 	let outputs: Option<(CandidateHash, Vec<PeerId>)> = {
@@ -865,7 +967,7 @@ async fn circulate_statement_and_dependents(
 			{
 				Some((
 					*stored.compact().candidate_hash(),
-					circulate_statement(peers, ctx, relay_parent, stored, priority_peers).await,
+					circulate_statement(peers, ctx, relay_parent, stored, priority_peers, &grid).await,
 				))
 			},
 			_ => None,
@@ -896,10 +998,10 @@ async fn circulate_statement_and_dependents(
 }
 
 fn statement_message(relay_parent: Hash, statement: SignedFullStatement)
-	-> protocol_v1::ValidationProtocol
+	-> protocol_v2::ValidationProtocol
 {
 	let msg = if is_statement_large(&statement) {
-		protocol_v1::StatementDistributionMessage::LargeStatement(
+		protocol_v2::StatementDistributionMessage::LargeStatement(
 			StatementMetadata {
 				relay_parent,
 				candidate_hash: statement.payload().candidate_hash(),
@@ -908,10 +1010,70 @@ fn statement_message(relay_parent: Hash, statement: SignedFullStatement)
 			}
 		)
 	} else {
-		protocol_v1::StatementDistributionMessage::Statement(relay_parent, statement.into())
+		protocol_v2::StatementDistributionMessage::Statement(relay_parent, statement.into())
 	};
 
-	protocol_v1::ValidationProtocol::StatementDistribution(msg)
+	protocol_v2::ValidationProtocol::StatementDistribution(msg)
+}
+
+/// The statement in compact form, for peers on the second protocol version.
+fn compact_statement_message(relay_parent: Hash, statement: &SignedFullStatement)
+	-> protocol_v2::ValidationProtocol
+{
+	protocol_v2::ValidationProtocol::StatementDistribution(
+		protocol_v2::StatementDistributionMessage::CompactStatement(
+			relay_parent,
+			statement.as_unchecked().unchecked_convert_payload(),
+		)
+	)
+}
+
+/// The statement in the form suitable for the given peer.
+fn statement_message_for_peer(
+	peer_data: &PeerData,
+	relay_parent: Hash,
+	statement: &SignedFullStatement,
+) -> protocol_v2::ValidationProtocol {
+	if peer_data.uses_grid() {
+		compact_statement_message(relay_parent, statement)
+	} else {
+		statement_message(relay_parent, statement.clone())
+	}
+}
+
+/// Announce a candidate to a peer on the second protocol version, if it is not aware of it yet.
+///
+/// Returns `true` if the manifest was sent, in which case the peer should be sent all statements
+/// about the candidate.
+async fn send_manifest_if_unknown(
+	peer: PeerId,
+	peer_data: &mut PeerData,
+	ctx: &mut impl SubsystemContext,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+) -> bool {
+	if peer_data.send_manifest(&relay_parent, candidate_hash) != Some(true) {
+		return false
+	}
+
+	tracing::trace!(
+		target: LOG_TARGET,
+		?peer,
+		?relay_parent,
+		?candidate_hash,
+		"Sending manifest",
+	);
+	let payload = protocol_v2::ValidationProtocol::StatementDistribution(
+		protocol_v2::StatementDistributionMessage::Manifest(protocol_v2::CandidateManifest {
+			relay_parent,
+			candidate_hash,
+		})
+	);
+	ctx.send_message(AllMessages::NetworkBridge(
+		NetworkBridgeMessage::SendValidationMessageV2(vec![peer], payload)
+	)).await;
+
+	true
 }
 
 /// Check whether a statement should be treated as large statement.
@@ -939,17 +1101,24 @@ fn is_statement_large(statement: &SignedFullStatement) -> bool {
 
 /// Circulates a statement to all peers who have not seen it yet, and returns
 /// an iterator over peers who need to have dependent statements sent.
+///
+/// Peers on the first protocol version get the statement via random gossip, while peers on the
+/// second version only get it along the grid, or if they are priority peers.
 async fn circulate_statement<'a>(
 	peers: &mut HashMap<PeerId, PeerData>,
 	ctx: &mut impl SubsystemContext,
 	relay_parent: Hash,
 	stored: StoredStatement<'a>,
-	mut priority_peers: Vec<PeerId>,
+	priority_peers: Vec<PeerId>,
+	grid: &SessionGrid,
 ) -> Vec<PeerId> {
 	let fingerprint = stored.fingerprint();
 
+	let (grid_priority_peers, mut priority_peers): (Vec<PeerId>, Vec<PeerId>) =
+		priority_peers.into_iter().partition(|p| peers.get(p).map_or(false, |d| d.uses_grid()));
+
 	let mut peers_to_send: Vec<PeerId> = peers.iter().filter_map(|(peer, data)| {
-		if data.can_send(&relay_parent, &fingerprint) {
+		if !data.uses_grid() && data.can_send(&relay_parent, &fingerprint) {
 			Some(peer.clone())
 		} else {
 			None
@@ -1001,17 +1170,59 @@ async fn circulate_statement<'a>(
 			statement = ?stored.statement,
 			"Sending statement",
 		);
-		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(
 			peers_to_send.iter().map(|(p, _)| p.clone()).collect(),
 			payload,
 		))).await;
 	}
 
-	peers_to_send.into_iter().filter_map(|(peer, needs_dependent)| if needs_dependent {
-		Some(peer)
-	} else {
-		None
-	}).collect()
+	let mut peers_needing_dependents: Vec<PeerId> = peers_to_send.into_iter()
+		.filter_map(|(peer, needs_dependent)| if needs_dependent {
+			Some(peer)
+		} else {
+			None
+		}).collect();
+
+	let originator = stored.statement.validator_index();
+	let candidate_hash = *stored.compact().candidate_hash();
+	let grid_peers: Vec<PeerId> = peers.iter().filter_map(|(peer, data)| {
+		let routed = grid_priority_peers.contains(peer) ||
+			grid.routes_to_peer(originator, data.maybe_authority.as_ref());
+		if data.uses_grid() && routed {
+			Some(peer.clone())
+		} else {
+			None
+		}
+	}).collect();
+
+	let mut compact_peers_to_send = Vec::new();
+	for peer in grid_peers {
+		let peer_data = peers.get_mut(&peer).expect("taken from `peers` above; qed");
+		if send_manifest_if_unknown(peer, peer_data, ctx, relay_parent, candidate_hash).await {
+			// The peer just learned about the candidate. It gets this statement together with
+			// all others about the candidate, so a `Seconded` statement always comes first.
+			peers_needing_dependents.push(peer);
+		} else if peer_data.can_send(&relay_parent, &fingerprint) {
+			peer_data.send(&relay_parent, &fingerprint);
+			compact_peers_to_send.push(peer);
+		}
+	}
+
+	if !compact_peers_to_send.is_empty() {
+		tracing::trace!(
+			target: LOG_TARGET,
+			?compact_peers_to_send,
+			?relay_parent,
+			statement = ?stored.statement,
+			"Sending compact statement",
+		);
+		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(
+			compact_peers_to_send,
+			compact_statement_message(relay_parent, stored.statement),
+		))).await;
+	}
+
+	peers_needing_dependents
 }
 
 /// Send all statements about a given candidate hash to a peer.
@@ -1030,9 +1241,10 @@ async fn send_statements_about(
 			continue;
 		}
 		peer_data.send(&relay_parent, &fingerprint);
-		let payload = statement_message(
+		let payload = statement_message_for_peer(
+			peer_data,
 			relay_parent,
-			statement.statement.clone(),
+			statement.statement,
 		);
 
 		tracing::trace!(
@@ -1044,7 +1256,7 @@ async fn send_statements_about(
 			"Sending statement",
 		);
 		ctx.send_message(AllMessages::NetworkBridge(
-			NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload)
+			NetworkBridgeMessage::SendValidationMessageV2(vec![peer.clone()], payload)
 		)).await;
 
 		metrics.on_statement_distributed();
@@ -1052,6 +1264,9 @@ async fn send_statements_about(
 }
 
 /// Send all statements at a given relay-parent to a peer.
+///
+/// Peers on the second protocol version only get the candidates routed to them along the grid,
+/// each announced by a `Manifest` and followed by all statements about it.
 async fn send_statements(
 	peer: PeerId,
 	peer_data: &mut PeerData,
@@ -1060,6 +1275,33 @@ async fn send_statements(
 	active_head: &ActiveHeadData,
 	metrics: &Metrics,
 ) {
+	if peer_data.uses_grid() {
+		let routed_candidates: Vec<CandidateHash> = active_head.statements()
+			.filter_map(|statement| match statement.compact() {
+				CompactStatement::Seconded(candidate_hash) if active_head.grid.routes_to_peer(
+					statement.statement.validator_index(),
+					peer_data.maybe_authority.as_ref(),
+				) => Some(*candidate_hash),
+				_ => None,
+			})
+			.collect();
+
+		for candidate_hash in routed_candidates {
+			if send_manifest_if_unknown(peer, peer_data, ctx, relay_parent, candidate_hash).await {
+				send_statements_about(
+					peer,
+					peer_data,
+					ctx,
+					relay_parent,
+					candidate_hash,
+					active_head,
+					metrics,
+				).await;
+			}
+		}
+		return
+	}
+
 	for statement in active_head.statements() {
 		let fingerprint = statement.fingerprint();
 		if !peer_data.can_send(&relay_parent, &fingerprint) {
@@ -1079,7 +1321,7 @@ async fn send_statements(
 			"Sending statement"
 		);
 		ctx.send_message(AllMessages::NetworkBridge(
-			NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload)
+			NetworkBridgeMessage::SendValidationMessageV2(vec![peer.clone()], payload)
 		)).await;
 
 		metrics.on_statement_distributed();
@@ -1103,35 +1345,51 @@ async fn report_peer(
 /// your statement.
 ///
 /// If the message was large, but the result has been fetched already that one is returned.
+///
+/// Compact statements are treated the same way: `Valid` ones are returned right away and
+/// `Seconded` ones like large statements.
 async fn retrieve_statement_from_message<'a>(
 	peer: PeerId,
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	active_head: &'a mut ActiveHeadData,
 	ctx: &mut impl SubsystemContext,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) -> Option<UncheckedSignedFullStatement> {
 
-	let fingerprint = message.get_fingerprint();
+	let fingerprint = message.get_fingerprint()?;
 	let candidate_hash = *fingerprint.0.candidate_hash();
 
 	// Immediately return any Seconded statement:
-	let message =
-		if let protocol_v1::StatementDistributionMessage::Statement(h, s) = message {
+	let message = match message {
+		protocol_v2::StatementDistributionMessage::Statement(h, s) => {
 			if let Statement::Seconded(_) = s.unchecked_payload() {
 				return Some(s)
 			}
-			protocol_v1::StatementDistributionMessage::Statement(h, s)
-		} else {
-			message
-		};
+			protocol_v2::StatementDistributionMessage::Statement(h, s)
+		}
+		// The signature of a `Valid` statement covers its compact form, so it can be turned into
+		// a full statement right away:
+		protocol_v2::StatementDistributionMessage::CompactStatement(h, s) => match s.unchecked_payload() {
+			CompactStatement::Valid(candidate_hash) => protocol_v2::StatementDistributionMessage::Statement(
+				h,
+				UncheckedSignedFullStatement::new(
+					Statement::Valid(*candidate_hash),
+					s.unchecked_validator_index(),
+					s.unchecked_signature().clone(),
+				),
+			),
+			CompactStatement::Seconded(_) => protocol_v2::StatementDistributionMessage::CompactStatement(h, s),
+		},
+		message => message,
+	};
 
 	match active_head.waiting_large_statements.entry(candidate_hash) {
 		Entry::Occupied(mut occupied) => {
 			match occupied.get_mut() {
 				LargeStatementStatus::Fetching(info) => {
 
-					let is_large_statement = message.is_large_statement();
+					let requires_candidate_fetch = message.requires_candidate_fetch();
 
 					let is_new_peer =
 						match info.available_peers.entry(peer) {
@@ -1145,7 +1403,7 @@ async fn retrieve_statement_from_message<'a>(
 							}
 					};
 
-					if is_new_peer & is_large_statement {
+					if is_new_peer & requires_candidate_fetch {
 						info.peers_to_try.push(peer);
 						// Answer any pending request for more peers:
 						if let Some(sender) = info.peer_sender.take() {
@@ -1160,17 +1418,18 @@ async fn retrieve_statement_from_message<'a>(
 				}
 				LargeStatementStatus::FetchedOrShared(committed) => {
 					match message {
-						protocol_v1::StatementDistributionMessage::Statement(_, s) => {
+						protocol_v2::StatementDistributionMessage::Statement(_, s) => {
 							// We can now immediately return any statements (should only be
 							// `Statement::Valid` ones, but we don't care at this point.)
 							return Some(s)
 						}
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata) => {
+						message => {
+							let metadata = message.get_metadata()?;
 							return Some(UncheckedSignedFullStatement::new(
 								Statement::Seconded(
 									committed.clone()),
 									metadata.signed_by,
-									metadata.signature.clone(),
+									metadata.signature,
 							))
 						}
 					}
@@ -1179,9 +1438,28 @@ async fn retrieve_statement_from_message<'a>(
 		}
 		Entry::Vacant(vacant) => {
 			match message {
-				protocol_v1::StatementDistributionMessage::LargeStatement(metadata) => {
+				protocol_v2::StatementDistributionMessage::Statement(_, s) => {
+					// No fetch in progress, safe to return any statement immediately (we don't bother
+					// about normal network jitter which might cause `Valid` statements to arrive early
+					// for now.).
+					return Some(s)
+				}
+				message => {
+					let metadata = message.get_metadata()?;
+
+					// We might hold the candidate already, from a full `Seconded` statement of
+					// another validator:
+					if let Some(committed) = seconded_candidate(&active_head.statements, &candidate_hash) {
+						return Some(UncheckedSignedFullStatement::new(
+							Statement::Seconded(committed.clone()),
+							metadata.signed_by,
+							metadata.signature,
+						))
+					}
+
 					if let Some(new_status) = launch_request(
 						metadata,
+						message,
 						peer,
 						req_sender.clone(),
 						ctx,
@@ -1190,12 +1468,6 @@ async fn retrieve_statement_from_message<'a>(
 						vacant.insert(new_status);
 					}
 				}
-				protocol_v1::StatementDistributionMessage::Statement(_, s) => {
-					// No fetch in progress, safe to return any statement immediately (we don't bother
-					// about normal network jitter which might cause `Valid` statements to arrive early
-					// for now.).
-					return Some(s)
-				}
 			}
 		}
 	}
@@ -1204,9 +1476,13 @@ async fn retrieve_statement_from_message<'a>(
 
 /// Launch request for a large statement and get tracking status.
 ///
+/// `message` is the message which triggered the request, it is kept for processing once the
+/// candidate has been fetched.
+///
 /// Returns `None` if spawning task failed.
 async fn launch_request(
 	meta: StatementMetadata,
+	message: protocol_v2::StatementDistributionMessage,
 	peer: PeerId,
 	req_sender: mpsc::Sender<RequesterMessage>,
 	ctx: &mut impl SubsystemContext,
@@ -1230,7 +1506,7 @@ async fn launch_request(
 	}
 	let available_peers = {
 		let mut m = IndexMap::new();
-		m.insert(peer, vec![protocol_v1::StatementDistributionMessage::LargeStatement(meta)]);
+		m.insert(peer, vec![message]);
 		m
 	};
 	Some(LargeStatementStatus::Fetching(FetchingInfo {
@@ -1248,10 +1524,13 @@ async fn handle_incoming_message_and_circulate<'a>(
 	peers: &mut HashMap<PeerId, PeerData>,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut impl SubsystemContext,
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) {
+	let relay_parent = message.get_relay_parent();
+	let grid = active_heads.get(&relay_parent).map(|head| head.grid.clone());
+
 	let handled_incoming = match peers.get_mut(&peer) {
 		Some(data) => {
 			handle_incoming_message(
@@ -1268,21 +1547,41 @@ async fn handle_incoming_message_and_circulate<'a>(
 	};
 
 	// if we got a fresh message, we need to circulate it to all peers.
-	if let Some((relay_parent, statement)) = handled_incoming {
-		// we can ignore the set of peers who this function returns as now expecting
-		// dependent statements.
-		//
+	if let (Some((relay_parent, statement)), Some(grid)) = (handled_incoming, grid) {
+		let candidate_hash = *statement.compact().candidate_hash();
+
 		// we have the invariant in this subsystem that we never store a `Valid` or `Invalid`
 		// statement before a `Seconded` statement. `Seconded` statements are the only ones
-		// that require dependents. Thus, if this is a `Seconded` statement for a candidate we
-		// were not aware of before, we cannot have any dependent statements from the candidate.
-		let _ = circulate_statement(
+		// that require dependents on the first protocol version. Thus, if this is a `Seconded`
+		// statement for a candidate we were not aware of before, we cannot have any dependent
+		// statements from the candidate.
+		//
+		// Peers on the second protocol version however might just have been announced the
+		// candidate of a `Valid` statement, so they need all statements about it.
+		let peers_needing_dependents = circulate_statement(
 			peers,
 			ctx,
 			relay_parent,
 			statement,
 			Vec::new(),
+			&grid,
 		).await;
+
+		if let Some(active_head) = active_heads.get(&relay_parent) {
+			for peer in peers_needing_dependents {
+				if let Some(peer_data) = peers.get_mut(&peer) {
+					send_statements_about(
+						peer,
+						peer_data,
+						ctx,
+						relay_parent,
+						candidate_hash,
+						active_head,
+						metrics,
+					).await;
+				}
+			}
+		}
 	}
 }
 
@@ -1296,7 +1595,7 @@ async fn handle_incoming_message<'a>(
 	peer_data: &mut PeerData,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut impl SubsystemContext,
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) -> Option<(Hash, StoredStatement<'a>)> {
@@ -1315,7 +1614,72 @@ async fn handle_incoming_message<'a>(
 		}
 	};
 
-	if let protocol_v1::StatementDistributionMessage::LargeStatement(_) = message {
+	let is_v2_message = match message {
+		protocol_v2::StatementDistributionMessage::CompactStatement(..) |
+		protocol_v2::StatementDistributionMessage::Manifest(_) => true,
+		protocol_v2::StatementDistributionMessage::Statement(..) |
+		protocol_v2::StatementDistributionMessage::LargeStatement(_) => false,
+	};
+	if is_v2_message && !peer_data.uses_grid() {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?message,
+			"Message of the second protocol version from peer on the first one.",
+		);
+		report_peer(ctx, peer, COST_UNEXPECTED_STATEMENT).await;
+		return None;
+	}
+
+	if let protocol_v2::StatementDistributionMessage::Manifest(manifest) = message {
+		let max_manifests = active_head.validators.len() * VC_THRESHOLD;
+		match peer_data.receive_manifest(&relay_parent, manifest.candidate_hash, max_manifests) {
+			Err(rep) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?peer,
+					?manifest,
+					?rep,
+					"Unexpected manifest.",
+				);
+				report_peer(ctx, peer, rep).await;
+			}
+			Ok(true) => {
+				// Like for statements, the peer gets all statements about a candidate it just
+				// told us about.
+				send_statements_about(
+					peer,
+					peer_data,
+					ctx,
+					relay_parent,
+					manifest.candidate_hash,
+					&*active_head,
+					metrics,
+				).await;
+			}
+			Ok(false) => {}
+		}
+		return None;
+	}
+
+	if let protocol_v2::StatementDistributionMessage::CompactStatement(_, ref statement) = message {
+		// We fetch the candidate of a compact `Seconded` statement from the peer, so it must have
+		// announced it to us.
+		if let CompactStatement::Seconded(candidate_hash) = statement.unchecked_payload() {
+			if !peer_data.is_known_candidate(&relay_parent, candidate_hash) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?peer,
+					?message,
+					"Compact `Seconded` statement for unannounced candidate.",
+				);
+				report_peer(ctx, peer, COST_UNEXPECTED_STATEMENT).await;
+				return None;
+			}
+		}
+	}
+
+	if let protocol_v2::StatementDistributionMessage::LargeStatement(_) = message {
 		if let Err(rep) = peer_data.receive_large_statement(&relay_parent) {
 			tracing::debug!(
 				target: LOG_TARGET,
@@ -1329,7 +1693,10 @@ async fn handle_incoming_message<'a>(
 		}
 	}
 
-	let fingerprint = message.get_fingerprint();
+	let fingerprint = match message.get_fingerprint() {
+		Some(fingerprint) => fingerprint,
+		None => return None,
+	};
 	let candidate_hash = fingerprint.0.candidate_hash().clone();
 	let handle_incoming_span = active_head.span.child("handle-incoming")
 		.with_candidate(candidate_hash)
@@ -1481,21 +1848,23 @@ async fn handle_network_update(
 	active_heads: &mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut impl SubsystemContext,
 	req_sender: &mpsc::Sender<RequesterMessage>,
-	update: NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>,
+	update: NetworkBridgeEvent<protocol_v2::StatementDistributionMessage>,
 	metrics: &Metrics,
 ) {
 	match update {
-		NetworkBridgeEvent::PeerConnected(peer, role, maybe_authority) => {
+		NetworkBridgeEvent::PeerConnected(peer, role, protocol_version, maybe_authority) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?peer,
 				?role,
+				protocol_version,
 				"Peer connected",
 			);
 			peers.insert(peer, PeerData {
 				view: Default::default(),
 				view_knowledge: Default::default(),
 				maybe_authority: maybe_authority.clone(),
+				protocol_version,
 			});
 			if let Some(authority) = maybe_authority {
 				authorities.insert(authority, peer);
@@ -1642,9 +2011,9 @@ impl StatementDistribution {
 						.get(&relay_parent)
 						.ok_or(NonFatal::NoSuchHead(relay_parent))?;
 
-				let committed = match active_head.waiting_large_statements.get(&candidate_hash) {
-					Some(LargeStatementStatus::FetchedOrShared(committed)) => committed.clone(),
-					_ => {
+				let committed = match active_head.committed_candidate(&candidate_hash) {
+					Some(committed) => committed.clone(),
+					None => {
 						return Err(
 							NonFatal::NoSuchFetchedLargeStatement(relay_parent, candidate_hash)
 						)
@@ -1808,7 +2177,15 @@ impl StatementDistribution {
 					let session_info = &info.session_info;
 
 					active_heads.entry(relay_parent)
-						.or_insert(ActiveHeadData::new(session_info.validators.clone(), session_index, span));
+						.or_insert_with(|| ActiveHeadData::new(
+							session_info.validators.clone(),
+							session_index,
+							Arc::new(SessionGrid::new(
+								&session_info.discovery_keys,
+								info.validator_info.our_index,
+							)),
+							span,
+						));
 
 					active_heads.retain(|h, _| {
 						let live = !deactivated.contains(h);
@@ -1879,7 +2256,7 @@ impl StatementDistribution {
 						metrics,
 					).await;
 				}
-				StatementDistributionMessage::NetworkBridgeUpdateV1(event) => {
+				StatementDistributionMessage::NetworkBridgeUpdateV2(event) => {
 					let _timer = metrics.time_network_bridge_update_v1();
 
 					handle_network_update(
@@ -2060,7 +2437,9 @@ mod tests {
 	use futures_timer::Delay;
 	use sp_keystore::{CryptoStore, SyncCryptoStorePtr, SyncCryptoStore};
 	use sc_keystore::LocalKeystore;
-	use polkadot_node_network_protocol::{view, ObservedRole, request_response::Recipient};
+	use polkadot_node_network_protocol::{
+		view, ObservedRole, request_response::Recipient, peer_set::{PROTOCOL_V1, VALIDATION_PROTOCOL_V2},
+	};
 	use polkadot_subsystem::{
 		jaeger, ActivatedLeaf, messages::{RuntimeApiMessage, RuntimeApiRequest}, LeafStatus,
 	};
@@ -2111,6 +2490,7 @@ mod tests {
 		let mut head_data = ActiveHeadData::new(
 			validators,
 			session_index,
+			Arc::new(SessionGrid::new(&[], None)),
 			PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 		);
 
@@ -2410,6 +2790,7 @@ mod tests {
 			let mut data = ActiveHeadData::new(
 				validators,
 				session_index,
+				Arc::new(SessionGrid::new(&[], None)),
 				PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 			);
 
@@ -2464,6 +2845,7 @@ mod tests {
 				k
 			},
 			maybe_authority: None,
+			protocol_version: PROTOCOL_V1,
 		};
 
 		let pool = sp_core::testing::TaskExecutor::new();
@@ -2513,7 +2895,7 @@ mod tests {
 
 				assert_matches!(
 					message,
-					AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+					AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(
 						to,
 						payload,
 					)) => {
@@ -2552,6 +2934,7 @@ mod tests {
 			view: view.clone(),
 			view_knowledge: view.iter().map(|v| (v.clone(), Default::default())).collect(),
 			maybe_authority: None,
+			protocol_version: PROTOCOL_V1,
 		};
 
 		let mut peer_data: HashMap<_, _> = vec![
@@ -2601,6 +2984,7 @@ mod tests {
 				hash_b,
 				statement,
 				Vec::new(),
+				&SessionGrid::new(&[], None),
 			).await;
 
 			{
@@ -2626,7 +3010,7 @@ mod tests {
 			let message = handle.recv().await;
 			assert_matches!(
 				message,
-				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(
 					to,
 					payload,
 				)) => {
@@ -2643,6 +3027,172 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn circulated_statement_goes_along_grid_to_v2_peers() {
+		let hash_a = Hash::repeat_byte(1);
+
+		let candidate = {
+			let mut c = CommittedCandidateReceipt::default();
+			c.descriptor.relay_parent = hash_a;
+			c.descriptor.para_id = 1.into();
+			c
+		};
+
+		// Grid of 2x2 with us (Alice) at index 0: Bob shares our row, Charlie our column.
+		let authorities: Vec<AuthorityDiscoveryId> = vec![
+			Sr25519Keyring::Alice.public().into(),
+			Sr25519Keyring::Bob.public().into(),
+			Sr25519Keyring::Charlie.public().into(),
+			Sr25519Keyring::Dave.public().into(),
+		];
+		let grid = SessionGrid::new(&authorities, Some(ValidatorIndex(0)));
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+		let peer_d = PeerId::random();
+		let peer_v1 = PeerId::random();
+
+		let peer_data_for = |maybe_authority: Option<AuthorityDiscoveryId>, protocol_version| PeerData {
+			view: view![hash_a],
+			view_knowledge: vec![(hash_a, Default::default())].into_iter().collect(),
+			maybe_authority,
+			protocol_version,
+		};
+
+		let mut peer_data: HashMap<_, _> = vec![
+			(peer_b, peer_data_for(Some(authorities[1].clone()), VALIDATION_PROTOCOL_V2)),
+			(peer_c, peer_data_for(Some(authorities[2].clone()), VALIDATION_PROTOCOL_V2)),
+			(peer_d, peer_data_for(Some(authorities[3].clone()), VALIDATION_PROTOCOL_V2)),
+			(peer_v1, peer_data_for(None, PROTOCOL_V1)),
+		].into_iter().collect();
+
+		let pool = sp_core::testing::TaskExecutor::new();
+		let (mut ctx, mut handle) =
+			polkadot_node_subsystem_test_helpers
+				::make_subsystem_context
+				::<StatementDistributionMessage,_>(pool);
+
+		executor::block_on(async move {
+			let signing_context = SigningContext {
+				parent_hash: hash_a,
+				session_index: 1,
+			};
+
+			let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+			let alice_public = CryptoStore::sr25519_generate_new(
+				&*keystore, ValidatorId::ID, Some(&Sr25519Keyring::Alice.to_seed())
+			).await.unwrap();
+
+			let seconded = SignedFullStatement::sign(
+				&keystore,
+				Statement::Seconded(candidate.clone()),
+				&signing_context,
+				ValidatorIndex(0),
+				&alice_public.clone().into(),
+			).await.ok().flatten().expect("should be signed");
+			let candidate_hash = candidate.hash();
+
+			let comparator = StoredStatementComparator {
+				compact: seconded.payload().to_compact(),
+				validator_index: ValidatorIndex(0),
+				signature: seconded.signature().clone(),
+			};
+			let statement = StoredStatement {
+				comparator: &comparator,
+				statement: &seconded,
+			};
+
+			let needs_dependents = circulate_statement(
+				&mut peer_data,
+				&mut ctx,
+				hash_a,
+				statement,
+				Vec::new(),
+				&grid,
+			).await;
+
+			// The first version peer gets the full statement, grid neighbors only a manifest for
+			// now. Peer D is neither in our row nor in our column.
+			assert_eq!(
+				needs_dependents.into_iter().collect::<HashSet<_>>(),
+				vec![peer_v1, peer_b, peer_c].into_iter().collect(),
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(to, payload)) => {
+					assert_eq!(to, vec![peer_v1]);
+					assert_eq!(payload, statement_message(hash_a, seconded.clone()));
+				}
+			);
+
+			let mut announced = HashSet::new();
+			for _ in 0..2 {
+				assert_matches!(
+					handle.recv().await,
+					AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(
+						mut to,
+						protocol_v2::ValidationProtocol::StatementDistribution(
+							protocol_v2::StatementDistributionMessage::Manifest(manifest)
+						),
+					)) => {
+						assert_eq!(manifest.relay_parent, hash_a);
+						assert_eq!(manifest.candidate_hash, candidate_hash);
+						assert_eq!(to.len(), 1);
+						announced.insert(to.pop().unwrap());
+					}
+				);
+			}
+			assert_eq!(announced, vec![peer_b, peer_c].into_iter().collect());
+			assert!(!peer_data[&peer_d].is_known_candidate(&hash_a, &candidate_hash));
+
+			// Peers aware of the candidate get further statements directly, in compact form:
+			let valid = SignedFullStatement::sign(
+				&keystore,
+				Statement::Valid(candidate_hash),
+				&signing_context,
+				ValidatorIndex(0),
+				&alice_public.into(),
+			).await.ok().flatten().expect("should be signed");
+
+			let comparator = StoredStatementComparator {
+				compact: valid.payload().to_compact(),
+				validator_index: ValidatorIndex(0),
+				signature: valid.signature().clone(),
+			};
+			let statement = StoredStatement {
+				comparator: &comparator,
+				statement: &valid,
+			};
+
+			let needs_dependents = circulate_statement(
+				&mut peer_data,
+				&mut ctx,
+				hash_a,
+				statement,
+				Vec::new(),
+				&grid,
+			).await;
+			assert!(needs_dependents.is_empty());
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(to, payload)) => {
+					assert_eq!(to, vec![peer_v1]);
+					assert_eq!(payload, statement_message(hash_a, valid.clone()));
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessageV2(to, payload)) => {
+					assert_eq!(to.into_iter().collect::<HashSet<_>>(), announced);
+					assert_eq!(payload, compact_statement_message(hash_a, &valid));
+				}
+			);
+		});
+	}
+
 	#[test]
 	fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 		let hash_a = Hash::repeat_byte(1);
@@ -2711,25 +3261,25 @@ mod tests {
 
 			// notify of peers and view
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None)
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, PROTOCOL_V1, None)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None)
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, PROTOCOL_V1, None)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_a.clone(), view![hash_a])
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_b.clone(), view![hash_a])
 				)
 			}).await;
@@ -2757,10 +3307,10 @@ mod tests {
 			};
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerMessage(
 						peer_a.clone(),
-						protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into()),
+						protocol_v2::StatementDistributionMessage::Statement(hash_a, statement.clone().into()),
 					)
 				)
 			}).await;
@@ -2782,10 +3332,10 @@ mod tests {
 			assert_matches!(
 				handle.recv().await,
 				AllMessages::NetworkBridge(
-					NetworkBridgeMessage::SendValidationMessage(
+					NetworkBridgeMessage::SendValidationMessageV2(
 						recipients,
-						protocol_v1::ValidationProtocol::StatementDistribution(
-							protocol_v1::StatementDistributionMessage::Statement(r, s)
+						protocol_v2::ValidationProtocol::StatementDistribution(
+							protocol_v2::StatementDistributionMessage::Statement(r, s)
 						),
 					)
 				) => {
@@ -2887,57 +3437,60 @@ mod tests {
 
 			// notify of peers and view
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Alice.public().into())
 					)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Bob.public().into())
 					)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_c.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Charlie.public().into())
 					)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None)
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, PROTOCOL_V1, None)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_a.clone(), view![hash_a])
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_b.clone(), view![hash_a])
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_c.clone(), view![hash_a])
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_bad.clone(), view![hash_a])
 				)
 			}).await;
@@ -2966,13 +3519,13 @@ mod tests {
 			};
 
 			let metadata =
-				protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata();
+				protocol_v2::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata().unwrap();
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerMessage(
 						peer_a.clone(),
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+						protocol_v2::StatementDistributionMessage::LargeStatement(metadata.clone()),
 					)
 				)
 			}).await;
@@ -3003,20 +3556,20 @@ mod tests {
 			Delay::new(Duration::from_millis(20)).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerMessage(
 						peer_c.clone(),
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+						protocol_v2::StatementDistributionMessage::LargeStatement(metadata.clone()),
 					)
 				)
 			}).await;
 
 			// Malicious peer:
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerMessage(
 						peer_bad.clone(),
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+						protocol_v2::StatementDistributionMessage::LargeStatement(metadata.clone()),
 					)
 				)
 			}).await;
@@ -3174,10 +3727,10 @@ mod tests {
 			assert_matches!(
 				handle.recv().await,
 				AllMessages::NetworkBridge(
-					NetworkBridgeMessage::SendValidationMessage(
+					NetworkBridgeMessage::SendValidationMessageV2(
 						mut recipients,
-						protocol_v1::ValidationProtocol::StatementDistribution(
-							protocol_v1::StatementDistributionMessage::LargeStatement(meta)
+						protocol_v2::ValidationProtocol::StatementDistribution(
+							protocol_v2::StatementDistributionMessage::LargeStatement(meta)
 						),
 					)
 				) => {
@@ -3260,6 +3813,288 @@ mod tests {
 		executor::block_on(future::join(test_fut, bg));
 	}
 
+	#[test]
+	fn receiving_compact_statements_from_grid_peer() {
+		let hash_a = Hash::repeat_byte(1);
+
+		let candidate = {
+			let mut c = CommittedCandidateReceipt::default();
+			c.descriptor.relay_parent = hash_a;
+			c.descriptor.para_id = 1.into();
+			c
+		};
+		let candidate_hash = candidate.hash();
+
+		let peer_a = PeerId::random(); // Alice
+		let peer_b = PeerId::random(); // Bob
+		let peer_c = PeerId::random(); // Charlie
+		let peer_v1 = PeerId::random(); // No validator, first protocol version
+
+		// Grid of 2x2: Charlie shares our row, Bob our column.
+		let validators = vec![
+			Sr25519Keyring::Alice.pair(),
+			Sr25519Keyring::Bob.pair(),
+			Sr25519Keyring::Charlie.pair(),
+			// We:
+			Sr25519Keyring::Ferdie.pair(),
+		];
+
+		let session_info = make_session_info(
+			validators,
+			vec![vec![0, 1], vec![2, 3]],
+		);
+
+		let session_index = 1;
+
+		let pool = sp_core::testing::TaskExecutor::new();
+		let (ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context(pool);
+
+		let bg = async move {
+			let s = StatementDistribution { metrics: Default::default(), keystore: make_ferdie_keystore()};
+			s.run(ctx).await.unwrap();
+		};
+
+		let (mut tx_reqs, rx_reqs) = mpsc::channel(1);
+
+		let test_fut = async move {
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::StatementFetchingReceiver(rx_reqs)
+			}).await;
+
+			// register our active heads.
+			handle.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: vec![ActivatedLeaf {
+					hash: hash_a,
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}].into(),
+				deactivated: vec![].into(),
+			}))).await;
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+				)
+					if r == hash_a
+				=> {
+					let _ = tx.send(Ok(session_index));
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionInfo(sess_index, tx))
+				)
+					if r == hash_a && sess_index == session_index
+				=> {
+					let _ = tx.send(Ok(Some(session_info)));
+				}
+			);
+
+			// notify of peers and view
+			let peers = vec![
+				(peer_a, VALIDATION_PROTOCOL_V2, Some(Sr25519Keyring::Alice.public().into())),
+				(peer_b, VALIDATION_PROTOCOL_V2, Some(Sr25519Keyring::Bob.public().into())),
+				(peer_c, VALIDATION_PROTOCOL_V2, Some(Sr25519Keyring::Charlie.public().into())),
+				(peer_v1, PROTOCOL_V1, None),
+			];
+			for (peer, version, authority) in peers {
+				handle.send(FromOverseer::Communication {
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+						NetworkBridgeEvent::PeerConnected(peer, ObservedRole::Full, version, authority)
+					)
+				}).await;
+
+				handle.send(FromOverseer::Communication {
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+						NetworkBridgeEvent::PeerViewChange(peer, view![hash_a])
+					)
+				}).await;
+			}
+
+			let statement = {
+				let signing_context = SigningContext {
+					parent_hash: hash_a,
+					session_index,
+				};
+
+				let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+				let charlie_public = CryptoStore::sr25519_generate_new(
+					&*keystore, ValidatorId::ID, Some(&Sr25519Keyring::Charlie.to_seed())
+				).await.unwrap();
+
+				SignedFullStatement::sign(
+					&keystore,
+					Statement::Seconded(candidate.clone()),
+					&signing_context,
+					ValidatorIndex(2),
+					&charlie_public.into(),
+				).await.ok().flatten().expect("should be signed")
+			};
+			let compact = protocol_v2::StatementDistributionMessage::CompactStatement(
+				hash_a,
+				statement.as_unchecked().unchecked_convert_payload(),
+			);
+			let manifest = protocol_v2::StatementDistributionMessage::Manifest(
+				protocol_v2::CandidateManifest { relay_parent: hash_a, candidate_hash },
+			);
+
+			// Peers on the first protocol version must not send second version messages:
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerMessage(peer_v1, manifest.clone())
+				)
+			}).await;
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(p, r)
+				) if p == peer_v1 && r == COST_UNEXPECTED_STATEMENT => {}
+			);
+
+			// A compact `Seconded` statement needs the candidate to be announced first:
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerMessage(peer_c, compact.clone())
+				)
+			}).await;
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(p, r)
+				) if p == peer_c && r == COST_UNEXPECTED_STATEMENT => {}
+			);
+
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerMessage(peer_c, manifest.clone())
+				)
+			}).await;
+
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerMessage(peer_c, compact.clone())
+				)
+			}).await;
+
+			// The candidate gets fetched from the announcing peer:
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendRequests(
+						mut reqs, IfDisconnected::ImmediateError
+					)
+				) => {
+					let reqs = reqs.pop().unwrap();
+					let outgoing = match reqs {
+						Requests::StatementFetching(outgoing) => outgoing,
+						_ => panic!("Unexpected request"),
+					};
+					let req = outgoing.payload;
+					assert_eq!(req.relay_parent, hash_a);
+					assert_eq!(req.candidate_hash, candidate_hash);
+					assert_eq!(outgoing.peer, Recipient::Peer(peer_c));
+					let response = StatementFetchingResponse::Statement(candidate.clone());
+					outgoing.pending_response.send(Ok(response.encode())).unwrap();
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(p, r)
+				) if p == peer_c && r == BENEFIT_VALID_RESPONSE => {}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::ReportPeer(p, r)
+				) if p == peer_c && r == BENEFIT_VALID_STATEMENT_FIRST => {}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::CandidateBacking(
+					CandidateBackingMessage::Statement(r, s)
+				) if r == hash_a && s == statement => {}
+			);
+
+			// The first version peer gets the full statement:
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendValidationMessageV2(recipients, payload)
+				) => {
+					assert_eq!(recipients, vec![peer_v1]);
+					assert_eq!(payload, statement_message(hash_a, statement.clone()));
+				}
+			);
+
+			// Charlie shares our row, so we forward along our column to Bob, but not to Alice:
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendValidationMessageV2(
+						recipients,
+						protocol_v2::ValidationProtocol::StatementDistribution(
+							protocol_v2::StatementDistributionMessage::Manifest(m)
+						),
+					)
+				) => {
+					assert_eq!(recipients, vec![peer_b]);
+					assert_eq!(m, protocol_v2::CandidateManifest { relay_parent: hash_a, candidate_hash });
+				}
+			);
+
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::NetworkBridge(
+					NetworkBridgeMessage::SendValidationMessageV2(recipients, payload)
+				) => {
+					assert_eq!(recipients, vec![peer_b]);
+					assert_eq!(payload, compact_statement_message(hash_a, &statement));
+				}
+			);
+
+			// Bob can now fetch the candidate from us, while Alice was never announced it:
+			let (pending_response, response_rx) = oneshot::channel();
+			let req = sc_network::config::IncomingRequest {
+				peer: peer_a,
+				payload: StatementFetchingRequest { relay_parent: hash_a, candidate_hash }.encode(),
+				pending_response,
+			};
+			tx_reqs.send(req).await.unwrap();
+			assert_matches!(
+				response_rx.await.unwrap().result,
+				Err(()) => {}
+			);
+
+			let (pending_response, response_rx) = oneshot::channel();
+			let req = sc_network::config::IncomingRequest {
+				peer: peer_b,
+				payload: StatementFetchingRequest { relay_parent: hash_a, candidate_hash }.encode(),
+				pending_response,
+			};
+			tx_reqs.send(req).await.unwrap();
+			let StatementFetchingResponse::Statement(committed) =
+				Decode::decode(&mut response_rx.await.unwrap().result.unwrap().as_ref()).unwrap();
+			assert_eq!(committed, candidate);
+
+			handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+		};
+
+		futures::pin_mut!(test_fut);
+		futures::pin_mut!(bg);
+
+		executor::block_on(future::join(test_fut, bg));
+	}
+
 	#[test]
 	fn share_prioritizes_backing_group() {
 		sp_tracing::try_init_simple();
@@ -3360,17 +4195,18 @@ mod tests {
 			// notify of dummy peers and view
 			for (peer, pair) in dummy_peers.clone().into_iter().zip(dummy_pairs) {
 				handle.send(FromOverseer::Communication {
-					msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 						NetworkBridgeEvent::PeerConnected(
 							peer,
 							ObservedRole::Full,
+							PROTOCOL_V1,
 							Some(pair.public().into()),
 						)
 					)
 				}).await;
 
 				handle.send(FromOverseer::Communication {
-					msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 						NetworkBridgeEvent::PeerViewChange(peer, view![hash_a])
 					)
 				}).await;
@@ -3378,70 +4214,74 @@ mod tests {
 
 			// notify of peers and view
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Alice.public().into())
 					)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_b.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Bob.public().into())
 					)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_c.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Charlie.public().into())
 					)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None)
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
+					NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, PROTOCOL_V1, None)
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_other_group.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Dave.public().into())
 					)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_a.clone(), view![hash_a])
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_b.clone(), view![hash_a])
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_c.clone(), view![hash_a])
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_bad.clone(), view![hash_a])
 				)
 			}).await;
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_other_group.clone(), view![hash_a])
 				)
 			}).await;
@@ -3470,7 +4310,7 @@ mod tests {
 			};
 
 			let metadata =
-				protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata();
+				protocol_v2::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata().unwrap();
 
 			handle.send(FromOverseer::Communication {
 				msg: StatementDistributionMessage::Share(hash_a, statement.clone())
@@ -3480,10 +4320,10 @@ mod tests {
 			assert_matches!(
 				handle.recv().await,
 				AllMessages::NetworkBridge(
-					NetworkBridgeMessage::SendValidationMessage(
+					NetworkBridgeMessage::SendValidationMessageV2(
 						mut recipients,
-						protocol_v1::ValidationProtocol::StatementDistribution(
-							protocol_v1::StatementDistributionMessage::LargeStatement(meta)
+						protocol_v2::ValidationProtocol::StatementDistribution(
+							protocol_v2::StatementDistributionMessage::LargeStatement(meta)
 						),
 					)
 				) => {
@@ -3615,17 +4455,18 @@ mod tests {
 
 			// notify of peers and view
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerConnected(
 						peer_a.clone(),
 						ObservedRole::Full,
+						PROTOCOL_V1,
 						Some(Sr25519Keyring::Alice.public().into())
 					)
 				)
 			}).await;
 
 			handle.send(FromOverseer::Communication {
-				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 					NetworkBridgeEvent::PeerViewChange(peer_a.clone(), view![hash_a])
 				)
 			}).await;
@@ -3652,14 +4493,14 @@ mod tests {
 			};
 
 			let metadata =
-				protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata();
+				protocol_v2::StatementDistributionMessage::Statement(hash_a, statement.clone().into()).get_metadata().unwrap();
 
 			for _ in 0..MAX_LARGE_STATEMENTS_PER_SENDER + 1 {
 				handle.send(FromOverseer::Communication {
-					msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					msg: StatementDistributionMessage::NetworkBridgeUpdateV2(
 						NetworkBridgeEvent::PeerMessage(
 							peer_a.clone(),
-							protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+							protocol_v2::StatementDistributionMessage::LargeStatement(metadata.clone()),
 						)
 					)
 				}).await;
@@ -87,7 +87,8 @@ use polkadot_subsystem::messages::{
 	ProvisionerMessage, RuntimeApiMessage,
 	AvailabilityStoreMessage, NetworkBridgeMessage, AllMessages, CollationGenerationMessage,
 	CollatorProtocolMessage, AvailabilityRecoveryMessage, ApprovalDistributionMessage,
-	ApprovalVotingMessage, GossipSupportMessage,
+	ApprovalVotingMessage, GossipSupportMessage, DisputeCoordinatorMessage,
+	DisputeDistributionMessage, ChainSelectionMessage, PvfCheckerMessage,
 };
 pub use polkadot_subsystem::{
 	Subsystem, SubsystemContext, SubsystemSender, OverseerSignal, FromOverseer, SubsystemError,
@@ -150,7 +151,7 @@ impl<Client> HeadSupportsParachains for Arc<Client> where
 pub struct AllSubsystems<
 	CV = (), CB = (), SD = (), AD = (), AR = (), BS = (), BD = (), P = (),
 	RA = (), AS = (), NB = (), CA = (), CG = (), CP = (), ApD = (), ApV = (),
-	GS = (),
+	GS = (), DC = (), DD = (), CS = (), PC = (),
 > {
 	/// A candidate validation subsystem.
 	pub candidate_validation: CV,
@@ -186,10 +187,18 @@ pub struct AllSubsystems<
 	pub approval_voting: ApV,
 	/// A Connection Request Issuer subsystem.
 	pub gossip_support: GS,
+	/// A Dispute Coordinator subsystem.
+	pub dispute_coordinator: DC,
+	/// A dispute distribution subsystem.
+	pub dispute_distribution: DD,
+	/// A chain selection subsystem.
+	pub chain_selection: CS,
+	/// A PVF pre-checker subsystem.
+	pub pvf_checker: PC,
 }
 
-impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
-	AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
+impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, DC, DD, CS, PC>
+	AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, DC, DD, CS, PC>
 {
 	/// Create a new instance of [`AllSubsystems`].
 	///
@@ -221,6 +230,10 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 		DummySubsystem,
 		DummySubsystem,
 		DummySubsystem,
+		DummySubsystem,
+		DummySubsystem,
+		DummySubsystem,
+		DummySubsystem,
 	> {
 		AllSubsystems {
 			candidate_validation: DummySubsystem,
@@ -240,10 +253,14 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 			approval_distribution: DummySubsystem,
 			approval_voting: DummySubsystem,
 			gossip_support: DummySubsystem,
+			dispute_coordinator: DummySubsystem,
+			dispute_distribution: DummySubsystem,
+			chain_selection: DummySubsystem,
+			pvf_checker: DummySubsystem,
 		}
 	}
 
-	fn as_ref(&self) -> AllSubsystems<&'_ CV, &'_ CB, &'_ SD, &'_ AD, &'_ AR, &'_ BS, &'_ BD, &'_ P, &'_ RA, &'_ AS, &'_ NB, &'_ CA, &'_ CG, &'_ CP, &'_ ApD, &'_ ApV, &'_ GS> {
+	fn as_ref(&self) -> AllSubsystems<&'_ CV, &'_ CB, &'_ SD, &'_ AD, &'_ AR, &'_ BS, &'_ BD, &'_ P, &'_ RA, &'_ AS, &'_ NB, &'_ CA, &'_ CG, &'_ CP, &'_ ApD, &'_ ApV, &'_ GS, &'_ DC, &'_ DD, &'_ CS, &'_ PC> {
 		AllSubsystems {
 			candidate_validation: &self.candidate_validation,
 			candidate_backing: &self.candidate_backing,
@@ -262,6 +279,10 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 			approval_distribution: &self.approval_distribution,
 			approval_voting: &self.approval_voting,
 			gossip_support: &self.gossip_support,
+			dispute_coordinator: &self.dispute_coordinator,
+			dispute_distribution: &self.dispute_distribution,
+			chain_selection: &self.chain_selection,
+			pvf_checker: &self.pvf_checker,
 		}
 	}
 
@@ -284,6 +305,10 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 			<M as MapSubsystem<ApD>>::Output,
 			<M as MapSubsystem<ApV>>::Output,
 			<M as MapSubsystem<GS>>::Output,
+			<M as MapSubsystem<DC>>::Output,
+			<M as MapSubsystem<DD>>::Output,
+			<M as MapSubsystem<CS>>::Output,
+			<M as MapSubsystem<PC>>::Output,
 		>
 	where
 		M: MapSubsystem<CV>,
@@ -303,6 +328,10 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 		M: MapSubsystem<ApD>,
 		M: MapSubsystem<ApV>,
 		M: MapSubsystem<GS>,
+		M: MapSubsystem<DC>,
+		M: MapSubsystem<DD>,
+		M: MapSubsystem<CS>,
+		M: MapSubsystem<PC>,
 	{
 		AllSubsystems {
 			candidate_validation: m.map_subsystem(self.candidate_validation),
@@ -322,6 +351,10 @@ impl<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>
 			approval_distribution: m.map_subsystem(self.approval_distribution),
 			approval_voting: m.map_subsystem(self.approval_voting),
 			gossip_support: m.map_subsystem(self.gossip_support),
+			dispute_coordinator: m.map_subsystem(self.dispute_coordinator),
+			dispute_distribution: m.map_subsystem(self.dispute_distribution),
+			chain_selection: m.map_subsystem(self.chain_selection),
+			pvf_checker: m.map_subsystem(self.pvf_checker),
 		}
 	}
 }
@@ -330,7 +363,8 @@ type AllSubsystemsSame<T> = AllSubsystems<
 	T, T, T, T, T,
 	T, T, T, T, T,
 	T, T, T, T, T,
-	T, T,
+	T, T, T, T, T,
+	T,
 >;
 
 /// A type of messages that are sent from [`Subsystem`] to [`Overseer`].
@@ -548,6 +582,10 @@ struct ChannelsOut {
 	approval_distribution: metered::MeteredSender<MessagePacket<ApprovalDistributionMessage>>,
 	approval_voting: metered::MeteredSender<MessagePacket<ApprovalVotingMessage>>,
 	gossip_support: metered::MeteredSender<MessagePacket<GossipSupportMessage>>,
+	dispute_coordinator: metered::MeteredSender<MessagePacket<DisputeCoordinatorMessage>>,
+	dispute_distribution: metered::MeteredSender<MessagePacket<DisputeDistributionMessage>>,
+	chain_selection: metered::MeteredSender<MessagePacket<ChainSelectionMessage>>,
+	pvf_checker: metered::MeteredSender<MessagePacket<PvfCheckerMessage>>,
 
 	candidate_validation_unbounded: metered::UnboundedMeteredSender<MessagePacket<CandidateValidationMessage>>,
 	candidate_backing_unbounded: metered::UnboundedMeteredSender<MessagePacket<CandidateBackingMessage>>,
@@ -566,6 +604,10 @@ struct ChannelsOut {
 	approval_distribution_unbounded: metered::UnboundedMeteredSender<MessagePacket<ApprovalDistributionMessage>>,
 	approval_voting_unbounded: metered::UnboundedMeteredSender<MessagePacket<ApprovalVotingMessage>>,
 	gossip_support_unbounded: metered::UnboundedMeteredSender<MessagePacket<GossipSupportMessage>>,
+	dispute_coordinator_unbounded: metered::UnboundedMeteredSender<MessagePacket<DisputeCoordinatorMessage>>,
+	dispute_distribution_unbounded: metered::UnboundedMeteredSender<MessagePacket<DisputeDistributionMessage>>,
+	chain_selection_unbounded: metered::UnboundedMeteredSender<MessagePacket<ChainSelectionMessage>>,
+	pvf_checker_unbounded: metered::UnboundedMeteredSender<MessagePacket<PvfCheckerMessage>>,
 }
 
 impl ChannelsOut {
@@ -626,6 +668,18 @@ impl ChannelsOut {
 			AllMessages::GossipSupport(msg) => {
 				self.gossip_support.send(make_packet(signals_received, msg)).await
 			},
+			AllMessages::DisputeCoordinator(msg) => {
+				self.dispute_coordinator.send(make_packet(signals_received, msg)).await
+			},
+			AllMessages::DisputeDistribution(msg) => {
+				self.dispute_distribution.send(make_packet(signals_received, msg)).await
+			},
+			AllMessages::ChainSelection(msg) => {
+				self.chain_selection.send(make_packet(signals_received, msg)).await
+			},
+			AllMessages::PvfChecker(msg) => {
+				self.pvf_checker.send(make_packet(signals_received, msg)).await
+			},
 		};
 
 		if res.is_err() {
@@ -728,6 +782,26 @@ impl ChannelsOut {
 					.unbounded_send(make_packet(signals_received, msg))
 					.map_err(|e| e.into_send_error())
 			},
+			AllMessages::DisputeCoordinator(msg) => {
+				self.dispute_coordinator_unbounded
+					.unbounded_send(make_packet(signals_received, msg))
+					.map_err(|e| e.into_send_error())
+			},
+			AllMessages::DisputeDistribution(msg) => {
+				self.dispute_distribution_unbounded
+					.unbounded_send(make_packet(signals_received, msg))
+					.map_err(|e| e.into_send_error())
+			},
+			AllMessages::ChainSelection(msg) => {
+				self.chain_selection_unbounded
+					.unbounded_send(make_packet(signals_received, msg))
+					.map_err(|e| e.into_send_error())
+			},
+			AllMessages::PvfChecker(msg) => {
+				self.pvf_checker_unbounded
+					.unbounded_send(make_packet(signals_received, msg))
+					.map_err(|e| e.into_send_error())
+			},
 		};
 
 		if res.is_err() {
@@ -1050,6 +1124,10 @@ pub struct Overseer<S, SupportsParachains> {
 		OverseenSubsystem<ApprovalDistributionMessage>,
 		OverseenSubsystem<ApprovalVotingMessage>,
 		OverseenSubsystem<GossipSupportMessage>,
+		OverseenSubsystem<DisputeCoordinatorMessage>,
+		OverseenSubsystem<DisputeDistributionMessage>,
+		OverseenSubsystem<ChainSelectionMessage>,
+		OverseenSubsystem<PvfCheckerMessage>,
 	>,
 
 	/// Spawner to spawn tasks to.
@@ -1355,9 +1433,9 @@ where
 	/// #
 	/// # }); }
 	/// ```
-	pub fn new<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>(
+	pub fn new<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, DC, DD, CS, PC>(
 		leaves: impl IntoIterator<Item = BlockInfo>,
-		all_subsystems: AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>,
+		all_subsystems: AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, DC, DD, CS, PC>,
 		prometheus_registry: Option<&prometheus::Registry>,
 		supports_parachains: SupportsParachains,
 		mut s: S,
@@ -1380,6 +1458,10 @@ where
 		ApD: Subsystem<OverseerSubsystemContext<ApprovalDistributionMessage>> + Send,
 		ApV: Subsystem<OverseerSubsystemContext<ApprovalVotingMessage>> + Send,
 		GS: Subsystem<OverseerSubsystemContext<GossipSupportMessage>> + Send,
+		DC: Subsystem<OverseerSubsystemContext<DisputeCoordinatorMessage>> + Send,
+		DD: Subsystem<OverseerSubsystemContext<DisputeDistributionMessage>> + Send,
+		CS: Subsystem<OverseerSubsystemContext<ChainSelectionMessage>> + Send,
+		PC: Subsystem<OverseerSubsystemContext<PvfCheckerMessage>> + Send,
 	{
 		let (events_tx, events_rx) = metered::channel(CHANNEL_CAPACITY);
 
@@ -1427,6 +1509,14 @@ where
 			= metered::channel(CHANNEL_CAPACITY);
 		let (gossip_support_bounded_tx, gossip_support_bounded_rx)
 			= metered::channel(CHANNEL_CAPACITY);
+		let (dispute_coordinator_bounded_tx, dispute_coordinator_bounded_rx)
+			= metered::channel(CHANNEL_CAPACITY);
+		let (dispute_distribution_bounded_tx, dispute_distribution_bounded_rx)
+			= metered::channel(CHANNEL_CAPACITY);
+		let (chain_selection_bounded_tx, chain_selection_bounded_rx)
+			= metered::channel(CHANNEL_CAPACITY);
+		let (pvf_checker_bounded_tx, pvf_checker_bounded_rx)
+			= metered::channel(CHANNEL_CAPACITY);
 
 		let (candidate_validation_unbounded_tx, candidate_validation_unbounded_rx)
 			= metered::unbounded();
@@ -1462,6 +1552,14 @@ where
 			= metered::unbounded();
 		let (gossip_support_unbounded_tx, gossip_support_unbounded_rx)
 			= metered::unbounded();
+		let (dispute_coordinator_unbounded_tx, dispute_coordinator_unbounded_rx)
+			= metered::unbounded();
+		let (dispute_distribution_unbounded_tx, dispute_distribution_unbounded_rx)
+			= metered::unbounded();
+		let (chain_selection_unbounded_tx, chain_selection_unbounded_rx)
+			= metered::unbounded();
+		let (pvf_checker_unbounded_tx, pvf_checker_unbounded_rx)
+			= metered::unbounded();
 
 		let channels_out = ChannelsOut {
 			candidate_validation: candidate_validation_bounded_tx.clone(),
@@ -1481,6 +1579,10 @@ where
 			approval_distribution: approval_distribution_bounded_tx.clone(),
 			approval_voting: approval_voting_bounded_tx.clone(),
 			gossip_support: gossip_support_bounded_tx.clone(),
+			dispute_coordinator: dispute_coordinator_bounded_tx.clone(),
+			dispute_distribution: dispute_distribution_bounded_tx.clone(),
+			chain_selection: chain_selection_bounded_tx.clone(),
+			pvf_checker: pvf_checker_bounded_tx.clone(),
 
 			candidate_validation_unbounded: candidate_validation_unbounded_tx.clone(),
 			candidate_backing_unbounded: candidate_backing_unbounded_tx.clone(),
@@ -1499,6 +1601,10 @@ where
 			approval_distribution_unbounded: approval_distribution_unbounded_tx.clone(),
 			approval_voting_unbounded: approval_voting_unbounded_tx.clone(),
 			gossip_support_unbounded: gossip_support_unbounded_tx.clone(),
+			dispute_coordinator_unbounded: dispute_coordinator_unbounded_tx.clone(),
+			dispute_distribution_unbounded: dispute_distribution_unbounded_tx.clone(),
+			chain_selection_unbounded: chain_selection_unbounded_tx.clone(),
+			pvf_checker_unbounded: pvf_checker_unbounded_tx.clone(),
 		};
 
 		let candidate_validation_subsystem = spawn(
@@ -1722,6 +1828,58 @@ where
 			TaskKind::Regular,
 		)?;
 
+		let dispute_coordinator_subsystem = spawn(
+			&mut s,
+			dispute_coordinator_bounded_tx,
+			stream::select(dispute_coordinator_bounded_rx, dispute_coordinator_unbounded_rx),
+			dispute_coordinator_unbounded_tx.meter().clone(),
+			channels_out.clone(),
+			to_overseer_tx.clone(),
+			all_subsystems.dispute_coordinator,
+			&metrics,
+			&mut running_subsystems,
+			TaskKind::Blocking,
+		)?;
+
+		let dispute_distribution_subsystem = spawn(
+			&mut s,
+			dispute_distribution_bounded_tx,
+			stream::select(dispute_distribution_bounded_rx, dispute_distribution_unbounded_rx),
+			dispute_distribution_unbounded_tx.meter().clone(),
+			channels_out.clone(),
+			to_overseer_tx.clone(),
+			all_subsystems.dispute_distribution,
+			&metrics,
+			&mut running_subsystems,
+			TaskKind::Regular,
+		)?;
+
+		let chain_selection_subsystem = spawn(
+			&mut s,
+			chain_selection_bounded_tx,
+			stream::select(chain_selection_bounded_rx, chain_selection_unbounded_rx),
+			chain_selection_unbounded_tx.meter().clone(),
+			channels_out.clone(),
+			to_overseer_tx.clone(),
+			all_subsystems.chain_selection,
+			&metrics,
+			&mut running_subsystems,
+			TaskKind::Blocking,
+		)?;
+
+		let pvf_checker_subsystem = spawn(
+			&mut s,
+			pvf_checker_bounded_tx,
+			stream::select(pvf_checker_bounded_rx, pvf_checker_unbounded_rx),
+			pvf_checker_unbounded_tx.meter().clone(),
+			channels_out.clone(),
+			to_overseer_tx.clone(),
+			all_subsystems.pvf_checker,
+			&metrics,
+			&mut running_subsystems,
+			TaskKind::Regular,
+		)?;
+
 		let leaves = leaves
 			.into_iter()
 			.map(|BlockInfo { hash, parent_hash: _, number }| (hash, number))
@@ -1748,6 +1906,10 @@ where
 			approval_distribution: approval_distribution_subsystem,
 			approval_voting: approval_voting_subsystem,
 			gossip_support: gossip_support_subsystem,
+			dispute_coordinator: dispute_coordinator_subsystem,
+			dispute_distribution: dispute_distribution_subsystem,
+			chain_selection: chain_selection_subsystem,
+			pvf_checker: pvf_checker_subsystem,
 		};
 
 		{
@@ -1823,6 +1985,10 @@ where
 		let _ = self.subsystems.approval_distribution.send_signal(OverseerSignal::Conclude).await;
 		let _ = self.subsystems.approval_voting.send_signal(OverseerSignal::Conclude).await;
 		let _ = self.subsystems.gossip_support.send_signal(OverseerSignal::Conclude).await;
+		let _ = self.subsystems.dispute_coordinator.send_signal(OverseerSignal::Conclude).await;
+		let _ = self.subsystems.dispute_distribution.send_signal(OverseerSignal::Conclude).await;
+		let _ = self.subsystems.chain_selection.send_signal(OverseerSignal::Conclude).await;
+		let _ = self.subsystems.pvf_checker.send_signal(OverseerSignal::Conclude).await;
 
 		let mut stop_delay = Delay::new(Duration::from_secs(STOP_DELAY)).fuse();
 
@@ -2000,7 +2166,11 @@ where
 		self.subsystems.collation_generation.send_signal(signal.clone()).await?;
 		self.subsystems.approval_distribution.send_signal(signal.clone()).await?;
 		self.subsystems.approval_voting.send_signal(signal.clone()).await?;
-		self.subsystems.gossip_support.send_signal(signal).await?;
+		self.subsystems.gossip_support.send_signal(signal.clone()).await?;
+		self.subsystems.dispute_coordinator.send_signal(signal.clone()).await?;
+		self.subsystems.dispute_distribution.send_signal(signal.clone()).await?;
+		self.subsystems.chain_selection.send_signal(signal.clone()).await?;
+		self.subsystems.pvf_checker.send_signal(signal).await?;
 
 		Ok(())
 	}
@@ -2059,6 +2229,18 @@ where
 			AllMessages::GossipSupport(msg) => {
 				self.subsystems.gossip_support.send_message(msg).await?;
 			},
+			AllMessages::DisputeCoordinator(msg) => {
+				self.subsystems.dispute_coordinator.send_message(msg).await?;
+			},
+			AllMessages::DisputeDistribution(msg) => {
+				self.subsystems.dispute_distribution.send_message(msg).await?;
+			},
+			AllMessages::ChainSelection(msg) => {
+				self.subsystems.chain_selection.send_message(msg).await?;
+			},
+			AllMessages::PvfChecker(msg) => {
+				self.subsystems.pvf_checker.send_message(msg).await?;
+			},
 		}
 
 		Ok(())
@@ -2203,10 +2385,19 @@ mod tests {
 	use std::collections::HashMap;
 	use futures::{executor, pin_mut, select, FutureExt, pending};
 
-	use polkadot_primitives::v1::{CollatorPair, CandidateHash};
+	use polkadot_primitives::v1::{
+		CollatorPair, CandidateHash, InvalidDisputeStatementKind, ValidDisputeStatementKind,
+		ValidatorIndex, ValidatorSignature,
+	};
 	use polkadot_subsystem::{messages::RuntimeApiRequest, messages::NetworkBridgeEvent, jaeger};
-	use polkadot_node_primitives::{CollationResult, CollationGenerationConfig, PoV, BlockData};
-	use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange};
+	use polkadot_node_primitives::{
+		CollationResult, CollationGenerationConfig, PoV, BlockData, UncheckedDisputeMessage,
+		InvalidDisputeVote, ValidDisputeVote,
+	};
+	use polkadot_node_network_protocol::{
+		PeerId, UnifiedReputationChange,
+		request_response::{IncomingRequest, v1::DisputeRequest},
+	};
 	use polkadot_node_subsystem_util::metered;
 
 	use sp_core::crypto::Pair as _;
@@ -3066,7 +3257,7 @@ mod tests {
 	}
 
 	fn test_statement_distribution_msg() -> StatementDistributionMessage {
-		StatementDistributionMessage::NetworkBridgeUpdateV1(test_network_bridge_event())
+		StatementDistributionMessage::NetworkBridgeUpdateV2(test_network_bridge_event())
 	}
 
 	fn test_availability_recovery_msg() -> AvailabilityRecoveryMessage {
@@ -3111,12 +3302,45 @@ mod tests {
 		ApprovalVotingMessage::ApprovedAncestor(Default::default(), 0, sender)
 	}
 
+	fn test_dispute_coordinator_msg() -> DisputeCoordinatorMessage {
+		let (sender, _) = oneshot::channel();
+		DisputeCoordinatorMessage::RecentDisputes(sender)
+	}
+
+	fn test_dispute_distribution_msg() -> DisputeDistributionMessage {
+		let dummy_signature: ValidatorSignature = sp_core::sr25519::Signature([0u8; 64]).into();
+		let (pending_response, _) = oneshot::channel();
+		let req = IncomingRequest::new(
+			PeerId::random(),
+			DisputeRequest(UncheckedDisputeMessage {
+				candidate_receipt: Default::default(),
+				session_index: 0,
+				invalid_vote: InvalidDisputeVote {
+					validator_index: ValidatorIndex(0),
+					signature: dummy_signature.clone(),
+					kind: InvalidDisputeStatementKind::Explicit,
+				},
+				valid_vote: ValidDisputeVote {
+					validator_index: ValidatorIndex(0),
+					signature: dummy_signature,
+					kind: ValidDisputeStatementKind::Explicit,
+				},
+			}),
+			pending_response,
+		);
+		DisputeDistributionMessage::DisputeSendingRequest(req)
+	}
+
+	fn test_chain_selection_msg() -> ChainSelectionMessage {
+		ChainSelectionMessage::Approved(Default::default())
+	}
+
 	// Checks that `stop`, `broadcast_signal` and `broadcast_message` are implemented correctly.
 	#[test]
 	fn overseer_all_subsystems_receive_signals_and_messages() {
-		const NUM_SUBSYSTEMS: usize = 17;
-		// -3 for BitfieldSigning, GossipSupport and AvailabilityDistribution
-		const NUM_SUBSYSTEMS_MESSAGED: usize = NUM_SUBSYSTEMS - 3;
+		const NUM_SUBSYSTEMS: usize = 21;
+		// -4 for BitfieldSigning, GossipSupport, AvailabilityDistribution and PvfChecker
+		const NUM_SUBSYSTEMS_MESSAGED: usize = NUM_SUBSYSTEMS - 4;
 
 		let spawner = sp_core::testing::TaskExecutor::new();
 		executor::block_on(async move {
@@ -3148,6 +3372,10 @@ mod tests {
 				approval_distribution: subsystem.clone(),
 				approval_voting: subsystem.clone(),
 				gossip_support: subsystem.clone(),
+				dispute_coordinator: subsystem.clone(),
+				dispute_distribution: subsystem.clone(),
+				chain_selection: subsystem.clone(),
+				pvf_checker: subsystem.clone(),
 			};
 			let (overseer, mut handler) = Overseer::new(
 				vec![],
@@ -3185,6 +3413,9 @@ mod tests {
 			handler.send_msg(AllMessages::ChainApi(test_chain_api_msg())).await;
 			handler.send_msg(AllMessages::ApprovalDistribution(test_approval_distribution_msg())).await;
 			handler.send_msg(AllMessages::ApprovalVoting(test_approval_voting_msg())).await;
+			handler.send_msg(AllMessages::DisputeCoordinator(test_dispute_coordinator_msg())).await;
+			handler.send_msg(AllMessages::DisputeDistribution(test_dispute_distribution_msg())).await;
+			handler.send_msg(AllMessages::ChainSelection(test_chain_selection_msg())).await;
 
 			// Wait until all subsystems have received. Otherwise the messages might race against
 			// the conclude signal.
@@ -3235,6 +3466,10 @@ mod tests {
 		let (approval_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 		let (approval_voting_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 		let (gossip_support_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+		let (dispute_coordinator_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+		let (dispute_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+		let (chain_selection_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+		let (pvf_checker_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 
 		let (candidate_validation_unbounded_tx, _) = metered::unbounded();
 		let (candidate_backing_unbounded_tx, _) = metered::unbounded();
@@ -3253,6 +3488,10 @@ mod tests {
 		let (approval_distribution_unbounded_tx, _) = metered::unbounded();
 		let (approval_voting_unbounded_tx, _) = metered::unbounded();
 		let (gossip_support_unbounded_tx, _) = metered::unbounded();
+		let (dispute_coordinator_unbounded_tx, _) = metered::unbounded();
+		let (dispute_distribution_unbounded_tx, _) = metered::unbounded();
+		let (chain_selection_unbounded_tx, _) = metered::unbounded();
+		let (pvf_checker_unbounded_tx, _) = metered::unbounded();
 
 		let channels_out = ChannelsOut {
 			candidate_validation: candidate_validation_bounded_tx.clone(),
@@ -3272,6 +3511,10 @@ mod tests {
 			approval_distribution: approval_distribution_bounded_tx.clone(),
 			approval_voting: approval_voting_bounded_tx.clone(),
 			gossip_support: gossip_support_bounded_tx.clone(),
+			dispute_coordinator: dispute_coordinator_bounded_tx.clone(),
+			dispute_distribution: dispute_distribution_bounded_tx.clone(),
+			chain_selection: chain_selection_bounded_tx.clone(),
+			pvf_checker: pvf_checker_bounded_tx.clone(),
 
 			candidate_validation_unbounded: candidate_validation_unbounded_tx.clone(),
 			candidate_backing_unbounded: candidate_backing_unbounded_tx.clone(),
@@ -3290,6 +3533,10 @@ mod tests {
 			approval_distribution_unbounded: approval_distribution_unbounded_tx.clone(),
 			approval_voting_unbounded: approval_voting_unbounded_tx.clone(),
 			gossip_support_unbounded: gossip_support_unbounded_tx.clone(),
+			dispute_coordinator_unbounded: dispute_coordinator_unbounded_tx.clone(),
+			dispute_distribution_unbounded: dispute_distribution_unbounded_tx.clone(),
+			chain_selection_unbounded: chain_selection_unbounded_tx.clone(),
+			pvf_checker_unbounded: pvf_checker_unbounded_tx.clone(),
 		};
 
 		let (mut signal_tx, signal_rx) = metered::channel(CHANNEL_CAPACITY);
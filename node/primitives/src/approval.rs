@@ -18,6 +18,7 @@
 
 pub use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof, Randomness};
 pub use sp_consensus_babe::Slot;
+pub use polkadot_primitives::v1::ApprovalVote;
 
 use polkadot_primitives::v1::{
 	CandidateHash, Hash, ValidatorIndex, ValidatorSignature, CoreIndex,
@@ -98,10 +99,6 @@ pub struct IndirectAssignmentCert {
 	pub cert: AssignmentCert,
 }
 
-/// A vote of approval on a candidate.
-#[derive(Debug, Clone, Encode, Decode)]
-pub struct ApprovalVote(pub CandidateHash);
-
 /// A signed approval vote which references the candidate indirectly via the block.
 ///
 /// In practice, we have a look-up from block hash and candidate index to candidate hash,
@@ -33,11 +33,21 @@ pub use sp_consensus_babe::{
 	Epoch as BabeEpoch, BabeEpochConfiguration, AllowedSlots as BabeAllowedSlots,
 };
 
-use polkadot_primitives::v1::{BlakeTwo256, CandidateCommitments, CandidateHash, CollatorPair, CommittedCandidateReceipt, CompactStatement, EncodeAs, Hash, HashT, HeadData, Id as ParaId, OutboundHrmpMessage, PersistedValidationData, Signed, UncheckedSigned, UpwardMessage, ValidationCode, ValidatorIndex};
+use polkadot_primitives::v1::{BlakeTwo256, CandidateCommitments, CandidateHash, CollatorPair, CommittedCandidateReceipt, CompactStatement, EncodeAs, Hash, HashT, HeadData, Id as ParaId, OutboundHrmpMessage, PersistedValidationData, SessionIndex, Signed, UncheckedSigned, UpwardMessage, ValidationCode, ValidatorIndex};
 pub use polkadot_parachain::primitives::BlockData;
 
 pub mod approval;
 
+/// Disputes related types.
+pub mod disputes;
+pub use disputes::{
+	SignedDisputeStatement, CandidateVotes, DisputeStatus, Timestamp, DisputeMessage,
+	UncheckedDisputeMessage, InvalidDisputeVote, ValidDisputeVote, DisputeMessageCheckError,
+};
+
+/// The cumulative weight of a block in a fork-choice rule.
+pub type BlockWeight = u32;
+
 /// The bomb limit for decompressing code blobs.
 pub const VALIDATION_CODE_BOMB_LIMIT: usize = 16 * 1024 * 1024;
 
@@ -47,6 +57,13 @@ pub const MAX_POV_SIZE: u32 = 20 * 1024 * 1024;
 /// The bomb limit for decompressing PoV blobs.
 pub const POV_BOMB_LIMIT: usize = MAX_POV_SIZE as usize;
 
+/// The number of sessions before the current one for which disputes are still accepted.
+///
+/// This should match the runtime's `dispute_period` configuration, as the runtime ignores dispute
+/// statements from older sessions. It would be nice to draw this from the chain state, but we
+/// have no tools for it right now. On Polkadot this is 1 day, and on Kusama it's 6 hours.
+pub const DISPUTE_WINDOW: SessionIndex = 6;
+
 /// A statement, where the candidate receipt is included in the `Seconded` variant.
 ///
 /// This is the committed candidate receipt instead of the bare candidate receipt. As such,
@@ -0,0 +1,94 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Loading of the operator-provided allowlist of preferred collators.
+//!
+//! The allowlist is a plain text file with one `<para id> <ss58 collator key>` entry per line.
+//! Empty lines and lines starting with `#` are ignored.
+
+#![cfg(feature = "full-node")]
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use sp_core::crypto::Ss58Codec;
+use polkadot_primitives::v1::{CollatorId, Id as ParaId};
+
+use crate::Error;
+
+/// Load the collator allowlist from the file at the given path.
+pub fn load(path: &Path) -> Result<HashMap<ParaId, HashSet<CollatorId>>, Error> {
+	parse(&std::fs::read_to_string(path)?)
+}
+
+fn parse(content: &str) -> Result<HashMap<ParaId, HashSet<CollatorId>>, Error> {
+	let mut allowlist = HashMap::<ParaId, HashSet<CollatorId>>::new();
+
+	for (index, line) in content.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue
+		}
+
+		let invalid = |what: &str| Error::InvalidCollatorAllowlist(format!("{} in line {}", what, index + 1));
+
+		let mut parts = line.split_whitespace();
+		let (para_id, collator_id) = match (parts.next(), parts.next(), parts.next()) {
+			(Some(para_id), Some(collator_id), None) => (para_id, collator_id),
+			_ => return Err(invalid("Expected `<para id> <collator key>`")),
+		};
+
+		let para_id = para_id.parse::<u32>().map_err(|_| invalid("Invalid para id"))?;
+		let collator_id = sp_core::sr25519::Public::from_ss58check(collator_id)
+			.map_err(|_| invalid("Invalid collator key"))?;
+
+		allowlist.entry(ParaId::from(para_id)).or_default().insert(CollatorId::from(collator_id));
+	}
+
+	Ok(allowlist)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	#[test]
+	fn parses_allowlist() {
+		let content = format!(
+			"# Preferred collators\n\n100 {}\n100 {}\n 200\t{} \n",
+			Sr25519Keyring::Alice.public().to_ss58check(),
+			Sr25519Keyring::Bob.public().to_ss58check(),
+			Sr25519Keyring::Alice.public().to_ss58check(),
+		);
+
+		let allowlist = parse(&content).unwrap();
+
+		assert_eq!(allowlist.len(), 2);
+		assert_eq!(allowlist[&ParaId::from(100)].len(), 2);
+		assert!(allowlist[&ParaId::from(200)].contains(&CollatorId::from(Sr25519Keyring::Alice.public())));
+	}
+
+	#[test]
+	fn rejects_malformed_lines() {
+		let alice = Sr25519Keyring::Alice.public().to_ss58check();
+
+		assert!(parse(&format!("foo {}", alice)).is_err());
+		assert!(parse("100 not-a-key").is_err());
+		assert!(parse(&format!("100 {} 200", alice)).is_err());
+		assert!(parse("100").is_err());
+	}
+}
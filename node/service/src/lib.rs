@@ -19,8 +19,10 @@
 #![deny(unused_results)]
 
 pub mod chain_spec;
+mod collator_allowlist;
 mod grandpa_support;
 mod parachains_db;
+mod relay_chain_selection;
 
 #[cfg(feature = "full-node")]
 use {
@@ -29,7 +31,10 @@ use {
 	polkadot_node_core_av_store::Config as AvailabilityConfig,
 	polkadot_node_core_av_store::Error as AvailabilityError,
 	polkadot_node_core_approval_voting::Config as ApprovalVotingConfig,
+	polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig,
+	polkadot_node_core_chain_selection::Config as ChainSelectionConfig,
 	polkadot_node_core_candidate_validation::Config as CandidateValidationConfig,
+	polkadot_collator_protocol::ReputationConfig as CollatorReputationConfig,
 	polkadot_overseer::{AllSubsystems, BlockInfo, Overseer, OverseerHandler},
 	polkadot_primitives::v1::ParachainHost,
 	sc_authority_discovery::Service as AuthorityDiscoveryService,
@@ -55,6 +60,9 @@ use prometheus_endpoint::Registry;
 use service::RpcHandlers;
 use telemetry::{Telemetry, TelemetryWorker, TelemetryWorkerHandle};
 
+#[cfg(feature = "full-node")]
+pub use polkadot_approval_distribution::AggressionConfig as ApprovalAggressionConfig;
+
 #[cfg(feature = "rococo-native")]
 pub use polkadot_client::RococoExecutor;
 
@@ -133,6 +141,10 @@ pub enum Error {
 	#[cfg(feature = "full-node")]
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
+
+	#[cfg(feature = "full-node")]
+	#[error("Invalid collator allowlist: {0}")]
+	InvalidCollatorAllowlist(String),
 }
 
 /// Can be called for a `Configuration` to identify which network the configuration targets.
@@ -195,7 +207,7 @@ fn jaeger_launch_collector_with_agent(spawner: impl SpawnNamed, config: &Configu
 }
 
 #[cfg(feature = "full-node")]
-type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullSelectChain = relay_chain_selection::SelectRelayChain<FullBackend>;
 #[cfg(feature = "full-node")]
 type FullGrandpaBlockImport<RuntimeApi, Executor> = grandpa::GrandpaBlockImport<
 	FullBackend, Block, FullClient<RuntimeApi, Executor>, FullSelectChain
@@ -279,7 +291,7 @@ fn new_partial<RuntimeApi, Executor>(
 
 	jaeger_launch_collector_with_agent(task_manager.spawn_handle(), &*config, jaeger_agent)?;
 
-	let select_chain = sc_consensus::LongestChain::new(backend.clone());
+	let select_chain = relay_chain_selection::SelectRelayChain::new(backend.clone());
 
 	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
 		config.transaction_pool.clone(),
@@ -412,6 +424,10 @@ fn real_overseer<Spawner, RuntimeClient>(
 	parachains_db: Arc<dyn kvdb::KeyValueDB>,
 	availability_config: AvailabilityConfig,
 	approval_voting_config: ApprovalVotingConfig,
+	dispute_coordinator_config: DisputeCoordinatorConfig,
+	chain_selection_config: ChainSelectionConfig,
+	collator_reputation_config: CollatorReputationConfig,
+	approval_aggression_config: ApprovalAggressionConfig,
 	network_service: Arc<sc_network::NetworkService<Block, Hash>>,
 	authority_discovery: AuthorityDiscoveryService,
 	request_multiplexer: RequestMultiplexer,
@@ -444,14 +460,17 @@ where
 	use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 	use polkadot_node_core_approval_voting::ApprovalVotingSubsystem;
 	use polkadot_gossip_support::GossipSupport as GossipSupportSubsystem;
+	use polkadot_node_core_dispute_coordinator::DisputeCoordinatorSubsystem;
+	use polkadot_dispute_distribution::DisputeDistributionSubsystem;
+	use polkadot_node_core_chain_selection::ChainSelectionSubsystem;
+	use polkadot_node_core_pvf_checker::PvfCheckerSubsystem;
 
 	let all_subsystems = AllSubsystems {
 		availability_distribution: AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
 			Metrics::register(registry)?,
 		),
-		availability_recovery: AvailabilityRecoverySubsystem::with_chunks_only(
-		),
+		availability_recovery: AvailabilityRecoverySubsystem::with_fast_path_if_small(),
 		availability_store: AvailabilityStoreSubsystem::new(
 			parachains_db.clone(),
 			availability_config,
@@ -492,6 +511,8 @@ where
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
 					metrics: Metrics::register(registry)?,
+					db: parachains_db.clone(),
+					reputation_config: collator_reputation_config,
 				},
 			};
 			CollatorProtocolSubsystem::new(
@@ -519,12 +540,13 @@ where
 			keystore.clone(),
 			Metrics::register(registry)?,
 		),
-		approval_distribution: ApprovalDistributionSubsystem::new(
+		approval_distribution: ApprovalDistributionSubsystem::with_aggression_config(
+			approval_aggression_config,
 			Metrics::register(registry)?,
 		),
 		approval_voting: ApprovalVotingSubsystem::with_config(
 			approval_voting_config,
-			parachains_db,
+			parachains_db.clone(),
 			keystore.clone(),
 			Box::new(network_service.clone()),
 			Metrics::register(registry)?,
@@ -532,6 +554,21 @@ where
 		gossip_support: GossipSupportSubsystem::new(
 			keystore.clone(),
 		),
+		dispute_coordinator: DisputeCoordinatorSubsystem::new(
+			parachains_db.clone(),
+			dispute_coordinator_config,
+			keystore.clone(),
+		),
+		dispute_distribution: DisputeDistributionSubsystem::new(
+			keystore.clone(),
+		),
+		chain_selection: ChainSelectionSubsystem::new(
+			chain_selection_config,
+			parachains_db,
+		),
+		pvf_checker: PvfCheckerSubsystem::new(
+			keystore.clone(),
+		),
 	};
 
 	Overseer::new(
@@ -600,7 +637,7 @@ impl IsCollator {
 /// Returns the active leaves the overseer should start with.
 #[cfg(feature = "full-node")]
 fn active_leaves<RuntimeApi, Executor>(
-	select_chain: &sc_consensus::LongestChain<FullBackend, Block>,
+	select_chain: &FullSelectChain,
 	client: &FullClient<RuntimeApi, Executor>,
 ) -> Result<Vec<BlockInfo>, Error>
 where
@@ -660,6 +697,9 @@ pub fn new_full<RuntimeApi, Executor>(
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	program_path: Option<std::path::PathBuf>,
+	collator_allowlist: Option<std::path::PathBuf>,
+	collator_block_cooldown: Option<std::time::Duration>,
+	approval_aggression_config: ApprovalAggressionConfig,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, Executor>>>, Error>
 	where
 		RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
@@ -761,6 +801,26 @@ pub fn new_full<RuntimeApi, Executor>(
 		slot_duration_millis: slot_duration.as_millis() as u64,
 	};
 
+	let dispute_coordinator_config = DisputeCoordinatorConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+	};
+
+	let chain_selection_config = ChainSelectionConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_chain_selection_data,
+		stagnant_check_interval: Default::default(),
+	};
+
+	let default_collator_reputation_config =
+		CollatorReputationConfig::new(crate::parachains_db::REAL_COLUMNS.col_collator_reputation_data);
+	let collator_reputation_config = CollatorReputationConfig {
+		cooldown: collator_block_cooldown.unwrap_or(default_collator_reputation_config.cooldown),
+		allowlist: match collator_allowlist {
+			None => Default::default(),
+			Some(path) => crate::collator_allowlist::load(&path)?,
+		},
+		..default_collator_reputation_config
+	};
+
 	let candidate_validation_config = CandidateValidationConfig {
 		artifacts_cache_path: config.database
 			.path()
@@ -847,6 +907,10 @@ pub fn new_full<RuntimeApi, Executor>(
 			parachains_db,
 			availability_config,
 			approval_voting_config,
+			dispute_coordinator_config,
+			chain_selection_config,
+			collator_reputation_config,
+			approval_aggression_config,
 			network.clone(),
 			authority_discovery_service,
 			request_multiplexer,
@@ -857,6 +921,10 @@ pub fn new_full<RuntimeApi, Executor>(
 		)?;
 		let overseer_handler_clone = overseer_handler.clone();
 
+		// Now that the overseer exists, switch block selection over to the
+		// chain-selection subsystem.
+		select_chain.connect_overseer(overseer_handler.clone());
+
 		task_manager.spawn_essential_handle().spawn_blocking("overseer", Box::pin(async move {
 			use futures::{pin_mut, select, FutureExt};
 
@@ -1285,6 +1353,9 @@ pub fn build_full(
 	disable_beefy: bool,
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	collator_allowlist: Option<std::path::PathBuf>,
+	collator_block_cooldown: Option<std::time::Duration>,
+	approval_aggression_config: ApprovalAggressionConfig,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "rococo-native")]
 	if config.chain_spec.is_rococo() || config.chain_spec.is_wococo() {
@@ -1296,6 +1367,9 @@ pub fn build_full(
 			jaeger_agent,
 			telemetry_worker_handle,
 			None,
+			collator_allowlist,
+			collator_block_cooldown,
+			approval_aggression_config,
 		).map(|full| full.with_client(Client::Rococo))
 	}
 
@@ -1309,6 +1383,9 @@ pub fn build_full(
 			jaeger_agent,
 			telemetry_worker_handle,
 			None,
+			collator_allowlist,
+			collator_block_cooldown,
+			approval_aggression_config,
 		).map(|full| full.with_client(Client::Kusama))
 	}
 
@@ -1322,6 +1399,9 @@ pub fn build_full(
 			jaeger_agent,
 			telemetry_worker_handle,
 			None,
+			collator_allowlist,
+			collator_block_cooldown,
+			approval_aggression_config,
 		).map(|full| full.with_client(Client::Westend))
 	}
 
@@ -1333,5 +1413,8 @@ pub fn build_full(
 		jaeger_agent,
 		telemetry_worker_handle,
 		None,
+		collator_allowlist,
+		collator_block_cooldown,
+		approval_aggression_config,
 	).map(|full| full.with_client(Client::Polkadot))
 }
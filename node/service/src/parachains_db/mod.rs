@@ -11,7 +11,8 @@
 // MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
 // GNU General Public License for more details.
 
-//! A RocksDB instance for storing parachain data; availability data, and approvals.
+//! A RocksDB instance for storing parachain data; availability data, approvals, disputes,
+//! and chain selection.
 
 #[cfg(feature = "full-node")]
 use {
@@ -25,12 +26,30 @@ use {
 mod upgrade;
 
 mod columns {
-	pub const NUM_COLUMNS: u32 = 3;
+	pub mod v0 {
+		pub const NUM_COLUMNS: u32 = 3;
+	}
+
+	pub mod v1 {
+		pub const NUM_COLUMNS: u32 = 4;
+	}
+
+	pub mod v2 {
+		pub const NUM_COLUMNS: u32 = 5;
+	}
+
+	pub mod v3 {
+		pub const NUM_COLUMNS: u32 = 5;
+	}
 
+	pub const NUM_COLUMNS: u32 = 6;
 
 	pub const COL_AVAILABILITY_DATA: u32 = 0;
 	pub const COL_AVAILABILITY_META: u32 = 1;
 	pub const COL_APPROVAL_DATA: u32 = 2;
+	pub const COL_DISPUTE_COORDINATOR_DATA: u32 = 3;
+	pub const COL_CHAIN_SELECTION_DATA: u32 = 4;
+	pub const COL_COLLATOR_REPUTATION_DATA: u32 = 5;
 }
 
 /// Columns used by different subsystems.
@@ -42,6 +61,12 @@ pub struct ColumnsConfig {
 	pub col_availability_meta: u32,
 	/// The column used by approval voting for data.
 	pub col_approval_data: u32,
+	/// The column used by the dispute coordinator for data.
+	pub col_dispute_coordinator_data: u32,
+	/// The column used by chain selection for data.
+	pub col_chain_selection_data: u32,
+	/// The column used by the collator protocol for collator reputations.
+	pub col_collator_reputation_data: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -49,6 +74,9 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_availability_data: columns::COL_AVAILABILITY_DATA,
 	col_availability_meta: columns::COL_AVAILABILITY_META,
 	col_approval_data: columns::COL_APPROVAL_DATA,
+	col_dispute_coordinator_data: columns::COL_DISPUTE_COORDINATOR_DATA,
+	col_chain_selection_data: columns::COL_CHAIN_SELECTION_DATA,
+	col_collator_reputation_data: columns::COL_COLLATOR_REPUTATION_DATA,
 };
 
 /// The cache size for each column, in megabytes.
@@ -60,6 +88,12 @@ pub struct CacheSizes {
 	pub availability_meta: usize,
 	/// Cache used by approval data.
 	pub approval_data: usize,
+	/// Cache used by dispute coordinator data.
+	pub dispute_coordinator_data: usize,
+	/// Cache used by chain selection data.
+	pub chain_selection_data: usize,
+	/// Cache used by collator reputation data.
+	pub collator_reputation_data: usize,
 }
 
 impl Default for CacheSizes {
@@ -68,6 +102,9 @@ impl Default for CacheSizes {
 			availability_data: 25,
 			availability_meta: 1,
 			approval_data: 5,
+			dispute_coordinator_data: 1,
+			chain_selection_data: 1,
+			collator_reputation_data: 1,
 		}
 	}
 }
@@ -77,6 +114,20 @@ fn other_io_error(err: String) -> io::Error {
 	io::Error::new(io::ErrorKind::Other, err)
 }
 
+/// The data transforms of all subsystems, to be run when the DB is upgraded.
+#[cfg(feature = "full-node")]
+fn migrations() -> upgrade::Migrations {
+	let mut migrations = upgrade::Migrations::default();
+
+	migrations.register(
+		3,
+		columns::COL_APPROVAL_DATA,
+		polkadot_node_core_approval_voting::migrate_db_v1_to_v2,
+	);
+
+	migrations
+}
+
 /// Open the database on disk, creating it if it doesn't exist.
 #[cfg(feature = "full-node")]
 pub fn open_creating(
@@ -95,13 +146,19 @@ pub fn open_creating(
 		.insert(columns::COL_AVAILABILITY_META, cache_sizes.availability_meta);
 	let _ = db_config.memory_budget
 		.insert(columns::COL_APPROVAL_DATA, cache_sizes.approval_data);
+	let _ = db_config.memory_budget
+		.insert(columns::COL_DISPUTE_COORDINATOR_DATA, cache_sizes.dispute_coordinator_data);
+	let _ = db_config.memory_budget
+		.insert(columns::COL_CHAIN_SELECTION_DATA, cache_sizes.chain_selection_data);
+	let _ = db_config.memory_budget
+		.insert(columns::COL_COLLATOR_REPUTATION_DATA, cache_sizes.collator_reputation_data);
 
 	let path_str = path.to_str().ok_or_else(|| other_io_error(
 		format!("Bad database path: {:?}", path),
 	))?;
 
 	std::fs::create_dir_all(&path_str)?;
-	upgrade::try_upgrade_db(&path)?;
+	upgrade::try_upgrade_db(&path, &migrations())?;
 	let db = Database::open(&db_config, &path_str)?;
 
 	Ok(Arc::new(db))
@@ -12,22 +12,35 @@
 // GNU General Public License for more details.
 
 //! Migration code for the parachain's DB.
+//!
+//! The DB is taken through numbered schema versions, one step at a time. Each step may add
+//! columns and run any per-column data transforms registered for the version it upgrades to.
+//! Subsystems which own a column register their transforms in [`Migrations`] whenever the
+//! on-disk format of their data changes.
 
 
 #![cfg(feature = "full-node")]
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use kvdb::KeyValueDB;
+
 type Version = u32;
 
 /// Version file name.
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
 /// Current db version.
-const CURRENT_VERSION: Version = 0;
+///
+/// * Version 1 added the dispute coordinator column.
+/// * Version 2 added the chain selection column.
+/// * Version 3 changed the approval-voting data to its v2 schema.
+/// * Version 4 added the collator reputation column.
+const CURRENT_VERSION: Version = 4;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -40,6 +53,13 @@ pub enum Error {
 		current: Version,
 		got: Version,
 	},
+	#[error("Failed to migrate column {column} to version {version}: {source}")]
+	Transform {
+		version: Version,
+		column: u32,
+		#[source]
+		source: io::Error,
+	},
 }
 
 impl From<Error> for io::Error {
@@ -51,20 +71,86 @@ impl From<Error> for io::Error {
 	}
 }
 
-/// Try upgrading parachain's database to the current version.
-pub fn try_upgrade_db(db_path: &Path) -> Result<(), Error> {
+/// A transform of the data stored within a single column of the DB.
+pub type ColumnTransform = fn(&dyn KeyValueDB, u32) -> io::Result<()>;
+
+/// Per-column data transforms, keyed by the DB version they upgrade to.
+#[derive(Default)]
+pub struct Migrations {
+	transforms: BTreeMap<Version, Vec<(u32, ColumnTransform)>>,
+}
+
+impl Migrations {
+	/// Register a transform of the data within `column`, to be run when upgrading the DB
+	/// to `version`. Transforms for the same version are run in order of registration.
+	pub fn register(&mut self, version: Version, column: u32, transform: ColumnTransform) -> &mut Self {
+		self.transforms.entry(version).or_default().push((column, transform));
+		self
+	}
+
+	fn transforms_for(&self, version: Version) -> &[(u32, ColumnTransform)] {
+		self.transforms.get(&version).map(|t| &t[..]).unwrap_or(&[])
+	}
+}
+
+/// Try upgrading parachain's database to the current version, running all
+/// registered migrations along the way.
+pub fn try_upgrade_db(db_path: &Path, migrations: &Migrations) -> Result<(), Error> {
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
-		match current_version(db_path)? {
-			CURRENT_VERSION => (),
-			v => return Err(Error::FutureVersion {
+		let version = current_version(db_path)?;
+		if version > CURRENT_VERSION {
+			return Err(Error::FutureVersion {
 				current: CURRENT_VERSION,
-				got: v,
-			}),
+				got: version,
+			});
+		}
+
+		for from in version..CURRENT_VERSION {
+			migrate(db_path, from, migrations)?;
+			// Record every completed step, so an interrupted upgrade resumes where it stopped.
+			update_version(db_path, from + 1)?;
 		}
 	}
 
-	update_version(db_path)
+	update_version(db_path, CURRENT_VERSION)
+}
+
+/// The number of columns in the given version of the DB.
+fn num_columns(version: Version) -> u32 {
+	match version {
+		0 => super::columns::v0::NUM_COLUMNS,
+		1 => super::columns::v1::NUM_COLUMNS,
+		2 => super::columns::v2::NUM_COLUMNS,
+		3 => super::columns::v3::NUM_COLUMNS,
+		_ => super::columns::NUM_COLUMNS,
+	}
+}
+
+/// Migrate the DB from version `from` to the version after it: add any new columns, then
+/// run the transforms registered for the new version.
+fn migrate(path: &Path, from: Version, migrations: &Migrations) -> Result<(), Error> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let to = from + 1;
+	let db_path = path.to_str()
+		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(num_columns(from));
+	let db = Database::open(&db_cfg, db_path)?;
+
+	for _ in num_columns(from)..num_columns(to) {
+		db.add_column()?;
+	}
+
+	for &(column, transform) in migrations.transforms_for(to) {
+		transform(&db, column).map_err(|source| Error::Transform {
+			version: to,
+			column,
+			source,
+		})?;
+	}
+
+	Ok(())
 }
 
 /// Reads current database version from the file at given path.
@@ -77,11 +163,11 @@ fn current_version(path: &Path) -> Result<Version, Error> {
 	}
 }
 
-/// Writes current database version to the file.
+/// Writes the given database version to the file.
 /// Creates a new file if the version file does not exist yet.
-fn update_version(path: &Path) -> Result<(), Error> {
+fn update_version(path: &Path, version: Version) -> Result<(), Error> {
 	fs::create_dir_all(path)?;
-	fs::write(version_file_path(path), CURRENT_VERSION.to_string()).map_err(Into::into)
+	fs::write(version_file_path(path), version.to_string()).map_err(Into::into)
 }
 
 /// Returns the version file path.
@@ -0,0 +1,337 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A [`SelectChain`] implementation designed for relay chains.
+//!
+//! This uses information about parachains to inform GRANDPA and BABE
+//! about blocks which are safe to build on and blocks which are safe to
+//! finalize.
+//!
+//! To learn more about chain-selection rules for Relay Chains, please see the
+//! documentation on [chain-selection][chain-selection-guide]
+//! in the implementers' guide.
+//!
+//! This is mostly a wrapper around a subsystem which implements the
+//! chain-selection rule, which leaves the code to be very simple.
+//!
+//! However, this does apply the further finality constraints to the best
+//! leaf returned from the chain selection subsystem by calling into other
+//! subsystems which yield information about approvals and disputes.
+//!
+//! Until the overseer has been connected, this falls back to the
+//! [`LongestChain`] rule. This is needed during startup, as the select chain
+//! must exist before the overseer can be built.
+//!
+//! [chain-selection-guide]: https://w3f.github.io/parachain-implementers-guide/protocol-chain-selection.html
+
+#![cfg(feature = "full-node")]
+
+use std::sync::Arc;
+
+use consensus_common::{Error as ConsensusError, SelectChain};
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use polkadot_overseer::OverseerHandler;
+use polkadot_primitives::v1::{
+	Block as PolkadotBlock, BlockNumber, CandidateEvent, CandidateHash, Hash, Header as PolkadotHeader,
+	SessionIndex,
+};
+use polkadot_subsystem::messages::{
+	ApprovalVotingMessage, ChainSelectionMessage, DisputeCoordinatorMessage, RuntimeApiMessage,
+	RuntimeApiRequest,
+};
+use sc_client_api::Backend as BackendT;
+use sc_consensus::LongestChain;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+
+const LOG_TARGET: &str = "parachain::chain-selection";
+
+/// A chain-selection implementation which provides safety for relay chains.
+pub struct SelectRelayChain<B> {
+	backend: Arc<B>,
+	fallback: LongestChain<B, PolkadotBlock>,
+	overseer: Arc<Mutex<Option<OverseerHandler>>>,
+}
+
+impl<B> Clone for SelectRelayChain<B> {
+	fn clone(&self) -> Self {
+		SelectRelayChain {
+			backend: self.backend.clone(),
+			fallback: self.fallback.clone(),
+			overseer: self.overseer.clone(),
+		}
+	}
+}
+
+impl<B> SelectRelayChain<B>
+	where B: BackendT<PolkadotBlock> + 'static
+{
+	/// Create a new [`SelectRelayChain`] wrapping the given chain backend.
+	///
+	/// The overseer must be connected with [`SelectRelayChain::connect_overseer`]
+	/// before the chain-selection subsystem is consulted.
+	pub fn new(backend: Arc<B>) -> Self {
+		SelectRelayChain {
+			fallback: LongestChain::new(backend.clone()),
+			backend,
+			overseer: Arc::new(Mutex::new(None)),
+		}
+	}
+
+	/// Connect the overseer handler. This is shared by all clones of this
+	/// [`SelectRelayChain`].
+	pub fn connect_overseer(&self, handler: OverseerHandler) {
+		*self.overseer.lock() = Some(handler);
+	}
+
+	fn overseer(&self) -> Option<OverseerHandler> {
+		self.overseer.lock().clone()
+	}
+
+	fn block_header(&self, hash: Hash) -> Result<PolkadotHeader, ConsensusError> {
+		match self.backend.blockchain().header(BlockId::Hash(hash)) {
+			Ok(Some(header)) => Ok(header),
+			Ok(None) => Err(ConsensusError::ChainLookup(
+				format!("Missing header with hash {:?}", hash),
+			)),
+			Err(e) => Err(ConsensusError::ChainLookup(
+				format!("Lookup failed for header with hash {:?}: {:?}", hash, e),
+			)),
+		}
+	}
+
+	fn block_number(&self, hash: Hash) -> Result<BlockNumber, ConsensusError> {
+		self.block_header(hash).map(|header| header.number)
+	}
+
+	async fn leaves_async(&self, mut overseer: OverseerHandler) -> Result<Vec<Hash>, ConsensusError> {
+		let (tx, rx) = oneshot::channel();
+
+		overseer.send_msg(ChainSelectionMessage::Leaves(tx)).await;
+
+		rx.await.map_err(|e| ConsensusError::ChainLookup(
+			format!("Unable to retrieve leaves from chain selection: {:?}", e),
+		))
+	}
+
+	async fn finality_target_async(
+		&self,
+		mut overseer: OverseerHandler,
+		target_hash: Hash,
+		maybe_max_number: Option<BlockNumber>,
+	) -> Result<Option<Hash>, ConsensusError> {
+		let target_number = self.block_number(target_hash)?;
+
+		let best_leaf = {
+			let (tx, rx) = oneshot::channel();
+			overseer.send_msg(ChainSelectionMessage::BestLeafContaining(target_hash, tx)).await;
+
+			rx.await.map_err(|e| ConsensusError::ChainLookup(
+				format!("Unable to retrieve best leaf containing {:?}: {:?}", target_hash, e),
+			))?
+		};
+
+		let best_leaf = match best_leaf {
+			Some(leaf) => leaf,
+			// The target is not part of any viable chain. Vote on the target itself.
+			None => return Ok(Some(target_hash)),
+		};
+
+		// Constrain the best leaf to the maximum number, if any.
+		let subchain_head = match maybe_max_number {
+			None => best_leaf,
+			Some(max) if max <= target_number => return Ok(Some(target_hash)),
+			Some(max) => {
+				let mut header = self.block_header(best_leaf)?;
+				while header.number > max {
+					header = self.block_header(header.parent_hash)?;
+				}
+
+				header.hash()
+			}
+		};
+
+		// Constrain the subchain head to the highest approved ancestor.
+		let (approved_hash, approved_number) = {
+			let (tx, rx) = oneshot::channel();
+			overseer.send_msg(
+				ApprovalVotingMessage::ApprovedAncestor(subchain_head, target_number, tx),
+			).await;
+
+			let approved = rx.await.map_err(|e| ConsensusError::ChainLookup(
+				format!("Unable to retrieve approved ancestor of {:?}: {:?}", subchain_head, e),
+			))?;
+
+			match approved {
+				Some(approved) => approved,
+				None => return Ok(Some(target_hash)),
+			}
+		};
+
+		if approved_number <= target_number {
+			return Ok(Some(target_hash));
+		}
+
+		// Constrain the approved chain to blocks which include no disputed candidates.
+		let block_descriptions = self.block_descriptions(
+			&mut overseer,
+			approved_hash,
+			target_number,
+		).await?;
+
+		let (tx, rx) = oneshot::channel();
+		overseer.send_msg(DisputeCoordinatorMessage::DetermineUndisputedChain {
+			base_number: target_number,
+			block_descriptions,
+			tx,
+		}).await;
+
+		let undisputed = rx.await.map_err(|e| ConsensusError::ChainLookup(
+			format!("Unable to determine undisputed chain: {:?}", e),
+		))?;
+
+		Ok(Some(undisputed.map_or(target_hash, |(_, hash)| hash)))
+	}
+
+	// Describe all blocks from the one after `base_number` up to and including
+	// `head`, in ascending order.
+	async fn block_descriptions(
+		&self,
+		overseer: &mut OverseerHandler,
+		head: Hash,
+		base_number: BlockNumber,
+	) -> Result<Vec<(Hash, SessionIndex, Vec<CandidateHash>)>, ConsensusError> {
+		let mut descriptions = Vec::new();
+		let mut header = self.block_header(head)?;
+
+		while header.number > base_number {
+			let hash = header.hash();
+
+			let session = {
+				let (tx, rx) = oneshot::channel();
+				overseer.send_msg(RuntimeApiMessage::Request(
+					header.parent_hash,
+					RuntimeApiRequest::SessionIndexForChild(tx),
+				)).await;
+
+				runtime_api_response(rx.await, hash)?
+			};
+
+			let candidates = {
+				let (tx, rx) = oneshot::channel();
+				overseer.send_msg(RuntimeApiMessage::Request(
+					hash,
+					RuntimeApiRequest::CandidateEvents(tx),
+				)).await;
+
+				runtime_api_response(rx.await, hash)?
+					.into_iter()
+					.filter_map(|event| match event {
+						CandidateEvent::CandidateIncluded(receipt, ..) => Some(receipt.hash()),
+						_ => None,
+					})
+					.collect()
+			};
+
+			descriptions.push((hash, session, candidates));
+			header = self.block_header(header.parent_hash)?;
+		}
+
+		descriptions.reverse();
+		Ok(descriptions)
+	}
+}
+
+fn runtime_api_response<T>(
+	response: Result<Result<T, polkadot_subsystem::errors::RuntimeApiError>, oneshot::Canceled>,
+	hash: Hash,
+) -> Result<T, ConsensusError> {
+	match response {
+		Ok(Ok(value)) => Ok(value),
+		Ok(Err(e)) => Err(ConsensusError::ChainLookup(
+			format!("Runtime API request failed at {:?}: {:?}", hash, e),
+		)),
+		Err(e) => Err(ConsensusError::ChainLookup(
+			format!("Runtime API request canceled at {:?}: {:?}", hash, e),
+		)),
+	}
+}
+
+impl<B> SelectChain<PolkadotBlock> for SelectRelayChain<B>
+	where B: BackendT<PolkadotBlock> + 'static
+{
+	/// Get all leaves of the chain, i.e. block hashes that are suitable to
+	/// build upon and have no suitable children.
+	fn leaves(&self) -> Result<Vec<Hash>, ConsensusError> {
+		match self.overseer() {
+			None => self.fallback.leaves(),
+			Some(overseer) => futures::executor::block_on(self.leaves_async(overseer)),
+		}
+	}
+
+	/// Among all leaves, pick the one which is the best chain to build upon.
+	fn best_chain(&self) -> Result<PolkadotHeader, ConsensusError> {
+		let overseer = match self.overseer() {
+			None => return self.fallback.best_chain(),
+			Some(overseer) => overseer,
+		};
+
+		// The chain-selection subsystem always returns at least one leaf,
+		// falling back to the last finalized block.
+		let leaves = futures::executor::block_on(self.leaves_async(overseer))?;
+		let best_leaf = leaves.first().copied().ok_or_else(|| ConsensusError::ChainLookup(
+			"No leaves returned from chain selection".into(),
+		))?;
+
+		self.block_header(best_leaf)
+	}
+
+	/// Get the best descendant of `target_hash` that we should attempt to
+	/// finalize next, if any. It is valid to return the `target_hash` if
+	/// no better block exists.
+	///
+	/// This will search all leaves to find the best one containing the
+	/// given target hash, and then constrain to the given block number.
+	///
+	/// It will also constrain the chain to only chains which are fully
+	/// approved, and chains which contain no disputes.
+	fn finality_target(
+		&self,
+		target_hash: Hash,
+		maybe_max_number: Option<BlockNumber>,
+	) -> Result<Option<Hash>, ConsensusError> {
+		match self.overseer() {
+			None => self.fallback.finality_target(target_hash, maybe_max_number),
+			Some(overseer) => {
+				let res = futures::executor::block_on(
+					self.finality_target_async(overseer, target_hash, maybe_max_number),
+				);
+
+				if let Err(ref e) = res {
+					tracing::warn!(
+						target: LOG_TARGET,
+						?target_hash,
+						err = ?e,
+						"Unable to determine finality target",
+					);
+				}
+
+				res
+			}
+		}
+	}
+}
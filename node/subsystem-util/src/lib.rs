@@ -39,7 +39,8 @@ use polkadot_primitives::v1::{
 	CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs, PersistedValidationData,
 	GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
 	SessionIndex, Signed, SigningContext, ValidationCode, ValidatorId, ValidatorIndex, SessionInfo,
-	AuthorityDiscoveryId, GroupIndex,
+	AuthorityDiscoveryId, GroupIndex, BlockNumber, CandidateHash, DisputeState, PvfCheckStatement,
+	ValidatorSignature,
 };
 use sp_core::{traits::SpawnNamed, Public};
 use sp_application_crypto::AppKey;
@@ -184,6 +185,10 @@ specialize_requests! {
 	fn request_candidate_pending_availability(para_id: ParaId) -> Option<CommittedCandidateReceipt>; CandidatePendingAvailability;
 	fn request_candidate_events() -> Vec<CandidateEvent>; CandidateEvents;
 	fn request_session_info(index: SessionIndex) -> Option<SessionInfo>; SessionInfo;
+	fn request_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>; Disputes;
+	fn request_validation_code_by_hash(hash: Hash) -> Option<ValidationCode>; ValidationCodeByHash;
+	fn request_pvfs_require_precheck() -> Vec<Hash>; PvfsRequirePrecheck;
+	fn request_submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) -> (); SubmitPvfCheckStatement;
 }
 
 /// From the given set of validators, find the first key we can sign with, if any.
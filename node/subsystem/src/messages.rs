@@ -33,11 +33,13 @@ use polkadot_node_network_protocol::{
 		Requests, request::IncomingRequest, v1 as req_res_v1
 	},
 	v1 as protocol_v1,
+	v2 as protocol_v2,
 };
 use polkadot_node_primitives::{
 	CollationGenerationConfig, SignedFullStatement, ValidationResult,
 	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
-	BabeEpoch, AvailableData, PoV, ErasureChunk
+	BabeEpoch, AvailableData, PoV, ErasureChunk,
+	SignedDisputeStatement, CandidateVotes, DisputeStatus, DisputeMessage, BlockWeight,
 };
 use polkadot_primitives::v1::{
 	AuthorityDiscoveryId, BackedCandidate, BlockNumber, SessionInfo,
@@ -48,6 +50,7 @@ use polkadot_primitives::v1::{
 	ValidationCode, ValidatorId, CandidateHash,
 	ValidatorIndex, ValidatorSignature, InboundDownwardMessage, InboundHrmpMessage,
 	CandidateIndex, GroupIndex, MultiDisputeStatementSet, SignedAvailabilityBitfields,
+	DisputeState, BackingMisbehaviorProof, PvfCheckStatement,
 };
 use polkadot_statement_table::v1::Misbehavior;
 use polkadot_procmacro_subsystem_dispatch_gen::subsystem_dispatch_gen;
@@ -133,6 +136,15 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
+	/// Try to compile the given validation code and send back the outcome.
+	///
+	/// The validation code is specified by the hash and will be queried from the runtime API at
+	/// the given relay-parent.
+	PreCheck(
+		Hash,
+		Hash,
+		oneshot::Sender<PreCheckOutcome>,
+	),
 }
 
 impl CandidateValidationMessage {
@@ -141,10 +153,23 @@ impl CandidateValidationMessage {
 		match self {
 			Self::ValidateFromChainState(_, _, _) => None,
 			Self::ValidateFromExhaustive(_, _, _, _, _) => None,
+			Self::PreCheck(relay_parent, _, _) => Some(*relay_parent),
 		}
 	}
 }
 
+/// The outcome of the PVF pre-checking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreCheckOutcome {
+	/// The PVF has been compiled successfully.
+	Valid,
+	/// The PVF could not be compiled.
+	Invalid,
+	/// The PVF could not be checked, e.g. because the code is not available or the preparation
+	/// didn't conclude. No vote should be cast in this case.
+	Failed,
+}
+
 
 /// Messages received by the Collator Protocol subsystem.
 #[derive(Debug, derive_more::From)]
@@ -158,9 +183,12 @@ pub enum CollatorProtocolMessage {
 	CollateOn(ParaId),
 	/// Provide a collation to distribute to validators with an optional result sender.
 	///
+	/// The hash is the hash of the parent head data the collation builds on, it is advertised to
+	/// validators along with the candidate hash.
+	///
 	/// The result sender should be informed when at least one parachain validator seconded the collation. It is also
 	/// completely okay to just drop the sender.
-	DistributeCollation(CandidateReceipt, PoV, Option<oneshot::Sender<SignedFullStatement>>),
+	DistributeCollation(CandidateReceipt, Hash, PoV, Option<oneshot::Sender<SignedFullStatement>>),
 	/// Report a collator as having provided an invalid collation. This should lead to disconnect
 	/// and blacklist of the collator.
 	ReportCollator(CollatorId),
@@ -169,6 +197,8 @@ pub enum CollatorProtocolMessage {
 	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::CollatorProtocolMessage>),
 	/// Incoming network request for a collation.
 	CollationFetchingRequest(IncomingRequest<req_res_v1::CollationFetchingRequest>),
+	/// Incoming network request for a specific collation.
+	CollationFetchingV2Request(IncomingRequest<req_res_v1::CollationFetchingV2Request>),
 	/// We recommended a particular candidate to be seconded, but it was invalid; penalize the collator.
 	///
 	/// The hash is the relay parent.
@@ -211,6 +241,13 @@ pub enum NetworkBridgeMessage {
 	/// NOTE: Messages will be processed in order (at least statement distribution relies on this).
 	SendValidationMessages(Vec<(Vec<PeerId>, protocol_v1::ValidationProtocol)>),
 
+	/// Send a message of the v2 validation protocol to one or more peers on the validation
+	/// peer-set.
+	///
+	/// Peers which only speak v1 receive the v1 encoding of the message, messages without a v1
+	/// equivalent are not sent to them.
+	SendValidationMessageV2(Vec<PeerId>, protocol_v2::ValidationProtocol),
+
 	/// Send a batch of collation messages.
 	///
 	/// NOTE: Messages will be processed in order.
@@ -251,6 +288,7 @@ impl NetworkBridgeMessage {
 			Self::SendValidationMessage(_, _) => None,
 			Self::SendCollationMessage(_, _) => None,
 			Self::SendValidationMessages(_) => None,
+			Self::SendValidationMessageV2(_, _) => None,
 			Self::SendCollationMessages(_) => None,
 			Self::ConnectToValidators { .. } => None,
 			Self::SendRequests { .. } => None,
@@ -351,6 +389,12 @@ pub enum AvailabilityStoreMessage {
 	/// Query all chunks that we have for the given candidate hash.
 	QueryAllChunks(CandidateHash, oneshot::Sender<Vec<ErasureChunk>>),
 
+	/// Query the size of the erasure chunks of the given candidate, if we have any of them.
+	///
+	/// All the chunks of a candidate have the same size, so this allows to estimate the size of
+	/// the `AvailableData` without loading it.
+	QueryChunkSize(CandidateHash, oneshot::Sender<Option<usize>>),
+
 	/// Query whether an `ErasureChunk` exists within the AV Store.
 	///
 	/// This is useful in cases like bitfield signing, when existence
@@ -402,6 +446,10 @@ pub enum ChainApiMessage {
 	/// Returns `None` if a block with the given number is not present in the db.
 	/// Note: the caller must ensure the block is finalized.
 	FinalizedBlockHash(BlockNumber, ChainApiResponseChannel<Option<Hash>>),
+	/// Request the cumulative weight of the block with the given hash, according to the
+	/// fork-choice rule of the consensus engine.
+	/// Returns `None` if the weight of the block is not known.
+	BlockWeight(Hash, ChainApiResponseChannel<Option<BlockWeight>>),
 	/// Request the last finalized block number.
 	/// This request always succeeds.
 	FinalizedBlockNumber(ChainApiResponseChannel<BlockNumber>),
@@ -494,6 +542,16 @@ pub enum RuntimeApiRequest {
 	),
 	/// Get information about the BABE epoch the block was included in.
 	CurrentBabeEpoch(RuntimeApiSender<BabeEpoch>),
+	/// Get all disputes known to the chain, along with their on-chain state.
+	Disputes(RuntimeApiSender<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>),
+	/// Get the validation code by its hash.
+	ValidationCodeByHash(Hash, RuntimeApiSender<Option<ValidationCode>>),
+	/// Get the hashes of the validation code awaiting pre-checking.
+	PvfsRequirePrecheck(RuntimeApiSender<Vec<Hash>>),
+	/// Submit a signed PVF pre-checking statement to the transaction pool.
+	///
+	/// Responses to this request are never cached.
+	SubmitPvfCheckStatement(PvfCheckStatement, ValidatorSignature, RuntimeApiSender<()>),
 }
 
 /// A message to the Runtime API subsystem.
@@ -520,7 +578,7 @@ pub enum StatementDistributionMessage {
 	Share(Hash, SignedFullStatement),
 	/// Event from the network bridge.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>),
+	NetworkBridgeUpdateV2(NetworkBridgeEvent<protocol_v2::StatementDistributionMessage>),
 	/// Get receiver for receiving incoming network requests for statement fetching.
 	StatementFetchingReceiver(mpsc::Receiver<sc_network::config::IncomingRequest>),
 }
@@ -548,6 +606,8 @@ pub struct ProvisionerInherentData {
 	pub backed_candidates: Vec<BackedCandidate>,
 	/// Dispute statement sets.
 	pub disputes: MultiDisputeStatementSet,
+	/// Proofs of misbehavior during candidate backing.
+	pub backing_misbehavior: Vec<BackingMisbehaviorProof>,
 }
 
 /// Message to the Provisioner.
@@ -656,13 +716,111 @@ pub enum ApprovalDistributionMessage {
 	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>),
 }
 
+/// Result of an `ImportStatements` request to the dispute coordinator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatementsResult {
+	/// The statements were discarded, see `DisputeCoordinatorMessage::ImportStatements`.
+	InvalidImport,
+	/// The statements were imported.
+	ValidImport,
+}
+
+/// Message to the Dispute Coordinator subsystem.
+#[derive(Debug)]
+pub enum DisputeCoordinatorMessage {
+	/// Import a statement by a validator about a candidate.
+	///
+	/// The subsystem will silently discard ancient statements or sets of only dispute-specific
+	/// statements for candidates that are previously unknown to the subsystem. The former is
+	/// simply because ancient data is not relevant and the latter is as a DoS prevention
+	/// mechanism. Both backing and approval statements already undergo anti-DoS procedures in
+	/// their respective subsystems, but statements cast specifically for disputes are not
+	/// necessarily relevant to any candidate the system is already aware of and thus present a
+	/// DoS vector. Our expectation is that nodes will notify each other of disputes over the
+	/// network by providing (at least) 2 conflicting statements, of which one is either a
+	/// backing or validation statement.
+	///
+	/// This does not do any checking of the message signature.
+	ImportStatements {
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The candidate receipt itself.
+		candidate_receipt: CandidateReceipt,
+		/// The session the candidate appears in.
+		session: SessionIndex,
+		/// Statements, with signatures checked, by validators participating in disputes.
+		///
+		/// The validator index passed alongside each statement should correspond to the index
+		/// of the validator in the set.
+		statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
+		/// Notified once the statements have been imported or discarded.
+		///
+		/// Dropped without a result if the import failed, e.g. because of a DB error.
+		pending_confirmation: Option<oneshot::Sender<ImportStatementsResult>>,
+	},
+	/// Fetch a list of all active disputes that the coordinator is aware of.
+	ActiveDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash)>>),
+	/// Fetch a list of all recent disputes the coordinator is aware of, along with their status.
+	/// These are disputes which have occurred any time in recent sessions, which may have
+	/// already concluded.
+	RecentDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash, DisputeStatus)>>),
+	/// Get candidate votes for a candidate.
+	QueryCandidateVotes(SessionIndex, CandidateHash, oneshot::Sender<Option<CandidateVotes>>),
+	/// Sign and issue local dispute votes. A value of `true` indicates validity, and `false`
+	/// invalidity.
+	IssueLocalStatement(SessionIndex, CandidateHash, CandidateReceipt, bool),
+	/// Determine the highest undisputed block within the given chain, based on where candidates
+	/// were included. If even the base block should not be finalized due to a dispute,
+	/// then `None` should be returned on the channel.
+	///
+	/// The block descriptions begin counting upwards from the block after the given `base_number`.
+	/// The `base_number` is typically the number of the last finalized block but may be slightly
+	/// higher. This block is inevitably going to be finalized so it is not accounted for by this
+	/// function.
+	DetermineUndisputedChain {
+		/// The number of the lowest possible block to vote on.
+		base_number: BlockNumber,
+		/// Descriptions of all the blocks counting upwards from the block after the base number.
+		block_descriptions: Vec<(Hash, SessionIndex, Vec<CandidateHash>)>,
+		/// A response channel - `None` to vote on base, `Some` to vote higher.
+		tx: oneshot::Sender<Option<(BlockNumber, Hash)>>,
+	},
+}
+
+/// Message to the Dispute Distribution subsystem.
+#[derive(Debug)]
+pub enum DisputeDistributionMessage {
+	/// Tell dispute distribution to distribute an explicit dispute statement to
+	/// validators.
+	SendDispute(DisputeMessage),
+	/// Incoming network request for a dispute.
+	DisputeSendingRequest(IncomingRequest<req_res_v1::DisputeRequest>),
+}
+
 /// Message to the Gossip Support subsystem.
 #[derive(Debug)]
 pub enum GossipSupportMessage {
 }
 
+/// Messages received by the chain selection subsystem.
+#[derive(Debug)]
+pub enum ChainSelectionMessage {
+	/// Signal to the chain selection subsystem that a specific block has been approved.
+	Approved(Hash),
+	/// Request the leaves in descending order by score.
+	Leaves(oneshot::Sender<Vec<Hash>>),
+	/// Request the best leaf containing the given block in its ancestry. Return `None` if
+	/// there is no such leaf.
+	BestLeafContaining(Hash, oneshot::Sender<Option<Hash>>),
+}
+
+/// Message to the PVF pre-checker subsystem.
+#[derive(Debug)]
+pub enum PvfCheckerMessage {
+}
+
 /// A message type tying together all message types that are used across Subsystems.
-#[subsystem_dispatch_gen(NetworkBridgeEvent<protocol_v1::ValidationProtocol>)]
+#[subsystem_dispatch_gen(NetworkBridgeEvent<protocol_v2::ValidationProtocol>)]
 #[derive(Debug, derive_more::From)]
 pub enum AllMessages {
 	/// Message for the validation subsystem.
@@ -713,6 +871,18 @@ pub enum AllMessages {
 	/// Message for the Gossip Support subsystem.
 	#[skip]
 	GossipSupport(GossipSupportMessage),
+	/// Message for the Dispute Coordinator subsystem.
+	#[skip]
+	DisputeCoordinator(DisputeCoordinatorMessage),
+	/// Message for the Dispute Distribution subsystem.
+	#[skip]
+	DisputeDistribution(DisputeDistributionMessage),
+	/// Message for the Chain Selection subsystem.
+	#[skip]
+	ChainSelection(ChainSelectionMessage),
+	/// Message for the PVF pre-checker subsystem.
+	#[skip]
+	PvfChecker(PvfCheckerMessage),
 }
 
 impl From<IncomingRequest<req_res_v1::PoVFetchingRequest>> for AvailabilityDistributionMessage {
@@ -730,6 +900,17 @@ impl From<IncomingRequest<req_res_v1::CollationFetchingRequest>> for CollatorPro
 		Self::CollationFetchingRequest(req)
 	}
 }
+impl From<IncomingRequest<req_res_v1::CollationFetchingV2Request>> for CollatorProtocolMessage {
+	fn from(req: IncomingRequest<req_res_v1::CollationFetchingV2Request>) -> Self {
+		Self::CollationFetchingV2Request(req)
+	}
+}
+
+impl From<IncomingRequest<req_res_v1::DisputeRequest>> for DisputeDistributionMessage {
+	fn from(req: IncomingRequest<req_res_v1::DisputeRequest>) -> Self {
+		Self::DisputeSendingRequest(req)
+	}
+}
 
 impl From<IncomingRequest<req_res_v1::PoVFetchingRequest>> for AllMessages {
 	fn from(req: IncomingRequest<req_res_v1::PoVFetchingRequest>) -> Self {
@@ -746,8 +927,18 @@ impl From<IncomingRequest<req_res_v1::CollationFetchingRequest>> for AllMessages
 		From::<CollatorProtocolMessage>::from(From::from(req))
 	}
 }
+impl From<IncomingRequest<req_res_v1::CollationFetchingV2Request>> for AllMessages {
+	fn from(req: IncomingRequest<req_res_v1::CollationFetchingV2Request>) -> Self {
+		From::<CollatorProtocolMessage>::from(From::from(req))
+	}
+}
 impl From<IncomingRequest<req_res_v1::AvailableDataFetchingRequest>> for AllMessages {
 	fn from(req: IncomingRequest<req_res_v1::AvailableDataFetchingRequest>) -> Self {
 		From::<AvailabilityRecoveryMessage>::from(From::from(req))
 	}
 }
+impl From<IncomingRequest<req_res_v1::DisputeRequest>> for AllMessages {
+	fn from(req: IncomingRequest<req_res_v1::DisputeRequest>) -> Self {
+		From::<DisputeDistributionMessage>::from(From::from(req))
+	}
+}
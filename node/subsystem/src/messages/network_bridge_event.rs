@@ -18,14 +18,16 @@ use std::convert::TryFrom;
 
 pub use sc_network::{ReputationChange, PeerId};
 
-use polkadot_node_network_protocol::{WrongVariant, ObservedRole, OurView, View};
+use polkadot_node_network_protocol::{
+	WrongVariant, ObservedRole, OurView, View, peer_set::ProtocolVersion,
+};
 use polkadot_primitives::v1::AuthorityDiscoveryId;
 
 /// Events from network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkBridgeEvent<M> {
-	/// A peer has connected.
-	PeerConnected(PeerId, ObservedRole, Option<AuthorityDiscoveryId>),
+	/// A peer has connected, speaking the given version of the peer set's protocol.
+	PeerConnected(PeerId, ObservedRole, ProtocolVersion, Option<AuthorityDiscoveryId>),
 
 	/// A peer has disconnected.
 	PeerDisconnected(PeerId),
@@ -60,8 +62,13 @@ impl<M> NetworkBridgeEvent<M> {
 		where T: 'a + Clone, &'a T: TryFrom<&'a M, Error = WrongVariant>
 	{
 		Ok(match *self {
-			NetworkBridgeEvent::PeerConnected(ref peer, ref role, ref authority_id)
-				=> NetworkBridgeEvent::PeerConnected(peer.clone(), role.clone(), authority_id.clone()),
+			NetworkBridgeEvent::PeerConnected(ref peer, ref role, ref version, ref authority_id)
+				=> NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					role.clone(),
+					*version,
+					authority_id.clone(),
+				),
 			NetworkBridgeEvent::PeerDisconnected(ref peer)
 				=> NetworkBridgeEvent::PeerDisconnected(peer.clone()),
 			NetworkBridgeEvent::PeerMessage(ref peer, ref msg)
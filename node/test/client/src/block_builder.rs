@@ -105,6 +105,7 @@ impl InitPolkadotBlockBuilder for Client {
 			bitfields: Vec::new(),
 			backed_candidates: Vec::new(),
 			disputes: Vec::new(),
+			backing_misbehavior: Vec::new(),
 			parent_header: parent_header,
 		};
 
@@ -86,6 +86,9 @@ pub fn new_full(
 		None,
 		None,
 		worker_program_path,
+		None,
+		None,
+		Default::default(),
 	)
 }
 
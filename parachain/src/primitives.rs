@@ -308,3 +308,58 @@ pub struct ValidationResult {
 	/// The mark which specifies the block number up to which all inbound HRMP messages are processed.
 	pub hrmp_watermark: RelayChainBlockNumber,
 }
+
+/// The format of the XCMP message blob exchanged between parachains over an HRMP channel.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum XcmpMessageFormat {
+	/// Encoded `VersionedXcm` messages, all concatenated.
+	ConcatenatedVersionedXcm,
+	/// Encoded `Vec<u8>` messages, all concatenated.
+	ConcatenatedEncodedBlob,
+	/// One or more channel control signals; these should be interpreted immediately upon receipt
+	/// from the relay-chain.
+	Signals,
+}
+
+/// Something that should be called for each batch of messages received over XCMP.
+pub trait XcmpMessageHandler {
+	/// Handle some incoming XCMP messages (note these are the big one-per-block aggregate
+	/// messages).
+	///
+	/// Also, process messages up to some `max_weight`.
+	fn handle_xcmp_messages<'a, I: Iterator<Item=(Id, RelayChainBlockNumber, &'a [u8])>>(
+		iter: I,
+		max_weight: u64,
+	) -> u64;
+}
+
+impl XcmpMessageHandler for () {
+	fn handle_xcmp_messages<'a, I: Iterator<Item=(Id, RelayChainBlockNumber, &'a [u8])>>(
+		iter: I,
+		_max_weight: u64,
+	) -> u64 {
+		for _ in iter {}
+		0
+	}
+}
+
+/// Something that should be called when a downward message is received.
+pub trait DmpMessageHandler {
+	/// Handle some incoming DMP messages (note these are individual XCM messages).
+	///
+	/// Also, process messages up to some `max_weight`.
+	fn handle_dmp_messages(
+		iter: impl Iterator<Item=(RelayChainBlockNumber, Vec<u8>)>,
+		max_weight: u64,
+	) -> u64;
+}
+
+impl DmpMessageHandler for () {
+	fn handle_dmp_messages(
+		iter: impl Iterator<Item=(RelayChainBlockNumber, Vec<u8>)>,
+		_max_weight: u64,
+	) -> u64 {
+		iter.for_each(drop);
+		0
+	}
+}
@@ -65,6 +65,9 @@ fn main() -> Result<()> {
 							true,
 							None,
 							None,
+							None,
+							None,
+							Default::default(),
 						).map_err(|e| e.to_string())?;
 						let mut overseer_handler = full_node
 							.overseer_handler
@@ -476,6 +476,8 @@ impl From<BitVec<bitvec::order::Lsb0, u8>> for AvailabilityBitfield {
 
 /// A signed compact statement, suitable to be sent to the chain.
 pub type SignedStatement = Signed<CompactStatement>;
+/// A signed compact statement with signature not yet checked.
+pub type UncheckedSignedStatement = UncheckedSigned<CompactStatement>;
 
 /// A bitfield signed by a particular validator about the availability of pending candidates.
 pub type SignedAvailabilityBitfield = Signed<AvailabilityBitfield>;
@@ -941,6 +943,20 @@ sp_api::decl_runtime_apis! {
 		/// Get the validation code from its hash.
 		#[skip_initialize_block]
 		fn validation_code_by_hash(hash: Hash) -> Option<ValidationCode>;
+
+		/// Get all disputes known to the chain, along with their on-chain state.
+		#[skip_initialize_block]
+		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<N>)>;
+
+		/// Returns the hashes of all validation code which is currently awaiting PVF pre-checking
+		/// votes from the validators.
+		#[skip_initialize_block]
+		fn pvfs_require_precheck() -> Vec<Hash>;
+
+		/// Submits a signed PVF pre-checking statement into the transaction pool.
+		///
+		/// NOTE: This function is meant to be used by the node side and has no effect on-chain.
+		fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature);
 	}
 }
 
@@ -1039,6 +1055,16 @@ pub enum ConsensusLog {
 	/// number in the current chain, inclusive.
 	#[codec(index = 3)]
 	ForceApprove(BlockNumber),
+	/// A signal to revert the block number in the same chain as the
+	/// header this digest is part of and all of its descendents.
+	///
+	/// It is a no-op for a block to contain a revert digest targeting
+	/// its own number or a higher number.
+	///
+	/// In practice, these are issued when on-chain logic has detected an
+	/// invalid parachain block within its own chain, due to a dispute.
+	#[codec(index = 4)]
+	Revert(BlockNumber),
 }
 
 impl ConsensusLog {
@@ -1073,18 +1099,79 @@ pub enum DisputeStatement {
 	Invalid(InvalidDisputeStatementKind),
 }
 
+impl DisputeStatement {
+	/// Get the payload data for this type of dispute statement.
+	pub fn payload_data(&self, candidate_hash: CandidateHash, session: SessionIndex) -> Vec<u8> {
+		match *self {
+			DisputeStatement::Valid(ValidDisputeStatementKind::Explicit) => {
+				ExplicitDisputeStatement {
+					valid: true,
+					candidate_hash,
+					session,
+				}.signing_payload()
+			},
+			DisputeStatement::Valid(ValidDisputeStatementKind::BackingSeconded(inclusion_parent)) => {
+				(
+					CompactStatement::Seconded(candidate_hash),
+					SigningContext { session_index: session, parent_hash: inclusion_parent },
+				).encode()
+			},
+			DisputeStatement::Valid(ValidDisputeStatementKind::BackingValid(inclusion_parent)) => {
+				(
+					CompactStatement::Valid(candidate_hash),
+					SigningContext { session_index: session, parent_hash: inclusion_parent },
+				).encode()
+			},
+			DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) => {
+				ApprovalVote(candidate_hash).signing_payload(session)
+			},
+			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit) => {
+				ExplicitDisputeStatement {
+					valid: false,
+					candidate_hash,
+					session,
+				}.signing_payload()
+			},
+		}
+	}
+
+	/// Check the signature on a dispute statement.
+	pub fn check_signature(
+		&self,
+		validator_public: &ValidatorId,
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+		validator_signature: &ValidatorSignature,
+	) -> Result<(), ()> {
+		let payload = self.payload_data(candidate_hash, session);
+
+		if validator_signature.verify(&payload[..], &validator_public) {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Whether the statement indicates validity of the candidate.
+	pub fn indicates_validity(&self) -> bool {
+		matches!(self, DisputeStatement::Valid(_))
+	}
+}
+
 /// Different kinds of statements of validity on  a candidate.
 #[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
 pub enum ValidDisputeStatementKind {
 	/// An explicit statement issued as part of a dispute.
 	#[codec(index = 0)]
 	Explicit,
-	/// A seconded statement on a candidate from the backing phase.
+	/// A seconded statement on a candidate from the backing phase, signed under the given
+	/// relay-parent.
 	#[codec(index = 1)]
-	BackingSeconded,
-	/// A valid statement on a candidate from the backing phase.
+	BackingSeconded(Hash),
+	/// A valid statement on a candidate from the backing phase, signed under the given
+	/// relay-parent.
 	#[codec(index = 2)]
-	BackingValid,
+	BackingValid(Hash),
 	/// An approval vote from the approval checking phase.
 	#[codec(index = 3)]
 	ApprovalChecking,
@@ -1109,6 +1196,53 @@ pub struct ExplicitDisputeStatement {
 	pub session: SessionIndex,
 }
 
+impl ExplicitDisputeStatement {
+	/// Produce the payload used for signing this type of statement.
+	pub fn signing_payload(&self) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"DISP";
+
+		(MAGIC, self.valid, self.candidate_hash, self.session).encode()
+	}
+}
+
+/// A vote of approval on a candidate.
+#[derive(Clone, Encode, Decode, RuntimeDebug)]
+pub struct ApprovalVote(pub CandidateHash);
+
+impl ApprovalVote {
+	/// Yields the signing payload for this approval vote.
+	pub fn signing_payload(
+		&self,
+		session_index: SessionIndex,
+	) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"APPR";
+
+		(MAGIC, &self.0, session_index).encode()
+	}
+}
+
+/// A statement from a validator on whether some validation code passed PVF pre-checking.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct PvfCheckStatement {
+	/// `true` if the code passed pre-checking and `false` otherwise.
+	pub accept: bool,
+	/// The hash of the validation code that was checked.
+	pub subject: Hash,
+	/// The session index this statement is issued in.
+	pub session_index: SessionIndex,
+	/// The index of the validator within the session's active validator set.
+	pub validator_index: ValidatorIndex,
+}
+
+impl PvfCheckStatement {
+	/// Produce the payload used for signing this statement.
+	pub fn signing_payload(&self) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"VCPC"; // for "validation code pre-checking"
+
+		(MAGIC, self.accept, self.subject, self.session_index, self.validator_index).encode()
+	}
+}
+
 /// A set of statements about a specific candidate.
 #[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
 pub struct DisputeStatementSet {
@@ -1124,11 +1258,14 @@ pub struct DisputeStatementSet {
 pub type MultiDisputeStatementSet = Vec<DisputeStatementSet>;
 
 /// The entire state of a dispute.
-#[derive(Encode, Decode, Clone, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
 pub struct DisputeState<N = BlockNumber> {
 	/// A bitfield indicating all validators for the candidate.
+	#[cfg_attr(feature = "std", ignore_malloc_size_of = "outside type")]
 	pub validators_for: BitVec<bitvec::order::Lsb0, u8>, // one bit per validator.
 	/// A bitfield indicating all validators against the candidate.
+	#[cfg_attr(feature = "std", ignore_malloc_size_of = "outside type")]
 	pub validators_against: BitVec<bitvec::order::Lsb0, u8>, // one bit per validator.
 	/// The block number at which the dispute started on-chain.
 	pub start: N,
@@ -1136,6 +1273,77 @@ pub struct DisputeState<N = BlockNumber> {
 	pub concluded_at: Option<N>,
 }
 
+/// Conflicting statements issued by a single validator during candidate backing.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub enum BackingMisbehavior {
+	/// The validator seconded two distinct candidates under the same relay-parent.
+	#[codec(index = 0)]
+	DoubleSeconded {
+		/// The first candidate seconded and the signature on the `Seconded` statement.
+		first: (CandidateHash, ValidatorSignature),
+		/// The second candidate seconded and the signature on the `Seconded` statement.
+		second: (CandidateHash, ValidatorSignature),
+	},
+	/// The validator both seconded a candidate and issued a separate `Valid` statement
+	/// on the same candidate.
+	#[codec(index = 1)]
+	SecondedAndValid {
+		/// The candidate which was both seconded and explicitly voted valid.
+		candidate_hash: CandidateHash,
+		/// The signature on the `Seconded` statement.
+		seconded: ValidatorSignature,
+		/// The signature on the `Valid` statement.
+		valid: ValidatorSignature,
+	},
+}
+
+/// A self-contained proof of misbehavior by a validator during candidate backing.
+///
+/// All statements within the proof were signed by the same validator in the signing context
+/// given by the session and relay-parent.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct BackingMisbehaviorProof {
+	/// The session index the statements were signed in.
+	pub session: SessionIndex,
+	/// The relay-parent the statements were signed under.
+	pub relay_parent: Hash,
+	/// The index of the misbehaving validator within the session.
+	pub validator_index: ValidatorIndex,
+	/// The conflicting statements.
+	pub misbehavior: BackingMisbehavior,
+}
+
+impl BackingMisbehaviorProof {
+	/// Check the proof against the public key of the validator it accuses.
+	///
+	/// This checks both that the statements actually conflict and that they carry valid
+	/// signatures by the validator.
+	pub fn check(&self, validator_public: &ValidatorId) -> Result<(), ()> {
+		let seconded = DisputeStatement::Valid(
+			ValidDisputeStatementKind::BackingSeconded(self.relay_parent),
+		);
+
+		match self.misbehavior {
+			BackingMisbehavior::DoubleSeconded { ref first, ref second } => {
+				if first.0 == second.0 {
+					return Err(());
+				}
+
+				seconded.check_signature(validator_public, first.0, self.session, &first.1)?;
+				seconded.check_signature(validator_public, second.0, self.session, &second.1)
+			}
+			BackingMisbehavior::SecondedAndValid { candidate_hash, seconded: ref seconded_sig, ref valid } => {
+				let valid_statement = DisputeStatement::Valid(
+					ValidDisputeStatementKind::BackingValid(self.relay_parent),
+				);
+
+				seconded.check_signature(validator_public, candidate_hash, self.session, seconded_sig)?;
+				valid_statement.check_signature(validator_public, candidate_hash, self.session, valid)
+			}
+		}
+	}
+}
+
 /// Parachains inherent-data passed into the runtime by a block author
 #[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
 pub struct InherentData<HDR: HeaderT = Header> {
@@ -1147,6 +1355,8 @@ pub struct InherentData<HDR: HeaderT = Header> {
 	pub disputes: MultiDisputeStatementSet,
 	/// The parent block header. Used for checking state proofs.
 	pub parent_header: HDR,
+	/// Proofs of misbehavior by validators during candidate backing.
+	pub backing_misbehavior: Vec<BackingMisbehaviorProof>,
 }
 
 #[cfg(test)]
@@ -65,7 +65,7 @@ frame_support::construct_runtime!(
 
 		// Parachains Runtime
 		Configuration: configuration::{Pallet, Call, Storage, Config<T>},
-		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config<T>},
+		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config<T>, ValidateUnsigned},
 
 		// Para Onboarding Pallets
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>},
@@ -163,6 +163,11 @@ impl configuration::Config for Test { }
 
 impl shared::Config for Test { }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
 impl paras::Config for Test {
 	type Origin = Origin;
 	type Event = Event;
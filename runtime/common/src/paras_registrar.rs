@@ -577,7 +577,7 @@ mod tests {
 		{
 			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-			Parachains: paras::{Pallet, Origin, Call, Storage, Config<T>, Event},
+			Parachains: paras::{Pallet, Origin, Call, Storage, Config<T>, Event, ValidateUnsigned},
 			Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>},
 		}
 	);
@@ -635,6 +635,11 @@ mod tests {
 
 	impl shared::Config for Test {}
 
+	impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+		type Extrinsic = UncheckedExtrinsic;
+		type OverarchingCall = Call;
+	}
+
 	impl paras::Config for Test {
 		type Origin = Origin;
 		type Event = Event;
@@ -18,22 +18,28 @@
 
 use parity_scale_codec::Encode;
 use sp_std::marker::PhantomData;
-use xcm::opaque::{VersionedXcm, v0::{SendXcm, MultiLocation, Junction, Xcm, Result, Error}};
+use xcm::opaque::v0::{SendXcm, MultiLocation, Junction, Xcm, Result, Error};
+use xcm::WrapVersion;
 use runtime_parachains::{configuration, dmp};
 
 /// Xcm sender for relay chain. It only sends downward message.
-pub struct ChildParachainRouter<T>(PhantomData<T>);
+///
+/// Messages are translated by `W` into the XCM version which the destination parachain understands before
+/// they are enqueued.
+pub struct ChildParachainRouter<T, W>(PhantomData<(T, W)>);
 
-impl<T: configuration::Config + dmp::Config> SendXcm for ChildParachainRouter<T> {
+impl<T: configuration::Config + dmp::Config, W: WrapVersion> SendXcm for ChildParachainRouter<T, W> {
 	fn send_xcm(dest: MultiLocation, msg: Xcm) -> Result {
 		match dest {
 			MultiLocation::X1(Junction::Parachain(id)) => {
 				// Downward message passing.
+				let versioned_xcm = W::wrap_version(&dest, msg)
+					.map_err(|()| Error::DestinationUnsupported)?;
 				let config = <configuration::Module<T>>::config();
 				<dmp::Module<T>>::queue_downward_message(
 					&config,
 					id.into(),
-					VersionedXcm::from(msg).encode(),
+					versioned_xcm.encode(),
 				).map_err(Into::<Error>::into)?;
 				Ok(())
 			}
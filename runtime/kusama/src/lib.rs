@@ -29,7 +29,8 @@ use primitives::v1::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
 	CoreState, GroupRotationInfo, Hash, Id as ParaId, Moment, Nonce, OccupiedCoreAssumption,
 	PersistedValidationData, Signature, ValidationCode, ValidatorId, ValidatorIndex,
-	InboundDownwardMessage, InboundHrmpMessage, SessionInfo,
+	InboundDownwardMessage, InboundHrmpMessage, SessionInfo, CandidateHash,
+	DisputeState, PvfCheckStatement, ValidatorSignature,
 };
 use runtime_common::{
 	claims, paras_registrar, xcm_sender, slots, auctions, crowdloan,
@@ -51,6 +52,7 @@ use runtime_parachains::ump as parachains_ump;
 use runtime_parachains::hrmp as parachains_hrmp;
 use runtime_parachains::scheduler as parachains_scheduler;
 use runtime_parachains::reward_points as parachains_reward_points;
+use runtime_parachains::slashing as parachains_slashing;
 use runtime_parachains::runtime_api_impl::v1 as parachains_runtime_api_impl;
 
 use xcm::v0::{MultiLocation::{self, Null, X1}, NetworkId, BodyId, Xcm, Junction::Parachain};
@@ -58,10 +60,11 @@ use xcm::v0::MultiAsset::{self, AllConcreteFungible};
 use xcm_builder::{
 	AccountId32Aliases, ChildParachainConvertsVia, SovereignSignedViaLocation, CurrencyAdapter as XcmCurrencyAdapter,
 	ChildParachainAsNative, SignedAccountId32AsNative, ChildSystemParachainAsSuperuser, LocationInverter,
-	IsConcrete, FixedWeightBounds, TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
-	IsChildSystemParachain, UsingComponents, BackingToPlurality, SignedToAccountId32,
+	IsConcrete, WeightInfoBounds, TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
+	AllowKnownQueryResponses, AllowSubscriptionsFrom, IsChildSystemParachain, UsingComponents, BackingToPlurality,
+	SignedToAccountId32,
 };
-use xcm_executor::XcmExecutor;
+use xcm_executor::{XcmExecutor, traits::XcmWeightInfo};
 use sp_arithmetic::Perquintill;
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
@@ -1059,6 +1062,7 @@ impl parachains_session_info::Config for Runtime {}
 
 impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
+	type DisputesHandler = ();
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
 }
 
@@ -1075,6 +1079,7 @@ impl parachains_ump::Config for Runtime {
 	type Event = Event;
 	type UmpSink = crate::parachains_ump::XcmSink<XcmExecutor<XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
+	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
 
 impl parachains_dmp::Config for Runtime {}
@@ -1085,7 +1090,9 @@ impl parachains_hrmp::Config for Runtime {
 	type Currency = Balances;
 }
 
-impl parachains_paras_inherent::Config for Runtime {}
+impl parachains_paras_inherent::Config for Runtime {
+	type PunishBackingMisbehavior = parachains_slashing::SlashValidatorsForBackingMisbehavior<Runtime>;
+}
 
 impl parachains_scheduler::Config for Runtime {}
 
@@ -1227,13 +1234,47 @@ type LocalOriginConverter = (
 parameter_types! {
 	/// The amount of weight an XCM operation takes. This is a safe overestimate.
 	pub const BaseXcmWeight: Weight = 1_000_000_000;
-}
+	/// The most assets the holding register is weighed as containing, which is how a wildcard is weighed.
+	pub const MaxAssetsInHolding: u32 = 8;
+}
+
+/// The weight of each XCM instruction and order. They haven't been benchmarked for this runtime yet, so
+/// each of them costs `BaseXcmWeight`, for each asset it carries.
+pub struct XcmWeight;
+impl XcmWeightInfo for XcmWeight {
+	fn withdraw_asset() -> Weight { BaseXcmWeight::get() }
+	fn reserve_asset_deposit() -> Weight { BaseXcmWeight::get() }
+	fn teleport_asset() -> Weight { BaseXcmWeight::get() }
+	fn query_response() -> Weight { BaseXcmWeight::get() }
+	fn transfer_asset() -> Weight { BaseXcmWeight::get() }
+	fn transfer_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn transact() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_new_channel_open_request() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_accepted() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_closing() -> Weight { BaseXcmWeight::get() }
+	fn relayed_from() -> Weight { BaseXcmWeight::get() }
+	fn claim_asset() -> Weight { BaseXcmWeight::get() }
+	fn subscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn unsubscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn order_null() -> Weight { BaseXcmWeight::get() }
+	fn deposit_asset() -> Weight { BaseXcmWeight::get() }
+	fn deposit_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn exchange_asset() -> Weight { BaseXcmWeight::get() }
+	fn initiate_reserve_withdraw() -> Weight { BaseXcmWeight::get() }
+	fn initiate_teleport() -> Weight { BaseXcmWeight::get() }
+	fn query_holding() -> Weight { BaseXcmWeight::get() }
+	fn buy_execution() -> Weight { BaseXcmWeight::get() }
+	fn report_outcome() -> Weight { BaseXcmWeight::get() }
+}
+
+/// The weigher of XCM messages.
+pub type XcmWeigher = WeightInfoBounds<XcmWeight, Call, MaxAssetsInHolding>;
 
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
 /// individual routers.
 pub type XcmRouter = (
 	// Only one router so far - use DMP to communicate with child parachains.
-	xcm_sender::ChildParachainRouter<Runtime>,
+	xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
 );
 
 parameter_types! {
@@ -1252,6 +1293,10 @@ pub type Barrier = (
 	AllowTopLevelPaidExecutionFrom<All<MultiLocation>>,
 	// Messages coming from system parachains need not pay for execution.
 	AllowUnpaidExecutionFrom<IsChildSystemParachain<ParaId>>,
+	// Expected responses to queries that we have sent are OK.
+	AllowKnownQueryResponses<XcmPallet>,
+	// Subscriptions for version tracking are OK.
+	AllowSubscriptionsFrom<All<MultiLocation>>,
 );
 
 pub struct XcmConfig;
@@ -1264,10 +1309,13 @@ impl xcm_executor::Config for XcmConfig {
 	type IsTeleporter = TrustedTeleporters;
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
 	// The weight trader piggybacks on the existing transaction-fee conversion logic.
 	type Trader = UsingComponents<WeightToFee, KsmLocation, AccountId, Balances, ToAuthor<Runtime>>;
-	type ResponseHandler = ();
+	type ResponseHandler = XcmPallet;
+	type AssetTrap = XcmPallet;
+	type AssetClaims = XcmPallet;
+	type SubscriptionService = XcmPallet;
 }
 
 parameter_types! {
@@ -1331,6 +1379,11 @@ impl frame_support::traits::Contains<(MultiLocation, Xcm<Call>)> for OnlyWithdra
 	}
 }
 
+parameter_types! {
+	pub const MaxVersionNotifyTargets: u32 = 1_000;
+	pub const VersionNotificationsPerBlock: u32 = 10;
+}
+
 impl pallet_xcm::Config for Runtime {
 	type Event = Event;
 	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
@@ -1342,7 +1395,13 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = All<(MultiLocation, Vec<MultiAsset>)>;
 	type XcmReserveTransferFilter = All<(MultiLocation, Vec<MultiAsset>)>;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Origin = Origin;
+	type Call = Call;
+	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type MaxVersionNotifyTargets = MaxVersionNotifyTargets;
+	type VersionNotificationsPerBlock = VersionNotificationsPerBlock;
 }
 
 parameter_types! {
@@ -1456,7 +1515,7 @@ construct_runtime! {
 		ParasInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 53,
 		ParasInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 54,
 		ParasScheduler: parachains_scheduler::{Pallet, Call, Storage} = 55,
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>} = 56,
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>, ValidateUnsigned} = 56,
 		ParasInitializer: parachains_initializer::{Pallet, Call, Storage} = 57,
 		ParasDmp: parachains_dmp::{Pallet, Call, Storage} = 58,
 		ParasUmp: parachains_ump::{Pallet, Call, Storage, Event} = 59,
@@ -1470,7 +1529,14 @@ construct_runtime! {
 		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 73,
 
 		// Pallet for sending XCM.
-		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>} = 99,
+		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 99,
+	}
+}
+
+pub struct ParachainsConfigurationMigration;
+impl frame_support::traits::OnRuntimeUpgrade for ParachainsConfigurationMigration {
+	fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		parachains_configuration::migration::migrate_to_pvf_checking::<Runtime>()
 	}
 }
 
@@ -1528,7 +1594,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
-	GrandpaStoragePrefixMigration,
+	(GrandpaStoragePrefixMigration, ParachainsConfigurationMigration),
 >;
 /// The payload being signed in the transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -1663,6 +1729,18 @@ sp_api::impl_runtime_apis! {
 		fn validation_code_by_hash(hash: Hash) -> Option<ValidationCode> {
 			parachains_runtime_api_impl::validation_code_by_hash::<Runtime>(hash)
 		}
+
+		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
+			Vec::new()
+		}
+
+		fn pvfs_require_precheck() -> Vec<Hash> {
+			parachains_runtime_api_impl::pvfs_require_precheck::<Runtime>()
+		}
+
+		fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) {
+			parachains_runtime_api_impl::submit_pvf_check_statement::<Runtime>(stmt, signature)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block, BeefyId> for Runtime {
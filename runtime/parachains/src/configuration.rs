@@ -24,13 +24,15 @@ use frame_support::{
 	decl_storage, decl_module, decl_error,
 	ensure,
 	dispatch::DispatchResult,
-	weights::{DispatchClass, Weight},
+	weights::{constants::WEIGHT_PER_MILLIS, DispatchClass, Weight},
 };
 use parity_scale_codec::{Encode, Decode};
 use frame_system::ensure_root;
 use sp_runtime::traits::Zero;
 use crate::shared;
 
+pub mod migration;
+
 /// All configuration of the runtime with respect to parachains and parathreads.
 #[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -172,6 +174,18 @@ pub struct HostConfiguration<BlockNumber> {
 	pub needed_approvals: u32,
 	/// The number of samples to do of the RelayVRFModulo approval assignment criterion.
 	pub relay_vrf_modulo_samples: u32,
+	/// Whether new validation code must pass PVF pre-checking by the validators before a code
+	/// upgrade or onboarding that uses it is enacted.
+	pub pvf_checking_enabled: bool,
+	/// The number of sessions a PVF pre-checking vote can stay open. If the vote hasn't reached
+	/// a supermajority by then, the code is rejected.
+	///
+	/// Must be at least 1.
+	pub pvf_voting_ttl: SessionIndex,
+	/// The maximum amount of weight any individual upward message may consume. Messages requiring
+	/// more weight are moved to the overweight queue, from where they can only be dispatched
+	/// manually.
+	pub ump_max_individual_weight: Weight,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -217,6 +231,9 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			hrmp_max_parachain_outbound_channels: Default::default(),
 			hrmp_max_parathread_outbound_channels: Default::default(),
 			hrmp_max_message_num_per_candidate: Default::default(),
+			pvf_checking_enabled: false,
+			pvf_voting_ttl: 2,
+			ump_max_individual_weight: 20 * WEIGHT_PER_MILLIS,
 		}
 	}
 }
@@ -243,6 +260,10 @@ impl<BlockNumber: Zero> HostConfiguration<BlockNumber> {
 		if self.no_show_slots.is_zero() {
 			panic!("`no_show_slots` must be at least 1!")
 		}
+
+		if self.pvf_voting_ttl.is_zero() {
+			panic!("`pvf_voting_ttl` must be at least 1!")
+		}
 	}
 }
 
@@ -693,6 +714,40 @@ decl_module! {
 			});
 			Ok(())
 		}
+
+		/// Enable or disable PVF pre-checking of new validation code.
+		#[weight = (1_000, DispatchClass::Operational)]
+		pub fn set_pvf_checking_enabled(origin, new: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::update_config_member(|config| {
+				sp_std::mem::replace(&mut config.pvf_checking_enabled, new) != new
+			});
+			Ok(())
+		}
+
+		/// Set the number of sessions a PVF pre-checking vote can stay open.
+		/// Must be at least 1.
+		#[weight = (1_000, DispatchClass::Operational)]
+		pub fn set_pvf_voting_ttl(origin, new: SessionIndex) -> DispatchResult {
+			ensure_root(origin)?;
+
+			ensure!(!new.is_zero(), Error::<T>::InvalidNewValue);
+
+			Self::update_config_member(|config| {
+				sp_std::mem::replace(&mut config.pvf_voting_ttl, new) != new
+			});
+			Ok(())
+		}
+
+		/// Sets the maximum amount of weight any individual upward message may consume.
+		#[weight = (1_000, DispatchClass::Operational)]
+		pub fn set_ump_max_individual_weight(origin, new: Weight) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::update_config_member(|config| {
+				sp_std::mem::replace(&mut config.ump_max_individual_weight, new) != new
+			});
+			Ok(())
+		}
 	}
 }
 
@@ -820,6 +875,9 @@ mod tests {
 				hrmp_max_parachain_outbound_channels: 100,
 				hrmp_max_parathread_outbound_channels: 200,
 				hrmp_max_message_num_per_candidate: 20,
+				pvf_checking_enabled: true,
+				pvf_voting_ttl: 3,
+				ump_max_individual_weight: 909,
 			};
 
 			assert!(<Configuration as Store>::PendingConfig::get(shared::SESSION_DELAY).is_none());
@@ -955,6 +1013,18 @@ mod tests {
 				Origin::root(),
 				new_config.hrmp_max_message_num_per_candidate,
 			).unwrap();
+			Configuration::set_pvf_checking_enabled(
+				Origin::root(),
+				new_config.pvf_checking_enabled,
+			).unwrap();
+			Configuration::set_pvf_voting_ttl(
+				Origin::root(),
+				new_config.pvf_voting_ttl,
+			).unwrap();
+			Configuration::set_ump_max_individual_weight(
+				Origin::root(),
+				new_config.ump_max_individual_weight,
+			).unwrap();
 
 			assert_eq!(<Configuration as Store>::PendingConfig::get(shared::SESSION_DELAY), Some(new_config));
 		})
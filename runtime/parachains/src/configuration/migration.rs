@@ -0,0 +1,223 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations for the configuration module.
+
+use super::{Config, HostConfiguration, Store, Module};
+use frame_support::{
+	weights::{constants::WEIGHT_PER_MILLIS, Weight},
+	storage::{StorageValue, IterableStorageMap},
+	traits::Get,
+};
+use parity_scale_codec::{Encode, Decode};
+use primitives::v1::{Balance, SessionIndex};
+
+/// The layout of [`HostConfiguration`] before the PVF pre-checking parameters and the maximum
+/// weight of an individual upward message were added.
+#[derive(Encode, Decode)]
+pub struct OldHostConfiguration<BlockNumber> {
+	pub max_code_size: u32,
+	pub max_head_data_size: u32,
+	pub max_upward_queue_count: u32,
+	pub max_upward_queue_size: u32,
+	pub max_upward_message_size: u32,
+	pub max_upward_message_num_per_candidate: u32,
+	pub hrmp_max_message_num_per_candidate: u32,
+	pub validation_upgrade_frequency: BlockNumber,
+	pub validation_upgrade_delay: BlockNumber,
+	pub max_pov_size: u32,
+	pub max_downward_message_size: u32,
+	pub ump_service_total_weight: Weight,
+	pub hrmp_max_parachain_outbound_channels: u32,
+	pub hrmp_max_parathread_outbound_channels: u32,
+	pub hrmp_open_request_ttl: u32,
+	pub hrmp_sender_deposit: Balance,
+	pub hrmp_recipient_deposit: Balance,
+	pub hrmp_channel_max_capacity: u32,
+	pub hrmp_channel_max_total_size: u32,
+	pub hrmp_max_parachain_inbound_channels: u32,
+	pub hrmp_max_parathread_inbound_channels: u32,
+	pub hrmp_channel_max_message_size: u32,
+	pub code_retention_period: BlockNumber,
+	pub parathread_cores: u32,
+	pub parathread_retries: u32,
+	pub group_rotation_frequency: BlockNumber,
+	pub chain_availability_period: BlockNumber,
+	pub thread_availability_period: BlockNumber,
+	pub scheduling_lookahead: u32,
+	pub max_validators_per_core: Option<u32>,
+	pub max_validators: Option<u32>,
+	pub dispute_period: SessionIndex,
+	pub dispute_post_conclusion_acceptance_period: BlockNumber,
+	pub dispute_max_spam_slots: u32,
+	pub dispute_conclusion_by_time_out_period: BlockNumber,
+	pub no_show_slots: u32,
+	pub n_delay_tranches: u32,
+	pub zeroth_delay_tranche_width: u32,
+	pub needed_approvals: u32,
+	pub relay_vrf_modulo_samples: u32,
+}
+
+fn migrate_config<BlockNumber>(
+	old: OldHostConfiguration<BlockNumber>,
+) -> HostConfiguration<BlockNumber> {
+	HostConfiguration {
+		max_code_size: old.max_code_size,
+		max_head_data_size: old.max_head_data_size,
+		max_upward_queue_count: old.max_upward_queue_count,
+		max_upward_queue_size: old.max_upward_queue_size,
+		max_upward_message_size: old.max_upward_message_size,
+		max_upward_message_num_per_candidate: old.max_upward_message_num_per_candidate,
+		hrmp_max_message_num_per_candidate: old.hrmp_max_message_num_per_candidate,
+		validation_upgrade_frequency: old.validation_upgrade_frequency,
+		validation_upgrade_delay: old.validation_upgrade_delay,
+		max_pov_size: old.max_pov_size,
+		max_downward_message_size: old.max_downward_message_size,
+		ump_service_total_weight: old.ump_service_total_weight,
+		hrmp_max_parachain_outbound_channels: old.hrmp_max_parachain_outbound_channels,
+		hrmp_max_parathread_outbound_channels: old.hrmp_max_parathread_outbound_channels,
+		hrmp_open_request_ttl: old.hrmp_open_request_ttl,
+		hrmp_sender_deposit: old.hrmp_sender_deposit,
+		hrmp_recipient_deposit: old.hrmp_recipient_deposit,
+		hrmp_channel_max_capacity: old.hrmp_channel_max_capacity,
+		hrmp_channel_max_total_size: old.hrmp_channel_max_total_size,
+		hrmp_max_parachain_inbound_channels: old.hrmp_max_parachain_inbound_channels,
+		hrmp_max_parathread_inbound_channels: old.hrmp_max_parathread_inbound_channels,
+		hrmp_channel_max_message_size: old.hrmp_channel_max_message_size,
+		code_retention_period: old.code_retention_period,
+		parathread_cores: old.parathread_cores,
+		parathread_retries: old.parathread_retries,
+		group_rotation_frequency: old.group_rotation_frequency,
+		chain_availability_period: old.chain_availability_period,
+		thread_availability_period: old.thread_availability_period,
+		scheduling_lookahead: old.scheduling_lookahead,
+		max_validators_per_core: old.max_validators_per_core,
+		max_validators: old.max_validators,
+		dispute_period: old.dispute_period,
+		dispute_post_conclusion_acceptance_period: old.dispute_post_conclusion_acceptance_period,
+		dispute_max_spam_slots: old.dispute_max_spam_slots,
+		dispute_conclusion_by_time_out_period: old.dispute_conclusion_by_time_out_period,
+		no_show_slots: old.no_show_slots,
+		n_delay_tranches: old.n_delay_tranches,
+		zeroth_delay_tranche_width: old.zeroth_delay_tranche_width,
+		needed_approvals: old.needed_approvals,
+		relay_vrf_modulo_samples: old.relay_vrf_modulo_samples,
+		pvf_checking_enabled: false,
+		pvf_voting_ttl: 2,
+		ump_max_individual_weight: 20 * WEIGHT_PER_MILLIS,
+	}
+}
+
+/// Migrate the active and all pending configurations to the layout which includes the PVF
+/// pre-checking parameters and the maximum weight of an individual upward message. PVF
+/// pre-checking stays disabled after the migration.
+///
+/// This must be run exactly once, on the runtime upgrade that introduces the new layout.
+pub fn migrate_to_pvf_checking<T: Config>() -> Weight {
+	let mut reads_writes = 1;
+
+	let translated = <Module<T> as Store>::ActiveConfig::translate(
+		|old: Option<OldHostConfiguration<T::BlockNumber>>| old.map(migrate_config)
+	);
+	if translated.is_err() {
+		log::error!(
+			target: "runtime::configuration",
+			"the active configuration could not be decoded with the old layout",
+		);
+	}
+
+	<Module<T> as Store>::PendingConfig::translate(
+		|_session: SessionIndex, old: OldHostConfiguration<T::BlockNumber>| {
+			reads_writes += 1;
+			Some(migrate_config(old))
+		}
+	);
+
+	T::DbWeight::get().reads_writes(reads_writes, reads_writes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::storage::{unhashed, StorageMap};
+
+	fn old_config() -> OldHostConfiguration<u32> {
+		OldHostConfiguration {
+			max_code_size: 1,
+			max_head_data_size: 2,
+			max_upward_queue_count: 3,
+			max_upward_queue_size: 4,
+			max_upward_message_size: 5,
+			max_upward_message_num_per_candidate: 6,
+			hrmp_max_message_num_per_candidate: 7,
+			validation_upgrade_frequency: 8,
+			validation_upgrade_delay: 9,
+			max_pov_size: 10,
+			max_downward_message_size: 11,
+			ump_service_total_weight: 12,
+			hrmp_max_parachain_outbound_channels: 13,
+			hrmp_max_parathread_outbound_channels: 14,
+			hrmp_open_request_ttl: 15,
+			hrmp_sender_deposit: 16,
+			hrmp_recipient_deposit: 17,
+			hrmp_channel_max_capacity: 18,
+			hrmp_channel_max_total_size: 19,
+			hrmp_max_parachain_inbound_channels: 20,
+			hrmp_max_parathread_inbound_channels: 21,
+			hrmp_channel_max_message_size: 22,
+			code_retention_period: 23,
+			parathread_cores: 24,
+			parathread_retries: 25,
+			group_rotation_frequency: 26,
+			chain_availability_period: 27,
+			thread_availability_period: 28,
+			scheduling_lookahead: 29,
+			max_validators_per_core: Some(30),
+			max_validators: Some(31),
+			dispute_period: 32,
+			dispute_post_conclusion_acceptance_period: 33,
+			dispute_max_spam_slots: 34,
+			dispute_conclusion_by_time_out_period: 35,
+			no_show_slots: 36,
+			n_delay_tranches: 37,
+			zeroth_delay_tranche_width: 38,
+			needed_approvals: 39,
+			relay_vrf_modulo_samples: 40,
+		}
+	}
+
+	#[test]
+	fn migrate_to_pvf_checking_works() {
+		new_test_ext(Default::default()).execute_with(|| {
+			let old = old_config();
+			let active_key = <Module<Test> as Store>::ActiveConfig::hashed_key();
+			let pending_key = <Module<Test> as Store>::PendingConfig::hashed_key_for(5);
+			unhashed::put_raw(&active_key, &old.encode());
+			unhashed::put_raw(&pending_key, &old.encode());
+
+			migrate_to_pvf_checking::<Test>();
+
+			let expected = migrate_config(old_config());
+			assert!(!expected.pvf_checking_enabled);
+			assert_eq!(expected.pvf_voting_ttl, 2);
+			assert_eq!(expected.ump_max_individual_weight, 20 * WEIGHT_PER_MILLIS);
+			assert_eq!(expected.dispute_period, old.dispute_period);
+			assert_eq!(<Module<Test> as Store>::ActiveConfig::get(), expected);
+			assert_eq!(<Module<Test> as Store>::PendingConfig::get(5), Some(expected));
+		});
+	}
+}
@@ -0,0 +1,1129 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime component for handling disputes of parachain candidates.
+//!
+//! See https://w3f.github.io/parachain-implementers-guide/runtime/disputes.html.
+
+use sp_std::{collections::btree_set::BTreeSet, prelude::*};
+use primitives::v1::{
+	BlockNumber, CandidateHash, ConsensusLog, DisputeState, DisputeStatementSet,
+	MultiDisputeStatementSet, SessionIndex, ValidatorIndex,
+};
+use sp_runtime::{
+	DispatchError, RuntimeDebug,
+	traits::{One, Zero, UniqueSaturatedInto},
+};
+use frame_support::{
+	decl_storage, decl_module, decl_error, decl_event, ensure, dispatch::DispatchResult,
+	traits::Get, weights::Weight, IterableStorageDoubleMap,
+};
+use frame_system::ensure_root;
+use parity_scale_codec::{Encode, Decode};
+use bitvec::order::Lsb0 as BitOrderLsb0;
+use crate::{
+	configuration::{self, HostConfiguration},
+	initializer::SessionChangeNotification,
+	session_info,
+};
+
+const LOG_TARGET: &str = "runtime::disputes";
+
+/// Whether the dispute is local or remote.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DisputeLocation {
+	/// The candidate is included in this fork of the relay chain.
+	Local,
+	/// The candidate is not known to be included in this fork of the relay chain.
+	Remote,
+}
+
+/// The result of a dispute, whether the candidate is deemed valid (for) or invalid (against).
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum DisputeResult {
+	/// The dispute resolved in favor of the candidate.
+	Valid,
+	/// The dispute resolved against the candidate.
+	Invalid,
+}
+
+/// Reward hooks for disputes.
+pub trait RewardValidators {
+	// Give each validator a reward, likely small, for participating in the dispute.
+	fn reward_dispute_statement(session: SessionIndex, validators: impl IntoIterator<Item=ValidatorIndex>);
+}
+
+impl RewardValidators for () {
+	fn reward_dispute_statement(_: SessionIndex, _: impl IntoIterator<Item=ValidatorIndex>) { }
+}
+
+/// Punishment hooks for disputes.
+pub trait PunishValidators {
+	/// Punish a series of validators who were for an invalid parablock. This is expected to be a major
+	/// punishment.
+	fn punish_for_invalid(session: SessionIndex, validators: impl IntoIterator<Item=ValidatorIndex>);
+
+	/// Punish a series of validators who were against a valid parablock. This is expected to be a minor
+	/// punishment.
+	fn punish_against_valid(session: SessionIndex, validators: impl IntoIterator<Item=ValidatorIndex>);
+
+	/// Punish a series of validators who were part of a dispute which never concluded. This is expected
+	/// to be a minor punishment.
+	fn punish_inconclusive(session: SessionIndex, validators: impl IntoIterator<Item=ValidatorIndex>);
+}
+
+impl PunishValidators for () {
+	fn punish_for_invalid(_: SessionIndex, _: impl IntoIterator<Item=ValidatorIndex>) { }
+
+	fn punish_against_valid(_: SessionIndex, _: impl IntoIterator<Item=ValidatorIndex>) { }
+
+	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item=ValidatorIndex>) { }
+}
+
+/// Hook into disputes handling.
+///
+/// Allows decoupling parachains handling from disputes so that it can
+/// potentially be disabled when instantiating a specific runtime.
+pub trait DisputesHandler<BlockNumber> {
+	/// Handle sets of dispute statements corresponding to 0 or more candidates.
+	/// Returns a vector of freshly created disputes.
+	///
+	/// Invalid sets are ignored.
+	fn provide_multi_dispute_data(
+		statement_sets: MultiDisputeStatementSet,
+	) -> Vec<(SessionIndex, CandidateHash)>;
+
+	/// Whether the given candidate could be invalid, i.e. there is an ongoing
+	/// or concluded dispute with supermajority-against.
+	fn could_be_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool;
+
+	/// Whether the given candidate concluded invalid in a dispute with supermajority.
+	fn concluded_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool;
+
+	/// Note that the given candidate has been included.
+	fn note_included(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		included_in: BlockNumber,
+	);
+
+	/// Whether the chain is frozen, if the chain is frozen it will not accept
+	/// any new parachain blocks for backing or inclusion.
+	fn is_frozen() -> bool;
+
+	/// Called by the initializer to initialize the disputes module.
+	fn initializer_initialize(now: BlockNumber) -> Weight;
+
+	/// Called by the initializer to finalize the disputes module.
+	fn initializer_finalize();
+
+	/// Called by the initializer to note that a new session has started.
+	fn initializer_on_new_session(notification: &SessionChangeNotification<BlockNumber>);
+}
+
+impl<BlockNumber> DisputesHandler<BlockNumber> for () {
+	fn provide_multi_dispute_data(
+		_statement_sets: MultiDisputeStatementSet,
+	) -> Vec<(SessionIndex, CandidateHash)> {
+		Vec::new()
+	}
+
+	fn could_be_invalid(_session: SessionIndex, _candidate_hash: CandidateHash) -> bool {
+		false
+	}
+
+	fn concluded_invalid(_session: SessionIndex, _candidate_hash: CandidateHash) -> bool {
+		false
+	}
+
+	fn note_included(
+		_session: SessionIndex,
+		_candidate_hash: CandidateHash,
+		_included_in: BlockNumber,
+	) { }
+
+	fn is_frozen() -> bool {
+		false
+	}
+
+	fn initializer_initialize(_now: BlockNumber) -> Weight {
+		0
+	}
+
+	fn initializer_finalize() { }
+
+	fn initializer_on_new_session(_notification: &SessionChangeNotification<BlockNumber>) { }
+}
+
+impl<T: Config> DisputesHandler<T::BlockNumber> for Module<T> {
+	fn provide_multi_dispute_data(
+		statement_sets: MultiDisputeStatementSet,
+	) -> Vec<(SessionIndex, CandidateHash)> {
+		Module::<T>::provide_multi_dispute_data(statement_sets)
+	}
+
+	fn could_be_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool {
+		Module::<T>::could_be_invalid(session, candidate_hash)
+	}
+
+	fn concluded_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool {
+		Module::<T>::concluded_invalid(session, candidate_hash)
+	}
+
+	fn note_included(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		included_in: T::BlockNumber,
+	) {
+		Module::<T>::note_included(session, candidate_hash, included_in)
+	}
+
+	fn is_frozen() -> bool {
+		Module::<T>::is_frozen()
+	}
+
+	fn initializer_initialize(now: T::BlockNumber) -> Weight {
+		Module::<T>::initializer_initialize(now)
+	}
+
+	fn initializer_finalize() {
+		Module::<T>::initializer_finalize()
+	}
+
+	fn initializer_on_new_session(notification: &SessionChangeNotification<T::BlockNumber>) {
+		Module::<T>::initializer_on_new_session(notification)
+	}
+}
+
+pub trait Config:
+	frame_system::Config
+	+ configuration::Config
+	+ session_info::Config
+{
+	/// The outer event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// A hook for rewarding validators participating in disputes.
+	type RewardValidators: RewardValidators;
+	/// A hook for punishing validators on the losing side of disputes.
+	type PunishValidators: PunishValidators;
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as ParasDisputes {
+		/// The last pruned session, if any. All data stored by this module
+		/// references sessions.
+		LastPrunedSession: Option<SessionIndex>;
+		/// All ongoing or concluded disputes for the last several sessions.
+		Disputes: double_map
+			hasher(twox_64_concat) SessionIndex,
+			hasher(blake2_128_concat) CandidateHash
+			=> Option<DisputeState<T::BlockNumber>>;
+		/// All included blocks on the chain, as well as the block number in this chain that
+		/// should be reverted back to if the candidate is disputed and determined to be invalid.
+		Included: double_map
+			hasher(twox_64_concat) SessionIndex,
+			hasher(blake2_128_concat) CandidateHash
+			=> Option<T::BlockNumber>;
+		/// Maps session indices to a vector indicating the number of potentially-spam disputes
+		/// each validator is participating in. Potentially-spam disputes are remote disputes which have
+		/// fewer than `byzantine_threshold + 1` validators.
+		///
+		/// The i'th entry of the vector corresponds to the i'th validator in the session.
+		SpamSlots: map hasher(twox_64_concat) SessionIndex => Option<Vec<u32>>;
+		/// Whether the chain is frozen or not. Starts as `false`. When this is `true`,
+		/// the chain will not accept any new parachain blocks for backing or inclusion.
+		/// It can only be set back to `false` by governance intervention.
+		Frozen get(fn is_frozen): bool;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where <T as frame_system::Config>::BlockNumber {
+		/// A dispute has been initiated. \[candidate hash, dispute location\]
+		DisputeInitiated(CandidateHash, DisputeLocation),
+		/// A dispute has concluded for or against a candidate.
+		/// \[candidate hash, dispute result\]
+		DisputeConcluded(CandidateHash, DisputeResult),
+		/// A dispute has timed out due to insufficient participation.
+		/// \[candidate hash\]
+		DisputeTimedOut(CandidateHash),
+		/// A dispute has concluded with supermajority against a candidate.
+		/// Block authors should no longer build on top of this head and should
+		/// instead revert to the block at the given height which is the last
+		/// known valid block in this chain.
+		Revert(BlockNumber),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Ancient dispute statement provided.
+		AncientDisputeStatement,
+		/// Validator index on statement is out of bounds for session.
+		ValidatorIndexOutOfBounds,
+		/// Invalid signature on statement.
+		InvalidSignature,
+		/// Validator vote submitted more than once to dispute.
+		DuplicateStatement,
+		/// Too many spam slots used by some specific validator.
+		PotentialSpam,
+		/// A dispute statement set without any statements was provided.
+		EmptyDisputeStatementSet,
+		/// A dispute can only be initiated with statements on both sides.
+		SingleSidedDispute,
+	}
+}
+
+decl_module! {
+	/// The disputes module.
+	pub struct Module<T: Config> for enum Call where origin: <T as frame_system::Config>::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Unfreeze the chain after it was frozen due to a dispute concluding against a candidate
+		/// included in it.
+		///
+		/// Origin must be Root.
+		#[weight = 0]
+		pub fn force_unfreeze(origin) -> DispatchResult {
+			ensure_root(origin)?;
+			Frozen::set(false);
+			Ok(())
+		}
+	}
+}
+
+/// The maximum number of validators `f` which may safely be faulty.
+///
+/// The total number of validators is `n = 3f + e` where `e in { 1, 2, 3 }`.
+fn byzantine_threshold(n: usize) -> usize {
+	n.saturating_sub(1) / 3
+}
+
+/// The supermajority threshold of validators which is required to
+/// conclude a dispute.
+pub(crate) fn supermajority_threshold(n: usize) -> usize {
+	n - byzantine_threshold(n)
+}
+
+/// Indices of all validators participating in the dispute, on either side.
+fn participants<N>(dispute: &DisputeState<N>) -> Vec<ValidatorIndex> {
+	dispute.validators_for.iter()
+		.zip(dispute.validators_against.iter())
+		.enumerate()
+		.filter(|(_, (for_bit, against_bit))| **for_bit || **against_bit)
+		.map(|(i, _)| ValidatorIndex(i as _))
+		.collect()
+}
+
+/// Indices of all validators with a bit set in the given bitfield.
+fn set_bits(bits: &bitvec::slice::BitSlice<BitOrderLsb0, u8>) -> Vec<ValidatorIndex> {
+	bits.iter()
+		.enumerate()
+		.filter(|(_, bit)| **bit)
+		.map(|(i, _)| ValidatorIndex(i as _))
+		.collect()
+}
+
+/// Whether the dispute has concluded with a supermajority against the candidate.
+fn has_supermajority_against<N>(dispute: &DisputeState<N>) -> bool {
+	let n_validators = dispute.validators_against.len();
+	dispute.validators_against.count_ones() >= supermajority_threshold(n_validators)
+}
+
+impl<T: Config> Module<T> {
+	/// Called by the initializer to initialize the disputes module.
+	///
+	/// Concludes all disputes which have been running for longer than
+	/// `config.dispute_conclusion_by_time_out_period` without reaching a supermajority.
+	pub(crate) fn initializer_initialize(now: T::BlockNumber) -> Weight {
+		let config = <configuration::Module<T>>::config();
+
+		let mut weight = 0;
+		for (session_index, candidate_hash, mut dispute) in <Disputes<T>>::iter() {
+			weight += T::DbWeight::get().reads_writes(1, 0);
+
+			if dispute.concluded_at.is_none()
+				&& dispute.start + config.dispute_conclusion_by_time_out_period < now
+			{
+				dispute.concluded_at = Some(now);
+				<Disputes<T>>::insert(session_index, candidate_hash, &dispute);
+
+				let participating = participants(&dispute);
+				let n_validators = dispute.validators_for.len();
+
+				// A remote dispute which never got past the spam threshold no longer occupies
+				// the spam slots of its participants.
+				if !<Included<T>>::contains_key(&session_index, &candidate_hash)
+					&& participating.len() <= byzantine_threshold(n_validators)
+				{
+					Self::release_spam_slots(session_index, &participating);
+					weight += T::DbWeight::get().reads_writes(1, 1);
+				}
+
+				// Mildly punish all validators involved. They've failed to make
+				// data available to others.
+				T::PunishValidators::punish_inconclusive(session_index, participating);
+
+				Self::deposit_event(Event::<T>::DisputeTimedOut(candidate_hash));
+
+				weight += T::DbWeight::get().reads_writes(1, 1);
+			}
+		}
+
+		weight
+	}
+
+	/// Called by the initializer to finalize the disputes module.
+	pub(crate) fn initializer_finalize() {}
+
+	/// Called by the initializer to note a new session in the disputes module.
+	///
+	/// Prunes everything which is older than `config.dispute_period` full sessions.
+	pub(crate) fn initializer_on_new_session(notification: &SessionChangeNotification<T::BlockNumber>) {
+		let config = <configuration::Module<T>>::config();
+
+		if notification.session_index <= config.dispute_period + 1 {
+			return
+		}
+
+		let pruning_target = notification.session_index - config.dispute_period - 1;
+
+		LastPrunedSession::mutate(|last_pruned| {
+			if let Some(last_pruned) = last_pruned {
+				for to_prune in *last_pruned + 1 ..= pruning_target {
+					<Disputes<T>>::drain_prefix(to_prune).for_each(drop);
+					<Included<T>>::drain_prefix(to_prune).for_each(drop);
+					SpamSlots::remove(to_prune);
+				}
+			}
+
+			*last_pruned = Some(pruning_target);
+		});
+	}
+
+	/// Handle sets of dispute statements corresponding to 0 or more candidates.
+	/// Returns a vector of freshly created disputes.
+	///
+	/// Sets which duplicate an earlier set, refer to a session which is not known anymore or
+	/// contain invalid statements are ignored, so that they can't prevent the remaining sets from
+	/// being imported.
+	pub(crate) fn provide_multi_dispute_data(
+		statement_sets: MultiDisputeStatementSet,
+	) -> Vec<(SessionIndex, CandidateHash)> {
+		let config = <configuration::Module<T>>::config();
+
+		let mut targets = BTreeSet::new();
+		let mut fresh = Vec::with_capacity(statement_sets.len());
+		for statement_set in statement_sets {
+			let dispute_target = (statement_set.session, statement_set.candidate_hash);
+			if !targets.insert((statement_set.session, statement_set.candidate_hash.0)) {
+				log::debug!(
+					target: LOG_TARGET,
+					"Ignoring duplicate dispute statement set for {:?}",
+					dispute_target,
+				);
+				continue
+			}
+
+			match Self::provide_dispute_data(&config, statement_set) {
+				Ok(true) => fresh.push(dispute_target),
+				Ok(false) => {},
+				Err(err) => log::debug!(
+					target: LOG_TARGET,
+					"Ignoring invalid dispute statement set for {:?}: {:?}",
+					dispute_target,
+					err,
+				),
+			}
+		}
+
+		fresh
+	}
+
+	/// Provide data to an ongoing dispute or initiate a dispute.
+	///
+	/// Returns `true` if the dispute was just initiated and `false` otherwise. All checks are
+	/// performed before any state is modified, so an error leaves the state untouched.
+	fn provide_dispute_data(
+		config: &HostConfiguration<T::BlockNumber>,
+		set: DisputeStatementSet,
+	) -> Result<bool, DispatchError> {
+		let now = <frame_system::Pallet<T>>::block_number();
+		ensure!(!set.statements.is_empty(), Error::<T>::EmptyDisputeStatementSet);
+
+		let session_info = <session_info::Module<T>>::session_info(set.session)
+			.ok_or(Error::<T>::AncientDisputeStatement)?;
+		let n_validators = session_info.validators.len();
+
+		// Check all signatures before touching any state.
+		for (statement, validator_index, signature) in &set.statements {
+			let validator_public = session_info.validators.get(validator_index.0 as usize)
+				.ok_or(Error::<T>::ValidatorIndexOutOfBounds)?;
+
+			statement.check_signature(validator_public, set.candidate_hash, set.session, signature)
+				.map_err(|()| Error::<T>::InvalidSignature)?;
+		}
+
+		let maybe_dispute = <Disputes<T>>::get(&set.session, &set.candidate_hash);
+		let fresh = maybe_dispute.is_none();
+
+		// Without a vote on either side there is nothing to dispute, and a new dispute would
+		// occupy the candidate and block it from being backed.
+		if fresh {
+			let has_valid = set.statements.iter().any(|(statement, _, _)| statement.indicates_validity());
+			let has_invalid = set.statements.iter().any(|(statement, _, _)| !statement.indicates_validity());
+			ensure!(has_valid && has_invalid, Error::<T>::SingleSidedDispute);
+		}
+		let mut dispute_state = maybe_dispute.unwrap_or_else(|| DisputeState {
+			validators_for: bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators],
+			validators_against: bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators],
+			start: now,
+			concluded_at: None,
+		});
+
+		// Statements about disputes which concluded long ago are no longer accepted.
+		if let Some(concluded_at) = dispute_state.concluded_at {
+			ensure!(
+				concluded_at + config.dispute_post_conclusion_acceptance_period >= now,
+				Error::<T>::AncientDisputeStatement,
+			);
+		}
+
+		let previous_participants = participants(&dispute_state);
+
+		// Import all statements into the dispute.
+		let mut new_for = Vec::new();
+		let mut new_against = Vec::new();
+		let mut new_participants = Vec::new();
+		for (statement, validator_index, _) in &set.statements {
+			let i = validator_index.0 as usize;
+			let participated = dispute_state.validators_for[i] || dispute_state.validators_against[i];
+
+			if statement.indicates_validity() {
+				ensure!(!dispute_state.validators_for[i], Error::<T>::DuplicateStatement);
+				dispute_state.validators_for.set(i, true);
+				new_for.push(*validator_index);
+			} else {
+				ensure!(!dispute_state.validators_against[i], Error::<T>::DuplicateStatement);
+				dispute_state.validators_against.set(i, true);
+				new_against.push(*validator_index);
+			}
+
+			if !participated {
+				new_participants.push(*validator_index);
+			}
+		}
+
+		// Update the spam slots of remote disputes.
+		let included_in = <Included<T>>::get(&set.session, &set.candidate_hash);
+		if included_in.is_none() {
+			let byzantine_threshold = byzantine_threshold(n_validators);
+			let participants_after = previous_participants.len() + new_participants.len();
+
+			if participants_after <= byzantine_threshold {
+				// The dispute might still be spam, so the new participants occupy a slot.
+				let mut spam_slots = SpamSlots::get(&set.session)
+					.unwrap_or_else(|| vec![0; n_validators]);
+
+				for validator_index in &new_participants {
+					let spam_slot = &mut spam_slots[validator_index.0 as usize];
+					*spam_slot += 1;
+
+					ensure!(*spam_slot <= config.dispute_max_spam_slots, Error::<T>::PotentialSpam);
+				}
+
+				SpamSlots::insert(&set.session, spam_slots);
+			} else if previous_participants.len() <= byzantine_threshold {
+				// The dispute has just crossed the threshold, meaning that at least one honest
+				// validator participates: those who were participating before get their slots back.
+				Self::release_spam_slots(set.session, &previous_participants);
+			}
+		}
+
+		T::RewardValidators::reward_dispute_statement(
+			set.session,
+			new_for.iter().chain(new_against.iter()).cloned(),
+		);
+
+		if fresh {
+			let location = if included_in.is_some() {
+				DisputeLocation::Local
+			} else {
+				DisputeLocation::Remote
+			};
+
+			Self::deposit_event(Event::<T>::DisputeInitiated(set.candidate_hash, location));
+		}
+
+		// Check whether either side has reached a supermajority and slash the other side. Once
+		// concluded, only the statements arriving late on the losing side are punished.
+		let supermajority = supermajority_threshold(n_validators);
+		let already_concluded = dispute_state.concluded_at.is_some();
+		let concluded_valid = dispute_state.validators_for.count_ones() >= supermajority;
+		let concluded_invalid = dispute_state.validators_against.count_ones() >= supermajority;
+
+		if concluded_valid {
+			let losers = if already_concluded {
+				new_against
+			} else {
+				set_bits(&dispute_state.validators_against)
+			};
+
+			T::PunishValidators::punish_against_valid(set.session, losers);
+		}
+
+		if concluded_invalid {
+			let losers = if already_concluded {
+				new_for
+			} else {
+				set_bits(&dispute_state.validators_for)
+			};
+
+			T::PunishValidators::punish_for_invalid(set.session, losers);
+		}
+
+		if !already_concluded && (concluded_valid || concluded_invalid) {
+			dispute_state.concluded_at = Some(now);
+
+			if concluded_valid {
+				Self::deposit_event(Event::<T>::DisputeConcluded(set.candidate_hash, DisputeResult::Valid));
+			}
+
+			if concluded_invalid {
+				Self::deposit_event(Event::<T>::DisputeConcluded(set.candidate_hash, DisputeResult::Invalid));
+
+				if let Some(revert_to) = included_in {
+					Self::revert_and_freeze(revert_to);
+				}
+			}
+		}
+
+		<Disputes<T>>::insert(&set.session, &set.candidate_hash, &dispute_state);
+
+		Ok(fresh)
+	}
+
+	/// Get a list of all disputes and info about dispute state.
+	pub fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<T::BlockNumber>)> {
+		<Disputes<T>>::iter().collect()
+	}
+
+	/// Note that the given candidate has been included in the block `included_in`.
+	pub(crate) fn note_included(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		included_in: T::BlockNumber,
+	) {
+		if included_in.is_zero() { return }
+
+		let revert_to = included_in - One::one();
+
+		<Included<T>>::insert(&session, &candidate_hash, revert_to);
+
+		if let Some(dispute) = <Disputes<T>>::get(&session, &candidate_hash) {
+			// The dispute is now local, so it cannot be considered spam any more.
+			let participating = participants(&dispute);
+			if participating.len() <= byzantine_threshold(dispute.validators_for.len()) {
+				Self::release_spam_slots(session, &participating);
+			}
+
+			if dispute.concluded_at.is_some() && has_supermajority_against(&dispute) {
+				Self::revert_and_freeze(revert_to);
+			}
+		}
+	}
+
+	/// Whether the given candidate has a live dispute ongoing or a dispute which
+	/// has already concluded in the negative.
+	pub(crate) fn could_be_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool {
+		<Disputes<T>>::get(&session, &candidate_hash).map_or(false, |dispute| {
+			dispute.concluded_at.is_none() || has_supermajority_against(&dispute)
+		})
+	}
+
+	/// Whether the given candidate has a dispute which concluded in the negative.
+	pub(crate) fn concluded_invalid(session: SessionIndex, candidate_hash: CandidateHash) -> bool {
+		<Disputes<T>>::get(&session, &candidate_hash).map_or(false, |dispute| {
+			dispute.concluded_at.is_some() && has_supermajority_against(&dispute)
+		})
+	}
+
+	/// Issue a digest signalling that the chain is to be abandoned back to `revert_to` and
+	/// freeze the parachains.
+	///
+	/// This is a no-op if the chain is already frozen.
+	pub(crate) fn revert_and_freeze(revert_to: T::BlockNumber) {
+		if Self::is_frozen() { return }
+
+		let revert_to_number: BlockNumber = revert_to.unique_saturated_into();
+		<frame_system::Pallet<T>>::deposit_log(ConsensusLog::Revert(revert_to_number).into());
+
+		Frozen::set(true);
+
+		Self::deposit_event(Event::<T>::Revert(revert_to));
+	}
+
+	/// Free one spam slot for each of the given validators in the given session.
+	fn release_spam_slots(session: SessionIndex, validators: &[ValidatorIndex]) {
+		SpamSlots::mutate(&session, |spam_slots| {
+			if let Some(spam_slots) = spam_slots {
+				for validator_index in validators {
+					if let Some(spam_slot) = spam_slots.get_mut(validator_index.0 as usize) {
+						*spam_slot = spam_slot.saturating_sub(1);
+					}
+				}
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		new_test_ext, ParasDisputes, SessionInfo, System, MockGenesisConfig,
+		Origin, Test, Event as MockEvent, REWARD_VALIDATORS, PUNISH_VALIDATORS_FOR,
+		PUNISH_VALIDATORS_AGAINST, PUNISH_VALIDATORS_INCONCLUSIVE,
+	};
+	use crate::configuration::HostConfiguration;
+	use frame_support::{assert_noop, assert_ok};
+	use primitives::v1::{
+		BlockNumber, DisputeStatement, ExplicitDisputeStatement, InvalidDisputeStatementKind,
+		ValidDisputeStatementKind, ValidatorId, ValidatorSignature,
+	};
+	use sp_core::H256;
+	use keyring::Sr25519Keyring;
+
+	const VALIDATORS: [Sr25519Keyring; 7] = [
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Eve,
+		Sr25519Keyring::Ferdie,
+		Sr25519Keyring::One,
+	];
+
+	fn default_config() -> HostConfiguration<BlockNumber> {
+		HostConfiguration {
+			dispute_period: 2,
+			dispute_post_conclusion_acceptance_period: 5,
+			dispute_max_spam_slots: 1,
+			dispute_conclusion_by_time_out_period: 10,
+			..Default::default()
+		}
+	}
+
+	fn genesis_config() -> MockGenesisConfig {
+		MockGenesisConfig {
+			configuration: configuration::GenesisConfig {
+				config: default_config(),
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	fn start_session(session_index: SessionIndex) {
+		let notification = SessionChangeNotification {
+			validators: VALIDATORS.iter().map(|k| ValidatorId::from(k.public())).collect(),
+			session_index,
+			..Default::default()
+		};
+
+		SessionInfo::initializer_on_new_session(&notification);
+		ParasDisputes::initializer_on_new_session(&notification);
+	}
+
+	fn run_to_block(to: BlockNumber) {
+		while System::block_number() < to {
+			let b = System::block_number() + 1;
+			System::set_block_number(b);
+			ParasDisputes::initializer_initialize(b);
+		}
+	}
+
+	fn statement(
+		validator: usize,
+		valid: bool,
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+	) -> (DisputeStatement, ValidatorIndex, ValidatorSignature) {
+		let payload = ExplicitDisputeStatement { valid, candidate_hash, session }.signing_payload();
+		let statement = if valid {
+			DisputeStatement::Valid(ValidDisputeStatementKind::Explicit)
+		} else {
+			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit)
+		};
+
+		(statement, ValidatorIndex(validator as _), VALIDATORS[validator].sign(&payload).into())
+	}
+
+	fn statement_set(
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+		for_validators: &[usize],
+		against_validators: &[usize],
+	) -> DisputeStatementSet {
+		DisputeStatementSet {
+			candidate_hash,
+			session,
+			statements: for_validators.iter()
+				.map(|v| statement(*v, true, candidate_hash, session))
+				.chain(against_validators.iter().map(|v| statement(*v, false, candidate_hash, session)))
+				.collect(),
+		}
+	}
+
+	fn has_event(event: Event<Test>) -> bool {
+		System::events().iter().any(|record| record.event == MockEvent::ParasDisputes(event.clone()))
+	}
+
+	#[test]
+	fn thresholds() {
+		assert_eq!(byzantine_threshold(1), 0);
+		assert_eq!(supermajority_threshold(1), 1);
+		assert_eq!(byzantine_threshold(4), 1);
+		assert_eq!(supermajority_threshold(4), 3);
+		assert_eq!(byzantine_threshold(7), 2);
+		assert_eq!(supermajority_threshold(7), 5);
+		assert_eq!(byzantine_threshold(9), 2);
+		assert_eq!(supermajority_threshold(9), 7);
+	}
+
+	#[test]
+	fn statement_sets_are_checked() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let config = <configuration::Module<Test>>::config();
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+
+			// Unknown session.
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_hash, 2, &[0], &[1])),
+				Error::<Test>::AncientDisputeStatement,
+			);
+
+			// Validator index out of bounds.
+			let mut set = statement_set(candidate_hash, 1, &[0], &[1]);
+			set.statements[0].1 = ValidatorIndex(VALIDATORS.len() as _);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, set),
+				Error::<Test>::ValidatorIndexOutOfBounds,
+			);
+
+			// Signature of a different validator.
+			let mut set = statement_set(candidate_hash, 1, &[0], &[1]);
+			set.statements[0].1 = ValidatorIndex(2);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, set),
+				Error::<Test>::InvalidSignature,
+			);
+
+			// Signature for a different statement.
+			let mut set = statement_set(candidate_hash, 1, &[0], &[1]);
+			set.statements[0].0 = DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, set),
+				Error::<Test>::InvalidSignature,
+			);
+
+			// A valid set initiates a remote dispute, while invalid and duplicate sets are ignored.
+			assert_eq!(
+				ParasDisputes::provide_multi_dispute_data(vec![
+					statement_set(candidate_hash, 2, &[0], &[1]),
+					statement_set(candidate_hash, 1, &[0], &[1]),
+					statement_set(candidate_hash, 1, &[2], &[]),
+				]),
+				vec![(1, candidate_hash)],
+			);
+			assert!(!<Disputes<Test>>::get(1, candidate_hash).unwrap().validators_for[2]);
+			assert!(has_event(Event::<Test>::DisputeInitiated(candidate_hash, DisputeLocation::Remote)));
+			assert!(ParasDisputes::could_be_invalid(1, candidate_hash));
+			assert!(!ParasDisputes::concluded_invalid(1, candidate_hash));
+
+			// The same statement can't be submitted twice.
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_hash, 1, &[0], &[])),
+				Error::<Test>::DuplicateStatement,
+			);
+
+			// But a validator may vote on both sides, which isn't a fresh dispute anymore.
+			assert_eq!(
+				ParasDisputes::provide_multi_dispute_data(vec![
+					statement_set(candidate_hash, 1, &[1], &[]),
+				]),
+				vec![],
+			);
+		});
+	}
+
+	#[test]
+	fn empty_and_single_sided_sets_dont_create_disputes() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let config = <configuration::Module<Test>>::config();
+			let candidate_a = CandidateHash(H256::repeat_byte(1));
+			let candidate_b = CandidateHash(H256::repeat_byte(2));
+			let candidate_c = CandidateHash(H256::repeat_byte(3));
+
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_a, 1, &[], &[])),
+				Error::<Test>::EmptyDisputeStatementSet,
+			);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_a, 1, &[0, 2], &[])),
+				Error::<Test>::SingleSidedDispute,
+			);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_a, 1, &[], &[1])),
+				Error::<Test>::SingleSidedDispute,
+			);
+
+			assert_eq!(
+				ParasDisputes::provide_multi_dispute_data(vec![
+					statement_set(candidate_a, 1, &[], &[]),
+					statement_set(candidate_b, 1, &[0], &[]),
+					statement_set(candidate_c, 1, &[], &[1]),
+				]),
+				vec![],
+			);
+			for candidate_hash in &[candidate_a, candidate_b, candidate_c] {
+				assert!(<Disputes<Test>>::get(1, candidate_hash).is_none());
+				assert!(!ParasDisputes::could_be_invalid(1, *candidate_hash));
+			}
+
+			// Once a dispute exists, statements on a single side are accepted.
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_a, 1, &[0], &[1]),
+			]);
+			assert_eq!(
+				ParasDisputes::provide_multi_dispute_data(vec![
+					statement_set(candidate_a, 1, &[2], &[]),
+					statement_set(candidate_a, 1, &[], &[]),
+				]),
+				vec![],
+			);
+			assert!(<Disputes<Test>>::get(1, candidate_a).unwrap().validators_for[2]);
+
+			// But empty sets are not.
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_a, 1, &[], &[])),
+				Error::<Test>::EmptyDisputeStatementSet,
+			);
+		});
+	}
+
+	#[test]
+	fn spam_slots_are_enforced_and_released() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let config = <configuration::Module<Test>>::config();
+			let candidate_a = CandidateHash(H256::repeat_byte(1));
+			let candidate_b = CandidateHash(H256::repeat_byte(2));
+
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_a, 1, &[0], &[1]),
+			]);
+			assert_eq!(SpamSlots::get(1), Some(vec![1, 1, 0, 0, 0, 0, 0]));
+
+			// Validator 0 only has a single spam slot.
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_b, 1, &[0], &[2])),
+				Error::<Test>::PotentialSpam,
+			);
+
+			// Crossing the byzantine threshold releases the spam slots.
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_a, 1, &[2], &[]),
+			]);
+			assert_eq!(SpamSlots::get(1), Some(vec![0, 0, 0, 0, 0, 0, 0]));
+
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_b, 1, &[0], &[2]),
+			]);
+			assert_eq!(SpamSlots::get(1), Some(vec![1, 0, 1, 0, 0, 0, 0]));
+
+			// Local disputes don't occupy spam slots.
+			ParasDisputes::note_included(1, candidate_b, 3);
+			assert_eq!(SpamSlots::get(1), Some(vec![0, 0, 0, 0, 0, 0, 0]));
+		});
+	}
+
+	#[test]
+	fn dispute_concluding_valid_punishes_against() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let config = <configuration::Module<Test>>::config();
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+			ParasDisputes::note_included(1, candidate_hash, 1);
+
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_hash, 1, &[0, 1, 2, 3, 4], &[5]),
+			]);
+
+			assert!(has_event(Event::<Test>::DisputeInitiated(candidate_hash, DisputeLocation::Local)));
+			assert!(has_event(Event::<Test>::DisputeConcluded(candidate_hash, DisputeResult::Valid)));
+			assert!(!ParasDisputes::could_be_invalid(1, candidate_hash));
+			assert!(!ParasDisputes::is_frozen());
+
+			REWARD_VALIDATORS.with(|r| assert_eq!(
+				*r.borrow(),
+				vec![(1, (0..6).map(ValidatorIndex).collect())],
+			));
+			PUNISH_VALIDATORS_AGAINST.with(|r| assert_eq!(
+				*r.borrow(),
+				vec![(1, vec![ValidatorIndex(5)])],
+			));
+
+			// Late statements against are still accepted and punished.
+			run_to_block(6);
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_hash, 1, &[], &[6]),
+			]);
+			PUNISH_VALIDATORS_AGAINST.with(|r| assert_eq!(
+				r.borrow().last(),
+				Some(&(1, vec![ValidatorIndex(6)])),
+			));
+
+			// Until the post conclusion acceptance period is over.
+			run_to_block(7);
+			assert_noop!(
+				ParasDisputes::provide_dispute_data(&config, statement_set(candidate_hash, 1, &[6], &[])),
+				Error::<Test>::AncientDisputeStatement,
+			);
+		});
+	}
+
+	#[test]
+	fn dispute_concluding_invalid_reverts_and_freezes() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+			run_to_block(4);
+
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+			ParasDisputes::note_included(1, candidate_hash, 3);
+
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_hash, 1, &[0], &[1, 2, 3, 4, 5]),
+			]);
+
+			assert!(has_event(Event::<Test>::DisputeConcluded(candidate_hash, DisputeResult::Invalid)));
+			assert!(has_event(Event::<Test>::Revert(2)));
+			assert!(System::digest().logs.contains(&ConsensusLog::Revert(2).into()));
+			assert!(ParasDisputes::is_frozen());
+			assert!(ParasDisputes::could_be_invalid(1, candidate_hash));
+			assert!(ParasDisputes::concluded_invalid(1, candidate_hash));
+
+			PUNISH_VALIDATORS_FOR.with(|r| assert_eq!(
+				*r.borrow(),
+				vec![(1, vec![ValidatorIndex(0)])],
+			));
+
+			assert_noop!(ParasDisputes::force_unfreeze(Origin::signed(1)), DispatchError::BadOrigin);
+			assert_ok!(ParasDisputes::force_unfreeze(Origin::root()));
+			assert!(!ParasDisputes::is_frozen());
+		});
+	}
+
+	#[test]
+	fn including_invalid_candidate_reverts_and_freezes() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_hash, 1, &[5], &[0, 1, 2, 3, 4]),
+			]);
+			assert!(!ParasDisputes::is_frozen());
+
+			run_to_block(3);
+			ParasDisputes::note_included(1, candidate_hash, 3);
+
+			assert!(System::digest().logs.contains(&ConsensusLog::Revert(2).into()));
+			assert!(ParasDisputes::is_frozen());
+		});
+	}
+
+	#[test]
+	fn disputes_time_out() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+			ParasDisputes::provide_multi_dispute_data(vec![
+				statement_set(candidate_hash, 1, &[0], &[1]),
+			]);
+
+			run_to_block(11);
+			assert!(!has_event(Event::<Test>::DisputeTimedOut(candidate_hash)));
+
+			run_to_block(12);
+			assert!(has_event(Event::<Test>::DisputeTimedOut(candidate_hash)));
+			assert_eq!(ParasDisputes::disputes()[0].2.concluded_at, Some(12));
+			assert!(!ParasDisputes::could_be_invalid(1, candidate_hash));
+			assert_eq!(SpamSlots::get(1), Some(vec![0, 0, 0, 0, 0, 0, 0]));
+
+			PUNISH_VALIDATORS_INCONCLUSIVE.with(|r| assert_eq!(
+				*r.borrow(),
+				vec![(1, vec![ValidatorIndex(0), ValidatorIndex(1)])],
+			));
+		});
+	}
+
+	#[test]
+	fn old_sessions_are_pruned() {
+		new_test_ext(genesis_config()).execute_with(|| {
+			System::set_block_number(1);
+			start_session(1);
+			start_session(2);
+			start_session(3);
+
+			let candidate_hash = CandidateHash(H256::repeat_byte(1));
+			for session in 1..=3 {
+				ParasDisputes::provide_multi_dispute_data(vec![
+					statement_set(candidate_hash, session, &[0], &[1]),
+				]);
+				ParasDisputes::note_included(session, candidate_hash, 1);
+			}
+
+			start_session(4);
+			assert_eq!(LastPrunedSession::get(), Some(1));
+			assert_eq!(ParasDisputes::disputes().len(), 3);
+
+			start_session(5);
+			assert_eq!(LastPrunedSession::get(), Some(2));
+			assert!(<Disputes<Test>>::get(1, candidate_hash).is_some());
+			assert!(<Disputes<Test>>::get(2, candidate_hash).is_none());
+			assert!(<Included<Test>>::get(2, candidate_hash).is_none());
+			assert!(SpamSlots::get(2).is_none());
+			assert!(<Disputes<Test>>::get(3, candidate_hash).is_some());
+		});
+	}
+}
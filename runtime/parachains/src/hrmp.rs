@@ -22,7 +22,8 @@ use crate::{
 use parity_scale_codec::{Decode, Encode};
 use frame_support::{
 	decl_storage, decl_module, decl_error, decl_event, ensure, traits::{Get, ReservableCurrency},
-	weights::Weight, StorageMap, StorageValue, dispatch::DispatchResult,
+	weights::{DispatchClass, Weight}, StorageMap, StorageValue, dispatch::DispatchResult,
+	transactional,
 };
 use frame_system::ensure_root;
 use primitives::v1::{
@@ -378,6 +379,12 @@ decl_error! {
 		CloseHrmpChannelDoesntExist,
 		/// The channel close request is already requested.
 		CloseHrmpChannelAlreadyUnderway,
+		/// Canceling is requested by neither the sender nor recipient of the open channel request.
+		CancelHrmpOpenChannelUnauthorized,
+		/// The open request doesn't exist.
+		OpenHrmpChannelDoesntExist,
+		/// Cannot cancel an HRMP open channel request because it is already confirmed.
+		OpenHrmpChannelAlreadyConfirmed,
 	 }
 }
 
@@ -390,6 +397,11 @@ decl_event! {
 		OpenChannelAccepted(ParaId, ParaId),
 		/// HRMP channel closed. \[by_parachain, channel_id\]
 		ChannelClosed(ParaId, HrmpChannelId),
+		/// An HRMP open channel request was canceled. \[by_parachain, channel_id\]
+		OpenChannelCanceled(ParaId, HrmpChannelId),
+		/// An HRMP channel was opened via Root origin.
+		/// \[sender, recipient, proposed_max_capacity, proposed_max_message_size\]
+		HrmpChannelForceOpened(ParaId, ParaId, u32, u32),
 	}
 }
 
@@ -410,7 +422,7 @@ decl_module! {
 		///
 		/// The channel can be opened only after the recipient confirms it and only on a session
 		/// change.
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(10, 6)]
 		pub fn hrmp_init_open_channel(
 			origin,
 			recipient: ParaId,
@@ -436,7 +448,7 @@ decl_module! {
 		/// Accept a pending open channel request from the given sender.
 		///
 		/// The channel will be opened only on the next session boundary.
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(8, 5)]
 		pub fn hrmp_accept_open_channel(origin, sender: ParaId) -> DispatchResult {
 			let origin = ensure_parachain(<T as Config>::Origin::from(origin))?;
 			Self::accept_open_channel(origin, sender)?;
@@ -448,7 +460,7 @@ decl_module! {
 		/// recipient in the channel being closed.
 		///
 		/// The closure can only happen on a session change.
-		#[weight = 0]
+		#[weight = T::DbWeight::get().reads_writes(5, 4)]
 		pub fn hrmp_close_channel(origin, channel_id: HrmpChannelId) -> DispatchResult {
 			let origin = ensure_parachain(<T as Config>::Origin::from(origin))?;
 			Self::close_channel(origin, channel_id.clone())?;
@@ -490,6 +502,63 @@ decl_module! {
 			Self::process_hrmp_close_channel_requests();
 			Ok(())
 		}
+
+		/// This cancels a pending open channel request. It can be canceled by either the sender
+		/// or the recipient for that request. The origin must be either of those.
+		///
+		/// The cancellation happens immediately. It is not possible to cancel the request if it is
+		/// already accepted. The sender's deposit is returned.
+		#[weight = T::DbWeight::get().reads_writes(4, 4)]
+		pub fn hrmp_cancel_open_request(origin, channel_id: HrmpChannelId) -> DispatchResult {
+			let origin = ensure_parachain(<T as Config>::Origin::from(origin))?;
+			ensure!(
+				origin == channel_id.sender || origin == channel_id.recipient,
+				Error::<T>::CancelHrmpOpenChannelUnauthorized,
+			);
+			Self::cancel_open_request(channel_id.clone())?;
+			Self::deposit_event(Event::OpenChannelCanceled(origin, channel_id));
+			Ok(())
+		}
+
+		/// Open a channel from a `sender` to a `recipient` `ParaId` using the Root origin. Although
+		/// opened by Root, the `max_capacity` and `max_message_size` are still subject to the Relay
+		/// Chain's configured limits.
+		///
+		/// The deposits are reserved from the sender and the recipient as if they had requested
+		/// and accepted the channel themselves. Any pending, unaccepted request for the same
+		/// channel is canceled first.
+		///
+		/// The channel will be opened on the next session boundary. If the channel can't be
+		/// requested or accepted, e.g. because one of the paras can't pay its deposit, nothing is
+		/// changed.
+		///
+		/// Origin must be Root.
+		// Covers canceling a pending request, then requesting and accepting the channel.
+		#[weight = (T::DbWeight::get().reads_writes(23, 15), DispatchClass::Operational)]
+		#[transactional]
+		pub fn force_open_hrmp_channel(
+			origin,
+			sender: ParaId,
+			recipient: ParaId,
+			max_capacity: u32,
+			max_message_size: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let channel_id = HrmpChannelId { sender, recipient };
+			if let Some(request) = <Self as Store>::HrmpOpenChannelRequests::get(&channel_id) {
+				ensure!(!request.confirmed, Error::<T>::OpenHrmpChannelAlreadyConfirmed);
+				Self::cancel_open_request(channel_id)?;
+			}
+			Self::init_open_channel(sender, recipient, max_capacity, max_message_size)?;
+			Self::accept_open_channel(recipient, sender)?;
+			Self::deposit_event(Event::HrmpChannelForceOpened(
+				sender,
+				recipient,
+				max_capacity,
+				max_message_size,
+			));
+			Ok(())
+		}
 	}
 }
 
@@ -1124,6 +1193,38 @@ impl<T: Config> Module<T> {
 		Ok(())
 	}
 
+	/// Cancel a pending, not yet accepted, open channel request and return the sender's deposit.
+	fn cancel_open_request(channel_id: HrmpChannelId) -> DispatchResult {
+		let request = <Self as Store>::HrmpOpenChannelRequests::get(&channel_id)
+			.ok_or(Error::<T>::OpenHrmpChannelDoesntExist)?;
+		ensure!(!request.confirmed, Error::<T>::OpenHrmpChannelAlreadyConfirmed);
+
+		<Self as Store>::HrmpOpenChannelRequests::remove(&channel_id);
+		<Self as Store>::HrmpOpenChannelRequestsList::mutate(|list| {
+			if let Some(idx) = list.iter().position(|id| id == &channel_id) {
+				let _ = list.swap_remove(idx);
+			}
+		});
+		let new_open_channel_req_cnt =
+			<Self as Store>::HrmpOpenChannelRequestCount::get(&channel_id.sender)
+				.saturating_sub(1);
+		if new_open_channel_req_cnt != 0 {
+			<Self as Store>::HrmpOpenChannelRequestCount::insert(
+				&channel_id.sender,
+				new_open_channel_req_cnt,
+			);
+		} else {
+			<Self as Store>::HrmpOpenChannelRequestCount::remove(&channel_id.sender);
+		}
+
+		T::Currency::unreserve(
+			&channel_id.sender.into_account(),
+			request.sender_deposit.unique_saturated_into(),
+		);
+
+		Ok(())
+	}
+
 	fn close_channel(origin: ParaId, channel_id: HrmpChannelId) -> Result<(), Error<T>> {
 		// check if the origin is allowed to close the channel.
 		ensure!(
@@ -1217,7 +1318,7 @@ impl<T: Config> Module<T> {
 mod tests {
 	use super::*;
 	use crate::mock::{
-		new_test_ext, Test, Configuration, Paras, Shared, Hrmp, System, MockGenesisConfig,
+		new_test_ext, Test, Configuration, Paras, Shared, Hrmp, System, MockGenesisConfig, Origin,
 		Event as MockEvent,
 	};
 	use frame_support::{assert_noop, assert_ok, traits::Currency as _};
@@ -1573,6 +1674,178 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn force_open_channel_works() {
+		let para_a = 1.into();
+		let para_b = 3.into();
+
+		new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+			// We need both A & B to be registered and alive parachains.
+			register_parachain(para_a);
+			register_parachain(para_b);
+
+			run_to_block(5, Some(vec![4, 5]));
+			Hrmp::force_open_hrmp_channel(Origin::root(), para_a, para_b, 2, 8).unwrap();
+			assert_storage_consistency_exhaustive();
+			assert!(System::events().iter().any(|record|
+				record.event == MockEvent::Hrmp(Event::HrmpChannelForceOpened(para_a, para_b, 2, 8))
+			));
+
+			// Advance to a block 6, but without session change. That means that the channel has
+			// not been created yet.
+			run_to_block(6, None);
+			assert!(!channel_exists(para_a, para_b));
+			assert_storage_consistency_exhaustive();
+
+			// Now let the session change happen and thus open the channel.
+			run_to_block(8, Some(vec![8]));
+			assert!(channel_exists(para_a, para_b));
+		});
+	}
+
+	#[test]
+	fn force_open_channel_replaces_pending_request() {
+		let para_a = 1.into();
+		let para_b = 3.into();
+
+		let mut genesis = GenesisConfigBuilder::default();
+		genesis.hrmp_sender_deposit = 20;
+		genesis.hrmp_recipient_deposit = 15;
+		new_test_ext(genesis.build()).execute_with(|| {
+			register_parachain_with_balance(para_a, 100);
+			register_parachain_with_balance(para_b, 110);
+
+			run_to_block(5, Some(vec![4, 5]));
+			Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+			Hrmp::force_open_hrmp_channel(Origin::root(), para_a, para_b, 4, 16).unwrap();
+			assert_storage_consistency_exhaustive();
+
+			// The deposit of the replaced request is returned, so only one is held.
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&para_a.into_account()),
+				80
+			);
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&para_b.into_account()),
+				95
+			);
+
+			run_to_block(8, Some(vec![8]));
+			let channel = <Hrmp as Store>::HrmpChannels::get(HrmpChannelId {
+				sender: para_a,
+				recipient: para_b,
+			}).unwrap();
+			assert_eq!(channel.max_capacity, 4);
+			assert_eq!(channel.max_message_size, 16);
+		});
+	}
+
+	#[test]
+	fn force_open_channel_changes_nothing_if_recipient_cannot_pay() {
+		let para_a = 1.into();
+		let para_b = 3.into();
+
+		let mut genesis = GenesisConfigBuilder::default();
+		genesis.hrmp_sender_deposit = 20;
+		genesis.hrmp_recipient_deposit = 15;
+		new_test_ext(genesis.build()).execute_with(|| {
+			register_parachain_with_balance(para_a, 100);
+			register_parachain_with_balance(para_b, 10);
+
+			run_to_block(5, Some(vec![4, 5]));
+			Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+
+			// The pending request, the sender's deposit and the notification of the recipient
+			// are all left as they were.
+			assert_noop!(
+				Hrmp::force_open_hrmp_channel(Origin::root(), para_a, para_b, 4, 16),
+				pallet_balances::Error::<Test, _>::InsufficientBalance,
+			);
+			assert_storage_consistency_exhaustive();
+
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&para_a.into_account()),
+				80
+			);
+			let request = <Hrmp as Store>::HrmpOpenChannelRequests::get(HrmpChannelId {
+				sender: para_a,
+				recipient: para_b,
+			}).unwrap();
+			assert!(!request.confirmed);
+			assert_eq!(request.max_capacity, 2);
+			assert_eq!(request.max_message_size, 8);
+		});
+	}
+
+	#[test]
+	fn cancel_open_request_works() {
+		let para_a = 1.into();
+		let para_b = 3.into();
+		let para_b_origin: crate::Origin = 3.into();
+		let para_c_origin: crate::Origin = 5.into();
+
+		let mut genesis = GenesisConfigBuilder::default();
+		genesis.hrmp_sender_deposit = 20;
+		genesis.hrmp_recipient_deposit = 15;
+		new_test_ext(genesis.build()).execute_with(|| {
+			register_parachain_with_balance(para_a, 100);
+			register_parachain_with_balance(para_b, 110);
+
+			run_to_block(5, Some(vec![4, 5]));
+			Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&para_a.into_account()),
+				80
+			);
+
+			let channel_id = HrmpChannelId { sender: para_a, recipient: para_b };
+
+			// Only the parties of the channel can cancel the request.
+			assert_noop!(
+				Hrmp::hrmp_cancel_open_request(para_c_origin.into(), channel_id.clone()),
+				Error::<Test>::CancelHrmpOpenChannelUnauthorized,
+			);
+
+			Hrmp::hrmp_cancel_open_request(para_b_origin.into(), channel_id.clone()).unwrap();
+			assert_storage_consistency_exhaustive();
+			assert!(System::events().iter().any(|record|
+				record.event == MockEvent::Hrmp(Event::OpenChannelCanceled(para_b, channel_id.clone()))
+			));
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&para_a.into_account()),
+				100
+			);
+
+			// The channel is never opened.
+			run_to_block(8, Some(vec![8]));
+			assert!(!channel_exists(para_a, para_b));
+		});
+	}
+
+	#[test]
+	fn cannot_cancel_accepted_open_request() {
+		let para_a = 1.into();
+		let para_a_origin: crate::Origin = 1.into();
+		let para_b = 3.into();
+
+		new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+			register_parachain(para_a);
+			register_parachain(para_b);
+
+			run_to_block(5, Some(vec![4, 5]));
+			Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+			Hrmp::accept_open_channel(para_b, para_a).unwrap();
+
+			assert_noop!(
+				Hrmp::hrmp_cancel_open_request(
+					para_a_origin.into(),
+					HrmpChannelId { sender: para_a, recipient: para_b },
+				),
+				Error::<Test>::OpenHrmpChannelAlreadyConfirmed,
+			);
+		});
+	}
+
 	#[test]
 	fn close_channel_works() {
 		let para_a = 5.into();
@@ -25,7 +25,7 @@ use primitives::v1::{
 	CandidateCommitments, CandidateDescriptor, ValidatorIndex, Id as ParaId,
 	AvailabilityBitfield as AvailabilityBitfield, UncheckedSignedAvailabilityBitfields, SigningContext,
 	BackedCandidate, CoreIndex, GroupIndex, CommittedCandidateReceipt,
-	CandidateReceipt, HeadData, CandidateHash,
+	CandidateReceipt, HeadData, CandidateHash, SessionIndex,
 };
 use frame_support::{
 	decl_storage, decl_module, decl_error, decl_event, ensure, dispatch::DispatchResult, IterableStorageMap,
@@ -35,7 +35,10 @@ use parity_scale_codec::{Encode, Decode};
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use sp_runtime::{DispatchError, traits::{One, Saturating}};
 
-use crate::{configuration, paras, dmp, ump, hrmp, shared, scheduler::CoreAssignment};
+use crate::{
+	configuration, disputes::DisputesHandler, paras, dmp, ump, hrmp, shared,
+	scheduler::CoreAssignment,
+};
 
 /// A bitfield signed by a validator indicating that it is keeping its piece of the erasure-coding
 /// for any backed candidates referred to by a `1` bit available.
@@ -118,6 +121,7 @@ pub trait Config:
 	+ configuration::Config
 {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	type DisputesHandler: DisputesHandler<Self::BlockNumber>;
 	type RewardValidators: RewardValidators;
 }
 
@@ -666,6 +670,12 @@ impl<T: Config> Module<T> {
 		let commitments = receipt.commitments;
 		let config = <configuration::Module<T>>::config();
 
+		T::DisputesHandler::note_included(
+			shared::Module::<T>::session_index(),
+			plain.hash(),
+			<frame_system::Pallet<T>>::block_number(),
+		);
+
 		T::RewardValidators::reward_backing(backers.iter().enumerate()
 			.filter(|(_, backed)| **backed)
 			.map(|(i, _)| ValidatorIndex(i as _))
@@ -754,6 +764,29 @@ impl<T: Config> Module<T> {
 		cleaned_up_cores
 	}
 
+	/// Cleans up all paras pending availability whose candidates have been concluded invalid
+	/// by a dispute.
+	///
+	/// Returns a vector of cleaned-up core IDs.
+	pub(crate) fn collect_disputed(session: SessionIndex) -> Vec<CoreIndex> {
+		let mut cleaned_up_ids = Vec::new();
+		let mut cleaned_up_cores = Vec::new();
+
+		for (para_id, pending_record) in <PendingAvailability<T>>::iter() {
+			if T::DisputesHandler::concluded_invalid(session, pending_record.hash) {
+				cleaned_up_ids.push(para_id);
+				cleaned_up_cores.push(pending_record.core);
+			}
+		}
+
+		for para_id in cleaned_up_ids {
+			let _ = <PendingAvailability<T>>::take(&para_id);
+			let _ = <PendingAvailabilityCommitments>::take(&para_id);
+		}
+
+		cleaned_up_cores
+	}
+
 	/// Forcibly enact the candidate with the given ID as though it had been deemed available
 	/// by bitfields.
 	///
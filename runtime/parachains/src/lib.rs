@@ -30,11 +30,13 @@ pub mod paras;
 pub mod paras_inherent;
 pub mod scheduler;
 pub mod session_info;
+pub mod disputes;
 pub mod origin;
 pub mod dmp;
 pub mod ump;
 pub mod hrmp;
 pub mod reward_points;
+pub mod slashing;
 
 pub mod runtime_api_impl;
 
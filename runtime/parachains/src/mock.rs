@@ -21,14 +21,16 @@ use sp_core::H256;
 use sp_runtime::traits::{
 	BlakeTwo256, IdentityLookup,
 };
-use primitives::v1::{AuthorityDiscoveryId, Balance, BlockNumber, Header, ValidatorIndex};
+use primitives::v1::{
+	AuthorityDiscoveryId, Balance, BlockNumber, Header, SessionIndex, ValidatorIndex,
+};
 use frame_support::parameter_types;
 use frame_support_test::TestRandomness;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use crate::{
 	inclusion, scheduler, dmp, ump, hrmp, session_info, paras, configuration,
-	initializer, shared,
+	initializer, shared, disputes,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -42,7 +44,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config<T>},
+		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config<T>, ValidateUnsigned},
 		Configuration: configuration::{Pallet, Call, Storage, Config<T>},
 		Shared: shared::{Pallet, Call, Storage},
 		Inclusion: inclusion::{Pallet, Call, Storage, Event<T>},
@@ -52,6 +54,7 @@ frame_support::construct_runtime!(
 		Ump: ump::{Pallet, Call, Storage, Event},
 		Hrmp: hrmp::{Pallet, Call, Storage, Event},
 		SessionInfo: session_info::{Pallet, Call, Storage},
+		ParasDisputes: disputes::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -112,6 +115,11 @@ impl crate::configuration::Config for Test { }
 
 impl crate::shared::Config for Test { }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
 impl crate::paras::Config for Test {
 	type Origin = Origin;
 	type Event = Event;
@@ -127,6 +135,7 @@ impl crate::ump::Config for Test {
 	type Event = Event;
 	type UmpSink = crate::ump::mock_sink::MockUmpSink;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
+	type ExecuteOverweightOrigin = frame_system::EnsureRoot<u64>;
 }
 
 impl crate::hrmp::Config for Test {
@@ -139,10 +148,68 @@ impl crate::scheduler::Config for Test { }
 
 impl crate::inclusion::Config for Test {
 	type Event = Event;
+	type DisputesHandler = ParasDisputes;
 	type RewardValidators = TestRewardValidators;
 }
 
-impl crate::paras_inherent::Config for Test { }
+impl crate::disputes::Config for Test {
+	type Event = Event;
+	type RewardValidators = Self;
+	type PunishValidators = Self;
+}
+
+thread_local! {
+	pub static REWARD_VALIDATORS: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
+	pub static PUNISH_VALIDATORS_FOR: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
+	pub static PUNISH_VALIDATORS_AGAINST: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
+	pub static PUNISH_VALIDATORS_INCONCLUSIVE: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
+	pub static PUNISH_BACKING_MISBEHAVIOR: RefCell<Vec<(SessionIndex, ValidatorIndex)>> = RefCell::new(Vec::new());
+}
+
+impl crate::disputes::RewardValidators for Test {
+	fn reward_dispute_statement(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item=ValidatorIndex>
+	) {
+		REWARD_VALIDATORS.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+	}
+}
+
+impl crate::disputes::PunishValidators for Test {
+	fn punish_for_invalid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item=ValidatorIndex>,
+	) {
+		PUNISH_VALIDATORS_FOR
+			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item=ValidatorIndex>,
+	) {
+		PUNISH_VALIDATORS_AGAINST
+			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+	}
+
+	fn punish_inconclusive(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item=ValidatorIndex>,
+	) {
+		PUNISH_VALIDATORS_INCONCLUSIVE
+			.with(|r| r.borrow_mut().push((session, validators.into_iter().collect())))
+	}
+}
+
+impl crate::paras_inherent::Config for Test {
+	type PunishBackingMisbehavior = Self;
+}
+
+impl crate::paras_inherent::PunishBackingMisbehavior for Test {
+	fn punish_backing_misbehavior(session: SessionIndex, validator: ValidatorIndex) {
+		PUNISH_BACKING_MISBEHAVIOR.with(|r| r.borrow_mut().push((session, validator)))
+	}
+}
 
 impl crate::session_info::Config for Test { }
 
@@ -205,6 +272,11 @@ impl inclusion::RewardValidators for TestRewardValidators {
 pub fn new_test_ext(state: MockGenesisConfig) -> TestExternalities {
 	BACKING_REWARDS.with(|r| r.borrow_mut().clear());
 	AVAILABILITY_REWARDS.with(|r| r.borrow_mut().clear());
+	REWARD_VALIDATORS.with(|r| r.borrow_mut().clear());
+	PUNISH_VALIDATORS_FOR.with(|r| r.borrow_mut().clear());
+	PUNISH_VALIDATORS_AGAINST.with(|r| r.borrow_mut().clear());
+	PUNISH_VALIDATORS_INCONCLUSIVE.with(|r| r.borrow_mut().clear());
+	PUNISH_BACKING_MISBEHAVIOR.with(|r| r.borrow_mut().clear());
 
 	let mut t = state.system.build_storage::<Test>().unwrap();
 	state.configuration.assimilate_storage(&mut t).unwrap();
@@ -28,17 +28,29 @@ use sp_std::result;
 #[cfg(feature = "std")]
 use sp_std::marker::PhantomData;
 use primitives::v1::{
-	Id as ParaId, ValidationCode, HeadData, SessionIndex, Hash, ConsensusLog,
+	Id as ParaId, ValidationCode, HeadData, SessionIndex, Hash, ConsensusLog, PvfCheckStatement,
+	ValidatorSignature,
 };
-use sp_runtime::{traits::One, DispatchResult, SaturatedConversion};
-use frame_system::ensure_root;
+use sp_runtime::{
+	traits::{AppVerify, One},
+	transaction_validity::{
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+		TransactionValidity, ValidTransaction,
+	},
+	DispatchResult, SaturatedConversion,
+};
+use frame_system::{ensure_none, ensure_root, offchain::SubmitTransaction};
 use frame_support::{
 	decl_storage, decl_module, decl_error, decl_event, ensure,
 	traits::Get,
-	weights::Weight,
+	weights::{DispatchClass, Weight},
 };
 use parity_scale_codec::{Encode, Decode};
-use crate::{configuration, shared, initializer::SessionChangeNotification};
+use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
+use crate::{
+	configuration, shared, initializer::SessionChangeNotification,
+	disputes::supermajority_threshold,
+};
 use sp_core::RuntimeDebug;
 
 #[cfg(feature = "std")]
@@ -49,7 +61,8 @@ pub use crate::Origin;
 pub trait Config:
 	frame_system::Config +
 	configuration::Config +
-	shared::Config
+	shared::Config +
+	frame_system::offchain::SendTransactionTypes<Call<Self>>
 {
 	/// The outer origin type.
 	type Origin: From<Origin>
@@ -267,6 +280,94 @@ impl<N: Ord + Copy + PartialEq> ParaPastCodeMeta<N> {
 	}
 }
 
+/// The action to take once some validation code has passed PVF pre-checking.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub enum PvfCheckCause<N> {
+	/// The code is the genesis code of the given onboarding para.
+	Onboarding(ParaId),
+	/// The code is the new code of a para which requested an upgrade, to be enacted no earlier
+	/// than `expected_at`.
+	Upgrade {
+		/// The para which requested the upgrade.
+		id: ParaId,
+		/// The relay-chain block number the upgrade was originally expected at.
+		expected_at: N,
+	},
+}
+
+impl<N> PvfCheckCause<N> {
+	fn para_id(&self) -> ParaId {
+		match *self {
+			PvfCheckCause::Onboarding(id) => id,
+			PvfCheckCause::Upgrade { id, .. } => id,
+		}
+	}
+}
+
+/// The state of an ongoing PVF pre-checking vote.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct PvfCheckActiveVoteState<N> {
+	// Both bitfields have one bit per active validator of the current session. The votes are
+	// reset at every session change since the validator set may change.
+	votes_accept: BitVec<BitOrderLsb0, u8>,
+	votes_reject: BitVec<BitOrderLsb0, u8>,
+	/// The number of session changes this vote has survived.
+	age: SessionIndex,
+	/// The relay-chain block number at which the vote was started.
+	created_at: N,
+	/// The actions to take once the vote is concluded, in the order they were requested.
+	causes: Vec<PvfCheckCause<N>>,
+}
+
+/// The result of a concluded PVF pre-checking vote.
+#[derive(Clone, Copy, PartialEq, RuntimeDebug)]
+enum PvfCheckOutcome {
+	Accepted,
+	Rejected,
+}
+
+impl<N> PvfCheckActiveVoteState<N> {
+	fn new(now: N, n_validators: usize, cause: PvfCheckCause<N>) -> Self {
+		Self {
+			votes_accept: bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators],
+			votes_reject: bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators],
+			age: 0,
+			created_at: now,
+			causes: vec![cause],
+		}
+	}
+
+	// Clear all the votes and resize the bitfields for a new validator set.
+	fn reinitialize_ballots(&mut self, n_validators: usize) {
+		self.votes_accept = bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators];
+		self.votes_reject = bitvec::bitvec![BitOrderLsb0, u8; 0; n_validators];
+	}
+
+	// Whether the validator with the given index has already voted, in either direction.
+	fn has_vote(&self, validator_index: usize) -> bool {
+		let accepted = self.votes_accept.get(validator_index).map_or(false, |b| *b);
+		let rejected = self.votes_reject.get(validator_index).map_or(false, |b| *b);
+		accepted || rejected
+	}
+
+	// The outcome of the vote, if it has been reached.
+	//
+	// The code is accepted once a supermajority of the validators accepted it, and rejected as
+	// soon as a supermajority can no longer be reached.
+	fn quorum(&self, n_validators: usize) -> Option<PvfCheckOutcome> {
+		let accept_threshold = supermajority_threshold(n_validators);
+		let reject_threshold = n_validators - accept_threshold + 1;
+
+		if self.votes_accept.count_ones() >= accept_threshold {
+			Some(PvfCheckOutcome::Accepted)
+		} else if self.votes_reject.count_ones() >= reject_threshold {
+			Some(PvfCheckOutcome::Rejected)
+		} else {
+			None
+		}
+	}
+}
+
 /// Arguments for initializing a para.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -324,9 +425,17 @@ decl_storage! {
 		CodeByHashRefs: map hasher(identity) Hash => u32;
 		/// Validation code stored by its hash.
 		///
-		/// This storage is consistent with [`FutureCodeHash`], [`CurrentCodeHash`] and
-		/// [`PastCodeHash`].
+		/// This storage is consistent with [`FutureCodeHash`], [`CurrentCodeHash`],
+		/// [`PastCodeHash`] and [`PvfActiveVoteMap`].
 		CodeByHash get(fn code_by_hash): map hasher(identity) Hash => Option<ValidationCode>;
+		/// The ongoing PVF pre-checking votes, by the hash of the validation code voted on.
+		///
+		/// Every vote holds a reference on its code in [`CodeByHash`] so that the validators can
+		/// fetch it.
+		PvfActiveVoteMap:
+			map hasher(identity) Hash => Option<PvfCheckActiveVoteState<T::BlockNumber>>;
+		/// The code hashes of all votes in [`PvfActiveVoteMap`], in the order they were started.
+		PvfActiveVoteList get(fn pvfs_require_precheck): Vec<Hash>;
 	}
 	add_extra_genesis {
 		config(paras): Vec<(ParaId, ParaGenesisArgs)>;
@@ -374,6 +483,18 @@ decl_error! {
 		CannotUpgrade,
 		/// Para cannot be downgraded to a parathread.
 		CannotDowngrade,
+		/// The PVF pre-checking statement was issued in a session which has already ended.
+		PvfCheckStatementStale,
+		/// The PVF pre-checking statement was issued in a session which hasn't started yet.
+		PvfCheckStatementFuture,
+		/// The validator index in the PVF pre-checking statement is out of bounds.
+		PvfCheckValidatorIndexOutOfBounds,
+		/// The signature on the PVF pre-checking statement is invalid.
+		PvfCheckInvalidSignature,
+		/// The validator has already voted on the given code.
+		PvfCheckDoubleVote,
+		/// The given code isn't awaiting PVF pre-checking.
+		PvfCheckSubjectInvalid,
 	}
 }
 
@@ -389,6 +510,15 @@ decl_event! {
 		NewHeadNoted(ParaId),
 		/// A para has been queued to execute pending actions. \[para_id\]
 		ActionQueued(ParaId, SessionIndex),
+		/// A para either started or joined a PVF pre-checking vote on the given code.
+		/// \[code_hash, para_id\]
+		PvfCheckStarted(Hash, ParaId),
+		/// The given code passed PVF pre-checking and the action of the para which requested it
+		/// is going ahead. \[code_hash, para_id\]
+		PvfCheckAccepted(Hash, ParaId),
+		/// The given code was rejected by PVF pre-checking and the action of the para which
+		/// requested it was dropped. \[code_hash, para_id\]
+		PvfCheckRejected(Hash, ParaId),
 	}
 }
 
@@ -452,6 +582,75 @@ decl_module! {
 			});
 			Self::deposit_event(Event::ActionQueued(para, next_session));
 		}
+
+		/// Include a validator's statement on whether some validation code passed PVF
+		/// pre-checking.
+		///
+		/// This is submitted by the validators as an unsigned transaction. The vote concludes
+		/// once a supermajority of the validators accepted the code, or as soon as such a
+		/// supermajority cannot be reached anymore.
+		#[weight = (T::DbWeight::get().reads_writes(4, 4), DispatchClass::Operational)]
+		fn include_pvf_check_statement(
+			origin,
+			stmt: PvfCheckStatement,
+			signature: ValidatorSignature,
+		) {
+			ensure_none(origin)?;
+
+			let mut active_vote = Self::check_pvf_check_statement(&stmt, &signature)?;
+			let n_validators = shared::Module::<T>::active_validator_keys().len();
+
+			let validator_index = stmt.validator_index.0 as usize;
+			if stmt.accept {
+				active_vote.votes_accept.set(validator_index, true);
+			} else {
+				active_vote.votes_reject.set(validator_index, true);
+			}
+
+			match active_vote.quorum(n_validators) {
+				Some(outcome) => {
+					<Self as Store>::PvfActiveVoteMap::remove(&stmt.subject);
+					<Self as Store>::PvfActiveVoteList::mutate(|list| {
+						list.retain(|code_hash| code_hash != &stmt.subject);
+					});
+					Self::conclude_pvf_check(stmt.subject, active_vote.causes, outcome);
+				}
+				None => {
+					<Self as Store>::PvfActiveVoteMap::insert(&stmt.subject, active_vote);
+				}
+			}
+		}
+	}
+}
+
+impl<T: Config> sp_runtime::traits::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		let (stmt, signature) = match call {
+			Call::include_pvf_check_statement(stmt, signature) => (stmt, signature),
+			_ => return InvalidTransaction::Call.into(),
+		};
+
+		if let Err(e) = Self::check_pvf_check_statement(stmt, signature) {
+			let invalid = match e {
+				Error::<T>::PvfCheckStatementStale => InvalidTransaction::Stale,
+				Error::<T>::PvfCheckStatementFuture => InvalidTransaction::Future,
+				Error::<T>::PvfCheckInvalidSignature => InvalidTransaction::BadProof,
+				Error::<T>::PvfCheckValidatorIndexOutOfBounds => InvalidTransaction::Custom(1),
+				Error::<T>::PvfCheckDoubleVote => InvalidTransaction::Custom(2),
+				Error::<T>::PvfCheckSubjectInvalid => InvalidTransaction::Custom(3),
+				_ => InvalidTransaction::Call,
+			};
+			return invalid.into();
+		}
+
+		ValidTransaction::with_tag_prefix("PvfPreCheckingVote")
+			.priority(TransactionPriority::max_value())
+			.longevity(TransactionLongevity::max_value())
+			.and_provides((stmt.session_index, stmt.validator_index, stmt.subject))
+			.propagate(true)
+			.build()
 	}
 }
 
@@ -469,6 +668,7 @@ impl<T: Config> Module<T> {
 	/// Returns the list of outgoing paras from the actions queue.
 	pub(crate) fn initializer_on_new_session(notification: &SessionChangeNotification<T::BlockNumber>) -> Vec<ParaId> {
 		let outgoing_paras = Self::apply_actions_queue(notification.session_index);
+		Self::groom_ongoing_pvf_votes(&notification.new_config, notification.validators.len());
 		outgoing_paras
 	}
 
@@ -665,20 +865,20 @@ impl<T: Config> Module<T> {
 
 	/// Schedule a para to be initialized at the start of the next session.
 	///
+	/// If PVF pre-checking is enabled, the para is only scheduled once its genesis code has been
+	/// accepted by the validators, and is dropped if the code is rejected.
+	///
 	/// Will return error if para is already registered in the system.
 	pub(crate) fn schedule_para_initialize(id: ParaId, genesis: ParaGenesisArgs) -> DispatchResult {
-		let scheduled_session = Self::scheduled_session();
-
 		// Make sure parachain isn't already in our system.
 		ensure!(Self::can_schedule_para_initialize(&id, &genesis), Error::<T>::CannotOnboard);
 
+		let code = genesis.validation_code.clone();
 		ParaLifecycles::insert(&id, ParaLifecycle::Onboarding);
 		UpcomingParasGenesis::insert(&id, genesis);
-		ActionsQueue::mutate(scheduled_session, |v| {
-			if let Err(i) = v.binary_search(&id) {
-				v.insert(i, id);
-			}
-		});
+
+		// The para is queued for onboarding only once its genesis code passed PVF pre-checking.
+		Self::start_pvf_check(PvfCheckCause::Onboarding(id), code.hash(), &code);
 
 		Ok(())
 	}
@@ -756,27 +956,41 @@ impl<T: Config> Module<T> {
 	/// of a block of the same parachain executed in the context of a relay-chain block
 	/// with number >= `expected_at`
 	///
-	/// If there is already a scheduled code upgrade for the para, this is a no-op.
+	/// If PVF pre-checking is enabled, the upgrade is only scheduled once the new code has been
+	/// accepted by the validators, and is dropped if the code is rejected. It is never scheduled
+	/// earlier than `expected_at`.
+	///
+	/// If there is already a scheduled code upgrade for the para, or one awaiting pre-checking,
+	/// this is a no-op.
 	pub(crate) fn schedule_code_upgrade(
 		id: ParaId,
 		new_code: ValidationCode,
 		expected_at: T::BlockNumber,
 	) -> Weight {
-		<Self as Store>::FutureCodeUpgrades::mutate(&id, |up| {
-			if up.is_some() {
-				T::DbWeight::get().reads_writes(1, 0)
-			} else {
-				*up = Some(expected_at);
+		if FutureCodeHash::contains_key(&id) {
+			return T::DbWeight::get().reads_writes(1, 0);
+		}
 
-				let new_code_hash = new_code.hash();
-				let expected_at_u32 = expected_at.saturated_into();
-				let log = ConsensusLog::ParaScheduleUpgradeCode(id, new_code_hash, expected_at_u32);
-				<frame_system::Pallet<T>>::deposit_log(log.into());
+		let new_code_hash = new_code.hash();
+		FutureCodeHash::insert(&id, new_code_hash);
 
-				let (reads, writes) = Self::increase_code_ref(&new_code_hash, &new_code);
-				FutureCodeHash::insert(&id, new_code_hash);
-				T::DbWeight::get().reads_writes(1 + reads, 2 + writes)
+		let cause = PvfCheckCause::Upgrade { id, expected_at };
+		let weight = Self::start_pvf_check(cause, new_code_hash, &new_code);
+
+		let (reads, writes) = Self::increase_code_ref(&new_code_hash, &new_code);
+		weight + T::DbWeight::get().reads_writes(1 + reads, 1 + writes)
+	}
+
+	/// Returns the `expected_at` block number of the code upgrade of the given para which is
+	/// awaiting PVF pre-checking, if any.
+	fn upgrade_pending_pvf_check(id: ParaId) -> Option<T::BlockNumber> {
+		let code_hash = FutureCodeHash::get(&id)?;
+		let active_vote = <Self as Store>::PvfActiveVoteMap::get(&code_hash)?;
+		active_vote.causes.iter().find_map(|cause| match *cause {
+			PvfCheckCause::Upgrade { id: cause_id, expected_at } if cause_id == id => {
+				Some(expected_at)
 			}
+			_ => None,
 		})
 	}
 
@@ -922,6 +1136,10 @@ impl<T: Config> Module<T> {
 			if let Some(at) = Self::future_code_upgrade_at(id) {
 				return Some(at);
 			}
+
+			if let Some(at) = Self::upgrade_pending_pvf_check(id) {
+				return Some(at);
+			}
 		}
 
 		Self::past_code_meta(&id).most_recent_change()
@@ -932,6 +1150,237 @@ impl<T: Config> Module<T> {
 		shared::Module::<T>::scheduled_session()
 	}
 
+	/// Submits a PVF pre-checking statement signed by a validator into the transaction pool.
+	///
+	/// This is meant to be called from the node side through the runtime API.
+	pub fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) {
+		let call = Call::include_pvf_check_statement(stmt, signature);
+		if let Err(()) = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()) {
+			log::error!(
+				target: "runtime::paras",
+				"Error submitting PVF pre-checking statement",
+			);
+		}
+	}
+
+	/// Checks a PVF pre-checking statement against the current session and the ongoing votes.
+	///
+	/// Returns the state of the vote the statement is about.
+	fn check_pvf_check_statement(
+		stmt: &PvfCheckStatement,
+		signature: &ValidatorSignature,
+	) -> Result<PvfCheckActiveVoteState<T::BlockNumber>, Error<T>> {
+		let current_session = shared::Module::<T>::session_index();
+		ensure!(stmt.session_index >= current_session, Error::<T>::PvfCheckStatementStale);
+		ensure!(stmt.session_index <= current_session, Error::<T>::PvfCheckStatementFuture);
+
+		let validators = shared::Module::<T>::active_validator_keys();
+		let validator_index = stmt.validator_index.0 as usize;
+		let validator_public = validators
+			.get(validator_index)
+			.ok_or(Error::<T>::PvfCheckValidatorIndexOutOfBounds)?;
+
+		ensure!(
+			signature.verify(&stmt.signing_payload()[..], validator_public),
+			Error::<T>::PvfCheckInvalidSignature,
+		);
+
+		let active_vote = <Self as Store>::PvfActiveVoteMap::get(&stmt.subject)
+			.ok_or(Error::<T>::PvfCheckSubjectInvalid)?;
+		ensure!(!active_vote.has_vote(validator_index), Error::<T>::PvfCheckDoubleVote);
+
+		Ok(active_vote)
+	}
+
+	/// Starts a PVF pre-checking vote on the given code on behalf of `cause`, or subscribes
+	/// `cause` to the ongoing vote on that code.
+	///
+	/// The cause is enacted right away if PVF pre-checking is disabled or if the code is already
+	/// stored on-chain and thus has been checked before.
+	fn start_pvf_check(
+		cause: PvfCheckCause<T::BlockNumber>,
+		code_hash: Hash,
+		code: &ValidationCode,
+	) -> Weight {
+		let config = configuration::Module::<T>::config();
+		let mut weight = T::DbWeight::get().reads_writes(1, 0);
+
+		if !config.pvf_checking_enabled {
+			return weight + Self::enact_pvf_accepted(&code_hash, cause);
+		}
+
+		weight += T::DbWeight::get().reads_writes(1, 0);
+		if let Some(mut active_vote) = <Self as Store>::PvfActiveVoteMap::get(&code_hash) {
+			Self::deposit_event(Event::PvfCheckStarted(code_hash, cause.para_id()));
+			active_vote.causes.push(cause);
+			<Self as Store>::PvfActiveVoteMap::insert(&code_hash, active_vote);
+			return weight + T::DbWeight::get().reads_writes(0, 1);
+		}
+
+		weight += T::DbWeight::get().reads_writes(1, 0);
+		if <Self as Store>::CodeByHashRefs::get(&code_hash) > 0 {
+			return weight + Self::enact_pvf_accepted(&code_hash, cause);
+		}
+
+		Self::deposit_event(Event::PvfCheckStarted(code_hash, cause.para_id()));
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let n_validators = shared::Module::<T>::active_validator_keys().len();
+		let active_vote = PvfCheckActiveVoteState::new(now, n_validators, cause);
+		<Self as Store>::PvfActiveVoteMap::insert(&code_hash, active_vote);
+		<Self as Store>::PvfActiveVoteList::append(code_hash);
+
+		let (reads, writes) = Self::increase_code_ref(&code_hash, code);
+		weight + T::DbWeight::get().reads_writes(1 + reads, 2 + writes)
+	}
+
+	/// Concludes a PVF pre-checking vote which has already been removed from the list of ongoing
+	/// votes, enacting or dropping all of its causes.
+	fn conclude_pvf_check(
+		code_hash: Hash,
+		causes: Vec<PvfCheckCause<T::BlockNumber>>,
+		outcome: PvfCheckOutcome,
+	) -> Weight {
+		let mut weight = 0;
+
+		match outcome {
+			PvfCheckOutcome::Accepted => {
+				let now = <frame_system::Pallet<T>>::block_number();
+				for cause in causes {
+					Self::deposit_event(Event::PvfCheckAccepted(code_hash, cause.para_id()));
+
+					// The upgrade might have been delayed by the vote, in which case it is
+					// scheduled right away.
+					let cause = match cause {
+						PvfCheckCause::Upgrade { id, expected_at } => PvfCheckCause::Upgrade {
+							id,
+							expected_at: sp_std::cmp::max(expected_at, now),
+						},
+						cause => cause,
+					};
+					weight += Self::enact_pvf_accepted(&code_hash, cause);
+				}
+			}
+			PvfCheckOutcome::Rejected => {
+				for cause in causes {
+					Self::deposit_event(Event::PvfCheckRejected(code_hash, cause.para_id()));
+					weight += Self::enact_pvf_rejected(&code_hash, cause);
+				}
+			}
+		}
+
+		// Release the reference held by the vote.
+		Self::decrease_code_ref(&code_hash);
+		weight + T::DbWeight::get().reads_writes(1, 2)
+	}
+
+	/// Goes ahead with the action requested by `cause` now that its code has been accepted.
+	fn enact_pvf_accepted(code_hash: &Hash, cause: PvfCheckCause<T::BlockNumber>) -> Weight {
+		match cause {
+			PvfCheckCause::Onboarding(id) => {
+				// The para could only have left the onboarding state by being dropped.
+				if ParaLifecycles::get(&id) != Some(ParaLifecycle::Onboarding) {
+					return T::DbWeight::get().reads_writes(1, 0);
+				}
+
+				let scheduled_session = Self::scheduled_session();
+				ActionsQueue::mutate(scheduled_session, |v| {
+					if let Err(i) = v.binary_search(&id) {
+						v.insert(i, id);
+					}
+				});
+
+				T::DbWeight::get().reads_writes(3, 1)
+			}
+			PvfCheckCause::Upgrade { id, expected_at } => {
+				// The para could have been offboarded in the meantime, in which case its future
+				// code was discarded.
+				if FutureCodeHash::get(&id).as_ref() != Some(code_hash) {
+					return T::DbWeight::get().reads_writes(1, 0);
+				}
+
+				<Self as Store>::FutureCodeUpgrades::insert(&id, expected_at);
+
+				let expected_at_u32 = expected_at.saturated_into();
+				let log = ConsensusLog::ParaScheduleUpgradeCode(id, *code_hash, expected_at_u32);
+				<frame_system::Pallet<T>>::deposit_log(log.into());
+
+				T::DbWeight::get().reads_writes(1, 2)
+			}
+		}
+	}
+
+	/// Drops the action requested by `cause` now that its code has been rejected.
+	fn enact_pvf_rejected(code_hash: &Hash, cause: PvfCheckCause<T::BlockNumber>) -> Weight {
+		match cause {
+			PvfCheckCause::Onboarding(id) => {
+				if ParaLifecycles::get(&id) != Some(ParaLifecycle::Onboarding) {
+					return T::DbWeight::get().reads_writes(1, 0);
+				}
+
+				ParaLifecycles::remove(&id);
+				UpcomingParasGenesis::remove(&id);
+
+				T::DbWeight::get().reads_writes(1, 2)
+			}
+			PvfCheckCause::Upgrade { id, .. } => {
+				if FutureCodeHash::get(&id).as_ref() != Some(code_hash) {
+					return T::DbWeight::get().reads_writes(1, 0);
+				}
+
+				FutureCodeHash::remove(&id);
+				Self::decrease_code_ref(code_hash);
+
+				T::DbWeight::get().reads_writes(2, 2)
+			}
+		}
+	}
+
+	/// Ages all ongoing PVF pre-checking votes at a session change, rejecting the ones which
+	/// reached the configured TTL and resetting the ballots of the others for the new validator
+	/// set.
+	fn groom_ongoing_pvf_votes(
+		config: &configuration::HostConfiguration<T::BlockNumber>,
+		n_validators: usize,
+	) -> Weight {
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+		let ongoing_votes = <Self as Store>::PvfActiveVoteList::get();
+		let mut still_active = Vec::with_capacity(ongoing_votes.len());
+
+		for code_hash in ongoing_votes {
+			let mut active_vote = match <Self as Store>::PvfActiveVoteMap::get(&code_hash) {
+				Some(active_vote) => active_vote,
+				None => {
+					log::warn!(
+						target: "runtime::paras",
+						"No PVF pre-checking vote found for hash {:?}",
+						code_hash,
+					);
+					continue
+				}
+			};
+
+			active_vote.age += 1;
+			if active_vote.age < config.pvf_voting_ttl {
+				active_vote.reinitialize_ballots(n_validators);
+				<Self as Store>::PvfActiveVoteMap::insert(&code_hash, active_vote);
+				still_active.push(code_hash);
+				weight += T::DbWeight::get().reads_writes(1, 1);
+			} else {
+				<Self as Store>::PvfActiveVoteMap::remove(&code_hash);
+				weight += Self::conclude_pvf_check(
+					code_hash,
+					active_vote.causes,
+					PvfCheckOutcome::Rejected,
+				);
+			}
+		}
+
+		<Self as Store>::PvfActiveVoteList::put(still_active);
+		weight
+	}
+
 	/// Store the validation code if not already stored, and increase the number of reference.
 	///
 	/// Returns the number of storage reads and number of storage writes.
@@ -979,7 +1428,12 @@ mod tests {
 		traits::{OnFinalize, OnInitialize}
 	};
 
-	use crate::mock::{new_test_ext, Paras, Shared, System, MockGenesisConfig};
+	use primitives::v1::{ValidatorId, ValidatorIndex};
+	use keyring::Sr25519Keyring;
+
+	use crate::mock::{
+		new_test_ext, Paras, Shared, System, MockGenesisConfig, Test, Event as MockEvent,
+	};
 	use crate::configuration::HostConfiguration;
 
 	fn run_to_block(to: BlockNumber, new_session: Option<Vec<BlockNumber>>) {
@@ -1802,4 +2256,262 @@ mod tests {
 			assert!(!CodeByHashRefs::contains_key(code.hash()));
 		});
 	}
+
+	const VALIDATORS: [Sr25519Keyring; 4] = [
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+	];
+
+	fn pvf_checking_genesis(paras: Vec<(ParaId, ParaGenesisArgs)>) -> MockGenesisConfig {
+		MockGenesisConfig {
+			paras: GenesisConfig { paras, ..Default::default() },
+			configuration: crate::configuration::GenesisConfig {
+				config: HostConfiguration {
+					pvf_checking_enabled: true,
+					pvf_voting_ttl: 2,
+					validation_upgrade_delay: 5,
+					..Default::default()
+				},
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	fn set_validators() {
+		Shared::set_active_validators_ascending(
+			VALIDATORS.iter().map(|k| ValidatorId::from(k.public())).collect(),
+		);
+	}
+
+	fn submit_vote(validator: usize, subject: Hash, accept: bool) -> DispatchResult {
+		let stmt = PvfCheckStatement {
+			accept,
+			subject,
+			session_index: Shared::session_index(),
+			validator_index: ValidatorIndex(validator as _),
+		};
+		let signature = VALIDATORS[validator].sign(&stmt.signing_payload()).into();
+
+		Paras::include_pvf_check_statement(None.into(), stmt, signature)
+			.map(|_| ())
+			.map_err(|e| e.error)
+	}
+
+	fn has_event(event: Event) -> bool {
+		System::events().iter().any(|record| record.event == MockEvent::Paras(event.clone()))
+	}
+
+	#[test]
+	fn pvf_check_upgrade_is_enacted_when_accepted() {
+		let para_id = ParaId::from(0);
+		let original_code = ValidationCode(vec![1, 2, 3]);
+		let paras = vec![
+			(para_id, ParaGenesisArgs {
+				parachain: true,
+				genesis_head: Default::default(),
+				validation_code: original_code.clone(),
+			}),
+		];
+
+		new_test_ext(pvf_checking_genesis(paras)).execute_with(|| {
+			run_to_block(2, None);
+			set_validators();
+
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			let expected_at = 1 + 5;
+			Paras::schedule_code_upgrade(para_id, new_code.clone(), expected_at);
+
+			// The upgrade is not scheduled before the code was accepted, but no other upgrade
+			// can be attempted in the meantime.
+			assert_eq!(Paras::pvfs_require_precheck(), vec![new_code.hash()]);
+			assert!(<Paras as Store>::FutureCodeUpgrades::get(&para_id).is_none());
+			assert_eq!(Paras::last_code_upgrade(para_id, true), Some(expected_at));
+			assert!(has_event(Event::PvfCheckStarted(new_code.hash(), para_id)));
+			check_code_is_stored(&new_code);
+
+			// A supermajority out of 4 validators is 3.
+			assert_ok!(submit_vote(0, new_code.hash(), true));
+			assert_ok!(submit_vote(1, new_code.hash(), false));
+			assert_ok!(submit_vote(2, new_code.hash(), true));
+			assert!(<Paras as Store>::FutureCodeUpgrades::get(&para_id).is_none());
+
+			run_to_block(8, None);
+			assert_ok!(submit_vote(3, new_code.hash(), true));
+
+			// The vote took longer than the upgrade delay, so the upgrade is scheduled right away.
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(!<Paras as Store>::PvfActiveVoteMap::contains_key(&new_code.hash()));
+			assert_eq!(<Paras as Store>::FutureCodeUpgrades::get(&para_id), Some(8));
+			assert_eq!(<Paras as Store>::FutureCodeHash::get(&para_id), Some(new_code.hash()));
+			assert_eq!(<Paras as Store>::CodeByHashRefs::get(&new_code.hash()), 1);
+			assert!(has_event(Event::PvfCheckAccepted(new_code.hash(), para_id)));
+
+			Paras::note_new_head(para_id, Default::default(), 8);
+			assert_eq!(Paras::current_code(&para_id), Some(new_code.clone()));
+		});
+	}
+
+	#[test]
+	fn pvf_check_onboarding_is_dropped_when_rejected() {
+		new_test_ext(pvf_checking_genesis(Vec::new())).execute_with(|| {
+			run_to_block(1, None);
+			set_validators();
+
+			let para_id = ParaId::from(42);
+			let code = ValidationCode(vec![1, 2, 3]);
+			assert_ok!(Paras::schedule_para_initialize(para_id, ParaGenesisArgs {
+				parachain: true,
+				genesis_head: Default::default(),
+				validation_code: code.clone(),
+			}));
+
+			assert_eq!(ParaLifecycles::get(&para_id), Some(ParaLifecycle::Onboarding));
+			assert!(Paras::actions_queue(Paras::scheduled_session()).is_empty());
+			check_code_is_stored(&code);
+
+			// With 4 validators, 2 rejections make a supermajority unreachable.
+			assert_ok!(submit_vote(0, code.hash(), false));
+			assert!(ParaLifecycles::get(&para_id).is_some());
+			assert_ok!(submit_vote(1, code.hash(), false));
+
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(ParaLifecycles::get(&para_id).is_none());
+			assert!(!<Paras as Store>::UpcomingParasGenesis::contains_key(&para_id));
+			assert!(Paras::actions_queue(Paras::scheduled_session()).is_empty());
+			check_code_is_not_stored(&code);
+			assert!(has_event(Event::PvfCheckRejected(code.hash(), para_id)));
+		});
+	}
+
+	#[test]
+	fn pvf_check_onboarding_reuses_ongoing_vote() {
+		new_test_ext(pvf_checking_genesis(Vec::new())).execute_with(|| {
+			run_to_block(1, None);
+			set_validators();
+
+			let code = ValidationCode(vec![1, 2, 3]);
+			for id in &[1u32, 2] {
+				assert_ok!(Paras::schedule_para_initialize((*id).into(), ParaGenesisArgs {
+					parachain: false,
+					genesis_head: Default::default(),
+					validation_code: code.clone(),
+				}));
+			}
+
+			assert_eq!(Paras::pvfs_require_precheck(), vec![code.hash()]);
+			assert_eq!(<Paras as Store>::CodeByHashRefs::get(&code.hash()), 1);
+
+			for validator in 0..3 {
+				assert_ok!(submit_vote(validator, code.hash(), true));
+			}
+
+			assert_eq!(
+				Paras::actions_queue(Paras::scheduled_session()),
+				vec![ParaId::from(1), ParaId::from(2)],
+			);
+			check_code_is_not_stored(&code);
+		});
+	}
+
+	#[test]
+	fn pvf_check_vote_is_rejected_after_ttl() {
+		let para_id = ParaId::from(0);
+		let paras = vec![
+			(para_id, ParaGenesisArgs {
+				parachain: true,
+				genesis_head: Default::default(),
+				validation_code: ValidationCode(vec![1, 2, 3]),
+			}),
+		];
+
+		new_test_ext(pvf_checking_genesis(paras)).execute_with(|| {
+			run_to_block(1, None);
+			set_validators();
+
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			Paras::schedule_code_upgrade(para_id, new_code.clone(), 6);
+			assert_ok!(submit_vote(0, new_code.hash(), true));
+
+			// The first session change resets the votes, the second one reaches the TTL.
+			run_to_block(3, Some(vec![3]));
+			assert_eq!(Paras::pvfs_require_precheck(), vec![new_code.hash()]);
+			assert!(<Paras as Store>::PvfActiveVoteMap::get(&new_code.hash()).unwrap()
+				.votes_accept.not_any());
+
+			run_to_block(5, Some(vec![5]));
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(<Paras as Store>::FutureCodeHash::get(&para_id).is_none());
+			assert!(Paras::last_code_upgrade(para_id, true).is_none());
+			check_code_is_not_stored(&new_code);
+			assert!(has_event(Event::PvfCheckRejected(new_code.hash(), para_id)));
+		});
+	}
+
+	#[test]
+	fn pvf_check_statement_is_checked() {
+		let para_id = ParaId::from(0);
+		let paras = vec![
+			(para_id, ParaGenesisArgs {
+				parachain: true,
+				genesis_head: Default::default(),
+				validation_code: ValidationCode(vec![1, 2, 3]),
+			}),
+		];
+
+		new_test_ext(pvf_checking_genesis(paras)).execute_with(|| {
+			run_to_block(1, None);
+			set_validators();
+
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			Paras::schedule_code_upgrade(para_id, new_code.clone(), 6);
+
+			assert_ok!(submit_vote(0, new_code.hash(), true));
+			assert_eq!(
+				submit_vote(0, new_code.hash(), false),
+				Err(Error::<Test>::PvfCheckDoubleVote.into()),
+			);
+			assert_eq!(
+				submit_vote(1, Hash::repeat_byte(1), true),
+				Err(Error::<Test>::PvfCheckSubjectInvalid.into()),
+			);
+
+			let stmt = PvfCheckStatement {
+				accept: true,
+				subject: new_code.hash(),
+				session_index: Shared::session_index(),
+				validator_index: ValidatorIndex(4),
+			};
+			let signature = VALIDATORS[0].sign(&stmt.signing_payload()).into();
+			assert_eq!(
+				Paras::include_pvf_check_statement(None.into(), stmt, signature)
+					.map_err(|e| e.error),
+				Err(Error::<Test>::PvfCheckValidatorIndexOutOfBounds.into()),
+			);
+
+			let stmt = PvfCheckStatement {
+				accept: true,
+				subject: new_code.hash(),
+				session_index: Shared::session_index(),
+				validator_index: ValidatorIndex(1),
+			};
+			let signature = VALIDATORS[2].sign(&stmt.signing_payload()).into();
+			assert_eq!(
+				Paras::include_pvf_check_statement(None.into(), stmt.clone(), signature)
+					.map_err(|e| e.error),
+				Err(Error::<Test>::PvfCheckInvalidSignature.into()),
+			);
+
+			let signature = VALIDATORS[1].sign(&stmt.signing_payload()).into();
+			assert_eq!(
+				<Paras as sp_runtime::traits::ValidateUnsigned>::validate_unsigned(
+					TransactionSource::External,
+					&Call::include_pvf_check_statement(stmt, signature),
+				).map(|_| ()),
+				Ok(()),
+			);
+		});
+	}
 }
@@ -24,7 +24,8 @@
 use sp_std::prelude::*;
 use sp_runtime::traits::Header as HeaderT;
 use primitives::v1::{
-	BackedCandidate, PARACHAINS_INHERENT_IDENTIFIER, InherentData as ParachainsInherentData,
+	BackedCandidate, BackingMisbehaviorProof, DisputeStatementSet, PARACHAINS_INHERENT_IDENTIFIER,
+	InherentData as ParachainsInherentData, SessionIndex, ValidatorIndex,
 };
 use frame_support::{
 	decl_error, decl_module, decl_storage, ensure,
@@ -35,19 +36,39 @@ use frame_support::{
 };
 use frame_system::ensure_none;
 use crate::{
+	disputes::DisputesHandler,
 	inclusion,
 	scheduler::{self, FreedReason},
+	session_info,
+	shared,
 	ump,
 };
 
 const LOG_TARGET: &str = "runtime::inclusion-inherent";
 // In the future, we should benchmark these consts; these are all untested assumptions for now.
 const BACKED_CANDIDATE_WEIGHT: Weight = 100_000;
+const DISPUTE_PER_STATEMENT_WEIGHT: Weight = 200_000;
+const DISPUTE_STATEMENT_SET_WEIGHT: Weight = 1_000_000;
+const BACKING_MISBEHAVIOR_PROOF_WEIGHT: Weight = 400_000;
 const INCLUSION_INHERENT_CLAIMED_WEIGHT: Weight = 1_000_000_000;
 // we assume that 75% of an paras inherent's weight is used processing backed candidates
 const MINIMAL_INCLUSION_INHERENT_WEIGHT: Weight = INCLUSION_INHERENT_CLAIMED_WEIGHT / 4;
 
-pub trait Config: inclusion::Config + scheduler::Config {}
+/// Punishment hooks for misbehavior during candidate backing.
+pub trait PunishBackingMisbehavior {
+	/// Punish a validator who issued conflicting statements during candidate backing in the
+	/// given session. The proof of misbehavior has already been checked.
+	fn punish_backing_misbehavior(session: SessionIndex, validator: ValidatorIndex);
+}
+
+impl PunishBackingMisbehavior for () {
+	fn punish_backing_misbehavior(_: SessionIndex, _: ValidatorIndex) { }
+}
+
+pub trait Config: inclusion::Config + scheduler::Config + session_info::Config {
+	/// A hook for punishing validators who misbehaved during candidate backing.
+	type PunishBackingMisbehavior: PunishBackingMisbehavior;
+}
 
 decl_storage! {
 	trait Store for Module<T: Config> as ParaInherent {
@@ -88,7 +109,10 @@ decl_module! {
 
 		/// Enter the paras inherent. This will process bitfields and backed candidates.
 		#[weight = (
-			MINIMAL_INCLUSION_INHERENT_WEIGHT + data.backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT,
+			MINIMAL_INCLUSION_INHERENT_WEIGHT
+				+ data.backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT
+				+ dispute_statements_weight(&data.disputes)
+				+ backing_misbehavior_weight(&data.backing_misbehavior),
 			DispatchClass::Mandatory,
 		)]
 		pub fn enter(
@@ -99,7 +123,8 @@ decl_module! {
 				bitfields: signed_bitfields,
 				backed_candidates,
 				parent_header,
-				disputes: _,
+				disputes,
+				backing_misbehavior,
 			} = data;
 
 			ensure_none(origin)?;
@@ -112,6 +137,40 @@ decl_module! {
 				Error::<T>::InvalidParentHeader,
 			);
 
+			let current_session = <shared::Module<T>>::session_index();
+
+			// Punish validators which issued conflicting statements during backing.
+			let backing_misbehavior_weight = backing_misbehavior_weight(&backing_misbehavior);
+			Self::process_backing_misbehavior(current_session, backing_misbehavior);
+
+			// Handle disputes logic.
+			let disputes_weight = dispute_statements_weight(&disputes);
+			let freed_disputed: Vec<_> = {
+				let any_current_session_disputes = disputes.iter()
+					.any(|set| set.session == current_session);
+
+				// Invalid dispute statement sets are ignored rather than failing the inherent.
+				T::DisputesHandler::provide_multi_dispute_data(disputes);
+				if T::DisputesHandler::is_frozen() {
+					// The relay chain we are currently on is invalid. Proceed no further on parachains.
+					Included::set(Some(()));
+					return Ok(Some(
+						MINIMAL_INCLUSION_INHERENT_WEIGHT + disputes_weight + backing_misbehavior_weight
+					).into());
+				}
+
+				// Free the cores of any candidates pending availability which have just been
+				// concluded invalid.
+				if any_current_session_disputes {
+					<inclusion::Module<T>>::collect_disputed(current_session)
+						.into_iter()
+						.map(|core| (core, FreedReason::Concluded))
+						.collect()
+				} else {
+					Vec::new()
+				}
+			};
+
 			// Process new availability bitfields, yielding any availability cores whose
 			// work has now concluded.
 			let expected_bits = <scheduler::Module<T>>::availability_cores().len();
@@ -130,7 +189,8 @@ decl_module! {
 			};
 
 			// Schedule paras again, given freed cores, and reasons for freeing.
-			let freed = freed_concluded.into_iter().map(|c| (c, FreedReason::Concluded))
+			let freed = freed_disputed.into_iter()
+				.chain(freed_concluded.into_iter().map(|c| (c, FreedReason::Concluded)))
 				.chain(freed_timeout.into_iter().map(|c| (c, FreedReason::TimedOut)));
 
 			<scheduler::Module<T>>::clear();
@@ -139,9 +199,14 @@ decl_module! {
 				<frame_system::Pallet<T>>::block_number(),
 			);
 
-			let backed_candidates = limit_backed_candidates::<T>(backed_candidates);
+			let mut backed_candidates = limit_backed_candidates::<T>(backed_candidates);
 			let backed_candidates_len = backed_candidates.len() as Weight;
 
+			// Refuse to back any candidates that are disputed or invalid.
+			backed_candidates.retain(|backed_candidate| {
+				!T::DisputesHandler::could_be_invalid(current_session, backed_candidate.candidate.hash())
+			});
+
 			// Process backed candidates according to scheduled cores.
 			let parent_storage_root = parent_header.state_root().clone();
 			let occupied = <inclusion::Module<T>>::process_candidates(
@@ -162,12 +227,60 @@ decl_module! {
 
 			Ok(Some(
 				MINIMAL_INCLUSION_INHERENT_WEIGHT +
-				(backed_candidates_len * BACKED_CANDIDATE_WEIGHT)
+				(backed_candidates_len * BACKED_CANDIDATE_WEIGHT) +
+				disputes_weight +
+				backing_misbehavior_weight
 			).into())
 		}
 	}
 }
 
+impl<T: Config> Module<T> {
+	/// Check the proofs of backing misbehavior against the validators of the session they were
+	/// signed in and punish the misbehaving validators.
+	///
+	/// Proofs which are invalid or don't belong to the current session are ignored, so that a
+	/// single bad proof can't prevent the inherent from being included.
+	fn process_backing_misbehavior(
+		current_session: SessionIndex,
+		proofs: Vec<BackingMisbehaviorProof>,
+	) {
+		for proof in proofs {
+			if proof.session != current_session {
+				continue
+			}
+
+			let is_valid = <session_info::Module<T>>::session_info(proof.session)
+				.and_then(|info| info.validators.get(proof.validator_index.0 as usize).cloned())
+				.map_or(false, |validator_public| proof.check(&validator_public).is_ok());
+
+			if !is_valid {
+				continue
+			}
+
+			T::PunishBackingMisbehavior::punish_backing_misbehavior(
+				proof.session,
+				proof.validator_index,
+			);
+		}
+	}
+}
+
+/// The weight assumed for importing the given dispute statement sets.
+fn dispute_statements_weight(disputes: &[DisputeStatementSet]) -> Weight {
+	disputes.iter()
+		.map(|set| {
+			DISPUTE_STATEMENT_SET_WEIGHT
+				+ set.statements.len() as Weight * DISPUTE_PER_STATEMENT_WEIGHT
+		})
+		.sum()
+}
+
+/// The weight assumed for checking the given proofs of backing misbehavior.
+fn backing_misbehavior_weight(proofs: &[BackingMisbehaviorProof]) -> Weight {
+	proofs.len() as Weight * BACKING_MISBEHAVIOR_PROOF_WEIGHT
+}
+
 /// Limit the number of backed candidates processed in order to stay within block weight limits.
 ///
 /// Use a configured assumption about the weight required to process a backed candidate and the
@@ -250,6 +363,7 @@ impl<T: Config> ProvideInherent for Module<T> {
 					bitfields: Vec::new(),
 					backed_candidates: Vec::new(),
 					disputes: Vec::new(),
+					backing_misbehavior: Vec::new(),
 					parent_header: inherent_data.parent_header,
 				}
 			}
@@ -327,6 +441,156 @@ mod tests {
 		}
 	}
 
+	mod backing_misbehavior {
+		use super::*;
+
+		use crate::initializer::SessionChangeNotification;
+		use crate::mock::{SessionInfo, PUNISH_BACKING_MISBEHAVIOR};
+		use keyring::Sr25519Keyring;
+		use primitives::v1::{
+			BackingMisbehavior, CandidateHash, DisputeStatement, Hash, ValidDisputeStatementKind,
+			ValidatorId, ValidatorSignature,
+		};
+
+		const VALIDATORS: [Sr25519Keyring; 3] = [
+			Sr25519Keyring::Alice,
+			Sr25519Keyring::Bob,
+			Sr25519Keyring::Charlie,
+		];
+
+		fn start_session(session_index: SessionIndex) {
+			let notification = SessionChangeNotification {
+				validators: VALIDATORS.iter().map(|k| ValidatorId::from(k.public())).collect(),
+				session_index,
+				..Default::default()
+			};
+
+			SessionInfo::initializer_on_new_session(&notification);
+		}
+
+		fn sign(
+			validator: usize,
+			statement: DisputeStatement,
+			candidate_hash: CandidateHash,
+			session: SessionIndex,
+		) -> ValidatorSignature {
+			VALIDATORS[validator].sign(&statement.payload_data(candidate_hash, session)).into()
+		}
+
+		fn double_seconded(
+			validator: usize,
+			signer: usize,
+			session: SessionIndex,
+			relay_parent: Hash,
+		) -> BackingMisbehaviorProof {
+			let seconded = DisputeStatement::Valid(ValidDisputeStatementKind::BackingSeconded(relay_parent));
+			let first = CandidateHash(Hash::repeat_byte(1));
+			let second = CandidateHash(Hash::repeat_byte(2));
+
+			BackingMisbehaviorProof {
+				session,
+				relay_parent,
+				validator_index: ValidatorIndex(validator as _),
+				misbehavior: BackingMisbehavior::DoubleSeconded {
+					first: (first, sign(signer, seconded.clone(), first, session)),
+					second: (second, sign(signer, seconded, second, session)),
+				},
+			}
+		}
+
+		#[test]
+		fn valid_proofs_punish_validators() {
+			new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+				start_session(1);
+
+				let relay_parent = Hash::repeat_byte(0xAA);
+				let candidate_hash = CandidateHash(Hash::repeat_byte(3));
+				let seconded_and_valid = BackingMisbehaviorProof {
+					session: 1,
+					relay_parent,
+					validator_index: ValidatorIndex(2),
+					misbehavior: BackingMisbehavior::SecondedAndValid {
+						candidate_hash,
+						seconded: sign(
+							2,
+							DisputeStatement::Valid(ValidDisputeStatementKind::BackingSeconded(relay_parent)),
+							candidate_hash,
+							1,
+						),
+						valid: sign(
+							2,
+							DisputeStatement::Valid(ValidDisputeStatementKind::BackingValid(relay_parent)),
+							candidate_hash,
+							1,
+						),
+					},
+				};
+
+				Module::<Test>::process_backing_misbehavior(1, vec![
+					double_seconded(0, 0, 1, relay_parent),
+					seconded_and_valid,
+				]);
+
+				PUNISH_BACKING_MISBEHAVIOR.with(|r| assert_eq!(
+					r.borrow().clone(),
+					vec![(1, ValidatorIndex(0)), (1, ValidatorIndex(2))],
+				));
+			});
+		}
+
+		#[test]
+		fn invalid_proofs_are_ignored() {
+			new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+				start_session(1);
+
+				let relay_parent = Hash::repeat_byte(0xAA);
+
+				// Seconding the same candidate twice is not misbehavior.
+				let mut same_candidate = double_seconded(0, 0, 1, relay_parent);
+				if let BackingMisbehavior::DoubleSeconded { ref first, ref mut second } = same_candidate.misbehavior {
+					*second = first.clone();
+				}
+
+				Module::<Test>::process_backing_misbehavior(1, vec![
+					// Signed by a different validator than the one accused.
+					double_seconded(0, 1, 1, relay_parent),
+					// Unknown validator.
+					double_seconded(5, 0, 1, relay_parent),
+					same_candidate,
+					double_seconded(1, 1, 1, relay_parent),
+				]);
+
+				PUNISH_BACKING_MISBEHAVIOR.with(|r| assert_eq!(
+					r.borrow().clone(),
+					vec![(1, ValidatorIndex(1))],
+				));
+			});
+		}
+
+		#[test]
+		fn proofs_from_other_sessions_are_ignored() {
+			new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+				start_session(1);
+				start_session(2);
+
+				let relay_parent = Hash::repeat_byte(0xAA);
+
+				Module::<Test>::process_backing_misbehavior(2, vec![
+					// Valid, but from a past session.
+					double_seconded(0, 0, 1, relay_parent),
+					// Unknown session.
+					double_seconded(1, 1, 3, relay_parent),
+					double_seconded(2, 2, 2, relay_parent),
+				]);
+
+				PUNISH_BACKING_MISBEHAVIOR.with(|r| assert_eq!(
+					r.borrow().clone(),
+					vec![(2, ValidatorIndex(2))],
+				));
+			});
+		}
+	}
+
 	mod paras_inherent_weight {
 		use super::*;
 
@@ -376,6 +640,7 @@ mod tests {
 					bitfields: signed_bitfields,
 					backed_candidates,
 					disputes: Vec::new(),
+					backing_misbehavior: Vec::new(),
 					parent_header: default_header(),
 				})
 					.dispatch_bypass_filter(None.into()).unwrap_err().post_info;
@@ -421,6 +686,7 @@ mod tests {
 					bitfields: signed_bitfields,
 					backed_candidates,
 					disputes: Vec::new(),
+					backing_misbehavior: Vec::new(),
 					parent_header: header,
 				})
 					.dispatch_bypass_filter(None.into()).unwrap();
@@ -25,9 +25,13 @@ use primitives::v1::{
 	Id as ParaId, OccupiedCoreAssumption, SessionIndex, ValidationCode,
 	CommittedCandidateReceipt, ScheduledCore, OccupiedCore, CoreOccupied, CoreIndex,
 	GroupIndex, CandidateEvent, PersistedValidationData, SessionInfo,
-	InboundDownwardMessage, InboundHrmpMessage, AuthorityDiscoveryId, Hash
+	InboundDownwardMessage, InboundHrmpMessage, AuthorityDiscoveryId, Hash, CandidateHash,
+	DisputeState, PvfCheckStatement, ValidatorSignature,
+};
+use crate::{
+	initializer, inclusion, scheduler, configuration, paras, session_info, dmp, hrmp, shared,
+	disputes,
 };
-use crate::{initializer, inclusion, scheduler, configuration, paras, session_info, dmp, hrmp, shared};
 
 
 /// Implementation for the `validators` function of the runtime API.
@@ -337,3 +341,23 @@ pub fn validation_code_by_hash<T: paras::Config>(
 ) -> Option<ValidationCode> {
 	<paras::Module<T>>::code_by_hash(hash)
 }
+
+/// Implementation for the `disputes` function of the runtime API.
+pub fn get_session_disputes<T: disputes::Config>()
+	-> Vec<(SessionIndex, CandidateHash, DisputeState<T::BlockNumber>)>
+{
+	<disputes::Module<T>>::disputes()
+}
+
+/// Implementation for the `pvfs_require_precheck` function of the runtime API.
+pub fn pvfs_require_precheck<T: paras::Config>() -> Vec<Hash> {
+	<paras::Module<T>>::pvfs_require_precheck()
+}
+
+/// Implementation for the `submit_pvf_check_statement` function of the runtime API.
+pub fn submit_pvf_check_statement<T: paras::Config>(
+	stmt: PvfCheckStatement,
+	signature: ValidatorSignature,
+) {
+	<paras::Module<T>>::submit_pvf_check_statement(stmt, signature)
+}
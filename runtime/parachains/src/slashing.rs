@@ -0,0 +1,165 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An implementation of the `PunishBackingMisbehavior` trait used by `paras_inherent` that
+//! employs `pallet-offences` to report validators who issued conflicting statements during
+//! candidate backing. The offences are then handled by the runtime's offence handler, which in
+//! runtimes with staking results in the misbehaving validators being slashed.
+
+use sp_std::prelude::*;
+use primitives::v1::{SessionIndex, ValidatorIndex};
+use sp_runtime::{Perbill, traits::Convert};
+use sp_staking::offence::{Kind, Offence, ReportOffence};
+use crate::shared;
+
+const LOG_TARGET: &str = "runtime::parachains::slashing";
+
+/// An offence committed by a validator which issued conflicting statements during candidate
+/// backing.
+#[derive(sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct BackingMisbehaviorOffence<Offender> {
+	/// The session index in which the offence was committed.
+	pub session_index: SessionIndex,
+	/// The size of the validator set in that session.
+	pub validator_set_count: u32,
+	/// The misbehaving validator.
+	pub offender: Offender,
+}
+
+impl<Offender: Clone> Offence<Offender> for BackingMisbehaviorOffence<Offender> {
+	const ID: Kind = *b"para:backing-eqv";
+	// A validator is reported at most once per session, regardless of how many conflicting
+	// statements it issued.
+	type TimeSlot = SessionIndex;
+
+	fn offenders(&self) -> Vec<Offender> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.session_index
+	}
+
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		// The same curve as for GRANDPA and BABE equivocations: `min(1, 3k / n)^2`.
+		let x = Perbill::from_rational(3 * offenders_count, validator_set_count);
+		x.square()
+	}
+}
+
+/// Reports validators who misbehaved during candidate backing to `pallet-offences`.
+pub struct SlashValidatorsForBackingMisbehavior<C>(sp_std::marker::PhantomData<C>);
+
+impl<C> crate::paras_inherent::PunishBackingMisbehavior for SlashValidatorsForBackingMisbehavior<C>
+	where C: pallet_offences::Config<IdentificationTuple = pallet_session::historical::IdentificationTuple<C>>
+		+ pallet_session::historical::Config
+		+ shared::Config,
+{
+	fn punish_backing_misbehavior(session: SessionIndex, validator: ValidatorIndex) {
+		// The session module only knows the validators of the current session, so misbehavior
+		// from any other session can't be attributed to an account.
+		if <pallet_session::Module<C>>::current_index() != session
+			|| <shared::Module<C>>::session_index() != session
+		{
+			log::debug!(
+				target: LOG_TARGET,
+				"Ignoring backing misbehavior of validator {:?} in past session {}",
+				validator,
+				session,
+			);
+			return;
+		}
+
+		let validator_set_count = <shared::Module<C>>::active_validator_keys().len() as u32;
+		let offender = match identify_validator::<C>(validator) {
+			Some(offender) => offender,
+			None => return,
+		};
+
+		let offence = BackingMisbehaviorOffence {
+			session_index: session,
+			validator_set_count,
+			offender,
+		};
+
+		if let Err(e) = <pallet_offences::Pallet<C>>::report_offence(Vec::new(), offence) {
+			// Duplicate reports are expected when the same misbehavior is submitted in
+			// several blocks.
+			log::debug!(
+				target: LOG_TARGET,
+				"Failed to report backing misbehavior of validator {:?}: {:?}",
+				validator,
+				e,
+			);
+		}
+	}
+}
+
+// Map a validator index in the current session to the full identification of the
+// validator's account.
+fn identify_validator<C>(validator: ValidatorIndex)
+	-> Option<pallet_session::historical::IdentificationTuple<C>>
+	where C: pallet_session::historical::Config + shared::Config,
+{
+	let validator_indirection = <shared::Module<C>>::active_validator_indices();
+	let validators = <pallet_session::Module<C>>::validators();
+
+	let validator_id = validator_indirection.get(validator.0 as usize)
+		.and_then(|i| validators.get(i.0 as usize))
+		.cloned()?;
+	let full_identification = C::FullIdentificationOf::convert(validator_id.clone())?;
+
+	Some((validator_id, full_identification))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn slash_fraction_grows_quadratically_and_saturates() {
+		type O = BackingMisbehaviorOffence<u64>;
+
+		assert_eq!(O::slash_fraction(0, 100), Perbill::zero());
+		assert_eq!(O::slash_fraction(1, 300), Perbill::from_rational(1u32, 10_000));
+		assert_eq!(O::slash_fraction(10, 300), Perbill::from_percent(1));
+		assert_eq!(O::slash_fraction(100, 300), Perbill::one());
+		assert_eq!(O::slash_fraction(300, 300), Perbill::one());
+	}
+
+	#[test]
+	fn offence_is_keyed_by_session() {
+		let offence = BackingMisbehaviorOffence {
+			session_index: 5,
+			validator_set_count: 10,
+			offender: 42u64,
+		};
+
+		assert_eq!(offence.offenders(), vec![42]);
+		assert_eq!(offence.time_slot(), 5);
+		assert_eq!(offence.session_index(), 5);
+		assert_eq!(offence.validator_set_count(), 10);
+	}
+}
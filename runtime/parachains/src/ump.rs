@@ -20,7 +20,10 @@ use crate::{
 };
 use sp_std::{prelude::*, fmt, marker::PhantomData, convert::TryFrom};
 use sp_std::collections::{btree_map::BTreeMap, vec_deque::VecDeque};
-use frame_support::{decl_module, decl_event, decl_storage, StorageMap, StorageValue, weights::Weight, traits::Get};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, StorageMap, StorageValue,
+	dispatch::DispatchResultWithPostInfo, weights::Weight, traits::{Get, EnsureOrigin},
+};
 use primitives::v1::{Id as ParaId, UpwardMessage};
 use xcm::v0::Outcome;
 
@@ -59,6 +62,9 @@ impl UmpSink for () {
 /// if the message content is unique.
 pub type MessageId = [u8; 32];
 
+/// Index used to identify overweight messages.
+pub type OverweightIndex = u64;
+
 /// A specific implementation of a UmpSink where messages are in the XCM format
 /// and will be forwarded to the XCM Executor.
 pub struct XcmSink<XcmExecutor, Config>(PhantomData<(XcmExecutor, Config)>);
@@ -165,6 +171,9 @@ pub trait Config: frame_system::Config + configuration::Config {
 	///
 	/// Generally you'll want this to be a bit more - 150 or 200 would be good values.
 	type FirstMessageFactorPercent: Get<Weight>;
+
+	/// Origin which is allowed to execute overweight messages.
+	type ExecuteOverweightOrigin: EnsureOrigin<Self::Origin>;
 }
 
 decl_storage! {
@@ -202,6 +211,14 @@ decl_storage! {
 		/// Invariant:
 		/// - If `Some(para)`, then `para` must be present in `NeedsDispatch`.
 		NextDispatchRoundStartWith: Option<ParaId>;
+		/// The messages that exceeded the weight any single message can be given during the
+		/// dispatch stage.
+		///
+		/// These messages stay there until manually dispatched by `service_overweight`.
+		Overweight: map hasher(twox_64_concat) OverweightIndex => Option<(ParaId, UpwardMessage)>;
+		/// The number of overweight messages ever recorded in `Overweight` (and thus the lowest free
+		/// index).
+		OverweightCount: OverweightIndex;
 	}
 }
 
@@ -222,14 +239,66 @@ decl_event! {
 		/// Some downward messages have been received and will be processed.
 		/// \[ para, count, size \]
 		UpwardMessagesReceived(ParaId, u32, u32),
+		/// The weight budget was exceeded for an individual upward message.
+		///
+		/// This message can be later dispatched manually using `service_overweight` dispatchable
+		/// using the assigned `overweight_index`.
+		///
+		/// \[ para, id, overweight_index, required \]
+		OverweightEnqueued(ParaId, MessageId, OverweightIndex, Weight),
+		/// Upward message from the overweight queue was executed with the given actual weight
+		/// used.
+		///
+		/// \[ overweight_index, used \]
+		OverweightServiced(OverweightIndex, Weight),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The message index given is unknown.
+		UnknownMessageIndex,
+		/// The amount of weight given is possibly not enough for executing the message.
+		WeightOverLimit,
 	}
 }
 
 decl_module! {
 	/// The UMP module.
 	pub struct Module<T: Config> for enum Call where origin: <T as frame_system::Config>::Origin {
+		type Error = Error<T>;
+
 		/// Deposit one of this module's events by using the default implementation.
 		fn deposit_event() = default;
+
+		/// Service a single overweight upward message.
+		///
+		/// - `origin`: Must pass `ExecuteOverweightOrigin`.
+		/// - `index`: The index of the overweight message to service.
+		/// - `weight_limit`: The amount of weight that message execution may take.
+		///
+		/// Errors:
+		/// - `UnknownMessageIndex`: Message of `index` is unknown.
+		/// - `WeightOverLimit`: Message execution may use greater than `weight_limit`.
+		///
+		/// Events:
+		/// - `OverweightServiced`: On success.
+		#[weight = weight_limit.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn service_overweight(
+			origin,
+			index: OverweightIndex,
+			weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			T::ExecuteOverweightOrigin::ensure_origin(origin)?;
+
+			let (sender, data) = <Self as Store>::Overweight::get(index)
+				.ok_or(Error::<T>::UnknownMessageIndex)?;
+			let used = T::UmpSink::process_upward_message(sender, &data[..], weight_limit)
+				.map_err(|_| Error::<T>::WeightOverLimit)?;
+			<Self as Store>::Overweight::remove(index);
+			Self::deposit_event(Event::OverweightServiced(index, used));
+			Ok(Some(used.saturating_add(T::DbWeight::get().reads_writes(1, 1))).into())
+		}
 	}
 }
 
@@ -387,21 +456,37 @@ impl<T: Config> Module<T> {
 				config.ump_service_total_weight - weight_used
 			};
 
-			// dequeue the next message from the queue of the dispatchee
-			let (upward_message, became_empty) = queue_cache.dequeue::<T>(dispatchee);
-			if let Some(upward_message) = upward_message {
+			// attempt to process the next message from the queue of the dispatchee; if not beyond
+			// our remaining weight limit, then consume it.
+			let maybe_next = queue_cache.peek_front::<T>(dispatchee);
+			if let Some(upward_message) = maybe_next {
 				match T::UmpSink::process_upward_message(dispatchee, &upward_message[..], max_weight) {
-					Ok(used) => weight_used += used,
+					Ok(used) => {
+						weight_used += used;
+						let _ = queue_cache.consume_front::<T>(dispatchee);
+					},
+					Err((id, required)) if required > config.ump_max_individual_weight => {
+						// the message requires more weight than any single message may get. It
+						// would stall the queue forever, so we move it to the overweight queue and
+						// carry on with the messages after it.
+						let upward_message = queue_cache.consume_front::<T>(dispatchee).expect(
+							"`consume_front` returns the same message as `peek_front`;\
+							we are in this branch only if `peek_front` returned `Some`;\
+							thus `upward_message` cannot be `None`; qed",
+						);
+						let index = Self::stash_overweight(dispatchee, upward_message);
+						Self::deposit_event(Event::OverweightEnqueued(dispatchee, id, index, required));
+					},
 					Err((id, required)) => {
-						// we process messages in order and don't drop them if we run out of weight, so need to break
-						// here.
+						// we process messages in order and don't drop them if we run out of weight,
+						// so need to break here without calling `consume_front`.
 						Self::deposit_event(Event::WeightExhausted(id, max_weight, required));
 						break
 					},
 				}
 			}
 
-			if became_empty {
+			if queue_cache.is_empty::<T>(dispatchee) {
 				// the queue is empty now - this para doesn't need attention anymore.
 				cursor.remove();
 			} else {
@@ -414,6 +499,19 @@ impl<T: Config> Module<T> {
 
 		weight_used
 	}
+
+	/// Puts a given upward message into the list of overweight messages allowing it to be executed
+	/// later.
+	fn stash_overweight(sender: ParaId, upward_message: UpwardMessage) -> OverweightIndex {
+		let index = <Self as Store>::OverweightCount::mutate(|count| {
+			let index = *count;
+			*count += 1;
+			index
+		});
+
+		<Self as Store>::Overweight::insert(index, (sender, upward_message));
+		index
+	}
 }
 
 /// To avoid constant fetching, deserializing and serialization the queues are cached.
@@ -447,14 +545,8 @@ impl QueueCache {
 		Self(BTreeMap::new())
 	}
 
-	/// Dequeues one item from the upward message queue of the given para.
-	///
-	/// Returns `(upward_message, became_empty)`, where
-	///
-	/// - `upward_message` a dequeued message or `None` if the queue _was_ empty.
-	/// - `became_empty` is true if the queue _became_ empty.
-	fn dequeue<T: Config>(&mut self, para: ParaId) -> (Option<UpwardMessage>, bool) {
-		let cache_entry = self.0.entry(para).or_insert_with(|| {
+	fn ensure_cached<T: Config>(&mut self, para: ParaId) -> &mut QueueCacheEntry {
+		self.0.entry(para).or_insert_with(|| {
 			let queue = <Module<T> as Store>::RelayDispatchQueues::get(&para);
 			let (count, total_size) = <Module<T> as Store>::RelayDispatchQueueSize::get(&para);
 			QueueCacheEntry {
@@ -462,15 +554,36 @@ impl QueueCache {
 				count,
 				total_size,
 			}
-		});
+		})
+	}
+
+	/// Returns the message at the front of `para`'s queue, or `None` if the queue is empty.
+	///
+	/// Does not mutate the queue.
+	fn peek_front<T: Config>(&mut self, para: ParaId) -> Option<&UpwardMessage> {
+		self.ensure_cached::<T>(para).queue.front()
+	}
+
+	/// Dequeues one item from the upward message queue of the given para.
+	///
+	/// Returns the dequeued message or `None` if the queue _was_ empty.
+	fn consume_front<T: Config>(&mut self, para: ParaId) -> Option<UpwardMessage> {
+		let cache_entry = self.ensure_cached::<T>(para);
 		let upward_message = cache_entry.queue.pop_front();
 		if let Some(ref msg) = upward_message {
 			cache_entry.count -= 1;
 			cache_entry.total_size -= msg.len() as u32;
 		}
+		upward_message
+	}
 
-		let became_empty = cache_entry.queue.is_empty();
-		(upward_message, became_empty)
+	/// Returns if the queue for the given para is empty.
+	///
+	/// That is, if this returns `true` then the next call to [`peek_front`] will return `None`.
+	///
+	/// Does not mutate the queue.
+	fn is_empty<T: Config>(&mut self, para: ParaId) -> bool {
+		self.ensure_cached::<T>(para).queue.is_empty()
 	}
 
 	/// Flushes the updated queues into the storage.
@@ -593,6 +706,9 @@ pub(crate) mod mock_sink {
 	//!
 	//! 2. All messages expected by the probe must be received by the time of dropping it. Unreceived
 	//!    messages will lead to a panic while dropping a probe.
+	//!
+	//! A message can also be expected as overweight. In that case the mock sink reports that it
+	//! requires the mocked weight and doesn't execute it, no matter what weight limit was given.
 
 	use super::{UmpSink, UpwardMessage, ParaId, MessageId};
 	use std::cell::RefCell;
@@ -604,6 +720,7 @@ pub(crate) mod mock_sink {
 		expected_origin: ParaId,
 		expected_msg: UpwardMessage,
 		mock_weight: Weight,
+		overweight: bool,
 	}
 
 	std::thread_local! {
@@ -614,11 +731,12 @@ pub(crate) mod mock_sink {
 	pub struct MockUmpSink;
 	impl UmpSink for MockUmpSink {
 		fn process_upward_message(actual_origin: ParaId, actual_msg: &[u8], _max_weight: Weight) -> Result<Weight, (MessageId, Weight)> {
-			Ok(HOOK.with(|opt_hook| opt_hook.borrow_mut().as_mut().map(|hook| {
+			HOOK.with(|opt_hook| opt_hook.borrow_mut().as_mut().map(|hook| {
 				let UmpExpectation {
 					expected_origin,
 					expected_msg,
 					mock_weight,
+					overweight,
 				} = match hook.pop_front() {
 					Some(expectation) => expectation,
 					None => {
@@ -630,8 +748,12 @@ pub(crate) mod mock_sink {
 				};
 				assert_eq!(expected_origin, actual_origin);
 				assert_eq!(expected_msg, &actual_msg[..]);
-				mock_weight
-			})).unwrap_or(0))
+				if overweight {
+					Err((sp_io::hashing::blake2_256(actual_msg), mock_weight))
+				} else {
+					Ok(mock_weight)
+				}
+			})).unwrap_or(Ok(0))
 		}
 	}
 
@@ -662,16 +784,39 @@ pub(crate) mod mock_sink {
 			expected_msg: UpwardMessage,
 			mock_weight: Weight,
 		) {
+			Self::push_expectation(UmpExpectation {
+				expected_origin,
+				expected_msg,
+				mock_weight,
+				overweight: false,
+			});
+		}
+
+		/// Add an expected message which the sink will refuse to execute, claiming that it
+		/// requires `required_weight`.
+		///
+		/// The enqueued messages are processed in FIFO order.
+		pub fn assert_overweight_msg(
+			&mut self,
+			expected_origin: ParaId,
+			expected_msg: UpwardMessage,
+			required_weight: Weight,
+		) {
+			Self::push_expectation(UmpExpectation {
+				expected_origin,
+				expected_msg,
+				mock_weight: required_weight,
+				overweight: true,
+			});
+		}
+
+		fn push_expectation(expectation: UmpExpectation) {
 			HOOK.with(|opt_hook| {
 				opt_hook
 					.borrow_mut()
 					.as_mut()
 					.unwrap()
-					.push_back(UmpExpectation {
-						expected_origin,
-						expected_msg,
-						mock_weight,
-					})
+					.push_back(expectation)
 			});
 		}
 	}
@@ -708,8 +853,9 @@ pub(crate) mod mock_sink {
 mod tests {
 	use super::*;
 	use super::mock_sink::Probe;
-	use crate::mock::{Configuration, Ump, new_test_ext, MockGenesisConfig};
-	use frame_support::IterableStorageMap;
+	use crate::mock::{Configuration, Ump, Origin, Test, new_test_ext, MockGenesisConfig};
+	use frame_support::{IterableStorageMap, assert_ok, assert_noop};
+	use sp_runtime::DispatchError;
 	use std::collections::HashSet;
 
 	struct GenesisConfigBuilder {
@@ -718,6 +864,7 @@ mod tests {
 		max_upward_queue_count: u32,
 		max_upward_queue_size: u32,
 		ump_service_total_weight: Weight,
+		ump_max_individual_weight: Weight,
 	}
 
 	impl Default for GenesisConfigBuilder {
@@ -728,6 +875,7 @@ mod tests {
 				max_upward_queue_count: 4,
 				max_upward_queue_size: 64,
 				ump_service_total_weight: 1000,
+				ump_max_individual_weight: 1000,
 			}
 		}
 	}
@@ -743,6 +891,7 @@ mod tests {
 			config.max_upward_queue_size = self.max_upward_queue_size;
 			config.ump_service_total_weight =
 				self.ump_service_total_weight;
+			config.ump_max_individual_weight = self.ump_max_individual_weight;
 			genesis
 		}
 	}
@@ -957,6 +1106,94 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn overweight_queue_works() {
+		let a = ParaId::from(2021);
+		let b = ParaId::from(2022);
+
+		let a_msg_1 = vec![1, 2, 3];
+		let a_msg_2 = vec![3, 2, 1];
+		let b_msg_1 = vec![4, 5, 6];
+		let b_msg_2 = vec![6, 5, 4];
+
+		new_test_ext(
+			GenesisConfigBuilder {
+				ump_service_total_weight: 500,
+				ump_max_individual_weight: 500,
+				..Default::default()
+			}
+			.build(),
+		)
+		.execute_with(|| {
+			queue_upward_msg(a, a_msg_1.clone());
+			queue_upward_msg(a, a_msg_2.clone());
+			queue_upward_msg(b, b_msg_1.clone());
+			queue_upward_msg(b, b_msg_2.clone());
+
+			// the first message requires more than the maximum individual weight and gets stashed.
+			// The next one requires more than remains but no more than the maximum, so it stays in
+			// the queue and blocks it until the next round.
+			{
+				let mut probe = Probe::new();
+
+				probe.assert_overweight_msg(a, a_msg_1.clone(), 600);
+				probe.assert_msg(b, b_msg_1.clone(), 300);
+				probe.assert_overweight_msg(a, a_msg_2.clone(), 300);
+				Ump::process_pending_upward_messages();
+				assert_storage_consistency_exhaustive();
+
+				drop(probe);
+			}
+
+			assert_eq!(<Ump as Store>::OverweightCount::get(), 1);
+			assert_eq!(<Ump as Store>::Overweight::get(0), Some((a, a_msg_1.clone())));
+
+			// the message left in the queue is not overweight and gets executed normally. An
+			// overweight message is stashed even if it isn't the first one to be dispatched.
+			{
+				let mut probe = Probe::new();
+
+				probe.assert_msg(a, a_msg_2.clone(), 300);
+				probe.assert_overweight_msg(b, b_msg_2.clone(), 700);
+				Ump::process_pending_upward_messages();
+				assert_storage_consistency_exhaustive();
+
+				drop(probe);
+			}
+
+			assert_eq!(<Ump as Store>::OverweightCount::get(), 2);
+			assert_eq!(<Ump as Store>::Overweight::get(1), Some((b, b_msg_2.clone())));
+			assert!(<Ump as Store>::RelayDispatchQueues::get(a).is_empty());
+			assert!(<Ump as Store>::RelayDispatchQueues::get(b).is_empty());
+
+			// servicing the overweight message requires a privileged origin and enough weight.
+			assert_noop!(
+				Ump::service_overweight(Origin::signed(1), 0, 1000),
+				DispatchError::BadOrigin,
+			);
+			{
+				let mut probe = Probe::new();
+
+				probe.assert_overweight_msg(a, a_msg_1.clone(), 600);
+				assert_noop!(
+					Ump::service_overweight(Origin::root(), 0, 500),
+					Error::<Test>::WeightOverLimit,
+				);
+
+				probe.assert_msg(a, a_msg_1.clone(), 600);
+				assert_ok!(Ump::service_overweight(Origin::root(), 0, 1000));
+
+				drop(probe);
+			}
+
+			assert_eq!(<Ump as Store>::Overweight::get(0), None);
+			assert_noop!(
+				Ump::service_overweight(Origin::root(), 0, 1000),
+				Error::<Test>::UnknownMessageIndex,
+			);
+		});
+	}
+
 	#[test]
 	fn verify_relay_dispatch_queue_size_is_externally_accessible() {
 		// Make sure that the relay dispatch queue size storage entry is accessible via well known
@@ -37,7 +37,8 @@ use primitives::v1::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
 	CoreState, GroupRotationInfo, Hash, Id, Moment, Nonce, OccupiedCoreAssumption,
 	PersistedValidationData, Signature, ValidationCode, ValidatorId, ValidatorIndex,
-	InboundDownwardMessage, InboundHrmpMessage, SessionInfo,
+	InboundDownwardMessage, InboundHrmpMessage, SessionInfo, CandidateHash,
+	DisputeState, PvfCheckStatement, ValidatorSignature,
 };
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys, ApplyExtrinsicResult,
@@ -1214,6 +1215,16 @@ sp_api::impl_runtime_apis! {
 		fn validation_code_by_hash(_hash: Hash) -> Option<ValidationCode> {
 			None
 		}
+
+		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
+			Vec::new()
+		}
+
+		fn pvfs_require_precheck() -> Vec<Hash> {
+			Vec::new()
+		}
+
+		fn submit_pvf_check_statement(_: PvfCheckStatement, _: ValidatorSignature) {}
 	}
 
 	impl beefy_primitives::BeefyApi<Block, BeefyId> for Runtime {
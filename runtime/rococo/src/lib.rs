@@ -29,7 +29,8 @@ use primitives::v1::{
 	GroupRotationInfo, CoreState, Id, ValidationCode, CandidateEvent,
 	ValidatorId, ValidatorIndex, CommittedCandidateReceipt, OccupiedCoreAssumption,
 	PersistedValidationData, InboundDownwardMessage, InboundHrmpMessage,
-	SessionInfo as SessionInfoData,
+	SessionInfo as SessionInfoData, CandidateHash, DisputeState, PvfCheckStatement,
+	ValidatorSignature,
 };
 use runtime_common::{
 	mmr as mmr_common,
@@ -76,6 +77,7 @@ use runtime_parachains::inclusion as parachains_inclusion;
 use runtime_parachains::paras_inherent as parachains_paras_inherent;
 use runtime_parachains::initializer as parachains_initializer;
 use runtime_parachains::session_info as parachains_session_info;
+use runtime_parachains::disputes as parachains_disputes;
 use runtime_parachains::paras as parachains_paras;
 use runtime_parachains::dmp as parachains_dmp;
 use runtime_parachains::ump as parachains_ump;
@@ -87,11 +89,11 @@ pub use pallet_balances::Call as BalancesCall;
 use polkadot_parachain::primitives::Id as ParaId;
 
 use xcm::v0::{Xcm, MultiLocation, NetworkId, BodyId};
-use xcm_executor::XcmExecutor;
+use xcm_executor::{XcmExecutor, traits::XcmWeightInfo};
 use xcm_builder::{
 	AccountId32Aliases, ChildParachainConvertsVia, SovereignSignedViaLocation,
 	CurrencyAdapter as XcmCurrencyAdapter, ChildParachainAsNative, SignedAccountId32AsNative,
-	ChildSystemParachainAsSuperuser, LocationInverter, IsConcrete, FixedWeightBounds,
+	ChildSystemParachainAsSuperuser, LocationInverter, IsConcrete, WeightInfoBounds,
 	BackingToPlurality, SignedToAccountId32, UsingComponents,
 };
 use constants::{time::*, currency::*, fee::*, size::*};
@@ -165,7 +167,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
-	GrandpaStoragePrefixMigration,
+	(GrandpaStoragePrefixMigration, ParachainsConfigurationMigration),
 >;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -214,7 +216,7 @@ construct_runtime! {
 		Inclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>},
 		ParasInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent},
 		Scheduler: parachains_scheduler::{Pallet, Call, Storage},
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>},
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>, ValidateUnsigned},
 		Initializer: parachains_initializer::{Pallet, Call, Storage},
 		Dmp: parachains_dmp::{Pallet, Call, Storage},
 		Ump: parachains_ump::{Pallet, Call, Storage, Event},
@@ -245,6 +247,9 @@ construct_runtime! {
 		// Validator Manager pallet.
 		ValidatorManager: validator_manager::{Pallet, Call, Storage, Event<T>},
 
+		// Parachains disputes.
+		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 43,
+
 		// A "council"
 		Collective: pallet_collective::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 80,
 		Membership: pallet_membership::{Pallet, Call, Storage, Event<T>, Config<T>} = 81,
@@ -253,7 +258,14 @@ construct_runtime! {
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 91,
 
 		// Pallet for sending XCM.
-		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>} = 99,
+		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 99,
+	}
+}
+
+pub struct ParachainsConfigurationMigration;
+impl frame_support::traits::OnRuntimeUpgrade for ParachainsConfigurationMigration {
+	fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		parachains_configuration::migration::migrate_to_pvf_checking::<Runtime>()
 	}
 }
 
@@ -579,6 +591,7 @@ impl runtime_parachains::inclusion::RewardValidators for RewardValidators {
 
 impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
+	type DisputesHandler = ParasDisputes;
 	type RewardValidators = RewardValidators;
 }
 
@@ -622,13 +635,47 @@ type LocalOriginConverter = (
 
 parameter_types! {
 	pub const BaseXcmWeight: Weight = 100_000;
-}
+	/// The most assets the holding register is weighed as containing, which is how a wildcard is weighed.
+	pub const MaxAssetsInHolding: u32 = 8;
+}
+
+/// The weight of each XCM instruction and order. They haven't been benchmarked for this runtime yet, so
+/// each of them costs `BaseXcmWeight`, for each asset it carries.
+pub struct XcmWeight;
+impl XcmWeightInfo for XcmWeight {
+	fn withdraw_asset() -> Weight { BaseXcmWeight::get() }
+	fn reserve_asset_deposit() -> Weight { BaseXcmWeight::get() }
+	fn teleport_asset() -> Weight { BaseXcmWeight::get() }
+	fn query_response() -> Weight { BaseXcmWeight::get() }
+	fn transfer_asset() -> Weight { BaseXcmWeight::get() }
+	fn transfer_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn transact() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_new_channel_open_request() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_accepted() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_closing() -> Weight { BaseXcmWeight::get() }
+	fn relayed_from() -> Weight { BaseXcmWeight::get() }
+	fn claim_asset() -> Weight { BaseXcmWeight::get() }
+	fn subscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn unsubscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn order_null() -> Weight { BaseXcmWeight::get() }
+	fn deposit_asset() -> Weight { BaseXcmWeight::get() }
+	fn deposit_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn exchange_asset() -> Weight { BaseXcmWeight::get() }
+	fn initiate_reserve_withdraw() -> Weight { BaseXcmWeight::get() }
+	fn initiate_teleport() -> Weight { BaseXcmWeight::get() }
+	fn query_holding() -> Weight { BaseXcmWeight::get() }
+	fn buy_execution() -> Weight { BaseXcmWeight::get() }
+	fn report_outcome() -> Weight { BaseXcmWeight::get() }
+}
+
+/// The weigher of XCM messages.
+pub type XcmWeigher = WeightInfoBounds<XcmWeight, Call, MaxAssetsInHolding>;
 
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
 /// individual routers.
 pub type XcmRouter = (
 	// Only one router so far - use DMP to communicate with child parachains.
-	xcm_sender::ChildParachainRouter<Runtime>,
+	xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
 );
 
 use xcm::v0::{MultiAsset, MultiAsset::AllConcreteFungible, MultiLocation::{Null, X1}, Junction::Parachain};
@@ -659,11 +706,16 @@ parameter_types! {
 		];
 }
 
-use xcm_builder::{TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom};
+use xcm_builder::{
+	TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, AllowKnownQueryResponses,
+	AllowSubscriptionsFrom,
+};
 pub type Barrier = (
 	TakeWeightCredit,
 	AllowTopLevelPaidExecutionFrom<All<MultiLocation>>,
 	AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,	// <- Trusted parachains get free execution
+	AllowKnownQueryResponses<XcmPallet>,	// <- Expected responses to our queries
+	AllowSubscriptionsFrom<All<MultiLocation>>,	// <- Subscriptions for version tracking
 );
 
 pub struct XcmConfig;
@@ -676,9 +728,12 @@ impl xcm_executor::Config for XcmConfig {
 	type IsTeleporter = TrustedTeleporters;
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
 	type Trader = UsingComponents<WeightToFee, RocLocation, AccountId, Balances, ToAuthor<Runtime>>;
-	type ResponseHandler = ();
+	type ResponseHandler = XcmPallet;
+	type AssetTrap = XcmPallet;
+	type AssetClaims = XcmPallet;
+	type SubscriptionService = XcmPallet;
 }
 
 parameter_types! {
@@ -742,6 +797,11 @@ impl frame_support::traits::Contains<(MultiLocation, Xcm<Call>)> for OnlyWithdra
 	}
 }
 
+parameter_types! {
+	pub const MaxVersionNotifyTargets: u32 = 1_000;
+	pub const VersionNotificationsPerBlock: u32 = 10;
+}
+
 impl pallet_xcm::Config for Runtime {
 	type Event = Event;
 	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
@@ -753,11 +813,23 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = All<(MultiLocation, Vec<MultiAsset>)>;
 	type XcmReserveTransferFilter = All<(MultiLocation, Vec<MultiAsset>)>;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Origin = Origin;
+	type Call = Call;
+	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type MaxVersionNotifyTargets = MaxVersionNotifyTargets;
+	type VersionNotificationsPerBlock = VersionNotificationsPerBlock;
 }
 
 impl parachains_session_info::Config for Runtime {}
 
+impl parachains_disputes::Config for Runtime {
+	type Event = Event;
+	type RewardValidators = ();
+	type PunishValidators = ();
+}
+
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
 }
@@ -766,6 +838,7 @@ impl parachains_ump::Config for Runtime {
 	type Event = Event;
 	type UmpSink = crate::parachains_ump::XcmSink<XcmExecutor<XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
+	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
 
 impl parachains_dmp::Config for Runtime {}
@@ -776,7 +849,9 @@ impl parachains_hrmp::Config for Runtime {
 	type Currency = Balances;
 }
 
-impl parachains_paras_inherent::Config for Runtime {}
+impl parachains_paras_inherent::Config for Runtime {
+	type PunishBackingMisbehavior = ();
+}
 
 impl parachains_scheduler::Config for Runtime {}
 
@@ -1145,6 +1220,18 @@ sp_api::impl_runtime_apis! {
 		fn validation_code_by_hash(hash: Hash) -> Option<ValidationCode> {
 			runtime_api_impl::validation_code_by_hash::<Runtime>(hash)
 		}
+
+		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
+			runtime_api_impl::get_session_disputes::<Runtime>()
+		}
+
+		fn pvfs_require_precheck() -> Vec<Hash> {
+			runtime_api_impl::pvfs_require_precheck::<Runtime>()
+		}
+
+		fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) {
+			runtime_api_impl::submit_pvf_check_statement::<Runtime>(stmt, signature)
+		}
 	}
 
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
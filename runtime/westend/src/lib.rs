@@ -28,7 +28,8 @@ use primitives::v1::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
 	CoreState, GroupRotationInfo, Hash, Id as ParaId, Moment, Nonce, OccupiedCoreAssumption,
 	PersistedValidationData, Signature, ValidationCode, ValidatorId, ValidatorIndex,
-	InboundDownwardMessage, InboundHrmpMessage, SessionInfo,
+	InboundDownwardMessage, InboundHrmpMessage, SessionInfo, CandidateHash,
+	DisputeState, PvfCheckStatement, ValidatorSignature,
 };
 use runtime_common::{
 	paras_sudo_wrapper, paras_registrar, xcm_sender, slots, crowdloan, auctions,
@@ -51,16 +52,17 @@ use runtime_parachains::ump as parachains_ump;
 use runtime_parachains::hrmp as parachains_hrmp;
 use runtime_parachains::scheduler as parachains_scheduler;
 use runtime_parachains::reward_points as parachains_reward_points;
+use runtime_parachains::slashing as parachains_slashing;
 use runtime_parachains::runtime_api_impl::v1 as parachains_runtime_api_impl;
 
 use xcm::v0::{MultiLocation::{self, Null, X1}, NetworkId, Xcm, Junction::Parachain};
 use xcm::v0::MultiAsset::{self, AllConcreteFungible};
-use xcm_executor::XcmExecutor;
+use xcm_executor::{XcmExecutor, traits::XcmWeightInfo};
 use xcm_builder::{
 	AccountId32Aliases, ChildParachainConvertsVia, SovereignSignedViaLocation, CurrencyAdapter as XcmCurrencyAdapter,
 	ChildParachainAsNative, SignedAccountId32AsNative, ChildSystemParachainAsSuperuser, LocationInverter, IsConcrete,
-	FixedWeightBounds, TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
-	IsChildSystemParachain, UsingComponents, SignedToAccountId32,
+	WeightInfoBounds, TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
+	AllowKnownQueryResponses, AllowSubscriptionsFrom, IsChildSystemParachain, UsingComponents, SignedToAccountId32,
 };
 
 use sp_runtime::{
@@ -738,6 +740,7 @@ impl parachains_session_info::Config for Runtime {}
 
 impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
+	type DisputesHandler = ();
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
 }
 
@@ -754,6 +757,7 @@ impl parachains_ump::Config for Runtime {
 	type Event = Event;
 	type UmpSink = crate::parachains_ump::XcmSink<XcmExecutor<XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
+	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
 
 impl parachains_dmp::Config for Runtime {}
@@ -764,7 +768,9 @@ impl parachains_hrmp::Config for Runtime {
 	type Currency = Balances;
 }
 
-impl parachains_paras_inherent::Config for Runtime {}
+impl parachains_paras_inherent::Config for Runtime {
+	type PunishBackingMisbehavior = parachains_slashing::SlashValidatorsForBackingMisbehavior<Runtime>;
+}
 
 impl parachains_scheduler::Config for Runtime {}
 
@@ -881,13 +887,47 @@ type LocalOriginConverter = (
 
 parameter_types! {
 	pub const BaseXcmWeight: Weight = 10_000_000;
-}
+	/// The most assets the holding register is weighed as containing, which is how a wildcard is weighed.
+	pub const MaxAssetsInHolding: u32 = 8;
+}
+
+/// The weight of each XCM instruction and order. They haven't been benchmarked with `pallet_xcm_benchmarks` for this runtime yet, so
+/// each of them costs `BaseXcmWeight`, for each asset it carries.
+pub struct XcmWeight;
+impl XcmWeightInfo for XcmWeight {
+	fn withdraw_asset() -> Weight { BaseXcmWeight::get() }
+	fn reserve_asset_deposit() -> Weight { BaseXcmWeight::get() }
+	fn teleport_asset() -> Weight { BaseXcmWeight::get() }
+	fn query_response() -> Weight { BaseXcmWeight::get() }
+	fn transfer_asset() -> Weight { BaseXcmWeight::get() }
+	fn transfer_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn transact() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_new_channel_open_request() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_accepted() -> Weight { BaseXcmWeight::get() }
+	fn hrmp_channel_closing() -> Weight { BaseXcmWeight::get() }
+	fn relayed_from() -> Weight { BaseXcmWeight::get() }
+	fn claim_asset() -> Weight { BaseXcmWeight::get() }
+	fn subscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn unsubscribe_version() -> Weight { BaseXcmWeight::get() }
+	fn order_null() -> Weight { BaseXcmWeight::get() }
+	fn deposit_asset() -> Weight { BaseXcmWeight::get() }
+	fn deposit_reserve_asset() -> Weight { BaseXcmWeight::get() }
+	fn exchange_asset() -> Weight { BaseXcmWeight::get() }
+	fn initiate_reserve_withdraw() -> Weight { BaseXcmWeight::get() }
+	fn initiate_teleport() -> Weight { BaseXcmWeight::get() }
+	fn query_holding() -> Weight { BaseXcmWeight::get() }
+	fn buy_execution() -> Weight { BaseXcmWeight::get() }
+	fn report_outcome() -> Weight { BaseXcmWeight::get() }
+}
+
+/// The weigher of XCM messages.
+pub type XcmWeigher = WeightInfoBounds<XcmWeight, Call, MaxAssetsInHolding>;
 
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
 /// individual routers.
 pub type XcmRouter = (
 	// Only one router so far - use DMP to communicate with child parachains.
-	xcm_sender::ChildParachainRouter<Runtime>,
+	xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
 );
 
 parameter_types! {
@@ -906,6 +946,10 @@ pub type Barrier = (
 	AllowTopLevelPaidExecutionFrom<All<MultiLocation>>,
 	// Messages coming from system parachains need not pay for execution.
 	AllowUnpaidExecutionFrom<IsChildSystemParachain<ParaId>>,
+	// Expected responses to queries that we have sent are OK.
+	AllowKnownQueryResponses<XcmPallet>,
+	// Subscriptions for version tracking are OK.
+	AllowSubscriptionsFrom<All<MultiLocation>>,
 );
 
 pub struct XcmConfig;
@@ -918,9 +962,12 @@ impl xcm_executor::Config for XcmConfig {
 	type IsTeleporter = TrustedTeleporters;
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
 	type Trader = UsingComponents<WeightToFee, WndLocation, AccountId, Balances, ToAuthor<Runtime>>;
-	type ResponseHandler = ();
+	type ResponseHandler = XcmPallet;
+	type AssetTrap = XcmPallet;
+	type AssetClaims = XcmPallet;
+	type SubscriptionService = XcmPallet;
 }
 
 /// Type to convert an `Origin` type value into a `MultiLocation` value which represents an interior location
@@ -977,6 +1024,11 @@ impl frame_support::traits::Contains<(MultiLocation, Xcm<Call>)> for OnlyWithdra
 	}
 }
 
+parameter_types! {
+	pub const MaxVersionNotifyTargets: u32 = 1_000;
+	pub const VersionNotificationsPerBlock: u32 = 10;
+}
+
 impl pallet_xcm::Config for Runtime {
 	type Event = Event;
 	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
@@ -988,7 +1040,13 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = All<(MultiLocation, Vec<MultiAsset>)>;
 	type XcmReserveTransferFilter = All<(MultiLocation, Vec<MultiAsset>)>;
-	type Weigher = FixedWeightBounds<BaseXcmWeight, Call>;
+	type Weigher = XcmWeigher;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Origin = Origin;
+	type Call = Call;
+	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type MaxVersionNotifyTargets = MaxVersionNotifyTargets;
+	type VersionNotificationsPerBlock = VersionNotificationsPerBlock;
 }
 
 construct_runtime! {
@@ -1053,7 +1111,7 @@ construct_runtime! {
 		ParasInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 44,
 		ParasInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 45,
 		ParasScheduler: parachains_scheduler::{Pallet, Call, Storage} = 46,
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>} = 47,
+		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config<T>, ValidateUnsigned} = 47,
 		ParasInitializer: parachains_initializer::{Pallet, Call, Storage} = 48,
 		ParasDmp: parachains_dmp::{Pallet, Call, Storage} = 49,
 		ParasUmp: parachains_ump::{Pallet, Call, Storage, Event} = 50,
@@ -1068,7 +1126,14 @@ construct_runtime! {
 		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 64,
 
 		// Pallet for sending XCM.
-		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>} = 99,
+		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 99,
+	}
+}
+
+pub struct ParachainsConfigurationMigration;
+impl frame_support::traits::OnRuntimeUpgrade for ParachainsConfigurationMigration {
+	fn on_runtime_upgrade() -> frame_support::weights::Weight {
+		parachains_configuration::migration::migrate_to_pvf_checking::<Runtime>()
 	}
 }
 
@@ -1126,7 +1191,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
-	GrandpaStoragePrefixMigration,
+	(GrandpaStoragePrefixMigration, ParachainsConfigurationMigration),
 >;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -1261,6 +1326,18 @@ sp_api::impl_runtime_apis! {
 		fn validation_code_by_hash(hash: Hash) -> Option<ValidationCode> {
 			parachains_runtime_api_impl::validation_code_by_hash::<Runtime>(hash)
 		}
+
+		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
+			Vec::new()
+		}
+
+		fn pvfs_require_precheck() -> Vec<Hash> {
+			parachains_runtime_api_impl::pvfs_require_precheck::<Runtime>()
+		}
+
+		fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) {
+			parachains_runtime_api_impl::submit_pvf_check_statement::<Runtime>(stmt, signature)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block, BeefyId> for Runtime {
@@ -1437,11 +1514,41 @@ sp_api::impl_runtime_apis! {
 			use pallet_session_benchmarking::Pallet as SessionBench;
 			use pallet_offences_benchmarking::Pallet as OffencesBench;
 			use frame_system_benchmarking::Pallet as SystemBench;
+			use pallet_xcm_benchmarks::Pallet as XcmBench;
 
 			impl pallet_session_benchmarking::Config for Runtime {}
 			impl pallet_offences_benchmarking::Config for Runtime {}
 			impl frame_system_benchmarking::Config for Runtime {}
 
+			impl pallet_xcm_benchmarks::Config for Runtime {
+				type XcmConfig = XcmConfig;
+
+				fn valid_origin() -> MultiLocation {
+					X1(xcm::v0::Junction::AccountId32 { network: NetworkId::Any, id: [1u8; 32] })
+				}
+
+				fn valid_destination() -> MultiLocation {
+					X1(Parachain(1000))
+				}
+
+				fn trusted_reserve() -> Option<MultiLocation> {
+					// We don't trust any reserves.
+					None
+				}
+
+				fn trusted_teleporter() -> MultiLocation {
+					WestendForWestmint::get().1
+				}
+
+				fn worst_case_asset() -> MultiAsset {
+					MultiAsset::ConcreteFungible { id: WndLocation::get(), amount: 100 * ExistentialDeposit::get() }
+				}
+
+				fn transact_call() -> Call {
+					Call::System(frame_system::Call::remark(vec![]))
+				}
+			}
+
 			let whitelist: Vec<TrackedStorageKey> = vec![
 				// Block Number
 				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec().into(),
@@ -1483,6 +1590,7 @@ sp_api::impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_utility, Utility);
 			add_benchmark!(params, batches, pallet_vesting, Vesting);
+			add_benchmark!(params, batches, pallet_xcm_benchmarks, XcmBench::<Runtime>);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -0,0 +1,252 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The benchmarks of each `Xcm` and `Order` variant.
+//!
+//! Instructions are executed as whole messages, paid for with weight credit. Orders are executed on their own
+//! against a holding register which contains `worst_case_asset`. Instructions and orders which the executor
+//! does not handle are still benchmarked, since rejecting them is not free either.
+
+use super::*;
+use sp_std::{prelude::*, boxed::Box};
+use codec::Encode;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::weights::Weight;
+use xcm::v0::{
+	Xcm, Order, Response, OriginKind, ExecuteXcm, Outcome, Error as XcmError, Junction::OnlyChild,
+	MultiAsset::All, MultiLocation::{Null, X1},
+};
+use xcm_executor::{XcmExecutor, Assets, traits::{TransactAsset, WeightTrader, DropAssets}};
+
+type XcmCallOf<T> = <<T as Config>::XcmConfig as xcm_executor::Config>::Call;
+
+/// Give `who` the asset `what`, out of thin air.
+fn fund<T: Config>(who: &MultiLocation, what: &MultiAsset) -> Result<(), &'static str> {
+	<<T::XcmConfig as xcm_executor::Config>::AssetTransactor as TransactAsset>::deposit_asset(what, who)
+		.map_err(|_| "`worst_case_asset` must be depositable")
+}
+
+/// Execute `message` from `origin` with unlimited weight credit. Only a rejection by the barrier is an error;
+/// any other outcome is part of what is being measured.
+fn execute<T: Config>(origin: MultiLocation, message: Xcm<XcmCallOf<T>>) -> Result<(), &'static str> {
+	let outcome = XcmExecutor::<T::XcmConfig>::execute_xcm_in_credit(
+		origin,
+		message,
+		Weight::max_value(),
+		Weight::max_value(),
+	);
+	match outcome {
+		Outcome::Error(XcmError::Barrier) | Outcome::Incomplete(_, XcmError::Barrier) =>
+			Err("the barrier must allow messages paid for with weight credit"),
+		_ => Ok(()),
+	}
+}
+
+/// Execute `order` from `origin` on its own, against `holding`.
+fn execute_order<T: Config>(origin: &MultiLocation, mut holding: Assets, order: Order<XcmCallOf<T>>) {
+	let mut trader = <<T::XcmConfig as xcm_executor::Config>::Trader as WeightTrader>::new();
+	let _ = XcmExecutor::<T::XcmConfig>::execute_effects(origin, &mut holding, order, &mut trader);
+}
+
+benchmarks! {
+	withdraw_asset {
+		let origin = T::valid_origin();
+		let asset = T::worst_case_asset();
+		fund::<T>(&origin, &asset)?;
+		let message = Xcm::WithdrawAsset { assets: vec![asset], effects: vec![] };
+	}: {
+		execute::<T>(origin, message)?;
+	}
+
+	reserve_asset_deposit {
+		let message = Xcm::ReserveAssetDeposit { assets: vec![T::worst_case_asset()], effects: vec![] };
+	}: {
+		execute::<T>(T::trusted_reserve().unwrap_or_else(T::valid_destination), message)?;
+	}
+
+	teleport_asset {
+		let message = Xcm::TeleportAsset { assets: vec![T::worst_case_asset()], effects: vec![] };
+	}: {
+		execute::<T>(T::trusted_teleporter(), message)?;
+	}
+
+	query_response {
+		let message = Xcm::QueryResponse { query_id: 0, response: Response::Assets(vec![]) };
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	transfer_asset {
+		let origin = T::valid_origin();
+		let asset = T::worst_case_asset();
+		fund::<T>(&origin, &asset)?;
+		let message = Xcm::TransferAsset { assets: vec![asset], dest: T::valid_destination() };
+	}: {
+		execute::<T>(origin, message)?;
+	}
+
+	transfer_reserve_asset {
+		let origin = T::valid_origin();
+		let asset = T::worst_case_asset();
+		fund::<T>(&origin, &asset)?;
+		let message = Xcm::TransferReserveAsset {
+			assets: vec![asset],
+			dest: T::valid_destination(),
+			effects: vec![],
+		};
+	}: {
+		execute::<T>(origin, message)?;
+	}
+
+	transact {
+		let message = Xcm::Transact {
+			origin_type: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::max_value(),
+			call: T::transact_call().encode().into(),
+		};
+	}: {
+		execute::<T>(T::valid_origin(), message)?;
+	}
+
+	hrmp_new_channel_open_request {
+		let message = Xcm::HrmpNewChannelOpenRequest { sender: 0, max_message_size: 0, max_capacity: 0 };
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	hrmp_channel_accepted {
+		let message = Xcm::HrmpChannelAccepted { recipient: 0 };
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	hrmp_channel_closing {
+		let message = Xcm::HrmpChannelClosing { initiator: 0, sender: 0, recipient: 0 };
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	relayed_from {
+		// The relayed message is the cheapest one possible, so that mostly the relaying itself is measured.
+		let message = Xcm::RelayedFrom {
+			who: X1(OnlyChild),
+			message: Box::new(Xcm::HrmpChannelAccepted { recipient: 0 }),
+		};
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	claim_asset {
+		let origin = T::valid_origin();
+		let asset = T::worst_case_asset();
+		<<T::XcmConfig as xcm_executor::Config>::AssetTrap as DropAssets>::drop_assets(
+			&origin,
+			asset.clone().into(),
+		);
+		let message = Xcm::ClaimAsset { assets: vec![asset], ticket: Null, effects: vec![] };
+	}: {
+		execute::<T>(origin, message)?;
+	}
+
+	subscribe_version {
+		let message = Xcm::SubscribeVersion { query_id: 0, max_response_weight: 0 };
+	}: {
+		execute::<T>(T::valid_destination(), message)?;
+	}
+
+	unsubscribe_version {
+		let origin = T::valid_destination();
+		execute::<T>(origin.clone(), Xcm::SubscribeVersion { query_id: 0, max_response_weight: 0 })?;
+	}: {
+		execute::<T>(origin, Xcm::UnsubscribeVersion)?;
+	}
+
+	order_null {
+		let holding: Assets = T::worst_case_asset().into();
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, Order::Null);
+	}
+
+	deposit_asset {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::DepositAsset { assets: vec![All], dest: T::valid_destination() };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	deposit_reserve_asset {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::DepositReserveAsset { assets: vec![All], dest: T::valid_destination(), effects: vec![] };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	exchange_asset {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::ExchangeAsset { give: vec![All], receive: vec![T::worst_case_asset()] };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	initiate_reserve_withdraw {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::InitiateReserveWithdraw {
+			assets: vec![All],
+			reserve: T::trusted_reserve().unwrap_or_else(T::valid_destination),
+			effects: vec![],
+		};
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	initiate_teleport {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::InitiateTeleport { assets: vec![All], dest: T::trusted_teleporter(), effects: vec![] };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	query_holding {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::QueryHolding { query_id: 0, dest: T::valid_destination(), assets: vec![All] };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	buy_execution {
+		let holding: Assets = T::worst_case_asset().into();
+		let order = Order::BuyExecution { fees: All, weight: 0, debt: 1, halt_on_error: false, xcm: vec![] };
+	}: {
+		execute_order::<T>(&T::valid_origin(), holding, order);
+	}
+
+	report_outcome {
+		// `ReportOutcome` is handled alongside the other orders of a message rather than on its own, so it's
+		// executed within a message that otherwise does nothing.
+		let message = Xcm::WithdrawAsset {
+			assets: vec![],
+			effects: vec![Order::ReportOutcome { query_id: 0, dest: T::valid_destination() }],
+		};
+	}: {
+		execute::<T>(T::valid_origin(), message)?;
+	}
+}
+
+impl_benchmark_test_suite!(
+	Pallet,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,66 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of each XCM instruction and order, as executed by the `XcmExecutor` of a runtime's XCM
+//! configuration.
+//!
+//! The weights which result from these implement `WeightInfo` and are meant to be used with
+//! `xcm_builder::WeightInfoBounds` as the runtime's `Weigher`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::marker::PhantomData;
+use xcm::v0::{MultiAsset, MultiLocation};
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+
+/// The weights of the XCM instructions and orders, as measured by these benchmarks.
+pub use xcm_executor::traits::XcmWeightInfo as WeightInfo;
+
+/// The type on which the benchmarks are implemented.
+pub struct Pallet<T: Config>(PhantomData<T>);
+
+/// The configuration of the benchmarks for a runtime.
+///
+/// The `Barrier` of `XcmConfig` must allow messages which are paid for with weight credit (e.g. it includes
+/// `TakeWeightCredit`), since that is how all benchmarked messages are executed.
+pub trait Config: frame_system::Config {
+	/// The XCM configuration whose executor is benchmarked.
+	type XcmConfig: xcm_executor::Config;
+
+	/// A location which may execute XCM and which can hold `worst_case_asset`, e.g. an account.
+	fn valid_origin() -> MultiLocation;
+
+	/// A location to which XCM may be sent and which can hold `worst_case_asset`.
+	fn valid_destination() -> MultiLocation;
+
+	/// The location which is trusted as the reserve of `worst_case_asset`, if any.
+	///
+	/// Without one, reserve-based transfers are benchmarked as what the runtime does with them: rejecting them.
+	fn trusted_reserve() -> Option<MultiLocation>;
+
+	/// The location which is trusted to teleport `worst_case_asset`, if any.
+	fn trusted_teleporter() -> MultiLocation;
+
+	/// A non-wildcard asset which the `AssetTransactor` can deposit and withdraw and which the `Trader` accepts
+	/// as payment. It should be the most expensive such asset to handle.
+	fn worst_case_asset() -> MultiAsset;
+
+	/// A call, dispatchable by the sovereign account of `valid_origin`, whose own weight is negligible.
+	fn transact_call() -> <Self::XcmConfig as xcm_executor::Config>::Call;
+}
@@ -0,0 +1,169 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A mock runtime for testing the benchmarks.
+
+use crate as pallet_xcm_benchmarks;
+use frame_support::{parameter_types, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, AccountId32};
+use xcm::v0::{
+	Junction::{AccountId32 as AccountId32Junction, Parachain}, MultiAsset, MultiLocation::{self, Null, X1},
+	NetworkId, SendXcm, Xcm, Result as XcmResult,
+};
+use xcm_builder::{
+	AccountId32Aliases, CurrencyAdapter, FixedRateOfConcreteFungible, FixedWeightBounds, IsConcrete,
+	LocationInverter, SovereignSignedViaLocation, TakeWeightCredit,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+	}
+);
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+}
+
+/// A router which accepts every message and delivers none of them.
+pub struct DevNull;
+impl SendXcm for DevNull {
+	fn send_xcm(_dest: MultiLocation, _msg: Xcm<()>) -> XcmResult {
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const NativeLocation: MultiLocation = Null;
+	pub const AnyNetwork: NetworkId = NetworkId::Any;
+	pub Ancestry: MultiLocation = X1(Parachain(101));
+	pub const UnitWeightCost: Weight = 10;
+	pub WeightPrice: (MultiLocation, u128) = (Null, 1_000_000_000_000);
+}
+
+pub type LocationToAccountId = AccountId32Aliases<AnyNetwork, AccountId>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+	type Call = Call;
+	type XcmSender = DevNull;
+	type AssetTransactor = CurrencyAdapter<Balances, IsConcrete<NativeLocation>, LocationToAccountId, AccountId, ()>;
+	type OriginConverter = SovereignSignedViaLocation<LocationToAccountId, Origin>;
+	type IsReserve = ();
+	type IsTeleporter = ();
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Barrier = TakeWeightCredit;
+	type Weigher = FixedWeightBounds<UnitWeightCost, Call>;
+	type Trader = FixedRateOfConcreteFungible<WeightPrice, ()>;
+	type ResponseHandler = ();
+	type AssetTrap = ();
+	type AssetClaims = ();
+	type SubscriptionService = ();
+}
+
+fn account_location(id: u8) -> MultiLocation {
+	X1(AccountId32Junction { network: NetworkId::Any, id: [id; 32] })
+}
+
+impl pallet_xcm_benchmarks::Config for Test {
+	type XcmConfig = XcmConfig;
+
+	fn valid_origin() -> MultiLocation {
+		account_location(1)
+	}
+
+	fn valid_destination() -> MultiLocation {
+		account_location(2)
+	}
+
+	fn trusted_reserve() -> Option<MultiLocation> {
+		// `IsReserve` trusts no reserves.
+		None
+	}
+
+	fn trusted_teleporter() -> MultiLocation {
+		account_location(4)
+	}
+
+	fn worst_case_asset() -> MultiAsset {
+		MultiAsset::ConcreteFungible { id: Null, amount: 1_000_000 }
+	}
+
+	fn transact_call() -> Call {
+		Call::System(frame_system::Call::remark(vec![]))
+	}
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	t.into()
+}
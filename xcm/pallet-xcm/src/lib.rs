@@ -20,10 +20,14 @@
 
 use sp_std::{prelude::*, marker::PhantomData, convert::TryInto, boxed::Box, vec};
 use codec::{Encode, Decode};
-use xcm::v0::prelude::*;
-use xcm_executor::traits::ConvertOrigin;
-use sp_runtime::{RuntimeDebug, traits::BadOrigin};
+use xcm::v0::{prelude::*, Response};
+use xcm::{VersionedXcm, Version as XcmVersion, WrapVersion};
+use xcm_executor::{Assets, traits::{
+	ConvertOrigin, InvertLocation, OnResponse, DropAssets, ClaimAssets, VersionChangeNotifier,
+}};
+use sp_runtime::{RuntimeDebug, traits::{BadOrigin, Dispatchable, Saturating, One, Hash}};
 use frame_support::traits::{EnsureOrigin, OriginTrait, Filter, Get, Contains};
+use frame_support::weights::{GetDispatchInfo, PostDispatchInfo};
 
 pub use pallet::*;
 use frame_support::PalletId;
@@ -35,6 +39,7 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 	use xcm_executor::traits::WeightBounds;
 	use sp_runtime::traits::AccountIdConversion;
+	use frame_system::Config as SysConfig;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
@@ -48,7 +53,7 @@ pub mod pallet {
 
 		/// Required origin for sending XCM messages. If successful, the it resolves to `MultiLocation`
 		/// which exists as an interior location within this chain's XCM context.
-		type SendXcmOrigin: EnsureOrigin<Self::Origin, Success=MultiLocation>;
+		type SendXcmOrigin: EnsureOrigin<<Self as SysConfig>::Origin, Success=MultiLocation>;
 
 		/// The type used to actually dispatch an XCM to its destination.
 		type XcmRouter: SendXcm;
@@ -56,13 +61,13 @@ pub mod pallet {
 		/// Required origin for executing XCM messages, including the teleport functionality. If successful,
 		/// then it resolves to `MultiLocation` which exists as an interior location within this chain's XCM
 		/// context.
-		type ExecuteXcmOrigin: EnsureOrigin<Self::Origin, Success=MultiLocation>;
+		type ExecuteXcmOrigin: EnsureOrigin<<Self as SysConfig>::Origin, Success=MultiLocation>;
 
 		/// Our XCM filter which messages to be executed using `XcmExecutor` must pass.
-		type XcmExecuteFilter: Contains<(MultiLocation, Xcm<Self::Call>)>;
+		type XcmExecuteFilter: Contains<(MultiLocation, Xcm<<Self as SysConfig>::Call>)>;
 
 		/// Something to execute an XCM message.
-		type XcmExecutor: ExecuteXcm<Self::Call>;
+		type XcmExecutor: ExecuteXcm<<Self as SysConfig>::Call>;
 
 		/// Our XCM filter which messages to be teleported using the dedicated extrinsic must pass.
 		type XcmTeleportFilter: Contains<(MultiLocation, Vec<MultiAsset>)>;
@@ -71,16 +76,103 @@ pub mod pallet {
 		type XcmReserveTransferFilter: Contains<(MultiLocation, Vec<MultiAsset>)>;
 
 		/// Means of measuring the weight consumed by an XCM message locally.
-		type Weigher: WeightBounds<Self::Call>;
+		type Weigher: WeightBounds<<Self as SysConfig>::Call>;
+
+		/// Means of inverting a location; used to tell a responder where to send its response.
+		type LocationInverter: InvertLocation;
+
+		/// The outer `Origin` type, used to dispatch response notifications.
+		type Origin: From<Origin> + From<<Self as SysConfig>::Origin>;
+
+		/// The outer `Call` type, used to decode and dispatch response notifications.
+		///
+		/// NOTE: The weight of a notification is not known when the `QueryResponse` message carrying it is
+		/// weighed, so notification calls should be light.
+		type Call: Parameter
+			+ GetDispatchInfo
+			+ Dispatchable<Origin=<Self as Config>::Origin, PostInfo=PostDispatchInfo>;
+
+		/// The latest XCM version that this runtime supports, which is what locations subscribed through
+		/// `SubscribeVersion` are told. They are notified again after any runtime upgrade which changes it.
+		type AdvertisedXcmVersion: Get<XcmVersion>;
+
+		/// The most locations which may be subscribed to our XCM version at once.
+		type MaxVersionNotifyTargets: Get<u32>;
+
+		/// The most notifications of a change to our XCM version which are sent in a single block.
+		type VersionNotificationsPerBlock: Get<u32>;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::Hash = "Hash")]
 	pub enum Event<T: Config> {
 		Attempted(xcm::v0::Outcome),
 		Sent(MultiLocation, MultiLocation, Xcm<()>),
+		/// The supported version of a location has been changed. This might be through an
+		/// automatic notification or a manual intervention.
+		///
+		/// \[ location, XCM version \]
+		SupportedVersionChanged(MultiLocation, XcmVersion),
+		/// The default XCM version, used for destinations whose supported version is unknown,
+		/// has been changed.
+		///
+		/// \[ XCM version \]
+		SafeXcmVersionChanged(XcmVersion),
+		/// A response has been received which is ready for taking with `take_response`. There is
+		/// no registered notification call.
+		///
+		/// \[ id, response \]
+		ResponseReady(QueryId, Response),
+		/// A response has been received and its notification call has been dispatched successfully.
+		///
+		/// \[ id, pallet index, call index \]
+		Notified(QueryId, u8, u8),
+		/// A response has been received but its notification call returned an error.
+		///
+		/// \[ id, pallet index, call index \]
+		NotifyDispatchError(QueryId, u8, u8),
+		/// A response has been received but its notification call could not be decoded. This
+		/// generally means that the call's pallet or signature has changed since it was registered.
+		///
+		/// \[ id, pallet index, call index \]
+		NotifyDecodeFailed(QueryId, u8, u8),
+		/// A response has been received but its notification call weighs more than the most weight
+		/// registered for it, so it has not been dispatched.
+		///
+		/// \[ id, pallet index, call index, actual weight, max weight \]
+		NotifyOverweight(QueryId, u8, u8, Weight, Weight),
+		/// A response has been received from a location for a query which it is not expected to
+		/// answer. It has been ignored.
+		///
+		/// \[ origin location, id \]
+		UnexpectedResponse(MultiLocation, QueryId),
+		/// No response was received for a query before its timeout. The query has been removed.
+		///
+		/// \[ id \]
+		ResponseTimedOut(QueryId),
+		/// Some assets have been left in the holding register at the end of an XCM execution. They
+		/// can be claimed by the same origin with a `ClaimAsset` instruction.
+		///
+		/// \[ hash, origin, assets \]
+		AssetsTrapped(T::Hash, MultiLocation, Vec<MultiAsset>),
+		/// Some trapped assets have been claimed.
+		///
+		/// \[ hash, origin, assets \]
+		AssetsClaimed(T::Hash, MultiLocation, Vec<MultiAsset>),
+		/// A location subscribed to our XCM version has been notified of it.
+		///
+		/// \[ destination, XCM version \]
+		VersionChangeNotified(MultiLocation, XcmVersion),
+		/// A location subscribed to our XCM version could not be sent its notification.
+		///
+		/// \[ destination, id, error \]
+		NotifyTargetSendFail(MultiLocation, QueryId, XcmError),
 	}
 
+	#[pallet::origin]
+	pub type Origin = super::Origin;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		Unreachable,
@@ -89,15 +181,126 @@ pub mod pallet {
 		Filtered,
 		/// The message's weight could not be determined.
 		UnweighableMessage,
+		/// We are already subscribed to the XCM version of the location.
+		AlreadySubscribed,
+		/// We are not subscribed to the XCM version of the location.
+		NoSubscription,
 	}
 
+	/// The status of a query.
+	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+	pub enum QueryStatus<BlockNumber> {
+		/// The query was sent but no response has yet been received.
+		Pending {
+			/// The location from which the response is expected.
+			responder: MultiLocation,
+			/// The pallet and call index of the notification call to dispatch with the response,
+			/// together with the most weight it may take, if any.
+			maybe_notify: Option<(u8, u8, Weight)>,
+			/// The last block in which a response is accepted.
+			timeout: BlockNumber,
+		},
+		/// A subscription to the XCM version of a location, whose notifications are used to keep
+		/// `SupportedVersion` up to date. It does not time out.
+		VersionNotifier {
+			/// The location which is subscribed to.
+			origin: MultiLocation,
+			/// Whether a notification has been received yet.
+			is_active: bool,
+		},
+		/// A response has been received.
+		Ready { response: Response, at: BlockNumber },
+	}
+
+	/// The latest XCM version that we know a particular destination supports.
+	#[pallet::storage]
+	pub(super) type SupportedVersion<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, XcmVersion, OptionQuery>;
+
+	/// The XCM version to use when sending to a destination whose supported version is unknown.
+	///
+	/// Version 0 is understood by every destination, so that is the default.
+	#[pallet::storage]
+	pub(super) type SafeXcmVersion<T: Config> = StorageValue<_, XcmVersion, ValueQuery>;
+
+	/// The latest available query index.
+	#[pallet::storage]
+	pub(super) type QueryCounter<T: Config> = StorageValue<_, QueryId, ValueQuery>;
+
+	/// The ongoing queries.
+	#[pallet::storage]
+	#[pallet::getter(fn query)]
+	pub(super) type Queries<T: Config> =
+		StorageMap<_, Blake2_128Concat, QueryId, QueryStatus<T::BlockNumber>, OptionQuery>;
+
+	/// The queries which time out at the start of a given block, if they are still pending.
+	#[pallet::storage]
+	pub(super) type QueryTimeouts<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<QueryId>, ValueQuery>;
+
+	/// The number of times each set of assets was trapped for an origin, keyed by the hash of the
+	/// origin and the assets.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_trap)]
+	pub(super) type AssetTraps<T: Config> = StorageMap<_, Identity, T::Hash, u32, ValueQuery>;
+
+	/// The locations whose XCM version we are subscribed to, along with the query which their
+	/// notifications answer.
+	#[pallet::storage]
+	pub(super) type VersionNotifiers<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, QueryId, OptionQuery>;
+
+	/// The locations which are subscribed to our XCM version, along with the query which our
+	/// notifications answer and the version they were last told.
+	#[pallet::storage]
+	pub(super) type VersionNotifyTargets<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, (QueryId, XcmVersion), OptionQuery>;
+
+	/// The number of locations in `VersionNotifyTargets`.
+	#[pallet::storage]
+	pub(super) type VersionNotifyTargetCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The locations which are yet to be notified of a change to our XCM version, in the order in
+	/// which they will be.
+	#[pallet::storage]
+	pub(super) type PendingVersionNotifications<T: Config> = StorageValue<_, Vec<MultiLocation>, ValueQuery>;
+
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let query_ids = QueryTimeouts::<T>::take(now);
+			let mut timed_out = 0;
+			for query_id in query_ids.iter() {
+				if let Some(QueryStatus::Pending { .. }) = Queries::<T>::get(query_id) {
+					Queries::<T>::remove(query_id);
+					Self::deposit_event(Event::ResponseTimedOut(*query_id));
+					timed_out += 1;
+				}
+			}
+			T::DbWeight::get().reads_writes(1 + query_ids.len() as Weight, 1 + timed_out)
+				.saturating_add(Self::send_pending_version_notifications())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			// The XCM version we support can only change with the runtime, so this is the time to tell
+			// those who want to know. There may be too many of them for a single block, so they are
+			// queued and told from `on_initialize`.
+			let version = T::AdvertisedXcmVersion::get();
+			let mut reads = 0;
+			let targets = VersionNotifyTargets::<T>::iter()
+				.inspect(|_| reads += 1)
+				.filter(|(_, (_, last_version))| *last_version != version)
+				.map(|(target, _)| target)
+				.collect::<Vec<_>>();
+			PendingVersionNotifications::<T>::put(targets);
+			T::DbWeight::get().reads_writes(reads, 1)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::weight(100_000_000)]
-		fn send(origin: OriginFor<T>, dest: MultiLocation, message: Xcm<()>) -> DispatchResult {
+		pub fn send(origin: OriginFor<T>, dest: MultiLocation, message: Xcm<()>) -> DispatchResult {
 			let origin_location = T::SendXcmOrigin::ensure_origin(origin)?;
 			Self::send_xcm(origin_location.clone(), dest.clone(), message.clone())
 				.map_err(|e| match e {
@@ -130,7 +333,7 @@ pub mod pallet {
 			};
 			T::Weigher::weight(&mut message).map_or(Weight::max_value(), |w| 100_000_000 + w)
 		})]
-		fn teleport_assets(
+		pub fn teleport_assets(
 			origin: OriginFor<T>,
 			dest: MultiLocation,
 			beneficiary: MultiLocation,
@@ -188,7 +391,7 @@ pub mod pallet {
 			};
 			T::Weigher::weight(&mut message).map_or(Weight::max_value(), |w| 100_000_000 + w)
 		})]
-		fn reserve_transfer_assets(
+		pub fn reserve_transfer_assets(
 			origin: OriginFor<T>,
 			dest: MultiLocation,
 			beneficiary: MultiLocation,
@@ -233,7 +436,7 @@ pub mod pallet {
 		/// NOTE: A successful return to this does *not* imply that the `msg` was executed successfully
 		/// to completion; only that *some* of it was executed.
 		#[pallet::weight(max_weight.saturating_add(100_000_000u64))]
-		fn execute(origin: OriginFor<T>, message: Box<Xcm<T::Call>>, max_weight: Weight)
+		pub fn execute(origin: OriginFor<T>, message: Box<Xcm<<T as SysConfig>::Call>>, max_weight: Weight)
 			-> DispatchResult
 		{
 			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin)?;
@@ -244,6 +447,82 @@ pub mod pallet {
 			Self::deposit_event(Event::Attempted(outcome));
 			Ok(())
 		}
+
+		/// Extoll that a particular destination can be communicated with through a particular
+		/// version of XCM.
+		///
+		/// - `origin`: Must be Root.
+		/// - `location`: The destination that is being described.
+		/// - `xcm_version`: The latest version of XCM that `location` supports.
+		#[pallet::weight(100_000_000u64)]
+		pub fn force_xcm_version(
+			origin: OriginFor<T>,
+			location: MultiLocation,
+			xcm_version: XcmVersion,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			SupportedVersion::<T>::insert(&location, xcm_version);
+			Self::deposit_event(Event::SupportedVersionChanged(location, xcm_version));
+			Ok(())
+		}
+
+		/// Set the XCM version to use for destinations whose supported version is not known.
+		///
+		/// - `origin`: Must be Root.
+		/// - `xcm_version`: The version of XCM to fall back to.
+		#[pallet::weight(100_000_000u64)]
+		pub fn force_default_xcm_version(origin: OriginFor<T>, xcm_version: XcmVersion) -> DispatchResult {
+			ensure_root(origin)?;
+			SafeXcmVersion::<T>::put(xcm_version);
+			Self::deposit_event(Event::SafeXcmVersionChanged(xcm_version));
+			Ok(())
+		}
+
+		/// Ask a location to notify us of the XCM version it supports, now and whenever it changes,
+		/// so that `SupportedVersion` is kept up to date automatically.
+		///
+		/// - `origin`: Must be Root.
+		/// - `location`: The location to which we should subscribe for XCM version notifications.
+		#[pallet::weight(100_000_000u64)]
+		pub fn force_subscribe_version_notify(origin: OriginFor<T>, location: MultiLocation) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!VersionNotifiers::<T>::contains_key(&location), Error::<T>::AlreadySubscribed);
+			let query_id = Self::next_query_id();
+			// Any weight will do, since responses to our queries are not paid for.
+			let message = Xcm::SubscribeVersion { query_id, max_response_weight: 0 };
+			Self::send_xcm(MultiLocation::Null, location.clone(), message)
+				.map_err(|_| Error::<T>::SendFailure)?;
+			VersionNotifiers::<T>::insert(&location, query_id);
+			Queries::<T>::insert(query_id, QueryStatus::VersionNotifier { origin: location, is_active: false });
+			Ok(())
+		}
+
+		/// Ask a location to stop notifying us of the XCM version it supports. The version we last
+		/// learnt of is kept in `SupportedVersion`.
+		///
+		/// - `origin`: Must be Root.
+		/// - `location`: The location to which we are currently subscribed for XCM version
+		///   notifications which we no longer desire.
+		#[pallet::weight(100_000_000u64)]
+		pub fn force_unsubscribe_version_notify(origin: OriginFor<T>, location: MultiLocation) -> DispatchResult {
+			ensure_root(origin)?;
+			let query_id = VersionNotifiers::<T>::get(&location).ok_or(Error::<T>::NoSubscription)?;
+			Self::send_xcm(MultiLocation::Null, location.clone(), Xcm::UnsubscribeVersion)
+				.map_err(|_| Error::<T>::SendFailure)?;
+			VersionNotifiers::<T>::remove(&location);
+			Queries::<T>::remove(query_id);
+			Ok(())
+		}
+	}
+
+	impl<T: Config> WrapVersion for Pallet<T> {
+		fn wrap_version<Call>(
+			dest: &MultiLocation,
+			xcm: impl Into<VersionedXcm<Call>>,
+		) -> Result<VersionedXcm<Call>, ()> {
+			let version = SupportedVersion::<T>::get(dest).unwrap_or_else(SafeXcmVersion::<T>::get);
+			xcm.into().into_version(version)
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -261,14 +540,306 @@ pub mod pallet {
 			const ID: PalletId = PalletId(*b"py/xcmch");
 			AccountIdConversion::<T::AccountId>::into_account(&ID)
 		}
+
+		/// Append an `Order::ReportOutcome` to `message` so that the outcome of its execution is reported
+		/// back to us, and register a query which expects the report.
+		///
+		/// - `message`: The message whose outcome should be reported. It must be a `WithdrawAsset`,
+		///   `ReserveAssetDeposit` or `TeleportAsset`, since only those carry orders.
+		/// - `responder`: The location to which `message` is sent and from which the report is expected.
+		/// - `timeout`: The last block in which the report is accepted.
+		///
+		/// The result of the query can be found with `query` and taken with `take_response`.
+		pub fn report_outcome(
+			message: &mut Xcm<()>,
+			responder: MultiLocation,
+			timeout: T::BlockNumber,
+		) -> Result<QueryId, XcmError> {
+			Self::do_report_outcome(message, responder, None, timeout)
+		}
+
+		/// Like `report_outcome`, but the report is handed to `notify` rather than being stored.
+		///
+		/// `notify` must be a call of this runtime which takes a `QueryId` and a `Response` as its only
+		/// arguments, in that order. Only its pallet and call indices and its weight are used. It is dispatched
+		/// with an `Origin::Response` of `responder`, unless it would then weigh more than it does with the
+		/// values it is constructed with.
+		pub fn report_outcome_notify(
+			message: &mut Xcm<()>,
+			responder: MultiLocation,
+			notify: impl Into<<T as Config>::Call>,
+			timeout: T::BlockNumber,
+		) -> Result<QueryId, XcmError> {
+			Self::do_report_outcome(message, responder, Some(Self::notify_info(notify)), timeout)
+		}
+
+		/// Register a query which expects a response from `responder` until `timeout`.
+		pub fn new_query(responder: MultiLocation, timeout: T::BlockNumber) -> QueryId {
+			Self::do_new_query(responder, None, timeout)
+		}
+
+		/// Register a query which expects a response from `responder` until `timeout`, which is then handed to
+		/// `notify`. See `report_outcome_notify` for the requirements on `notify`.
+		pub fn new_notify_query(
+			responder: MultiLocation,
+			notify: impl Into<<T as Config>::Call>,
+			timeout: T::BlockNumber,
+		) -> QueryId {
+			Self::do_new_query(responder, Some(Self::notify_info(notify)), timeout)
+		}
+
+		/// Remove and return the response of a query, if it has been received.
+		pub fn take_response(query_id: QueryId) -> Option<(Response, T::BlockNumber)> {
+			match Queries::<T>::get(query_id) {
+				Some(QueryStatus::Ready { response, at }) => {
+					Queries::<T>::remove(query_id);
+					Some((response, at))
+				}
+				_ => None,
+			}
+		}
+
+		fn do_report_outcome(
+			message: &mut Xcm<()>,
+			responder: MultiLocation,
+			maybe_notify: Option<(u8, u8, Weight)>,
+			timeout: T::BlockNumber,
+		) -> Result<QueryId, XcmError> {
+			let effects = match message {
+				Xcm::WithdrawAsset { effects, .. }
+				| Xcm::ReserveAssetDeposit { effects, .. }
+				| Xcm::TeleportAsset { effects, .. }
+				=> effects,
+				_ => return Err(XcmError::Unimplemented),
+			};
+			let dest = T::LocationInverter::invert_location(&responder);
+			let query_id = Self::do_new_query(responder, maybe_notify, timeout);
+			effects.push(Order::ReportOutcome { query_id, dest });
+			Ok(query_id)
+		}
+
+		fn do_new_query(
+			responder: MultiLocation,
+			maybe_notify: Option<(u8, u8, Weight)>,
+			timeout: T::BlockNumber,
+		) -> QueryId {
+			let query_id = Self::next_query_id();
+			Queries::<T>::insert(query_id, QueryStatus::Pending { responder, maybe_notify, timeout });
+			// A timeout in the past still needs to expire at some point.
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at = timeout.max(now).saturating_add(One::one());
+			QueryTimeouts::<T>::append(expires_at, query_id);
+			query_id
+		}
+
+		fn next_query_id() -> QueryId {
+			QueryCounter::<T>::mutate(|q| {
+				let r = *q;
+				*q = q.saturating_add(1);
+				r
+			})
+		}
+
+		fn notify_info(notify: impl Into<<T as Config>::Call>) -> (u8, u8, Weight) {
+			let notify: <T as Config>::Call = notify.into();
+			let max_weight = notify.get_dispatch_info().weight;
+			notify.using_encoded(|bytes| (bytes[0], bytes[1], max_weight))
+		}
+
+		/// Tell up to `VersionNotificationsPerBlock` of the `PendingVersionNotifications` which are still
+		/// subscribed about our current XCM version.
+		fn send_pending_version_notifications() -> Weight {
+			let mut pending = PendingVersionNotifications::<T>::get();
+			if pending.is_empty() {
+				return T::DbWeight::get().reads(1)
+			}
+			let count = pending.len().min(T::VersionNotificationsPerBlock::get() as usize);
+			let version = T::AdvertisedXcmVersion::get();
+			for target in pending.drain(..count) {
+				match VersionNotifyTargets::<T>::get(&target) {
+					Some((query_id, last_version)) if last_version != version =>
+						Self::notify_version(target, query_id, version),
+					// Unsubscribed or told since it was queued.
+					_ => {}
+				}
+			}
+			if pending.is_empty() {
+				PendingVersionNotifications::<T>::kill();
+			} else {
+				PendingVersionNotifications::<T>::put(pending);
+			}
+			// Each notification reads and updates its target and the message queue of its destination.
+			let count = count as Weight;
+			T::DbWeight::get().reads_writes(1 + 2 * count, 1 + 2 * count)
+		}
+
+		/// Tell `dest` that we support XCM `version` in answer to its subscription `query_id`, and record
+		/// that it has been told.
+		fn notify_version(dest: MultiLocation, query_id: QueryId, version: XcmVersion) {
+			let message = Xcm::QueryResponse { query_id, response: Response::Version(version) };
+			match T::XcmRouter::send_xcm(dest.clone(), message) {
+				Ok(()) => {
+					VersionNotifyTargets::<T>::insert(&dest, (query_id, version));
+					Self::deposit_event(Event::VersionChangeNotified(dest, version));
+				}
+				Err(e) => {
+					// Keep the subscription, but with the version it was last told, so that the
+					// notification is attempted again with the next runtime upgrade.
+					Self::deposit_event(Event::NotifyTargetSendFail(dest, query_id, e));
+				}
+			}
+		}
+	}
+
+	impl<T: Config> VersionChangeNotifier for Pallet<T> {
+		fn start(location: &MultiLocation, query_id: QueryId, _max_response_weight: u64) -> XcmResult {
+			// Responses in this version of XCM carry no weight limit, so there is nothing to do with
+			// `_max_response_weight`.
+			let is_new = !VersionNotifyTargets::<T>::contains_key(location);
+			let count = VersionNotifyTargetCount::<T>::get();
+			if is_new && count >= T::MaxVersionNotifyTargets::get() {
+				return Err(XcmError::TooManySubscriptions)
+			}
+			let message = Xcm::QueryResponse {
+				query_id,
+				response: Response::Version(T::AdvertisedXcmVersion::get()),
+			};
+			T::XcmRouter::send_xcm(location.clone(), message)?;
+			VersionNotifyTargets::<T>::insert(location, (query_id, T::AdvertisedXcmVersion::get()));
+			if is_new {
+				VersionNotifyTargetCount::<T>::put(count + 1);
+			}
+			Self::deposit_event(Event::VersionChangeNotified(location.clone(), T::AdvertisedXcmVersion::get()));
+			Ok(())
+		}
+
+		fn stop(location: &MultiLocation) -> XcmResult {
+			if VersionNotifyTargets::<T>::take(location).is_some() {
+				VersionNotifyTargetCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> DropAssets for Pallet<T> {
+		fn drop_assets(origin: &MultiLocation, assets: Assets) {
+			let assets: Vec<MultiAsset> = assets.into();
+			let hash = T::Hashing::hash_of(&(origin, &assets));
+			AssetTraps::<T>::mutate(hash, |n| *n = n.saturating_add(1));
+			Self::deposit_event(Event::AssetsTrapped(hash, origin.clone(), assets));
+		}
+	}
+
+	impl<T: Config> ClaimAssets for Pallet<T> {
+		fn claim_assets(origin: &MultiLocation, ticket: &MultiLocation, assets: &[MultiAsset]) -> bool {
+			// Traps are identified by their origin and assets alone, so there's no use for a ticket.
+			if ticket != &MultiLocation::Null {
+				return false
+			}
+			let hash = T::Hashing::hash_of(&(origin, assets));
+			match AssetTraps::<T>::get(hash) {
+				0 => return false,
+				1 => AssetTraps::<T>::remove(hash),
+				n => AssetTraps::<T>::insert(hash, n - 1),
+			}
+			Self::deposit_event(Event::AssetsClaimed(hash, origin.clone(), assets.to_vec()));
+			true
+		}
+	}
+
+	impl<T: Config> OnResponse for Pallet<T> {
+		fn expecting_response(origin: &MultiLocation, query_id: QueryId) -> bool {
+			match Queries::<T>::get(query_id) {
+				Some(QueryStatus::Pending { responder, .. }) => &responder == origin,
+				Some(QueryStatus::VersionNotifier { origin: responder, .. }) => &responder == origin,
+				_ => false,
+			}
+		}
+
+		fn max_response_weight(origin: &MultiLocation, query_id: QueryId) -> Weight {
+			match Queries::<T>::get(query_id) {
+				Some(QueryStatus::Pending { responder, maybe_notify: Some((_, _, max_weight)), .. })
+				if &responder == origin => max_weight,
+				Some(QueryStatus::VersionNotifier { origin: responder, .. }) if &responder == origin
+				=> T::DbWeight::get().reads_writes(1, 2),
+				_ => 0,
+			}
+		}
+
+		fn on_response(origin: MultiLocation, query_id: QueryId, response: Response) -> Weight {
+			let (maybe_notify, response) = match (Queries::<T>::get(query_id), response) {
+				(Some(QueryStatus::Pending { responder, maybe_notify, .. }), response) if responder == origin
+				=> (maybe_notify, response),
+				(Some(QueryStatus::VersionNotifier { origin: responder, .. }), Response::Version(version))
+				if responder == origin => {
+					SupportedVersion::<T>::insert(&origin, version);
+					let status = QueryStatus::VersionNotifier { origin: origin.clone(), is_active: true };
+					Queries::<T>::insert(query_id, status);
+					Self::deposit_event(Event::SupportedVersionChanged(origin, version));
+					return T::DbWeight::get().reads_writes(1, 2)
+				}
+				_ => {
+					Self::deposit_event(Event::UnexpectedResponse(origin, query_id));
+					return 0
+				}
+			};
+			let (pallet_index, call_index, max_weight) = match maybe_notify {
+				Some(indices) => indices,
+				None => {
+					let at = frame_system::Pallet::<T>::block_number();
+					Self::deposit_event(Event::ResponseReady(query_id, response.clone()));
+					Queries::<T>::insert(query_id, QueryStatus::Ready { response, at });
+					return 0
+				}
+			};
+			Queries::<T>::remove(query_id);
+			// The notification call is known to take a `QueryId` and a `Response`, so its encoding is just
+			// its indices followed by those.
+			let bare = (pallet_index, call_index, query_id, response);
+			let call = match bare.using_encoded(|mut bytes| <T as Config>::Call::decode(&mut bytes)) {
+				Ok(call) => call,
+				Err(_) => {
+					Self::deposit_event(Event::NotifyDecodeFailed(query_id, pallet_index, call_index));
+					return 0
+				}
+			};
+			let weight = call.get_dispatch_info().weight;
+			if weight > max_weight {
+				let e = Event::NotifyOverweight(query_id, pallet_index, call_index, weight, max_weight);
+				Self::deposit_event(e);
+				return 0
+			}
+			let dispatch_origin = Origin::Response(origin).into();
+			let actual_weight = match call.dispatch(dispatch_origin) {
+				Ok(post_info) => {
+					Self::deposit_event(Event::Notified(query_id, pallet_index, call_index));
+					post_info.actual_weight
+				}
+				Err(error_and_info) => {
+					Self::deposit_event(Event::NotifyDispatchError(query_id, pallet_index, call_index));
+					error_and_info.post_info.actual_weight
+				}
+			};
+			actual_weight.unwrap_or(weight)
+		}
 	}
 }
 
+/// An identifier of a query, unique within this chain.
+pub type QueryId = u64;
+
+frame_support::parameter_types! {
+	/// The most recent version of XCM, which is what `Config::AdvertisedXcmVersion` should generally be.
+	pub const CurrentXcmVersion: XcmVersion = xcm::LATEST_VERSION;
+}
+
 /// Origin for the parachains module.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 pub enum Origin {
 	/// It comes from somewhere in the XCM space.
 	Xcm(MultiLocation),
+	/// It comes as an expected response from an XCM location.
+	Response(MultiLocation),
 }
 
 impl From<MultiLocation> for Origin {
@@ -288,6 +859,17 @@ pub fn ensure_xcm<OuterOrigin>(o: OuterOrigin) -> Result<MultiLocation, BadOrigi
 	}
 }
 
+/// Ensure that the origin `o` represents an expected response from some XCM location.
+/// Returns `Ok` with the location of the responder or an `Err` otherwise.
+pub fn ensure_response<OuterOrigin>(o: OuterOrigin) -> Result<MultiLocation, BadOrigin>
+	where OuterOrigin: Into<Result<Origin, OuterOrigin>>
+{
+	match o.into() {
+		Ok(Origin::Response(responder)) => Ok(responder),
+		_ => Err(BadOrigin),
+	}
+}
+
 /// Filter for `MultiLocation` to find those which represent a strict majority approval of an identified
 /// plurality.
 ///
@@ -310,13 +892,10 @@ impl<O: OriginTrait + From<Origin>, F: Filter<MultiLocation>> EnsureOrigin<O> fo
 
 	fn try_origin(outer: O) -> Result<Self::Success, O> {
 		outer.try_with_caller(|caller| caller.try_into()
-			.and_then(|Origin::Xcm(location)|
-				if F::filter(&location) {
-					Ok(location)
-				} else {
-					Err(Origin::Xcm(location).into())
-				}
-			))
+			.and_then(|o| match o {
+				Origin::Xcm(location) if F::filter(&location) => Ok(location),
+				o => Err(o.into()),
+			}))
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -325,6 +904,28 @@ impl<O: OriginTrait + From<Origin>, F: Filter<MultiLocation>> EnsureOrigin<O> fo
 	}
 }
 
+/// `EnsureOrigin` implementation succeeding with a `MultiLocation` value to recognize and filter the
+/// `Origin::Response` item.
+pub struct EnsureResponse<F>(PhantomData<F>);
+impl<O: OriginTrait + From<Origin>, F: Filter<MultiLocation>> EnsureOrigin<O> for EnsureResponse<F>
+	where O::PalletsOrigin: From<Origin> + TryInto<Origin, Error=O::PalletsOrigin>
+{
+	type Success = MultiLocation;
+
+	fn try_origin(outer: O) -> Result<Self::Success, O> {
+		outer.try_with_caller(|caller| caller.try_into()
+			.and_then(|o| match o {
+				Origin::Response(responder) if F::filter(&responder) => Ok(responder),
+				o => Err(o.into()),
+			}))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(Origin::Response(MultiLocation::Null))
+	}
+}
+
 /// A simple passthrough where we reuse the `MultiLocation`-typed XCM origin as the inner value of
 /// this crate's `Origin::Xcm` value.
 pub struct XcmPassthrough<Origin>(PhantomData<Origin>);
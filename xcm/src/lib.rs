@@ -23,10 +23,12 @@
 #![no_std]
 extern crate alloc;
 
+use core::{result::Result, convert::TryInto};
 use parity_scale_codec::{Encode, Decode};
 use derivative::Derivative;
 
 pub mod v0;
+pub mod v1;
 
 mod double_encoded;
 pub use double_encoded::DoubleEncoded;
@@ -38,6 +40,64 @@ pub use double_encoded::DoubleEncoded;
 #[codec(decode_bound())]
 pub enum VersionedXcm<Call> {
 	V0(v0::Xcm<Call>),
+	V1(v1::Xcm<Call>),
+}
+
+/// A version of the XCM format.
+pub type Version = u32;
+
+/// The most recent version of the XCM format.
+pub const LATEST_VERSION: Version = 1;
+
+impl<Call> VersionedXcm<Call> {
+	/// Convert the message into the given XCM `version`, if possible.
+	pub fn into_version(self, version: Version) -> Result<Self, ()> {
+		Ok(match version {
+			0 => VersionedXcm::V0(self.try_into()?),
+			1 => VersionedXcm::V1(self.try_into()?),
+			_ => return Err(()),
+		})
+	}
+}
+
+/// Convert an outgoing message into a version which the destination is able to interpret.
+pub trait WrapVersion {
+	fn wrap_version<Call>(
+		dest: &v0::MultiLocation,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()>;
+}
+
+/// `()` implementation does nothing with the XCM, just sending with whatever version it was authored as.
+impl WrapVersion for () {
+	fn wrap_version<Call>(
+		_: &v0::MultiLocation,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()> {
+		Ok(xcm.into())
+	}
+}
+
+/// `WrapVersion` implementation which attempts to always convert the XCM to version 0 before wrapping it.
+pub struct AlwaysV0;
+impl WrapVersion for AlwaysV0 {
+	fn wrap_version<Call>(
+		_: &v0::MultiLocation,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()> {
+		xcm.into().into_version(0)
+	}
+}
+
+/// `WrapVersion` implementation which attempts to always convert the XCM to version 1 before wrapping it.
+pub struct AlwaysV1;
+impl WrapVersion for AlwaysV1 {
+	fn wrap_version<Call>(
+		_: &v0::MultiLocation,
+		xcm: impl Into<VersionedXcm<Call>>,
+	) -> Result<VersionedXcm<Call>, ()> {
+		xcm.into().into_version(1)
+	}
 }
 
 pub mod opaque {
@@ -48,6 +108,13 @@ pub mod opaque {
 		pub use crate::v0::opaque::{Xcm, Order};
 	}
 
+	pub mod v1 {
+		// Everything from v1
+		pub use crate::v1::*;
+		// Then override with the opaque types in v1
+		pub use crate::v1::opaque::{Xcm, Order};
+	}
+
 	/// The basic VersionedXcm type which just uses the `Vec<u8>` as an encoded call.
 	pub type VersionedXcm = super::VersionedXcm<()>;
 }
@@ -56,10 +123,12 @@ pub mod opaque {
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
 pub enum VersionedMultiLocation {
 	V0(v0::MultiLocation),
+	V1(v1::MultiLocation),
 }
 
 /// A versioned multi-asset, an identifier for an asset within a consensus system.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
 pub enum VersionedMultiAsset {
 	V0(v0::MultiAsset),
+	V1(v1::MultiAsset),
 }
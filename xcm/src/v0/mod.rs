@@ -16,11 +16,11 @@
 
 //! Version 0 of the Cross-Consensus Message format data structures.
 
-use core::{result, convert::TryFrom, fmt::Debug};
+use core::{result, convert::{TryFrom, TryInto}, fmt::Debug};
 use derivative::Derivative;
 use alloc::vec::Vec;
 use parity_scale_codec::{self, Encode, Decode};
-use crate::{VersionedMultiAsset, DoubleEncoded, VersionedXcm};
+use crate::{VersionedMultiAsset, DoubleEncoded, VersionedXcm, Version};
 
 mod junction;
 mod multi_asset;
@@ -74,6 +74,12 @@ pub enum OriginKind {
 pub enum Response {
 	/// Some assets.
 	Assets(Vec<MultiAsset>),
+	/// The outcome of executing some orders, as requested by `Order::ReportOutcome`.
+	///
+	/// The error is boxed since it may itself contain an XCM message.
+	ExecutionResult(result::Result<(), alloc::boxed::Box<Error>>),
+	/// The latest XCM version supported by the responder, as requested by `Xcm::SubscribeVersion`.
+	Version(Version),
 }
 
 /// Cross-Consensus Message: A message from one consensus system to another.
@@ -259,6 +265,47 @@ pub enum Xcm<Call> {
 		who: MultiLocation,
 		message: alloc::boxed::Box<Xcm<Call>>,
 	},
+
+	/// Withdraw asset(s) (`assets`) which were trapped when a message from this origin was previously executed,
+	/// and place them into holding. Execute the orders (`effects`).
+	///
+	/// - `assets`: The asset(s) to be claimed; this must match exactly the assets which were trapped.
+	/// - `ticket`: Information regarding the trapping of the assets, as required by the asset trap of the
+	///   executing chain. Generally `Null`.
+	/// - `effects`: The order(s) to execute on the holding account.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 11)]
+	ClaimAsset { assets: Vec<MultiAsset>, ticket: MultiLocation, effects: Vec<Order<Call>> },
+
+	/// Ask the destination system to respond with the most recent version of XCM that it supports, and to
+	/// notify the origin whenever that changes.
+	///
+	/// The response is a `QueryResponse` of `Response::Version`, sent once immediately and then again each time
+	/// the version changes, until an `UnsubscribeVersion` is received.
+	///
+	/// - `query_id`: The identifier of the query which the responses should carry.
+	/// - `max_response_weight`: The maximum amount of weight that the `QueryResponse` should be allowed to take
+	///   when executed on the origin.
+	///
+	/// Kind: *Instruction*
+	///
+	/// Errors:
+	#[codec(index = 12)]
+	SubscribeVersion {
+		#[codec(compact)] query_id: u64,
+		#[codec(compact)] max_response_weight: u64,
+	},
+
+	/// Cancel the effect of a previous `SubscribeVersion` instruction from the origin.
+	///
+	/// Kind: *Instruction*
+	///
+	/// Errors:
+	#[codec(index = 13)]
+	UnsubscribeVersion,
 }
 
 impl<Call> From<Xcm<Call>> for VersionedXcm<Call> {
@@ -272,6 +319,7 @@ impl<Call> TryFrom<VersionedXcm<Call>> for Xcm<Call> {
 	fn try_from(x: VersionedXcm<Call>) -> result::Result<Self, ()> {
 		match x {
 			VersionedXcm::V0(x) => Ok(x),
+			VersionedXcm::V1(x) => x.try_into(),
 		}
 	}
 }
@@ -303,6 +351,12 @@ impl<Call> Xcm<Call> {
 			=> Transact { origin_type, require_weight_at_most, call: call.into() },
 			RelayedFrom { who, message }
 			=> RelayedFrom { who, message: alloc::boxed::Box::new((*message).into()) },
+			ClaimAsset { assets, ticket, effects }
+			=> ClaimAsset { assets, ticket, effects: effects.into_iter().map(Order::into).collect() },
+			SubscribeVersion { query_id, max_response_weight }
+			=> SubscribeVersion { query_id, max_response_weight },
+			UnsubscribeVersion
+			=> UnsubscribeVersion,
 		}
 	}
 }
@@ -16,7 +16,7 @@
 
 //! Cross-Consensus Message format data structures.
 
-use core::{result, convert::TryFrom};
+use core::{result, convert::{TryFrom, TryInto}};
 use alloc::vec::Vec;
 
 use parity_scale_codec::{self, Encode, Decode};
@@ -317,6 +317,7 @@ impl TryFrom<VersionedMultiAsset> for MultiAsset {
 	fn try_from(x: VersionedMultiAsset) -> result::Result<Self, ()> {
 		match x {
 			VersionedMultiAsset::V0(x) => Ok(x),
+			VersionedMultiAsset::V1(x) => x.try_into(),
 		}
 	}
 }
@@ -16,7 +16,7 @@
 
 //! Cross-Consensus Message format data structures.
 
-use core::{result, mem, convert::TryFrom};
+use core::{result, mem, convert::{TryFrom, TryInto}};
 
 use parity_scale_codec::{self, Encode, Decode};
 use super::Junction;
@@ -636,6 +636,7 @@ impl TryFrom<VersionedMultiLocation> for MultiLocation {
 	fn try_from(x: VersionedMultiLocation) -> result::Result<Self, ()> {
 		match x {
 			VersionedMultiLocation::V0(x) => Ok(x),
+			VersionedMultiLocation::V1(x) => x.try_into(),
 		}
 	}
 }
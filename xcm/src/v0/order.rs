@@ -107,6 +107,20 @@ pub enum Order<Call> {
 	/// Errors:
 	#[codec(index = 7)]
 	BuyExecution { fees: MultiAsset, weight: u64, debt: u64, halt_on_error: bool, xcm: Vec<Xcm<Call>> },
+
+	/// Send a `QueryResponse` XCM message to `dest` with a `Response::ExecutionResult` which reports whether the
+	/// orders preceding this one in the same message were executed successfully.
+	///
+	/// If one of the preceding orders fails, then none of the orders following it are executed except for any
+	/// `ReportOutcome`, which reports the error. Errors of the XCM executed within a `BuyExecution` are only seen if
+	/// it has `halt_on_error` set.
+	///
+	/// - `query_id`: An identifier that will be replicated into the returned XCM message.
+	/// - `dest`: A valid destination for the returned XCM message. This may be limited to the current origin.
+	///
+	/// Errors:
+	#[codec(index = 8)]
+	ReportOutcome { #[codec(compact)] query_id: u64, dest: MultiLocation },
 }
 
 pub mod opaque {
@@ -135,6 +149,8 @@ impl<Call> Order<Call> {
 				let xcm = xcm.into_iter().map(Xcm::from).collect();
 				BuyExecution { fees, weight, debt, halt_on_error, xcm }
 			},
+			ReportOutcome { query_id, dest }
+				=> ReportOutcome { query_id, dest },
 		}
 	}
 }
@@ -79,6 +79,18 @@ pub enum Error {
 	TooExpensive,
 	/// The given asset is not handled.
 	AssetNotFound,
+	/// The destination is reachable, but the message cannot be expressed in any XCM version it supports.
+	DestinationUnsupported,
+	/// The assets given to claim do not correspond to any assets which were trapped for the origin.
+	///
+	/// Used by:
+	/// - `ClaimAsset`
+	UnknownClaim,
+	/// The location cannot subscribe since as many locations as there may be are subscribed already.
+	///
+	/// Used by:
+	/// - `SubscribeVersion`
+	TooManySubscriptions,
 }
 
 impl From<()> for Error {
@@ -0,0 +1,111 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Support datastructures for `MultiLocation`, primarily the `Junction` datatype.
+
+use core::{result, convert::TryFrom};
+use alloc::vec::Vec;
+use parity_scale_codec::{self, Encode, Decode};
+use crate::v0::Junction as Junction0;
+pub use crate::v0::{NetworkId, BodyId, BodyPart};
+
+/// A single item in a path to describe the relative location of a consensus system.
+///
+/// Each item assumes a pre-existing location as its context and is defined in terms of it.
+///
+/// Unlike in version 0, there is no `Parent` junction: going up into the parent consensus is expressed by the
+/// `parents` field of `MultiLocation`, so every junction is an interior one.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum Junction {
+	/// An indexed parachain belonging to and operated by the context.
+	///
+	/// Generally used when the context is a Polkadot Relay-chain.
+	Parachain(#[codec(compact)] u32),
+	/// A 32-byte identifier for an account of a specific network that is respected as a sovereign endpoint within
+	/// the context.
+	///
+	/// Generally used when the context is a Substrate-based chain.
+	AccountId32 { network: NetworkId, id: [u8; 32] },
+	/// An 8-byte index for an account of a specific network that is respected as a sovereign endpoint within
+	/// the context.
+	///
+	/// May be used when the context is a Frame-based chain and includes e.g. an indices pallet.
+	AccountIndex64 { network: NetworkId, #[codec(compact)] index: u64 },
+	/// A 20-byte identifier for an account of a specific network that is respected as a sovereign endpoint within
+	/// the context.
+	///
+	/// May be used when the context is an Ethereum or Bitcoin chain or smart-contract.
+	AccountKey20 { network: NetworkId, key: [u8; 20] },
+	/// An instanced, indexed pallet that forms a constituent part of the context.
+	///
+	/// Generally used when the context is a Frame-based chain.
+	PalletInstance(u8),
+	/// A non-descript index within the context location.
+	///
+	/// Usage will vary widely owing to its generality.
+	///
+	/// NOTE: Try to avoid using this and instead use a more specific item.
+	GeneralIndex { #[codec(compact)] id: u128 },
+	/// A nondescript datum acting as a key within the context location.
+	///
+	/// Usage will vary widely owing to its generality.
+	///
+	/// NOTE: Try to avoid using this and instead use a more specific item.
+	GeneralKey(Vec<u8>),
+	/// The unambiguous child.
+	///
+	/// Not currently used except as a fallback when deriving ancestry.
+	OnlyChild,
+	/// A pluralistic body existing within consensus.
+	///
+	/// Typical to be used to represent a governance origin of a chain, but could in principle be used to represent
+	/// things such as multisigs also.
+	Plurality { id: BodyId, part: BodyPart },
+}
+
+impl TryFrom<Junction0> for Junction {
+	type Error = ();
+	fn try_from(value: Junction0) -> result::Result<Self, ()> {
+		Ok(match value {
+			Junction0::Parent => return Err(()),
+			Junction0::Parachain(id) => Self::Parachain(id),
+			Junction0::AccountId32 { network, id } => Self::AccountId32 { network, id },
+			Junction0::AccountIndex64 { network, index } => Self::AccountIndex64 { network, index },
+			Junction0::AccountKey20 { network, key } => Self::AccountKey20 { network, key },
+			Junction0::PalletInstance(index) => Self::PalletInstance(index),
+			Junction0::GeneralIndex { id } => Self::GeneralIndex { id },
+			Junction0::GeneralKey(key) => Self::GeneralKey(key),
+			Junction0::OnlyChild => Self::OnlyChild,
+			Junction0::Plurality { id, part } => Self::Plurality { id, part },
+		})
+	}
+}
+
+impl From<Junction> for Junction0 {
+	fn from(value: Junction) -> Self {
+		match value {
+			Junction::Parachain(id) => Self::Parachain(id),
+			Junction::AccountId32 { network, id } => Self::AccountId32 { network, id },
+			Junction::AccountIndex64 { network, index } => Self::AccountIndex64 { network, index },
+			Junction::AccountKey20 { network, key } => Self::AccountKey20 { network, key },
+			Junction::PalletInstance(index) => Self::PalletInstance(index),
+			Junction::GeneralIndex { id } => Self::GeneralIndex { id },
+			Junction::GeneralKey(key) => Self::GeneralKey(key),
+			Junction::OnlyChild => Self::OnlyChild,
+			Junction::Plurality { id, part } => Self::Plurality { id, part },
+		}
+	}
+}
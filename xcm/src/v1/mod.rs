@@ -0,0 +1,591 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Version 1 of the Cross-Consensus Message format data structures.
+//!
+//! The main differences to version 0 are:
+//! - `MultiLocation` is normalized: it is a count of `parents` followed by the `interior` junctions.
+//! - Assets are described by a struct of their `AssetId` and `Fungibility`, and collections of them are either a
+//!   definite, sorted set of assets (`MultiAssets`) or a single wildcard (`WildMultiAsset`).
+//!
+//! Every message of this version has a version 0 counterpart and vice versa, unless it uses a location or asset that
+//! cannot be expressed in the other version. The conversions are implemented as `TryFrom` between the types of the two
+//! versions and through `VersionedXcm::into_version`.
+
+use core::{result, convert::{TryFrom, TryInto}, fmt::Debug};
+use derivative::Derivative;
+use alloc::vec::Vec;
+use parity_scale_codec::{self, Encode, Decode};
+use crate::{DoubleEncoded, VersionedXcm, VersionedMultiAsset, VersionedMultiLocation, Version};
+use crate::v0::{Xcm as Xcm0, Response as Response0};
+
+mod junction;
+mod multi_asset;
+mod multi_location;
+mod order;
+pub use junction::{Junction, NetworkId, BodyId, BodyPart};
+pub use multi_asset::{
+	AssetId, AssetInstance, Fungibility, MultiAsset, MultiAssets, MultiAssetFilter, WildFungibility, WildMultiAsset,
+};
+pub use multi_location::{MultiLocation, Junctions, MAX_JUNCTIONS};
+pub use order::Order;
+
+// These parts of XCM didn't change between versions.
+pub use crate::v0::{OriginKind, Error};
+
+/// A prelude for importing all types typically used when interacting with XCM messages.
+pub mod prelude {
+	pub use super::junction::{Junction::*, NetworkId, BodyId, BodyPart};
+	pub use super::multi_asset::{
+		MultiAsset, MultiAssets, MultiAssetFilter::{self, *}, AssetId::{self, *},
+		WildMultiAsset::{self, *}, Fungibility::{self, *}, WildFungibility, AssetInstance::{self, *},
+	};
+	pub use super::multi_location::{MultiLocation, Junctions::{self, *}};
+	pub use super::order::Order::{self, *};
+	pub use super::{Xcm::{self, *}, OriginKind, Response, Error as XcmError};
+}
+
+/// Response data to a query.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug)]
+pub enum Response {
+	/// Some assets.
+	Assets(MultiAssets),
+	/// The outcome of executing some orders, as requested by `Order::ReportOutcome`.
+	///
+	/// The error is boxed since it may itself contain an XCM message.
+	ExecutionResult(result::Result<(), alloc::boxed::Box<Error>>),
+	/// The latest XCM version supported by the responder, as requested by `Xcm::SubscribeVersion`.
+	Version(Version),
+}
+
+/// Cross-Consensus Message: A message from one consensus system to another.
+///
+/// Consensus systems that may send and receive messages include blockchains and smart contracts.
+///
+/// All messages are delivered from a known *origin*, expressed as a `MultiLocation`.
+///
+/// This is the inner XCM format and is version-sensitive. Messages are typically passed using the outer
+/// XCM format, known as `VersionedXcm`.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
+#[codec(encode_bound())]
+#[codec(decode_bound())]
+pub enum Xcm<Call> {
+	/// Withdraw asset(s) (`assets`) from the ownership of `origin` and place them into `holding`. Execute the
+	/// orders (`effects`).
+	///
+	/// - `assets`: The asset(s) to be withdrawn into holding.
+	/// - `effects`: The order(s) to execute on the holding register.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 0)]
+	WithdrawAsset { assets: MultiAssets, effects: Vec<Order<Call>> },
+
+	/// Asset(s) (`assets`) have been received into the ownership of this system on the `origin` system.
+	///
+	/// Some orders are given (`effects`) which should be executed once the corresponding derivative assets have
+	/// been placed into `holding`.
+	///
+	/// - `assets`: The asset(s) that are minted into holding.
+	/// - `effects`: The order(s) to execute on the holding register.
+	///
+	/// Safety: `origin` must be trusted to have received and be storing `assets` such that they may later be
+	/// withdrawn should this system send a corresponding message.
+	///
+	/// Kind: *Trusted Indication*.
+	///
+	/// Errors:
+	#[codec(index = 1)]
+	ReserveAssetDeposited { assets: MultiAssets, effects: Vec<Order<Call>> },
+
+	/// Asset(s) (`assets`) have been destroyed on the `origin` system and equivalent assets should be
+	/// created on this system.
+	///
+	/// Some orders are given (`effects`) which should be executed once the corresponding derivative assets have
+	/// been placed into `holding`.
+	///
+	/// - `assets`: The asset(s) that are minted into holding.
+	/// - `effects`: The order(s) to execute on the holding register.
+	///
+	/// Safety: `origin` must be trusted to have irrevocably destroyed the `assets` prior as a consequence of
+	/// sending this message.
+	///
+	/// Kind: *Trusted Indication*.
+	///
+	/// Errors:
+	#[codec(index = 2)]
+	ReceiveTeleportedAsset { assets: MultiAssets, effects: Vec<Order<Call>> },
+
+	/// Indication of the contents of the holding register corresponding to the `QueryHolding` order of `query_id`.
+	///
+	/// - `query_id`: The identifier of the query that resulted in this message being sent.
+	/// - `response`: The message content.
+	///
+	/// Safety: No concerns.
+	///
+	/// Kind: *Information*.
+	///
+	/// Errors:
+	#[codec(index = 3)]
+	QueryResponse { #[codec(compact)] query_id: u64, response: Response },
+
+	/// Withdraw asset(s) (`assets`) from the ownership of `origin` and place equivalent assets under the
+	/// ownership of `beneficiary`.
+	///
+	/// - `assets`: The asset(s) to be withdrawn.
+	/// - `beneficiary`: The new owner for the assets.
+	///
+	/// Safety: No concerns.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 4)]
+	TransferAsset { assets: MultiAssets, beneficiary: MultiLocation },
+
+	/// Withdraw asset(s) (`assets`) from the ownership of `origin` and place equivalent assets under the
+	/// ownership of `dest` within this consensus system (i.e. its sovereign account).
+	///
+	/// Send an onward XCM message to `dest` of `ReserveAssetDeposited` with the given `effects`.
+	///
+	/// - `assets`: The asset(s) to be withdrawn.
+	/// - `dest`: The location whose sovereign account will own the assets and thus the effective beneficiary for the
+	///   assets and the notification target for the reserve asset deposit message.
+	/// - `effects`: The orders that should be contained in the `ReserveAssetDeposited` which is sent onwards to
+	///   `dest`.
+	///
+	/// Safety: No concerns.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 5)]
+	TransferReserveAsset { assets: MultiAssets, dest: MultiLocation, effects: Vec<Order<()>> },
+
+	/// Apply the encoded transaction `call`, whose dispatch-origin should be `origin` as expressed by the kind
+	/// of origin `origin_type`.
+	///
+	/// - `origin_type`: The means of expressing the message origin as a dispatch origin.
+	/// - `max_weight`: The weight of `call`; this should be at least the chain's calculated weight and will
+	///   be used in the weight determination arithmetic.
+	/// - `call`: The encoded transaction to be applied.
+	///
+	/// Safety: No concerns.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 6)]
+	Transact { origin_type: OriginKind, require_weight_at_most: u64, call: DoubleEncoded<Call> },
+
+	/// A message to notify about a new incoming HRMP channel. This message is meant to be sent by the
+	/// relay-chain to a para.
+	///
+	/// - `sender`: The sender in the to-be opened channel. Also, the initiator of the channel opening.
+	/// - `max_message_size`: The maximum size of a message proposed by the sender.
+	/// - `max_capacity`: The maximum number of messages that can be queued in the channel.
+	///
+	/// Safety: The message should originate directly from the relay-chain.
+	///
+	/// Kind: *System Notification*
+	#[codec(index = 7)]
+	HrmpNewChannelOpenRequest {
+		#[codec(compact)] sender: u32,
+		#[codec(compact)] max_message_size: u32,
+		#[codec(compact)] max_capacity: u32,
+	},
+
+	/// A message to notify about that a previously sent open channel request has been accepted by
+	/// the recipient. That means that the channel will be opened during the next relay-chain session
+	/// change. This message is meant to be sent by the relay-chain to a para.
+	///
+	/// Safety: The message should originate directly from the relay-chain.
+	///
+	/// Kind: *System Notification*
+	///
+	/// Errors:
+	#[codec(index = 8)]
+	HrmpChannelAccepted {
+		#[codec(compact)] recipient: u32,
+	},
+
+	/// A message to notify that the other party in an open channel decided to close it. In particular,
+	/// `initiator` is going to close the channel opened from `sender` to the `recipient`. The close
+	/// will be enacted at the next relay-chain session change. This message is meant to be sent by
+	/// the relay-chain to a para.
+	///
+	/// Safety: The message should originate directly from the relay-chain.
+	///
+	/// Kind: *System Notification*
+	///
+	/// Errors:
+	#[codec(index = 9)]
+	HrmpChannelClosing {
+		#[codec(compact)] initiator: u32,
+		#[codec(compact)] sender: u32,
+		#[codec(compact)] recipient: u32,
+	},
+
+	/// A message to indicate that the embedded XCM is actually arriving on behalf of some consensus
+	/// location within the origin.
+	///
+	/// Kind: *Instruction*
+	///
+	/// Errors:
+	#[codec(index = 10)]
+	RelayedFrom {
+		who: Junctions,
+		message: alloc::boxed::Box<Xcm<Call>>,
+	},
+
+	/// Withdraw asset(s) (`assets`) which were trapped when a message from this origin was previously executed,
+	/// and place them into holding. Execute the orders (`effects`).
+	///
+	/// - `assets`: The asset(s) to be claimed; this must match exactly the assets which were trapped.
+	/// - `ticket`: Information regarding the trapping of the assets, as required by the asset trap of the
+	///   executing chain. Generally `Here`.
+	/// - `effects`: The order(s) to execute on the holding account.
+	///
+	/// Kind: *Instruction*.
+	///
+	/// Errors:
+	#[codec(index = 11)]
+	ClaimAsset { assets: MultiAssets, ticket: MultiLocation, effects: Vec<Order<Call>> },
+
+	/// Ask the destination system to respond with the most recent version of XCM that it supports, and to
+	/// notify the origin whenever that changes.
+	///
+	/// The response is a `QueryResponse` of `Response::Version`, sent once immediately and then again each time
+	/// the version changes, until an `UnsubscribeVersion` is received.
+	///
+	/// - `query_id`: The identifier of the query which the responses should carry.
+	/// - `max_response_weight`: The maximum amount of weight that the `QueryResponse` should be allowed to take
+	///   when executed on the origin.
+	///
+	/// Kind: *Instruction*
+	///
+	/// Errors:
+	#[codec(index = 12)]
+	SubscribeVersion {
+		#[codec(compact)] query_id: u64,
+		#[codec(compact)] max_response_weight: u64,
+	},
+
+	/// Cancel the effect of a previous `SubscribeVersion` instruction from the origin.
+	///
+	/// Kind: *Instruction*
+	///
+	/// Errors:
+	#[codec(index = 13)]
+	UnsubscribeVersion,
+}
+
+impl<Call> From<Xcm<Call>> for VersionedXcm<Call> {
+	fn from(x: Xcm<Call>) -> Self {
+		VersionedXcm::V1(x)
+	}
+}
+
+impl<Call> TryFrom<VersionedXcm<Call>> for Xcm<Call> {
+	type Error = ();
+	fn try_from(x: VersionedXcm<Call>) -> result::Result<Self, ()> {
+		match x {
+			VersionedXcm::V0(x) => x.try_into(),
+			VersionedXcm::V1(x) => Ok(x),
+		}
+	}
+}
+
+impl From<MultiLocation> for VersionedMultiLocation {
+	fn from(x: MultiLocation) -> Self {
+		VersionedMultiLocation::V1(x)
+	}
+}
+
+impl TryFrom<VersionedMultiLocation> for MultiLocation {
+	type Error = ();
+	fn try_from(x: VersionedMultiLocation) -> result::Result<Self, ()> {
+		match x {
+			VersionedMultiLocation::V0(x) => x.try_into(),
+			VersionedMultiLocation::V1(x) => Ok(x),
+		}
+	}
+}
+
+impl From<MultiAsset> for VersionedMultiAsset {
+	fn from(x: MultiAsset) -> Self {
+		VersionedMultiAsset::V1(x)
+	}
+}
+
+impl TryFrom<VersionedMultiAsset> for MultiAsset {
+	type Error = ();
+	fn try_from(x: VersionedMultiAsset) -> result::Result<Self, ()> {
+		match x {
+			VersionedMultiAsset::V0(x) => x.try_into(),
+			VersionedMultiAsset::V1(x) => Ok(x),
+		}
+	}
+}
+
+impl<Call> Xcm<Call> {
+	pub fn into<C>(self) -> Xcm<C> { Xcm::from(self) }
+	pub fn from<C>(xcm: Xcm<C>) -> Self {
+		use Xcm::*;
+		match xcm {
+			WithdrawAsset { assets, effects }
+			=> WithdrawAsset { assets, effects: effects.into_iter().map(Order::into).collect() },
+			ReserveAssetDeposited { assets, effects }
+			=> ReserveAssetDeposited { assets, effects: effects.into_iter().map(Order::into).collect() },
+			ReceiveTeleportedAsset { assets, effects }
+			=> ReceiveTeleportedAsset { assets, effects: effects.into_iter().map(Order::into).collect() },
+			QueryResponse { query_id, response }
+			=> QueryResponse { query_id, response },
+			TransferAsset { assets, beneficiary }
+			=> TransferAsset { assets, beneficiary },
+			TransferReserveAsset { assets, dest, effects }
+			=> TransferReserveAsset { assets, dest, effects },
+			HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity }
+			=> HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity },
+			HrmpChannelAccepted { recipient }
+			=> HrmpChannelAccepted { recipient },
+			HrmpChannelClosing { initiator, sender, recipient }
+			=> HrmpChannelClosing { initiator, sender, recipient },
+			Transact { origin_type, require_weight_at_most, call }
+			=> Transact { origin_type, require_weight_at_most, call: call.into() },
+			RelayedFrom { who, message }
+			=> RelayedFrom { who, message: alloc::boxed::Box::new((*message).into()) },
+			ClaimAsset { assets, ticket, effects }
+			=> ClaimAsset { assets, ticket, effects: effects.into_iter().map(Order::into).collect() },
+			SubscribeVersion { query_id, max_response_weight }
+			=> SubscribeVersion { query_id, max_response_weight },
+			UnsubscribeVersion
+			=> UnsubscribeVersion,
+		}
+	}
+}
+
+impl<Call> TryFrom<Xcm0<Call>> for Xcm<Call> {
+	type Error = ();
+	fn try_from(old: Xcm0<Call>) -> result::Result<Xcm<Call>, ()> {
+		use Xcm::*;
+		Ok(match old {
+			Xcm0::WithdrawAsset { assets, effects } => WithdrawAsset {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm0::ReserveAssetDeposit { assets, effects } => ReserveAssetDeposited {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm0::TeleportAsset { assets, effects } => ReceiveTeleportedAsset {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm0::QueryResponse { query_id, response: Response0::Assets(assets) } => QueryResponse {
+				query_id,
+				response: Response::Assets(assets.try_into()?),
+			},
+			Xcm0::QueryResponse { query_id, response: Response0::ExecutionResult(result) } => QueryResponse {
+				query_id,
+				response: Response::ExecutionResult(result),
+			},
+			Xcm0::QueryResponse { query_id, response: Response0::Version(version) } => QueryResponse {
+				query_id,
+				response: Response::Version(version),
+			},
+			Xcm0::TransferAsset { assets, dest } => TransferAsset {
+				assets: assets.try_into()?,
+				beneficiary: dest.try_into()?,
+			},
+			Xcm0::TransferReserveAsset { assets, dest, effects } => TransferReserveAsset {
+				assets: assets.try_into()?,
+				dest: dest.try_into()?,
+				effects: effects.into_iter()
+					.map(Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm0::HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity }
+			=> HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity },
+			Xcm0::HrmpChannelAccepted { recipient }
+			=> HrmpChannelAccepted { recipient },
+			Xcm0::HrmpChannelClosing { initiator, sender, recipient }
+			=> HrmpChannelClosing { initiator, sender, recipient },
+			Xcm0::Transact { origin_type, require_weight_at_most, call }
+			=> Transact { origin_type, require_weight_at_most, call: call.into() },
+			Xcm0::RelayedFrom { who, message } => RelayedFrom {
+				who: who.try_into()?,
+				message: alloc::boxed::Box::new((*message).try_into()?),
+			},
+			Xcm0::ClaimAsset { assets, ticket, effects } => ClaimAsset {
+				assets: assets.try_into()?,
+				ticket: ticket.try_into()?,
+				effects: effects.into_iter()
+					.map(Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm0::SubscribeVersion { query_id, max_response_weight }
+			=> SubscribeVersion { query_id, max_response_weight },
+			Xcm0::UnsubscribeVersion
+			=> UnsubscribeVersion,
+		})
+	}
+}
+
+impl<Call> TryFrom<Xcm<Call>> for Xcm0<Call> {
+	type Error = ();
+	fn try_from(new: Xcm<Call>) -> result::Result<Xcm0<Call>, ()> {
+		use Xcm0::*;
+		Ok(match new {
+			Xcm::WithdrawAsset { assets, effects } => WithdrawAsset {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(crate::v0::Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm::ReserveAssetDeposited { assets, effects } => ReserveAssetDeposit {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(crate::v0::Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm::ReceiveTeleportedAsset { assets, effects } => TeleportAsset {
+				assets: assets.try_into()?,
+				effects: effects.into_iter()
+					.map(crate::v0::Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm::QueryResponse { query_id, response: Response::Assets(assets) } => QueryResponse {
+				query_id,
+				response: Response0::Assets(assets.try_into()?),
+			},
+			Xcm::QueryResponse { query_id, response: Response::ExecutionResult(result) } => QueryResponse {
+				query_id,
+				response: Response0::ExecutionResult(result),
+			},
+			Xcm::QueryResponse { query_id, response: Response::Version(version) } => QueryResponse {
+				query_id,
+				response: Response0::Version(version),
+			},
+			Xcm::TransferAsset { assets, beneficiary } => TransferAsset {
+				assets: assets.try_into()?,
+				dest: beneficiary.try_into()?,
+			},
+			Xcm::TransferReserveAsset { assets, dest, effects } => TransferReserveAsset {
+				assets: assets.try_into()?,
+				dest: dest.try_into()?,
+				effects: effects.into_iter()
+					.map(crate::v0::Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm::HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity }
+			=> HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity },
+			Xcm::HrmpChannelAccepted { recipient }
+			=> HrmpChannelAccepted { recipient },
+			Xcm::HrmpChannelClosing { initiator, sender, recipient }
+			=> HrmpChannelClosing { initiator, sender, recipient },
+			Xcm::Transact { origin_type, require_weight_at_most, call }
+			=> Transact { origin_type, require_weight_at_most, call: call.into() },
+			Xcm::RelayedFrom { who, message } => RelayedFrom {
+				who: who.try_into()?,
+				message: alloc::boxed::Box::new((*message).try_into()?),
+			},
+			Xcm::ClaimAsset { assets, ticket, effects } => ClaimAsset {
+				assets: assets.try_into()?,
+				ticket: ticket.try_into()?,
+				effects: effects.into_iter()
+					.map(crate::v0::Order::try_from)
+					.collect::<result::Result<_, _>>()?,
+			},
+			Xcm::SubscribeVersion { query_id, max_response_weight }
+			=> SubscribeVersion { query_id, max_response_weight },
+			Xcm::UnsubscribeVersion
+			=> UnsubscribeVersion,
+		})
+	}
+}
+
+pub mod opaque {
+	/// The basic concrete type of `generic::Xcm`, which doesn't make any assumptions about the format of a
+	/// call other than it is pre-encoded.
+	pub type Xcm = super::Xcm<()>;
+
+	pub use super::order::opaque::*;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::prelude::*;
+	use crate::v0::{self, prelude as prelude0};
+	use core::convert::TryFrom;
+	use alloc::vec;
+
+	#[test]
+	fn teleport_converts_both_ways() {
+		let old: v0::Xcm<()> = prelude0::TeleportAsset {
+			assets: vec![prelude0::ConcreteFungible { id: prelude0::X1(prelude0::Parent), amount: 100 }],
+			effects: vec![
+				prelude0::BuyExecution {
+					fees: prelude0::All,
+					weight: 0,
+					debt: 1000,
+					halt_on_error: false,
+					xcm: vec![],
+				},
+				prelude0::DepositAsset {
+					assets: vec![prelude0::All],
+					dest: prelude0::X1(prelude0::AccountIndex64 { network: NetworkId::Any, index: 1 }),
+				},
+			],
+		};
+		let new: Xcm<()> = ReceiveTeleportedAsset {
+			assets: vec![MultiAsset::from((MultiLocation::parent(), 100u128))].into(),
+			effects: vec![
+				BuyExecution {
+					fees: Wild(All),
+					weight: 0,
+					debt: 1000,
+					halt_on_error: false,
+					instructions: vec![],
+				},
+				DepositAsset {
+					assets: Wild(All),
+					max_assets: u32::max_value(),
+					beneficiary: X1(AccountIndex64 { network: NetworkId::Any, index: 1 }).into(),
+				},
+			],
+		};
+		assert_eq!(Xcm::try_from(old.clone()), Ok(new.clone()));
+		assert_eq!(v0::Xcm::try_from(new), Ok(old));
+	}
+
+	#[test]
+	fn relayed_from_with_parent_cannot_be_converted() {
+		let old: v0::Xcm<()> = prelude0::RelayedFrom {
+			who: prelude0::X1(prelude0::Parent),
+			message: alloc::boxed::Box::new(prelude0::HrmpChannelAccepted { recipient: 1 }),
+		};
+		assert_eq!(Xcm::try_from(old), Err(()));
+	}
+}
@@ -0,0 +1,539 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cross-Consensus Message format asset data structures.
+//!
+//! This encompasses four types for representing assets:
+//! - `MultiAsset`: A description of a single asset, either an instance of a non-fungible or some amount of a fungible.
+//! - `MultiAssets`: A collection of `MultiAsset`s. These are stored in a `Vec`, sorted and
+//!   with fungibles of the same identity merged.
+//! - `WildMultiAsset`: A single asset wildcard, this can either be "all" assets, or all assets of a specific kind.
+//! - `MultiAssetFilter`: A combination of `WildMultiAsset` and `MultiAssets` designed for efficiently filtering an XCM
+//!   holding account.
+
+use core::{result, convert::{TryFrom, TryInto}};
+use alloc::{vec, vec::Vec};
+use parity_scale_codec::{self, Encode, Decode};
+use super::MultiLocation;
+use crate::v0::MultiAsset as MultiAsset0;
+pub use crate::v0::AssetInstance;
+
+/// Classification of an asset being concrete or abstract.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum AssetId {
+	/// A specific location identifying an asset.
+	Concrete(MultiLocation),
+	/// An abstract location; this is a name which may mean different specific locations on different chains at
+	/// different times.
+	Abstract(Vec<u8>),
+}
+
+impl From<MultiLocation> for AssetId {
+	fn from(x: MultiLocation) -> Self {
+		Self::Concrete(x)
+	}
+}
+
+impl From<Vec<u8>> for AssetId {
+	fn from(x: Vec<u8>) -> Self {
+		Self::Abstract(x)
+	}
+}
+
+impl AssetId {
+	/// Prepend a `MultiLocation` to a concrete asset, giving it a new root location.
+	pub fn reanchor(&mut self, prepend: &MultiLocation) -> result::Result<(), ()> {
+		if let AssetId::Concrete(ref mut l) = self {
+			l.prepend_with(prepend.clone()).map_err(|_| ())?;
+		}
+		Ok(())
+	}
+
+	/// Use the value of `self` along with a `fun` fungibility specifier to create the corresponding `MultiAsset`
+	/// value.
+	pub fn into_multiasset(self, fun: Fungibility) -> MultiAsset {
+		MultiAsset { fun, id: self }
+	}
+
+	/// Use the value of `self` along with a `fun` fungibility specifier to create the corresponding
+	/// `WildMultiAsset` wildcard (`AllOf`) value.
+	pub fn into_wild(self, fun: WildFungibility) -> WildMultiAsset {
+		WildMultiAsset::AllOf { fun, id: self }
+	}
+}
+
+/// Classification of whether an asset is fungible or not, along with a mandatory amount or instance.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum Fungibility {
+	/// A fungible asset; we record a number of units, as a `u128` in the inner item.
+	Fungible(#[codec(compact)] u128),
+	/// A non-fungible asset. We record the instance identifier in the inner item. Only one asset of each
+	/// instance identifier may ever be in existence at once.
+	NonFungible(AssetInstance),
+}
+
+impl Fungibility {
+	/// Returns `true` if this is of the given kind of fungibility.
+	pub fn is_kind(&self, w: WildFungibility) -> bool {
+		use Fungibility::*;
+		use WildFungibility::{Fungible as WildFungible, NonFungible as WildNonFungible};
+		matches!((self, w), (Fungible(_), WildFungible) | (NonFungible(_), WildNonFungible))
+	}
+}
+
+impl From<u128> for Fungibility {
+	fn from(amount: u128) -> Fungibility {
+		Fungibility::Fungible(amount)
+	}
+}
+
+impl From<AssetInstance> for Fungibility {
+	fn from(instance: AssetInstance) -> Fungibility {
+		Fungibility::NonFungible(instance)
+	}
+}
+
+/// A single asset: some amount of a fungible or an instance of a non-fungible, identified by an `AssetId`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub struct MultiAsset {
+	/// The overall asset identity (aka *class*, in the case of a non-fungible).
+	pub id: AssetId,
+	/// The fungibility of the asset, which contains either the amount (in the case of a fungible asset) or the
+	/// *instance ID*, the secondary asset identifier.
+	pub fun: Fungibility,
+}
+
+impl<A: Into<AssetId>, B: Into<Fungibility>> From<(A, B)> for MultiAsset {
+	fn from((id, fun): (A, B)) -> MultiAsset {
+		MultiAsset { fun: fun.into(), id: id.into() }
+	}
+}
+
+impl MultiAsset {
+	/// Returns `true` if this is a fungible asset.
+	pub fn is_fungible(&self, maybe_id: Option<&AssetId>) -> bool {
+		use Fungibility::*;
+		matches!(self.fun, Fungible(..)) && maybe_id.map_or(true, |i| i == &self.id)
+	}
+
+	/// Returns `true` if this is a non-fungible asset.
+	pub fn is_non_fungible(&self, maybe_id: Option<&AssetId>) -> bool {
+		use Fungibility::*;
+		matches!(self.fun, NonFungible(..)) && maybe_id.map_or(true, |i| i == &self.id)
+	}
+
+	/// Prepend a `MultiLocation` to a concrete asset, giving it a new root location.
+	pub fn reanchor(&mut self, prepend: &MultiLocation) -> result::Result<(), ()> {
+		self.id.reanchor(prepend)
+	}
+
+	/// Returns `true` if `self` is a super-set of the given `inner`.
+	pub fn contains(&self, inner: &MultiAsset) -> bool {
+		use Fungibility::*;
+		if self.id == inner.id {
+			match (&self.fun, &inner.fun) {
+				(Fungible(a), Fungible(i)) if a >= i => return true,
+				(NonFungible(a), NonFungible(i)) if a == i => return true,
+				_ => (),
+			}
+		}
+		false
+	}
+}
+
+impl TryFrom<MultiAsset0> for MultiAsset {
+	type Error = ();
+	fn try_from(old: MultiAsset0) -> result::Result<MultiAsset, ()> {
+		use AssetId::*;
+		use Fungibility::*;
+		let (id, fun) = match old {
+			MultiAsset0::ConcreteFungible { id, amount } => (Concrete(id.try_into()?), Fungible(amount)),
+			MultiAsset0::ConcreteNonFungible { class, instance } =>
+				(Concrete(class.try_into()?), NonFungible(instance)),
+			MultiAsset0::AbstractFungible { id, amount } => (Abstract(id), Fungible(amount)),
+			MultiAsset0::AbstractNonFungible { class, instance } => (Abstract(class), NonFungible(instance)),
+			_ => return Err(()),
+		};
+		Ok(MultiAsset { id, fun })
+	}
+}
+
+impl TryFrom<MultiAsset> for MultiAsset0 {
+	type Error = ();
+	fn try_from(new: MultiAsset) -> result::Result<MultiAsset0, ()> {
+		use AssetId::*;
+		use Fungibility::*;
+		Ok(match (new.id, new.fun) {
+			(Concrete(id), Fungible(amount)) => MultiAsset0::ConcreteFungible { id: id.try_into()?, amount },
+			(Concrete(class), NonFungible(instance)) =>
+				MultiAsset0::ConcreteNonFungible { class: class.try_into()?, instance },
+			(Abstract(id), Fungible(amount)) => MultiAsset0::AbstractFungible { id, amount },
+			(Abstract(class), NonFungible(instance)) => MultiAsset0::AbstractNonFungible { class, instance },
+		})
+	}
+}
+
+/// A `Vec` of `MultiAsset`s. There may be no duplicate fungible items in here and when decoding, they must be sorted.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Debug)]
+pub struct MultiAssets(Vec<MultiAsset>);
+
+impl Decode for MultiAssets {
+	fn decode<I: parity_scale_codec::Input>(input: &mut I) -> result::Result<Self, parity_scale_codec::Error> {
+		Self::from_sorted_and_deduplicated(Vec::<MultiAsset>::decode(input)?)
+			.map_err(|()| "Out of order".into())
+	}
+}
+
+impl From<Vec<MultiAsset>> for MultiAssets {
+	fn from(mut assets: Vec<MultiAsset>) -> Self {
+		let mut res = Vec::with_capacity(assets.len());
+		if !assets.is_empty() {
+			assets.sort();
+			let mut iter = assets.into_iter();
+			if let Some(first) = iter.next() {
+				let last = iter.fold(first, |a, b| -> MultiAsset {
+					match (a, b) {
+						(
+							MultiAsset { fun: Fungibility::Fungible(a_amount), id: a_id },
+							MultiAsset { fun: Fungibility::Fungible(b_amount), id: b_id },
+						) if a_id == b_id => MultiAsset {
+							id: a_id,
+							fun: Fungibility::Fungible(a_amount.saturating_add(b_amount)),
+						},
+						(
+							MultiAsset { fun: Fungibility::NonFungible(a_instance), id: a_id },
+							MultiAsset { fun: Fungibility::NonFungible(b_instance), id: b_id },
+						) if a_id == b_id && a_instance == b_instance =>
+							MultiAsset { fun: Fungibility::NonFungible(a_instance), id: a_id },
+						(to_push, to_remember) => {
+							res.push(to_push);
+							to_remember
+						},
+					}
+				});
+				res.push(last);
+			}
+		}
+		Self(res)
+	}
+}
+
+impl From<MultiAsset> for MultiAssets {
+	fn from(x: MultiAsset) -> Self {
+		Self(vec![x])
+	}
+}
+
+impl MultiAssets {
+	/// A new (empty) value.
+	pub fn new() -> Self {
+		Self(Vec::new())
+	}
+
+	/// Create a new instance of `MultiAssets` from a `Vec<MultiAsset>` whose contents are sorted and
+	/// which contain no duplicates.
+	///
+	/// Returns `Ok` if the operation succeeds and `Err` if `r` is out of order or had duplicates. If you can't
+	/// guarantee that `r` is sorted and deduplicated, then use `From::<Vec<MultiAsset>>::from` which is infallible.
+	pub fn from_sorted_and_deduplicated(r: Vec<MultiAsset>) -> result::Result<Self, ()> {
+		if r.is_empty() {
+			return Ok(Self(Vec::new()))
+		}
+		r.iter().skip(1).try_fold(&r[0], |a, b| -> result::Result<&MultiAsset, ()> {
+			if a.id < b.id || a < b && (a.is_non_fungible(None) || b.is_non_fungible(None)) {
+				Ok(b)
+			} else {
+				Err(())
+			}
+		})?;
+		Ok(Self(r))
+	}
+
+	/// Add some asset onto the list, saturating. This is quite a laborious operation since it maintains the ordering.
+	pub fn push(&mut self, a: MultiAsset) {
+		if let Fungibility::Fungible(ref amount) = a.fun {
+			for asset in self.0.iter_mut().filter(|x| x.id == a.id) {
+				if let Fungibility::Fungible(ref mut balance) = asset.fun {
+					*balance = balance.saturating_add(*amount);
+					return
+				}
+			}
+		}
+		self.0.push(a);
+		self.0.sort();
+	}
+
+	/// Returns `true` if this definitely represents no asset.
+	pub fn is_none(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns true if `self` contains the given `inner` asset.
+	pub fn contains(&self, inner: &MultiAsset) -> bool {
+		self.0.iter().any(|i| i.contains(inner))
+	}
+
+	/// Consume `self` and return the inner vec.
+	pub fn drain(self) -> Vec<MultiAsset> {
+		self.0
+	}
+
+	/// Return a reference to the inner vec.
+	pub fn inner(&self) -> &Vec<MultiAsset> {
+		&self.0
+	}
+
+	/// Return the number of distinct asset instances contained.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Prepend a `MultiLocation` to any concrete asset items, giving it a new root location.
+	pub fn reanchor(&mut self, prepend: &MultiLocation) -> result::Result<(), ()> {
+		self.0.iter_mut().try_for_each(|i| i.reanchor(prepend))
+	}
+}
+
+impl TryFrom<Vec<MultiAsset0>> for MultiAssets {
+	type Error = ();
+	fn try_from(old: Vec<MultiAsset0>) -> result::Result<MultiAssets, ()> {
+		let v = old
+			.into_iter()
+			// `None` carries no asset, so it has no counterpart in a definite set of assets.
+			.filter(|a| *a != MultiAsset0::None)
+			.map(MultiAsset::try_from)
+			.collect::<result::Result<Vec<_>, ()>>()?;
+		Ok(v.into())
+	}
+}
+
+impl TryFrom<MultiAssets> for Vec<MultiAsset0> {
+	type Error = ();
+	fn try_from(new: MultiAssets) -> result::Result<Vec<MultiAsset0>, ()> {
+		new.0.into_iter().map(MultiAsset0::try_from).collect()
+	}
+}
+
+/// Classification of whether an asset is fungible or not.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum WildFungibility {
+	Fungible,
+	NonFungible,
+}
+
+/// A wildcard representing a set of assets.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum WildMultiAsset {
+	/// All assets in the holding register.
+	All,
+	/// All assets in the holding register of a given fungibility and ID.
+	AllOf { id: AssetId, fun: WildFungibility },
+}
+
+impl WildMultiAsset {
+	/// Returns true if `self` is a super-set of the given `inner`.
+	///
+	/// Typically, any wildcard is never contained in anything else, and a wildcard can contain any other non-wildcard.
+	/// For more details, see the implementation and tests.
+	pub fn contains(&self, inner: &MultiAsset) -> bool {
+		use WildMultiAsset::*;
+		match self {
+			AllOf { fun, id } => inner.fun.is_kind(*fun) && &inner.id == id,
+			All => true,
+		}
+	}
+
+	/// Prepend a `MultiLocation` to any concrete asset components, giving it a new root location.
+	pub fn reanchor(&mut self, prepend: &MultiLocation) -> result::Result<(), ()> {
+		use WildMultiAsset::*;
+		match self {
+			AllOf { ref mut id, .. } => id.reanchor(prepend).map_err(|_| ()),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl TryFrom<MultiAsset0> for WildMultiAsset {
+	type Error = ();
+	fn try_from(old: MultiAsset0) -> result::Result<WildMultiAsset, ()> {
+		use AssetId::*;
+		use WildFungibility::*;
+		let (id, fun) = match old {
+			MultiAsset0::All => return Ok(WildMultiAsset::All),
+			MultiAsset0::AllConcreteFungible { id } => (Concrete(id.try_into()?), Fungible),
+			MultiAsset0::AllConcreteNonFungible { class } => (Concrete(class.try_into()?), NonFungible),
+			MultiAsset0::AllAbstractFungible { id } => (Abstract(id), Fungible),
+			MultiAsset0::AllAbstractNonFungible { class } => (Abstract(class), NonFungible),
+			_ => return Err(()),
+		};
+		Ok(WildMultiAsset::AllOf { id, fun })
+	}
+}
+
+impl TryFrom<WildMultiAsset> for MultiAsset0 {
+	type Error = ();
+	fn try_from(new: WildMultiAsset) -> result::Result<MultiAsset0, ()> {
+		use AssetId::*;
+		use WildFungibility::*;
+		Ok(match new {
+			WildMultiAsset::All => MultiAsset0::All,
+			WildMultiAsset::AllOf { id: Concrete(id), fun: Fungible } =>
+				MultiAsset0::AllConcreteFungible { id: id.try_into()? },
+			WildMultiAsset::AllOf { id: Concrete(class), fun: NonFungible } =>
+				MultiAsset0::AllConcreteNonFungible { class: class.try_into()? },
+			WildMultiAsset::AllOf { id: Abstract(id), fun: Fungible } =>
+				MultiAsset0::AllAbstractFungible { id },
+			WildMultiAsset::AllOf { id: Abstract(class), fun: NonFungible } =>
+				MultiAsset0::AllAbstractNonFungible { class },
+		})
+	}
+}
+
+impl<A: Into<AssetId>, B: Into<WildFungibility>> From<(A, B)> for WildMultiAsset {
+	fn from((id, fun): (A, B)) -> WildMultiAsset {
+		WildMultiAsset::AllOf { fun: fun.into(), id: id.into() }
+	}
+}
+
+/// `MultiAsset` collection, either `MultiAssets` or a single wildcard.
+///
+/// Note: Vectors of wildcards whose encoding is supported in XCM v0 are unsupported
+/// in this implementation and will result in a decode error.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum MultiAssetFilter {
+	Definite(MultiAssets),
+	Wild(WildMultiAsset),
+}
+
+impl<T: Into<WildMultiAsset>> From<T> for MultiAssetFilter {
+	fn from(x: T) -> Self {
+		Self::Wild(x.into())
+	}
+}
+
+impl From<MultiAsset> for MultiAssetFilter {
+	fn from(x: MultiAsset) -> Self {
+		Self::Definite(vec![x].into())
+	}
+}
+
+impl From<Vec<MultiAsset>> for MultiAssetFilter {
+	fn from(x: Vec<MultiAsset>) -> Self {
+		Self::Definite(x.into())
+	}
+}
+
+impl From<MultiAssets> for MultiAssetFilter {
+	fn from(x: MultiAssets) -> Self {
+		Self::Definite(x)
+	}
+}
+
+impl MultiAssetFilter {
+	/// Returns true if `self` is a super-set of the given `inner`.
+	///
+	/// Typically, any wildcard is never contained in anything else, and a wildcard can contain any other non-wildcard.
+	/// For more details, see the implementation and tests.
+	pub fn contains(&self, inner: &MultiAsset) -> bool {
+		match self {
+			MultiAssetFilter::Definite(ref assets) => assets.contains(inner),
+			MultiAssetFilter::Wild(ref wild) => wild.contains(inner),
+		}
+	}
+
+	/// Prepend a `MultiLocation` to any concrete asset components, giving it a new root location.
+	pub fn reanchor(&mut self, prepend: &MultiLocation) -> result::Result<(), ()> {
+		match self {
+			MultiAssetFilter::Definite(ref mut assets) => assets.reanchor(prepend),
+			MultiAssetFilter::Wild(ref mut wild) => wild.reanchor(prepend),
+		}
+	}
+}
+
+impl TryFrom<Vec<MultiAsset0>> for MultiAssetFilter {
+	type Error = ();
+	fn try_from(mut old: Vec<MultiAsset0>) -> result::Result<MultiAssetFilter, ()> {
+		if old.len() == 1 && old[0].is_wildcard() {
+			return Ok(MultiAssetFilter::Wild(old.pop().ok_or(())?.try_into()?))
+		}
+		MultiAssets::try_from(old).map(MultiAssetFilter::Definite)
+	}
+}
+
+impl TryFrom<MultiAssetFilter> for Vec<MultiAsset0> {
+	type Error = ();
+	fn try_from(new: MultiAssetFilter) -> result::Result<Vec<MultiAsset0>, ()> {
+		match new {
+			MultiAssetFilter::Definite(assets) => assets.try_into(),
+			MultiAssetFilter::Wild(wild) => Ok(vec![wild.try_into()?]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::v1::{Junctions::*, Junction::*};
+
+	#[test]
+	fn multi_assets_from_vec_merges_fungibles() {
+		let dot: AssetId = MultiLocation::parent().into();
+		let para: AssetId = MultiLocation::new(1, X1(Parachain(1000))).into();
+		let assets = MultiAssets::from(vec![
+			(para.clone(), 5u128).into(),
+			(dot.clone(), 10u128).into(),
+			(dot.clone(), 20u128).into(),
+		]);
+		assert_eq!(assets.inner(), &vec![(dot, 30u128).into(), (para, 5u128).into()]);
+
+		// a decoded value must be sorted and deduplicated already.
+		let unsorted = vec![
+			MultiAsset::from((vec![2u8], 1u128)),
+			MultiAsset::from((vec![1u8], 1u128)),
+		];
+		assert!(MultiAssets::decode(&mut &unsorted.encode()[..]).is_err());
+		let duplicated = vec![
+			MultiAsset::from((vec![1u8], 1u128)),
+			MultiAsset::from((vec![1u8], 2u128)),
+		];
+		assert!(MultiAssets::decode(&mut &duplicated.encode()[..]).is_err());
+	}
+
+	#[test]
+	fn conversion_from_v0_works() {
+		use crate::v0::{MultiLocation as MultiLocation0, Junction as Junction0};
+
+		let old = vec![
+			MultiAsset0::ConcreteFungible { id: MultiLocation0::X1(Junction0::Parent), amount: 100 },
+			MultiAsset0::None,
+		];
+		let new = MultiAssetFilter::try_from(old).unwrap();
+		assert_eq!(new, MultiAssetFilter::from(MultiAsset::from((MultiLocation::parent(), 100u128))));
+		assert_eq!(
+			Vec::<MultiAsset0>::try_from(new),
+			Ok(vec![MultiAsset0::ConcreteFungible { id: MultiLocation0::X1(Junction0::Parent), amount: 100 }]),
+		);
+
+		let old = vec![MultiAsset0::AllAbstractFungible { id: vec![1] }];
+		let new = MultiAssetFilter::try_from(old.clone()).unwrap();
+		assert_eq!(new, MultiAssetFilter::from((vec![1u8], WildFungibility::Fungible)));
+		assert_eq!(Vec::<MultiAsset0>::try_from(new), Ok(old));
+
+		// more than one wildcard has no equivalent.
+		let old = vec![MultiAsset0::All, MultiAsset0::All];
+		assert!(MultiAssetFilter::try_from(old).is_err());
+	}
+}
@@ -0,0 +1,583 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cross-Consensus Message format data structures.
+
+use core::{result, mem, convert::TryFrom};
+
+use parity_scale_codec::{self, Encode, Decode};
+use super::Junction;
+use crate::v0::{MultiLocation as MultiLocation0, Junction as Junction0};
+
+/// A relative path between state-bearing consensus systems.
+///
+/// A location in a consensus system is defined as an *isolatable state machine* held within global consensus. The
+/// location in question need not have a sophisticated consensus algorithm of its own; a single account within
+/// Ethereum, for example, could be considered a location.
+///
+/// A `MultiLocation` is a *relative identifier*, meaning that it can only be used to define the relative path
+/// between two locations, and cannot generally be used to refer to a location universally. Unlike in version 0,
+/// it is not a plain list of junctions: the number of times to go up into the parent location is given by
+/// `parents`, and only then is the path descended along the `interior` junctions. This makes it impossible to
+/// express a non-normalized location such as one with a parent junction following an interior one.
+///
+/// The `MultiLocation` value of `{ parents: 0, interior: Here }` simply refers to the interpreting consensus system.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub struct MultiLocation {
+	/// The number of parent junctions at the beginning of this `MultiLocation`.
+	pub parents: u8,
+	/// The interior (i.e. non-parent) junctions that this `MultiLocation` contains.
+	pub interior: Junctions,
+}
+
+/// Maximum number of interior junctions a multilocation can contain.
+pub const MAX_JUNCTIONS: usize = 8;
+
+/// Non-parent junctions that can be constructed, up to the length of 8. This specific `Junctions`
+/// implementation uses a Rust `enum` in order to make pattern matching easier.
+///
+/// Parent junctions cannot be constructed with this type. Refer to `MultiLocation` for
+/// instructions on constructing parent junctions.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum Junctions {
+	/// The interpreting consensus system.
+	Here,
+	/// A relative path comprising 1 junction.
+	X1(Junction),
+	/// A relative path comprising 2 junctions.
+	X2(Junction, Junction),
+	/// A relative path comprising 3 junctions.
+	X3(Junction, Junction, Junction),
+	/// A relative path comprising 4 junctions.
+	X4(Junction, Junction, Junction, Junction),
+	/// A relative path comprising 5 junctions.
+	X5(Junction, Junction, Junction, Junction, Junction),
+	/// A relative path comprising 6 junctions.
+	X6(Junction, Junction, Junction, Junction, Junction, Junction),
+	/// A relative path comprising 7 junctions.
+	X7(Junction, Junction, Junction, Junction, Junction, Junction, Junction),
+	/// A relative path comprising 8 junctions.
+	X8(Junction, Junction, Junction, Junction, Junction, Junction, Junction, Junction),
+}
+
+impl Default for MultiLocation {
+	fn default() -> Self {
+		Self::here()
+	}
+}
+
+impl From<Junctions> for MultiLocation {
+	fn from(interior: Junctions) -> Self {
+		MultiLocation { parents: 0, interior }
+	}
+}
+
+impl From<Junction> for MultiLocation {
+	fn from(x: Junction) -> Self {
+		MultiLocation { parents: 0, interior: Junctions::X1(x) }
+	}
+}
+
+impl From<Junction> for Junctions {
+	fn from(x: Junction) -> Self {
+		Junctions::X1(x)
+	}
+}
+
+impl MultiLocation {
+	/// Creates a new `MultiLocation` with the given number of parents and interior junctions.
+	pub fn new(parents: u8, interior: Junctions) -> MultiLocation {
+		MultiLocation { parents, interior }
+	}
+
+	/// Creates a new `MultiLocation` with 0 parents and a `Here` interior, i.e. the interpreting consensus
+	/// system itself.
+	pub const fn here() -> MultiLocation {
+		MultiLocation { parents: 0, interior: Junctions::Here }
+	}
+
+	/// Creates a new `MultiLocation` which evaluates to the parent context.
+	pub const fn parent() -> MultiLocation {
+		MultiLocation { parents: 1, interior: Junctions::Here }
+	}
+
+	/// Whether or not the `MultiLocation` has no parents and has a `Here` interior.
+	pub fn is_here(&self) -> bool {
+		self.parents == 0 && self.interior.len() == 0
+	}
+
+	/// Returns the number of `Parent` junctions at the beginning of `self`.
+	pub fn parent_count(&self) -> u8 {
+		self.parents
+	}
+
+	/// Return a reference to the interior junctions.
+	pub fn interior(&self) -> &Junctions {
+		&self.interior
+	}
+
+	/// Return a mutable reference to the interior junctions.
+	pub fn interior_mut(&mut self) -> &mut Junctions {
+		&mut self.interior
+	}
+
+	/// Returns the number of parents and junctions in `self`.
+	pub fn len(&self) -> usize {
+		self.parents as usize + self.interior.len()
+	}
+
+	/// Returns the first interior junction, or `None` if the location is empty or contains only parents.
+	pub fn first_interior(&self) -> Option<&Junction> {
+		self.interior.first()
+	}
+
+	/// Returns last junction, or `None` if the location is empty or contains only parents.
+	pub fn last(&self) -> Option<&Junction> {
+		self.interior.last()
+	}
+
+	/// Mutates `self`, suffixing its interior junctions with `new`. Returns `Err` in case of overflow.
+	pub fn push_interior(&mut self, new: Junction) -> result::Result<(), ()> {
+		self.interior.push(new)
+	}
+
+	/// Mutates `self`, prefixing its interior junctions with `new`. Returns `Err` in case of overflow.
+	pub fn push_front_interior(&mut self, new: Junction) -> result::Result<(), ()> {
+		self.interior.push_front(new)
+	}
+
+	/// Ensures that `self` has the same number of parents as `prefix`, its junctions begins with
+	/// the junctions of `prefix` and that it has a single `Junction` item following.
+	/// If so, returns a reference to this `Junction` item.
+	///
+	/// # Example
+	/// ```rust
+	/// # use xcm::v1::{Junctions::*, Junction::*, MultiLocation};
+	/// # fn main() {
+	/// let mut m = MultiLocation::new(1, X2(PalletInstance(3), OnlyChild));
+	/// assert_eq!(
+	///     m.match_and_split(&MultiLocation::new(1, X1(PalletInstance(3)))),
+	///     Some(&OnlyChild),
+	/// );
+	/// assert_eq!(m.match_and_split(&MultiLocation::new(1, Here)), None);
+	/// # }
+	/// ```
+	pub fn match_and_split(&self, prefix: &MultiLocation) -> Option<&Junction> {
+		if self.parents != prefix.parents {
+			return None
+		}
+		self.interior.match_and_split(&prefix.interior)
+	}
+
+	/// Mutate `self` so that it is suffixed with `suffix`. The correct normalized form is returned,
+	/// removing any parents of `suffix` against the interior junctions of `self`.
+	///
+	/// Does not modify `self` and returns `Err` with `suffix` in case of overflow.
+	///
+	/// # Example
+	/// ```rust
+	/// # use xcm::v1::{Junctions::*, Junction::*, MultiLocation};
+	/// # fn main() {
+	/// let mut m = MultiLocation::new(1, X2(Parachain(21), OnlyChild));
+	/// assert_eq!(m.append_with(MultiLocation::new(1, X1(PalletInstance(3)))), Ok(()));
+	/// assert_eq!(m, MultiLocation::new(1, X2(Parachain(21), PalletInstance(3))));
+	/// # }
+	/// ```
+	pub fn append_with(&mut self, suffix: MultiLocation) -> result::Result<(), MultiLocation> {
+		let mut prefix = suffix;
+		mem::swap(self, &mut prefix);
+		match self.prepend_with(prefix) {
+			Ok(()) => Ok(()),
+			Err(prefix) => {
+				let mut suffix = prefix;
+				mem::swap(self, &mut suffix);
+				Err(suffix)
+			}
+		}
+	}
+
+	/// Mutate `self` so that it is prefixed with `prefix`. The correct normalized form is returned,
+	/// removing any parents of `self` against the interior junctions of `prefix`.
+	///
+	/// Does not modify `self` and returns `Err` with `prefix` in case of overflow.
+	///
+	/// # Example
+	/// ```rust
+	/// # use xcm::v1::{Junctions::*, Junction::*, MultiLocation};
+	/// # fn main() {
+	/// let mut m = MultiLocation::new(2, X1(PalletInstance(3)));
+	/// assert_eq!(m.prepend_with(MultiLocation::new(1, X2(Parachain(21), OnlyChild))), Ok(()));
+	/// assert_eq!(m, MultiLocation::new(1, X1(PalletInstance(3))));
+	/// # }
+	/// ```
+	pub fn prepend_with(&mut self, prefix: MultiLocation) -> result::Result<(), MultiLocation> {
+		let skipped = (self.parents as usize).min(prefix.interior.len());
+		let parents = prefix.parents as usize + self.parents as usize - skipped;
+		let interior_len = prefix.interior.len() - skipped + self.interior.len();
+		if parents > u8::max_value() as usize || interior_len > MAX_JUNCTIONS {
+			return Err(prefix)
+		}
+
+		let MultiLocation { parents: _, interior: mut prefix_interior } = prefix;
+		for _ in 0..skipped {
+			prefix_interior.take_last();
+		}
+		for j in prefix_interior.into_iter_rev() {
+			self.interior.push_front(j).expect("interior length was checked to be within bounds above; qed");
+		}
+		self.parents = parents as u8;
+		Ok(())
+	}
+}
+
+impl TryFrom<MultiLocation0> for MultiLocation {
+	type Error = ();
+	fn try_from(old: MultiLocation0) -> result::Result<Self, ()> {
+		let mut new = MultiLocation::here();
+		for junction in old.into_iter() {
+			match junction {
+				// `Parent` may only appear before all interior junctions.
+				Junction0::Parent if new.interior.len() == 0 => new.parents += 1,
+				Junction0::Parent => return Err(()),
+				j => new.push_interior(Junction::try_from(j)?)?,
+			}
+		}
+		Ok(new)
+	}
+}
+
+impl TryFrom<MultiLocation> for MultiLocation0 {
+	type Error = ();
+	fn try_from(new: MultiLocation) -> result::Result<Self, ()> {
+		let mut old = MultiLocation0::Null;
+		for _ in 0..new.parents {
+			old.push(Junction0::Parent)?;
+		}
+		for j in new.interior.into_iter() {
+			old.push(j.into())?;
+		}
+		Ok(old)
+	}
+}
+
+impl TryFrom<Junctions> for MultiLocation0 {
+	type Error = ();
+	fn try_from(junctions: Junctions) -> result::Result<Self, ()> {
+		MultiLocation0::try_from(MultiLocation::from(junctions))
+	}
+}
+
+impl TryFrom<MultiLocation0> for Junctions {
+	type Error = ();
+	fn try_from(old: MultiLocation0) -> result::Result<Self, ()> {
+		match MultiLocation::try_from(old)? {
+			MultiLocation { parents: 0, interior } => Ok(interior),
+			_ => Err(()),
+		}
+	}
+}
+
+pub struct JunctionsIterator(Junctions);
+impl Iterator for JunctionsIterator {
+	type Item = Junction;
+	fn next(&mut self) -> Option<Junction> {
+		self.0.take_first()
+	}
+}
+
+pub struct JunctionsReverseIterator(Junctions);
+impl Iterator for JunctionsReverseIterator {
+	type Item = Junction;
+	fn next(&mut self) -> Option<Junction> {
+		self.0.take_last()
+	}
+}
+
+pub struct JunctionsRefIterator<'a>(&'a Junctions, usize);
+impl<'a> Iterator for JunctionsRefIterator<'a> {
+	type Item = &'a Junction;
+	fn next(&mut self) -> Option<&'a Junction> {
+		let result = self.0.at(self.1);
+		self.1 += 1;
+		result
+	}
+}
+
+impl Junctions {
+	/// Returns first junction, or `None` if the location is empty.
+	pub fn first(&self) -> Option<&Junction> {
+		match &self {
+			Junctions::Here => None,
+			Junctions::X1(ref a) => Some(a),
+			Junctions::X2(ref a, ..) => Some(a),
+			Junctions::X3(ref a, ..) => Some(a),
+			Junctions::X4(ref a, ..) => Some(a),
+			Junctions::X5(ref a, ..) => Some(a),
+			Junctions::X6(ref a, ..) => Some(a),
+			Junctions::X7(ref a, ..) => Some(a),
+			Junctions::X8(ref a, ..) => Some(a),
+		}
+	}
+
+	/// Returns last junction, or `None` if the location is empty.
+	pub fn last(&self) -> Option<&Junction> {
+		match &self {
+			Junctions::Here => None,
+			Junctions::X1(ref a) => Some(a),
+			Junctions::X2(.., ref a) => Some(a),
+			Junctions::X3(.., ref a) => Some(a),
+			Junctions::X4(.., ref a) => Some(a),
+			Junctions::X5(.., ref a) => Some(a),
+			Junctions::X6(.., ref a) => Some(a),
+			Junctions::X7(.., ref a) => Some(a),
+			Junctions::X8(.., ref a) => Some(a),
+		}
+	}
+
+	/// Splits off the first junction, returning the remaining suffix (first item in tuple) and the first element
+	/// (second item in tuple) or `None` if it was empty.
+	pub fn split_first(self) -> (Junctions, Option<Junction>) {
+		match self {
+			Junctions::Here => (Junctions::Here, None),
+			Junctions::X1(a) => (Junctions::Here, Some(a)),
+			Junctions::X2(a, b) => (Junctions::X1(b), Some(a)),
+			Junctions::X3(a, b, c) => (Junctions::X2(b, c), Some(a)),
+			Junctions::X4(a, b, c, d) => (Junctions::X3(b, c, d), Some(a)),
+			Junctions::X5(a, b, c, d, e) => (Junctions::X4(b, c, d, e), Some(a)),
+			Junctions::X6(a, b, c, d, e, f) => (Junctions::X5(b, c, d, e, f), Some(a)),
+			Junctions::X7(a, b, c, d, e, f, g) => (Junctions::X6(b, c, d, e, f, g), Some(a)),
+			Junctions::X8(a, b, c, d, e, f, g, h) => (Junctions::X7(b, c, d, e, f, g, h), Some(a)),
+		}
+	}
+
+	/// Splits off the last junction, returning the remaining prefix (first item in tuple) and the last element
+	/// (second item in tuple) or `None` if it was empty.
+	pub fn split_last(self) -> (Junctions, Option<Junction>) {
+		match self {
+			Junctions::Here => (Junctions::Here, None),
+			Junctions::X1(a) => (Junctions::Here, Some(a)),
+			Junctions::X2(a, b) => (Junctions::X1(a), Some(b)),
+			Junctions::X3(a, b, c) => (Junctions::X2(a, b), Some(c)),
+			Junctions::X4(a, b, c, d) => (Junctions::X3(a, b, c), Some(d)),
+			Junctions::X5(a, b, c, d, e) => (Junctions::X4(a, b, c, d), Some(e)),
+			Junctions::X6(a, b, c, d, e, f) => (Junctions::X5(a, b, c, d, e), Some(f)),
+			Junctions::X7(a, b, c, d, e, f, g) => (Junctions::X6(a, b, c, d, e, f), Some(g)),
+			Junctions::X8(a, b, c, d, e, f, g, h) => (Junctions::X7(a, b, c, d, e, f, g), Some(h)),
+		}
+	}
+
+	/// Removes the first element from `self`, returning it (or `None` if it was empty).
+	pub fn take_first(&mut self) -> Option<Junction> {
+		let mut d = Junctions::Here;
+		mem::swap(&mut *self, &mut d);
+		let (tail, head) = d.split_first();
+		*self = tail;
+		head
+	}
+
+	/// Removes the last element from `self`, returning it (or `None` if it was empty).
+	pub fn take_last(&mut self) -> Option<Junction> {
+		let mut d = Junctions::Here;
+		mem::swap(&mut *self, &mut d);
+		let (head, tail) = d.split_last();
+		*self = head;
+		tail
+	}
+
+	/// Consumes `self` and returns a `Junctions` suffixed with `new`, or an `Err` with the original value of
+	/// `self` in case of overflow.
+	pub fn pushed_with(self, new: Junction) -> result::Result<Self, Self> {
+		Ok(match self {
+			Junctions::Here => Junctions::X1(new),
+			Junctions::X1(a) => Junctions::X2(a, new),
+			Junctions::X2(a, b) => Junctions::X3(a, b, new),
+			Junctions::X3(a, b, c) => Junctions::X4(a, b, c, new),
+			Junctions::X4(a, b, c, d) => Junctions::X5(a, b, c, d, new),
+			Junctions::X5(a, b, c, d, e) => Junctions::X6(a, b, c, d, e, new),
+			Junctions::X6(a, b, c, d, e, f) => Junctions::X7(a, b, c, d, e, f, new),
+			Junctions::X7(a, b, c, d, e, f, g) => Junctions::X8(a, b, c, d, e, f, g, new),
+			s => Err(s)?,
+		})
+	}
+
+	/// Consumes `self` and returns a `Junctions` prefixed with `new`, or an `Err` with the original value of
+	/// `self` in case of overflow.
+	pub fn pushed_front_with(self, new: Junction) -> result::Result<Self, Self> {
+		Ok(match self {
+			Junctions::Here => Junctions::X1(new),
+			Junctions::X1(a) => Junctions::X2(new, a),
+			Junctions::X2(a, b) => Junctions::X3(new, a, b),
+			Junctions::X3(a, b, c) => Junctions::X4(new, a, b, c),
+			Junctions::X4(a, b, c, d) => Junctions::X5(new, a, b, c, d),
+			Junctions::X5(a, b, c, d, e) => Junctions::X6(new, a, b, c, d, e),
+			Junctions::X6(a, b, c, d, e, f) => Junctions::X7(new, a, b, c, d, e, f),
+			Junctions::X7(a, b, c, d, e, f, g) => Junctions::X8(new, a, b, c, d, e, f, g),
+			s => Err(s)?,
+		})
+	}
+
+	/// Returns the number of junctions in `self`.
+	pub fn len(&self) -> usize {
+		match &self {
+			Junctions::Here => 0,
+			Junctions::X1(..) => 1,
+			Junctions::X2(..) => 2,
+			Junctions::X3(..) => 3,
+			Junctions::X4(..) => 4,
+			Junctions::X5(..) => 5,
+			Junctions::X6(..) => 6,
+			Junctions::X7(..) => 7,
+			Junctions::X8(..) => 8,
+		}
+	}
+
+	/// Returns the junction at index `i`, or `None` if the location doesn't contain that many elements.
+	pub fn at(&self, i: usize) -> Option<&Junction> {
+		Some(match (i, &self) {
+			(0, Junctions::X1(ref a)) => a,
+			(0, Junctions::X2(ref a, ..)) => a,
+			(0, Junctions::X3(ref a, ..)) => a,
+			(0, Junctions::X4(ref a, ..)) => a,
+			(0, Junctions::X5(ref a, ..)) => a,
+			(0, Junctions::X6(ref a, ..)) => a,
+			(0, Junctions::X7(ref a, ..)) => a,
+			(0, Junctions::X8(ref a, ..)) => a,
+			(1, Junctions::X2(_, ref a)) => a,
+			(1, Junctions::X3(_, ref a, ..)) => a,
+			(1, Junctions::X4(_, ref a, ..)) => a,
+			(1, Junctions::X5(_, ref a, ..)) => a,
+			(1, Junctions::X6(_, ref a, ..)) => a,
+			(1, Junctions::X7(_, ref a, ..)) => a,
+			(1, Junctions::X8(_, ref a, ..)) => a,
+			(2, Junctions::X3(_, _, ref a)) => a,
+			(2, Junctions::X4(_, _, ref a, ..)) => a,
+			(2, Junctions::X5(_, _, ref a, ..)) => a,
+			(2, Junctions::X6(_, _, ref a, ..)) => a,
+			(2, Junctions::X7(_, _, ref a, ..)) => a,
+			(2, Junctions::X8(_, _, ref a, ..)) => a,
+			(3, Junctions::X4(_, _, _, ref a)) => a,
+			(3, Junctions::X5(_, _, _, ref a, ..)) => a,
+			(3, Junctions::X6(_, _, _, ref a, ..)) => a,
+			(3, Junctions::X7(_, _, _, ref a, ..)) => a,
+			(3, Junctions::X8(_, _, _, ref a, ..)) => a,
+			(4, Junctions::X5(_, _, _, _, ref a)) => a,
+			(4, Junctions::X6(_, _, _, _, ref a, ..)) => a,
+			(4, Junctions::X7(_, _, _, _, ref a, ..)) => a,
+			(4, Junctions::X8(_, _, _, _, ref a, ..)) => a,
+			(5, Junctions::X6(_, _, _, _, _, ref a)) => a,
+			(5, Junctions::X7(_, _, _, _, _, ref a, ..)) => a,
+			(5, Junctions::X8(_, _, _, _, _, ref a, ..)) => a,
+			(6, Junctions::X7(_, _, _, _, _, _, ref a)) => a,
+			(6, Junctions::X8(_, _, _, _, _, _, ref a, ..)) => a,
+			(7, Junctions::X8(_, _, _, _, _, _, _, ref a)) => a,
+			_ => return None,
+		})
+	}
+
+	/// Returns a reference iterator over the junctions.
+	pub fn iter(&self) -> JunctionsRefIterator<'_> {
+		JunctionsRefIterator(self, 0)
+	}
+
+	/// Consumes `self` and returns an iterator over the junctions.
+	pub fn into_iter(self) -> JunctionsIterator {
+		JunctionsIterator(self)
+	}
+
+	/// Consumes `self` and returns an iterator over the junctions in reverse.
+	pub fn into_iter_rev(self) -> JunctionsReverseIterator {
+		JunctionsReverseIterator(self)
+	}
+
+	/// Ensures that self begins with `prefix` and that it has a single `Junction` item following.
+	/// If so, returns a reference to this `Junction` item.
+	pub fn match_and_split(&self, prefix: &Junctions) -> Option<&Junction> {
+		if prefix.len() + 1 != self.len() {
+			return None
+		}
+		for i in 0..prefix.len() {
+			if prefix.at(i) != self.at(i) {
+				return None
+			}
+		}
+		return self.at(prefix.len())
+	}
+
+	/// Mutates `self`, suffixing it with `new`. Returns `Err` in case of overflow.
+	pub fn push(&mut self, new: Junction) -> result::Result<(), ()> {
+		let mut n = Junctions::Here;
+		mem::swap(&mut *self, &mut n);
+		match n.pushed_with(new) {
+			Ok(result) => { *self = result; Ok(()) }
+			Err(old) => { *self = old; Err(()) }
+		}
+	}
+
+	/// Mutates `self`, prefixing it with `new`. Returns `Err` in case of overflow.
+	pub fn push_front(&mut self, new: Junction) -> result::Result<(), ()> {
+		let mut n = Junctions::Here;
+		mem::swap(&mut *self, &mut n);
+		match n.pushed_front_with(new) {
+			Ok(result) => { *self = result; Ok(()) }
+			Err(old) => { *self = old; Err(()) }
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use core::convert::TryFrom;
+	use super::{MultiLocation, Junctions::*};
+	use crate::v1::{Junction::*, NetworkId::Any};
+	use crate::v0::{MultiLocation as MultiLocation0, Junction as Junction0};
+
+	#[test]
+	fn prepend_with_works() {
+		let acc = AccountIndex64 { network: Any, index: 23 };
+		let mut m = MultiLocation::new(1, X2(Parachain(42), acc.clone()));
+		assert_eq!(m.prepend_with(MultiLocation::new(1, X1(OnlyChild))), Ok(()));
+		assert_eq!(m, MultiLocation::new(1, X2(Parachain(42), acc.clone())));
+
+		// cannot prepend to create overly long multilocation
+		let mut m = MultiLocation::new(0, X7(OnlyChild, OnlyChild, OnlyChild, OnlyChild, OnlyChild, OnlyChild, acc));
+		let prefix = MultiLocation::new(0, X2(Parachain(42), OnlyChild));
+		assert_eq!(m.prepend_with(prefix.clone()), Err(prefix));
+	}
+
+	#[test]
+	fn append_with_works() {
+		let acc = AccountIndex64 { network: Any, index: 23 };
+		let mut m = MultiLocation::new(1, X1(Parachain(42)));
+		assert_eq!(m.append_with(MultiLocation::from(X2(PalletInstance(3), acc.clone()))), Ok(()));
+		assert_eq!(m, MultiLocation::new(1, X3(Parachain(42), PalletInstance(3), acc)));
+	}
+
+	#[test]
+	fn conversion_from_v0_works() {
+		let old = MultiLocation0::X3(Junction0::Parent, Junction0::Parachain(42), Junction0::OnlyChild);
+		let new = MultiLocation::new(1, X2(Parachain(42), OnlyChild));
+		assert_eq!(MultiLocation::try_from(old.clone()), Ok(new.clone()));
+		assert_eq!(MultiLocation0::try_from(new), Ok(old));
+
+		// a parent after an interior junction has no equivalent.
+		let old = MultiLocation0::X2(Junction0::Parachain(42), Junction0::Parent);
+		assert_eq!(MultiLocation::try_from(old), Err(()));
+
+		// nor does a location that is longer than 8 junctions in total.
+		let new = MultiLocation::new(2, X7(OnlyChild, OnlyChild, OnlyChild, OnlyChild, OnlyChild, OnlyChild, OnlyChild));
+		assert_eq!(MultiLocation0::try_from(new), Err(()));
+	}
+}
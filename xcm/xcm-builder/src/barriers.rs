@@ -39,6 +39,11 @@ impl ShouldExecute for TakeWeightCredit {
 
 /// Allows execution from `origin` if it is contained in `T` (i.e. `T::Contains(origin)`) taking payments into
 /// account.
+///
+/// The message must be a top-level `WithdrawAsset`, `ReserveAssetDeposit`, `TeleportAsset` or `ClaimAsset` whose
+/// first order is a `BuyExecution` with a `debt` covering the message's shallow weight. Whether its `fees` can
+/// actually pay for that is up to the `Trader`; see `FixedRateOfConcreteFungibles` for one which accepts several
+/// assets.
 pub struct AllowTopLevelPaidExecutionFrom<T>(PhantomData<T>);
 impl<T: Contains<MultiLocation>> ShouldExecute for AllowTopLevelPaidExecutionFrom<T> {
 	fn should_execute<Call>(
@@ -54,6 +59,7 @@ impl<T: Contains<MultiLocation>> ShouldExecute for AllowTopLevelPaidExecutionFro
 			Xcm::TeleportAsset { effects, .. }
 			| Xcm::WithdrawAsset { effects, ..}
 			| Xcm::ReserveAssetDeposit { effects, ..}
+			| Xcm::ClaimAsset { effects, ..}
 			if matches!(
 					effects.first(),
 					Some(Order::BuyExecution { debt, ..}) if *debt >= shallow_weight
@@ -107,3 +113,23 @@ impl<ResponseHandler: OnResponse> ShouldExecute for AllowKnownQueryResponses<Res
 		}
 	}
 }
+
+/// Allows execution from `origin` if it is contained in `T` (i.e. `T::Contains(origin)`), provided that the message is
+/// a top-level `SubscribeVersion` or `UnsubscribeVersion`. These are cheap and their answers are free, so they don't
+/// need paying for.
+pub struct AllowSubscriptionsFrom<T>(PhantomData<T>);
+impl<T: Contains<MultiLocation>> ShouldExecute for AllowSubscriptionsFrom<T> {
+	fn should_execute<Call>(
+		origin: &MultiLocation,
+		top_level: bool,
+		message: &Xcm<Call>,
+		_shallow_weight: Weight,
+		_weight_credit: &mut Weight,
+	) -> Result<(), ()> {
+		ensure!(T::contains(origin) && top_level, ());
+		match message {
+			Xcm::SubscribeVersion { .. } | Xcm::UnsubscribeVersion => Ok(()),
+			_ => Err(()),
+		}
+	}
+}
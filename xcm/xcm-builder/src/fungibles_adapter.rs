@@ -17,9 +17,9 @@
 //! Adapters to work with `frame_support::traits::tokens::fungibles` through XCM.
 
 use sp_std::{prelude::*, result, marker::PhantomData, borrow::Borrow};
-use xcm::v0::{Error as XcmError, Result, MultiAsset, MultiLocation, Junction};
+use xcm::v0::{Error as XcmError, Result, MultiAsset, MultiLocation, Junction, AssetInstance};
 use frame_support::traits::{Get, tokens::fungibles, Contains};
-use xcm_executor::traits::{TransactAsset, Convert, MatchesFungibles, Error as MatchError};
+use xcm_executor::traits::{TransactAsset, Convert, MatchesFungibles, MatchesNonFungibles, Error as MatchError};
 
 /// Converter struct implementing `AssetIdConversion` converting a numeric asset ID (must be TryFrom/TryInto<u128>) into
 /// a `GeneralIndex` junction, prefixed by some `MultiLocation` value. The `MultiLocation` value will typically be a
@@ -70,6 +70,24 @@ impl<
 		Ok((what, amount))
 	}
 }
+impl<
+	ClassId: Clone,
+	InstanceId: Clone,
+	ConvertClassId: Convert<MultiLocation, ClassId>,
+	ConvertInstanceId: Convert<AssetInstance, InstanceId>,
+> MatchesNonFungibles<ClassId, InstanceId> for
+	ConvertedConcreteAssetId<ClassId, InstanceId, ConvertClassId, ConvertInstanceId>
+{
+	fn matches_nonfungibles(a: &MultiAsset) -> result::Result<(ClassId, InstanceId), MatchError> {
+		let (class, instance) = match a {
+			MultiAsset::ConcreteNonFungible { class, instance } => (class, instance),
+			_ => return Err(MatchError::AssetNotFound),
+		};
+		let what = ConvertClassId::convert_ref(class).map_err(|_| MatchError::AssetIdConversionFailed)?;
+		let instance = ConvertInstanceId::convert_ref(instance).map_err(|_| MatchError::InstanceConversionFailed)?;
+		Ok((what, instance))
+	}
+}
 
 pub struct ConvertedAbstractAssetId<AssetId, Balance, ConvertAssetId, ConvertBalance>(
 	PhantomData<(AssetId, Balance, ConvertAssetId, ConvertBalance)>
@@ -92,6 +110,24 @@ impl<
 		Ok((what, amount))
 	}
 }
+impl<
+	ClassId: Clone,
+	InstanceId: Clone,
+	ConvertClassId: Convert<Vec<u8>, ClassId>,
+	ConvertInstanceId: Convert<AssetInstance, InstanceId>,
+> MatchesNonFungibles<ClassId, InstanceId> for
+	ConvertedAbstractAssetId<ClassId, InstanceId, ConvertClassId, ConvertInstanceId>
+{
+	fn matches_nonfungibles(a: &MultiAsset) -> result::Result<(ClassId, InstanceId), MatchError> {
+		let (class, instance) = match a {
+			MultiAsset::AbstractNonFungible { class, instance } => (class, instance),
+			_ => return Err(MatchError::AssetNotFound),
+		};
+		let what = ConvertClassId::convert_ref(class).map_err(|_| MatchError::AssetIdConversionFailed)?;
+		let instance = ConvertInstanceId::convert_ref(instance).map_err(|_| MatchError::InstanceConversionFailed)?;
+		Ok((what, instance))
+	}
+}
 
 pub struct FungiblesTransferAdapter<Assets, Matcher, AccountIdConverter, AccountId>(
 	PhantomData<(Assets, Matcher, AccountIdConverter, AccountId)>
@@ -41,17 +41,27 @@ pub use origin_conversion::{
 mod barriers;
 pub use barriers::{
 	TakeWeightCredit, AllowUnpaidExecutionFrom, AllowTopLevelPaidExecutionFrom, AllowKnownQueryResponses,
-	IsChildSystemParachain,
+	AllowSubscriptionsFrom, IsChildSystemParachain,
 };
 
 mod currency_adapter;
 pub use currency_adapter::CurrencyAdapter;
 
 mod fungibles_adapter;
-pub use fungibles_adapter::FungiblesAdapter;
+pub use fungibles_adapter::{
+	FungiblesAdapter, AsPrefixedGeneralIndex, ConvertedConcreteAssetId, ConvertedAbstractAssetId,
+};
+
+mod nonfungibles_adapter;
+pub use nonfungibles_adapter::{
+	NonFungiblesAdapter, NonFungiblesMutateAdapter, NonFungiblesTransferAdapter, AsIndexInstance,
+};
 
 mod weight;
-pub use weight::{FixedRateOfConcreteFungible, FixedWeightBounds, UsingComponents};
+pub use weight::{
+	FixedRateOfConcreteFungible, FixedRateOfConcreteFungibles, FixedWeightBounds, UsingComponents, WeightInfoBounds,
+	TakeRevenue,
+};
 
 mod matches_fungible;
 pub use matches_fungible::{IsAbstract, IsConcrete};
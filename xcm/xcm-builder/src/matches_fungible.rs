@@ -14,13 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Various implementations for the `MatchesFungible` trait.
+//! Various implementations for the `MatchesFungible` and `MatchesNonFungible` traits.
 
 use sp_std::{marker::PhantomData, convert::TryFrom};
 use sp_runtime::traits::CheckedConversion;
-use xcm::v0::{MultiAsset, MultiLocation};
+use xcm::v0::{MultiAsset, MultiLocation, AssetInstance};
 use frame_support::traits::Get;
-use xcm_executor::traits::MatchesFungible;
+use xcm_executor::traits::{MatchesFungible, MatchesNonFungible};
 
 /// Converts a `MultiAsset` into balance `B` if it is a concrete fungible with an id equal to that
 /// given by `T`'s `Get`.
@@ -53,6 +53,15 @@ impl<T: Get<MultiLocation>, B: TryFrom<u128>> MatchesFungible<B> for IsConcrete<
 		}
 	}
 }
+impl<T: Get<MultiLocation>, I: TryFrom<AssetInstance>> MatchesNonFungible<I> for IsConcrete<T> {
+	fn matches_nonfungible(a: &MultiAsset) -> Option<I> {
+		match a {
+			MultiAsset::ConcreteNonFungible { class, instance } if class == &T::get() =>
+				I::try_from(instance.clone()).ok(),
+			_ => None,
+		}
+	}
+}
 
 /// Same as [`IsConcrete`] but for a fungible with abstract location.
 ///
@@ -83,3 +92,12 @@ impl<T: Get<&'static [u8]>, B: TryFrom<u128>> MatchesFungible<B> for IsAbstract<
 		}
 	}
 }
+impl<T: Get<&'static [u8]>, I: TryFrom<AssetInstance>> MatchesNonFungible<I> for IsAbstract<T> {
+	fn matches_nonfungible(a: &MultiAsset) -> Option<I> {
+		match a {
+			MultiAsset::AbstractNonFungible { class, instance } if &class[..] == T::get() =>
+				I::try_from(instance.clone()).ok(),
+			_ => None,
+		}
+	}
+}
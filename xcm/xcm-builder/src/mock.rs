@@ -29,11 +29,14 @@ pub use frame_support::{
 	traits::{Get, Contains, IsInVec},
 };
 pub use xcm_executor::{
-	Assets, Config, traits::{TransactAsset, ConvertOrigin, FilterAssetLocation, InvertLocation, OnResponse}
+	Assets, Config, traits::{
+		TransactAsset, ConvertOrigin, FilterAssetLocation, InvertLocation, OnResponse, DropAssets, ClaimAssets,
+		VersionChangeNotifier,
+	}
 };
 pub use crate::{
 	TakeWeightCredit, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, FixedWeightBounds,
-	FixedRateOfConcreteFungible, AllowKnownQueryResponses, LocationInverter,
+	FixedRateOfConcreteFungible, AllowKnownQueryResponses, AllowSubscriptionsFrom, LocationInverter,
 };
 
 pub enum TestOrigin {
@@ -221,6 +224,9 @@ impl OnResponse for TestResponseHandler {
 			_ => false,
 		})
 	}
+	fn max_response_weight(origin: &MultiLocation, query_id: u64) -> Weight {
+		if Self::expecting_response(origin, query_id) { 20 } else { 0 }
+	}
 	fn on_response(_origin: MultiLocation, query_id: u64, response: xcm::v0::Response) -> Weight {
 		QUERIES.with(|q| {
 			q.borrow_mut()
@@ -247,6 +253,59 @@ pub fn response(query_id: u64) -> Option<Response> {
 	)
 }
 
+thread_local! {
+	pub static TRAPPED_ASSETS: RefCell<Vec<(MultiLocation, Vec<MultiAsset>)>> = RefCell::new(Vec::new());
+}
+pub fn trapped_assets() -> Vec<(MultiLocation, Vec<MultiAsset>)> {
+	TRAPPED_ASSETS.with(|t| t.borrow().clone())
+}
+pub struct TestAssetTrap;
+impl DropAssets for TestAssetTrap {
+	fn drop_assets(origin: &MultiLocation, assets: Assets) {
+		TRAPPED_ASSETS.with(|t| t.borrow_mut().push((origin.clone(), assets.into())));
+	}
+}
+impl ClaimAssets for TestAssetTrap {
+	fn claim_assets(origin: &MultiLocation, ticket: &MultiLocation, what: &[MultiAsset]) -> bool {
+		if ticket != &Null {
+			return false
+		}
+		TRAPPED_ASSETS.with(|t| {
+			let mut trapped = t.borrow_mut();
+			match trapped.iter().position(|(o, a)| o == origin && &a[..] == what) {
+				Some(index) => { trapped.remove(index); true }
+				None => false,
+			}
+		})
+	}
+}
+
+thread_local! {
+	pub static SUBSCRIPTIONS: RefCell<Vec<(MultiLocation, u64, u64)>> = RefCell::new(Vec::new());
+}
+pub fn subscriptions() -> Vec<(MultiLocation, u64, u64)> {
+	SUBSCRIPTIONS.with(|s| s.borrow().clone())
+}
+pub struct TestSubscriptionService;
+impl VersionChangeNotifier for TestSubscriptionService {
+	fn start(location: &MultiLocation, query_id: u64, max_response_weight: u64) -> XcmResult {
+		SUBSCRIPTIONS.with(|s| {
+			let mut s = s.borrow_mut();
+			s.retain(|(l, ..)| l != location);
+			s.push((location.clone(), query_id, max_response_weight));
+		});
+		Ok(())
+	}
+	fn stop(location: &MultiLocation) -> XcmResult {
+		SUBSCRIPTIONS.with(|s| {
+			let mut s = s.borrow_mut();
+			let len = s.len();
+			s.retain(|(l, ..)| l != location);
+			if s.len() < len { Ok(()) } else { Err(XcmError::BadOrigin) }
+		})
+	}
+}
+
 parameter_types! {
 	pub TestAncestry: MultiLocation = X1(Parachain(42));
 	pub UnitWeightCost: Weight = 10;
@@ -255,6 +314,7 @@ parameter_types! {
 	// Nothing is allowed to be paid/unpaid by default.
 	pub static AllowUnpaidFrom: Vec<MultiLocation> = vec![];
 	pub static AllowPaidFrom: Vec<MultiLocation> = vec![];
+	pub static AllowSubsFrom: Vec<MultiLocation> = vec![];
 	// 1_000_000_000_000 => 1 unit of asset for 1 unit of Weight.
 	pub static WeightPrice: (MultiLocation, u128) = (Null, 1_000_000_000_000);
 }
@@ -264,6 +324,7 @@ pub type TestBarrier = (
 	AllowKnownQueryResponses<TestResponseHandler>,
 	AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
 	AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+	AllowSubscriptionsFrom<IsInVec<AllowSubsFrom>>,
 );
 
 pub struct TestConfig;
@@ -279,4 +340,7 @@ impl Config for TestConfig {
 	type Weigher = FixedWeightBounds<UnitWeightCost, TestCall>;
 	type Trader = FixedRateOfConcreteFungible<WeightPrice, ()>;
 	type ResponseHandler = TestResponseHandler;
+	type AssetTrap = TestAssetTrap;
+	type AssetClaims = TestAssetTrap;
+	type SubscriptionService = TestSubscriptionService;
 }
@@ -0,0 +1,184 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Adapters to work with `frame_support::traits::tokens::nonfungibles` through XCM.
+
+use sp_std::{result, marker::PhantomData, borrow::Borrow};
+use xcm::v0::{Error as XcmError, Result, MultiAsset, MultiLocation, AssetInstance};
+use frame_support::{ensure, traits::{Get, tokens::nonfungibles, Contains}};
+use xcm_executor::traits::{TransactAsset, Convert, MatchesNonFungibles, Error as MatchError};
+
+/// Converter struct implementing `Convert<AssetInstance, InstanceId>` for a numeric instance ID (must be
+/// TryFrom/TryInto<u128>) carried in an `AssetInstance::Index`.
+pub struct AsIndexInstance<InstanceId, ConvertInstanceId>(PhantomData<(InstanceId, ConvertInstanceId)>);
+impl<
+	InstanceId: Clone,
+	ConvertInstanceId: Convert<u128, InstanceId>,
+> Convert<AssetInstance, InstanceId> for AsIndexInstance<InstanceId, ConvertInstanceId> {
+	fn convert_ref(instance: impl Borrow<AssetInstance>) -> result::Result<InstanceId, ()> {
+		match instance.borrow() {
+			AssetInstance::Index { id } => ConvertInstanceId::convert_ref(id),
+			_ => Err(()),
+		}
+	}
+	fn reverse_ref(what: impl Borrow<InstanceId>) -> result::Result<AssetInstance, ()> {
+		let id = ConvertInstanceId::reverse_ref(what)?;
+		Ok(AssetInstance::Index { id })
+	}
+}
+
+pub struct NonFungiblesTransferAdapter<Assets, Matcher, AccountIdConverter, AccountId>(
+	PhantomData<(Assets, Matcher, AccountIdConverter, AccountId)>
+);
+impl<
+	Assets: nonfungibles::Transfer<AccountId>,
+	Matcher: MatchesNonFungibles<Assets::ClassId, Assets::InstanceId>,
+	AccountIdConverter: Convert<MultiLocation, AccountId>,
+	AccountId: Clone + Eq,	// can't get away without it since Currency is generic over it.
+> TransactAsset for NonFungiblesTransferAdapter<Assets, Matcher, AccountIdConverter, AccountId> {
+	fn transfer_asset(
+		what: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let source = AccountIdConverter::convert_ref(from)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		let dest = AccountIdConverter::convert_ref(to)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		// The asset may only be moved by its current owner.
+		ensure!(Assets::owner(&class, &instance).as_ref() == Some(&source), XcmError::NotWithdrawable);
+		Assets::transfer(&class, &instance, &dest)
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))?;
+		Ok(what.clone().into())
+	}
+}
+
+pub struct NonFungiblesMutateAdapter<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>(
+	PhantomData<(Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount)>
+);
+impl<
+	Assets: nonfungibles::Mutate<AccountId>,
+	Matcher: MatchesNonFungibles<Assets::ClassId, Assets::InstanceId>,
+	AccountIdConverter: Convert<MultiLocation, AccountId>,
+	AccountId: Clone + Eq,	// can't get away without it since Currency is generic over it.
+	CheckAsset: Contains<Assets::ClassId>,
+	CheckingAccount: Get<AccountId>,
+> TransactAsset for NonFungiblesMutateAdapter<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount> {
+	fn can_check_in(_origin: &MultiLocation, what: &MultiAsset) -> Result {
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		if CheckAsset::contains(&class) {
+			// This is an asset whose teleports we track; it must have been checked out previously.
+			let checking_account = CheckingAccount::get();
+			let owner = Assets::owner(&class, &instance);
+			ensure!(owner == Some(checking_account), XcmError::NotWithdrawable);
+		}
+		Ok(())
+	}
+
+	fn check_in(_origin: &MultiLocation, what: &MultiAsset) {
+		if let Ok((class, instance)) = Matcher::matches_nonfungibles(what) {
+			if CheckAsset::contains(&class) {
+				let ok = Assets::burn_from(&class, &instance).is_ok();
+				debug_assert!(ok, "`can_check_in` must have returned `true` immediately prior; qed");
+			}
+		}
+	}
+
+	fn check_out(_dest: &MultiLocation, what: &MultiAsset) {
+		if let Ok((class, instance)) = Matcher::matches_nonfungibles(what) {
+			if CheckAsset::contains(&class) {
+				let checking_account = CheckingAccount::get();
+				let ok = Assets::mint_into(&class, &instance, &checking_account).is_ok();
+				debug_assert!(ok, "`mint_into` cannot generally fail; qed");
+			}
+		}
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> Result {
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let who = AccountIdConverter::convert_ref(who)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		Assets::mint_into(&class, &instance, &who)
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))
+	}
+
+	fn withdraw_asset(
+		what: &MultiAsset,
+		who: &MultiLocation
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		// Check we handle this asset.
+		let (class, instance) = Matcher::matches_nonfungibles(what)?;
+		let who = AccountIdConverter::convert_ref(who)
+			.map_err(|()| MatchError::AccountIdConversionFailed)?;
+		// Only the owner of an instance may withdraw it.
+		ensure!(Assets::owner(&class, &instance) == Some(who), XcmError::NotWithdrawable);
+		Assets::burn_from(&class, &instance)
+			.map_err(|e| XcmError::FailedToTransactAsset(e.into()))?;
+		Ok(what.clone().into())
+	}
+}
+
+pub struct NonFungiblesAdapter<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>(
+	PhantomData<(Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount)>
+);
+impl<
+	Assets: nonfungibles::Mutate<AccountId> + nonfungibles::Transfer<AccountId>,
+	Matcher: MatchesNonFungibles<Assets::ClassId, Assets::InstanceId>,
+	AccountIdConverter: Convert<MultiLocation, AccountId>,
+	AccountId: Clone + Eq,	// can't get away without it since Currency is generic over it.
+	CheckAsset: Contains<Assets::ClassId>,
+	CheckingAccount: Get<AccountId>,
+> TransactAsset for NonFungiblesAdapter<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount> {
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset) -> Result {
+		NonFungiblesMutateAdapter::<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>
+			::can_check_in(origin, what)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset) {
+		NonFungiblesMutateAdapter::<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>
+			::check_in(origin, what)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset) {
+		NonFungiblesMutateAdapter::<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>
+			::check_out(dest, what)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> Result {
+		NonFungiblesMutateAdapter::<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>
+			::deposit_asset(what, who)
+	}
+
+	fn withdraw_asset(
+		what: &MultiAsset,
+		who: &MultiLocation
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		NonFungiblesMutateAdapter::<Assets, Matcher, AccountIdConverter, AccountId, CheckAsset, CheckingAccount>
+			::withdraw_asset(what, who)
+	}
+
+	fn transfer_asset(
+		what: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+	) -> result::Result<xcm_executor::Assets, XcmError> {
+		NonFungiblesTransferAdapter::<Assets, Matcher, AccountIdConverter, AccountId>::transfer_asset(what, from, to)
+	}
+}
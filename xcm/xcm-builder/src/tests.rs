@@ -49,6 +49,65 @@ fn weigher_should_work() {
 	assert_eq!(<TestConfig as Config>::Weigher::shallow(&mut message), Ok(30));
 }
 
+#[test]
+fn weight_info_bounds_should_work() {
+	struct TestWeightInfo;
+	impl XcmWeightInfo for TestWeightInfo {
+		fn withdraw_asset() -> Weight { 100 }
+		fn reserve_asset_deposit() -> Weight { 1 }
+		fn teleport_asset() -> Weight { 1 }
+		fn query_response() -> Weight { 1 }
+		fn transfer_asset() -> Weight { 1 }
+		fn transfer_reserve_asset() -> Weight { 1 }
+		fn transact() -> Weight { 1_000 }
+		fn hrmp_new_channel_open_request() -> Weight { 1 }
+		fn hrmp_channel_accepted() -> Weight { 1 }
+		fn hrmp_channel_closing() -> Weight { 1 }
+		fn relayed_from() -> Weight { 1 }
+		fn claim_asset() -> Weight { 1 }
+		fn subscribe_version() -> Weight { 1 }
+		fn unsubscribe_version() -> Weight { 1 }
+		fn order_null() -> Weight { 1 }
+		fn deposit_asset() -> Weight { 20 }
+		fn deposit_reserve_asset() -> Weight { 1 }
+		fn exchange_asset() -> Weight { 1 }
+		fn initiate_reserve_withdraw() -> Weight { 1 }
+		fn initiate_teleport() -> Weight { 1 }
+		fn query_holding() -> Weight { 1 }
+		fn buy_execution() -> Weight { 10 }
+		fn report_outcome() -> Weight { 1 }
+	}
+	parameter_types! {
+		pub const MaxAssetsInHolding: u32 = 4;
+	}
+	type Weigher = WeightInfoBounds<TestWeightInfo, TestCall, MaxAssetsInHolding>;
+
+	let mut message = Xcm::<TestCall>::WithdrawAsset {
+		assets: vec![
+			ConcreteFungible { id: X1(Parent), amount: 100 },
+			ConcreteFungible { id: Null, amount: 100 },
+		],
+		effects: vec![
+			Order::BuyExecution { fees: All, weight: 0, debt: 30, halt_on_error: true, xcm: vec![
+				Xcm::<TestCall>::Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: 50,
+					call: TestCall::Any(50, None).encode().into(),
+				},
+			] },
+			Order::DepositAsset { assets: vec![All], dest: Null },
+		],
+	};
+	// Two assets withdrawn, one execution bought and a deposit of as many assets as holding may contain.
+	assert_eq!(Weigher::shallow(&mut message), Ok(2 * 100 + 10 + 4 * 20));
+	// The transact and its call.
+	assert_eq!(Weigher::deep(&mut message), Ok(1_000 + 50));
+
+	// An instruction without assets still costs as much as one with a single asset.
+	let mut message = Xcm::<TestCall>::TransferAsset { assets: vec![], dest: Null };
+	assert_eq!(Weigher::shallow(&mut message), Ok(1));
+}
+
 #[test]
 fn take_weight_credit_barrier_should_work() {
 	let mut message = opaque::Xcm::TransferAsset {
@@ -313,6 +372,177 @@ fn paid_transacting_should_refund_payment_for_unused_weight() {
 	assert_eq!(assets(1), vec![ ConcreteFungible { id: X1(Parent), amount: 50 } ]);
 }
 
+#[test]
+fn fixed_rate_of_concrete_fungibles_should_work() {
+	parameter_types! {
+		// 1_000_000_000_000 => 1 unit of asset for 1 unit of Weight.
+		pub WeightPrices: Vec<(MultiLocation, u128)> = vec![
+			(X1(Parent), 1_000_000_000_000),
+			(X1(GeneralIndex { id: 1 }), 2_000_000_000_000),
+		];
+		pub static Revenue: Vec<MultiAsset> = vec![];
+	}
+	struct TestRevenue;
+	impl TakeRevenue for TestRevenue {
+		fn take_revenue(revenue: MultiAsset) {
+			Revenue::set(Revenue::get().into_iter().chain(Some(revenue)).collect());
+		}
+	}
+	type Trader = FixedRateOfConcreteFungibles<WeightPrices, TestRevenue>;
+	let other = |amount| ConcreteFungible { id: X1(GeneralIndex { id: 1 }), amount };
+	let parent = |amount| ConcreteFungible { id: X1(Parent), amount };
+
+	let mut trader = Trader::new();
+	// An asset which is not in the table cannot be used to pay...
+	let unknown = ConcreteFungible { id: X1(GeneralIndex { id: 2 }), amount: 100 };
+	assert_eq!(trader.buy_weight(10, unknown.into()).map(|_| ()), Err(XcmError::TooExpensive));
+	// ...but any which is can, at its own rate.
+	let unused = trader.buy_weight(10, vec![ other(100) ].into()).unwrap();
+	assert_eq!(Vec::<MultiAsset>::from(unused), vec![ other(80) ]);
+	// An asset already used to pay is preferred, even when it's not the first in the table.
+	let unused = trader.buy_weight(10, vec![ parent(100), other(100) ].into()).unwrap();
+	assert_eq!(Vec::<MultiAsset>::from(unused), vec![ parent(100), other(80) ]);
+	// Another is used if there's not enough of it.
+	let unused = trader.buy_weight(10, vec![ parent(100), other(10) ].into()).unwrap();
+	assert_eq!(Vec::<MultiAsset>::from(unused), vec![ parent(90), other(10) ]);
+
+	// Refunds are made in the asset most recently paid with, up to the weight bought with it.
+	assert_eq!(trader.refund_weight(15), parent(10));
+	drop(trader);
+	assert_eq!(Revenue::get(), vec![ other(40) ]);
+}
+
+#[test]
+fn report_outcome_should_work() {
+	let one = X1(AccountIndex64{index:1, network:Any});
+	AllowPaidFrom::set(vec![ one.clone() ]);
+	add_asset(1, ConcreteFungible { id: X1(Parent), amount: 300 });
+	WeightPrice::set((X1(Parent), 1_000_000_000_000));
+
+	let message = |query_id, call: TestCall, dest: MultiLocation| Xcm::<TestCall>::WithdrawAsset {
+		assets: vec![ ConcreteFungible { id: X1(Parent), amount: 100 } ],	// enough for 100 units of weight.
+		effects: vec![
+			Order::<TestCall>::BuyExecution { fees: All, weight: 20, debt: 40, halt_on_error: true, xcm: vec![
+				Xcm::<TestCall>::Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: 10,
+					call: call.encode().into(),
+				}
+			] },
+			Order::<TestCall>::DepositAsset { assets: vec![ All ], dest },
+			Order::<TestCall>::ReportOutcome { query_id, dest: one.clone() },
+		],
+	};
+	let weight_limit = 100;
+
+	// Everything succeeds, so a successful outcome is reported.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(
+		one.clone(),
+		message(1, TestCall::Any(10, None), one.clone()),
+		weight_limit,
+	);
+	assert_eq!(r, Outcome::Complete(60));
+	assert_eq!(assets(1), vec![ ConcreteFungible { id: X1(Parent), amount: 240 } ]);
+	assert_eq!(sent_xcm(), vec![(
+		one.clone(),
+		Xcm::QueryResponse { query_id: 1, response: Response::ExecutionResult(Ok(())) },
+	)]);
+
+	// A failing call doesn't fail the message, so the remaining orders still execute.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(
+		one.clone(),
+		message(2, TestCall::OnlyRoot(10, None), one.clone()),
+		weight_limit,
+	);
+	assert_eq!(r, Outcome::Complete(60));
+	assert_eq!(assets(1), vec![ ConcreteFungible { id: X1(Parent), amount: 180 } ]);
+	assert_eq!(sent_xcm()[1], (
+		one.clone(),
+		Xcm::QueryResponse { query_id: 2, response: Response::ExecutionResult(Ok(())) },
+	));
+
+	// The deposit fails, so the failure is reported and the assets are trapped.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(
+		one.clone(),
+		message(3, TestCall::Any(10, None), X1(GeneralIndex { id: 69 })),
+		weight_limit,
+	);
+	assert_eq!(r, Outcome::Incomplete(60, XcmError::LocationCannotHold));
+	assert_eq!(assets(1), vec![ ConcreteFungible { id: X1(Parent), amount: 80 } ]);
+	assert_eq!(sent_xcm()[2], (
+		one.clone(),
+		Xcm::QueryResponse {
+			query_id: 3,
+			response: Response::ExecutionResult(Err(Box::new(XcmError::LocationCannotHold))),
+		},
+	));
+}
+
+#[test]
+fn trapped_assets_can_be_claimed() {
+	let one = X1(AccountIndex64{index:1, network:Any});
+	AllowPaidFrom::set(vec![ one.clone() ]);
+	add_asset(1, ConcreteFungible { id: X1(Parent), amount: 100 });
+	WeightPrice::set((X1(Parent), 1_000_000_000_000));
+
+	let message = Xcm::<TestCall>::WithdrawAsset {
+		assets: vec![ ConcreteFungible { id: X1(Parent), amount: 100 } ],
+		effects: vec![
+			Order::<TestCall>::BuyExecution { fees: All, weight: 0, debt: 30, halt_on_error: true, xcm: vec![] },
+			// This will fail since the destination is not one we can deposit into.
+			Order::<TestCall>::DepositAsset { assets: vec![ All ], dest: X1(GeneralIndex { id: 69 }) },
+		],
+	};
+	let r = XcmExecutor::<TestConfig>::execute_xcm(one.clone(), message, 30);
+	assert_eq!(r, Outcome::Incomplete(30, XcmError::LocationCannotHold));
+	assert_eq!(assets(1), vec![]);
+	let leftover = vec![ ConcreteFungible { id: X1(Parent), amount: 70 } ];
+	assert_eq!(trapped_assets(), vec![ (one.clone(), leftover.clone()) ]);
+
+	let message = |origin: &MultiLocation| Xcm::<TestCall>::ClaimAsset {
+		assets: leftover.clone(),
+		ticket: Null,
+		effects: vec![
+			Order::<TestCall>::BuyExecution { fees: All, weight: 0, debt: 30, halt_on_error: true, xcm: vec![] },
+			Order::<TestCall>::DepositAsset { assets: vec![ All ], dest: origin.clone() },
+		],
+	};
+
+	// Another origin cannot claim them...
+	let two = X1(AccountIndex64{index:2, network:Any});
+	AllowPaidFrom::set(vec![ one.clone(), two.clone() ]);
+	let r = XcmExecutor::<TestConfig>::execute_xcm(two.clone(), message(&two), 30);
+	assert_eq!(r, Outcome::Incomplete(30, XcmError::UnknownClaim));
+
+	// ...but the origin which they were trapped for can, exactly once.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(one.clone(), message(&one), 30);
+	assert_eq!(r, Outcome::Complete(30));
+	assert_eq!(assets(1), vec![ ConcreteFungible { id: X1(Parent), amount: 40 } ]);
+	assert!(trapped_assets().is_empty());
+
+	let r = XcmExecutor::<TestConfig>::execute_xcm(one.clone(), message(&one), 30);
+	assert_eq!(r, Outcome::Incomplete(30, XcmError::UnknownClaim));
+}
+
+#[test]
+fn version_subscription_instructions_should_work() {
+	let origin = X1(Parachain(1000));
+	let subscribe = Xcm::<TestCall>::SubscribeVersion { query_id: 42, max_response_weight: 5000 };
+
+	// Not allowed by the barrier yet.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(origin.clone(), subscribe.clone(), 10);
+	assert_eq!(r, Outcome::Incomplete(10, XcmError::Barrier));
+
+	AllowSubsFrom::set(vec![ origin.clone() ]);
+	let r = XcmExecutor::<TestConfig>::execute_xcm(origin.clone(), subscribe, 10);
+	assert_eq!(r, Outcome::Complete(10));
+	assert_eq!(subscriptions(), vec![ (origin.clone(), 42, 5000) ]);
+
+	let r = XcmExecutor::<TestConfig>::execute_xcm(origin.clone(), Xcm::UnsubscribeVersion, 10);
+	assert_eq!(r, Outcome::Complete(10));
+	assert!(subscriptions().is_empty());
+}
+
 #[test]
 fn prepaid_result_of_query_should_get_free_execution() {
 	let query_id = 33;
@@ -325,11 +555,16 @@ fn prepaid_result_of_query_should_get_free_execution() {
 		query_id,
 		response: the_response.clone(),
 	};
-	let weight_limit = 10;
+	let weight_limit = 30;
 
-	// First time the response gets through since we're expecting it...
+	// The weight the response handler may use must fit in the limit...
+	let r = XcmExecutor::<TestConfig>::execute_xcm(origin.clone(), message.clone(), 20);
+	assert_eq!(r, Outcome::Error(XcmError::WeightLimitReached(30)));
+
+	// ...and what it doesn't use is refunded. The first time the response gets through since we're expecting
+	// it...
 	let r = XcmExecutor::<TestConfig>::execute_xcm(origin.clone(), message.clone(), weight_limit);
-	assert_eq!(r, Outcome::Complete(10));
+	assert_eq!(r, Outcome::Complete(20));
 	assert_eq!(response(query_id).unwrap(), the_response);
 
 	// Second time it doesn't, since we're not.
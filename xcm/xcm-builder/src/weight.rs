@@ -14,13 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use sp_std::{result::Result, marker::PhantomData, convert::TryInto};
+use sp_std::{prelude::*, result::Result, marker::PhantomData, convert::TryInto};
 use parity_scale_codec::Decode;
 use xcm::v0::{Xcm, Order, MultiAsset, MultiLocation, Error};
 use sp_runtime::traits::{Zero, Saturating, SaturatedConversion};
 use frame_support::traits::{Get, OnUnbalanced as OnUnbalancedT, tokens::currency::Currency as CurrencyT};
 use frame_support::weights::{Weight, GetDispatchInfo, WeightToFeePolynomial};
-use xcm_executor::{Assets, traits::{WeightBounds, WeightTrader}};
+use xcm_executor::{Assets, traits::{WeightBounds, WeightTrader, XcmWeightInfo}};
 
 pub struct FixedWeightBounds<T, C>(PhantomData<(T, C)>);
 impl<T: Get<Weight>, C: Decode + GetDispatchInfo> WeightBounds<C> for FixedWeightBounds<T, C> {
@@ -33,6 +33,7 @@ impl<T: Get<Weight>, C: Decode + GetDispatchInfo> WeightBounds<C> for FixedWeigh
 			Xcm::WithdrawAsset { effects, .. }
 			| Xcm::ReserveAssetDeposit { effects, .. }
 			| Xcm::TeleportAsset { effects, .. }
+			| Xcm::ClaimAsset { effects, .. }
 			=> {
 				let inner: Weight = effects.iter_mut()
 					.map(|effect| match effect {
@@ -57,6 +58,7 @@ impl<T: Get<Weight>, C: Decode + GetDispatchInfo> WeightBounds<C> for FixedWeigh
 			Xcm::WithdrawAsset { effects, .. }
 			| Xcm::ReserveAssetDeposit { effects, .. }
 			| Xcm::TeleportAsset { effects, .. }
+			| Xcm::ClaimAsset { effects, .. }
 			=> {
 				let mut extra = 0;
 				for effect in effects.iter_mut() {
@@ -76,6 +78,92 @@ impl<T: Get<Weight>, C: Decode + GetDispatchInfo> WeightBounds<C> for FixedWeigh
 	}
 }
 
+/// Weigher which uses the weight of each instruction and order as given by `W`, typically the result of
+/// benchmarking, rather than a fixed weight for all of them.
+///
+/// Those which carry assets are weighed per asset, and a wildcard is weighed as the `M` assets the holding
+/// register is limited to.
+pub struct WeightInfoBounds<W, C, M>(PhantomData<(W, C, M)>);
+impl<W: XcmWeightInfo, C: Decode + GetDispatchInfo, M: Get<u32>> WeightBounds<C> for WeightInfoBounds<W, C, M> {
+	fn shallow(message: &mut Xcm<C>) -> Result<Weight, ()> {
+		Ok(match message {
+			Xcm::WithdrawAsset { assets, effects } =>
+				Self::per_asset(W::withdraw_asset(), assets).saturating_add(Self::shallow_effects(effects)),
+			Xcm::ReserveAssetDeposit { assets, effects } =>
+				Self::per_asset(W::reserve_asset_deposit(), assets).saturating_add(Self::shallow_effects(effects)),
+			Xcm::TeleportAsset { assets, effects } =>
+				Self::per_asset(W::teleport_asset(), assets).saturating_add(Self::shallow_effects(effects)),
+			Xcm::ClaimAsset { assets, effects, .. } =>
+				Self::per_asset(W::claim_asset(), assets).saturating_add(Self::shallow_effects(effects)),
+			Xcm::QueryResponse { .. } => W::query_response(),
+			Xcm::SubscribeVersion { .. } => W::subscribe_version(),
+			Xcm::UnsubscribeVersion => W::unsubscribe_version(),
+			Xcm::TransferAsset { assets, .. } => Self::per_asset(W::transfer_asset(), assets),
+			// The effects are not executed here but sent on to the destination.
+			Xcm::TransferReserveAsset { assets, .. } => Self::per_asset(W::transfer_reserve_asset(), assets),
+			Xcm::Transact { call, .. } =>
+				call.ensure_decoded()?.get_dispatch_info().weight.saturating_add(W::transact()),
+			Xcm::HrmpNewChannelOpenRequest { .. } => W::hrmp_new_channel_open_request(),
+			Xcm::HrmpChannelAccepted { .. } => W::hrmp_channel_accepted(),
+			Xcm::HrmpChannelClosing { .. } => W::hrmp_channel_closing(),
+			Xcm::RelayedFrom { ref mut message, .. } =>
+				W::relayed_from().saturating_add(Self::shallow(message.as_mut())?),
+		})
+	}
+
+	fn deep(message: &mut Xcm<C>) -> Result<Weight, ()> {
+		Ok(match message {
+			Xcm::RelayedFrom { ref mut message, .. } => Self::deep(message.as_mut())?,
+			Xcm::WithdrawAsset { effects, .. }
+			| Xcm::ReserveAssetDeposit { effects, .. }
+			| Xcm::TeleportAsset { effects, .. }
+			| Xcm::ClaimAsset { effects, .. }
+			=> {
+				let mut extra: Weight = 0;
+				for effect in effects.iter_mut() {
+					if let Order::BuyExecution { xcm, .. } = effect {
+						for message in xcm.iter_mut() {
+							extra = extra
+								.saturating_add(Self::shallow(message)?)
+								.saturating_add(Self::deep(message)?);
+						}
+					}
+				}
+				extra
+			},
+			_ => 0,
+		})
+	}
+}
+
+impl<W: XcmWeightInfo, C, M: Get<u32>> WeightInfoBounds<W, C, M> {
+	/// The weight of an instruction or order which costs `weight` for each of its `assets`, of which there is
+	/// at least one.
+	fn per_asset(weight: Weight, assets: &[MultiAsset]) -> Weight {
+		let count = assets.iter()
+			.map(|asset| if asset.is_wildcard() { M::get() as Weight } else { 1 })
+			.fold(0, |total: Weight, count| total.saturating_add(count));
+		weight.saturating_mul(count.max(1))
+	}
+
+	fn shallow_effects(effects: &[Order<C>]) -> Weight {
+		effects.iter()
+			.map(|effect| match effect {
+				Order::Null => W::order_null(),
+				Order::DepositAsset { assets, .. } => Self::per_asset(W::deposit_asset(), assets),
+				Order::DepositReserveAsset { assets, .. } => Self::per_asset(W::deposit_reserve_asset(), assets),
+				Order::ExchangeAsset { give, .. } => Self::per_asset(W::exchange_asset(), give),
+				Order::InitiateReserveWithdraw { assets, .. } => Self::per_asset(W::initiate_reserve_withdraw(), assets),
+				Order::InitiateTeleport { assets, .. } => Self::per_asset(W::initiate_teleport(), assets),
+				Order::QueryHolding { .. } => W::query_holding(),
+				// As with `FixedWeightBounds`, the XCM bought with this is counted only in the deep weight.
+				Order::BuyExecution { .. } => W::buy_execution(),
+				Order::ReportOutcome { .. } => W::report_outcome(),
+			})
+			.fold(0, |total: Weight, weight| total.saturating_add(weight))
+	}
+}
+
 /// Function trait for handling some revenue. Similar to a negative imbalance (credit) handler, but for a
 /// `MultiAsset`. Sensible implementations will deposit the asset in some known treasury or block-author account.
 pub trait TakeRevenue {
@@ -128,6 +216,80 @@ impl<T: Get<(MultiLocation, u128)>, R: TakeRevenue> Drop for FixedRateOfConcrete
 	}
 }
 
+/// The weight bought with, and the amount paid of, one of the assets accepted by `FixedRateOfConcreteFungibles`.
+struct Purchase {
+	id: MultiLocation,
+	units_per_second: u128,
+	weight: Weight,
+	amount: u128,
+}
+
+/// Fee calculator that accepts payment in any of several concrete fungibles, each at its own fixed rate.
+///
+/// The constant `Get` type parameter should give, for each accepted asset, its concrete fungible ID and the
+/// amount of it required for one second of weight. It may be backed by storage in order that the rates can be
+/// changed without a runtime upgrade.
+///
+/// Weight is paid for in the first asset of the table which `payment` holds enough of, although an asset which
+/// has already been used to pay is always tried first so that refunds are made in as few assets as possible.
+pub struct FixedRateOfConcreteFungibles<
+	T: Get<Vec<(MultiLocation, u128)>>,
+	R: TakeRevenue,
+>(Vec<Purchase>, PhantomData<(T, R)>);
+impl<T: Get<Vec<(MultiLocation, u128)>>, R: TakeRevenue> WeightTrader for FixedRateOfConcreteFungibles<T, R> {
+	fn new() -> Self { Self(Vec::new(), PhantomData) }
+
+	fn buy_weight(&mut self, weight: Weight, payment: Assets) -> Result<Assets, Error> {
+		use frame_support::weights::constants::WEIGHT_PER_SECOND;
+		let (used, unused): (Vec<_>, Vec<_>) = T::get().into_iter()
+			.partition(|(id, _)| self.0.iter().any(|p| &p.id == id));
+		let mut payment = payment;
+		for (id, units_per_second) in used.into_iter().chain(unused) {
+			let amount = units_per_second.saturating_mul(weight as u128) / (WEIGHT_PER_SECOND as u128);
+			let required = MultiAsset::ConcreteFungible { amount, id: id.clone() };
+			payment = match payment.less(required) {
+				Ok((unused, _)) => {
+					match self.0.iter_mut().find(|p| p.id == id) {
+						Some(p) => {
+							p.weight = p.weight.saturating_add(weight);
+							p.amount = p.amount.saturating_add(amount);
+						}
+						None => self.0.push(Purchase { id, units_per_second, weight, amount }),
+					}
+					return Ok(unused)
+				}
+				Err(payment) => payment,
+			};
+		}
+		Err(Error::TooExpensive)
+	}
+
+	fn refund_weight(&mut self, weight: Weight) -> MultiAsset {
+		use frame_support::weights::constants::WEIGHT_PER_SECOND;
+		// Only one asset can be refunded, so it's the one most recently used to pay.
+		let purchase = match self.0.last_mut() {
+			Some(p) => p,
+			None => return MultiAsset::None,
+		};
+		let weight = weight.min(purchase.weight);
+		let amount = purchase.units_per_second.saturating_mul(weight as u128) / (WEIGHT_PER_SECOND as u128);
+		let amount = amount.min(purchase.amount);
+		purchase.weight -= weight;
+		purchase.amount -= amount;
+		MultiAsset::ConcreteFungible { amount, id: purchase.id.clone() }
+	}
+}
+
+impl<T: Get<Vec<(MultiLocation, u128)>>, R: TakeRevenue> Drop for FixedRateOfConcreteFungibles<T, R> {
+	fn drop(&mut self) {
+		for Purchase { id, amount, .. } in self.0.drain(..) {
+			if amount > 0 {
+				R::take_revenue(MultiAsset::ConcreteFungible { amount, id });
+			}
+		}
+	}
+}
+
 /// Weight trader which uses the TransactionPayment pallet to set the right price for weight and then
 /// places any weight bought into the right account.
 pub struct UsingComponents<
@@ -93,6 +93,11 @@ impl Assets {
 	/// New value, containing no assets.
 	pub fn new() -> Self { Self::default() }
 
+	/// Returns `true` if `self` contains no assets.
+	pub fn is_empty(&self) -> bool {
+		self.fungible.is_empty() && self.non_fungible.is_empty()
+	}
+
 	/// An iterator over the fungible assets.
 	pub fn fungible_assets_iter<'a>(&'a self) -> impl Iterator<Item=MultiAsset> + 'a {
 		self.fungible.iter()
@@ -249,6 +254,10 @@ impl Assets {
 	pub fn try_remove_fungible(&mut self, id: &AssetId, amount: u128) -> Result<(), ()> {
 		let self_amount = self.fungible.get_mut(&id).ok_or(())?;
 		*self_amount = self_amount.checked_sub(amount).ok_or(())?;
+		if *self_amount == 0 {
+			// Keep `self` free of empty entries so that `is_empty` and the list of assets are accurate.
+			self.fungible.remove(id);
+		}
 		Ok(())
 	}
 
@@ -262,6 +271,9 @@ impl Assets {
 	/// Modify `self` to include a new fungible asset by `id` and `amount`,
 	/// saturating if necessary.
 	pub fn saturating_subsume_fungible(&mut self, id: AssetId, amount: u128) {
+		if amount == 0 {
+			return
+		}
 		self.fungible
 			.entry(id)
 			.and_modify(|e| *e = e.saturating_add(amount))
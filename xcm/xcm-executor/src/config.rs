@@ -19,7 +19,7 @@ use frame_support::dispatch::{Dispatchable, Parameter};
 use frame_support::weights::{PostDispatchInfo, GetDispatchInfo};
 use crate::traits::{
 	TransactAsset, ConvertOrigin, FilterAssetLocation, InvertLocation, ShouldExecute, WeightTrader, WeightBounds,
-	OnResponse,
+	OnResponse, DropAssets, ClaimAssets, VersionChangeNotifier,
 };
 
 /// The trait to parametrize the `XcmExecutor`.
@@ -56,4 +56,13 @@ pub trait Config {
 
 	/// What to do when a response of a query is found.
 	type ResponseHandler: OnResponse;
+
+	/// What to do with assets which are left in the holding register once a message's orders have run.
+	type AssetTrap: DropAssets;
+
+	/// The handler for `ClaimAsset` instructions.
+	type AssetClaims: ClaimAssets;
+
+	/// How to handle requests to be notified of changes to the XCM version we support.
+	type SubscriptionService: VersionChangeNotifier;
 }
@@ -29,7 +29,7 @@ use xcm::v0::{
 pub mod traits;
 use traits::{
 	TransactAsset, ConvertOrigin, FilterAssetLocation, InvertLocation, WeightBounds, WeightTrader,
-	ShouldExecute, OnResponse
+	ShouldExecute, OnResponse, DropAssets, ClaimAssets, VersionChangeNotifier,
 };
 
 mod assets;
@@ -57,7 +57,11 @@ impl<Config: config::Config> ExecuteXcm<Config::Call> for XcmExecutor<Config> {
 			Ok(x) => x,
 			Err(()) => return Outcome::Error(XcmError::WeightNotComputable),
 		};
-		let maximum_weight = match shallow_weight.checked_add(deep_weight) {
+		// Whatever a response handler may use comes on top of the weight of the message itself.
+		let response_weight = Self::max_response_weight(&origin, &message);
+		let maximum_weight = match shallow_weight.checked_add(deep_weight)
+			.and_then(|weight| weight.checked_add(response_weight))
+		{
 			Some(x) => x,
 			None => return Outcome::Error(XcmError::Overflow),
 		};
@@ -83,6 +87,22 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		assets.into_assets_iter().collect::<Vec<_>>()
 	}
 
+	/// The most weight that the `Config::ResponseHandler` may use for the top-level `QueryResponse` of `message`,
+	/// if there is one.
+	fn max_response_weight(origin: &MultiLocation, message: &Xcm<Config::Call>) -> Weight {
+		match message {
+			Xcm::QueryResponse { query_id, .. } => Config::ResponseHandler::max_response_weight(origin, *query_id),
+			Xcm::RelayedFrom { who, message } => {
+				let mut origin = origin.clone();
+				match origin.append_with(who.clone()) {
+					Ok(()) => Self::max_response_weight(&origin, message),
+					Err(_) => 0,
+				}
+			}
+			_ => 0,
+		}
+	}
+
 	/// Execute the XCM and return the portion of weight of `shallow_weight + deep_weight` that `message` did not use.
 	///
 	/// NOTE: The amount returned must be less than `shallow_weight + deep_weight` of `message`.
@@ -195,8 +215,29 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				// execution has taken.
 				None
 			}
+			(origin, Xcm::ClaimAsset { assets, ticket, effects }) => {
+				for asset in assets.iter() {
+					ensure!(!asset.is_wildcard(), XcmError::Wildcard);
+				}
+				let claimed = Config::AssetClaims::claim_assets(&origin, &ticket, &assets);
+				ensure!(claimed, XcmError::UnknownClaim);
+				Some((Assets::from(assets), effects))
+			}
+			(origin, Xcm::SubscribeVersion { query_id, max_response_weight }) => {
+				Config::SubscriptionService::start(&origin, query_id, max_response_weight)?;
+				None
+			}
+			(origin, Xcm::UnsubscribeVersion) => {
+				Config::SubscriptionService::stop(&origin)?;
+				None
+			}
 			(origin, Xcm::QueryResponse { query_id, response }) => {
-				Config::ResponseHandler::on_response(origin, query_id, response);
+				// The weight of the response handler is only reserved for a top-level response.
+				ensure!(top_level, XcmError::UnhandledXcmMessage);
+				let max_weight = Config::ResponseHandler::max_response_weight(&origin, query_id);
+				let weight = Config::ResponseHandler::on_response(origin, query_id, response);
+				// Whatever the handler didn't use of the reserved weight is surplus.
+				total_surplus = total_surplus.saturating_add(max_weight.saturating_sub(weight));
 				None
 			}
 			(origin, Xcm::RelayedFrom { who, message }) => {
@@ -211,15 +252,39 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		};
 
 		if let Some((mut holding, effects)) = maybe_holding_effects {
+			let mut outcome = Ok(());
 			for effect in effects.into_iter() {
-				total_surplus += Self::execute_effects(&origin, &mut holding, effect, trader)?;
+				match effect {
+					Order::ReportOutcome { query_id, dest } => {
+						let response = Response::ExecutionResult(outcome.clone().map_err(Box::new));
+						if let Err(e) = Config::XcmSender::send_xcm(dest, Xcm::QueryResponse { query_id, response }) {
+							outcome = outcome.and(Err(e));
+						}
+					}
+					// Once an order has failed, the only thing left to do is reporting it.
+					_ if outcome.is_err() => {}
+					effect => match Self::execute_effects(&origin, &mut holding, effect, trader) {
+						Ok(surplus) => total_surplus += surplus,
+						Err(e) => outcome = Err(e),
+					},
+				}
 			}
+			// Whatever is left in holding would otherwise be lost, so it's trapped in order that `origin` may later
+			// claim it.
+			if !holding.is_empty() {
+				Config::AssetTrap::drop_assets(&origin, holding);
+			}
+			outcome?;
 		}
 
 		Ok(total_surplus)
 	}
 
-	fn execute_effects(
+	/// Execute a single order `effect` on `holding`, returning the surplus weight as `do_execute_xcm` does.
+	///
+	/// This is public only so that orders can be benchmarked in isolation; messages should be executed through
+	/// `ExecuteXcm`.
+	pub fn execute_effects(
 		origin: &MultiLocation,
 		holding: &mut Assets,
 		effect: Order<Config::Call>,
@@ -228,9 +293,14 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		let mut total_surplus = 0;
 		match effect {
 			Order::DepositAsset { assets, dest } => {
-				let deposited = holding.saturating_take(assets);
-				for asset in deposited.into_assets_iter() {
-					Config::AssetTransactor::deposit_asset(&asset, &dest)?;
+				let mut deposited = holding.saturating_take(assets).into_assets_iter();
+				while let Some(asset) = deposited.next() {
+					if let Err(e) = Config::AssetTransactor::deposit_asset(&asset, &dest) {
+						// Put back whatever could not be deposited so that it gets trapped rather than lost.
+						holding.saturating_subsume(asset);
+						deposited.for_each(|asset| holding.saturating_subsume(asset));
+						return Err(e)
+					}
 				}
 			},
 			Order::DepositReserveAsset { assets, dest, effects } => {
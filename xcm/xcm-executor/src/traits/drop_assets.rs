@@ -0,0 +1,37 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use xcm::v0::{MultiAsset, MultiLocation};
+use crate::Assets;
+
+/// Define a handler for when some non-empty `Assets` value should be dropped.
+pub trait DropAssets {
+	/// Handler for receiving dropped assets which were left in the holding register of a message from `origin`.
+	fn drop_assets(origin: &MultiLocation, assets: Assets);
+}
+impl DropAssets for () {
+	fn drop_assets(_origin: &MultiLocation, _assets: Assets) {}
+}
+
+/// Define any handlers for the `ClaimAsset` instruction.
+pub trait ClaimAssets {
+	/// Claim any assets available to `origin` and return `true` if they have been claimed. `ticket` and `what` are
+	/// the values given in the `ClaimAsset` instruction.
+	fn claim_assets(origin: &MultiLocation, ticket: &MultiLocation, what: &[MultiAsset]) -> bool;
+}
+impl ClaimAssets for () {
+	fn claim_assets(_origin: &MultiLocation, _ticket: &MultiLocation, _what: &[MultiAsset]) -> bool { false }
+}
@@ -27,6 +27,8 @@ pub enum Error {
 	AmountToBalanceConversionFailed,
 	/// `MultiLocation` to `AssetId` conversion failed.
 	AssetIdConversionFailed,
+	/// `AssetInstance` to non-fungibles instance ID conversion failed.
+	InstanceConversionFailed,
 }
 
 impl From<Error> for XcmError {
@@ -37,6 +39,7 @@ impl From<Error> for XcmError {
 			Error::AccountIdConversionFailed => FailedToTransactAsset("AccountIdConversionFailed"),
 			Error::AmountToBalanceConversionFailed => FailedToTransactAsset("AmountToBalanceConversionFailed"),
 			Error::AssetIdConversionFailed => FailedToTransactAsset("AssetIdConversionFailed"),
+			Error::InstanceConversionFailed => FailedToTransactAsset("InstanceConversionFailed"),
 		}
 	}
 }
@@ -0,0 +1,37 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use xcm::v0::MultiAsset;
+use sp_std::result;
+use crate::traits::Error;
+
+pub trait MatchesNonFungibles<ClassId, InstanceId> {
+	fn matches_nonfungibles(a: &MultiAsset) -> result::Result<(ClassId, InstanceId), Error>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<
+	ClassId,
+	InstanceId,
+> MatchesNonFungibles<ClassId, InstanceId> for Tuple {
+	fn matches_nonfungibles(a: &MultiAsset) -> result::Result<(ClassId, InstanceId), Error> {
+		for_tuples!( #(
+			match Tuple::matches_nonfungibles(a) { o @ Ok(_) => return o, _ => () }
+		)* );
+		Err(Error::AssetNotFound)
+	}
+}
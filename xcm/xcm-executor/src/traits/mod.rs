@@ -18,17 +18,23 @@
 
 mod conversion;
 pub use conversion::{InvertLocation, ConvertOrigin, Convert, JustTry, Identity, Encoded, Decoded};
+mod drop_assets;
+pub use drop_assets::{DropAssets, ClaimAssets};
 mod filter_asset_location;
 pub use filter_asset_location::{FilterAssetLocation};
 mod matches_fungible;
 pub use matches_fungible::{MatchesFungible};
 mod matches_fungibles;
 pub use matches_fungibles::{MatchesFungibles, Error};
+mod matches_non_fungible;
+pub use matches_non_fungible::{MatchesNonFungible};
+mod matches_non_fungibles;
+pub use matches_non_fungibles::{MatchesNonFungibles};
 mod on_response;
-pub use on_response::OnResponse;
+pub use on_response::{OnResponse, VersionChangeNotifier};
 mod should_execute;
 pub use should_execute::ShouldExecute;
 mod transact_asset;
 pub use transact_asset::TransactAsset;
 mod weight;
-pub use weight::{WeightBounds, WeightTrader};
+pub use weight::{WeightBounds, WeightTrader, XcmWeightInfo};
@@ -14,17 +14,41 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use xcm::v0::{Response, MultiLocation};
+use xcm::v0::{Response, MultiLocation, Result as XcmResult, Error as XcmError};
 use frame_support::weights::Weight;
 
 /// Define what needs to be done upon receiving a query response.
 pub trait OnResponse {
 	/// Returns `true` if we are expecting a response from `origin` for query `query_id`.
 	fn expecting_response(origin: &MultiLocation, query_id: u64) -> bool;
-	/// Handler for receiving a `response` from `origin` relating to `query_id`.
+	/// Returns the most weight that `on_response` may use for a response from `origin` for query `query_id`.
+	///
+	/// The executor reserves this on top of the weight of the `QueryResponse` before it executes it.
+	fn max_response_weight(origin: &MultiLocation, query_id: u64) -> Weight;
+	/// Handler for receiving a `response` from `origin` relating to `query_id`. Returns the weight used, which
+	/// must be no more than `max_response_weight` for it.
 	fn on_response(origin: MultiLocation, query_id: u64, response: Response) -> Weight;
 }
 impl OnResponse for () {
 	fn expecting_response(_origin: &MultiLocation, _query_id: u64) -> bool { false }
+	fn max_response_weight(_origin: &MultiLocation, _query_id: u64) -> Weight { 0 }
 	fn on_response(_origin: MultiLocation, _query_id: u64, _response: Response) -> Weight { 0 }
 }
+
+/// Define the handling of `SubscribeVersion` and `UnsubscribeVersion` instructions.
+pub trait VersionChangeNotifier {
+	/// Start notifying `location` of the XCM version supported by this chain, and of any later changes to it. The
+	/// notifications are `QueryResponse` messages of `query_id`, each of which should take no more than
+	/// `max_response_weight` to execute on `location`.
+	///
+	/// If `location` is already subscribed, its subscription is replaced.
+	fn start(location: &MultiLocation, query_id: u64, max_response_weight: u64) -> XcmResult;
+	/// Stop notifying `location` of changes to the XCM version supported by this chain.
+	fn stop(location: &MultiLocation) -> XcmResult;
+}
+impl VersionChangeNotifier for () {
+	fn start(_location: &MultiLocation, _query_id: u64, _max_response_weight: u64) -> XcmResult {
+		Err(XcmError::Unimplemented)
+	}
+	fn stop(_location: &MultiLocation) -> XcmResult { Err(XcmError::Unimplemented) }
+}
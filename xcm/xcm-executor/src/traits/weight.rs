@@ -50,6 +50,36 @@ pub trait WeightBounds<Call> {
 	}
 }
 
+/// The weight of executing each kind of XCM instruction and order once, generally as determined by benchmarking.
+///
+/// The weight of instructions and orders which carry assets is per asset. Anything executed as a consequence of
+/// an instruction or order, such as the call of a `Transact` or the XCM of a `BuyExecution`, is not included.
+pub trait XcmWeightInfo {
+	fn withdraw_asset() -> Weight;
+	fn reserve_asset_deposit() -> Weight;
+	fn teleport_asset() -> Weight;
+	fn query_response() -> Weight;
+	fn transfer_asset() -> Weight;
+	fn transfer_reserve_asset() -> Weight;
+	fn transact() -> Weight;
+	fn hrmp_new_channel_open_request() -> Weight;
+	fn hrmp_channel_accepted() -> Weight;
+	fn hrmp_channel_closing() -> Weight;
+	fn relayed_from() -> Weight;
+	fn claim_asset() -> Weight;
+	fn subscribe_version() -> Weight;
+	fn unsubscribe_version() -> Weight;
+	fn order_null() -> Weight;
+	fn deposit_asset() -> Weight;
+	fn deposit_reserve_asset() -> Weight;
+	fn exchange_asset() -> Weight;
+	fn initiate_reserve_withdraw() -> Weight;
+	fn initiate_teleport() -> Weight;
+	fn query_holding() -> Weight;
+	fn buy_execution() -> Weight;
+	fn report_outcome() -> Weight;
+}
+
 /// A means of getting approximate weight consumption for a given destination message executor and a
 /// message.
 pub trait UniversalWeigher {
@@ -0,0 +1,398 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An example network of one relay chain and two parachains, built with `xcm-simulator`.
+
+mod parachain;
+mod relay_chain;
+
+use polkadot_parachain::primitives::Id as ParaId;
+use sp_runtime::{traits::AccountIdConversion, AccountId32};
+use xcm_simulator::{decl_test_network, decl_test_parachain, decl_test_relay_chain};
+
+pub const ALICE: AccountId32 = AccountId32::new([0u8; 32]);
+pub const INITIAL_BALANCE: u128 = 1_000_000_000;
+pub const HRMP_SENDER_DEPOSIT: u128 = 1_000;
+pub const HRMP_RECIPIENT_DEPOSIT: u128 = 500;
+
+decl_test_parachain! {
+	pub struct ParaA {
+		Runtime = parachain::Runtime,
+		XcmpMessageHandler = parachain::MsgQueue,
+		DmpMessageHandler = parachain::MsgQueue,
+		new_ext = para_ext(1),
+	}
+}
+
+decl_test_parachain! {
+	pub struct ParaB {
+		Runtime = parachain::Runtime,
+		XcmpMessageHandler = parachain::MsgQueue,
+		DmpMessageHandler = parachain::MsgQueue,
+		new_ext = para_ext(2),
+	}
+}
+
+decl_test_relay_chain! {
+	pub struct Relay {
+		Runtime = relay_chain::Runtime,
+		XcmConfig = relay_chain::XcmConfig,
+		new_ext = relay_ext(),
+	}
+}
+
+decl_test_network! {
+	pub struct MockNet {
+		relay_chain = Relay,
+		parachains = vec![
+			(1, ParaA),
+			(2, ParaB),
+		],
+	}
+}
+
+pub fn para_account_id(id: u32) -> relay_chain::AccountId {
+	ParaId::from(id).into_account()
+}
+
+pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
+	use parachain::{MsgQueue, Origin, Runtime, System, Uniques};
+
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(ALICE, INITIAL_BALANCE)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		MsgQueue::set_para_id(para_id.into());
+		// the derivative class of the relay chain's NFT class `1`.
+		Uniques::force_create(Origin::root(), 1, ALICE, true).unwrap();
+	});
+	ext
+}
+
+pub fn relay_ext() -> sp_io::TestExternalities {
+	use polkadot_parachain::primitives::{HeadData, ValidationCode};
+	use polkadot_runtime_parachains::{configuration::HostConfiguration, paras::ParaGenesisArgs};
+	use relay_chain::{Runtime, System};
+
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![
+			(ALICE, INITIAL_BALANCE),
+			(para_account_id(1), INITIAL_BALANCE),
+			(para_account_id(2), INITIAL_BALANCE),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	polkadot_runtime_parachains::configuration::GenesisConfig::<Runtime> {
+		config: HostConfiguration {
+			max_downward_message_size: 1024,
+			hrmp_sender_deposit: HRMP_SENDER_DEPOSIT,
+			hrmp_recipient_deposit: HRMP_RECIPIENT_DEPOSIT,
+			hrmp_channel_max_capacity: 8,
+			hrmp_channel_max_total_size: 8 * 1024,
+			hrmp_channel_max_message_size: 1024,
+			hrmp_max_parachain_inbound_channels: 4,
+			hrmp_max_parachain_outbound_channels: 4,
+			..Default::default()
+		},
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let para_genesis = ParaGenesisArgs {
+		genesis_head: HeadData(vec![]),
+		validation_code: ValidationCode(vec![1]),
+		parachain: true,
+	};
+	polkadot_runtime_parachains::paras::GenesisConfig::<Runtime> {
+		paras: vec![(1.into(), para_genesis.clone()), (2.into(), para_genesis)],
+		_phdata: Default::default(),
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub type RelayChainPalletXcm = pallet_xcm::Pallet<relay_chain::Runtime>;
+pub type ParachainPalletXcm = pallet_xcm::Pallet<parachain::Runtime>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use codec::Encode;
+	use frame_support::{assert_ok, traits::{tokens::nonfungibles::Inspect, PalletInfoAccess}};
+	use polkadot_runtime_parachains::hrmp;
+	use xcm::v0::{
+		AssetInstance,
+		Junction::{self, GeneralIndex, PalletInstance, Parachain, Parent},
+		MultiAsset::*,
+		MultiLocation::*,
+		NetworkId, OriginKind,
+		Xcm::*,
+	};
+	use xcm_simulator::TestExt;
+
+	fn print_events<T: frame_system::Config>(context: &str) {
+		println!("------ {:?} events ------", context);
+		frame_system::Pallet::<T>::events().iter().for_each(|r| {
+			println!("{:?}", r.event);
+		});
+	}
+
+	#[test]
+	fn reserve_transfer() {
+		MockNet::reset();
+
+		Relay::execute_with(|| {
+			assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+				relay_chain::Origin::signed(ALICE),
+				X1(Parachain(1)),
+				X1(Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }),
+				vec![ConcreteFungible { id: Null, amount: 123 }],
+				123,
+			));
+		});
+
+		ParaA::execute_with(|| {
+			// free execution, full amount received
+			assert_eq!(
+				pallet_balances::Pallet::<parachain::Runtime>::free_balance(&ALICE),
+				INITIAL_BALANCE + 123
+			);
+
+			print_events::<parachain::Runtime>("ParaA");
+		});
+	}
+
+	#[test]
+	fn reserve_transfer_nft() {
+		MockNet::reset();
+
+		Relay::execute_with(|| {
+			use relay_chain::{Origin, Uniques};
+			assert_ok!(Uniques::force_create(Origin::root(), 1, ALICE, true));
+			assert_ok!(Uniques::mint(Origin::signed(ALICE), 1, 42, ALICE));
+
+			let class = X2(PalletInstance(<Uniques as PalletInfoAccess>::index() as u8), GeneralIndex { id: 1 });
+			assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+				Origin::signed(ALICE),
+				X1(Parachain(1)),
+				X1(Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }),
+				vec![ConcreteNonFungible { class, instance: AssetInstance::Index { id: 42 } }],
+				0,
+			));
+			// the relay chain keeps the instance in reserve on behalf of the parachain
+			assert_eq!(Uniques::owner(&1, &42), Some(para_account_id(1)));
+		});
+
+		ParaA::execute_with(|| {
+			assert_eq!(parachain::Uniques::owner(&1, &42), Some(ALICE));
+
+			print_events::<parachain::Runtime>("ParaA");
+		});
+	}
+
+	#[test]
+	fn dmp() {
+		MockNet::reset();
+
+		let remark = parachain::Call::System(
+			frame_system::Call::<parachain::Runtime>::remark_with_event(vec![1, 2, 3])
+		);
+		Relay::execute_with(|| {
+			assert_ok!(RelayChainPalletXcm::send_xcm(
+				Null,
+				X1(Parachain(1)),
+				Transact {
+					origin_type: OriginKind::SovereignAccount,
+					require_weight_at_most: INITIAL_BALANCE as u64,
+					call: remark.encode().into(),
+				},
+			));
+		});
+
+		ParaA::execute_with(|| {
+			use parachain::{Event, System};
+			assert!(System::events()
+				.iter()
+				.any(|r| matches!(r.event, Event::System(frame_system::Event::Remarked(_, _)))));
+
+			print_events::<parachain::Runtime>("ParaA");
+		});
+	}
+
+	#[test]
+	fn ump() {
+		MockNet::reset();
+
+		let remark = relay_chain::Call::System(
+			frame_system::Call::<relay_chain::Runtime>::remark_with_event(vec![1, 2, 3])
+		);
+		ParaA::execute_with(|| {
+			assert_ok!(ParachainPalletXcm::send_xcm(
+				Null,
+				X1(Parent),
+				Transact {
+					origin_type: OriginKind::SovereignAccount,
+					require_weight_at_most: INITIAL_BALANCE as u64,
+					call: remark.encode().into(),
+				},
+			));
+		});
+
+		Relay::execute_with(|| {
+			use relay_chain::{Event, System};
+			assert!(System::events()
+				.iter()
+				.any(|r| matches!(r.event, Event::System(frame_system::Event::Remarked(_, _)))));
+
+			print_events::<relay_chain::Runtime>("RelayChain");
+		});
+	}
+
+	#[test]
+	fn xcmp() {
+		MockNet::reset();
+
+		let remark = parachain::Call::System(
+			frame_system::Call::<parachain::Runtime>::remark_with_event(vec![1, 2, 3])
+		);
+		ParaA::execute_with(|| {
+			assert_ok!(ParachainPalletXcm::send_xcm(
+				Null,
+				X2(Parent, Parachain(2)),
+				Transact {
+					origin_type: OriginKind::SovereignAccount,
+					require_weight_at_most: INITIAL_BALANCE as u64,
+					call: remark.encode().into(),
+				},
+			));
+		});
+
+		ParaB::execute_with(|| {
+			use parachain::{Event, System};
+			assert!(System::events()
+				.iter()
+				.any(|r| matches!(r.event, Event::System(frame_system::Event::Remarked(_, _)))));
+
+			print_events::<parachain::Runtime>("ParaB");
+		});
+	}
+
+	#[test]
+	fn hrmp_channel_opened_via_transact() {
+		MockNet::reset();
+
+		let init_open_channel = relay_chain::Call::Hrmp(
+			hrmp::Call::<relay_chain::Runtime>::hrmp_init_open_channel(2.into(), 8, 1024)
+		);
+		ParaA::execute_with(|| {
+			assert_ok!(ParachainPalletXcm::send_xcm(
+				Null,
+				X1(Parent),
+				Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: INITIAL_BALANCE as u64,
+					call: init_open_channel.encode().into(),
+				},
+			));
+		});
+
+		Relay::execute_with(|| {
+			use relay_chain::{Balances, Event, System};
+			assert!(System::events().iter().any(|r| matches!(
+				r.event,
+				Event::Hrmp(hrmp::Event::OpenChannelRequested(sender, recipient, 8, 1024))
+					if sender == 1.into() && recipient == 2.into()
+			)));
+
+			// The deposit is paid by the requesting para.
+			assert_eq!(Balances::reserved_balance(&para_account_id(1)), HRMP_SENDER_DEPOSIT);
+			assert_eq!(Balances::reserved_balance(&para_account_id(2)), 0);
+		});
+
+		let accept_open_channel = relay_chain::Call::Hrmp(
+			hrmp::Call::<relay_chain::Runtime>::hrmp_accept_open_channel(1.into())
+		);
+		ParaB::execute_with(|| {
+			assert_ok!(ParachainPalletXcm::send_xcm(
+				Null,
+				X1(Parent),
+				Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: INITIAL_BALANCE as u64,
+					call: accept_open_channel.encode().into(),
+				},
+			));
+		});
+
+		Relay::execute_with(|| {
+			use relay_chain::{Balances, Event, System};
+			assert!(System::events().iter().any(|r| matches!(
+				r.event,
+				Event::Hrmp(hrmp::Event::OpenChannelAccepted(sender, recipient))
+					if sender == 1.into() && recipient == 2.into()
+			)));
+
+			assert_eq!(Balances::reserved_balance(&para_account_id(1)), HRMP_SENDER_DEPOSIT);
+			assert_eq!(Balances::reserved_balance(&para_account_id(2)), HRMP_RECIPIENT_DEPOSIT);
+
+			print_events::<relay_chain::Runtime>("RelayChain");
+		});
+	}
+
+	#[test]
+	fn version_subscription() {
+		MockNet::reset();
+
+		Relay::execute_with(|| {
+			assert_ok!(RelayChainPalletXcm::force_subscribe_version_notify(
+				relay_chain::Origin::root(),
+				X1(Parachain(1)),
+			));
+		});
+
+		ParaA::execute_with(|| {
+			use parachain::{Event, System};
+			assert!(System::events().iter().any(|r| matches!(
+				r.event,
+				Event::PolkadotXcm(pallet_xcm::Event::VersionChangeNotified(X1(Parent), xcm::LATEST_VERSION))
+			)));
+		});
+
+		Relay::execute_with(|| {
+			use relay_chain::{Event, System};
+			assert!(System::events().iter().any(|r| matches!(
+				r.event,
+				Event::XcmPallet(pallet_xcm::Event::SupportedVersionChanged(X1(Parachain(1)), xcm::LATEST_VERSION))
+			)));
+
+			print_events::<relay_chain::Runtime>("RelayChain");
+		});
+	}
+}